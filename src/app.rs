@@ -1,37 +1,60 @@
 use std::{
-    cmp::{max, min},
-    collections::HashMap,
-    time::Instant,
+    collections::{HashMap, HashSet, VecDeque},
+    net::SocketAddr,
+    path::PathBuf,
+    time::{Duration, Instant},
 };
 
+use alerts::*;
 use concat_string::concat_string;
 use data_farmer::*;
-use data_harvester::temperature;
+use data_harvester::{
+    cpu::CpuDataType, network::connections::Protocol, processes::current_user,
+    remote_uptime::RemoteHost, temperature, CollectionError,
+};
 use filter::*;
 use layout_manager::*;
 pub use states::*;
 use typed_builder::*;
 use unicode_segmentation::{GraphemeCursor, UnicodeSegmentation};
 
-use crate::widgets::{ProcWidgetMode, ProcWidgetState};
+#[cfg(target_family = "unix")]
+use crate::widgets::process_table::signals;
+use crate::widgets::{
+    write_export, ConnectionsWidgetState, ExportFormat, GroupBy, ProcWidgetMode, ProcWidgetState,
+    UserFilter,
+};
 use crate::{
     constants,
     data_conversion::ConvertedData,
+    remote::discovery::DiscoveredAgent,
     units::data_units::DataUnit,
     utils::error::{BottomError, Result},
     Pid,
 };
 
+pub mod alerts;
 pub mod data_farmer;
 pub mod data_harvester;
 pub mod filter;
 pub mod frozen_state;
+pub mod key_bindings;
+pub mod layout_editor;
 pub mod layout_manager;
+#[cfg(target_family = "unix")]
+pub mod mem_map;
+pub mod process_details;
 mod process_killer;
+mod process_spawner;
 pub mod query;
+mod snapshot_dump;
 pub mod states;
 
 use frozen_state::FrozenState;
+use key_bindings::{KeyBindings, PendingKeys};
+
+/// How long the path of a saved snapshot stays on the status line.
+const SNAPSHOT_NOTICE_DURATION: Duration = Duration::from_secs(5);
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum AxisScaling {
@@ -45,15 +68,48 @@ impl Default for AxisScaling {
     }
 }
 
+/// The kind of line used to draw widget borders.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum BorderType {
+    #[default]
+    Plain,
+    Rounded,
+    Double,
+    Thick,
+}
+
+impl From<BorderType> for tui::widgets::BorderType {
+    fn from(border_type: BorderType) -> Self {
+        match border_type {
+            BorderType::Plain => tui::widgets::BorderType::Plain,
+            BorderType::Rounded => tui::widgets::BorderType::Rounded,
+            BorderType::Double => tui::widgets::BorderType::Double,
+            BorderType::Thick => tui::widgets::BorderType::Thick,
+        }
+    }
+}
+
+/// A layout tab, as far as switching to it goes.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AppTab {
+    pub name: String,
+    /// The widget to select when switching to the tab, which is the one selected when it was
+    /// last left.
+    pub widget_id: u64,
+}
+
 /// AppConfigFields is meant to cover basic fields that would normally be set
 /// by config files or launch options.
-#[derive(Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct AppConfigFields {
     pub update_rate_in_milliseconds: u64,
     pub temperature_type: temperature::TemperatureType,
     pub use_dot: bool,
     pub left_legend: bool,
     pub show_average_cpu: bool,
+    /// Whether to show the clock and scaling governor of each core in CPU widgets.
+    pub show_cpu_frequency: bool,
+    pub highlight_throttled_cpus: bool,
     pub use_current_cpu_total: bool,
     pub unnormalized_cpu: bool,
     pub use_basic_mode: bool,
@@ -71,6 +127,84 @@ pub struct AppConfigFields {
     pub network_unit_type: DataUnit,
     pub network_scale_type: AxisScaling,
     pub network_use_binary_prefix: bool,
+    pub show_connection_count: bool,
+    pub collection_timeout_ms: u64,
+    /// How often temperatures, disks and other slowly changing sources are harvested.
+    pub slow_update_rate_in_milliseconds: u64,
+    pub border_type: BorderType,
+    pub use_nerd_fonts: bool,
+    pub show_scrollbar: bool,
+    pub group_sensors: bool,
+    pub sensor_group_prefixes: Vec<String>,
+    /// Which kinds of sensors the temperature widget shows.
+    pub sensor_types: Vec<temperature::SensorType>,
+    pub process_columns: Vec<String>,
+    /// Where process table exports are saved.
+    pub process_export_dir: PathBuf,
+    /// Where snapshots of all collected data are saved.
+    pub snapshot_dir: PathBuf,
+    /// The config file that the layout editor writes to, if there is one.
+    pub config_path: Option<PathBuf>,
+    pub cpu_alert_threshold: u64,
+    /// In Celsius, regardless of [`AppConfigFields::temperature_type`].
+    pub temp_alert_threshold: u64,
+    pub disk_alert_threshold: u64,
+    /// Inode usage, in percent, past which a disk's row is coloured red.
+    pub inode_alert_threshold: u64,
+    pub disable_alert_blinking: bool,
+    /// The alerts set up under `[[alert]]` in the config.
+    pub alert_rules: Vec<AlertRule>,
+    pub disable_terminal_colors: bool,
+    /// The program and arguments to run terminal widget commands with, before the command.
+    pub terminal_shell: Vec<String>,
+    pub terminal_history_size: usize,
+    pub save_terminal_history: bool,
+    /// How long terminal widget commands may run before they are killed, or 0 for no limit.
+    pub terminal_timeout_secs: u64,
+    /// Where the longest uptime seen is saved.
+    pub uptime_streak_file: Option<PathBuf>,
+    pub uptime_milestones: Vec<u64>,
+    /// Other hosts shown in uptime widgets.
+    pub uptime_hosts: Vec<RemoteHost>,
+    /// The file log widgets follow, rather than the systemd journal.
+    pub log_file: Option<PathBuf>,
+    /// How many lines each log widget keeps.
+    pub log_max_lines: usize,
+    pub hide_ipv6_connections: bool,
+    /// Whether to show IPv4-mapped IPv6 addresses in the connections widget as IPv4 addresses.
+    pub collapse_ipv4_mapped: bool,
+    /// How long connection addresses can get before they are shortened, or 0 for no limit.
+    pub max_address_length: usize,
+    /// Whether to show the hostnames of remote addresses in the connections widget.
+    pub resolve_hostnames: bool,
+    pub hostname_ttl_secs: u64,
+    /// Whether killing in the connections widget closes the socket instead of killing its owner.
+    pub close_instead_of_kill: bool,
+    /// MaxMind databases to look remote addresses up in, for the location column.
+    pub geoip_country_db: Option<PathBuf>,
+    pub geoip_asn_db: Option<PathBuf>,
+    /// Where to serve the collected data as JSON, if anywhere.
+    pub serve_address: Option<SocketAddr>,
+    /// Whether the server also has Prometheus metrics at `/metrics`.
+    pub serve_prometheus: bool,
+    /// Whether to only serve the collected data, without an interface.
+    pub headless: bool,
+    /// Where to stream the collected data to viewers from, when running without an interface.
+    pub agent_address: Option<SocketAddr>,
+    /// Whether the agent runs commands sent by viewers' terminal widgets.
+    pub agent_allows_commands: bool,
+    /// The secret that commands sent to an agent have to carry, set on both the agent and viewers.
+    pub agent_token: Option<String>,
+    /// Whether the agent answers viewers looking for agents on the local network.
+    pub agent_advertises: bool,
+    /// The agent to show the data of instead of collecting it, like `host:port`.
+    pub remote_address: Option<String>,
+    /// The agents shown in the dashboard widget, like `host:port`.
+    pub dashboard_agents: Vec<String>,
+    /// Short names for commands typed into terminal widgets, expanded when run.
+    pub terminal_aliases: HashMap<String, String>,
+    /// Which keys do what, for the actions that can be bound to other keys.
+    pub key_bindings: KeyBindings,
     pub retention_ms: u64,
 }
 
@@ -91,6 +225,10 @@ pub struct App {
     #[builder(default, setter(skip))]
     second_char: Option<char>,
 
+    /// The keys pressed so far towards a key binding of several keys.
+    #[builder(default, setter(skip))]
+    pub pending_keys: PendingKeys,
+
     // FIXME: The way we do deletes is really gross.
     #[builder(default, setter(skip))]
     pub dd_err: Option<String>,
@@ -98,12 +236,33 @@ pub struct App {
     #[builder(default, setter(skip))]
     to_delete_process_list: Option<(String, Vec<Pid>)>,
 
+    /// The socket to close instead of killing a process, if killing from a connections widget
+    /// with [`AppConfigFields::close_instead_of_kill`] set.
+    #[builder(default, setter(skip))]
+    to_close_connection: Option<(Protocol, SocketAddr, SocketAddr)>,
+
     #[builder(default, setter(skip))]
     pub frozen_state: FrozenState,
 
+    /// The IDs of widgets that are frozen on their own, independent of [`App::frozen_state`].
+    #[builder(default, setter(skip))]
+    pub frozen_widget_ids: HashSet<u64>,
+
+    /// The IDs of widgets whose values are over their alert thresholds.
+    #[builder(default, setter(skip))]
+    pub alerting_widget_ids: HashSet<u64>,
+
+    /// The alerts set up in the config, and which of them are going off.
+    pub alerts: Alerts,
+
     #[builder(default = Instant::now(), setter(skip))]
     last_key_press: Instant,
 
+    /// The file the last snapshot was saved to, and when, so it can be shown on the status line
+    /// for a little while.
+    #[builder(default, setter(skip))]
+    snapshot_notice: Option<(Instant, String)>,
+
     #[builder(default, setter(skip))]
     pub converted_data: ConvertedData,
 
@@ -116,6 +275,39 @@ pub struct App {
     #[builder(default, setter(skip))]
     pub help_dialog_state: AppHelpDialogState,
 
+    #[builder(default, setter(skip))]
+    pub spawn_dialog_state: AppSpawnDialogState,
+
+    #[builder(default, setter(skip))]
+    pub command_line_state: AppCommandLineState,
+
+    #[cfg(target_os = "linux")]
+    #[builder(default, setter(skip))]
+    pub io_priority_dialog_state: AppIoPriorityDialogState,
+
+    #[builder(default, setter(skip))]
+    pub user_picker_state: AppUserPickerState,
+
+    /// The memory map of a process, shown over everything else while open.
+    #[cfg(target_family = "unix")]
+    #[builder(default, setter(skip))]
+    pub mem_map_overlay: Option<mem_map::MemMapOverlay>,
+
+    /// The details of a process, shown over everything else while open.
+    #[builder(default, setter(skip))]
+    pub process_details_overlay: Option<process_details::ProcessDetailsOverlay>,
+
+    /// The layout being edited, shown over everything else while open.
+    #[builder(default, setter(skip))]
+    pub layout_editor: Option<layout_editor::LayoutEditor>,
+
+    /// The most recent collection errors, oldest first.
+    #[builder(default, setter(skip))]
+    pub errors: VecDeque<CollectionError>,
+
+    #[builder(default, setter(skip))]
+    pub error_log_state: AppErrorLogState,
+
     #[builder(default = false)]
     pub is_expanded: bool,
 
@@ -132,6 +324,10 @@ pub struct App {
     #[builder(default, setter(skip))]
     pub user_table: data_harvester::processes::UserTable,
 
+    /// Agents found on the local network, for the discovery widget.
+    #[builder(default, setter(skip))]
+    pub discovered_agents: Vec<DiscoveredAgent>,
+
     pub cpu_state: CpuState,
     pub mem_state: MemState,
     pub net_state: NetState,
@@ -142,24 +338,49 @@ pub struct App {
     pub terminal_state: TerminalState,
     pub uptime_state: UptimeState,
     pub connections_state: ConnectionsState,
+    pub dashboard_state: DashboardState,
+    pub discovery_state: DiscoveryState,
+    pub container_state: ContainerState,
+    pub pressure_state: PressureState,
+    pub wifi_state: WifiState,
+    pub raid_state: RaidState,
+    pub zfs_pool_state: ZfsPoolState,
+    pub cgroup_state: CgroupState,
+    pub scheduled_job_state: ScheduledJobState,
+    pub log_state: LogState,
+    pub custom_widget_state: CustomWidgetState,
     pub basic_table_widget_state: Option<BasicTableWidgetState>,
     pub app_config_fields: AppConfigFields,
     pub widget_map: HashMap<u64, BottomWidget>,
     pub current_widget: BottomWidget,
+    /// Every layout tab, the widgets of all of which are in [`App::widget_map`].
+    pub tabs: Vec<AppTab>,
+    /// The index of the tab being shown.
+    pub current_tab: usize,
     pub used_widgets: UsedWidgets,
     pub filters: DataFilters,
 }
 
-// TODO: Should probably set a fallback max signal/not supported for this.
-#[cfg(target_os = "windows")]
-const MAX_SIGNAL: usize = 1;
-#[cfg(target_os = "linux")]
-const MAX_SIGNAL: usize = 64;
-#[cfg(target_os = "macos")]
-const MAX_SIGNAL: usize = 31;
-// https://www.freebsd.org/cgi/man.cgi?query=signal&apropos=0&sektion=3&manpath=FreeBSD+13.1-RELEASE+and+Ports&arch=default&format=html
-#[cfg(target_os = "freebsd")]
-const MAX_SIGNAL: usize = 33;
+/// Describes why an action on a process failed, pointing out when it needs root, as acting on
+/// another user's process does.
+#[cfg(target_family = "unix")]
+fn process_error_message(pid: Pid, err: &std::io::Error) -> String {
+    if err.kind() == std::io::ErrorKind::PermissionDenied {
+        format!("process {pid}: permission denied, needs root")
+    } else {
+        format!("process {pid}: {err}")
+    }
+}
+
+/// Replaces each state in `states` with the one of the same widget in `previous`, if it had one.
+/// Each kind of widget keeps its states apart, so a widget found in both is still the same type.
+fn carry_over_states<T>(states: &mut HashMap<u64, T>, mut previous: HashMap<u64, T>) {
+    for (widget_id, state) in states.iter_mut() {
+        if let Some(previous_state) = previous.remove(widget_id) {
+            *state = previous_state;
+        }
+    }
+}
 
 impl App {
     pub fn reset(&mut self) {
@@ -180,20 +401,205 @@ impl App {
 
         // Clear current delete list
         self.to_delete_process_list = None;
+        self.to_close_connection = None;
         self.dd_err = None;
 
         // Unfreeze.
         self.frozen_state.thaw();
+        self.frozen_widget_ids.clear();
+        self.alerting_widget_ids.clear();
+        self.alerts = Alerts::new(&self.app_config_fields.alert_rules);
 
         // Reset zoom
         self.reset_cpu_zoom();
         self.reset_mem_zoom();
         self.reset_net_zoom();
+        self.reset_pressure_zoom();
+        self.reset_wifi_zoom();
+
+        // Reset network peaks
+        for net_widget_state in self.net_state.widget_states.values_mut() {
+            net_widget_state.reset_peaks();
+        }
 
         // Reset data
         self.data_collection.reset();
     }
 
+    /// Takes over what `previous` collected and how its widgets were left, like after the config
+    /// is reloaded. Widgets that are still in the layout keep their selection, sorting, search and
+    /// so on, while new ones start out as they would on startup.
+    pub fn carry_over(&mut self, previous: &mut App) {
+        use std::mem::take;
+
+        self.data_collection = take(&mut previous.data_collection);
+        self.errors = take(&mut previous.errors);
+        self.discovered_agents = take(&mut previous.discovered_agents);
+        self.app_config_fields.remote_address = previous.app_config_fields.remote_address.take();
+        self.frozen_state = take(&mut previous.frozen_state);
+
+        carry_over_states(
+            &mut self.cpu_state.widget_states,
+            take(&mut previous.cpu_state.widget_states),
+        );
+        carry_over_states(
+            &mut self.mem_state.widget_states,
+            take(&mut previous.mem_state.widget_states),
+        );
+        carry_over_states(
+            &mut self.net_state.widget_states,
+            take(&mut previous.net_state.widget_states),
+        );
+        carry_over_states(
+            &mut self.proc_state.widget_states,
+            take(&mut previous.proc_state.widget_states),
+        );
+        carry_over_states(
+            &mut self.temp_state.widget_states,
+            take(&mut previous.temp_state.widget_states),
+        );
+        carry_over_states(
+            &mut self.disk_state.widget_states,
+            take(&mut previous.disk_state.widget_states),
+        );
+        carry_over_states(
+            &mut self.battery_state.widget_states,
+            take(&mut previous.battery_state.widget_states),
+        );
+        carry_over_states(
+            &mut self.terminal_state.widget_states,
+            take(&mut previous.terminal_state.widget_states),
+        );
+        carry_over_states(
+            &mut self.uptime_state.widget_states,
+            take(&mut previous.uptime_state.widget_states),
+        );
+        carry_over_states(
+            &mut self.connections_state.widget_states,
+            take(&mut previous.connections_state.widget_states),
+        );
+        carry_over_states(
+            &mut self.dashboard_state.widget_states,
+            take(&mut previous.dashboard_state.widget_states),
+        );
+        carry_over_states(
+            &mut self.discovery_state.widget_states,
+            take(&mut previous.discovery_state.widget_states),
+        );
+        carry_over_states(
+            &mut self.container_state.widget_states,
+            take(&mut previous.container_state.widget_states),
+        );
+        carry_over_states(
+            &mut self.pressure_state.widget_states,
+            take(&mut previous.pressure_state.widget_states),
+        );
+        carry_over_states(
+            &mut self.wifi_state.widget_states,
+            take(&mut previous.wifi_state.widget_states),
+        );
+        carry_over_states(
+            &mut self.raid_state.widget_states,
+            take(&mut previous.raid_state.widget_states),
+        );
+        carry_over_states(
+            &mut self.zfs_pool_state.widget_states,
+            take(&mut previous.zfs_pool_state.widget_states),
+        );
+        carry_over_states(
+            &mut self.cgroup_state.widget_states,
+            take(&mut previous.cgroup_state.widget_states),
+        );
+        carry_over_states(
+            &mut self.scheduled_job_state.widget_states,
+            take(&mut previous.scheduled_job_state.widget_states),
+        );
+        carry_over_states(
+            &mut self.log_state.widget_states,
+            take(&mut previous.log_state.widget_states),
+        );
+        carry_over_states(
+            &mut self.custom_widget_state.widget_states,
+            take(&mut previous.custom_widget_state.widget_states),
+        );
+
+        // The rest only carries over for widgets that are still there, as the same type.
+        let is_still_there = |widget: &BottomWidget| {
+            self.widget_map
+                .get(&widget.widget_id)
+                .is_some_and(|new_widget| new_widget.widget_type == widget.widget_type)
+        };
+        let is_same_tab = self
+            .tabs
+            .get(previous.current_tab)
+            .zip(previous.tabs.get(previous.current_tab))
+            .is_some_and(|(tab, previous_tab)| tab.name == previous_tab.name);
+        if is_same_tab && is_still_there(&previous.current_widget) {
+            self.current_tab = previous.current_tab;
+            self.current_widget = self.widget_map[&previous.current_widget.widget_id].clone();
+            self.is_expanded = previous.is_expanded;
+        }
+        for tab in &mut self.tabs {
+            let previous_widget = previous
+                .tabs
+                .iter()
+                .find(|previous_tab| previous_tab.name == tab.name)
+                .and_then(|previous_tab| previous.widget_map.get(&previous_tab.widget_id));
+            if let Some(previous_widget) = previous_widget.filter(|widget| is_still_there(widget)) {
+                tab.widget_id = previous_widget.widget_id;
+            }
+        }
+        if let (Some(basic_table), Some(previous_basic_table)) = (
+            &mut self.basic_table_widget_state,
+            previous.basic_table_widget_state.take(),
+        ) {
+            let is_shown_widget_there = self
+                .widget_map
+                .get(&previous_basic_table.currently_displayed_widget_id)
+                .is_some_and(|widget| {
+                    widget.widget_type == previous_basic_table.currently_displayed_widget_type
+                });
+            if is_shown_widget_there {
+                *basic_table = previous_basic_table;
+            }
+        }
+        self.frozen_widget_ids = previous
+            .frozen_widget_ids
+            .iter()
+            .filter(|widget_id| {
+                previous
+                    .widget_map
+                    .get(widget_id)
+                    .is_some_and(|widget| is_still_there(widget))
+            })
+            .copied()
+            .collect();
+    }
+
+    /// Whether the kill dialog lets a signal be picked, rather than just asking yes or no.
+    pub fn is_choosing_signal(&self) -> bool {
+        (self.app_config_fields.is_advanced_kill || self.delete_dialog_state.is_picking_signal)
+            && self.to_close_connection.is_none()
+    }
+
+    /// Whether the kill dialog is asking yes or no, but can be switched to the signal picker.
+    pub fn can_open_signal_picker(&self) -> bool {
+        cfg!(target_family = "unix")
+            && !self.is_choosing_signal()
+            && self.to_close_connection.is_none()
+            && self.dd_err.is_none()
+    }
+
+    /// Switches the kill dialog from asking yes or no to picking the signal to send.
+    fn open_signal_picker(&mut self) {
+        if self.can_open_signal_picker() {
+            self.delete_dialog_state.is_picking_signal = true;
+            self.delete_dialog_state.selected_signal = KillSignal::default();
+            self.delete_dialog_state.scroll_pos = 0;
+            self.is_force_redraw = true;
+        }
+    }
+
     pub fn should_get_widget_bounds(&self) -> bool {
         self.is_force_redraw || self.is_determining_widget_boundary
     }
@@ -202,7 +608,9 @@ impl App {
         self.delete_dialog_state.is_showing_dd = false;
         self.delete_dialog_state.selected_signal = KillSignal::default();
         self.delete_dialog_state.scroll_pos = 0;
+        self.delete_dialog_state.is_picking_signal = false;
         self.to_delete_process_list = None;
+        self.to_close_connection = None;
         self.dd_err = None;
     }
 
@@ -212,12 +620,30 @@ impl App {
             if self.help_dialog_state.is_showing_help {
                 self.help_dialog_state.is_showing_help = false;
                 self.help_dialog_state.scroll_state.current_scroll_index = 0;
+            } else if self.spawn_dialog_state.is_showing {
+                self.spawn_dialog_state.close();
+            } else if self.is_showing_io_priority_dialog() {
+                #[cfg(target_os = "linux")]
+                self.io_priority_dialog_state.close();
+            } else if self.user_picker_state.is_showing {
+                self.user_picker_state.close();
+            } else if self.is_showing_mem_map_overlay() {
+                #[cfg(target_family = "unix")]
+                {
+                    self.mem_map_overlay = None;
+                }
+            } else if self.process_details_overlay.is_some() {
+                self.process_details_overlay = None;
             } else {
                 self.close_dd();
             }
 
             self.is_force_redraw = true;
         } else {
+            if self.close_port_filter_input() {
+                return;
+            }
+
             match self.current_widget.widget_type {
                 BottomWidgetType::Proc => {
                     if let Some(pws) = self
@@ -229,6 +655,9 @@ impl App {
                             pws.is_sort_open = false;
                             self.is_force_redraw = true;
                             return;
+                        } else if pws.clear_selection() {
+                            self.is_force_redraw = true;
+                            return;
                         }
                     }
                 }
@@ -275,19 +704,96 @@ impl App {
         )
     }
 
-    fn reset_multi_tap_keys(&mut self) {
+    /// Whether printable keys should be typed rather than do anything, like in the search bar.
+    pub fn is_typing(&self) -> bool {
+        self.is_in_search_widget()
+            || self.spawn_dialog_state.is_showing
+            || self.command_line_state.is_showing
+            || self
+                .connections_state
+                .get_widget_state(self.current_widget.widget_id)
+                .is_some_and(|state| state.is_port_filter_input_open())
+    }
+
+    pub fn reset_multi_tap_keys(&mut self) {
         self.awaiting_second_char = false;
         self.second_char = None;
     }
 
-    fn is_in_dialog(&self) -> bool {
-        self.help_dialog_state.is_showing_help || self.delete_dialog_state.is_showing_dd
+    pub fn is_in_dialog(&self) -> bool {
+        self.help_dialog_state.is_showing_help
+            || self.delete_dialog_state.is_showing_dd
+            || self.spawn_dialog_state.is_showing
+            || self.is_showing_io_priority_dialog()
+            || self.user_picker_state.is_showing
+            || self.is_showing_mem_map_overlay()
+            || self.process_details_overlay.is_some()
+            || self.layout_editor.is_some()
+    }
+
+    /// Whether the memory map overlay is open, which is never the case outside of Unix-like
+    /// systems.
+    pub fn is_showing_mem_map_overlay(&self) -> bool {
+        #[cfg(target_family = "unix")]
+        {
+            self.mem_map_overlay.is_some()
+        }
+        #[cfg(not(target_family = "unix"))]
+        {
+            false
+        }
+    }
+
+    /// Whether the dialog for changing the I/O priority of processes is open, which is never the
+    /// case outside of Linux.
+    pub fn is_showing_io_priority_dialog(&self) -> bool {
+        #[cfg(target_os = "linux")]
+        {
+            self.io_priority_dialog_state.is_showing
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            false
+        }
     }
 
     fn ignore_normal_keybinds(&self) -> bool {
         self.is_in_dialog()
     }
 
+    /// Returns the state of the current connections widget, if its port filter input is open.
+    fn get_mut_port_filter_input_state(&mut self) -> Option<&mut ConnectionsWidgetState> {
+        if let BottomWidgetType::Connections = self.current_widget.widget_type {
+            self.connections_state
+                .get_mut_widget_state(self.current_widget.widget_id)
+                .filter(|state| state.is_port_filter_input_open())
+        } else {
+            None
+        }
+    }
+
+    /// Closes the port filter input of the current connections widget, returning whether it was
+    /// open.
+    fn close_port_filter_input(&mut self) -> bool {
+        if let Some(connections_widget_state) = self.get_mut_port_filter_input_state() {
+            connections_widget_state.close_port_filter_input();
+            self.is_force_redraw = true;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Re-applies the port filter of the current connections widget to the latest data.
+    fn refresh_port_filter(&mut self) {
+        if let Some(connections_widget_state) = self
+            .connections_state
+            .get_mut_widget_state(self.current_widget.widget_id)
+        {
+            connections_widget_state.ingest_data(&self.converted_data.connections_data);
+        }
+    }
+
     pub fn on_tab(&mut self) {
         // Allow usage whilst only in processes
 
@@ -386,6 +892,210 @@ impl App {
         }
     }
 
+    /// Records a collection error, showing the error log the first time one comes in.
+    pub fn push_collection_error(&mut self, error: CollectionError) {
+        // A repeat of an existing error just moves it to the back, so a collector that keeps failing
+        // doesn't push everything else out of the log.
+        if let Some(index) = self
+            .errors
+            .iter()
+            .position(|e| e.source == error.source && e.message == error.message)
+        {
+            self.errors.remove(index);
+        } else if self.errors.len() >= constants::MAX_COLLECTION_ERRORS {
+            self.errors.pop_front();
+        }
+        self.errors.push_back(error);
+
+        if !self.error_log_state.has_auto_shown {
+            self.error_log_state.has_auto_shown = true;
+            self.error_log_state.is_showing = true;
+        }
+    }
+
+    pub fn toggle_error_log(&mut self) {
+        self.error_log_state.is_showing = !self.error_log_state.is_showing;
+        self.error_log_state.has_auto_shown = true;
+    }
+
+    /// Saves everything collected so far, along with what the widgets show, to a JSON file in the
+    /// snapshot directory. Frozen data is saved while frozen.
+    pub fn dump_snapshot(&mut self) {
+        let data = match &self.frozen_state {
+            FrozenState::NotFrozen => &self.data_collection,
+            FrozenState::Frozen(data) => data,
+        };
+        let contents = snapshot_dump::to_json(data, &self.converted_data);
+        match snapshot_dump::write_snapshot(&self.app_config_fields.snapshot_dir, &contents) {
+            Ok(path) => {
+                self.snapshot_notice = Some((Instant::now(), path.display().to_string()));
+            }
+            Err(err) => {
+                self.push_collection_error(CollectionError::new(
+                    "snapshot",
+                    format!(
+                        "couldn't write to {}: {err}",
+                        self.app_config_fields.snapshot_dir.display()
+                    ),
+                ));
+                self.error_log_state.is_showing = true;
+                self.is_force_redraw = true;
+            }
+        }
+    }
+
+    /// The path of a snapshot that was just saved, while it should still be shown.
+    pub fn snapshot_notice(&self) -> Option<&str> {
+        self.snapshot_notice
+            .as_ref()
+            .filter(|(instant, _)| instant.elapsed() < SNAPSHOT_NOTICE_DURATION)
+            .map(|(_, path)| path.as_str())
+    }
+
+    pub fn toggle_freeze(&mut self) {
+        self.frozen_state.toggle(&self.data_collection); // TODO: Thawing should force a full data refresh and redraw immediately.
+    }
+
+    pub fn open_help(&mut self) {
+        self.help_dialog_state.is_showing_help = true;
+        self.is_force_redraw = true;
+    }
+
+    /// Freezes or unfreezes just the current widget. Only table widgets can be frozen on their own,
+    /// as graphs share their data, while log widgets are paused instead.
+    fn toggle_widget_freeze(&mut self) {
+        match self.current_widget.widget_type {
+            BottomWidgetType::Logs => {
+                if let Some(log_widget_state) = self
+                    .log_state
+                    .get_mut_widget_state(self.current_widget.widget_id)
+                {
+                    log_widget_state.toggle_pause();
+                }
+            }
+            BottomWidgetType::Proc
+            | BottomWidgetType::Temp
+            | BottomWidgetType::Disk
+            | BottomWidgetType::Connections
+            | BottomWidgetType::Dashboard
+            | BottomWidgetType::Containers
+            | BottomWidgetType::Raid
+            | BottomWidgetType::ZfsPools
+            | BottomWidgetType::Cgroups
+            | BottomWidgetType::ScheduledJobs => {
+                let widget_id = self.current_widget.widget_id;
+                if !self.frozen_widget_ids.remove(&widget_id) {
+                    self.frozen_widget_ids.insert(widget_id);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Checks the alerts set up in the config against the latest data, carrying out the actions of
+    /// those that just went off.
+    pub fn update_alerts(&mut self) {
+        for action in self
+            .alerts
+            .update(&self.data_collection, self.data_collection.current_instant)
+        {
+            match action {
+                AlertAction::Show => {}
+                AlertAction::Bell => {
+                    use std::io::Write;
+
+                    print!("\x07");
+                    let _ = std::io::stdout().flush();
+                }
+                AlertAction::Command(command) => {
+                    if let Err(err) = process_spawner::spawn_command(&command) {
+                        self.push_collection_error(CollectionError::new(
+                            "Alert",
+                            format!("couldn't run '{command}': {err}"),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Marks the CPU, temperature and disk widgets whose latest values are over their alert
+    /// thresholds, uptime widgets on a milestone, and widgets showing what a config alert is about,
+    /// so their borders can be drawn to stand out.
+    pub fn update_alerting_widgets(&mut self) {
+        let data = match &self.frozen_state {
+            FrozenState::NotFrozen => &self.data_collection,
+            FrozenState::Frozen(data) => data,
+        };
+        let config = &self.app_config_fields;
+
+        let is_cpu_alerting = data.cpu_harvest.iter().any(|cpu| {
+            matches!(cpu.data_type, CpuDataType::Avg)
+                && cpu.cpu_usage > config.cpu_alert_threshold as f64
+        });
+
+        let temp_threshold = config
+            .temperature_type
+            .convert_celsius(config.temp_alert_threshold as f32);
+        let is_temp_alerting = data.temp_harvest.iter().any(|sensor| {
+            sensor.sensor_type == temperature::SensorType::Temperature
+                && sensor.value > temp_threshold
+        });
+
+        // Milestones are celebrated for the whole day they're reached.
+        let is_uptime_alerting = uptime_lib::get()
+            .map(|uptime| {
+                config
+                    .uptime_milestones
+                    .contains(&(uptime.as_secs() / 60 / 60 / 24))
+            })
+            .unwrap_or(false);
+
+        let is_disk_alerting =
+            data.disk_harvest
+                .iter()
+                .any(|disk| match (disk.used_space, disk.total_space) {
+                    (Some(used_space), Some(total_space)) if total_space > 0 => {
+                        used_space as f64 / total_space as f64 * 100.0
+                            > config.disk_alert_threshold as f64
+                    }
+                    _ => false,
+                })
+                || data.disk_harvest.iter().any(|disk| {
+                    disk.inode_usage_percent()
+                        .is_some_and(|percent| percent > config.inode_alert_threshold as f64)
+                });
+
+        let is_raid_alerting = data.raid_harvest.iter().any(|array| array.is_degraded());
+
+        let is_zfs_pool_alerting = data.zfs_pool_harvest.iter().any(|pool| pool.is_unhealthy());
+
+        let is_scheduled_job_alerting = data
+            .scheduled_job_harvest
+            .iter()
+            .any(|job| job.has_failed());
+
+        self.alerting_widget_ids = self
+            .widget_map
+            .values()
+            .filter(|widget| {
+                let is_past_fixed_threshold = match widget.widget_type {
+                    BottomWidgetType::Cpu | BottomWidgetType::CpuLegend => is_cpu_alerting,
+                    BottomWidgetType::Temp => is_temp_alerting,
+                    BottomWidgetType::Disk => is_disk_alerting,
+                    BottomWidgetType::Raid => is_raid_alerting,
+                    BottomWidgetType::ZfsPools => is_zfs_pool_alerting,
+                    BottomWidgetType::ScheduledJobs => is_scheduled_job_alerting,
+                    BottomWidgetType::Uptime => is_uptime_alerting,
+                    _ => false,
+                };
+                // A widget also flashes for any `[[alert]]` rule on a metric it shows.
+                is_past_fixed_threshold || self.alerts.is_alerting(&widget.widget_type)
+            })
+            .map(|widget| widget.widget_id)
+            .collect();
+    }
+
     pub fn toggle_ignore_case(&mut self) {
         let is_in_search_widget = self.is_in_search_widget();
         if let Some(proc_widget_state) = self
@@ -428,6 +1138,20 @@ impl App {
         }
     }
 
+    pub fn toggle_search_cmdline(&mut self) {
+        let is_in_search_widget = self.is_in_search_widget();
+        if let Some(proc_widget_state) = self
+            .proc_state
+            .widget_states
+            .get_mut(&(self.current_widget.widget_id - 1))
+        {
+            if is_in_search_widget && proc_widget_state.is_search_enabled() {
+                proc_widget_state.proc_search.search_toggle_cmdline();
+                proc_widget_state.force_data_update();
+            }
+        }
+    }
+
     pub fn toggle_tree_mode(&mut self) {
         if let Some(proc_widget_state) = self
             .proc_state
@@ -476,8 +1200,43 @@ impl App {
                 self.delete_dialog_state.is_showing_dd = false;
             }
             self.is_force_redraw = true;
+        } else if self.spawn_dialog_state.is_showing {
+            if self.spawn_dialog_state.result.is_some() {
+                self.spawn_dialog_state.close();
+            } else if !self.spawn_dialog_state.input.text.trim().is_empty() {
+                self.spawn_dialog_state.result = Some(
+                    process_spawner::spawn_command(&self.spawn_dialog_state.input.text)
+                        .map_err(|err| err.to_string()),
+                );
+            }
+        } else if self.is_showing_io_priority_dialog() {
+            #[cfg(target_os = "linux")]
+            {
+                if self.io_priority_dialog_state.result.is_some() {
+                    self.io_priority_dialog_state.close();
+                } else {
+                    self.io_priority_dialog_state.apply();
+                }
+            }
+        } else if self.user_picker_state.is_showing {
+            if let Some(user) = self.user_picker_state.selected_user().cloned() {
+                if let Some(proc_widget_state) = self
+                    .proc_state
+                    .get_mut_widget_state(self.user_picker_state.widget_id)
+                {
+                    proc_widget_state.set_user_filter(UserFilter::User(user));
+                }
+            }
+            self.user_picker_state.close();
+            self.is_force_redraw = true;
         } else if !self.is_in_dialog() {
-            if let BottomWidgetType::ProcSort = self.current_widget.widget_type {
+            if self.close_port_filter_input() {
+                return;
+            }
+
+            if let BottomWidgetType::Proc = self.current_widget.widget_type {
+                self.open_process_details_overlay();
+            } else if let BottomWidgetType::ProcSort = self.current_widget.widget_type {
                 if let Some(proc_widget_state) = self
                     .proc_state
                     .widget_states
@@ -492,7 +1251,9 @@ impl App {
     }
 
     pub fn on_delete(&mut self) {
-        if let BottomWidgetType::ProcSearch = self.current_widget.widget_type {
+        if self.spawn_dialog_state.is_showing {
+            self.spawn_dialog_state.input.delete();
+        } else if let BottomWidgetType::ProcSearch = self.current_widget.widget_type {
             let is_in_search_widget = self.is_in_search_widget();
             if let Some(proc_widget_state) = self
                 .proc_state
@@ -538,6 +1299,17 @@ impl App {
     }
 
     pub fn on_backspace(&mut self) {
+        if self.spawn_dialog_state.is_showing {
+            self.spawn_dialog_state.input.backspace();
+            return;
+        }
+
+        if let Some(connections_widget_state) = self.get_mut_port_filter_input_state() {
+            connections_widget_state.pop_port_filter_digit();
+            self.refresh_port_filter();
+            return;
+        }
+
         if let BottomWidgetType::ProcSearch = self.current_widget.widget_type {
             let is_in_search_widget = self.is_in_search_widget();
             if let Some(proc_widget_state) = self
@@ -598,20 +1370,16 @@ impl App {
             {
                 self.delete_dialog_state.keyboard_signal_select = 0;
             }
-            let mut kbd_signal = self.delete_dialog_state.keyboard_signal_select * 10;
-            kbd_signal += number_char.to_digit(10).unwrap() as usize;
-            if kbd_signal > 64 {
-                kbd_signal %= 100;
-            }
-            #[cfg(target_os = "linux")]
-            if kbd_signal > 64 || kbd_signal == 32 || kbd_signal == 33 {
-                kbd_signal %= 10;
-            }
-            #[cfg(target_os = "macos")]
-            if kbd_signal > 31 {
-                kbd_signal %= 10;
+            let digit = number_char.to_digit(10).unwrap() as usize;
+            let mut kbd_signal = self.delete_dialog_state.keyboard_signal_select * 10 + digit;
+            if !signals::is_pickable(kbd_signal) {
+                kbd_signal = digit;
             }
-            self.delete_dialog_state.selected_signal = KillSignal::Kill(kbd_signal);
+            self.delete_dialog_state.selected_signal = if signals::is_pickable(kbd_signal) {
+                KillSignal::Kill(kbd_signal)
+            } else {
+                KillSignal::Cancel
+            };
             if kbd_signal < 10 {
                 self.delete_dialog_state.keyboard_signal_select = kbd_signal;
             } else {
@@ -626,12 +1394,24 @@ impl App {
             self.decrement_position_count();
         } else if self.help_dialog_state.is_showing_help {
             self.help_scroll_up();
+        } else if self.is_showing_io_priority_dialog() {
+            #[cfg(target_os = "linux")]
+            self.io_priority_dialog_state.decrease_level();
+        } else if self.user_picker_state.is_showing {
+            self.user_picker_state.move_up();
+        } else if self.is_showing_mem_map_overlay() {
+            #[cfg(target_family = "unix")]
+            if let Some(overlay) = &mut self.mem_map_overlay {
+                overlay.scroll_up();
+            }
+        } else if let Some(overlay) = &mut self.process_details_overlay {
+            overlay.scroll_up(1);
         } else if self.delete_dialog_state.is_showing_dd {
             #[cfg(target_os = "windows")]
             self.on_right_key();
             #[cfg(target_family = "unix")]
             {
-                if self.app_config_fields.is_advanced_kill {
+                if self.is_choosing_signal() {
                     self.on_left_key();
                 } else {
                     self.on_right_key();
@@ -647,12 +1427,24 @@ impl App {
             self.increment_position_count();
         } else if self.help_dialog_state.is_showing_help {
             self.help_scroll_down();
+        } else if self.is_showing_io_priority_dialog() {
+            #[cfg(target_os = "linux")]
+            self.io_priority_dialog_state.increase_level();
+        } else if self.user_picker_state.is_showing {
+            self.user_picker_state.move_down();
+        } else if self.is_showing_mem_map_overlay() {
+            #[cfg(target_family = "unix")]
+            if let Some(overlay) = &mut self.mem_map_overlay {
+                overlay.scroll_down();
+            }
+        } else if let Some(overlay) = &mut self.process_details_overlay {
+            overlay.scroll_down(1);
         } else if self.delete_dialog_state.is_showing_dd {
             #[cfg(target_os = "windows")]
             self.on_left_key();
             #[cfg(target_family = "unix")]
             {
-                if self.app_config_fields.is_advanced_kill {
+                if self.is_choosing_signal() {
                     self.on_right_key();
                 } else {
                     self.on_left_key();
@@ -696,21 +1488,17 @@ impl App {
                 }
                 _ => {}
             }
+        } else if self.spawn_dialog_state.is_showing {
+            self.spawn_dialog_state.input.move_left();
+        } else if self.is_showing_io_priority_dialog() {
+            #[cfg(target_os = "linux")]
+            self.io_priority_dialog_state.prev_class();
         } else if self.delete_dialog_state.is_showing_dd {
             #[cfg(target_family = "unix")]
             {
-                if self.app_config_fields.is_advanced_kill {
-                    match self.delete_dialog_state.selected_signal {
-                        KillSignal::Kill(prev_signal) => {
-                            self.delete_dialog_state.selected_signal = match prev_signal - 1 {
-                                0 => KillSignal::Cancel,
-                                // 32 + 33 are skipped
-                                33 => KillSignal::Kill(31),
-                                signal => KillSignal::Kill(signal),
-                            };
-                        }
-                        KillSignal::Cancel => {}
-                    };
+                if self.is_choosing_signal() {
+                    self.delete_dialog_state.selected_signal =
+                        signals::step_signal(&self.delete_dialog_state.selected_signal, -1);
                 } else {
                     self.delete_dialog_state.selected_signal = KillSignal::default();
                 }
@@ -758,21 +1546,17 @@ impl App {
                 }
                 _ => {}
             }
+        } else if self.spawn_dialog_state.is_showing {
+            self.spawn_dialog_state.input.move_right();
+        } else if self.is_showing_io_priority_dialog() {
+            #[cfg(target_os = "linux")]
+            self.io_priority_dialog_state.next_class();
         } else if self.delete_dialog_state.is_showing_dd {
             #[cfg(target_family = "unix")]
             {
-                if self.app_config_fields.is_advanced_kill {
-                    let new_signal = match self.delete_dialog_state.selected_signal {
-                        KillSignal::Cancel => 1,
-                        // 32+33 are skipped
-                        #[cfg(target_os = "linux")]
-                        KillSignal::Kill(31) => 34,
-                        #[cfg(target_os = "macos")]
-                        KillSignal::Kill(31) => 31,
-                        KillSignal::Kill(64) => 64,
-                        KillSignal::Kill(signal) => signal + 1,
-                    };
-                    self.delete_dialog_state.selected_signal = KillSignal::Kill(new_signal);
+                if self.is_choosing_signal() {
+                    self.delete_dialog_state.selected_signal =
+                        signals::step_signal(&self.delete_dialog_state.selected_signal, 1);
                 } else {
                     self.delete_dialog_state.selected_signal = KillSignal::Cancel;
                 }
@@ -786,21 +1570,17 @@ impl App {
 
     pub fn on_page_up(&mut self) {
         if self.delete_dialog_state.is_showing_dd {
-            let mut new_signal = match self.delete_dialog_state.selected_signal {
-                KillSignal::Cancel => 0,
-                KillSignal::Kill(signal) => max(signal, 8) - 8,
-            };
-            if new_signal > 23 && new_signal < 33 {
-                new_signal -= 2;
+            #[cfg(target_family = "unix")]
+            if self.is_choosing_signal() {
+                self.delete_dialog_state.selected_signal =
+                    signals::step_signal(&self.delete_dialog_state.selected_signal, -8);
             }
-            self.delete_dialog_state.selected_signal = match new_signal {
-                0 => KillSignal::Cancel,
-                sig => KillSignal::Kill(sig),
-            };
         } else if self.help_dialog_state.is_showing_help {
             let current = &mut self.help_dialog_state.scroll_state.current_scroll_index;
             let amount = self.help_dialog_state.height;
             *current = current.saturating_sub(amount);
+        } else if let Some(overlay) = &mut self.process_details_overlay {
+            overlay.scroll_up(overlay.page_height);
         } else if self.current_widget.widget_type.is_widget_table() {
             if let (Some((_tlc_x, tlc_y)), Some((_brc_x, brc_y))) = (
                 &self.current_widget.top_left_corner,
@@ -816,19 +1596,18 @@ impl App {
 
     pub fn on_page_down(&mut self) {
         if self.delete_dialog_state.is_showing_dd {
-            let mut new_signal = match self.delete_dialog_state.selected_signal {
-                KillSignal::Cancel => 8,
-                KillSignal::Kill(signal) => min(signal + 8, MAX_SIGNAL),
-            };
-            if new_signal > 31 && new_signal < 42 {
-                new_signal += 2;
+            #[cfg(target_family = "unix")]
+            if self.is_choosing_signal() {
+                self.delete_dialog_state.selected_signal =
+                    signals::step_signal(&self.delete_dialog_state.selected_signal, 8);
             }
-            self.delete_dialog_state.selected_signal = KillSignal::Kill(new_signal);
         } else if self.help_dialog_state.is_showing_help {
             let current = self.help_dialog_state.scroll_state.current_scroll_index;
             let amount = self.help_dialog_state.height;
 
             self.help_scroll_to_or_max(current + amount);
+        } else if let Some(overlay) = &mut self.process_details_overlay {
+            overlay.scroll_down(overlay.page_height);
         } else if self.current_widget.widget_type.is_widget_table() {
             if let (Some((_tlc_x, tlc_y)), Some((_brc_x, brc_y))) = (
                 &self.current_widget.top_left_corner,
@@ -1006,13 +1785,33 @@ impl App {
 
     pub fn start_killing_process(&mut self) {
         self.reset_multi_tap_keys();
+        self.delete_dialog_state.is_picking_signal = false;
+
+        if self.app_config_fields.remote_address.is_some() {
+            self.dd_err = Some("Processes on a remote machine can't be killed.".to_string());
+            self.delete_dialog_state.is_showing_dd = true;
+            self.is_determining_widget_boundary = true;
+            return;
+        }
+
+        if let BottomWidgetType::Connections = self.current_widget.widget_type {
+            self.start_killing_connection();
+            return;
+        }
 
         if let Some(pws) = self
             .proc_state
             .widget_states
             .get(&self.current_widget.widget_id)
         {
-            if let Some(current) = pws.table.current_item() {
+            if !pws.selected_pids.is_empty() {
+                let mut pids = pws.selected_pids.iter().copied().collect::<Vec<_>>();
+                pids.sort_unstable();
+
+                self.to_delete_process_list = Some((String::default(), pids));
+                self.delete_dialog_state.is_showing_dd = true;
+                self.is_determining_widget_boundary = true;
+            } else if let Some(current) = pws.table.current_item() {
                 let id = current.id.to_string();
                 if let Some(pids) = pws
                     .id_pid_map
@@ -1031,44 +1830,306 @@ impl App {
         // FIXME: This should handle errors.
     }
 
-    pub fn on_char_key(&mut self, caught_char: char) {
-        // Skip control code chars
-        if caught_char.is_control() {
+    /// Opens the kill dialog for the highlighted connection, which either kills the process that
+    /// owns it or just closes it.
+    fn start_killing_connection(&mut self) {
+        let Some(connection) = self
+            .connections_state
+            .widget_states
+            .get(&self.current_widget.widget_id)
+            .and_then(|state| state.table.current_item())
+        else {
             return;
+        };
+
+        if cfg!(target_os = "linux") && self.app_config_fields.close_instead_of_kill {
+            self.to_close_connection =
+                Some((connection.protocol, connection.local, connection.remote));
+        } else if let Some(pid) = connection.pid {
+            let name = connection.process_name().unwrap_or_default().to_string();
+            self.to_delete_process_list = Some((name, vec![pid]));
+        } else {
+            self.dd_err = Some("The process that owns this connection is unknown.".to_string());
         }
 
-        // Forbid any char key presses when showing a dialog box...
-        if !self.ignore_normal_keybinds() {
-            let current_key_press_inst = Instant::now();
-            if current_key_press_inst
-                .duration_since(self.last_key_press)
-                .as_millis()
-                > constants::MAX_KEY_TIMEOUT_IN_MILLISECONDS.into()
-            {
-                self.reset_multi_tap_keys();
-            }
-            self.last_key_press = current_key_press_inst;
+        self.delete_dialog_state.is_showing_dd = true;
+        self.is_determining_widget_boundary = true;
+    }
 
-            if let BottomWidgetType::ProcSearch = self.current_widget.widget_type {
-                let is_in_search_widget = self.is_in_search_widget();
-                if let Some(proc_widget_state) = self
-                    .proc_state
-                    .widget_states
-                    .get_mut(&(self.current_widget.widget_id - 1))
-                {
-                    if is_in_search_widget && proc_widget_state.is_search_enabled() {
-                        proc_widget_state
-                            .proc_search
-                            .search_state
-                            .current_search_query
-                            .insert(proc_widget_state.cursor_char_index(), caught_char);
+    /// Opens the details overlay for the highlighted process, reading its details right away.
+    pub fn open_process_details_overlay(&mut self) {
+        if self.app_config_fields.remote_address.is_some() {
+            self.push_collection_error(CollectionError::new(
+                "process details",
+                "processes on a remote machine can't be inspected",
+            ));
+            self.error_log_state.is_showing = true;
+            self.is_force_redraw = true;
+            return;
+        }
 
-                        proc_widget_state.proc_search.search_state.grapheme_cursor =
-                            GraphemeCursor::new(
-                                proc_widget_state.cursor_char_index(),
-                                proc_widget_state
-                                    .proc_search
-                                    .search_state
+        if let Some(pws) = self
+            .proc_state
+            .widget_states
+            .get(&self.current_widget.widget_id)
+        {
+            if let Some(current) = pws.table.current_item() {
+                self.process_details_overlay = Some(process_details::ProcessDetailsOverlay::new(
+                    current.pid,
+                    current.id.to_string(),
+                ));
+                self.is_force_redraw = true;
+            }
+        }
+    }
+
+    /// Opens the layout editor on the layout of the current tab, as it is in the config file.
+    pub fn open_layout_editor(&mut self) {
+        if self.ignore_normal_keybinds() {
+            return;
+        }
+
+        let opened = match &self.app_config_fields.config_path {
+            _ if self.app_config_fields.use_basic_mode => {
+                Err("the layout can't be edited in basic mode".to_string())
+            }
+            None => Err("there is no config file to save the layout to".to_string()),
+            Some(config_path) => layout_editor::LayoutEditor::open(
+                config_path,
+                self.current_tab,
+                self.used_widgets.use_battery,
+            )
+            .map_err(|err| err.to_string()),
+        };
+
+        match opened {
+            Ok(editor) => self.layout_editor = Some(editor),
+            Err(err) => {
+                self.push_collection_error(CollectionError::new("layout editor", err));
+                self.error_log_state.is_showing = true;
+            }
+        }
+        self.is_force_redraw = true;
+    }
+
+    /// Cycles the current process widget between showing every user's processes, only those of
+    /// the user running bottom, and only those of a user picked from a popup.
+    pub fn cycle_user_filter(&mut self) {
+        let widget_id = self.current_widget.widget_id;
+        let Some(proc_widget_state) = self.proc_state.get_mut_widget_state(widget_id) else {
+            return;
+        };
+
+        match &proc_widget_state.user_filter {
+            UserFilter::All => match current_user() {
+                Some(user) => proc_widget_state.set_user_filter(UserFilter::Current(user)),
+                None => {
+                    let users = ProcWidgetState::get_users(&self.data_collection.process_data);
+                    self.user_picker_state.open(widget_id, users, None);
+                }
+            },
+            UserFilter::Current(user) => {
+                let users = ProcWidgetState::get_users(&self.data_collection.process_data);
+                let current = Some(user.as_str());
+                self.user_picker_state.open(widget_id, users, current);
+            }
+            UserFilter::User(_) => proc_widget_state.set_user_filter(UserFilter::All),
+        }
+        self.is_force_redraw = true;
+    }
+
+    /// Opens the memory map overlay for the highlighted process.
+    #[cfg(target_family = "unix")]
+    pub fn open_mem_map_overlay(&mut self) {
+        if let Some(pws) = self
+            .proc_state
+            .widget_states
+            .get(&self.current_widget.widget_id)
+        {
+            if let Some(current) = pws.table.current_item() {
+                self.mem_map_overlay = Some(mem_map::MemMapOverlay::new(
+                    current.pid,
+                    current.id.to_string(),
+                ));
+                self.is_force_redraw = true;
+            }
+        }
+    }
+
+    /// Opens the I/O priority dialog for the highlighted process, or every process it groups.
+    #[cfg(target_os = "linux")]
+    pub fn start_changing_io_priority(&mut self) {
+        if let Some(pws) = self
+            .proc_state
+            .widget_states
+            .get(&self.current_widget.widget_id)
+        {
+            if let Some(current) = pws.table.current_item() {
+                let name = current.id.to_string();
+                let pids = pws
+                    .id_pid_map
+                    .get(&name)
+                    .cloned()
+                    .unwrap_or_else(|| vec![current.pid]);
+
+                self.io_priority_dialog_state.open(
+                    name,
+                    pids,
+                    current.io_class,
+                    current.io_priority,
+                );
+                self.is_force_redraw = true;
+            }
+        }
+    }
+
+    /// Changes the nice value of the marked processes, or else the highlighted process or every
+    /// process it groups, by `delta`. Failures, like lacking the permission to lower a nice
+    /// value, are shown in the error log.
+    #[cfg(target_family = "unix")]
+    pub fn renice_process(&mut self, delta: i32) {
+        if self.ignore_normal_keybinds() {
+            return;
+        }
+
+        if self.app_config_fields.remote_address.is_some() {
+            self.push_process_error("renice", "processes on a remote machine can't be reniced");
+            return;
+        }
+
+        for pid in self.targeted_pids() {
+            if let Err(err) = data_harvester::processes::renice(pid, delta) {
+                // Lowering a nice value, or changing another user's process, needs root.
+                self.push_process_error("renice", process_error_message(pid, &err));
+            }
+        }
+    }
+
+    /// Stops the marked processes, or else the highlighted process or every process it groups,
+    /// with `SIGSTOP` if `stopped` is set, and otherwise continues them with `SIGCONT`. Failures
+    /// are shown in the error log.
+    #[cfg(target_family = "unix")]
+    pub fn stop_process(&mut self, stopped: bool) {
+        if self.ignore_normal_keybinds() {
+            return;
+        }
+
+        let source = if stopped { "stop" } else { "continue" };
+        if self.app_config_fields.remote_address.is_some() {
+            self.push_process_error(source, "processes on a remote machine can't be signalled");
+            return;
+        }
+
+        for pid in self.targeted_pids() {
+            if let Err(err) = data_harvester::processes::set_stopped(pid, stopped) {
+                self.push_process_error(source, process_error_message(pid, &err));
+            }
+        }
+    }
+
+    /// Returns the PIDs an action from the current process widget applies to, which are the
+    /// marked processes, or else the highlighted process or every process it groups.
+    #[cfg(target_family = "unix")]
+    fn targeted_pids(&self) -> Vec<Pid> {
+        let Some(pws) = self
+            .proc_state
+            .widget_states
+            .get(&self.current_widget.widget_id)
+        else {
+            return Vec::new();
+        };
+
+        if !pws.selected_pids.is_empty() {
+            let mut pids = pws.selected_pids.iter().copied().collect::<Vec<_>>();
+            pids.sort_unstable();
+            pids
+        } else if let Some(current) = pws.table.current_item() {
+            pws.id_pid_map
+                .get(&current.id.to_string())
+                .cloned()
+                .unwrap_or_else(|| vec![current.pid])
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Saves what the current process widget shows to a file in `format`, showing the file's name
+    /// in the widget's title, or the error log if it couldn't be written.
+    pub fn export_processes(&mut self, format: ExportFormat) {
+        let Some(pws) = self
+            .proc_state
+            .get_mut_widget_state(self.current_widget.widget_id)
+        else {
+            return;
+        };
+
+        let contents = pws.export(format);
+        match write_export(
+            &self.app_config_fields.process_export_dir,
+            format,
+            &contents,
+        ) {
+            Ok(path) => pws.set_export_notice(
+                path.file_name()
+                    .unwrap_or(path.as_os_str())
+                    .to_string_lossy()
+                    .to_string(),
+            ),
+            Err(err) => self.push_process_error(
+                "export",
+                format!(
+                    "couldn't write to {}: {err}",
+                    self.app_config_fields.process_export_dir.display()
+                ),
+            ),
+        }
+    }
+
+    /// Records a failed action on processes, showing the error log so it isn't missed.
+    fn push_process_error(&mut self, source: &str, message: impl ToString) {
+        self.push_collection_error(CollectionError::new(source, message));
+        self.error_log_state.is_showing = true;
+        self.is_force_redraw = true;
+    }
+
+    pub fn on_char_key(&mut self, caught_char: char) {
+        // Skip control code chars
+        if caught_char.is_control() {
+            return;
+        }
+
+        // Forbid any char key presses when showing a dialog box...
+        if !self.ignore_normal_keybinds() {
+            let current_key_press_inst = Instant::now();
+            if current_key_press_inst
+                .duration_since(self.last_key_press)
+                .as_millis()
+                > constants::MAX_KEY_TIMEOUT_IN_MILLISECONDS.into()
+            {
+                self.reset_multi_tap_keys();
+            }
+            self.last_key_press = current_key_press_inst;
+
+            if let BottomWidgetType::ProcSearch = self.current_widget.widget_type {
+                let is_in_search_widget = self.is_in_search_widget();
+                if let Some(proc_widget_state) = self
+                    .proc_state
+                    .widget_states
+                    .get_mut(&(self.current_widget.widget_id - 1))
+                {
+                    if is_in_search_widget && proc_widget_state.is_search_enabled() {
+                        proc_widget_state
+                            .proc_search
+                            .search_state
+                            .current_search_query
+                            .insert(proc_widget_state.cursor_char_index(), caught_char);
+
+                        proc_widget_state.proc_search.search_state.grapheme_cursor =
+                            GraphemeCursor::new(
+                                proc_widget_state.cursor_char_index(),
+                                proc_widget_state
+                                    .proc_search
+                                    .search_state
                                     .current_search_query
                                     .len(),
                                 true,
@@ -1082,6 +2143,10 @@ impl App {
                         return;
                     }
                 }
+            } else if let Some(connections_widget_state) = self.get_mut_port_filter_input_state() {
+                connections_widget_state.push_port_filter_digit(caught_char);
+                self.refresh_port_filter();
+                return;
             }
             self.handle_char(caught_char);
         } else if self.help_dialog_state.is_showing_help {
@@ -1097,7 +2162,31 @@ impl App {
                         }
                     }
                 }
-                'j' | 'k' | 'g' | 'G' => self.handle_char(caught_char),
+                _ => {}
+            }
+        } else if self.spawn_dialog_state.is_showing {
+            if self.spawn_dialog_state.result.is_none() {
+                self.spawn_dialog_state.input.insert_char(caught_char);
+            }
+        } else if self.is_showing_io_priority_dialog() {
+            match caught_char {
+                'h' => self.on_left_key(),
+                'j' => self.on_down_key(),
+                'k' => self.on_up_key(),
+                'l' => self.on_right_key(),
+                #[cfg(target_os = "linux")]
+                '0'..='9' => self.io_priority_dialog_state.set_level(caught_char),
+                _ => {}
+            }
+        } else if self.user_picker_state.is_showing
+            || self.is_showing_mem_map_overlay()
+            || self.process_details_overlay.is_some()
+        {
+            match caught_char {
+                'j' => self.on_down_key(),
+                'k' => self.on_up_key(),
+                'g' => self.skip_to_first(),
+                'G' => self.skip_to_last(),
                 _ => {}
             }
         } else if self.delete_dialog_state.is_showing_dd {
@@ -1127,6 +2216,7 @@ impl App {
                     }
                 }
                 'G' => self.skip_to_last(),
+                's' | 'S' => self.open_signal_picker(),
                 _ => {}
             }
         }
@@ -1135,62 +2225,22 @@ impl App {
     // FIXME: Refactor this system...
     fn handle_char(&mut self, caught_char: char) {
         match caught_char {
-            '/' => {
-                self.on_slash();
-            }
             'd' => {
-                if let BottomWidgetType::Proc = self.current_widget.widget_type {
-                    let mut is_first_d = true;
-                    if let Some(second_char) = self.second_char {
-                        if self.awaiting_second_char && second_char == 'd' {
-                            is_first_d = false;
-                            self.awaiting_second_char = false;
-                            self.second_char = None;
-
-                            self.start_killing_process();
-                        }
-                    }
-
-                    if is_first_d {
-                        self.awaiting_second_char = true;
-                        self.second_char = Some('d');
-                    }
-                } else if let Some(disk) = self
+                if let Some(disk) = self
                     .disk_state
                     .get_mut_widget_state(self.current_widget.widget_id)
                 {
                     disk.set_index(0);
                 }
             }
-            'g' => {
-                let mut is_first_g = true;
-                if let Some(second_char) = self.second_char {
-                    if self.awaiting_second_char && second_char == 'g' {
-                        is_first_g = false;
-                        self.awaiting_second_char = false;
-                        self.second_char = None;
-                        self.skip_to_first();
-                    }
-                }
-
-                if is_first_g {
-                    self.awaiting_second_char = true;
-                    self.second_char = Some('g');
-                }
-            }
-            'G' => self.skip_to_last(),
-            'k' => self.on_up_key(),
-            'j' => self.on_down_key(),
-            'f' => {
-                self.frozen_state.toggle(&self.data_collection); // TODO: Thawing should force a full data refresh and redraw immediately.
-            }
+            'F' => self.toggle_widget_freeze(),
             'c' => {
                 if let BottomWidgetType::Proc = self.current_widget.widget_type {
                     if let Some(proc_widget_state) = self
                         .proc_state
                         .get_mut_widget_state(self.current_widget.widget_id)
                     {
-                        proc_widget_state.select_column(ProcWidgetState::CPU);
+                        proc_widget_state.select_cpu_column();
                     }
                 }
             }
@@ -1200,7 +2250,7 @@ impl App {
                         .proc_state
                         .get_mut_widget_state(self.current_widget.widget_id)
                     {
-                        proc_widget_state.select_column(ProcWidgetState::MEM);
+                        proc_widget_state.select_mem_column();
                     }
                 } else if let Some(disk) = self
                     .disk_state
@@ -1215,13 +2265,18 @@ impl App {
                         .proc_state
                         .get_mut_widget_state(self.current_widget.widget_id)
                     {
-                        proc_widget_state.select_column(ProcWidgetState::PID_OR_COUNT);
+                        proc_widget_state.select_pid_or_count_column();
                     }
                 } else if let Some(disk) = self
                     .disk_state
                     .get_mut_widget_state(self.current_widget.widget_id)
                 {
                     disk.set_index(5);
+                } else if let Some(connections) = self
+                    .connections_state
+                    .get_mut_widget_state(self.current_widget.widget_id)
+                {
+                    connections.open_port_filter_input();
                 }
             }
             'P' => {
@@ -1232,6 +2287,78 @@ impl App {
                     {
                         proc_widget_state.toggle_command();
                     }
+                } else if let Some(connections) = self
+                    .connections_state
+                    .get_mut_widget_state(self.current_widget.widget_id)
+                {
+                    connections.cycle_protocol_filter();
+                    connections.ingest_data(&self.converted_data.connections_data);
+                }
+            }
+            'N' => {
+                if let BottomWidgetType::Proc = self.current_widget.widget_type {
+                    self.spawn_dialog_state.open();
+                    self.is_force_redraw = true;
+                }
+            }
+            #[cfg(target_family = "unix")]
+            'M' => {
+                if let BottomWidgetType::Proc = self.current_widget.widget_type {
+                    self.open_mem_map_overlay();
+                }
+            }
+            #[cfg(target_os = "linux")]
+            'i' => {
+                if let BottomWidgetType::Proc = self.current_widget.widget_type {
+                    self.start_changing_io_priority();
+                }
+            }
+            'a' => {
+                if let BottomWidgetType::Proc = self.current_widget.widget_type {
+                    if let Some(proc_widget_state) = self
+                        .proc_state
+                        .get_mut_widget_state(self.current_widget.widget_id)
+                    {
+                        proc_widget_state.toggle_group_by(GroupBy::Executable);
+                    }
+                }
+            }
+            'U' => {
+                if let BottomWidgetType::Proc = self.current_widget.widget_type {
+                    self.cycle_user_filter();
+                }
+            }
+            'x' => {
+                if let BottomWidgetType::Proc = self.current_widget.widget_type {
+                    self.export_processes(ExportFormat::Csv);
+                }
+            }
+            'X' => {
+                if let BottomWidgetType::Proc = self.current_widget.widget_type {
+                    self.export_processes(ExportFormat::Json);
+                }
+            }
+            #[cfg(target_family = "unix")]
+            'z' => {
+                if let BottomWidgetType::Proc = self.current_widget.widget_type {
+                    self.stop_process(true);
+                }
+            }
+            #[cfg(target_family = "unix")]
+            'Z' => {
+                if let BottomWidgetType::Proc = self.current_widget.widget_type {
+                    self.stop_process(false);
+                }
+            }
+            #[cfg(target_os = "linux")]
+            'C' => {
+                if let BottomWidgetType::Proc = self.current_widget.widget_type {
+                    if let Some(proc_widget_state) = self
+                        .proc_state
+                        .get_mut_widget_state(self.current_widget.widget_id)
+                    {
+                        proc_widget_state.toggle_group_by(GroupBy::Container);
+                    }
                 }
             }
             'n' => {
@@ -1240,7 +2367,7 @@ impl App {
                         .proc_state
                         .get_mut_widget_state(self.current_widget.widget_id)
                     {
-                        proc_widget_state.select_column(ProcWidgetState::PROC_NAME_OR_CMD);
+                        proc_widget_state.select_name_or_command_column();
                     }
                 } else if let Some(disk) = self
                     .disk_state
@@ -1249,14 +2376,6 @@ impl App {
                     disk.set_index(3);
                 }
             }
-            '?' => {
-                self.help_dialog_state.is_showing_help = true;
-                self.is_force_redraw = true;
-            }
-            'H' | 'A' => self.move_widget_selection(&WidgetDirection::Left),
-            'L' | 'D' => self.move_widget_selection(&WidgetDirection::Right),
-            'K' | 'W' => self.move_widget_selection(&WidgetDirection::Up),
-            'J' | 'S' => self.move_widget_selection(&WidgetDirection::Down),
             't' => {
                 if let BottomWidgetType::Proc = self.current_widget.widget_type {
                     self.toggle_tree_mode()
@@ -1273,10 +2392,17 @@ impl App {
                     disk.set_index(4);
                 }
             }
+            ' ' => {
+                if let Some(pws) = self
+                    .proc_state
+                    .get_mut_widget_state(self.current_widget.widget_id)
+                {
+                    pws.toggle_current_selection();
+                }
+            }
             '+' => self.on_plus(),
             '-' => self.on_minus(),
             '=' => self.reset_zoom(),
-            'e' => self.toggle_expand_widget(),
             's' => {
                 if let BottomWidgetType::Proc = self.current_widget.widget_type {
                     self.toggle_sort_menu()
@@ -1313,6 +2439,34 @@ impl App {
                     disk.set_index(7);
                 }
             }
+            'v' => {
+                if let Some(connections) = self
+                    .connections_state
+                    .get_mut_widget_state(self.current_widget.widget_id)
+                {
+                    connections.toggle_listening_view();
+                    connections.ingest_data(&self.converted_data.connections_data);
+                    self.is_force_redraw = true;
+                }
+            }
+            'T' => {
+                if let Some(net) = self
+                    .net_state
+                    .get_mut_widget_state(self.current_widget.widget_id)
+                {
+                    net.toggle_tcp_stats();
+                    self.is_force_redraw = true;
+                }
+            }
+            'E' => {
+                if let Some(net) = self
+                    .net_state
+                    .get_mut_widget_state(self.current_widget.widget_id)
+                {
+                    net.toggle_interface_errors();
+                    self.is_force_redraw = true;
+                }
+            }
             'I' => self.invert_sort(),
             '%' => self.toggle_percentages(),
             _ => {}
@@ -1325,8 +2479,26 @@ impl App {
         }
     }
 
+    pub fn select_all_processes(&mut self) {
+        if let Some(pws) = self
+            .proc_state
+            .get_mut_widget_state(self.current_widget.widget_id)
+        {
+            pws.select_all();
+        }
+    }
+
     pub fn kill_highlighted_process(&mut self) -> Result<()> {
-        if let BottomWidgetType::Proc = self.current_widget.widget_type {
+        if let BottomWidgetType::Proc | BottomWidgetType::Connections =
+            self.current_widget.widget_type
+        {
+            #[cfg(target_os = "linux")]
+            if let Some((protocol, local, remote)) = self.to_close_connection {
+                data_harvester::network::sock_diag::destroy_socket(protocol, local, remote)?;
+                self.to_close_connection = None;
+                return Ok(());
+            }
+
             if let Some((_, pids)) = &self.to_delete_process_list {
                 #[cfg(target_family = "unix")]
                 let signal = match self.delete_dialog_state.selected_signal {
@@ -1345,10 +2517,17 @@ impl App {
                 }
             }
             self.to_delete_process_list = None;
+
+            if let Some(pws) = self
+                .proc_state
+                .get_mut_widget_state(self.current_widget.widget_id)
+            {
+                pws.clear_selection();
+            }
             Ok(())
         } else {
             Err(BottomError::GenericError(
-                "Cannot kill processes if the current widget is not the Process widget!"
+                "Cannot kill processes if the current widget is not the Process or Connections widget!"
                     .to_string(),
             ))
         }
@@ -1358,7 +2537,27 @@ impl App {
         self.to_delete_process_list.clone()
     }
 
-    fn toggle_expand_widget(&mut self) {
+    pub fn get_to_close_connection(&self) -> Option<(Protocol, SocketAddr, SocketAddr)> {
+        self.to_close_connection
+    }
+
+    /// Shows the tab at `index`, going back to the widget that was selected in it. Widgets keep
+    /// their state while their tab isn't shown.
+    pub fn switch_tab(&mut self, index: usize) {
+        if index == self.current_tab || index >= self.tabs.len() || self.ignore_normal_keybinds() {
+            return;
+        }
+
+        self.tabs[self.current_tab].widget_id = self.current_widget.widget_id;
+        self.current_tab = index;
+        if let Some(widget) = self.widget_map.get(&self.tabs[index].widget_id) {
+            self.current_widget = widget.clone();
+        }
+        self.is_expanded = false;
+        self.is_force_redraw = true;
+    }
+
+    pub fn toggle_expand_widget(&mut self) {
         if self.is_expanded {
             self.is_expanded = false;
             self.is_force_redraw = true;
@@ -1883,14 +3082,83 @@ impl App {
                         connections_widget_state.table.set_first();
                     }
                 }
+                BottomWidgetType::Dashboard => {
+                    if let Some(dashboard_widget_state) = self
+                        .dashboard_state
+                        .get_mut_widget_state(self.current_widget.widget_id)
+                    {
+                        dashboard_widget_state.table.set_first();
+                    }
+                }
+                BottomWidgetType::Discovery => {
+                    if let Some(discovery_widget_state) = self
+                        .discovery_state
+                        .get_mut_widget_state(self.current_widget.widget_id)
+                    {
+                        discovery_widget_state.table.set_first();
+                    }
+                }
+                BottomWidgetType::Containers => {
+                    if let Some(container_widget_state) = self
+                        .container_state
+                        .get_mut_widget_state(self.current_widget.widget_id)
+                    {
+                        container_widget_state.table.set_first();
+                    }
+                }
+                BottomWidgetType::Raid => {
+                    if let Some(raid_widget_state) = self
+                        .raid_state
+                        .get_mut_widget_state(self.current_widget.widget_id)
+                    {
+                        raid_widget_state.table.set_first();
+                    }
+                }
+                BottomWidgetType::ZfsPools => {
+                    if let Some(zfs_pool_widget_state) = self
+                        .zfs_pool_state
+                        .get_mut_widget_state(self.current_widget.widget_id)
+                    {
+                        zfs_pool_widget_state.table.set_first();
+                    }
+                }
+                BottomWidgetType::Cgroups => {
+                    if let Some(cgroup_widget_state) = self
+                        .cgroup_state
+                        .get_mut_widget_state(self.current_widget.widget_id)
+                    {
+                        cgroup_widget_state.table.set_first();
+                    }
+                }
+                BottomWidgetType::ScheduledJobs => {
+                    if let Some(scheduled_job_widget_state) = self
+                        .scheduled_job_state
+                        .get_mut_widget_state(self.current_widget.widget_id)
+                    {
+                        scheduled_job_widget_state.table.set_first();
+                    }
+                }
 
                 _ => {}
             }
             self.reset_multi_tap_keys();
         } else if self.help_dialog_state.is_showing_help {
             self.help_dialog_state.scroll_state.current_scroll_index = 0;
+        } else if self.user_picker_state.is_showing {
+            self.user_picker_state.move_to_first();
+        } else if self.is_showing_mem_map_overlay() {
+            #[cfg(target_family = "unix")]
+            if let Some(overlay) = &mut self.mem_map_overlay {
+                overlay.scroll_to_first();
+            }
+        } else if let Some(overlay) = &mut self.process_details_overlay {
+            overlay.scroll_to_first();
         } else if self.delete_dialog_state.is_showing_dd {
-            self.delete_dialog_state.selected_signal = KillSignal::Cancel;
+            if self.is_choosing_signal() {
+                self.delete_dialog_state.selected_signal = KillSignal::Cancel;
+            } else {
+                self.delete_dialog_state.selected_signal = KillSignal::default();
+            }
         }
     }
 
@@ -1947,14 +3215,88 @@ impl App {
                         connections_widget_state.table.set_last();
                     }
                 }
+                BottomWidgetType::Dashboard => {
+                    if let Some(dashboard_widget_state) = self
+                        .dashboard_state
+                        .get_mut_widget_state(self.current_widget.widget_id)
+                    {
+                        dashboard_widget_state.table.set_last();
+                    }
+                }
+                BottomWidgetType::Discovery => {
+                    if let Some(discovery_widget_state) = self
+                        .discovery_state
+                        .get_mut_widget_state(self.current_widget.widget_id)
+                    {
+                        discovery_widget_state.table.set_last();
+                    }
+                }
+                BottomWidgetType::Containers => {
+                    if let Some(container_widget_state) = self
+                        .container_state
+                        .get_mut_widget_state(self.current_widget.widget_id)
+                    {
+                        container_widget_state.table.set_last();
+                    }
+                }
+                BottomWidgetType::Raid => {
+                    if let Some(raid_widget_state) = self
+                        .raid_state
+                        .get_mut_widget_state(self.current_widget.widget_id)
+                    {
+                        raid_widget_state.table.set_last();
+                    }
+                }
+                BottomWidgetType::ZfsPools => {
+                    if let Some(zfs_pool_widget_state) = self
+                        .zfs_pool_state
+                        .get_mut_widget_state(self.current_widget.widget_id)
+                    {
+                        zfs_pool_widget_state.table.set_last();
+                    }
+                }
+                BottomWidgetType::Cgroups => {
+                    if let Some(cgroup_widget_state) = self
+                        .cgroup_state
+                        .get_mut_widget_state(self.current_widget.widget_id)
+                    {
+                        cgroup_widget_state.table.set_last();
+                    }
+                }
+                BottomWidgetType::ScheduledJobs => {
+                    if let Some(scheduled_job_widget_state) = self
+                        .scheduled_job_state
+                        .get_mut_widget_state(self.current_widget.widget_id)
+                    {
+                        scheduled_job_widget_state.table.set_last();
+                    }
+                }
                 _ => {}
             }
             self.reset_multi_tap_keys();
         } else if self.help_dialog_state.is_showing_help {
             self.help_dialog_state.scroll_state.current_scroll_index =
                 self.help_dialog_state.scroll_state.max_scroll_index;
+        } else if self.user_picker_state.is_showing {
+            self.user_picker_state.move_to_last();
+        } else if self.is_showing_mem_map_overlay() {
+            #[cfg(target_family = "unix")]
+            if let Some(overlay) = &mut self.mem_map_overlay {
+                overlay.scroll_to_last();
+            }
+        } else if let Some(overlay) = &mut self.process_details_overlay {
+            overlay.scroll_to_last();
         } else if self.delete_dialog_state.is_showing_dd {
-            self.delete_dialog_state.selected_signal = KillSignal::Kill(MAX_SIGNAL);
+            #[cfg(target_family = "unix")]
+            if self.is_choosing_signal() {
+                self.delete_dialog_state.selected_signal = signals::from_picker_index(usize::MAX);
+            } else {
+                self.delete_dialog_state.selected_signal = KillSignal::Cancel;
+            }
+            #[cfg(target_os = "windows")]
+            {
+                self.delete_dialog_state.selected_signal = KillSignal::Cancel;
+            }
         }
     }
 
@@ -1977,6 +3319,13 @@ impl App {
                 BottomWidgetType::Disk => self.change_disk_position(amount),
                 BottomWidgetType::CpuLegend => self.change_cpu_legend_position(amount),
                 BottomWidgetType::Connections => self.change_connections_position(amount),
+                BottomWidgetType::Dashboard => self.change_dashboard_position(amount),
+                BottomWidgetType::Discovery => self.change_discovery_position(amount),
+                BottomWidgetType::Containers => self.change_container_position(amount),
+                BottomWidgetType::Raid => self.change_raid_position(amount),
+                BottomWidgetType::ZfsPools => self.change_zfs_pool_position(amount),
+                BottomWidgetType::Cgroups => self.change_cgroup_position(amount),
+                BottomWidgetType::ScheduledJobs => self.change_scheduled_job_position(amount),
                 _ => {}
             }
         }
@@ -2047,6 +3396,88 @@ impl App {
         }
     }
 
+    fn change_dashboard_position(&mut self, num_to_change_by: i64) {
+        if let Some(dashboard_widget_state) = self
+            .dashboard_state
+            .widget_states
+            .get_mut(&self.current_widget.widget_id)
+        {
+            dashboard_widget_state
+                .table
+                .increment_position(num_to_change_by);
+        }
+    }
+
+    fn change_discovery_position(&mut self, num_to_change_by: i64) {
+        if let Some(discovery_widget_state) = self
+            .discovery_state
+            .widget_states
+            .get_mut(&self.current_widget.widget_id)
+        {
+            discovery_widget_state
+                .table
+                .increment_position(num_to_change_by);
+        }
+    }
+
+    fn change_container_position(&mut self, num_to_change_by: i64) {
+        if let Some(container_widget_state) = self
+            .container_state
+            .widget_states
+            .get_mut(&self.current_widget.widget_id)
+        {
+            container_widget_state
+                .table
+                .increment_position(num_to_change_by);
+        }
+    }
+
+    fn change_raid_position(&mut self, num_to_change_by: i64) {
+        if let Some(raid_widget_state) = self
+            .raid_state
+            .widget_states
+            .get_mut(&self.current_widget.widget_id)
+        {
+            raid_widget_state.table.increment_position(num_to_change_by);
+        }
+    }
+
+    fn change_zfs_pool_position(&mut self, num_to_change_by: i64) {
+        if let Some(zfs_pool_widget_state) = self
+            .zfs_pool_state
+            .widget_states
+            .get_mut(&self.current_widget.widget_id)
+        {
+            zfs_pool_widget_state
+                .table
+                .increment_position(num_to_change_by);
+        }
+    }
+
+    fn change_cgroup_position(&mut self, num_to_change_by: i64) {
+        if let Some(cgroup_widget_state) = self
+            .cgroup_state
+            .widget_states
+            .get_mut(&self.current_widget.widget_id)
+        {
+            cgroup_widget_state
+                .table
+                .increment_position(num_to_change_by);
+        }
+    }
+
+    fn change_scheduled_job_position(&mut self, num_to_change_by: i64) {
+        if let Some(scheduled_job_widget_state) = self
+            .scheduled_job_state
+            .widget_states
+            .get_mut(&self.current_widget.widget_id)
+        {
+            scheduled_job_widget_state
+                .table
+                .increment_position(num_to_change_by);
+        }
+    }
+
     fn help_scroll_up(&mut self) {
         if self.help_dialog_state.scroll_state.current_scroll_index > 0 {
             self.help_dialog_state.scroll_state.current_scroll_index -= 1;
@@ -2084,6 +3515,11 @@ impl App {
             self.zoom_in();
         } else if self.current_widget.widget_type.is_widget_table() {
             self.decrement_position_count();
+        } else if let Some(log_widget_state) = self
+            .log_state
+            .get_mut_widget_state(self.current_widget.widget_id)
+        {
+            log_widget_state.scrollback.scroll_up();
         }
     }
 
@@ -2101,24 +3537,29 @@ impl App {
             self.zoom_out();
         } else if self.current_widget.widget_type.is_widget_table() {
             self.increment_position_count();
+        } else if let Some(log_widget_state) = self
+            .log_state
+            .get_mut_widget_state(self.current_widget.widget_id)
+        {
+            log_widget_state.scrollback.scroll_down();
         }
     }
 
     fn on_plus(&mut self) {
-        if let BottomWidgetType::Proc = self.current_widget.widget_type {
+        match self.current_widget.widget_type {
             // Toggle collapsing if tree
-            self.toggle_collapsing_process_branch();
-        } else {
-            self.zoom_in();
+            BottomWidgetType::Proc => self.toggle_collapsing_process_branch(),
+            BottomWidgetType::Cgroups => self.toggle_collapsing_cgroup_branch(),
+            _ => self.zoom_in(),
         }
     }
 
     fn on_minus(&mut self) {
-        if let BottomWidgetType::Proc = self.current_widget.widget_type {
+        match self.current_widget.widget_type {
             // Toggle collapsing if tree
-            self.toggle_collapsing_process_branch();
-        } else {
-            self.zoom_out();
+            BottomWidgetType::Proc => self.toggle_collapsing_process_branch(),
+            BottomWidgetType::Cgroups => self.toggle_collapsing_cgroup_branch(),
+            _ => self.zoom_out(),
         }
     }
 
@@ -2132,6 +3573,17 @@ impl App {
         }
     }
 
+    fn toggle_collapsing_cgroup_branch(&mut self) {
+        if let Some(cgroup_widget_state) = self
+            .cgroup_state
+            .widget_states
+            .get_mut(&self.current_widget.widget_id)
+        {
+            cgroup_widget_state.toggle_current_branch();
+            cgroup_widget_state.ingest_data(&self.converted_data.cgroup_data);
+        }
+    }
+
     fn zoom_out(&mut self) {
         match self.current_widget.widget_type {
             BottomWidgetType::Cpu => {
@@ -2209,6 +3661,54 @@ impl App {
                     }
                 }
             }
+            BottomWidgetType::Pressure => {
+                if let Some(pressure_widget_state) = self
+                    .pressure_state
+                    .widget_states
+                    .get_mut(&self.current_widget.widget_id)
+                {
+                    let new_time = pressure_widget_state.current_display_time
+                        + self.app_config_fields.time_interval;
+                    if new_time <= self.app_config_fields.retention_ms {
+                        pressure_widget_state.current_display_time = new_time;
+                        if self.app_config_fields.autohide_time {
+                            pressure_widget_state.autohide_timer = Some(Instant::now());
+                        }
+                    } else if pressure_widget_state.current_display_time
+                        != self.app_config_fields.retention_ms
+                    {
+                        pressure_widget_state.current_display_time =
+                            self.app_config_fields.retention_ms;
+                        if self.app_config_fields.autohide_time {
+                            pressure_widget_state.autohide_timer = Some(Instant::now());
+                        }
+                    }
+                }
+            }
+            BottomWidgetType::Wifi => {
+                if let Some(wifi_widget_state) = self
+                    .wifi_state
+                    .widget_states
+                    .get_mut(&self.current_widget.widget_id)
+                {
+                    let new_time = wifi_widget_state.current_display_time
+                        + self.app_config_fields.time_interval;
+                    if new_time <= self.app_config_fields.retention_ms {
+                        wifi_widget_state.current_display_time = new_time;
+                        if self.app_config_fields.autohide_time {
+                            wifi_widget_state.autohide_timer = Some(Instant::now());
+                        }
+                    } else if wifi_widget_state.current_display_time
+                        != self.app_config_fields.retention_ms
+                    {
+                        wifi_widget_state.current_display_time =
+                            self.app_config_fields.retention_ms;
+                        if self.app_config_fields.autohide_time {
+                            wifi_widget_state.autohide_timer = Some(Instant::now());
+                        }
+                    }
+                }
+            }
             _ => {}
         }
     }
@@ -2290,6 +3790,53 @@ impl App {
                     }
                 }
             }
+            BottomWidgetType::Pressure => {
+                if let Some(pressure_widget_state) = self
+                    .pressure_state
+                    .widget_states
+                    .get_mut(&self.current_widget.widget_id)
+                {
+                    let new_time = pressure_widget_state.current_display_time
+                        - self.app_config_fields.time_interval;
+                    if new_time >= constants::STALE_MIN_MILLISECONDS {
+                        pressure_widget_state.current_display_time = new_time;
+                        if self.app_config_fields.autohide_time {
+                            pressure_widget_state.autohide_timer = Some(Instant::now());
+                        }
+                    } else if pressure_widget_state.current_display_time
+                        != constants::STALE_MIN_MILLISECONDS
+                    {
+                        pressure_widget_state.current_display_time =
+                            constants::STALE_MIN_MILLISECONDS;
+                        if self.app_config_fields.autohide_time {
+                            pressure_widget_state.autohide_timer = Some(Instant::now());
+                        }
+                    }
+                }
+            }
+            BottomWidgetType::Wifi => {
+                if let Some(wifi_widget_state) = self
+                    .wifi_state
+                    .widget_states
+                    .get_mut(&self.current_widget.widget_id)
+                {
+                    let new_time = wifi_widget_state.current_display_time
+                        - self.app_config_fields.time_interval;
+                    if new_time >= constants::STALE_MIN_MILLISECONDS {
+                        wifi_widget_state.current_display_time = new_time;
+                        if self.app_config_fields.autohide_time {
+                            wifi_widget_state.autohide_timer = Some(Instant::now());
+                        }
+                    } else if wifi_widget_state.current_display_time
+                        != constants::STALE_MIN_MILLISECONDS
+                    {
+                        wifi_widget_state.current_display_time = constants::STALE_MIN_MILLISECONDS;
+                        if self.app_config_fields.autohide_time {
+                            wifi_widget_state.autohide_timer = Some(Instant::now());
+                        }
+                    }
+                }
+            }
             _ => {}
         }
     }
@@ -2336,11 +3883,39 @@ impl App {
         }
     }
 
+    fn reset_pressure_zoom(&mut self) {
+        if let Some(pressure_widget_state) = self
+            .pressure_state
+            .widget_states
+            .get_mut(&self.current_widget.widget_id)
+        {
+            pressure_widget_state.current_display_time = self.app_config_fields.default_time_value;
+            if self.app_config_fields.autohide_time {
+                pressure_widget_state.autohide_timer = Some(Instant::now());
+            }
+        }
+    }
+
+    fn reset_wifi_zoom(&mut self) {
+        if let Some(wifi_widget_state) = self
+            .wifi_state
+            .widget_states
+            .get_mut(&self.current_widget.widget_id)
+        {
+            wifi_widget_state.current_display_time = self.app_config_fields.default_time_value;
+            if self.app_config_fields.autohide_time {
+                wifi_widget_state.autohide_timer = Some(Instant::now());
+            }
+        }
+    }
+
     fn reset_zoom(&mut self) {
         match self.current_widget.widget_type {
             BottomWidgetType::Cpu => self.reset_cpu_zoom(),
             BottomWidgetType::Mem => self.reset_mem_zoom(),
             BottomWidgetType::Net => self.reset_net_zoom(),
+            BottomWidgetType::Pressure => self.reset_pressure_zoom(),
+            BottomWidgetType::Wifi => self.reset_wifi_zoom(),
             _ => {}
         }
     }
@@ -2429,12 +4004,8 @@ impl App {
                 Some((_, _, _, _, 0)) => {
                     self.delete_dialog_state.selected_signal = KillSignal::Cancel
                 }
-                Some((_, _, _, _, idx)) => {
-                    if *idx > 31 {
-                        self.delete_dialog_state.selected_signal = KillSignal::Kill(*idx + 2)
-                    } else {
-                        self.delete_dialog_state.selected_signal = KillSignal::Kill(*idx)
-                    }
+                Some((_, _, _, _, signal)) => {
+                    self.delete_dialog_state.selected_signal = KillSignal::Kill(*signal)
                 }
                 _ => {}
             }
@@ -2494,7 +4065,14 @@ impl App {
                     | BottomWidgetType::CpuLegend
                     | BottomWidgetType::Temp
                     | BottomWidgetType::Disk
-                    | BottomWidgetType::Connections => {
+                    | BottomWidgetType::Connections
+                    | BottomWidgetType::Dashboard
+                    | BottomWidgetType::Discovery
+                    | BottomWidgetType::Containers
+                    | BottomWidgetType::Raid
+                    | BottomWidgetType::ZfsPools
+                    | BottomWidgetType::Cgroups
+                    | BottomWidgetType::ScheduledJobs => {
                         // Get our index...
                         let clicked_entry = y - *tlc_y;
                         let header_offset = self.header_offset(&self.current_widget);
@@ -2599,6 +4177,104 @@ impl App {
                                         }
                                     }
                                 }
+                                BottomWidgetType::Dashboard => {
+                                    if let Some(dashboard_widget_state) = self
+                                        .dashboard_state
+                                        .get_widget_state(self.current_widget.widget_id)
+                                    {
+                                        if let Some(visual_index) =
+                                            dashboard_widget_state.table.tui_selected()
+                                        {
+                                            self.change_dashboard_position(
+                                                offset_clicked_entry as i64 - visual_index as i64,
+                                            );
+                                        }
+                                    }
+                                }
+                                BottomWidgetType::Discovery => {
+                                    if let Some(discovery_widget_state) = self
+                                        .discovery_state
+                                        .get_widget_state(self.current_widget.widget_id)
+                                    {
+                                        if let Some(visual_index) =
+                                            discovery_widget_state.table.tui_selected()
+                                        {
+                                            self.change_discovery_position(
+                                                offset_clicked_entry as i64 - visual_index as i64,
+                                            );
+                                        }
+                                    }
+                                }
+                                BottomWidgetType::Containers => {
+                                    if let Some(container_widget_state) = self
+                                        .container_state
+                                        .get_widget_state(self.current_widget.widget_id)
+                                    {
+                                        if let Some(visual_index) =
+                                            container_widget_state.table.tui_selected()
+                                        {
+                                            self.change_container_position(
+                                                offset_clicked_entry as i64 - visual_index as i64,
+                                            );
+                                        }
+                                    }
+                                }
+                                BottomWidgetType::Raid => {
+                                    if let Some(raid_widget_state) = self
+                                        .raid_state
+                                        .get_widget_state(self.current_widget.widget_id)
+                                    {
+                                        if let Some(visual_index) =
+                                            raid_widget_state.table.tui_selected()
+                                        {
+                                            self.change_raid_position(
+                                                offset_clicked_entry as i64 - visual_index as i64,
+                                            );
+                                        }
+                                    }
+                                }
+                                BottomWidgetType::ZfsPools => {
+                                    if let Some(zfs_pool_widget_state) = self
+                                        .zfs_pool_state
+                                        .get_widget_state(self.current_widget.widget_id)
+                                    {
+                                        if let Some(visual_index) =
+                                            zfs_pool_widget_state.table.tui_selected()
+                                        {
+                                            self.change_zfs_pool_position(
+                                                offset_clicked_entry as i64 - visual_index as i64,
+                                            );
+                                        }
+                                    }
+                                }
+                                BottomWidgetType::Cgroups => {
+                                    if let Some(cgroup_widget_state) = self
+                                        .cgroup_state
+                                        .get_widget_state(self.current_widget.widget_id)
+                                    {
+                                        if let Some(visual_index) =
+                                            cgroup_widget_state.table.tui_selected()
+                                        {
+                                            self.change_cgroup_position(
+                                                offset_clicked_entry as i64 - visual_index as i64,
+                                            );
+                                        }
+                                    }
+                                }
+                                BottomWidgetType::ScheduledJobs => {
+                                    if let Some(scheduled_job_widget_state) = self
+                                        .scheduled_job_state
+                                        .get_widget_state(self.current_widget.widget_id)
+                                    {
+                                        if let Some(visual_index) =
+                                            scheduled_job_widget_state.table.tui_selected()
+                                        {
+                                            self.change_scheduled_job_position(
+                                                offset_clicked_entry as i64 - visual_index as i64,
+                                            );
+                                        }
+                                    }
+                                }
                                 _ => {}
                             }
                         } else {
@@ -2644,6 +4320,95 @@ impl App {
                                             connections.table.try_select_location(x, y).unwrap();
                                         }
                                     }
+                                    BottomWidgetType::Dashboard => {
+                                        if let Some(dashboard) = self
+                                            .dashboard_state
+                                            .get_mut_widget_state(self.current_widget.widget_id)
+                                        {
+                                            if dashboard.table.try_select_location(x, y).is_some() {
+                                                dashboard.ingest_data(
+                                                    &self.data_collection.agent_harvest,
+                                                );
+                                            }
+                                        }
+                                    }
+                                    BottomWidgetType::Containers => {
+                                        if let Some(containers) = self
+                                            .container_state
+                                            .get_mut_widget_state(self.current_widget.widget_id)
+                                        {
+                                            if containers.table.try_select_location(x, y).is_some()
+                                            {
+                                                containers.ingest_data(
+                                                    &self.converted_data.container_data,
+                                                );
+                                            }
+                                        }
+                                    }
+                                    BottomWidgetType::Raid => {
+                                        if let Some(raid) = self
+                                            .raid_state
+                                            .get_mut_widget_state(self.current_widget.widget_id)
+                                        {
+                                            if raid.table.try_select_location(x, y).is_some() {
+                                                raid.ingest_data(&self.converted_data.raid_data);
+                                            }
+                                        }
+                                    }
+                                    BottomWidgetType::ZfsPools => {
+                                        if let Some(zfs_pools) = self
+                                            .zfs_pool_state
+                                            .get_mut_widget_state(self.current_widget.widget_id)
+                                        {
+                                            if zfs_pools.table.try_select_location(x, y).is_some() {
+                                                zfs_pools.ingest_data(
+                                                    &self.converted_data.zfs_pool_data,
+                                                );
+                                            }
+                                        }
+                                    }
+                                    BottomWidgetType::Cgroups => {
+                                        if let Some(cgroups) = self
+                                            .cgroup_state
+                                            .get_mut_widget_state(self.current_widget.widget_id)
+                                        {
+                                            if cgroups.table.try_select_location(x, y).is_some() {
+                                                cgroups
+                                                    .ingest_data(&self.converted_data.cgroup_data);
+                                            }
+                                        }
+                                    }
+                                    BottomWidgetType::ScheduledJobs => {
+                                        if let Some(scheduled_jobs) = self
+                                            .scheduled_job_state
+                                            .get_mut_widget_state(self.current_widget.widget_id)
+                                        {
+                                            if scheduled_jobs
+                                                .table
+                                                .try_select_location(x, y)
+                                                .is_some()
+                                            {
+                                                scheduled_jobs.ingest_data(
+                                                    &self.converted_data.scheduled_job_data,
+                                                );
+                                            }
+                                        }
+                                    }
+                                    BottomWidgetType::Discovery => {
+                                        if let Some(discovery) = self
+                                            .discovery_state
+                                            .get_mut_widget_state(self.current_widget.widget_id)
+                                        {
+                                            if discovery.table.try_select_location(x, y).is_some() {
+                                                discovery.ingest_data(
+                                                    &self.discovered_agents,
+                                                    self.app_config_fields
+                                                        .remote_address
+                                                        .as_deref(),
+                                                );
+                                            }
+                                        }
+                                    }
                                     _ => (),
                                 }
                             }
@@ -0,0 +1,362 @@
+//! Alerts set up under `[[alert]]` in the config, which go off once a metric has been past its
+//! threshold for long enough.
+
+use std::time::{Duration, Instant};
+
+use crate::app::{
+    data_farmer::DataCollection,
+    data_harvester::{cpu::CpuDataType, temperature::SensorType},
+    layout_manager::BottomWidgetType,
+};
+
+/// What an alert watches.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AlertMetric {
+    /// The average CPU usage, in percent.
+    Cpu,
+    /// The memory in use, in percent.
+    Memory,
+    /// The swap in use, in percent.
+    Swap,
+    /// The hottest sensor, in the temperature unit that bottom shows.
+    Temperature,
+    /// The fullest disk, in percent.
+    Disk,
+    /// The one minute load average.
+    Load,
+    /// The rate data is received at, in megabits per second.
+    Rx,
+    /// The rate data is sent at, in megabits per second.
+    Tx,
+}
+
+impl std::str::FromStr for AlertMetric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "cpu" => Ok(AlertMetric::Cpu),
+            "mem" | "memory" => Ok(AlertMetric::Memory),
+            "swap" => Ok(AlertMetric::Swap),
+            "temp" | "temperature" => Ok(AlertMetric::Temperature),
+            "disk" => Ok(AlertMetric::Disk),
+            "load" => Ok(AlertMetric::Load),
+            "rx" => Ok(AlertMetric::Rx),
+            "tx" => Ok(AlertMetric::Tx),
+            _ => Err(format!(
+                "'{s}' is not a valid alert metric, use 'cpu', 'mem', 'swap', 'temp', 'disk', 'load', 'rx' or 'tx'."
+            )),
+        }
+    }
+}
+
+impl AlertMetric {
+    fn name(&self) -> &'static str {
+        match self {
+            AlertMetric::Cpu => "cpu",
+            AlertMetric::Memory => "mem",
+            AlertMetric::Swap => "swap",
+            AlertMetric::Temperature => "temp",
+            AlertMetric::Disk => "disk",
+            AlertMetric::Load => "load",
+            AlertMetric::Rx => "rx",
+            AlertMetric::Tx => "tx",
+        }
+    }
+
+    /// Returns the metric's latest value, if it has been collected.
+    fn value(&self, data: &DataCollection) -> Option<f64> {
+        match self {
+            AlertMetric::Cpu => data
+                .cpu_harvest
+                .iter()
+                .find(|cpu| matches!(cpu.data_type, CpuDataType::Avg))
+                .map(|cpu| cpu.cpu_usage),
+            AlertMetric::Memory => data.memory_harvest.use_percent,
+            AlertMetric::Swap => data.swap_harvest.use_percent,
+            AlertMetric::Temperature => data
+                .temp_harvest
+                .iter()
+                .filter(|sensor| sensor.sensor_type == SensorType::Temperature)
+                .map(|sensor| f64::from(sensor.value))
+                .reduce(f64::max),
+            AlertMetric::Disk => data
+                .disk_harvest
+                .iter()
+                .filter_map(|disk| match (disk.used_space, disk.total_space) {
+                    (Some(used_space), Some(total_space)) if total_space > 0 => {
+                        Some(used_space as f64 / total_space as f64 * 100.0)
+                    }
+                    _ => None,
+                })
+                .reduce(f64::max),
+            AlertMetric::Load => Some(f64::from(data.load_avg_harvest[0])),
+            AlertMetric::Rx => Some(data.network_harvest.rx as f64 / 1_000_000.0),
+            AlertMetric::Tx => Some(data.network_harvest.tx as f64 / 1_000_000.0),
+        }
+    }
+
+    /// Returns whether the metric is shown in a widget of type `widget_type`, so that the widget
+    /// can be marked while the alert is going off.
+    pub fn is_shown_in(&self, widget_type: &BottomWidgetType) -> bool {
+        use BottomWidgetType::*;
+
+        match self {
+            AlertMetric::Cpu | AlertMetric::Load => {
+                matches!(widget_type, Cpu | CpuLegend | BasicCpu)
+            }
+            AlertMetric::Memory | AlertMetric::Swap => matches!(widget_type, Mem | BasicMem),
+            AlertMetric::Temperature => matches!(widget_type, Temp),
+            AlertMetric::Disk => matches!(widget_type, Disk),
+            AlertMetric::Rx | AlertMetric::Tx => matches!(widget_type, Net | BasicNet),
+        }
+    }
+}
+
+/// How a metric is compared against its threshold.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Comparison {
+    #[default]
+    Above,
+    AtLeast,
+    Below,
+    AtMost,
+}
+
+impl std::str::FromStr for Comparison {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            ">" => Ok(Comparison::Above),
+            ">=" => Ok(Comparison::AtLeast),
+            "<" => Ok(Comparison::Below),
+            "<=" => Ok(Comparison::AtMost),
+            _ => Err(format!(
+                "'{s}' is not a valid comparison, use '>', '>=', '<' or '<='."
+            )),
+        }
+    }
+}
+
+impl Comparison {
+    fn symbol(&self) -> &'static str {
+        match self {
+            Comparison::Above => ">",
+            Comparison::AtLeast => ">=",
+            Comparison::Below => "<",
+            Comparison::AtMost => "<=",
+        }
+    }
+
+    fn holds(&self, value: f64, threshold: f64) -> bool {
+        match self {
+            Comparison::Above => value > threshold,
+            Comparison::AtLeast => value >= threshold,
+            Comparison::Below => value < threshold,
+            Comparison::AtMost => value <= threshold,
+        }
+    }
+}
+
+/// What happens when an alert goes off, besides it being shown.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum AlertAction {
+    #[default]
+    Show,
+    /// Rings the terminal bell.
+    Bell,
+    /// Runs a command through the system shell.
+    Command(String),
+}
+
+impl AlertAction {
+    /// Parses an action, where `command` is what a `"command"` action runs.
+    pub fn new(action: &str, command: Option<&str>) -> Result<Self, String> {
+        match (action, command) {
+            ("show", _) => Ok(AlertAction::Show),
+            ("bell", _) => Ok(AlertAction::Bell),
+            ("command", Some(command)) => Ok(AlertAction::Command(command.to_string())),
+            ("command", None) => {
+                Err("A 'command' alert action needs a command to run.".to_string())
+            }
+            _ => Err(format!(
+                "'{action}' is not a valid alert action, use 'show', 'bell' or 'command'."
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AlertRule {
+    pub metric: AlertMetric,
+    pub comparison: Comparison,
+    pub threshold: f64,
+    /// How long the metric has to stay past the threshold before the alert goes off.
+    pub duration: Duration,
+    pub action: AlertAction,
+}
+
+/// An alert and how long its metric has been past the threshold for.
+pub struct Alert {
+    pub rule: AlertRule,
+    /// When the metric went past the threshold, if it still is.
+    breached_since: Option<Instant>,
+    pub is_triggered: bool,
+    /// The latest value of the metric.
+    value: Option<f64>,
+}
+
+impl Alert {
+    /// Describes the alert with the latest value of its metric, e.g. `cpu 95.2 > 90`.
+    pub fn describe(&self) -> String {
+        format!(
+            "{} {:.1} {} {}",
+            self.rule.metric.name(),
+            self.value.unwrap_or_default(),
+            self.rule.comparison.symbol(),
+            self.rule.threshold
+        )
+    }
+}
+
+#[derive(Default)]
+pub struct Alerts {
+    alerts: Vec<Alert>,
+}
+
+impl Alerts {
+    pub fn new(rules: &[AlertRule]) -> Self {
+        Alerts {
+            alerts: rules
+                .iter()
+                .map(|rule| Alert {
+                    rule: rule.clone(),
+                    breached_since: None,
+                    is_triggered: false,
+                    value: None,
+                })
+                .collect(),
+        }
+    }
+
+    /// Checks every alert against newly harvested data, returning the actions of those that just
+    /// went off. An alert goes off again only after its metric is back within the threshold.
+    pub fn update(&mut self, data: &DataCollection, now: Instant) -> Vec<AlertAction> {
+        let mut actions = Vec::new();
+        for alert in &mut self.alerts {
+            alert.value = alert.rule.metric.value(data);
+            let is_breached = alert
+                .value
+                .is_some_and(|value| alert.rule.comparison.holds(value, alert.rule.threshold));
+            if !is_breached {
+                alert.breached_since = None;
+                alert.is_triggered = false;
+                continue;
+            }
+
+            let breached_since = *alert.breached_since.get_or_insert(now);
+            if !alert.is_triggered && now.duration_since(breached_since) >= alert.rule.duration {
+                alert.is_triggered = true;
+                actions.push(alert.rule.action.clone());
+            }
+        }
+        actions
+    }
+
+    /// Returns the alerts that are going off.
+    pub fn triggered(&self) -> impl Iterator<Item = &Alert> {
+        self.alerts.iter().filter(|alert| alert.is_triggered)
+    }
+
+    /// Returns whether an alert going off is about something shown in a widget of type
+    /// `widget_type`.
+    pub fn is_alerting(&self, widget_type: &BottomWidgetType) -> bool {
+        self.triggered()
+            .any(|alert| alert.rule.metric.is_shown_in(widget_type))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::app::data_harvester::cpu::CpuData;
+
+    fn cpu_data(cpu_usage: f64) -> DataCollection {
+        DataCollection {
+            cpu_harvest: vec![CpuData {
+                data_type: CpuDataType::Avg,
+                cpu_usage,
+                frequency: None,
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parsing() {
+        assert_eq!("Memory".parse(), Ok(AlertMetric::Memory));
+        assert!("gpu".parse::<AlertMetric>().is_err());
+        assert_eq!(">=".parse(), Ok(Comparison::AtLeast));
+        assert!("=>".parse::<Comparison>().is_err());
+        assert_eq!(
+            AlertAction::new("command", Some("notify-send hot")),
+            Ok(AlertAction::Command("notify-send hot".to_string()))
+        );
+        assert!(AlertAction::new("command", None).is_err());
+        assert!(AlertAction::new("email", None).is_err());
+    }
+
+    #[test]
+    fn alerts_go_off_after_their_duration() {
+        let mut alerts = Alerts::new(&[AlertRule {
+            metric: AlertMetric::Cpu,
+            comparison: Comparison::Above,
+            threshold: 90.0,
+            duration: Duration::from_secs(10),
+            action: AlertAction::Bell,
+        }]);
+        let start = Instant::now();
+
+        assert!(alerts.update(&cpu_data(95.0), start).is_empty());
+        assert!(alerts
+            .update(&cpu_data(95.0), start + Duration::from_secs(5))
+            .is_empty());
+        assert_eq!(
+            alerts.update(&cpu_data(99.0), start + Duration::from_secs(10)),
+            [AlertAction::Bell]
+        );
+        assert_eq!(
+            alerts.triggered().next().unwrap().describe(),
+            "cpu 99.0 > 90"
+        );
+        assert!(alerts.is_alerting(&BottomWidgetType::BasicCpu));
+        assert!(!alerts.is_alerting(&BottomWidgetType::Mem));
+
+        // It only goes off again once it has been back under the threshold.
+        assert!(alerts
+            .update(&cpu_data(99.0), start + Duration::from_secs(20))
+            .is_empty());
+        assert!(alerts
+            .update(&cpu_data(50.0), start + Duration::from_secs(21))
+            .is_empty());
+        assert_eq!(alerts.triggered().count(), 0);
+        assert!(alerts
+            .update(&cpu_data(95.0), start + Duration::from_secs(22))
+            .is_empty());
+    }
+
+    #[test]
+    fn missing_metrics_never_go_off() {
+        let mut alerts = Alerts::new(&[AlertRule {
+            metric: AlertMetric::Memory,
+            comparison: Comparison::Below,
+            threshold: 10.0,
+            duration: Duration::ZERO,
+            action: AlertAction::Show,
+        }]);
+        assert!(alerts
+            .update(&DataCollection::default(), Instant::now())
+            .is_empty());
+    }
+}
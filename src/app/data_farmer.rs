@@ -13,7 +13,11 @@
 //! memory usage and higher CPU usage - you will be trying to process more and
 //! more points as this is used!
 
-use std::{collections::BTreeMap, time::Instant, vec::Vec};
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    time::Instant,
+    vec::Vec,
+};
 
 use fxhash::FxHashMap;
 use once_cell::sync::Lazy;
@@ -22,7 +26,10 @@ use regex::Regex;
 #[cfg(feature = "battery")]
 use crate::data_harvester::batteries;
 use crate::{
-    data_harvester::{cpu, disks, memory, network, processes::ProcessHarvest, temperature, Data},
+    data_harvester::{
+        agents, cgroups, containers, cpu, disks, memory, network, pressure,
+        processes::ProcessHarvest, raid, remote_uptime, scheduled_jobs, temperature, zfs, Data,
+    },
     utils::gen_util::{get_decimal_bytes, GIGA_LIMIT},
     Pid,
 };
@@ -42,6 +49,30 @@ pub struct TimedData {
     pub arc_data: Option<Value>,
     #[cfg(feature = "gpu")]
     pub gpu_data: Vec<Option<Value>>,
+    pub pressure_data: Option<pressure::PressureHarvest>,
+    /// The signal level of each wireless interface, in dBm.
+    pub wifi_data: HashMap<String, Value>,
+}
+
+/// How many harvests of CPU and memory usage are kept for each process.
+pub const PROCESS_HISTORY_LEN: usize = 30;
+
+/// The recent CPU and memory usage of a process, as percentages, oldest first.
+#[derive(Clone, Debug, Default)]
+pub struct ProcessHistory {
+    pub cpu: VecDeque<f64>,
+    pub mem: VecDeque<f64>,
+}
+
+impl ProcessHistory {
+    fn push(&mut self, process: &ProcessHarvest) {
+        if self.cpu.len() >= PROCESS_HISTORY_LEN {
+            self.cpu.pop_front();
+            self.mem.pop_front();
+        }
+        self.cpu.push_back(process.cpu_usage_percent);
+        self.mem.push_back(process.mem_usage_percent);
+    }
 }
 
 #[derive(Clone, Debug, Default)]
@@ -54,6 +85,9 @@ pub struct ProcessData {
 
     /// PIDs corresponding to processes that have no parents.
     pub orphan_pids: Vec<Pid>,
+
+    /// The recent usage of each process, which is dropped once the process ends.
+    pub process_history: FxHashMap<Pid, ProcessHistory>,
 }
 
 impl ProcessData {
@@ -80,6 +114,12 @@ impl ProcessData {
             .collect();
         self.process_harvest = process_pid_map;
 
+        self.process_history
+            .retain(|pid, _| self.process_harvest.contains_key(pid));
+        for (pid, process) in &self.process_harvest {
+            self.process_history.entry(*pid).or_default().push(process);
+        }
+
         // We collect all processes that either:
         // - Do not have a parent PID (that is, they are orphan processes)
         // - Have a parent PID but we don't have the parent (we promote them as orphans)
@@ -117,8 +157,20 @@ pub struct DataCollection {
     pub disk_harvest: Vec<disks::DiskHarvest>,
     pub io_harvest: disks::IoHarvest,
     pub io_labels_and_prev: Vec<((u64, u64), (u64, u64))>,
+    /// When disk I/O was last harvested, which can be less often than everything else.
+    pub io_instant: Option<Instant>,
     pub io_labels: Vec<(String, String)>,
+    pub io_latencies_and_prev: Vec<((Option<f32>, Option<f32>), Option<disks::IoLatencyCounters>)>,
     pub temp_harvest: Vec<temperature::TempHarvest>,
+    pub connection_harvest: Vec<network::ConnectionHarvest>,
+    pub connection_stats: Vec<network::NetlinkSocketInfo>,
+    pub remote_uptime_harvest: Vec<remote_uptime::RemoteUptimeHarvest>,
+    pub agent_harvest: Vec<agents::AgentHarvest>,
+    pub container_harvest: Vec<containers::ContainerHarvest>,
+    pub raid_harvest: Vec<raid::RaidHarvest>,
+    pub zfs_pool_harvest: Vec<zfs::ZfsPoolHarvest>,
+    pub cgroup_harvest: Vec<cgroups::CgroupHarvest>,
+    pub scheduled_job_harvest: Vec<scheduled_jobs::ScheduledJobHarvest>,
     #[cfg(feature = "battery")]
     pub battery_harvest: Vec<batteries::BatteryHarvest>,
     #[cfg(feature = "zfs")]
@@ -141,8 +193,19 @@ impl Default for DataCollection {
             disk_harvest: Vec::default(),
             io_harvest: disks::IoHarvest::default(),
             io_labels_and_prev: Vec::default(),
+            io_instant: None,
             io_labels: Vec::default(),
+            io_latencies_and_prev: Vec::default(),
             temp_harvest: Vec::default(),
+            connection_harvest: Vec::default(),
+            connection_stats: Vec::default(),
+            remote_uptime_harvest: Vec::default(),
+            agent_harvest: Vec::default(),
+            container_harvest: Vec::default(),
+            raid_harvest: Vec::default(),
+            zfs_pool_harvest: Vec::default(),
+            cgroup_harvest: Vec::default(),
+            scheduled_job_harvest: Vec::default(),
             #[cfg(feature = "battery")]
             battery_harvest: Vec::default(),
             #[cfg(feature = "zfs")]
@@ -164,7 +227,18 @@ impl DataCollection {
         self.disk_harvest = Vec::default();
         self.io_harvest = disks::IoHarvest::default();
         self.io_labels_and_prev = Vec::default();
+        self.io_instant = None;
+        self.io_latencies_and_prev = Vec::default();
         self.temp_harvest = Vec::default();
+        self.connection_harvest = Vec::default();
+        self.connection_stats = Vec::default();
+        self.remote_uptime_harvest = Vec::default();
+        self.agent_harvest = Vec::default();
+        self.container_harvest = Vec::default();
+        self.raid_harvest = Vec::default();
+        self.zfs_pool_harvest = Vec::default();
+        self.cgroup_harvest = Vec::default();
+        self.scheduled_job_harvest = Vec::default();
         #[cfg(feature = "battery")]
         {
             self.battery_harvest = Vec::default();
@@ -233,6 +307,9 @@ impl DataCollection {
             self.eat_load_avg(load_avg, &mut new_entry);
         }
 
+        // Pressure
+        new_entry.pressure_data = harvested_data.pressure;
+
         // Temp
         if let Some(temperature_sensors) = harvested_data.temperature_sensors {
             self.eat_temp(temperature_sensors);
@@ -250,6 +327,51 @@ impl DataCollection {
             self.eat_proc(list_of_processes);
         }
 
+        // Connections
+        if let Some(connections) = harvested_data.connections {
+            self.connection_harvest = connections;
+        }
+
+        // Connection stats
+        if let Some(connection_stats) = harvested_data.connection_stats {
+            self.connection_stats = connection_stats;
+        }
+
+        // Remote uptime
+        if let Some(remote_uptime) = harvested_data.remote_uptime {
+            self.remote_uptime_harvest = remote_uptime;
+        }
+
+        // Agents
+        if let Some(agents) = harvested_data.agents {
+            self.agent_harvest = agents;
+        }
+
+        // Containers
+        if let Some(containers) = harvested_data.containers {
+            self.container_harvest = containers;
+        }
+
+        // RAID
+        if let Some(raid) = harvested_data.raid {
+            self.raid_harvest = raid;
+        }
+
+        // ZFS pools
+        if let Some(zfs_pools) = harvested_data.zfs_pools {
+            self.zfs_pool_harvest = zfs_pools;
+        }
+
+        // Cgroups
+        if let Some(cgroups) = harvested_data.cgroups {
+            self.cgroup_harvest = cgroups;
+        }
+
+        // Scheduled jobs
+        if let Some(scheduled_jobs) = harvested_data.scheduled_jobs {
+            self.scheduled_job_harvest = scheduled_jobs;
+        }
+
         #[cfg(feature = "battery")]
         {
             // Battery
@@ -288,6 +410,13 @@ impl DataCollection {
             new_entry.tx_data = network.tx as f64;
         }
 
+        // Wi-Fi
+        new_entry.wifi_data = network
+            .wifi_info
+            .iter()
+            .map(|(name, info)| (name.clone(), f64::from(info.rssi_dbm)))
+            .collect();
+
         // In addition copy over latest data for easy reference
         self.network_harvest = network;
     }
@@ -319,8 +448,9 @@ impl DataCollection {
         // TODO: [PO] To implement
 
         let time_since_last_harvest = harvested_time
-            .duration_since(self.current_instant)
+            .duration_since(self.io_instant.unwrap_or(self.current_instant))
             .as_secs_f64();
+        self.io_instant = Some(harvested_time);
 
         for (itx, device) in disks.iter().enumerate() {
             if let Some(trim) = device.name.split('/').last() {
@@ -336,6 +466,10 @@ impl DataCollection {
                     io.get(trim)
                 };
 
+                if self.io_latencies_and_prev.len() <= itx {
+                    self.io_latencies_and_prev.push(((None, None), None));
+                }
+
                 if let Some(io_device) = io_device {
                     let (io_r_pt, io_w_pt) = if let Some(io) = io_device {
                         (io.read_bytes, io.write_bytes)
@@ -343,6 +477,16 @@ impl DataCollection {
                         (0, 0)
                     };
 
+                    if let Some((latencies, latency_prev)) = self.io_latencies_and_prev.get_mut(itx)
+                    {
+                        let latency_curr = io_device.as_ref().and_then(|io| io.latency_counters);
+                        *latencies = match (&latency_curr, &latency_prev) {
+                            (Some(curr), Some(prev)) => curr.latency_since(prev),
+                            _ => (None, None),
+                        };
+                        *latency_prev = latency_curr;
+                    }
+
                     if self.io_labels.len() <= itx {
                         self.io_labels.push((String::default(), String::default()));
                     }
@@ -387,6 +531,10 @@ impl DataCollection {
                     if let Some(io_labels) = self.io_labels.get_mut(itx) {
                         *io_labels = ("N/A".to_string(), "N/A".to_string());
                     }
+
+                    if let Some(io_latencies) = self.io_latencies_and_prev.get_mut(itx) {
+                        *io_latencies = ((None, None), None);
+                    }
                 }
             }
         }
@@ -1,19 +1,25 @@
 //! This is the main file to house data collection functions.
 
-use std::time::Instant;
-
-#[cfg(target_os = "linux")]
-use fxhash::FxHashMap;
+use std::{
+    any::Any,
+    time::{Duration, Instant},
+};
 
 #[cfg(feature = "battery")]
 use starship_battery::{Battery, Manager};
 
-use sysinfo::{System, SystemExt};
-
-use self::temperature::TemperatureType;
+use self::{
+    collectors::*,
+    temperature::{SensorType, TemperatureType},
+};
 
 use super::DataFilters;
-use crate::app::layout_manager::UsedWidgets;
+use crate::{
+    app::layout_manager::UsedWidgets,
+    constants::{
+        DEFAULT_COLLECTION_TIMEOUT_MILLISECONDS, DEFAULT_SLOW_REFRESH_RATE_IN_MILLISECONDS,
+    },
+};
 
 #[cfg(feature = "nvidia")]
 pub mod nvidia;
@@ -21,12 +27,21 @@ pub mod nvidia;
 #[cfg(feature = "battery")]
 pub mod batteries;
 
+pub mod agents;
+pub mod cgroups;
+pub mod collectors;
+pub mod containers;
 pub mod cpu;
 pub mod disks;
 pub mod memory;
 pub mod network;
+pub mod pressure;
 pub mod processes;
+pub mod raid;
+pub mod remote_uptime;
+pub mod scheduled_jobs;
 pub mod temperature;
+pub mod zfs;
 
 #[derive(Clone, Debug)]
 pub struct Data {
@@ -40,6 +55,16 @@ pub struct Data {
     pub list_of_processes: Option<Vec<processes::ProcessHarvest>>,
     pub disks: Option<Vec<disks::DiskHarvest>>,
     pub io: Option<disks::IoHarvest>,
+    pub connections: Option<Vec<network::ConnectionHarvest>>,
+    pub connection_stats: Option<Vec<network::NetlinkSocketInfo>>,
+    pub remote_uptime: Option<Vec<remote_uptime::RemoteUptimeHarvest>>,
+    pub agents: Option<Vec<agents::AgentHarvest>>,
+    pub containers: Option<Vec<containers::ContainerHarvest>>,
+    pub pressure: Option<pressure::PressureHarvest>,
+    pub raid: Option<Vec<raid::RaidHarvest>>,
+    pub zfs_pools: Option<Vec<zfs::ZfsPoolHarvest>>,
+    pub cgroups: Option<Vec<cgroups::CgroupHarvest>>,
+    pub scheduled_jobs: Option<Vec<scheduled_jobs::ScheduledJobHarvest>>,
     #[cfg(feature = "battery")]
     pub list_of_batteries: Option<Vec<batteries::BatteryHarvest>>,
     #[cfg(feature = "zfs")]
@@ -61,6 +86,16 @@ impl Default for Data {
             disks: None,
             io: None,
             network: None,
+            connections: None,
+            connection_stats: None,
+            remote_uptime: None,
+            agents: None,
+            containers: None,
+            pressure: None,
+            raid: None,
+            zfs_pools: None,
+            cgroups: None,
+            scheduled_jobs: None,
             #[cfg(feature = "battery")]
             list_of_batteries: None,
             #[cfg(feature = "zfs")]
@@ -96,95 +131,134 @@ impl Data {
     }
 }
 
-#[derive(Debug)]
+/// A non-fatal error that occurred while harvesting a subsystem.
+#[derive(Clone, Debug)]
+pub struct CollectionError {
+    pub timestamp: Instant,
+    pub source: String,
+    pub message: String,
+}
+
+impl CollectionError {
+    pub fn new(source: &str, message: impl ToString) -> Self {
+        CollectionError {
+            timestamp: Instant::now(),
+            source: source.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    /// Creates an error from the payload of a caught panic.
+    pub fn from_panic(source: &str, payload: &(dyn Any + Send)) -> Self {
+        let reason = if let Some(reason) = payload.downcast_ref::<&str>() {
+            reason
+        } else if let Some(reason) = payload.downcast_ref::<String>() {
+            reason.as_str()
+        } else {
+            "unknown reason"
+        };
+
+        CollectionError::new(source, format!("panicked: {}", reason))
+    }
+}
+
+/// The running [`Collector`](collectors::Collector) for each harvested subsystem.
+#[derive(Default)]
+struct CollectorWorkers {
+    cpu: Option<CollectorWorker<CpuCollector>>,
+    memory: Option<CollectorWorker<MemoryCollector>>,
+    network: Option<CollectorWorker<NetworkCollector>>,
+    processes: Option<CollectorWorker<ProcessCollector>>,
+    temperature: Option<CollectorWorker<TempCollector>>,
+    disks: Option<CollectorWorker<DiskCollector>>,
+    connections: Option<CollectorWorker<ConnectionsCollector>>,
+    remote_uptime: Option<CollectorWorker<RemoteUptimeCollector>>,
+    agents: Option<CollectorWorker<AgentsCollector>>,
+    containers: Option<CollectorWorker<ContainersCollector>>,
+    pressure: Option<CollectorWorker<PressureCollector>>,
+    raid: Option<CollectorWorker<RaidCollector>>,
+    zfs_pools: Option<CollectorWorker<ZfsPoolCollector>>,
+    cgroups: Option<CollectorWorker<CgroupCollector>>,
+    scheduled_jobs: Option<CollectorWorker<ScheduledJobCollector>>,
+}
+
+impl CollectorWorkers {
+    /// Makes the collectors that only run every so often harvest on the next update.
+    fn make_due(&mut self) {
+        if let Some(temperature) = &mut self.temperature {
+            temperature.make_due();
+        }
+        if let Some(disks) = &mut self.disks {
+            disks.make_due();
+        }
+        if let Some(containers) = &mut self.containers {
+            containers.make_due();
+        }
+        if let Some(raid) = &mut self.raid {
+            raid.make_due();
+        }
+        if let Some(zfs_pools) = &mut self.zfs_pools {
+            zfs_pools.make_due();
+        }
+        if let Some(cgroups) = &mut self.cgroups {
+            cgroups.make_due();
+        }
+        if let Some(remote_uptime) = &mut self.remote_uptime {
+            remote_uptime.make_due();
+        }
+        if let Some(scheduled_jobs) = &mut self.scheduled_jobs {
+            scheduled_jobs.make_due();
+        }
+    }
+}
+
 pub struct DataCollector {
     pub data: Data,
-    sys: System,
-    #[cfg(target_os = "linux")]
-    pid_mapping: FxHashMap<crate::Pid, processes::PrevProcDetails>,
-    #[cfg(target_os = "linux")]
-    prev_idle: f64,
-    #[cfg(target_os = "linux")]
-    prev_non_idle: f64,
-    mem_total_kb: u64,
+    workers: Option<CollectorWorkers>,
     temperature_type: TemperatureType,
+    sensor_types: Vec<SensorType>,
     use_current_cpu_total: bool,
     unnormalized_cpu: bool,
-    last_collection_time: Instant,
-    total_rx: u64,
-    total_tx: u64,
     show_average_cpu: bool,
+    collect_cpu_frequency: bool,
+    collection_timeout: Duration,
+    /// How often the slower collectors are harvested.
+    slow_interval: Duration,
     widgets_to_harvest: UsedWidgets,
     #[cfg(feature = "battery")]
     battery_manager: Option<Manager>,
     #[cfg(feature = "battery")]
     battery_list: Option<Vec<Battery>>,
     filters: DataFilters,
-
-    #[cfg(target_family = "unix")]
-    user_table: self::processes::UserTable,
+    remote_hosts: Vec<remote_uptime::RemoteHost>,
+    agent_addresses: Vec<String>,
 }
 
 impl DataCollector {
     pub fn new(filters: DataFilters) -> Self {
         DataCollector {
             data: Data::default(),
-            sys: System::new_with_specifics(sysinfo::RefreshKind::new()),
-            #[cfg(target_os = "linux")]
-            pid_mapping: FxHashMap::default(),
-            #[cfg(target_os = "linux")]
-            prev_idle: 0_f64,
-            #[cfg(target_os = "linux")]
-            prev_non_idle: 0_f64,
-            mem_total_kb: 0,
+            workers: None,
             temperature_type: TemperatureType::Celsius,
+            sensor_types: vec![SensorType::Temperature],
             use_current_cpu_total: false,
             unnormalized_cpu: false,
-            last_collection_time: Instant::now(),
-            total_rx: 0,
-            total_tx: 0,
             show_average_cpu: false,
+            collect_cpu_frequency: false,
+            collection_timeout: Duration::from_millis(DEFAULT_COLLECTION_TIMEOUT_MILLISECONDS),
+            slow_interval: Duration::from_millis(DEFAULT_SLOW_REFRESH_RATE_IN_MILLISECONDS),
             widgets_to_harvest: UsedWidgets::default(),
             #[cfg(feature = "battery")]
             battery_manager: None,
             #[cfg(feature = "battery")]
             battery_list: None,
             filters,
-            #[cfg(target_family = "unix")]
-            user_table: Default::default(),
+            remote_hosts: Vec::new(),
+            agent_addresses: Vec::new(),
         }
     }
 
     pub fn init(&mut self) {
-        self.sys.refresh_memory();
-        self.mem_total_kb = self.sys.total_memory();
-
-        // Refresh network list once at the start.
-        if self.widgets_to_harvest.use_net {
-            self.sys.refresh_networks_list(); // TODO: refresh on a timer?
-        }
-
-        if self.widgets_to_harvest.use_proc || self.widgets_to_harvest.use_cpu {
-            self.sys.refresh_cpu();
-        }
-
-        #[cfg(not(target_os = "linux"))]
-        {
-            // Refresh components list once.
-            if self.widgets_to_harvest.use_temp {
-                self.sys.refresh_components_list(); // TODO: refresh on a timer?
-            }
-
-            if cfg!(target_os = "windows") && self.widgets_to_harvest.use_proc {
-                self.sys.refresh_users_list();
-            }
-
-            // Refresh disk list once...
-            if cfg!(target_os = "freebsd") && self.widgets_to_harvest.use_disk {
-                self.sys.refresh_disks_list();
-            }
-        }
-
         #[cfg(feature = "battery")]
         {
             if self.widgets_to_harvest.use_battery {
@@ -200,72 +274,216 @@ impl DataCollector {
             }
         }
 
-        futures::executor::block_on(self.update_data());
+        // Any errors here will just show up again on the next update.
+        let _ = self.update_data();
+
+        // That harvest is thrown away below, so the slower collectors shouldn't wait a whole
+        // interval before their data first shows up.
+        if let Some(workers) = &mut self.workers {
+            workers.make_due();
+        }
 
         std::thread::sleep(std::time::Duration::from_millis(250));
         self.data.cleanup();
     }
 
+    // Any of the setters below will cause the collectors to be restarted with the new settings on
+    // the next update.
+
     pub fn set_data_collection(&mut self, used_widgets: UsedWidgets) {
         self.widgets_to_harvest = used_widgets;
+        self.workers = None;
     }
 
     pub fn set_temperature_type(&mut self, temperature_type: TemperatureType) {
         self.temperature_type = temperature_type;
+        self.workers = None;
+    }
+
+    pub fn set_sensor_types(&mut self, sensor_types: Vec<SensorType>) {
+        self.sensor_types = sensor_types;
+        self.workers = None;
     }
 
     pub fn set_use_current_cpu_total(&mut self, use_current_cpu_total: bool) {
         self.use_current_cpu_total = use_current_cpu_total;
+        self.workers = None;
     }
 
     pub fn set_unnormalized_cpu(&mut self, unnormalized_cpu: bool) {
         self.unnormalized_cpu = unnormalized_cpu;
+        self.workers = None;
+    }
+
+    pub fn set_collect_cpu_frequency(&mut self, collect_cpu_frequency: bool) {
+        self.collect_cpu_frequency = collect_cpu_frequency;
+        self.workers = None;
     }
 
     pub fn set_show_average_cpu(&mut self, show_average_cpu: bool) {
         self.show_average_cpu = show_average_cpu;
+        self.workers = None;
     }
 
-    pub async fn update_data(&mut self) {
-        if self.widgets_to_harvest.use_proc || self.widgets_to_harvest.use_cpu {
-            self.sys.refresh_cpu();
-        }
+    pub fn set_remote_hosts(&mut self, remote_hosts: Vec<remote_uptime::RemoteHost>) {
+        self.remote_hosts = remote_hosts;
+        self.workers = None;
+    }
 
-        if self.widgets_to_harvest.use_mem {
-            self.sys.refresh_memory();
-        }
+    pub fn set_agent_addresses(&mut self, agent_addresses: Vec<String>) {
+        self.agent_addresses = agent_addresses;
+        self.workers = None;
+    }
 
-        if self.widgets_to_harvest.use_net {
-            self.sys.refresh_networks();
-        }
+    pub fn set_collection_timeout(&mut self, collection_timeout: Duration) {
+        self.collection_timeout = collection_timeout;
+    }
 
-        #[cfg(not(target_os = "linux"))]
-        {
-            if self.widgets_to_harvest.use_proc {
-                self.sys.refresh_processes();
-            }
+    pub fn set_slow_interval(&mut self, slow_interval: Duration) {
+        self.slow_interval = slow_interval;
+        self.workers = None;
+    }
 
-            if self.widgets_to_harvest.use_temp {
-                self.sys.refresh_components();
-            }
+    pub fn set_filters(&mut self, filters: DataFilters) {
+        self.filters = filters;
+        self.workers = None;
+    }
+
+    fn spawn_workers(&self) -> CollectorWorkers {
+        let widgets = &self.widgets_to_harvest;
+
+        CollectorWorkers {
+            cpu: widgets.use_cpu.then(|| {
+                CollectorWorker::spawn(CpuCollector::new(
+                    self.show_average_cpu,
+                    self.collect_cpu_frequency,
+                ))
+            }),
+            memory: widgets.use_mem.then(|| {
+                CollectorWorker::spawn(MemoryCollector::new(
+                    #[cfg(feature = "gpu")]
+                    widgets.use_gpu,
+                ))
+            }),
+            // Wi-Fi signal levels are collected along with network usage.
+            network: (widgets.use_net || widgets.use_wifi).then(|| {
+                CollectorWorker::spawn(NetworkCollector::new(self.filters.net_filter.clone()))
+            }),
+            processes: widgets.use_proc.then(|| {
+                CollectorWorker::spawn(ProcessCollector::new(
+                    self.use_current_cpu_total,
+                    self.unnormalized_cpu,
+                ))
+            }),
+            // Sensors, disks and the rest below change slowly but can be slow to read, like remote
+            // mounts or GPUs behind NVML, so they're only harvested every slow interval.
+            temperature: widgets.use_temp.then(|| {
+                CollectorWorker::spawn(TempCollector::new(
+                    self.temperature_type,
+                    self.sensor_types.clone(),
+                    self.filters.temp_filter.clone(),
+                ))
+                .with_interval(self.slow_interval)
+            }),
+            disks: widgets.use_disk.then(|| {
+                CollectorWorker::spawn(DiskCollector::new(
+                    self.filters.disk_filter.clone(),
+                    self.filters.mount_filter.clone(),
+                ))
+                .with_interval(self.slow_interval)
+            }),
+            connections: widgets
+                .use_connections
+                .then(|| CollectorWorker::spawn(ConnectionsCollector::default())),
+            remote_uptime: (widgets.use_uptime && !self.remote_hosts.is_empty()).then(|| {
+                CollectorWorker::spawn(RemoteUptimeCollector::new(self.remote_hosts.clone()))
+                    .with_interval(REMOTE_UPTIME_INTERVAL)
+            }),
+            agents: (widgets.use_dashboard && !self.agent_addresses.is_empty())
+                .then(|| CollectorWorker::spawn(AgentsCollector::new(&self.agent_addresses))),
+            containers: widgets.use_containers.then(|| {
+                CollectorWorker::spawn(ContainersCollector::default())
+                    .with_interval(self.slow_interval)
+            }),
+            pressure: widgets
+                .use_pressure
+                .then(|| CollectorWorker::spawn(PressureCollector)),
+            raid: widgets
+                .use_raid
+                .then(|| CollectorWorker::spawn(RaidCollector).with_interval(self.slow_interval)),
+            zfs_pools: widgets.use_zfs_pools.then(|| {
+                CollectorWorker::spawn(ZfsPoolCollector).with_interval(self.slow_interval)
+            }),
+            cgroups: widgets.use_cgroups.then(|| {
+                CollectorWorker::spawn(CgroupCollector::default()).with_interval(self.slow_interval)
+            }),
+            scheduled_jobs: widgets.use_scheduled_jobs.then(|| {
+                CollectorWorker::spawn(ScheduledJobCollector).with_interval(SCHEDULED_JOBS_INTERVAL)
+            }),
         }
+    }
 
-        #[cfg(target_os = "freebsd")]
-        if self.widgets_to_harvest.use_disk {
-            self.sys.refresh_disks();
+    /// Harvests all subsystems concurrently. Any collector that takes longer than the collection
+    /// timeout is skipped for this update, and its data is picked up on a later one instead.
+    ///
+    /// Returns any errors the collectors ran into.
+    pub fn update_data(&mut self) -> Vec<CollectionError> {
+        if self.workers.is_none() {
+            self.workers = Some(self.spawn_workers());
         }
 
         let current_instant = Instant::now();
+        let deadline = current_instant + self.collection_timeout;
 
-        self.update_cpu_usage();
-        self.update_processes(
-            #[cfg(target_os = "linux")]
-            current_instant,
-        );
-        self.update_temps();
-        self.update_memory_usage();
-        self.update_network_usage(current_instant);
+        if let Some(workers) = &mut self.workers {
+            if let Some(cpu) = &mut workers.cpu {
+                cpu.request(current_instant);
+            }
+            if let Some(memory) = &mut workers.memory {
+                memory.request(current_instant);
+            }
+            if let Some(network) = &mut workers.network {
+                network.request(current_instant);
+            }
+            if let Some(processes) = &mut workers.processes {
+                processes.request(current_instant);
+            }
+            if let Some(temperature) = &mut workers.temperature {
+                temperature.request(current_instant);
+            }
+            if let Some(disks) = &mut workers.disks {
+                disks.request(current_instant);
+            }
+            if let Some(connections) = &mut workers.connections {
+                connections.request(current_instant);
+            }
+            if let Some(remote_uptime) = &mut workers.remote_uptime {
+                remote_uptime.request(current_instant);
+            }
+            if let Some(agents) = &mut workers.agents {
+                agents.request(current_instant);
+            }
+            if let Some(containers) = &mut workers.containers {
+                containers.request(current_instant);
+            }
+            if let Some(pressure) = &mut workers.pressure {
+                pressure.request(current_instant);
+            }
+            if let Some(raid) = &mut workers.raid {
+                raid.request(current_instant);
+            }
+            if let Some(zfs_pools) = &mut workers.zfs_pools {
+                zfs_pools.request(current_instant);
+            }
+            if let Some(cgroups) = &mut workers.cgroups {
+                cgroups.request(current_instant);
+            }
+            if let Some(scheduled_jobs) = &mut workers.scheduled_jobs {
+                scheduled_jobs.request(current_instant);
+            }
+        }
 
+        // Batteries are cheap to check, so they're just handled here while waiting on the rest.
         #[cfg(feature = "battery")]
         if let Some(battery_manager) = &self.battery_manager {
             if let Some(battery_list) = &mut self.battery_list {
@@ -274,162 +492,165 @@ impl DataCollector {
             }
         }
 
-        let (disk_res, io_res) = futures::join!(
-            disks::get_disk_usage(
-                self.widgets_to_harvest.use_disk,
-                &self.filters.disk_filter,
-                &self.filters.mount_filter,
-            ),
-            disks::get_io_usage(self.widgets_to_harvest.use_disk)
-        );
-
-        if let Ok(disks) = disk_res {
-            self.data.disks = disks;
-        }
+        let mut errors = Vec::new();
 
-        if let Ok(io) = io_res {
-            self.data.io = io;
-        }
+        if let Some(workers) = &mut self.workers {
+            if let Some(output) = workers.cpu.as_mut().and_then(|w| w.receive(deadline)) {
+                match output {
+                    Ok((cpu, load_avg)) => {
+                        self.data.cpu = cpu;
+                        self.data.load_avg = load_avg;
+                    }
+                    Err(err) => errors.push(err),
+                }
+            }
 
-        // Update times for future reference.
-        self.last_collection_time = current_instant;
-        self.data.last_collection_time = current_instant;
-    }
+            if let Some(output) = workers.memory.as_mut().and_then(|w| w.receive(deadline)) {
+                match output {
+                    Ok(memory) => {
+                        self.data.memory = memory.memory;
+                        self.data.swap = memory.swap;
 
-    #[inline]
-    fn update_cpu_usage(&mut self) {
-        if self.widgets_to_harvest.use_cpu {
-            self.data.cpu = cpu::get_cpu_data_list(&self.sys, self.show_average_cpu).ok();
+                        #[cfg(feature = "zfs")]
+                        {
+                            self.data.arc = memory.arc;
+                        }
 
-            #[cfg(target_family = "unix")]
-            {
-                self.data.load_avg = cpu::get_load_avg().ok();
+                        #[cfg(feature = "gpu")]
+                        {
+                            self.data.gpu = memory.gpu;
+                        }
+                    }
+                    Err(err) => errors.push(err),
+                }
+            }
+
+            if let Some(output) = workers.network.as_mut().and_then(|w| w.receive(deadline)) {
+                match output {
+                    Ok(network) => self.data.network = Some(network),
+                    Err(err) => errors.push(err),
+                }
+            }
+
+            if let Some(output) = workers.processes.as_mut().and_then(|w| w.receive(deadline)) {
+                match output {
+                    Ok(processes) => self.data.list_of_processes = Some(processes),
+                    Err(err) => errors.push(err),
+                }
             }
-        }
-    }
 
-    #[inline]
-    fn update_processes(&mut self, #[cfg(target_os = "linux")] current_instant: Instant) {
-        if self.widgets_to_harvest.use_proc {
-            if let Ok(mut process_list) = {
-                #[cfg(target_os = "linux")]
-                {
-                    use self::processes::{PrevProc, ProcHarvestOptions};
-
-                    let prev_proc = PrevProc {
-                        prev_idle: &mut self.prev_idle,
-                        prev_non_idle: &mut self.prev_non_idle,
-                    };
-
-                    let proc_harvest_options = ProcHarvestOptions {
-                        use_current_cpu_total: self.use_current_cpu_total,
-                        unnormalized_cpu: self.unnormalized_cpu,
-                    };
-
-                    let time_diff = current_instant
-                        .duration_since(self.last_collection_time)
-                        .as_secs();
-
-                    processes::get_process_data(
-                        &self.sys,
-                        prev_proc,
-                        &mut self.pid_mapping,
-                        proc_harvest_options,
-                        time_diff,
-                        self.mem_total_kb,
-                        &mut self.user_table,
-                    )
+            if let Some(output) = workers
+                .temperature
+                .as_mut()
+                .and_then(|w| w.receive(deadline))
+            {
+                match output {
+                    Ok(temperature) => self.data.temperature_sensors = temperature,
+                    Err(err) => errors.push(err),
                 }
-                #[cfg(not(target_os = "linux"))]
-                {
-                    #[cfg(target_family = "unix")]
-                    {
-                        processes::get_process_data(
-                            &self.sys,
-                            self.use_current_cpu_total,
-                            self.unnormalized_cpu,
-                            self.mem_total_kb,
-                            &mut self.user_table,
-                        )
+            }
+
+            if let Some(output) = workers.disks.as_mut().and_then(|w| w.receive(deadline)) {
+                match output {
+                    Ok((disks, io)) => {
+                        self.data.disks = disks;
+                        self.data.io = io;
                     }
-                    #[cfg(not(target_family = "unix"))]
-                    {
-                        processes::get_process_data(
-                            &self.sys,
-                            self.use_current_cpu_total,
-                            self.unnormalized_cpu,
-                            self.mem_total_kb,
-                        )
+                    Err(err) => errors.push(err),
+                }
+            }
+
+            if let Some(output) = workers
+                .connections
+                .as_mut()
+                .and_then(|w| w.receive(deadline))
+            {
+                match output {
+                    Ok(connections) => {
+                        self.data.connections = Some(connections.connections);
+                        #[cfg(target_os = "linux")]
+                        {
+                            self.data.connection_stats = Some(connections.stats);
+                        }
                     }
+                    Err(err) => errors.push(err),
                 }
-            } {
-                // NB: To avoid duplicate sorts on rerenders/events, we sort the processes by PID here.
-                // We also want to avoid re-sorting *again* later on if we're sorting by PID, since we already
-                // did it here!
-                process_list.sort_unstable_by_key(|p| p.pid);
-                self.data.list_of_processes = Some(process_list);
             }
-        }
-    }
 
-    #[inline]
-    fn update_temps(&mut self) {
-        if self.widgets_to_harvest.use_temp {
-            #[cfg(not(target_os = "linux"))]
-            if let Ok(data) = temperature::get_temperature_data(
-                &self.sys,
-                &self.temperature_type,
-                &self.filters.temp_filter,
-            ) {
-                self.data.temperature_sensors = data;
-            }
-
-            #[cfg(target_os = "linux")]
-            if let Ok(data) =
-                temperature::get_temperature_data(&self.temperature_type, &self.filters.temp_filter)
+            // Remote hosts are rarely checked and can take a while, so they are never waited on.
+            if let Some(output) = workers
+                .remote_uptime
+                .as_mut()
+                .and_then(|w| w.receive(Instant::now()))
             {
-                self.data.temperature_sensors = data;
+                match output {
+                    Ok(remote_uptime) => self.data.remote_uptime = Some(remote_uptime),
+                    Err(err) => errors.push(err),
+                }
             }
-        }
-    }
 
-    #[inline]
-    fn update_memory_usage(&mut self) {
-        if self.widgets_to_harvest.use_mem {
-            self.data.memory = memory::get_ram_usage(&self.sys);
-            self.data.swap = memory::get_swap_usage(
-                #[cfg(not(target_os = "windows"))]
-                &self.sys,
-            );
+            if let Some(output) = workers.agents.as_mut().and_then(|w| w.receive(deadline)) {
+                match output {
+                    Ok(agents) => self.data.agents = Some(agents),
+                    Err(err) => errors.push(err),
+                }
+            }
 
-            #[cfg(feature = "zfs")]
+            if let Some(output) = workers
+                .containers
+                .as_mut()
+                .and_then(|w| w.receive(deadline))
             {
-                self.data.arc = memory::arc::get_arc_usage();
+                match output {
+                    Ok(containers) => self.data.containers = Some(containers),
+                    Err(err) => errors.push(err),
+                }
             }
 
-            #[cfg(feature = "gpu")]
-            if self.widgets_to_harvest.use_gpu {
-                self.data.gpu = memory::gpu::get_gpu_mem_usage();
+            if let Some(output) = workers.pressure.as_mut().and_then(|w| w.receive(deadline)) {
+                match output {
+                    Ok(pressure) => self.data.pressure = pressure,
+                    Err(err) => errors.push(err),
+                }
             }
-        }
-    }
 
-    #[inline]
-    fn update_network_usage(&mut self, current_instant: Instant) {
-        if self.widgets_to_harvest.use_net {
-            let net_data = network::get_network_data(
-                &self.sys,
-                self.last_collection_time,
-                &mut self.total_rx,
-                &mut self.total_tx,
-                current_instant,
-                &self.filters.net_filter,
-            );
-
-            self.total_rx = net_data.total_rx;
-            self.total_tx = net_data.total_tx;
-            self.data.network = Some(net_data);
+            if let Some(output) = workers.raid.as_mut().and_then(|w| w.receive(deadline)) {
+                match output {
+                    Ok(raid) => self.data.raid = Some(raid),
+                    Err(err) => errors.push(err),
+                }
+            }
+
+            if let Some(output) = workers.zfs_pools.as_mut().and_then(|w| w.receive(deadline)) {
+                match output {
+                    Ok(zfs_pools) => self.data.zfs_pools = Some(zfs_pools),
+                    Err(err) => errors.push(err),
+                }
+            }
+
+            if let Some(output) = workers.cgroups.as_mut().and_then(|w| w.receive(deadline)) {
+                match output {
+                    Ok(cgroups) => self.data.cgroups = Some(cgroups),
+                    Err(err) => errors.push(err),
+                }
+            }
+
+            if let Some(output) = workers
+                .scheduled_jobs
+                .as_mut()
+                .and_then(|w| w.receive(deadline))
+            {
+                match output {
+                    Ok(scheduled_jobs) => self.data.scheduled_jobs = Some(scheduled_jobs),
+                    Err(err) => errors.push(err),
+                }
+            }
         }
+
+        // Update times for future reference.
+        self.data.last_collection_time = current_instant;
+
+        errors
     }
 }
 
@@ -0,0 +1,186 @@
+//! A summary of each agent watched by the dashboard widget.
+
+use std::{
+    sync::{Arc, Mutex, Weak},
+    thread,
+    time::Duration,
+};
+
+use super::{cpu::CpuDataType, Data};
+use crate::remote::{AgentInfo, RemoteSource};
+
+/// How long to wait before connecting to an agent again after losing it.
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AgentHarvest {
+    /// The address the agent is connected to, like `host:port`.
+    pub address: String,
+    pub hostname: Option<String>,
+    pub cpu_percent: Option<f64>,
+    pub mem_percent: Option<f64>,
+    pub load_avg: Option<[f32; 3]>,
+    pub uptime_secs: Option<u64>,
+    /// Why the agent can't be reached, if it can't.
+    pub error: Option<String>,
+}
+
+impl AgentHarvest {
+    fn new(address: &str) -> Self {
+        AgentHarvest {
+            address: address.to_string(),
+            ..AgentHarvest::default()
+        }
+    }
+
+    fn update(&mut self, info: AgentInfo, data: &Data) {
+        self.hostname = Some(info.hostname).filter(|hostname| !hostname.is_empty());
+        self.uptime_secs = Some(info.uptime_secs);
+        self.error = None;
+
+        if let Some(cpu) = &data.cpu {
+            // Agents harvest the average unless told not to, otherwise it's worked out here.
+            self.cpu_percent = cpu
+                .iter()
+                .find(|cpu| matches!(cpu.data_type, CpuDataType::Avg))
+                .map(|cpu| cpu.cpu_usage)
+                .or_else(|| {
+                    (!cpu.is_empty()).then(|| {
+                        cpu.iter().map(|cpu| cpu.cpu_usage).sum::<f64>() / cpu.len() as f64
+                    })
+                });
+        }
+        if let Some(memory) = &data.memory {
+            self.mem_percent = memory.use_percent;
+        }
+        if data.load_avg.is_some() {
+            self.load_avg = data.load_avg;
+        }
+    }
+}
+
+/// Keeps a connection to every agent, each on its own thread so an unreachable one doesn't hold
+/// up the rest. The threads stop once the watcher is dropped.
+pub struct AgentWatcher {
+    agents: Arc<Mutex<Vec<AgentHarvest>>>,
+}
+
+impl AgentWatcher {
+    pub fn spawn(addresses: &[String]) -> Self {
+        let agents = Arc::new(Mutex::new(
+            addresses
+                .iter()
+                .map(|address| AgentHarvest::new(address))
+                .collect(),
+        ));
+
+        for (index, address) in addresses.iter().enumerate() {
+            let agents = Arc::downgrade(&agents);
+            let address = address.clone();
+            thread::spawn(move || watch_agent(index, &address, agents));
+        }
+
+        AgentWatcher { agents }
+    }
+
+    /// The latest summary of every agent, in the order they were given.
+    pub fn latest(&self) -> Vec<AgentHarvest> {
+        self.agents
+            .lock()
+            .map(|agents| agents.clone())
+            .unwrap_or_default()
+    }
+}
+
+fn watch_agent(index: usize, address: &str, agents: Weak<Mutex<Vec<AgentHarvest>>>) {
+    let mut source: Option<RemoteSource> = None;
+
+    loop {
+        let update = match source.as_mut() {
+            Some(source) => source.receive(),
+            None => RemoteSource::connect(address).map(|connected| {
+                source = Some(connected);
+                None
+            }),
+        };
+
+        let Some(agents) = agents.upgrade() else {
+            break;
+        };
+        let failed = {
+            let Ok(mut agents) = agents.lock() else {
+                break;
+            };
+            let Some(agent) = agents.get_mut(index) else {
+                break;
+            };
+            match update {
+                Ok(Some((info, data))) => {
+                    agent.update(info, &data);
+                    false
+                }
+                Ok(None) => false,
+                Err(err) => {
+                    agent.error = Some(err.to_string());
+                    true
+                }
+            }
+        };
+        drop(agents);
+
+        if failed {
+            thread::sleep(RECONNECT_INTERVAL);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::app::data_harvester::{cpu::CpuData, memory::MemHarvest};
+
+    #[test]
+    fn test_update_agent() {
+        let mut agent = AgentHarvest::new("rack1:9100");
+        agent.error = Some("unreachable".to_string());
+
+        let info = AgentInfo {
+            hostname: "rack1".to_string(),
+            uptime_secs: 3600,
+        };
+        agent.update(
+            info.clone(),
+            &Data {
+                cpu: Some(vec![
+                    CpuData {
+                        data_type: CpuDataType::Cpu(0),
+                        cpu_usage: 20.0,
+                        frequency: None,
+                    },
+                    CpuData {
+                        data_type: CpuDataType::Cpu(1),
+                        cpu_usage: 40.0,
+                        frequency: None,
+                    },
+                ]),
+                memory: Some(MemHarvest {
+                    total_kib: 4,
+                    used_kib: 1,
+                    use_percent: Some(25.0),
+                }),
+                load_avg: Some([1.0, 0.5, 0.25]),
+                ..Data::default()
+            },
+        );
+        assert_eq!(agent.hostname.as_deref(), Some("rack1"));
+        assert_eq!(agent.cpu_percent, Some(30.0));
+        assert_eq!(agent.mem_percent, Some(25.0));
+        assert_eq!(agent.uptime_secs, Some(3600));
+        assert!(agent.error.is_none());
+
+        // Parts missing from an update keep their last value.
+        agent.update(info, &Data::default());
+        assert_eq!(agent.cpu_percent, Some(30.0));
+        assert_eq!(agent.load_avg, Some([1.0, 0.5, 0.25]));
+    }
+}
@@ -0,0 +1,165 @@
+//! Resource usage of the cgroup v2 hierarchy on Linux, like systemd's slices and scopes, or the
+//! cgroups made by container runtimes.
+
+use std::{collections::HashMap, time::Instant};
+
+#[cfg(target_os = "linux")]
+use std::path::Path;
+
+/// A single cgroup. Like the kernel does, usage includes that of every descendant.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CgroupHarvest {
+    /// The path of the cgroup relative to the root of the hierarchy, like
+    /// `/system.slice/sshd.service`. The root itself is `/`.
+    pub path: String,
+    /// The usage of a single core, so a busy cgroup can go over 100%.
+    pub cpu_percent: Option<f64>,
+    /// The memory charged to the cgroup, page cache included. The root cgroup doesn't track this.
+    pub mem_bytes: Option<u64>,
+    /// The memory limit of the cgroup, if it has one.
+    pub mem_limit_bytes: Option<u64>,
+    /// The number of tasks in the cgroup.
+    pub tasks: Option<u64>,
+}
+
+impl CgroupHarvest {
+    /// Returns the path of the parent cgroup, or `None` for the root.
+    pub fn parent_path(&self) -> Option<&str> {
+        if self.path == "/" {
+            None
+        } else {
+            self.path
+                .rsplit_once('/')
+                .map(|(parent, _)| if parent.is_empty() { "/" } else { parent })
+        }
+    }
+
+    /// Returns the last component of the path, like `sshd.service`.
+    pub fn name(&self) -> &str {
+        match self.path.rsplit_once('/') {
+            Some((_, name)) if !name.is_empty() => name,
+            _ => &self.path,
+        }
+    }
+}
+
+/// Walks the cgroup v2 hierarchy, keeping the CPU time of each cgroup between harvests to work
+/// out its CPU usage.
+#[derive(Default)]
+pub struct CgroupTracker {
+    samples: HashMap<String, (Instant, u64)>,
+}
+
+impl CgroupTracker {
+    #[cfg(target_os = "linux")]
+    pub fn harvest(&mut self, current_instant: Instant) -> Vec<CgroupHarvest> {
+        let Some(root) = find_root() else {
+            return Vec::new();
+        };
+
+        let mut cgroups = Vec::new();
+        let mut samples = HashMap::new();
+        let mut stack = vec![root.to_path_buf()];
+        while let Some(dir) = stack.pop() {
+            let path = match dir.strip_prefix(root) {
+                Ok(relative) => format!("/{}", relative.display()),
+                Err(_) => continue,
+            };
+
+            let read = |file: &str| std::fs::read_to_string(dir.join(file)).ok();
+            let cpu_usage_usec = read("cpu.stat").and_then(|stat| parse_cpu_usage_usec(&stat));
+            let mut cgroup = CgroupHarvest {
+                mem_bytes: read("memory.current").and_then(|value| value.trim().parse().ok()),
+                mem_limit_bytes: read("memory.max").and_then(|value| value.trim().parse().ok()),
+                tasks: read("pids.current")
+                    .and_then(|value| value.trim().parse().ok())
+                    .or_else(|| read("cgroup.procs").map(|procs| procs.lines().count() as u64)),
+                path,
+                ..Default::default()
+            };
+
+            if let Some(usage_usec) = cpu_usage_usec {
+                if let Some((at, previous_usec)) = self.samples.get(&cgroup.path) {
+                    let elapsed = current_instant.duration_since(*at).as_secs_f64();
+                    if elapsed > 0.0 {
+                        let cpu_secs = usage_usec.saturating_sub(*previous_usec) as f64 / 1e6;
+                        cgroup.cpu_percent = Some(cpu_secs / elapsed * 100.0);
+                    }
+                }
+                samples.insert(cgroup.path.clone(), (current_instant, usage_usec));
+            }
+
+            if let Ok(entries) = std::fs::read_dir(&dir) {
+                stack.extend(
+                    entries
+                        .flatten()
+                        .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+                        .map(|entry| entry.path()),
+                );
+            }
+            cgroups.push(cgroup);
+        }
+
+        // Cgroups that went away are dropped along with their samples.
+        self.samples = samples;
+        cgroups
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn harvest(&mut self, _current_instant: Instant) -> Vec<CgroupHarvest> {
+        Vec::new()
+    }
+}
+
+/// Returns where the cgroup v2 hierarchy is mounted, which is under `unified` on systems that
+/// still mount the v1 hierarchies too.
+#[cfg(target_os = "linux")]
+fn find_root() -> Option<&'static Path> {
+    ["/sys/fs/cgroup", "/sys/fs/cgroup/unified"]
+        .into_iter()
+        .map(Path::new)
+        .find(|root| root.join("cgroup.controllers").exists())
+}
+
+/// Parses the total CPU time, in microseconds, out of a `cpu.stat` file.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_cpu_usage_usec(stat: &str) -> Option<u64> {
+    stat.lines().find_map(|line| {
+        line.strip_prefix("usage_usec ")
+            .and_then(|usage| usage.trim().parse().ok())
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_cpu_usage_usec() {
+        let stat = "usage_usec 123456789
+user_usec 100000000
+system_usec 23456789
+nr_periods 0
+";
+        assert_eq!(parse_cpu_usage_usec(stat), Some(123_456_789));
+        assert_eq!(parse_cpu_usage_usec("nr_periods 0\n"), None);
+    }
+
+    #[test]
+    fn test_cgroup_paths() {
+        let cgroup = |path: &str| CgroupHarvest {
+            path: path.to_string(),
+            ..Default::default()
+        };
+
+        assert_eq!(cgroup("/").parent_path(), None);
+        assert_eq!(cgroup("/").name(), "/");
+        assert_eq!(cgroup("/system.slice").parent_path(), Some("/"));
+        assert_eq!(cgroup("/system.slice").name(), "system.slice");
+        assert_eq!(
+            cgroup("/system.slice/sshd.service").parent_path(),
+            Some("/system.slice")
+        );
+        assert_eq!(cgroup("/system.slice/sshd.service").name(), "sshd.service");
+    }
+}
@@ -0,0 +1,788 @@
+//! Per-subsystem collectors. Each collector owns the state it needs between harvests and runs
+//! on its own thread, so one slow subsystem (say, a hanging network mount) can't hold up the
+//! others.
+
+use std::{
+    collections::HashMap,
+    panic::{self, AssertUnwindSafe},
+    sync::mpsc::{self, Receiver, RecvTimeoutError, Sender},
+    thread,
+    time::{Duration, Instant},
+};
+
+#[cfg(target_os = "linux")]
+use fxhash::FxHashMap;
+use sysinfo::{System, SystemExt};
+
+use super::{
+    agents::{AgentHarvest, AgentWatcher},
+    cgroups::{CgroupHarvest, CgroupTracker},
+    containers::{ContainerHarvest, ContainerTracker},
+    cpu, disks, memory, network,
+    pressure::{self, PressureHarvest},
+    processes,
+    raid::{self, RaidHarvest},
+    remote_uptime::{self, RemoteHost, RemoteUptimeStatus},
+    scheduled_jobs::{self, ScheduledJobHarvest},
+    temperature,
+    zfs::{self, ZfsPoolHarvest},
+    CollectionError,
+};
+use crate::app::Filter;
+
+/// Harvests the data of a single subsystem.
+pub trait Collector: Send + 'static {
+    type Output: Send + 'static;
+
+    /// The name of the harvested subsystem, used when reporting errors.
+    const NAME: &'static str;
+
+    fn collect(&mut self, current_instant: Instant) -> Result<Self::Output, CollectionError>;
+}
+
+/// Runs a [`Collector`] on a dedicated thread.
+pub struct CollectorWorker<C: Collector> {
+    request_sender: Sender<Instant>,
+    output_receiver: Receiver<Result<C::Output, CollectionError>>,
+    is_pending: bool,
+    /// How long to wait between harvests, for collectors that don't need to run on every update.
+    interval: Duration,
+    last_request: Option<Instant>,
+}
+
+impl<C: Collector> CollectorWorker<C> {
+    pub fn spawn(mut collector: C) -> Self {
+        let (request_sender, request_receiver) = mpsc::channel::<Instant>();
+        let (output_sender, output_receiver) = mpsc::channel();
+
+        thread::spawn(move || {
+            for current_instant in request_receiver {
+                // A panicking collector is reported like any other error rather than taking the
+                // worker down with it, so the subsystem gets retried on the next harvest.
+                let output =
+                    panic::catch_unwind(AssertUnwindSafe(|| collector.collect(current_instant)))
+                        .unwrap_or_else(|payload| {
+                            Err(CollectionError::from_panic(C::NAME, payload.as_ref()))
+                        });
+
+                if output_sender.send(output).is_err() {
+                    break;
+                }
+            }
+        });
+
+        CollectorWorker {
+            request_sender,
+            output_receiver,
+            is_pending: false,
+            interval: Duration::ZERO,
+            last_request: None,
+        }
+    }
+
+    /// Only harvests once every `interval`, skipping any updates in between.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Lets the next update harvest again, even if the interval hasn't passed.
+    pub fn make_due(&mut self) {
+        self.last_request = None;
+    }
+
+    /// Starts a new harvest, unless the collector is still busy with a previous one or isn't due
+    /// for another yet.
+    pub fn request(&mut self, current_instant: Instant) {
+        let is_due = self.last_request.is_none_or(|last_request| {
+            current_instant.saturating_duration_since(last_request) >= self.interval
+        });
+
+        if is_due && !self.is_pending && self.request_sender.send(current_instant).is_ok() {
+            self.is_pending = true;
+            self.last_request = Some(current_instant);
+        }
+    }
+
+    /// Waits until `deadline` for the requested harvest. If it doesn't finish in time, this
+    /// returns `None`, and the result is instead picked up by a later call.
+    pub fn receive(&mut self, deadline: Instant) -> Option<Result<C::Output, CollectionError>> {
+        if !self.is_pending {
+            return None;
+        }
+
+        match self
+            .output_receiver
+            .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+        {
+            Ok(output) => {
+                self.is_pending = false;
+                Some(output)
+            }
+            Err(RecvTimeoutError::Timeout) => None,
+            Err(RecvTimeoutError::Disconnected) => {
+                self.is_pending = false;
+                None
+            }
+        }
+    }
+}
+
+pub struct CpuCollector {
+    sys: System,
+    show_average_cpu: bool,
+    collect_frequency: bool,
+    throttle_counts: Vec<Option<u64>>,
+    #[cfg(target_os = "linux")]
+    freq_reader: cpu::linux::CpuFreqReader,
+}
+
+impl CpuCollector {
+    pub fn new(show_average_cpu: bool, collect_frequency: bool) -> Self {
+        let mut sys = System::new_with_specifics(sysinfo::RefreshKind::new());
+        sys.refresh_cpu();
+
+        // The clock files are only opened if the clock is collected at all.
+        #[cfg(target_os = "linux")]
+        let freq_reader = cpu::linux::CpuFreqReader::new(if collect_frequency {
+            sys.cpus().len()
+        } else {
+            0
+        });
+
+        CpuCollector {
+            sys,
+            show_average_cpu,
+            collect_frequency,
+            throttle_counts: Vec::new(),
+            #[cfg(target_os = "linux")]
+            freq_reader,
+        }
+    }
+}
+
+impl Collector for CpuCollector {
+    type Output = (Option<cpu::CpuHarvest>, Option<cpu::LoadAvgHarvest>);
+    const NAME: &'static str = "CPU";
+
+    fn collect(&mut self, _current_instant: Instant) -> Result<Self::Output, CollectionError> {
+        if self.collect_frequency {
+            self.sys.refresh_cpu_specifics(
+                sysinfo::CpuRefreshKind::new()
+                    .with_cpu_usage()
+                    .with_frequency(),
+            );
+        } else {
+            self.sys.refresh_cpu();
+        }
+
+        let mut cpu = cpu::get_cpu_data_list(&self.sys, self.show_average_cpu)
+            .map_err(|err| CollectionError::new("CPU", err))?;
+        if self.collect_frequency {
+            cpu::add_frequencies(
+                &self.sys,
+                &mut cpu,
+                &mut self.throttle_counts,
+                #[cfg(target_os = "linux")]
+                &mut self.freq_reader,
+            );
+        }
+
+        #[cfg(target_family = "unix")]
+        let load_avg =
+            Some(cpu::get_load_avg().map_err(|err| CollectionError::new("Load average", err))?);
+        #[cfg(not(target_family = "unix"))]
+        let load_avg = None;
+
+        Ok((Some(cpu), load_avg))
+    }
+}
+
+#[derive(Default)]
+pub struct MemoryCollection {
+    pub memory: Option<memory::MemHarvest>,
+    pub swap: Option<memory::MemHarvest>,
+    #[cfg(feature = "zfs")]
+    pub arc: Option<memory::MemHarvest>,
+    #[cfg(feature = "gpu")]
+    pub gpu: Option<Vec<(String, memory::MemHarvest)>>,
+}
+
+pub struct MemoryCollector {
+    sys: System,
+    #[cfg(feature = "gpu")]
+    use_gpu: bool,
+}
+
+impl MemoryCollector {
+    pub fn new(#[cfg(feature = "gpu")] use_gpu: bool) -> Self {
+        MemoryCollector {
+            sys: System::new_with_specifics(sysinfo::RefreshKind::new()),
+            #[cfg(feature = "gpu")]
+            use_gpu,
+        }
+    }
+}
+
+impl Collector for MemoryCollector {
+    type Output = MemoryCollection;
+    const NAME: &'static str = "Memory";
+
+    fn collect(&mut self, _current_instant: Instant) -> Result<Self::Output, CollectionError> {
+        self.sys.refresh_memory();
+
+        Ok(MemoryCollection {
+            memory: memory::get_ram_usage(&self.sys),
+            swap: memory::get_swap_usage(
+                #[cfg(not(target_os = "windows"))]
+                &self.sys,
+            ),
+            #[cfg(feature = "zfs")]
+            arc: memory::arc::get_arc_usage(),
+            #[cfg(feature = "gpu")]
+            gpu: if self.use_gpu {
+                memory::gpu::get_gpu_mem_usage()
+            } else {
+                None
+            },
+        })
+    }
+}
+
+pub struct NetworkCollector {
+    sys: System,
+    last_collection_time: Instant,
+    total_rx: u64,
+    total_tx: u64,
+    filter: Option<Filter>,
+}
+
+impl NetworkCollector {
+    pub fn new(filter: Option<Filter>) -> Self {
+        let mut sys = System::new_with_specifics(sysinfo::RefreshKind::new());
+        sys.refresh_networks_list(); // TODO: refresh on a timer?
+
+        NetworkCollector {
+            sys,
+            last_collection_time: Instant::now(),
+            total_rx: 0,
+            total_tx: 0,
+            filter,
+        }
+    }
+}
+
+impl Collector for NetworkCollector {
+    type Output = network::NetworkHarvest;
+    const NAME: &'static str = "Network";
+
+    fn collect(&mut self, current_instant: Instant) -> Result<Self::Output, CollectionError> {
+        self.sys.refresh_networks();
+
+        let net_data = network::get_network_data(
+            &self.sys,
+            self.last_collection_time,
+            &mut self.total_rx,
+            &mut self.total_tx,
+            current_instant,
+            &self.filter,
+        );
+
+        self.total_rx = net_data.total_rx;
+        self.total_tx = net_data.total_tx;
+        self.last_collection_time = current_instant;
+
+        Ok(net_data)
+    }
+}
+
+pub struct ProcessCollector {
+    sys: System,
+    #[cfg(target_os = "linux")]
+    pid_mapping: FxHashMap<crate::Pid, processes::PrevProcDetails>,
+    #[cfg(target_os = "linux")]
+    prev_idle: f64,
+    #[cfg(target_os = "linux")]
+    prev_non_idle: f64,
+    last_collection_time: Instant,
+    mem_total_kb: u64,
+    use_current_cpu_total: bool,
+    unnormalized_cpu: bool,
+    #[cfg(target_family = "unix")]
+    user_table: processes::UserTable,
+    #[cfg(feature = "gpu")]
+    gpu_tracker: processes::gpu::GpuProcessTracker,
+}
+
+impl ProcessCollector {
+    pub fn new(use_current_cpu_total: bool, unnormalized_cpu: bool) -> Self {
+        let mut sys = System::new_with_specifics(sysinfo::RefreshKind::new());
+        sys.refresh_memory();
+        sys.refresh_cpu();
+
+        if cfg!(target_os = "windows") {
+            sys.refresh_users_list();
+        }
+
+        ProcessCollector {
+            mem_total_kb: sys.total_memory(),
+            sys,
+            #[cfg(target_os = "linux")]
+            pid_mapping: FxHashMap::default(),
+            #[cfg(target_os = "linux")]
+            prev_idle: 0_f64,
+            #[cfg(target_os = "linux")]
+            prev_non_idle: 0_f64,
+            last_collection_time: Instant::now(),
+            use_current_cpu_total,
+            unnormalized_cpu,
+            #[cfg(target_family = "unix")]
+            user_table: Default::default(),
+            #[cfg(feature = "gpu")]
+            gpu_tracker: Default::default(),
+        }
+    }
+}
+
+impl Collector for ProcessCollector {
+    type Output = Vec<processes::ProcessHarvest>;
+    const NAME: &'static str = "Processes";
+
+    fn collect(&mut self, current_instant: Instant) -> Result<Self::Output, CollectionError> {
+        self.sys.refresh_cpu();
+
+        #[cfg(not(target_os = "linux"))]
+        self.sys.refresh_processes();
+
+        // I/O rates are worked out over the actual time between harvests, which can drift from the
+        // update rate.
+        let time_diff = current_instant
+            .duration_since(self.last_collection_time)
+            .as_secs_f64();
+        self.last_collection_time = current_instant;
+
+        let process_list = {
+            #[cfg(target_os = "linux")]
+            {
+                use self::processes::{PrevProc, ProcHarvestOptions};
+
+                let prev_proc = PrevProc {
+                    prev_idle: &mut self.prev_idle,
+                    prev_non_idle: &mut self.prev_non_idle,
+                };
+
+                let proc_harvest_options = ProcHarvestOptions {
+                    use_current_cpu_total: self.use_current_cpu_total,
+                    unnormalized_cpu: self.unnormalized_cpu,
+                };
+
+                processes::get_process_data(
+                    &self.sys,
+                    prev_proc,
+                    &mut self.pid_mapping,
+                    proc_harvest_options,
+                    time_diff,
+                    self.mem_total_kb,
+                    &mut self.user_table,
+                )
+            }
+            #[cfg(not(target_os = "linux"))]
+            {
+                #[cfg(target_family = "unix")]
+                {
+                    processes::get_process_data(
+                        &self.sys,
+                        self.use_current_cpu_total,
+                        self.unnormalized_cpu,
+                        time_diff,
+                        self.mem_total_kb,
+                        &mut self.user_table,
+                    )
+                }
+                #[cfg(not(target_family = "unix"))]
+                {
+                    processes::get_process_data(
+                        &self.sys,
+                        self.use_current_cpu_total,
+                        self.unnormalized_cpu,
+                        time_diff,
+                        self.mem_total_kb,
+                    )
+                }
+            }
+        };
+
+        let mut process_list =
+            process_list.map_err(|err| CollectionError::new("Processes", err))?;
+
+        #[cfg(feature = "gpu")]
+        self.gpu_tracker.add_gpu_usage(&mut process_list);
+
+        // NB: To avoid duplicate sorts on rerenders/events, we sort the processes by PID here.
+        // We also want to avoid re-sorting *again* later on if we're sorting by PID, since we already
+        // did it here!
+        process_list.sort_unstable_by_key(|p| p.pid);
+
+        Ok(process_list)
+    }
+}
+
+pub struct TempCollector {
+    #[cfg(not(target_os = "linux"))]
+    sys: System,
+    temperature_type: temperature::TemperatureType,
+    sensor_types: Vec<temperature::SensorType>,
+    filter: Option<Filter>,
+}
+
+impl TempCollector {
+    pub fn new(
+        temperature_type: temperature::TemperatureType, sensor_types: Vec<temperature::SensorType>,
+        filter: Option<Filter>,
+    ) -> Self {
+        TempCollector {
+            #[cfg(not(target_os = "linux"))]
+            sys: {
+                let mut sys = System::new_with_specifics(sysinfo::RefreshKind::new());
+                sys.refresh_components_list(); // TODO: refresh on a timer?
+                sys
+            },
+            temperature_type,
+            sensor_types,
+            filter,
+        }
+    }
+}
+
+impl Collector for TempCollector {
+    type Output = Option<Vec<temperature::TempHarvest>>;
+    const NAME: &'static str = "Temperature";
+
+    fn collect(&mut self, _current_instant: Instant) -> Result<Self::Output, CollectionError> {
+        #[cfg(not(target_os = "linux"))]
+        let temperature_data = {
+            // Only temperatures are read on these platforms.
+            if self
+                .sensor_types
+                .contains(&temperature::SensorType::Temperature)
+            {
+                self.sys.refresh_components();
+                temperature::get_temperature_data(&self.sys, &self.temperature_type, &self.filter)
+            } else {
+                Ok(Some(Vec::new()))
+            }
+        };
+
+        #[cfg(target_os = "linux")]
+        let temperature_data = temperature::get_temperature_data(
+            &self.temperature_type,
+            &self.sensor_types,
+            &self.filter,
+        );
+
+        temperature_data.map_err(|err| CollectionError::new("Temperature", err))
+    }
+}
+
+pub struct DiskCollector {
+    disk_filter: Option<Filter>,
+    mount_filter: Option<Filter>,
+    /// The last harvested disks, used to fill in for remote mounts that stop responding.
+    prev_disks: Option<Vec<disks::DiskHarvest>>,
+}
+
+impl DiskCollector {
+    pub fn new(disk_filter: Option<Filter>, mount_filter: Option<Filter>) -> Self {
+        DiskCollector {
+            disk_filter,
+            mount_filter,
+            prev_disks: None,
+        }
+    }
+}
+
+impl Collector for DiskCollector {
+    type Output = (Option<Vec<disks::DiskHarvest>>, Option<disks::IoHarvest>);
+    const NAME: &'static str = "Disks";
+
+    fn collect(&mut self, _current_instant: Instant) -> Result<Self::Output, CollectionError> {
+        let (disk_res, io_res) = futures::executor::block_on(async {
+            futures::join!(
+                disks::get_disk_usage(
+                    true,
+                    &self.disk_filter,
+                    &self.mount_filter,
+                    self.prev_disks.as_deref(),
+                ),
+                disks::get_io_usage(true)
+            )
+        });
+
+        let disks = disk_res.map_err(|err| CollectionError::new("Disks", err))?;
+        if disks.is_some() {
+            self.prev_disks = disks.clone();
+        }
+
+        let io = io_res.map_err(|err| CollectionError::new("Disk IO", err))?;
+
+        Ok((disks, io))
+    }
+}
+
+#[derive(Default)]
+pub struct ConnectionsCollection {
+    pub connections: Vec<network::ConnectionHarvest>,
+    /// The TCP details of each socket, read over netlink.
+    #[cfg(target_os = "linux")]
+    pub stats: Vec<network::NetlinkSocketInfo>,
+}
+
+#[derive(Default)]
+pub struct ConnectionsCollector {
+    #[cfg(target_os = "linux")]
+    transfer_rates: network::sock_diag::TransferRateTracker,
+}
+
+impl Collector for ConnectionsCollector {
+    type Output = ConnectionsCollection;
+    const NAME: &'static str = "Connections";
+
+    fn collect(&mut self, _current_instant: Instant) -> Result<Self::Output, CollectionError> {
+        #[cfg(target_os = "linux")]
+        let stats = {
+            let mut stats = network::sock_diag::collect_netlink_diag();
+            self.transfer_rates.update(&mut stats, _current_instant);
+            stats
+        };
+
+        Ok(ConnectionsCollection {
+            connections: network::connections::get_connections()
+                .map_err(|err| CollectionError::new("Connections", err))?,
+            #[cfg(target_os = "linux")]
+            stats,
+        })
+    }
+}
+
+/// How long to wait between checks on remote hosts. Unlike everything else, these go over the
+/// network, so there's no point in checking them on every harvest.
+pub const REMOTE_UPTIME_INTERVAL: Duration = Duration::from_secs(30);
+
+pub struct RemoteUptimeCollector {
+    hosts: Vec<RemoteHost>,
+    /// When each host that only gets pinged started answering, so it can be given a streak.
+    reachable_since: HashMap<String, Instant>,
+}
+
+impl RemoteUptimeCollector {
+    pub fn new(hosts: Vec<RemoteHost>) -> Self {
+        RemoteUptimeCollector {
+            hosts,
+            reachable_since: HashMap::new(),
+        }
+    }
+}
+
+impl Collector for RemoteUptimeCollector {
+    type Output = Vec<remote_uptime::RemoteUptimeHarvest>;
+    const NAME: &'static str = "Remote uptime";
+
+    fn collect(&mut self, current_instant: Instant) -> Result<Self::Output, CollectionError> {
+        let mut remote_uptimes = remote_uptime::get_remote_uptimes(&self.hosts);
+        for harvest in &mut remote_uptimes {
+            if let RemoteUptimeStatus::Reachable(_) = harvest.status {
+                let since = *self
+                    .reachable_since
+                    .entry(harvest.name.clone())
+                    .or_insert(current_instant);
+                harvest.status =
+                    RemoteUptimeStatus::Reachable(current_instant.duration_since(since).as_secs());
+            } else {
+                self.reachable_since.remove(&harvest.name);
+            }
+        }
+
+        Ok(remote_uptimes)
+    }
+}
+
+pub struct AgentsCollector {
+    watcher: AgentWatcher,
+}
+
+impl AgentsCollector {
+    pub fn new(addresses: &[String]) -> Self {
+        AgentsCollector {
+            watcher: AgentWatcher::spawn(addresses),
+        }
+    }
+}
+
+impl Collector for AgentsCollector {
+    type Output = Vec<AgentHarvest>;
+    const NAME: &'static str = "Agents";
+
+    fn collect(&mut self, _current_instant: Instant) -> Result<Self::Output, CollectionError> {
+        Ok(self.watcher.latest())
+    }
+}
+
+#[derive(Default)]
+pub struct ContainersCollector {
+    tracker: ContainerTracker,
+}
+
+impl Collector for ContainersCollector {
+    type Output = Vec<ContainerHarvest>;
+    const NAME: &'static str = "Containers";
+
+    fn collect(&mut self, current_instant: Instant) -> Result<Self::Output, CollectionError> {
+        self.tracker
+            .harvest(current_instant)
+            .map_err(|err| CollectionError::new(Self::NAME, err))
+    }
+}
+
+pub struct PressureCollector;
+
+impl Collector for PressureCollector {
+    type Output = Option<PressureHarvest>;
+    const NAME: &'static str = "Pressure";
+
+    fn collect(&mut self, _current_instant: Instant) -> Result<Self::Output, CollectionError> {
+        Ok(pressure::get_pressure())
+    }
+}
+
+pub struct RaidCollector;
+
+impl Collector for RaidCollector {
+    type Output = Vec<RaidHarvest>;
+    const NAME: &'static str = "RAID";
+
+    fn collect(&mut self, _current_instant: Instant) -> Result<Self::Output, CollectionError> {
+        Ok(raid::get_raid_arrays())
+    }
+}
+
+pub struct ZfsPoolCollector;
+
+impl Collector for ZfsPoolCollector {
+    type Output = Vec<ZfsPoolHarvest>;
+    const NAME: &'static str = "ZFS pools";
+
+    fn collect(&mut self, _current_instant: Instant) -> Result<Self::Output, CollectionError> {
+        Ok(zfs::get_zfs_pools())
+    }
+}
+
+#[derive(Default)]
+pub struct CgroupCollector {
+    tracker: CgroupTracker,
+}
+
+impl Collector for CgroupCollector {
+    type Output = Vec<CgroupHarvest>;
+    const NAME: &'static str = "Cgroups";
+
+    fn collect(&mut self, current_instant: Instant) -> Result<Self::Output, CollectionError> {
+        Ok(self.tracker.harvest(current_instant))
+    }
+}
+
+/// How long to wait between reading the scheduled jobs. They rarely change, and reading them runs
+/// `systemctl` and goes through every crontab.
+pub const SCHEDULED_JOBS_INTERVAL: Duration = Duration::from_secs(30);
+
+pub struct ScheduledJobCollector;
+
+impl Collector for ScheduledJobCollector {
+    type Output = Vec<ScheduledJobHarvest>;
+    const NAME: &'static str = "Scheduled jobs";
+
+    fn collect(&mut self, _current_instant: Instant) -> Result<Self::Output, CollectionError> {
+        Ok(scheduled_jobs::get_scheduled_jobs())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::*;
+
+    struct SlowCollector(Duration);
+
+    impl Collector for SlowCollector {
+        type Output = u64;
+        const NAME: &'static str = "Slow";
+
+        fn collect(&mut self, _current_instant: Instant) -> Result<Self::Output, CollectionError> {
+            thread::sleep(self.0);
+            Ok(1)
+        }
+    }
+
+    struct PanickingCollector;
+
+    impl Collector for PanickingCollector {
+        type Output = u64;
+        const NAME: &'static str = "Panicking";
+
+        fn collect(&mut self, _current_instant: Instant) -> Result<Self::Output, CollectionError> {
+            panic!("unexpected format");
+        }
+    }
+
+    #[test]
+    fn test_panic_is_reported_as_error() {
+        let mut worker = CollectorWorker::spawn(PanickingCollector);
+
+        for _ in 0..2 {
+            worker.request(Instant::now());
+            let output = worker.receive(Instant::now() + Duration::from_secs(5));
+            assert!(matches!(
+                output,
+                Some(Err(CollectionError { ref source, ref message, .. }))
+                    if source == "Panicking" && message.contains("unexpected format")
+            ));
+        }
+    }
+
+    #[test]
+    fn test_interval_skips_updates() {
+        let mut worker = CollectorWorker::spawn(SlowCollector(Duration::ZERO))
+            .with_interval(Duration::from_secs(5));
+        let start = Instant::now();
+
+        worker.request(start);
+        assert!(worker.receive(start + Duration::from_secs(5)).is_some());
+
+        // Not due yet, so there's nothing to wait for.
+        worker.request(start + Duration::from_secs(1));
+        assert!(worker
+            .receive(Instant::now() + Duration::from_secs(5))
+            .is_none());
+
+        worker.request(start + Duration::from_secs(5));
+        assert!(worker
+            .receive(Instant::now() + Duration::from_secs(5))
+            .is_some());
+    }
+
+    #[test]
+    fn test_late_output_is_picked_up_later() {
+        let mut worker = CollectorWorker::spawn(SlowCollector(Duration::from_millis(100)));
+
+        worker.request(Instant::now());
+        assert!(worker.receive(Instant::now()).is_none());
+
+        // The collector is still busy, so this shouldn't queue up another harvest.
+        worker.request(Instant::now());
+        assert!(matches!(
+            worker.receive(Instant::now() + Duration::from_secs(5)),
+            Some(Ok(1))
+        ));
+        assert!(worker.receive(Instant::now()).is_none());
+    }
+}
@@ -0,0 +1,330 @@
+//! Containers run by Docker or Podman, read from the engine's API over its Unix socket.
+//!
+//! Both engines answer the same Docker-compatible API. The socket is taken from `DOCKER_HOST` if
+//! it points at one, and otherwise looked for in the usual places, rootless ones included.
+
+use std::{
+    collections::HashMap,
+    env, io,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use crate::utils::json::JsonValue;
+
+/// How long the engine has to answer each request.
+#[cfg(target_family = "unix")]
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ContainerHarvest {
+    pub id: String,
+    pub name: String,
+    pub image: String,
+    /// Like `running` or `exited`.
+    pub state: String,
+    /// Like `Up 3 hours`, as the engine words it.
+    pub status: String,
+    /// The usage of a single core, so a busy container can go over 100%.
+    pub cpu_percent: Option<f64>,
+    pub mem_bytes: Option<u64>,
+    pub rx_bytes_per_sec: Option<u64>,
+    pub tx_bytes_per_sec: Option<u64>,
+}
+
+impl ContainerHarvest {
+    pub fn is_running(&self) -> bool {
+        self.state == "running"
+    }
+}
+
+/// The counters of a container's last harvest, which its rates are worked out from.
+#[derive(Clone, Copy, Debug)]
+struct Sample {
+    at: Instant,
+    cpu_total: u64,
+    system_total: u64,
+    rx_bytes: u64,
+    tx_bytes: u64,
+}
+
+/// Lists the engine's containers, keeping what is needed to work out their rates between
+/// harvests.
+#[derive(Default)]
+pub struct ContainerTracker {
+    samples: HashMap<String, Sample>,
+}
+
+impl ContainerTracker {
+    pub fn harvest(&mut self, current_instant: Instant) -> io::Result<Vec<ContainerHarvest>> {
+        let socket = find_socket().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, "no Docker or Podman socket found")
+        })?;
+
+        let list = request(&socket, "/containers/json?all=true")?;
+        let mut containers: Vec<ContainerHarvest> = list
+            .as_array()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(parse_container)
+            .collect();
+
+        for container in containers.iter_mut().filter(|c| c.is_running()) {
+            // A container can stop between being listed and its stats being asked for.
+            let path = format!(
+                "/containers/{}/stats?stream=false&one-shot=true",
+                container.id
+            );
+            if let Ok(stats) = request(&socket, &path) {
+                self.add_stats(container, &stats, current_instant);
+            }
+        }
+        self.samples
+            .retain(|id, _| containers.iter().any(|container| &container.id == id));
+
+        Ok(containers)
+    }
+
+    fn add_stats(
+        &mut self, container: &mut ContainerHarvest, stats: &JsonValue, current_instant: Instant,
+    ) {
+        let counter = |keys: &[&str]| stats.path(keys).and_then(JsonValue::as_u64);
+
+        // Cached files count towards usage, but are given back under pressure, so they are left
+        // out like `docker stats` does. cgroup v2 calls them inactive_file, v1 total_inactive_file.
+        let inactive = counter(&["memory_stats", "stats", "inactive_file"])
+            .or_else(|| counter(&["memory_stats", "stats", "total_inactive_file"]))
+            .unwrap_or(0);
+        container.mem_bytes =
+            counter(&["memory_stats", "usage"]).map(|usage| usage.saturating_sub(inactive));
+
+        let (rx_bytes, tx_bytes) = stats
+            .get("networks")
+            .and_then(JsonValue::as_object)
+            .unwrap_or_default()
+            .iter()
+            .fold((0, 0), |(rx, tx), (_, interface)| {
+                let bytes = |key| interface.get(key).and_then(JsonValue::as_u64).unwrap_or(0);
+                (rx + bytes("rx_bytes"), tx + bytes("tx_bytes"))
+            });
+        let (Some(cpu_total), Some(system_total)) = (
+            counter(&["cpu_stats", "cpu_usage", "total_usage"]),
+            counter(&["cpu_stats", "system_cpu_usage"]),
+        ) else {
+            return;
+        };
+        let online_cpus = counter(&["cpu_stats", "online_cpus"])
+            .or_else(|| {
+                stats
+                    .path(&["cpu_stats", "cpu_usage", "percpu_usage"])
+                    .and_then(JsonValue::as_array)
+                    .map(|cpus| cpus.len() as u64)
+            })
+            .unwrap_or(1);
+
+        let sample = Sample {
+            at: current_instant,
+            cpu_total,
+            system_total,
+            rx_bytes,
+            tx_bytes,
+        };
+        // The first harvest of a container has nothing to compare against.
+        if let Some(previous) = self.samples.insert(container.id.clone(), sample) {
+            let system_delta = system_total.saturating_sub(previous.system_total);
+            if system_delta > 0 {
+                let cpu_delta = cpu_total.saturating_sub(previous.cpu_total);
+                container.cpu_percent =
+                    Some(cpu_delta as f64 / system_delta as f64 * online_cpus as f64 * 100.0);
+            }
+
+            let elapsed = current_instant.duration_since(previous.at).as_secs_f64();
+            if elapsed > 0.0 {
+                let rate =
+                    |now: u64, before: u64| (now.saturating_sub(before) as f64 / elapsed) as u64;
+                container.rx_bytes_per_sec = Some(rate(rx_bytes, previous.rx_bytes));
+                container.tx_bytes_per_sec = Some(rate(tx_bytes, previous.tx_bytes));
+            }
+        }
+    }
+}
+
+fn parse_container(container: &JsonValue) -> Option<ContainerHarvest> {
+    let field = |key| {
+        container
+            .get(key)
+            .and_then(JsonValue::as_str)
+            .unwrap_or_default()
+            .to_string()
+    };
+    let id = container.get("Id")?.as_str()?.to_string();
+    // Names are given with a leading slash, and only the first is shown.
+    let name = container
+        .get("Names")
+        .and_then(JsonValue::as_array)
+        .and_then(|names| names.first())
+        .and_then(JsonValue::as_str)
+        .map(|name| name.trim_start_matches('/').to_string())
+        .unwrap_or_else(|| id.chars().take(12).collect());
+
+    Some(ContainerHarvest {
+        name,
+        image: field("Image"),
+        state: field("State"),
+        status: field("Status"),
+        id,
+        ..ContainerHarvest::default()
+    })
+}
+
+fn find_socket() -> Option<PathBuf> {
+    if let Ok(host) = env::var("DOCKER_HOST") {
+        if let Some(path) = host.strip_prefix("unix://") {
+            return Some(PathBuf::from(path));
+        }
+    }
+
+    let runtime_dir = env::var_os("XDG_RUNTIME_DIR").map(PathBuf::from);
+    [
+        Some(PathBuf::from("/var/run/docker.sock")),
+        Some(PathBuf::from("/run/podman/podman.sock")),
+        runtime_dir.as_ref().map(|dir| dir.join("docker.sock")),
+        runtime_dir
+            .as_ref()
+            .map(|dir| dir.join("podman/podman.sock")),
+    ]
+    .into_iter()
+    .flatten()
+    .find(|path| path.exists())
+}
+
+/// Asks the engine for `path`, returning the JSON it answers with.
+#[cfg(target_family = "unix")]
+fn request(socket: &Path, path: &str) -> io::Result<JsonValue> {
+    use std::{
+        io::{Read, Write},
+        os::unix::net::UnixStream,
+    };
+
+    let mut stream = UnixStream::connect(socket)?;
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+    // HTTP/1.0 has the engine close the connection once it has answered, without chunking.
+    write!(stream, "GET {path} HTTP/1.0\r\nHost: localhost\r\n\r\n")?;
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response)?;
+
+    parse_response(&String::from_utf8_lossy(&response))
+}
+
+#[cfg(not(target_family = "unix"))]
+fn request(_socket: &Path, _path: &str) -> io::Result<JsonValue> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "containers are only supported on Unix",
+    ))
+}
+
+fn parse_response(response: &str) -> io::Result<JsonValue> {
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or_else(|| invalid("incomplete response from the engine".to_string()))?;
+    let status = head.lines().next().unwrap_or_default();
+    if status.split_whitespace().nth(1) != Some("200") {
+        return Err(invalid(format!("the engine answered '{status}'")));
+    }
+
+    let is_chunked = head.lines().any(|header| {
+        header
+            .to_ascii_lowercase()
+            .starts_with("transfer-encoding: chunked")
+    });
+    let body = if is_chunked {
+        decode_chunked(body).ok_or_else(|| invalid("malformed chunked response".to_string()))?
+    } else {
+        body.to_string()
+    };
+
+    JsonValue::parse(&body).ok_or_else(|| invalid("malformed JSON from the engine".to_string()))
+}
+
+fn decode_chunked(mut body: &str) -> Option<String> {
+    let mut decoded = String::new();
+    loop {
+        let (size, rest) = body.split_once("\r\n")?;
+        let size = usize::from_str_radix(size.split(';').next()?.trim(), 16).ok()?;
+        if size == 0 {
+            return Some(decoded);
+        }
+        decoded.push_str(rest.get(..size)?);
+        body = rest.get(size..)?.strip_prefix("\r\n")?;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_response() {
+        let list = parse_response(
+            "HTTP/1.0 200 OK\r\nContent-Type: application/json\r\n\r\n\
+             [{\"Id\":\"abc123\",\"Names\":[\"/web\"],\"Image\":\"nginx:1.25\",\
+             \"State\":\"running\",\"Status\":\"Up 3 hours\"},{\"Id\":\"def456\"}]",
+        )
+        .unwrap();
+        let containers: Vec<_> = list
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(parse_container)
+            .collect();
+        assert_eq!(containers[0].name, "web");
+        assert_eq!(containers[0].image, "nginx:1.25");
+        assert!(containers[0].is_running());
+        assert_eq!(containers[1].name, "def456");
+        assert!(!containers[1].is_running());
+
+        let chunked = parse_response(
+            "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\n[1,2\r\n1\r\n]\r\n0\r\n\r\n",
+        );
+        assert_eq!(
+            chunked.unwrap(),
+            JsonValue::Array(vec![JsonValue::Number(1.0), JsonValue::Number(2.0)])
+        );
+        assert!(parse_response("HTTP/1.0 404 Not Found\r\n\r\n{}").is_err());
+    }
+
+    #[test]
+    fn test_container_rates() {
+        let stats = |cpu: u64, system: u64, rx: u64| {
+            JsonValue::parse(&format!(
+                r#"{{"cpu_stats": {{"cpu_usage": {{"total_usage": {cpu}}}, "system_cpu_usage": {system}, "online_cpus": 4}},
+                    "memory_stats": {{"usage": 3000, "limit": 8000, "stats": {{"inactive_file": 1000}}}},
+                    "networks": {{"eth0": {{"rx_bytes": {rx}, "tx_bytes": 0}}, "eth1": {{"rx_bytes": 500, "tx_bytes": 0}}}}}}"#
+            ))
+            .unwrap()
+        };
+        let mut tracker = ContainerTracker::default();
+        let mut container = ContainerHarvest {
+            id: "abc123".to_string(),
+            ..ContainerHarvest::default()
+        };
+
+        let start = Instant::now();
+        tracker.add_stats(&mut container, &stats(1000, 10_000, 1000), start);
+        assert_eq!(container.mem_bytes, Some(2000));
+        assert_eq!(container.cpu_percent, None);
+
+        tracker.add_stats(
+            &mut container,
+            &stats(2000, 20_000, 3000),
+            start + Duration::from_secs(2),
+        );
+        assert_eq!(container.cpu_percent, Some(40.0));
+        assert_eq!(container.rx_bytes_per_sec, Some(1000));
+        assert_eq!(container.tx_bytes_per_sec, Some(0));
+    }
+}
@@ -7,6 +7,9 @@
 pub mod sysinfo;
 pub use self::sysinfo::*;
 
+#[cfg(target_os = "linux")]
+pub mod linux;
+
 pub type LoadAvgHarvest = [f32; 3];
 
 #[derive(Debug, Clone, Copy)]
@@ -19,6 +22,18 @@ pub enum CpuDataType {
 pub struct CpuData {
     pub data_type: CpuDataType,
     pub cpu_usage: f64,
+    /// Only harvested for cores, and if enabled.
+    pub frequency: Option<CpuFrequency>,
+}
+
+/// The clock of a single core, and how it's being scaled.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CpuFrequency {
+    pub mhz: u64,
+    /// The cpufreq scaling governor, which is only known on Linux.
+    pub governor: Option<String>,
+    /// Whether the core was thermally throttled since the last harvest.
+    pub is_throttled: bool,
 }
 
 pub type CpuHarvest = Vec<CpuData>;
@@ -0,0 +1,80 @@
+//! Per-core clock scaling details from `/sys/devices/system/cpu`.
+
+use std::{
+    fs::{self, File},
+    io::{Read, Seek},
+    path::Path,
+};
+
+/// Reads each core's current clock from `cpufreq/scaling_cur_freq`, for kernels where sysinfo
+/// reports 0. The files are opened once and read again on each harvest.
+pub struct CpuFreqReader {
+    files: Vec<Option<File>>,
+}
+
+impl CpuFreqReader {
+    /// Opens the clock of each of the first `cpu_count` cores. Cores without cpufreq are skipped.
+    pub fn new(cpu_count: usize) -> Self {
+        Self::open(
+            (0..cpu_count).map(|index| {
+                format!("/sys/devices/system/cpu/cpu{index}/cpufreq/scaling_cur_freq")
+            }),
+        )
+    }
+
+    fn open(paths: impl Iterator<Item = impl AsRef<Path>>) -> Self {
+        CpuFreqReader {
+            files: paths.map(|path| File::open(path).ok()).collect(),
+        }
+    }
+
+    /// Returns the current clock of a core in MHz, if its file could be opened and read.
+    pub fn read_mhz(&mut self, index: usize) -> Option<u64> {
+        let file = self.files.get_mut(index)?.as_mut()?;
+        let mut khz = String::new();
+        file.rewind().ok()?;
+        file.read_to_string(&mut khz).ok()?;
+        khz.trim().parse::<u64>().ok().map(|khz| khz / 1000)
+    }
+}
+
+/// Returns the cpufreq scaling governor of a core, e.g. `powersave` or `schedutil`.
+pub fn get_governor(index: usize) -> Option<String> {
+    fs::read_to_string(format!(
+        "/sys/devices/system/cpu/cpu{index}/cpufreq/scaling_governor"
+    ))
+    .ok()
+    .map(|governor| governor.trim().to_string())
+    .filter(|governor| !governor.is_empty())
+}
+
+/// Returns how many times a core has been thermally throttled since boot. Only Intel CPUs report
+/// this.
+pub fn get_throttle_count(index: usize) -> Option<u64> {
+    fs::read_to_string(format!(
+        "/sys/devices/system/cpu/cpu{index}/thermal_throttle/core_throttle_count"
+    ))
+    .ok()
+    .and_then(|count| count.trim().parse().ok())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_cpu_freq_reader() {
+        let path = std::env::temp_dir().join(format!("btm_cpu_freq_{}", std::process::id()));
+        fs::write(&path, "2400000\n").unwrap();
+
+        let mut reader = CpuFreqReader::open([path.as_path(), Path::new("/nonexistent")].iter());
+        assert_eq!(reader.read_mhz(0), Some(2400));
+        // The same handle picks up the new clock.
+        fs::write(&path, "800000\n").unwrap();
+        assert_eq!(reader.read_mhz(0), Some(800));
+        assert_eq!(reader.read_mhz(1), None);
+        assert_eq!(reader.read_mhz(2), None);
+
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -5,7 +5,7 @@ use std::collections::VecDeque;
 
 use sysinfo::{CpuExt, LoadAvg, System, SystemExt};
 
-use super::{CpuData, CpuDataType, CpuHarvest};
+use super::{CpuData, CpuDataType, CpuFrequency, CpuHarvest};
 use crate::app::data_harvester::cpu::LoadAvgHarvest;
 
 pub fn get_cpu_data_list(
@@ -18,6 +18,7 @@ pub fn get_cpu_data_list(
         .map(|(i, cpu)| CpuData {
             data_type: CpuDataType::Cpu(i),
             cpu_usage: cpu.cpu_usage() as f64,
+            frequency: None,
         })
         .collect();
 
@@ -27,15 +28,80 @@ pub fn get_cpu_data_list(
         cpu_deque.push_front(CpuData {
             data_type: CpuDataType::Avg,
             cpu_usage: cpu.cpu_usage() as f64,
+            frequency: None,
         })
     }
 
     Ok(Vec::from(cpu_deque))
 }
 
+/// Adds the clock of each core to `cpus`, which must have been refreshed with their frequency.
+/// `throttle_counts` holds how many times each core had been throttled as of the last harvest.
+/// On Linux, a clock sysinfo reports as 0 is read from `freq_reader` instead.
+pub fn add_frequencies(
+    sys: &sysinfo::System, cpus: &mut [CpuData], throttle_counts: &mut Vec<Option<u64>>,
+    #[cfg(target_os = "linux")] freq_reader: &mut super::linux::CpuFreqReader,
+) {
+    let sys_cpus = sys.cpus();
+    throttle_counts.resize(sys_cpus.len(), None);
+
+    for cpu in cpus {
+        let CpuDataType::Cpu(index) = cpu.data_type else {
+            continue;
+        };
+        let Some(sys_cpu) = sys_cpus.get(index) else {
+            continue;
+        };
+
+        #[cfg(target_os = "linux")]
+        let (governor, throttle_count) = (
+            super::linux::get_governor(index),
+            super::linux::get_throttle_count(index),
+        );
+        #[cfg(not(target_os = "linux"))]
+        let (governor, throttle_count) = (None, None);
+
+        #[cfg(target_os = "linux")]
+        let mhz = match sys_cpu.frequency() {
+            0 => freq_reader.read_mhz(index).unwrap_or(0),
+            mhz => mhz,
+        };
+        #[cfg(not(target_os = "linux"))]
+        let mhz = sys_cpu.frequency();
+
+        let is_throttled = was_throttled(throttle_counts[index], throttle_count);
+        throttle_counts[index] = throttle_count;
+
+        cpu.frequency = Some(CpuFrequency {
+            mhz,
+            governor,
+            is_throttled,
+        });
+    }
+}
+
+/// Whether a core's throttle count went up since the last harvest.
+fn was_throttled(last_count: Option<u64>, count: Option<u64>) -> bool {
+    matches!((last_count, count), (Some(last_count), Some(count)) if count > last_count)
+}
+
 pub fn get_load_avg() -> crate::error::Result<LoadAvgHarvest> {
     let sys = System::new();
     let LoadAvg { one, five, fifteen } = sys.load_average();
 
     Ok([one as f32, five as f32, fifteen as f32])
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_was_throttled() {
+        assert!(was_throttled(Some(3), Some(5)));
+        assert!(!was_throttled(Some(5), Some(5)));
+        // The first harvest has nothing to compare against.
+        assert!(!was_throttled(None, Some(5)));
+        assert!(!was_throttled(None, None));
+    }
+}
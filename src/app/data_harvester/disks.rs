@@ -20,12 +20,173 @@ pub struct DiskHarvest {
     pub free_space: Option<u64>,
     pub used_space: Option<u64>,
     pub total_space: Option<u64>,
+    pub mount_type: MountType,
+    /// Whether the usage values are from a previous harvest, as the mount did not respond in time.
+    pub stale: bool,
+    /// The active I/O scheduler of the backing device. Only available on Linux, for physical,
+    /// whole-disk devices.
+    pub io_scheduler: Option<String>,
+    /// The number of inodes in use and in total, if the filesystem has a fixed number of them.
+    pub used_inodes: Option<u64>,
+    pub total_inodes: Option<u64>,
+}
+
+impl DiskHarvest {
+    /// The share of inodes in use, in percent, if the filesystem has a fixed number of them.
+    pub fn inode_usage_percent(&self) -> Option<f64> {
+        match (self.used_inodes, self.total_inodes) {
+            (Some(used_inodes), Some(total_inodes)) if total_inodes > 0 => {
+                Some(used_inodes as f64 / total_inodes as f64 * 100.0)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The kind of filesystem a disk is mounted as.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MountType {
+    #[default]
+    Local,
+    Nfs,
+    Cifs,
+    Tmpfs,
+    Other,
+}
+
+impl MountType {
+    /// Classifies a mount from its filesystem type (as in `/proc/mounts`), and whether it is
+    /// backed by a physical device.
+    pub fn new(fs_type: &str, is_physical: bool) -> Self {
+        match fs_type.to_lowercase().as_str() {
+            "nfs" | "nfs4" => MountType::Nfs,
+            "cifs" | "smbfs" | "smb3" => MountType::Cifs,
+            "tmpfs" => MountType::Tmpfs,
+            _ if is_physical => MountType::Local,
+            _ => MountType::Other,
+        }
+    }
+
+    /// Whether this is a network mount, which may block if the remote host stops responding.
+    pub fn is_remote(&self) -> bool {
+        matches!(self, MountType::Nfs | MountType::Cifs)
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct IoData {
     pub read_bytes: u64,
     pub write_bytes: u64,
+    /// Counters used to calculate read and write latency. Only available on Linux.
+    pub latency_counters: Option<IoLatencyCounters>,
+}
+
+/// Cumulative I/O request counters, as reported by `/proc/diskstats`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct IoLatencyCounters {
+    pub reads_completed: u64,
+    pub time_spent_reading_ms: u64,
+    pub writes_completed: u64,
+    pub time_spent_writing_ms: u64,
+}
+
+impl IoLatencyCounters {
+    /// Returns the average read and write latency in milliseconds of the requests completed
+    /// since `prev`. A latency is `None` if no requests of that type were completed.
+    pub fn latency_since(&self, prev: &IoLatencyCounters) -> (Option<f32>, Option<f32>) {
+        fn average(time_spent: u64, completed: u64) -> Option<f32> {
+            if completed == 0 {
+                None
+            } else {
+                Some(time_spent as f32 / completed as f32)
+            }
+        }
+
+        (
+            average(
+                self.time_spent_reading_ms
+                    .saturating_sub(prev.time_spent_reading_ms),
+                self.reads_completed.saturating_sub(prev.reads_completed),
+            ),
+            average(
+                self.time_spent_writing_ms
+                    .saturating_sub(prev.time_spent_writing_ms),
+                self.writes_completed.saturating_sub(prev.writes_completed),
+            ),
+        )
+    }
 }
 
 pub type IoHarvest = std::collections::HashMap<String, Option<IoData>>;
+
+/// Returns the used and total number of inodes of the filesystem mounted at `mount_point`.
+#[cfg(unix)]
+pub fn get_inode_usage(mount_point: &std::path::Path) -> (Option<u64>, Option<u64>) {
+    use std::{ffi::CString, mem::MaybeUninit, os::unix::ffi::OsStrExt};
+
+    let Ok(path) = CString::new(mount_point.as_os_str().as_bytes()) else {
+        return (None, None);
+    };
+    let mut vfs = MaybeUninit::<libc::statvfs>::uninit();
+
+    // SAFETY: The path is a valid nul-terminated string, and `statvfs` fills in the struct if it
+    // succeeds, which is the only case where it is read.
+    if unsafe { libc::statvfs(path.as_ptr(), vfs.as_mut_ptr()) } != 0 {
+        return (None, None);
+    }
+    let vfs = unsafe { vfs.assume_init() };
+
+    // `fsfilcnt_t` is only 32 bits on some platforms.
+    #[allow(clippy::useless_conversion)]
+    inode_usage(u64::from(vfs.f_files), u64::from(vfs.f_ffree))
+}
+
+/// Returns the used and total number of inodes from the total and free counts. Filesystems that
+/// allocate inodes dynamically, like btrfs, report a total of zero, in which case both are `None`.
+pub fn inode_usage(total: u64, free: u64) -> (Option<u64>, Option<u64>) {
+    if total == 0 {
+        (None, None)
+    } else {
+        (Some(total.saturating_sub(free)), Some(total))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{inode_usage, IoLatencyCounters, MountType};
+
+    #[test]
+    fn test_mount_type() {
+        assert_eq!(MountType::new("nfs4", false), MountType::Nfs);
+        assert_eq!(MountType::new("cifs", false), MountType::Cifs);
+        assert_eq!(MountType::new("tmpfs", false), MountType::Tmpfs);
+        assert_eq!(MountType::new("ext4", true), MountType::Local);
+        assert_eq!(MountType::new("fuse.sshfs", false), MountType::Other);
+        assert!(MountType::Nfs.is_remote());
+        assert!(!MountType::Local.is_remote());
+    }
+
+    #[test]
+    fn test_latency_since() {
+        let prev = IoLatencyCounters {
+            reads_completed: 100,
+            time_spent_reading_ms: 400,
+            writes_completed: 50,
+            time_spent_writing_ms: 1000,
+        };
+        let curr = IoLatencyCounters {
+            reads_completed: 110,
+            time_spent_reading_ms: 450,
+            writes_completed: 50,
+            time_spent_writing_ms: 1000,
+        };
+
+        assert_eq!(curr.latency_since(&prev), (Some(5.0), None));
+    }
+
+    #[test]
+    fn test_inode_usage() {
+        assert_eq!(inode_usage(1000, 250), (Some(750), Some(1000)));
+        assert_eq!(inode_usage(0, 0), (None, None));
+    }
+}
@@ -4,7 +4,7 @@ use std::io;
 
 use serde::Deserialize;
 
-use super::{DiskHarvest, IoHarvest};
+use super::{inode_usage, DiskHarvest, IoHarvest, MountType};
 use crate::app::Filter;
 use crate::data_harvester::deserialize_xo;
 
@@ -21,6 +21,10 @@ struct FileSystem {
     total_blocks: u64,
     used_blocks: u64,
     available_blocks: u64,
+    #[serde(default)]
+    used_inodes: u64,
+    #[serde(default)]
+    free_inodes: u64,
     mounted_on: String,
 }
 
@@ -41,6 +45,7 @@ pub async fn get_io_usage(actually_get: bool) -> crate::utils::error::Result<Opt
 
 pub async fn get_disk_usage(
     actually_get: bool, disk_filter: &Option<Filter>, mount_filter: &Option<Filter>,
+    _prev_disks: Option<&[DiskHarvest]>,
 ) -> crate::utils::error::Result<Option<Vec<DiskHarvest>>> {
     if !actually_get {
         return Ok(None);
@@ -66,12 +71,19 @@ pub async fn get_disk_usage(
                 if matches_allow_list(filter_check_map.as_slice())
                     || !matches_ignore_list(filter_check_map.as_slice())
                 {
+                    let (used_inodes, total_inodes) =
+                        inode_usage(disk.used_inodes + disk.free_inodes, disk.free_inodes);
                     Some(DiskHarvest {
                         free_space: Some(disk.available_blocks * 1024),
                         used_space: Some(disk.used_blocks * 1024),
                         total_space: Some(disk.total_blocks * 1024),
                         mount_point: disk.mounted_on,
                         name: disk.name,
+                        mount_type: MountType::Local,
+                        stale: false,
+                        io_scheduler: None,
+                        used_inodes,
+                        total_inodes,
                     })
                 } else {
                     None
@@ -100,7 +112,7 @@ fn matches_ignore_list(filter_check_map: &[(&Option<Filter>, &String)]) -> bool
 fn get_disk_info() -> io::Result<StorageSystemInformation> {
     // TODO: Ideally we don't have to shell out to a new program.
     let output = std::process::Command::new("df")
-        .args(["--libxo", "json", "-k", "-t", "ufs,msdosfs,zfs"])
+        .args(["--libxo", "json", "-k", "-i", "-t", "ufs,msdosfs,zfs"])
         .output()?;
     deserialize_xo("storage-system-information", &output.stdout)
 }
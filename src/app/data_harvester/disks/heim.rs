@@ -1,8 +1,32 @@
 //! Disk stats through heim.
 //! Supports macOS, Linux, and Windows.
 
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::{mpsc, Mutex},
+    time::Duration,
+};
+
+use once_cell::sync::Lazy;
+
 use crate::app::Filter;
-use crate::data_harvester::disks::{DiskHarvest, IoData, IoHarvest};
+use crate::data_harvester::disks::{DiskHarvest, IoData, IoHarvest, MountType};
+
+/// Windows has no concept of inodes.
+#[cfg(target_os = "windows")]
+fn get_inode_usage(_mount_point: &Path) -> (Option<u64>, Option<u64>) {
+    (None, None)
+}
+
+#[cfg(unix)]
+use crate::data_harvester::disks::get_inode_usage;
+
+/// How long to wait on the usage of a remote mount before falling back to stale data.
+const REMOTE_USAGE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Remote mount points that still have a usage call in flight from a previous harvest.
+static PENDING_REMOTE_USAGE: Lazy<Mutex<HashSet<PathBuf>>> = Lazy::new(Default::default);
 
 cfg_if::cfg_if! {
     if #[cfg(target_os = "linux")] {
@@ -24,6 +48,7 @@ pub async fn get_io_usage(actually_get: bool) -> crate::utils::error::Result<Opt
     let mut io_hash: std::collections::HashMap<String, Option<IoData>> =
         std::collections::HashMap::new();
 
+    let latency_counters = get_io_latency_counters();
     let counter_stream = heim::disk::io_counters().await?;
     futures::pin_mut!(counter_stream);
 
@@ -36,6 +61,7 @@ pub async fn get_io_usage(actually_get: bool) -> crate::utils::error::Result<Opt
                 Some(IoData {
                     read_bytes: io.read_bytes().get::<heim::units::information::byte>(),
                     write_bytes: io.write_bytes().get::<heim::units::information::byte>(),
+                    latency_counters: latency_counters.get(mount_point).copied(),
                 }),
             );
         }
@@ -44,8 +70,39 @@ pub async fn get_io_usage(actually_get: bool) -> crate::utils::error::Result<Opt
     Ok(Some(io_hash))
 }
 
+/// Gets the usage and inode usage of a remote mount on a separate thread, as `statvfs` can block
+/// indefinitely if the remote host becomes unavailable. Returns `None` if the calls did not finish
+/// in time.
+fn get_remote_usage(
+    mount_point: &Path,
+) -> Option<(heim::Result<heim::disk::Usage>, (Option<u64>, Option<u64>))> {
+    let mount_point = mount_point.to_path_buf();
+
+    // Don't pile up threads on a mount that is still hanging from a previous harvest.
+    if !PENDING_REMOTE_USAGE
+        .lock()
+        .ok()?
+        .insert(mount_point.clone())
+    {
+        return None;
+    }
+
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let usage = futures::executor::block_on(heim::disk::usage(&mount_point));
+        let inodes = get_inode_usage(&mount_point);
+        if let Ok(mut pending) = PENDING_REMOTE_USAGE.lock() {
+            pending.remove(&mount_point);
+        }
+        let _ = sender.send((usage, inodes));
+    });
+
+    receiver.recv_timeout(REMOTE_USAGE_TIMEOUT).ok()
+}
+
 pub async fn get_disk_usage(
     actually_get: bool, disk_filter: &Option<Filter>, mount_filter: &Option<Filter>,
+    prev_disks: Option<&[DiskHarvest]>,
 ) -> crate::utils::error::Result<Option<Vec<DiskHarvest>>> {
     if !actually_get {
         return Ok(None);
@@ -53,12 +110,31 @@ pub async fn get_disk_usage(
 
     use futures::StreamExt;
 
+    let physical_mount_points: HashSet<PathBuf> = heim::disk::partitions_physical()
+        .await?
+        .filter_map(|part| async move {
+            part.ok()
+                .map(|partition| partition.mount_point().to_path_buf())
+        })
+        .collect()
+        .await;
+
     let mut vec_disks: Vec<DiskHarvest> = Vec::new();
-    let partitions_stream = heim::disk::partitions_physical().await?;
+    let partitions_stream = heim::disk::partitions().await?;
     futures::pin_mut!(partitions_stream);
 
     while let Some(part) = partitions_stream.next().await {
         if let Ok(partition) = part {
+            let mount_type = MountType::new(
+                partition.file_system().as_str(),
+                physical_mount_points.contains(partition.mount_point()),
+            );
+
+            // Only physical disks and remote mounts are shown.
+            if mount_type != MountType::Local && !mount_type.is_remote() {
+                continue;
+            }
+
             let name = get_device_name(&partition);
 
             let mount_point = (partition
@@ -111,16 +187,56 @@ pub async fn get_disk_usage(
             };
 
             if to_keep {
+                let (usage, (used_inodes, total_inodes)) = if mount_type.is_remote() {
+                    match get_remote_usage(partition.mount_point()) {
+                        Some(usage) => usage,
+                        None => {
+                            // Timed out, so reuse whatever we last knew about this mount.
+                            let prev_disk = prev_disks.and_then(|prev_disks| {
+                                prev_disks
+                                    .iter()
+                                    .find(|disk| disk.mount_point == mount_point)
+                            });
+
+                            vec_disks.push(DiskHarvest {
+                                stale: true,
+                                ..prev_disk.cloned().unwrap_or_else(|| DiskHarvest {
+                                    mount_point,
+                                    name,
+                                    mount_type,
+                                    ..Default::default()
+                                })
+                            });
+                            continue;
+                        }
+                    }
+                } else {
+                    (
+                        heim::disk::usage(partition.mount_point()).await,
+                        get_inode_usage(partition.mount_point()),
+                    )
+                };
+
                 // The usage line can fail in some cases (for example, if you use Void Linux + LUKS,
                 // see https://github.com/ClementTsang/bottom/issues/419 for details).  As such, check
                 // it like this instead.
-                if let Ok(usage) = heim::disk::usage(partition.mount_point()).await {
+                #[cfg(target_os = "linux")]
+                let io_scheduler = get_io_scheduler(&name);
+                #[cfg(not(target_os = "linux"))]
+                let io_scheduler = None;
+
+                if let Ok(usage) = usage {
                     vec_disks.push(DiskHarvest {
                         free_space: Some(usage.free().get::<heim::units::information::byte>()),
                         used_space: Some(usage.used().get::<heim::units::information::byte>()),
                         total_space: Some(usage.total().get::<heim::units::information::byte>()),
                         mount_point,
                         name,
+                        mount_type,
+                        stale: false,
+                        io_scheduler,
+                        used_inodes,
+                        total_inodes,
                     });
                 } else {
                     vec_disks.push(DiskHarvest {
@@ -129,6 +245,11 @@ pub async fn get_disk_usage(
                         total_space: None,
                         mount_point,
                         name,
+                        mount_type,
+                        stale: false,
+                        io_scheduler,
+                        used_inodes,
+                        total_inodes,
                     });
                 }
             }
@@ -1,7 +1,11 @@
 //! Linux-specific things for Heim disk data collection.
 
+use std::collections::HashMap;
+
 use heim::disk::Partition;
 
+use crate::data_harvester::disks::IoLatencyCounters;
+
 pub fn get_device_name(partition: &Partition) -> String {
     if let Some(device) = partition.device() {
         // See if this disk is actually mounted elsewhere on Linux...
@@ -32,3 +36,92 @@ pub fn get_device_name(partition: &Partition) -> String {
         "Name Unavailable".to_string()
     }
 }
+
+/// Returns the active I/O scheduler of a device, such as `/dev/sda`. Partitions and virtual
+/// devices (e.g. LVM volumes or loop devices) don't have their own scheduler, so return `None`.
+pub fn get_io_scheduler(device_name: &str) -> Option<String> {
+    let device = device_name.rsplit('/').next()?;
+    let block_path = std::path::Path::new("/sys/block").join(device);
+
+    // Only physical devices have a backing `device` entry.
+    if !block_path.join("device").exists() {
+        return None;
+    }
+
+    std::fs::read_to_string(block_path.join("queue/scheduler"))
+        .ok()
+        .and_then(|contents| parse_scheduler(&contents))
+}
+
+/// Parses the active scheduler out of the contents of a `queue/scheduler` file, which marks it in
+/// brackets, e.g. `none [mq-deadline] kyber`.
+fn parse_scheduler(contents: &str) -> Option<String> {
+    contents
+        .split_whitespace()
+        .find_map(|scheduler| scheduler.strip_prefix('[')?.strip_suffix(']'))
+        .map(ToString::to_string)
+}
+
+/// Returns the latency counters of each device in `/proc/diskstats`, keyed by device name.
+pub fn get_io_latency_counters() -> HashMap<String, IoLatencyCounters> {
+    std::fs::read_to_string("/proc/diskstats")
+        .map(|contents| parse_diskstats(&contents))
+        .unwrap_or_default()
+}
+
+fn parse_diskstats(contents: &str) -> HashMap<String, IoLatencyCounters> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let field = |index: usize| fields.get(index)?.parse::<u64>().ok();
+
+            Some((
+                fields.get(2)?.to_string(),
+                IoLatencyCounters {
+                    reads_completed: field(3)?,
+                    time_spent_reading_ms: field(6)?,
+                    writes_completed: field(7)?,
+                    time_spent_writing_ms: field(10)?,
+                },
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_scheduler() {
+        assert_eq!(
+            parse_scheduler("none [mq-deadline] kyber bfq\n"),
+            Some("mq-deadline".to_string())
+        );
+        assert_eq!(
+            parse_scheduler("[none] mq-deadline"),
+            Some("none".to_string())
+        );
+        assert_eq!(parse_scheduler("none"), None);
+    }
+
+    #[test]
+    fn test_parse_diskstats() {
+        let contents = "   8       0 sda 1000 20 30000 400 500 60 7000 800 0 900 1200 0 0 0 0
+ 259       0 nvme0n1 12 0 96 3
+";
+        let counters = parse_diskstats(contents);
+
+        assert_eq!(counters.len(), 1);
+        assert_eq!(
+            counters.get("sda"),
+            Some(&IoLatencyCounters {
+                reads_completed: 1000,
+                time_spent_reading_ms: 400,
+                writes_completed: 500,
+                time_spent_writing_ms: 800,
+            })
+        );
+    }
+}
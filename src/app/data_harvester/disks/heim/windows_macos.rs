@@ -1,7 +1,11 @@
 //! macOS and Windows-specific things for Heim disk data collection.
 
+use std::collections::HashMap;
+
 use heim::disk::Partition;
 
+use crate::data_harvester::disks::IoLatencyCounters;
+
 pub fn get_device_name(partition: &Partition) -> String {
     if let Some(device) = partition.device() {
         device
@@ -12,3 +16,8 @@ pub fn get_device_name(partition: &Partition) -> String {
         "Name Unavailable".to_string()
     }
 }
+
+/// Latency counters are not supported on macOS and Windows, so this always returns an empty map.
+pub fn get_io_latency_counters() -> HashMap<String, IoLatencyCounters> {
+    HashMap::new()
+}
@@ -3,6 +3,23 @@
 pub mod sysinfo;
 pub use self::sysinfo::*;
 
+pub mod connections;
+#[cfg(feature = "geoip")]
+pub mod geoip;
+pub mod hostnames;
+pub mod tcp;
+
+#[cfg(target_os = "linux")]
+pub mod nl80211;
+
+#[cfg(target_os = "linux")]
+pub mod sock_diag;
+
+#[cfg(target_os = "linux")]
+pub mod wireless;
+
+use std::{collections::HashMap, net::SocketAddr};
+
 #[derive(Default, Clone, Debug)]
 /// All units in bits.
 pub struct NetworkHarvest {
@@ -10,6 +27,88 @@ pub struct NetworkHarvest {
     pub tx: u64,
     pub total_rx: u64,
     pub total_tx: u64,
+    pub total_tcp_connections: u64,
+    /// Signal information of wireless interfaces, keyed by interface name.
+    pub wifi_info: HashMap<String, WifiInfo>,
+    /// Bits received and transmitted by each interface, keyed by interface name.
+    pub interface_totals: HashMap<String, (u64, u64)>,
+    /// Error, drop and collision counters of each interface, keyed by interface name.
+    pub interface_errors: HashMap<String, InterfaceErrors>,
+    /// System-wide TCP/IP statistics, if the platform exposes them.
+    pub tcp_stats: Option<TcpStats>,
+}
+
+/// System-wide TCP/IP counters, like those shown by `netstat -s`. Everything but the current
+/// connection and socket counts is a running total since boot.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TcpStats {
+    pub active_opens: u64,
+    pub passive_opens: u64,
+    pub attempt_fails: u64,
+    pub estab_resets: u64,
+    pub curr_estab: u64,
+    pub in_segs: u64,
+    pub out_segs: u64,
+    pub retrans_segs: u64,
+    pub in_errs: u64,
+    pub out_rsts: u64,
+    /// The number of sockets of any kind in use, if known.
+    pub sockets_used: Option<u64>,
+    pub tcp_sockets: Option<u64>,
+    pub udp_sockets: Option<u64>,
+    pub time_wait_sockets: Option<u64>,
+}
+
+/// Running totals of the packets an interface failed to send or receive since it came up.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InterfaceErrors {
+    pub rx_errors: u64,
+    pub tx_errors: u64,
+    /// Packets that were dropped, and collisions, which not every platform reports.
+    pub rx_dropped: Option<u64>,
+    pub tx_dropped: Option<u64>,
+    pub collisions: Option<u64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WifiInfo {
+    /// The signal level, in dBm.
+    pub rssi_dbm: i32,
+    /// The link quality, as reported by the driver.
+    pub link_quality: f64,
+    /// The name of the network the interface is connected to.
+    pub ssid: Option<String>,
+    pub frequency_mhz: Option<u32>,
+    pub tx_bitrate_kbps: Option<u32>,
+    pub rx_bitrate_kbps: Option<u32>,
+}
+
+/// TCP statistics of a single socket, keyed by its local and remote addresses.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NetlinkSocketInfo {
+    pub local: SocketAddr,
+    pub remote: SocketAddr,
+    pub rtt_ms: f32,
+    pub retransmits: u32,
+    /// Bytes sent and acknowledged, if the kernel reports it.
+    pub bytes_sent: Option<u64>,
+    pub bytes_received: Option<u64>,
+    /// How fast the socket is sending and receiving, in bytes per second, once it has been seen
+    /// twice.
+    pub tx_per_sec: Option<u64>,
+    pub rx_per_sec: Option<u64>,
+}
+
+/// A single socket, and the process that owns it if it could be found.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConnectionHarvest {
+    pub protocol: connections::Protocol,
+    pub local: SocketAddr,
+    pub remote: SocketAddr,
+    /// The state, like `LISTEN`, which is empty for UDP sockets that aren't connected.
+    pub state: String,
+    pub pid: Option<crate::Pid>,
+    pub name: Option<String>,
 }
 
 impl NetworkHarvest {
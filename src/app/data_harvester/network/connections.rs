@@ -0,0 +1,468 @@
+//! Lists the TCP and UDP sockets on the system, along with the processes that own them.
+
+use std::net::SocketAddr;
+
+use super::ConnectionHarvest;
+use crate::utils::error;
+
+/// Returns every IPv4 and IPv6 TCP and UDP socket from `/proc/net`, matching each to its owner
+/// through the socket inodes in `/proc/<pid>/fd`. Sockets owned by processes we can't look into
+/// are still listed, just without an owner.
+#[cfg(target_os = "linux")]
+pub fn get_connections() -> error::Result<Vec<ConnectionHarvest>> {
+    use std::collections::HashMap;
+
+    use procfs::process::{all_processes, FDTarget};
+
+    let mut owners: HashMap<u64, (crate::Pid, String)> = HashMap::new();
+    for process in all_processes()?.flatten() {
+        let (Ok(fds), Ok(stat)) = (process.fd(), process.stat()) else {
+            continue;
+        };
+        for fd in fds.flatten() {
+            if let FDTarget::Socket(inode) = fd.target {
+                owners.insert(inode, (process.pid, stat.comm.clone()));
+            }
+        }
+    }
+
+    read_socket_tables(|inode| owners.get(&inode).cloned())
+}
+
+/// Returns the TCP and UDP sockets of a single process, found through the socket inodes in its
+/// `/proc/<pid>/fd`. Unlike [`get_connections`], a socket shared with other processes, like one
+/// inherited from a parent, is still listed.
+#[cfg(target_os = "linux")]
+pub fn get_process_connections(pid: crate::Pid) -> error::Result<Vec<ConnectionHarvest>> {
+    use std::collections::HashSet;
+
+    use procfs::process::{FDTarget, Process};
+
+    let process = Process::new(pid)?;
+    let name = process.stat()?.comm;
+    let inodes = process
+        .fd()?
+        .flatten()
+        .filter_map(|fd| match fd.target {
+            FDTarget::Socket(inode) => Some(inode),
+            _ => None,
+        })
+        .collect::<HashSet<_>>();
+
+    let mut connections =
+        read_socket_tables(|inode| inodes.contains(&inode).then(|| (pid, name.clone())))?;
+    connections.retain(|connection| connection.pid.is_some());
+
+    Ok(connections)
+}
+
+/// Returns the sockets of a single process, picked out of every socket on the system.
+#[cfg(not(target_os = "linux"))]
+pub fn get_process_connections(pid: crate::Pid) -> error::Result<Vec<ConnectionHarvest>> {
+    let mut connections = get_connections()?;
+    connections.retain(|connection| connection.pid == Some(pid));
+
+    Ok(connections)
+}
+
+/// Reads the TCP and UDP tables in `/proc/net`, using `owner` to find the process that owns
+/// each socket from its inode.
+#[cfg(target_os = "linux")]
+fn read_socket_tables(
+    owner: impl Fn(u64) -> Option<(crate::Pid, String)>,
+) -> error::Result<Vec<ConnectionHarvest>> {
+    let mut connections = Vec::new();
+    let mut push = |protocol, local, remote, state: String, inode| {
+        let owner = owner(inode);
+        connections.push(ConnectionHarvest {
+            protocol,
+            local,
+            remote,
+            state,
+            pid: owner.as_ref().map(|(pid, _)| *pid),
+            name: owner.map(|(_, name)| name),
+        });
+    };
+
+    // IPv6 may be disabled, in which case its tables just don't exist.
+    for entry in procfs::net::tcp()?
+        .into_iter()
+        .chain(procfs::net::tcp6().unwrap_or_default())
+    {
+        push(
+            Protocol::Tcp,
+            entry.local_address,
+            entry.remote_address,
+            tcp_state_name(entry.state as u8).to_string(),
+            entry.inode,
+        );
+    }
+    for entry in procfs::net::udp()?
+        .into_iter()
+        .chain(procfs::net::udp6().unwrap_or_default())
+    {
+        // Like `netstat`, only connected UDP sockets get a state.
+        let state = match entry.state {
+            procfs::net::UdpState::Established => "ESTABLISHED",
+            procfs::net::UdpState::Close => "",
+        };
+        push(
+            Protocol::Udp,
+            entry.local_address,
+            entry.remote_address,
+            state.to_string(),
+            entry.inode,
+        );
+    }
+
+    Ok(connections)
+}
+
+/// Returns every TCP and UDP socket from `GetExtendedTcpTable` and `GetExtendedUdpTable`, which
+/// also report the PID of each socket's owner.
+#[cfg(target_os = "windows")]
+pub fn get_connections() -> error::Result<Vec<ConnectionHarvest>> {
+    use std::net::{Ipv4Addr, Ipv6Addr, SocketAddrV6};
+
+    use sysinfo::{PidExt, ProcessExt, System, SystemExt};
+    use windows::Win32::NetworkManagement::IpHelper::{
+        GetExtendedTcpTable, GetExtendedUdpTable, MIB_TCP6ROW_OWNER_PID, MIB_TCPROW_OWNER_PID,
+        MIB_UDP6ROW_OWNER_PID, MIB_UDPROW_OWNER_PID, TCP_TABLE_OWNER_PID_ALL, UDP_TABLE_OWNER_PID,
+    };
+
+    const AF_INET: u32 = 2;
+    const AF_INET6: u32 = 23;
+
+    let tcp_table = |family| {
+        read_ip_table(|buffer, size| {
+            // SAFETY: `read_ip_table` passes a buffer at least `size` bytes long.
+            unsafe { GetExtendedTcpTable(buffer, size, false, family, TCP_TABLE_OWNER_PID_ALL, 0) }
+        })
+    };
+    let udp_table = |family| {
+        read_ip_table(|buffer, size| {
+            // SAFETY: `read_ip_table` passes a buffer at least `size` bytes long.
+            unsafe { GetExtendedUdpTable(buffer, size, false, family, UDP_TABLE_OWNER_PID, 0) }
+        })
+    };
+    let (tcp_buffer, udp_buffer) = (tcp_table(AF_INET)?, udp_table(AF_INET)?);
+    // IPv6 may be disabled, in which case there just aren't any IPv6 sockets.
+    let (tcp6_buffer, udp6_buffer) = (
+        tcp_table(AF_INET6).unwrap_or_default(),
+        udp_table(AF_INET6).unwrap_or_default(),
+    );
+
+    let mut sys = System::new();
+    let mut connection = |protocol, local, remote, state: &str, pid: u32| {
+        let sys_pid = sysinfo::Pid::from_u32(pid);
+        let name = sys
+            .refresh_process(sys_pid)
+            .then(|| {
+                sys.process(sys_pid)
+                    .map(|process| process.name().to_string())
+            })
+            .flatten();
+
+        ConnectionHarvest {
+            protocol,
+            local,
+            remote,
+            state: state.to_string(),
+            pid: Some(pid as crate::Pid),
+            name,
+        }
+    };
+    // Addresses and ports are in network byte order, with the port in the lower two bytes.
+    let address = |addr: u32, port: u32| {
+        SocketAddr::from((
+            Ipv4Addr::from(addr.to_ne_bytes()),
+            u16::from_be(port as u16),
+        ))
+    };
+    let address6 = |addr: [u8; 16], port: u32, scope_id: u32| {
+        SocketAddr::V6(SocketAddrV6::new(
+            Ipv6Addr::from(addr),
+            u16::from_be(port as u16),
+            0,
+            scope_id,
+        ))
+    };
+    let unspecified = SocketAddr::from(([0, 0, 0, 0], 0));
+    let unspecified6 = SocketAddr::from((Ipv6Addr::UNSPECIFIED, 0));
+
+    let mut connections = Vec::new();
+    // SAFETY: Each buffer was filled by the matching IP Helper call, so holds rows of its type.
+    unsafe {
+        for row in table_rows::<MIB_TCPROW_OWNER_PID>(&tcp_buffer) {
+            connections.push(connection(
+                Protocol::Tcp,
+                address(row.dwLocalAddr, row.dwLocalPort),
+                address(row.dwRemoteAddr, row.dwRemotePort),
+                mib_tcp_state_name(row.dwState),
+                row.dwOwningPid,
+            ));
+        }
+        for row in table_rows::<MIB_TCP6ROW_OWNER_PID>(&tcp6_buffer) {
+            connections.push(connection(
+                Protocol::Tcp,
+                address6(row.ucLocalAddr, row.dwLocalPort, row.dwLocalScopeId),
+                address6(row.ucRemoteAddr, row.dwRemotePort, row.dwRemoteScopeId),
+                mib_tcp_state_name(row.dwState),
+                row.dwOwningPid,
+            ));
+        }
+        for row in table_rows::<MIB_UDPROW_OWNER_PID>(&udp_buffer) {
+            connections.push(connection(
+                Protocol::Udp,
+                address(row.dwLocalAddr, row.dwLocalPort),
+                unspecified,
+                "",
+                row.dwOwningPid,
+            ));
+        }
+        for row in table_rows::<MIB_UDP6ROW_OWNER_PID>(&udp6_buffer) {
+            connections.push(connection(
+                Protocol::Udp,
+                address6(row.ucLocalAddr, row.dwLocalPort, row.dwLocalScopeId),
+                unspecified6,
+                "",
+                row.dwOwningPid,
+            ));
+        }
+    }
+
+    Ok(connections)
+}
+
+/// Returns the rows of an IP Helper table, which is a `u32` count followed by that many rows.
+///
+/// # Safety
+///
+/// The buffer must be empty, or hold a table of `Row`s.
+#[cfg(target_os = "windows")]
+unsafe fn table_rows<Row>(buffer: &[u64]) -> &[Row] {
+    if buffer.is_empty() {
+        return &[];
+    }
+
+    let count = *(buffer.as_ptr() as *const u32);
+    let offset = std::mem::size_of::<u32>().next_multiple_of(std::mem::align_of::<Row>());
+    let first = (buffer.as_ptr() as *const u8).add(offset) as *const Row;
+
+    std::slice::from_raw_parts(first, count as usize)
+}
+
+/// Reads an IP Helper table with `get_table`, which is passed the buffer to fill and its size,
+/// and returns a Win32 error code.
+#[cfg(target_os = "windows")]
+fn read_ip_table(
+    mut get_table: impl FnMut(Option<*mut std::ffi::c_void>, &mut u32) -> u32,
+) -> error::Result<Vec<u64>> {
+    use windows::Win32::Foundation::{ERROR_INSUFFICIENT_BUFFER, NO_ERROR};
+
+    // The table can grow between asking for its size and reading it, so keep trying with the
+    // newly required size until it fits. The buffer is made of `u64`s so it is aligned for it.
+    let mut size = 0;
+    let mut buffer: Vec<u64> = Vec::new();
+    loop {
+        let result = get_table(Some(buffer.as_mut_ptr().cast()), &mut size);
+
+        if result == ERROR_INSUFFICIENT_BUFFER.0 {
+            buffer.resize((size as usize).div_ceil(std::mem::size_of::<u64>()), 0);
+        } else if result == NO_ERROR.0 {
+            return Ok(buffer);
+        } else {
+            return Err(std::io::Error::from_raw_os_error(result as i32).into());
+        }
+    }
+}
+
+/// Names a `MIB_TCP_STATE` the way `netstat` does.
+#[cfg(target_os = "windows")]
+fn mib_tcp_state_name(state: u32) -> &'static str {
+    const NAMES: [&str; 12] = [
+        "CLOSED",
+        "LISTEN",
+        "SYN_SENT",
+        "SYN_RECV",
+        "ESTABLISHED",
+        "FIN_WAIT1",
+        "FIN_WAIT2",
+        "CLOSE_WAIT",
+        "CLOSING",
+        "LAST_ACK",
+        "TIME_WAIT",
+        "DELETE_TCB",
+    ];
+
+    // The states are numbered from 1.
+    state
+        .checked_sub(1)
+        .and_then(|index| NAMES.get(index as usize))
+        .copied()
+        .unwrap_or("UNKNOWN")
+}
+
+/// Returns every TCP and UDP socket by asking `lsof`, since the socket tables macOS exposes
+/// through `sysctl` are private and change layout between releases.
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+pub fn get_connections() -> error::Result<Vec<ConnectionHarvest>> {
+    let output = std::process::Command::new("lsof")
+        .args(["-n", "-P", "-i", "-F", "pcPnT"])
+        .output()?;
+
+    Ok(parse_lsof(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// The protocol of a socket.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Protocol {
+    Tcp,
+    Udp,
+}
+
+impl std::fmt::Display for Protocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Protocol::Tcp => "TCP",
+            Protocol::Udp => "UDP",
+        })
+    }
+}
+
+/// Names a TCP state the way `netstat` does, from its number in Linux's `enum tcp_state`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn tcp_state_name(state: u8) -> &'static str {
+    const NAMES: [&str; 12] = [
+        "ESTABLISHED",
+        "SYN_SENT",
+        "SYN_RECV",
+        "FIN_WAIT1",
+        "FIN_WAIT2",
+        "TIME_WAIT",
+        "CLOSE",
+        "CLOSE_WAIT",
+        "LAST_ACK",
+        "LISTEN",
+        "CLOSING",
+        "NEW_SYN_RECV",
+    ];
+
+    // The states are numbered from 1.
+    state
+        .checked_sub(1)
+        .and_then(|index| NAMES.get(usize::from(index)))
+        .copied()
+        .unwrap_or("UNKNOWN")
+}
+
+/// Parses the output of `lsof -F pcPnT`, which has one field per line, each starting with a
+/// letter saying what it is: `p` starts a process, `c` is its name, and every `f` after it
+/// starts one of its sockets, with `P` as the protocol, `n` as the addresses, and `TST=` as the
+/// TCP state.
+#[cfg_attr(any(target_os = "linux", target_os = "windows"), allow(dead_code))]
+fn parse_lsof(output: &str) -> Vec<ConnectionHarvest> {
+    fn parse_address(address: &str) -> Option<SocketAddr> {
+        // Addresses that aren't bound to anything show up as `*`.
+        let address = address.replace("*:", "0.0.0.0:");
+        address.parse().ok()
+    }
+
+    let mut connections: Vec<ConnectionHarvest> = Vec::new();
+    let mut pid = None;
+    let mut name = None;
+    let mut protocol = Protocol::Tcp;
+
+    for line in output.lines() {
+        let (kind, value) = line.split_at(line.len().min(1));
+        match kind {
+            "p" => pid = value.parse().ok(),
+            "c" => name = Some(value.to_string()),
+            "P" => {
+                protocol = if value == "UDP" {
+                    Protocol::Udp
+                } else {
+                    Protocol::Tcp
+                }
+            }
+            "n" => {
+                let (local, remote) = value.split_once("->").unwrap_or((value, "*:*"));
+                if let Some(local) = parse_address(local) {
+                    connections.push(ConnectionHarvest {
+                        protocol,
+                        local,
+                        remote: parse_address(remote)
+                            .unwrap_or_else(|| SocketAddr::from(([0, 0, 0, 0], 0))),
+                        state: String::new(),
+                        pid,
+                        name: name.clone(),
+                    });
+                }
+            }
+            "T" => {
+                if let (Some(state), Some(connection)) =
+                    (value.strip_prefix("ST="), connections.last_mut())
+                {
+                    connection.state = state.to_string();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    connections
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_lsof() {
+        let output = "\
+p512
+csshd
+f3
+PTCP
+n*:22
+TST=LISTEN
+f4
+PTCP
+n192.168.1.2:22->192.168.1.9:51000
+TST=ESTABLISHED
+p600
+cmDNSResponder
+f5
+PUDP
+n*:5353
+";
+
+        let connections = parse_lsof(output);
+        assert_eq!(connections.len(), 3);
+        assert_eq!(connections[0].local, "0.0.0.0:22".parse().unwrap());
+        assert_eq!(connections[0].state, "LISTEN");
+        assert_eq!(connections[1].remote, "192.168.1.9:51000".parse().unwrap());
+        assert_eq!(connections[1].pid, Some(512));
+        assert_eq!(connections[2].protocol, Protocol::Udp);
+        assert_eq!(connections[2].name.as_deref(), Some("mDNSResponder"));
+
+        assert_eq!(tcp_state_name(1), "ESTABLISHED");
+        assert_eq!(tcp_state_name(10), "LISTEN");
+        assert_eq!(tcp_state_name(0), "UNKNOWN");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_own_connections() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let local = listener.local_addr().unwrap();
+
+        let connections = get_connections().unwrap();
+        let own = connections
+            .iter()
+            .find(|connection| connection.local == local)
+            .unwrap();
+        assert_eq!(own.state, "LISTEN");
+        assert_eq!(own.pid, Some(std::process::id() as crate::Pid));
+    }
+}
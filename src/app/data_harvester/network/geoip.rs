@@ -0,0 +1,438 @@
+//! Looks up where remote addresses are in local MaxMind databases, like the free GeoLite2 Country
+//! and ASN ones. Only as much of the [MaxMind DB format](https://maxmind.github.io/MaxMind-DB/) as
+//! those need is read.
+
+use std::{net::IpAddr, path::Path};
+
+use crate::utils::error::{self, BottomError};
+
+/// Marks the start of the metadata, near the end of the file.
+const METADATA_MARKER: &[u8] = b"\xAB\xCD\xEFMaxMind.com";
+
+/// Separates the search tree from the data section.
+const DATA_SECTION_SEPARATOR_LEN: usize = 16;
+
+/// A value from the data section. Types we never need to read are kept as [`Value::Other`].
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    String(String),
+    Uint(u64),
+    Map(Vec<(String, Value)>),
+    Other,
+}
+
+impl Value {
+    fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Map(entries) => entries
+                .iter()
+                .find(|(entry_key, _)| entry_key == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    fn as_uint(&self) -> Option<u64> {
+        match self {
+            Value::Uint(uint) => Some(*uint),
+            _ => None,
+        }
+    }
+}
+
+/// A MaxMind database, read entirely into memory.
+pub struct MaxMindDb {
+    bytes: Vec<u8>,
+    node_count: usize,
+    record_size: usize,
+    ip_version: u64,
+    /// Where the data section starts, right after the search tree and its separator.
+    data_start: usize,
+}
+
+impl MaxMindDb {
+    pub fn open(path: &Path) -> error::Result<Self> {
+        Self::from_bytes(std::fs::read(path)?)
+    }
+
+    fn from_bytes(bytes: Vec<u8>) -> error::Result<Self> {
+        let invalid = || BottomError::GenericError("not a valid MaxMind database".to_string());
+
+        let metadata_start = bytes
+            .windows(METADATA_MARKER.len())
+            .rposition(|window| window == METADATA_MARKER)
+            .ok_or_else(invalid)?
+            + METADATA_MARKER.len();
+        let (metadata, _) = Decoder {
+            bytes: &bytes,
+            section_start: metadata_start,
+        }
+        .decode(metadata_start)
+        .ok_or_else(invalid)?;
+
+        let field = |key: &str| metadata.get(key).and_then(Value::as_uint);
+        let node_count = field("node_count").ok_or_else(invalid)? as usize;
+        let record_size = field("record_size").ok_or_else(invalid)? as usize;
+        let ip_version = field("ip_version").ok_or_else(invalid)?;
+        if ![24, 28, 32].contains(&record_size) {
+            return Err(invalid());
+        }
+
+        let data_start = node_count * record_size / 4 + DATA_SECTION_SEPARATOR_LEN;
+        if data_start > metadata_start {
+            return Err(invalid());
+        }
+
+        Ok(MaxMindDb {
+            bytes,
+            node_count,
+            record_size,
+            ip_version,
+            data_start,
+        })
+    }
+
+    /// Reads the left (`bit` is false) or right record of a node in the search tree.
+    fn record(&self, node: usize, bit: bool) -> Option<usize> {
+        let node_len = self.record_size / 4;
+        let node_bytes = self.bytes.get(node * node_len..(node + 1) * node_len)?;
+        let read = |bytes: &[u8]| {
+            bytes
+                .iter()
+                .fold(0_usize, |acc, byte| (acc << 8) | usize::from(*byte))
+        };
+
+        Some(match (self.record_size, bit) {
+            (24, false) => read(&node_bytes[..3]),
+            (24, true) => read(&node_bytes[3..]),
+            (28, false) => (usize::from(node_bytes[3] & 0xF0) << 20) | read(&node_bytes[..3]),
+            (28, true) => (usize::from(node_bytes[3] & 0x0F) << 24) | read(&node_bytes[4..]),
+            (_, false) => read(&node_bytes[..4]),
+            (_, true) => read(&node_bytes[4..]),
+        })
+    }
+
+    /// Finds the record for `ip`, if the database has one.
+    fn lookup(&self, ip: IpAddr) -> Option<Value> {
+        let bits: Vec<bool> = match (ip, self.ip_version) {
+            // IPv4 addresses live under `::/96` in databases that also have IPv6 ones.
+            (IpAddr::V4(ip), 6) => std::iter::repeat_n(false, 96)
+                .chain(bits_of(&ip.octets()))
+                .collect(),
+            (IpAddr::V4(ip), _) => bits_of(&ip.octets()).collect(),
+            (IpAddr::V6(ip), 6) => bits_of(&ip.octets()).collect(),
+            (IpAddr::V6(ip), _) => bits_of(&ip.to_ipv4_mapped()?.octets()).collect(),
+        };
+
+        let mut node = 0;
+        for bit in bits {
+            if node >= self.node_count {
+                break;
+            }
+            node = self.record(node, bit)?;
+        }
+
+        if node <= self.node_count {
+            // Either the address isn't in the database, or the tree is broken.
+            return None;
+        }
+
+        let offset = self.data_start + (node - self.node_count) - DATA_SECTION_SEPARATOR_LEN;
+        Decoder {
+            bytes: &self.bytes,
+            section_start: self.data_start,
+        }
+        .decode(offset)
+        .map(|(value, _)| value)
+    }
+
+    /// The two-letter code of the country `ip` is in, like `US`.
+    pub fn country(&self, ip: IpAddr) -> Option<String> {
+        let record = self.lookup(ip)?;
+        record
+            .get("country")
+            .or_else(|| record.get("registered_country"))?
+            .get("iso_code")?
+            .as_str()
+            .map(str::to_string)
+    }
+
+    /// The number of the autonomous system `ip` belongs to, and the organisation running it.
+    pub fn autonomous_system(&self, ip: IpAddr) -> Option<(u64, Option<String>)> {
+        let record = self.lookup(ip)?;
+        let number = record.get("autonomous_system_number")?.as_uint()?;
+        let organisation = record
+            .get("autonomous_system_organization")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+
+        Some((number, organisation))
+    }
+}
+
+/// The country and ASN databases to look remote addresses up in, either of which can be missing.
+#[derive(Default)]
+pub struct GeoIpLookup {
+    country_db: Option<MaxMindDb>,
+    asn_db: Option<MaxMindDb>,
+}
+
+impl GeoIpLookup {
+    /// Opens whichever databases are given. One that can't be read just isn't used.
+    pub fn open(country_db: Option<&Path>, asn_db: Option<&Path>) -> Self {
+        GeoIpLookup {
+            country_db: country_db.and_then(|path| MaxMindDb::open(path).ok()),
+            asn_db: asn_db.and_then(|path| MaxMindDb::open(path).ok()),
+        }
+    }
+
+    /// Describes where `ip` is, like `NZ AS64500 Example`.
+    pub fn location(&self, ip: IpAddr) -> Option<String> {
+        let country = self.country_db.as_ref().and_then(|db| db.country(ip));
+        let autonomous_system = self
+            .asn_db
+            .as_ref()
+            .and_then(|db| db.autonomous_system(ip))
+            .map(|(number, organisation)| match organisation {
+                Some(organisation) => format!("AS{number} {organisation}"),
+                None => format!("AS{number}"),
+            });
+
+        match (country, autonomous_system) {
+            (Some(country), Some(autonomous_system)) => {
+                Some(format!("{country} {autonomous_system}"))
+            }
+            (country, autonomous_system) => country.or(autonomous_system),
+        }
+    }
+}
+
+/// The bits of an address, most significant first.
+fn bits_of(octets: &[u8]) -> impl Iterator<Item = bool> + '_ {
+    octets
+        .iter()
+        .flat_map(|octet| (0..8).rev().map(move |bit| octet & (1 << bit) != 0))
+}
+
+/// Decodes values from the data section, or from the metadata, whose pointers are relative to
+/// `section_start`.
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    section_start: usize,
+}
+
+impl Decoder<'_> {
+    fn read(&self, offset: usize, len: usize) -> Option<u64> {
+        Some(
+            self.bytes
+                .get(offset..offset + len)?
+                .iter()
+                .fold(0, |acc, byte| (acc << 8) | u64::from(*byte)),
+        )
+    }
+
+    /// Decodes the value at `offset`, returning it along with the offset right after it.
+    fn decode(&self, offset: usize) -> Option<(Value, usize)> {
+        let control = *self.bytes.get(offset)?;
+        let mut offset = offset + 1;
+
+        let mut kind = control >> 5;
+        if kind == 1 {
+            // Pointers store their size differently to every other type.
+            let size = usize::from((control >> 3) & 0x3);
+            let low_bits = u64::from(control & 0x7);
+            let pointer = match size {
+                0 => (low_bits << 8) | self.read(offset, 1)?,
+                1 => ((low_bits << 16) | self.read(offset, 2)?) + 2048,
+                2 => ((low_bits << 24) | self.read(offset, 3)?) + 526_336,
+                _ => self.read(offset, 4)?,
+            };
+            let (value, _) = self.decode(self.section_start + pointer as usize)?;
+            return Some((value, offset + size + 1));
+        }
+        if kind == 0 {
+            kind = 7 + *self.bytes.get(offset)?;
+            offset += 1;
+        }
+
+        let mut size = usize::from(control & 0x1F);
+        match size {
+            29 => {
+                size = 29 + self.read(offset, 1)? as usize;
+                offset += 1;
+            }
+            30 => {
+                size = 285 + self.read(offset, 2)? as usize;
+                offset += 2;
+            }
+            31 => {
+                size = 65_821 + self.read(offset, 3)? as usize;
+                offset += 3;
+            }
+            _ => {}
+        }
+
+        match kind {
+            // UTF-8 string.
+            2 => {
+                let string = self.bytes.get(offset..offset + size)?;
+                Some((
+                    Value::String(String::from_utf8_lossy(string).into_owned()),
+                    offset + size,
+                ))
+            }
+            // Unsigned integers.
+            5 | 6 | 9 | 10 if size <= 8 => {
+                Some((Value::Uint(self.read(offset, size)?), offset + size))
+            }
+            // Map.
+            7 => {
+                let mut entries = Vec::with_capacity(size);
+                for _ in 0..size {
+                    let (key, after_key) = self.decode(offset)?;
+                    let (value, after_value) = self.decode(after_key)?;
+                    entries.push((key.as_str()?.to_string(), value));
+                    offset = after_value;
+                }
+                Some((Value::Map(entries), offset))
+            }
+            // Arrays are only skipped over, as nothing we look at is in one.
+            11 => {
+                for _ in 0..size {
+                    (_, offset) = self.decode(offset)?;
+                }
+                Some((Value::Other, offset))
+            }
+            // Booleans keep their value in the size, and take up no room.
+            14 => Some((Value::Other, offset)),
+            // Doubles and floats.
+            3 => Some((Value::Other, offset + 8)),
+            15 => Some((Value::Other, offset + 4)),
+            // Bytes, signed and 128-bit integers.
+            4 | 8 | 10 => Some((Value::Other, offset + size)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Encodes a string the way the data section does.
+    fn string(value: &str) -> Vec<u8> {
+        // Lengths of 29 and over go in an extra byte.
+        let mut bytes = if value.len() < 29 {
+            vec![(2 << 5) | value.len() as u8]
+        } else {
+            vec![(2 << 5) | 29, (value.len() - 29) as u8]
+        };
+        bytes.extend(value.as_bytes());
+        bytes
+    }
+
+    fn map(entries: &[(&str, Vec<u8>)]) -> Vec<u8> {
+        let mut bytes = vec![(7 << 5) | entries.len() as u8];
+        for (key, value) in entries {
+            bytes.extend(string(key));
+            bytes.extend(value);
+        }
+        bytes
+    }
+
+    fn uint16(value: u16) -> Vec<u8> {
+        let mut bytes = vec![(5 << 5) | 2];
+        bytes.extend(value.to_be_bytes());
+        bytes
+    }
+
+    /// Builds an IPv4 database with 24-bit records, where `10.0.0.0/8` is in `NZ` and belongs to
+    /// AS 64500, and nothing else has a record.
+    fn test_db() -> MaxMindDb {
+        let node_count = 8;
+        let mut bytes = Vec::new();
+        // Nodes 0 to 7 follow the bits of 10 (00001010), and everything else is not found.
+        for (node, bit) in [0, 0, 0, 0, 1, 0, 1, 0].into_iter().enumerate() {
+            let next = if node + 1 < node_count {
+                node + 1
+            } else {
+                // Points at the start of the data section.
+                node_count + DATA_SECTION_SEPARATOR_LEN
+            };
+            let (left, right) = if bit == 0 {
+                (next, node_count)
+            } else {
+                (node_count, next)
+            };
+            bytes.extend(&(left as u32).to_be_bytes()[1..]);
+            bytes.extend(&(right as u32).to_be_bytes()[1..]);
+        }
+        bytes.extend([0; DATA_SECTION_SEPARATOR_LEN]);
+
+        bytes.extend(map(&[
+            ("country", map(&[("iso_code", string("NZ"))])),
+            ("autonomous_system_number", uint16(64500)),
+            ("autonomous_system_organization", string("Example")),
+        ]));
+
+        bytes.extend(METADATA_MARKER);
+        bytes.extend(map(&[
+            ("node_count", uint16(node_count as u16)),
+            ("record_size", uint16(24)),
+            ("ip_version", uint16(4)),
+        ]));
+
+        MaxMindDb::from_bytes(bytes).unwrap()
+    }
+
+    #[test]
+    fn test_lookup() {
+        let db = test_db();
+
+        assert_eq!(
+            db.country("10.1.2.3".parse().unwrap()),
+            Some("NZ".to_string())
+        );
+        assert_eq!(
+            db.autonomous_system("10.0.0.1".parse().unwrap()),
+            Some((64500, Some("Example".to_string())))
+        );
+        assert_eq!(db.country("11.0.0.1".parse().unwrap()), None);
+        assert_eq!(
+            db.country("::ffff:10.0.0.1".parse().unwrap()),
+            Some("NZ".to_string())
+        );
+
+        assert!(MaxMindDb::from_bytes(b"not a database".to_vec()).is_err());
+
+        let lookup = GeoIpLookup {
+            country_db: Some(test_db()),
+            asn_db: Some(test_db()),
+        };
+        assert_eq!(
+            lookup.location("10.0.0.1".parse().unwrap()),
+            Some("NZ AS64500 Example".to_string())
+        );
+    }
+
+    #[test]
+    fn test_decode_pointer() {
+        // A pointer to offset 2, where a string is stored.
+        let bytes = [(1 << 5), 2, (2 << 5) | 2, b'h', b'i'];
+        let decoder = Decoder {
+            bytes: &bytes,
+            section_start: 0,
+        };
+        assert_eq!(
+            decoder.decode(0),
+            Some((Value::String("hi".to_string()), 2))
+        );
+    }
+}
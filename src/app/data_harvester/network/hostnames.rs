@@ -0,0 +1,214 @@
+//! Reverse DNS lookups for the connections widget, done on a background thread so a slow DNS
+//! server never holds up drawing or collection.
+
+use std::{
+    collections::{HashMap, HashSet},
+    net::IpAddr,
+    sync::{
+        mpsc::{self, Sender},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// How many addresses are remembered before the least recently used ones are forgotten.
+const MAX_CACHED_HOSTNAMES: usize = 1024;
+
+struct CacheEntry {
+    /// The hostname, or [`None`] if the address doesn't have one.
+    hostname: Option<String>,
+    resolved_at: Instant,
+    last_used: Instant,
+}
+
+/// Resolved hostnames, along with the addresses still waiting on a lookup.
+#[derive(Default)]
+struct HostnameCache {
+    entries: HashMap<IpAddr, CacheEntry>,
+    pending: HashSet<IpAddr>,
+}
+
+impl HostnameCache {
+    fn insert(&mut self, ip: IpAddr, hostname: Option<String>) {
+        self.pending.remove(&ip);
+
+        if self.entries.len() >= MAX_CACHED_HOSTNAMES && !self.entries.contains_key(&ip) {
+            if let Some(least_recent) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(ip, _)| *ip)
+            {
+                self.entries.remove(&least_recent);
+            }
+        }
+
+        let now = Instant::now();
+        self.entries.insert(
+            ip,
+            CacheEntry {
+                hostname,
+                resolved_at: now,
+                last_used: now,
+            },
+        );
+    }
+}
+
+/// Resolves addresses to hostnames in the background, caching each result for a while.
+pub struct HostnameResolver {
+    cache: Arc<Mutex<HostnameCache>>,
+    request_sender: Sender<IpAddr>,
+    ttl: Duration,
+}
+
+impl HostnameResolver {
+    pub fn new(ttl: Duration) -> Self {
+        let cache = Arc::new(Mutex::new(HostnameCache::default()));
+        let (request_sender, request_receiver) = mpsc::channel::<IpAddr>();
+
+        let thread_cache = cache.clone();
+        thread::spawn(move || {
+            for ip in request_receiver {
+                let hostname = lookup_hostname(ip);
+                if let Ok(mut cache) = thread_cache.lock() {
+                    cache.insert(ip, hostname);
+                }
+            }
+        });
+
+        HostnameResolver {
+            cache,
+            request_sender,
+            ttl,
+        }
+    }
+
+    /// Returns the hostname of `ip` if it has already been looked up. Otherwise, or if the last
+    /// lookup has expired, a lookup is started in the background and this returns [`None`] until
+    /// a later call.
+    pub fn hostname(&self, ip: IpAddr) -> Option<String> {
+        let mut cache = self.cache.lock().ok()?;
+
+        let (hostname, is_expired) = match cache.entries.get_mut(&ip) {
+            Some(entry) => {
+                entry.last_used = Instant::now();
+                (
+                    entry.hostname.clone(),
+                    entry.resolved_at.elapsed() >= self.ttl,
+                )
+            }
+            None => (None, true),
+        };
+
+        // An expired hostname is still shown until its replacement comes in.
+        if is_expired && cache.pending.insert(ip) {
+            let _ = self.request_sender.send(ip);
+        }
+
+        hostname
+    }
+}
+
+/// Looks up the hostname of an address with `getnameinfo`, which goes through the same sources
+/// as the rest of the system, like `/etc/hosts`.
+#[cfg(target_family = "unix")]
+fn lookup_hostname(ip: IpAddr) -> Option<String> {
+    use std::{ffi::CStr, mem};
+
+    // SAFETY: An all-zero `sockaddr_storage` is valid, and is large enough for either address.
+    let mut storage: libc::sockaddr_storage = unsafe { mem::zeroed() };
+    let len = match ip {
+        IpAddr::V4(ipv4) => {
+            // SAFETY: `sockaddr_storage` is larger than, and aligned for, `sockaddr_in`.
+            let addr = unsafe { &mut *(&mut storage as *mut _ as *mut libc::sockaddr_in) };
+            addr.sin_family = libc::AF_INET as libc::sa_family_t;
+            addr.sin_addr.s_addr = u32::from_ne_bytes(ipv4.octets());
+            mem::size_of::<libc::sockaddr_in>()
+        }
+        IpAddr::V6(ipv6) => {
+            // SAFETY: `sockaddr_storage` is larger than, and aligned for, `sockaddr_in6`.
+            let addr = unsafe { &mut *(&mut storage as *mut _ as *mut libc::sockaddr_in6) };
+            addr.sin6_family = libc::AF_INET6 as libc::sa_family_t;
+            addr.sin6_addr.s6_addr = ipv6.octets();
+            mem::size_of::<libc::sockaddr_in6>()
+        }
+    };
+
+    let mut host = [0 as libc::c_char; libc::NI_MAXHOST as usize];
+    // SAFETY: The address is valid for `len` bytes, and the host buffer for its length.
+    // `NI_NAMEREQD` makes this fail rather than give back the address as text.
+    let result = unsafe {
+        libc::getnameinfo(
+            &storage as *const _ as *const libc::sockaddr,
+            len as libc::socklen_t,
+            host.as_mut_ptr(),
+            host.len() as libc::socklen_t,
+            std::ptr::null_mut(),
+            0,
+            libc::NI_NAMEREQD,
+        )
+    };
+
+    if result == 0 {
+        // SAFETY: On success, `getnameinfo` wrote a nul-terminated string into the buffer.
+        let hostname = unsafe { CStr::from_ptr(host.as_ptr()) };
+        Some(hostname.to_string_lossy().into_owned())
+    } else {
+        None
+    }
+}
+
+#[cfg(not(target_family = "unix"))]
+fn lookup_hostname(_ip: IpAddr) -> Option<String> {
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_cache_eviction() {
+        let mut cache = HostnameCache::default();
+        let ip = |n: usize| IpAddr::from([10, 0, (n / 256) as u8, (n % 256) as u8]);
+
+        let start = Instant::now();
+        for n in 0..MAX_CACHED_HOSTNAMES {
+            cache.insert(ip(n), None);
+            cache.entries.get_mut(&ip(n)).unwrap().last_used =
+                start + Duration::from_secs(n as u64);
+        }
+        // Using the first address makes the second one the least recently used.
+        cache.entries.get_mut(&ip(0)).unwrap().last_used = start + Duration::from_secs(5000);
+        cache.insert(ip(MAX_CACHED_HOSTNAMES), Some("new".to_string()));
+
+        assert_eq!(cache.entries.len(), MAX_CACHED_HOSTNAMES);
+        assert!(cache.entries.contains_key(&ip(0)));
+        assert!(!cache.entries.contains_key(&ip(1)));
+    }
+
+    #[cfg(target_family = "unix")]
+    #[test]
+    fn test_background_lookup() {
+        let resolver = HostnameResolver::new(Duration::from_secs(60));
+        let localhost = IpAddr::from([127, 0, 0, 1]);
+
+        // The first call only starts the lookup.
+        assert_eq!(resolver.hostname(localhost), None);
+        for _ in 0..100 {
+            if resolver
+                .cache
+                .lock()
+                .unwrap()
+                .entries
+                .contains_key(&localhost)
+            {
+                break;
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+        assert!(resolver.cache.lock().unwrap().pending.is_empty());
+    }
+}
@@ -0,0 +1,347 @@
+//! Wireless link details through the Linux `nl80211` generic netlink interface, which is what `iw`
+//! uses. Unlike `/proc/net/wireless`, this also knows the network and link speed, and still works
+//! on kernels built without wireless extensions.
+
+use std::{
+    mem,
+    os::fd::{AsRawFd, FromRawFd, OwnedFd},
+};
+
+use super::sock_diag::{align, read_u16, read_u32};
+
+/// The netlink message type of the generic netlink controller, which resolves family names.
+const GENL_ID_CTRL: u16 = 0x10;
+const CTRL_CMD_GETFAMILY: u8 = 3;
+const CTRL_ATTR_FAMILY_ID: u16 = 1;
+const CTRL_ATTR_FAMILY_NAME: u16 = 2;
+
+/// Size of a `struct genlmsghdr`, which follows the netlink header of each message.
+const GENL_HEADER_LEN: usize = 4;
+
+const NL80211_CMD_GET_INTERFACE: u8 = 5;
+const NL80211_CMD_GET_STATION: u8 = 17;
+
+const NL80211_ATTR_IFINDEX: u16 = 3;
+const NL80211_ATTR_IFNAME: u16 = 4;
+const NL80211_ATTR_STA_INFO: u16 = 21;
+const NL80211_ATTR_WIPHY_FREQ: u16 = 38;
+const NL80211_ATTR_SSID: u16 = 52;
+
+const NL80211_STA_INFO_SIGNAL: u16 = 7;
+const NL80211_STA_INFO_TX_BITRATE: u16 = 8;
+const NL80211_STA_INFO_RX_BITRATE: u16 = 14;
+
+/// Bitrates in units of 100 kbit/s. The 16-bit one overflows past 6.5 Gbit/s.
+const NL80211_RATE_INFO_BITRATE: u16 = 1;
+const NL80211_RATE_INFO_BITRATE32: u16 = 5;
+
+/// Attribute types can have flags in their top bits, like `NLA_F_NESTED`.
+const NLA_TYPE_MASK: u16 = 0x3fff;
+
+/// The link of a single wireless interface.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LinkInfo {
+    pub name: String,
+    pub ssid: Option<String>,
+    pub frequency_mhz: Option<u32>,
+    /// The signal level of the access point, in dBm.
+    pub signal_dbm: Option<i32>,
+    pub tx_bitrate_kbps: Option<u32>,
+    pub rx_bitrate_kbps: Option<u32>,
+}
+
+/// Returns the link of every wireless interface. Interfaces that aren't connected have no signal
+/// or bitrates. If `nl80211` is unavailable, this just returns nothing.
+pub fn get_link_info() -> Vec<LinkInfo> {
+    let Some(socket) = open_socket() else {
+        return Vec::new();
+    };
+    let Some(family) = resolve_family(&socket, "nl80211") else {
+        return Vec::new();
+    };
+
+    let interfaces = request(
+        &socket,
+        family,
+        NL80211_CMD_GET_INTERFACE,
+        libc::NLM_F_DUMP,
+        &[],
+    );
+
+    interfaces
+        .iter()
+        .filter_map(|payload| parse_interface(payload))
+        .map(|(index, mut link)| {
+            let stations = request(
+                &socket,
+                family,
+                NL80211_CMD_GET_STATION,
+                libc::NLM_F_DUMP,
+                &attribute(NL80211_ATTR_IFINDEX, &index.to_ne_bytes()),
+            );
+            // A client interface only has the access point it's connected to as a station.
+            if let Some(station) = stations.first() {
+                parse_station(station, &mut link);
+            }
+            link
+        })
+        .collect()
+}
+
+fn open_socket() -> Option<OwnedFd> {
+    // SAFETY: `socket` has no memory safety requirements; the result is checked before use.
+    let fd = unsafe {
+        libc::socket(
+            libc::AF_NETLINK,
+            libc::SOCK_DGRAM | libc::SOCK_CLOEXEC,
+            libc::NETLINK_GENERIC,
+        )
+    };
+    if fd < 0 {
+        return None;
+    }
+    // SAFETY: `fd` is a freshly opened socket that nothing else owns.
+    Some(unsafe { OwnedFd::from_raw_fd(fd) })
+}
+
+/// Returns the message type of a generic netlink family, which is assigned when it's registered.
+fn resolve_family(socket: &OwnedFd, name: &str) -> Option<u16> {
+    let mut name = name.as_bytes().to_vec();
+    name.push(0);
+
+    let responses = request(
+        socket,
+        GENL_ID_CTRL,
+        CTRL_CMD_GETFAMILY,
+        0,
+        &attribute(CTRL_ATTR_FAMILY_NAME, &name),
+    );
+    let payload = responses.first()?;
+    let (_, id) = parse_attributes(payload)
+        .into_iter()
+        .find(|(kind, _)| *kind == CTRL_ATTR_FAMILY_ID)?;
+    read_u16(id, 0)
+}
+
+/// Encodes a single netlink attribute, padded to 4 bytes.
+fn attribute(kind: u16, payload: &[u8]) -> Vec<u8> {
+    let len = 4 + payload.len();
+    let mut bytes = Vec::with_capacity(align(len));
+    bytes.extend_from_slice(&(len as u16).to_ne_bytes());
+    bytes.extend_from_slice(&kind.to_ne_bytes());
+    bytes.extend_from_slice(payload);
+    bytes.resize(align(len), 0);
+    bytes
+}
+
+/// Splits the attributes in `bytes` into their types and payloads.
+fn parse_attributes(mut bytes: &[u8]) -> Vec<(u16, &[u8])> {
+    let mut attributes = Vec::new();
+    while let (Some(len), Some(kind)) = (read_u16(bytes, 0), read_u16(bytes, 2)) {
+        let len = usize::from(len);
+        if len < 4 || len > bytes.len() {
+            break;
+        }
+        attributes.push((kind & NLA_TYPE_MASK, &bytes[4..len]));
+        bytes = bytes.get(align(len)..).unwrap_or_default();
+    }
+    attributes
+}
+
+/// Sends a generic netlink request, and returns the payloads after the generic netlink header of
+/// each response.
+fn request(
+    socket: &OwnedFd, family: u16, command: u8, flags: libc::c_int, attributes: &[u8],
+) -> Vec<Vec<u8>> {
+    const HEADER_LEN: usize = mem::size_of::<libc::nlmsghdr>();
+
+    let len = HEADER_LEN + GENL_HEADER_LEN + attributes.len();
+    let mut message = Vec::with_capacity(len);
+    message.extend_from_slice(&(len as u32).to_ne_bytes());
+    message.extend_from_slice(&family.to_ne_bytes());
+    message.extend_from_slice(&((libc::NLM_F_REQUEST | flags) as u16).to_ne_bytes());
+    message.extend_from_slice(&[0; 8]); // The sequence number and port ID.
+    message.extend_from_slice(&[command, 1, 0, 0]); // The command and version.
+    message.extend_from_slice(attributes);
+
+    // SAFETY: An all-zero `sockaddr_nl` is valid, and addresses the kernel.
+    let mut kernel: libc::sockaddr_nl = unsafe { mem::zeroed() };
+    kernel.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+
+    // SAFETY: The message and address pointers are valid for the given sizes.
+    let sent = unsafe {
+        libc::sendto(
+            socket.as_raw_fd(),
+            message.as_ptr() as *const libc::c_void,
+            message.len(),
+            0,
+            &kernel as *const libc::sockaddr_nl as *const libc::sockaddr,
+            mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+        )
+    };
+    if sent < 0 {
+        return Vec::new();
+    }
+
+    let mut payloads = Vec::new();
+    let mut buffer = vec![0_u8; 32 * 1024];
+    loop {
+        // SAFETY: The buffer pointer is valid for writes of its length.
+        let received = unsafe {
+            libc::recv(
+                socket.as_raw_fd(),
+                buffer.as_mut_ptr() as *mut libc::c_void,
+                buffer.len(),
+                0,
+            )
+        };
+        if received <= 0 {
+            break;
+        }
+
+        let is_done = parse_messages(&buffer[..received as usize], family, &mut payloads);
+        // Anything but a dump is answered in a single batch.
+        if is_done || flags & libc::NLM_F_DUMP == 0 {
+            break;
+        }
+    }
+
+    payloads
+}
+
+/// Collects the payloads of a batch of netlink messages, returning whether the response is
+/// finished.
+fn parse_messages(mut bytes: &[u8], family: u16, payloads: &mut Vec<Vec<u8>>) -> bool {
+    const HEADER_LEN: usize = mem::size_of::<libc::nlmsghdr>();
+
+    while let (Some(len), Some(kind)) = (read_u32(bytes, 0), read_u16(bytes, 4)) {
+        let len = len as usize;
+        if len < HEADER_LEN || len > bytes.len() {
+            return true;
+        }
+
+        match i32::from(kind) {
+            libc::NLMSG_DONE | libc::NLMSG_ERROR => return true,
+            // The controller answers with its own type, rather than the family asked about.
+            _ if kind == family || kind == GENL_ID_CTRL => {
+                if let Some(payload) = bytes.get(HEADER_LEN + GENL_HEADER_LEN..len) {
+                    payloads.push(payload.to_vec());
+                }
+            }
+            _ => {}
+        }
+
+        bytes = bytes.get(align(len)..).unwrap_or_default();
+    }
+
+    false
+}
+
+/// Parses the response to `NL80211_CMD_GET_INTERFACE` into the interface's index and link.
+fn parse_interface(payload: &[u8]) -> Option<(u32, LinkInfo)> {
+    let mut index = None;
+    let mut link = LinkInfo::default();
+    for (kind, value) in parse_attributes(payload) {
+        match kind {
+            NL80211_ATTR_IFINDEX => index = read_u32(value, 0),
+            NL80211_ATTR_IFNAME => {
+                link.name = String::from_utf8_lossy(value)
+                    .trim_end_matches('\0')
+                    .to_string();
+            }
+            NL80211_ATTR_WIPHY_FREQ => link.frequency_mhz = read_u32(value, 0),
+            NL80211_ATTR_SSID => link.ssid = Some(String::from_utf8_lossy(value).to_string()),
+            _ => {}
+        }
+    }
+
+    Some((index?, link))
+}
+
+/// Parses the response to `NL80211_CMD_GET_STATION` into `link`.
+fn parse_station(payload: &[u8], link: &mut LinkInfo) {
+    let Some((_, info)) = parse_attributes(payload)
+        .into_iter()
+        .find(|(kind, _)| *kind == NL80211_ATTR_STA_INFO)
+    else {
+        return;
+    };
+
+    for (kind, value) in parse_attributes(info) {
+        match kind {
+            NL80211_STA_INFO_SIGNAL => {
+                link.signal_dbm = value.first().map(|signal| i32::from(*signal as i8));
+            }
+            NL80211_STA_INFO_TX_BITRATE => link.tx_bitrate_kbps = parse_bitrate(value),
+            NL80211_STA_INFO_RX_BITRATE => link.rx_bitrate_kbps = parse_bitrate(value),
+            _ => {}
+        }
+    }
+}
+
+/// Returns the bitrate of a nested `nl80211_rate_info`, in kbit/s.
+fn parse_bitrate(rate_info: &[u8]) -> Option<u32> {
+    let attributes = parse_attributes(rate_info);
+    let find = |wanted: u16| {
+        attributes
+            .iter()
+            .find(|(kind, _)| *kind == wanted)
+            .map(|(_, value)| *value)
+    };
+
+    let bitrate = match find(NL80211_RATE_INFO_BITRATE32) {
+        Some(value) => read_u32(value, 0)?,
+        None => u32::from(read_u16(find(NL80211_RATE_INFO_BITRATE)?, 0)?),
+    };
+    Some(bitrate * 100)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_interface_and_station() {
+        let interface = [
+            attribute(NL80211_ATTR_IFINDEX, &3_u32.to_ne_bytes()),
+            attribute(NL80211_ATTR_IFNAME, b"wlan0\0"),
+            attribute(NL80211_ATTR_WIPHY_FREQ, &5180_u32.to_ne_bytes()),
+            attribute(NL80211_ATTR_SSID, b"home"),
+        ]
+        .concat();
+        let (index, mut link) = parse_interface(&interface).unwrap();
+        assert_eq!(index, 3);
+
+        let tx_rate = [
+            attribute(NL80211_RATE_INFO_BITRATE, &4333_u16.to_ne_bytes()),
+            attribute(NL80211_RATE_INFO_BITRATE32, &8667_u32.to_ne_bytes()),
+        ]
+        .concat();
+        let rx_rate = attribute(NL80211_RATE_INFO_BITRATE, &4333_u16.to_ne_bytes());
+        let station_info = [
+            attribute(NL80211_STA_INFO_SIGNAL, &[-56_i8 as u8]),
+            attribute(NL80211_STA_INFO_TX_BITRATE, &tx_rate),
+            attribute(NL80211_STA_INFO_RX_BITRATE, &rx_rate),
+        ]
+        .concat();
+        let station = [
+            attribute(NL80211_ATTR_IFINDEX, &3_u32.to_ne_bytes()),
+            // Nested attributes are flagged with `NLA_F_NESTED`.
+            attribute(NL80211_ATTR_STA_INFO | 0x8000, &station_info),
+        ]
+        .concat();
+        parse_station(&station, &mut link);
+
+        assert_eq!(
+            link,
+            LinkInfo {
+                name: "wlan0".to_string(),
+                ssid: Some("home".to_string()),
+                frequency_mhz: Some(5180),
+                signal_dbm: Some(-56),
+                tx_bitrate_kbps: Some(866_700),
+                rx_bitrate_kbps: Some(433_300),
+            }
+        );
+    }
+}
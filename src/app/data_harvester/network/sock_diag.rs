@@ -0,0 +1,516 @@
+//! Per-socket TCP statistics through the Linux `sock_diag` netlink interface, which can also close
+//! sockets.
+
+use std::{
+    collections::HashMap,
+    mem,
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6},
+    os::fd::{AsRawFd, FromRawFd, OwnedFd},
+    time::Instant,
+};
+
+use super::{connections::Protocol, NetlinkSocketInfo};
+use crate::utils::error::{self, BottomError};
+
+/// The `sock_diag` request type for dumping sockets of a given family.
+const SOCK_DIAG_BY_FAMILY: u16 = 20;
+
+/// The `sock_diag` request type for closing a socket.
+const SOCK_DESTROY: u16 = 21;
+
+/// Tells the kernel to match a socket by its addresses alone.
+const INET_DIAG_NOCOOKIE: u32 = u32::MAX;
+
+/// The attribute holding a `struct tcp_info` in a `sock_diag` response.
+const INET_DIAG_INFO: u16 = 2;
+
+/// All TCP states, as a bitmask.
+const ALL_TCP_STATES: u32 = u32::MAX;
+
+/// Size of a `struct inet_diag_msg`, which precedes the attributes of each response.
+const INET_DIAG_MSG_LEN: usize = 72;
+
+/// Offsets of `tcpi_rtt` (in microseconds) and `tcpi_total_retrans` in a `struct tcp_info`.
+const TCPI_RTT_OFFSET: usize = 68;
+const TCPI_TOTAL_RETRANS_OFFSET: usize = 100;
+
+/// Offsets of `tcpi_bytes_acked` and `tcpi_bytes_received` in a `struct tcp_info`, which older
+/// kernels (before 4.1) leave out.
+const TCPI_BYTES_ACKED_OFFSET: usize = 120;
+const TCPI_BYTES_RECEIVED_OFFSET: usize = 128;
+
+/// Mirrors `struct inet_diag_sockid`.
+#[repr(C)]
+#[derive(Default)]
+struct InetDiagSockId {
+    sport: u16,
+    dport: u16,
+    src: [u32; 4],
+    dst: [u32; 4],
+    interface: u32,
+    cookie: [u32; 2],
+}
+
+/// Mirrors `struct inet_diag_req_v2`.
+#[repr(C)]
+#[derive(Default)]
+struct InetDiagReqV2 {
+    family: u8,
+    protocol: u8,
+    ext: u8,
+    pad: u8,
+    states: u32,
+    id: InetDiagSockId,
+}
+
+#[repr(C)]
+struct Request {
+    header: libc::nlmsghdr,
+    request: InetDiagReqV2,
+}
+
+/// Returns the RTT and retransmit count of every IPv4 and IPv6 TCP socket on the system. If
+/// netlink is unavailable, this just returns nothing.
+pub fn collect_netlink_diag() -> Vec<NetlinkSocketInfo> {
+    let mut sockets = dump_family(libc::AF_INET);
+    sockets.extend(dump_family(libc::AF_INET6));
+    sockets
+}
+
+/// Dumps the TCP sockets of a single address family.
+fn dump_family(family: libc::c_int) -> Vec<NetlinkSocketInfo> {
+    let request = Request {
+        header: request_header(SOCK_DIAG_BY_FAMILY, libc::NLM_F_DUMP),
+        request: InetDiagReqV2 {
+            family: family as u8,
+            protocol: libc::IPPROTO_TCP as u8,
+            ext: (1 << (INET_DIAG_INFO - 1)) as u8,
+            states: ALL_TCP_STATES,
+            ..Default::default()
+        },
+    };
+    let Some(socket) = send_request(&request) else {
+        return Vec::new();
+    };
+
+    let mut sockets = Vec::new();
+    let mut buffer = vec![0_u8; 32 * 1024];
+    loop {
+        // SAFETY: The buffer pointer is valid for writes of its length.
+        let received = unsafe {
+            libc::recv(
+                socket.as_raw_fd(),
+                buffer.as_mut_ptr() as *mut libc::c_void,
+                buffer.len(),
+                0,
+            )
+        };
+        if received <= 0 {
+            break;
+        }
+
+        if parse_messages(&buffer[..received as usize], &mut sockets) {
+            break;
+        }
+    }
+
+    sockets
+}
+
+fn request_header(kind: u16, flags: libc::c_int) -> libc::nlmsghdr {
+    libc::nlmsghdr {
+        nlmsg_len: mem::size_of::<Request>() as u32,
+        nlmsg_type: kind,
+        nlmsg_flags: (libc::NLM_F_REQUEST | flags) as u16,
+        nlmsg_seq: 0,
+        nlmsg_pid: 0,
+    }
+}
+
+/// Opens a `sock_diag` netlink socket and sends `request` to the kernel through it, returning the
+/// socket to read the response from.
+fn send_request(request: &Request) -> Option<OwnedFd> {
+    // SAFETY: `socket` has no memory safety requirements; the result is checked before use.
+    let fd = unsafe {
+        libc::socket(
+            libc::AF_NETLINK,
+            libc::SOCK_DGRAM | libc::SOCK_CLOEXEC,
+            libc::NETLINK_SOCK_DIAG,
+        )
+    };
+    if fd < 0 {
+        return None;
+    }
+    // SAFETY: `fd` is a freshly opened socket that nothing else owns.
+    let socket = unsafe { OwnedFd::from_raw_fd(fd) };
+
+    // SAFETY: An all-zero `sockaddr_nl` is valid, and addresses the kernel.
+    let mut kernel: libc::sockaddr_nl = unsafe { mem::zeroed() };
+    kernel.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+
+    // SAFETY: The request and address pointers are valid for the given sizes.
+    let sent = unsafe {
+        libc::sendto(
+            socket.as_raw_fd(),
+            request as *const Request as *const libc::c_void,
+            mem::size_of::<Request>(),
+            0,
+            &kernel as *const libc::sockaddr_nl as *const libc::sockaddr,
+            mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+        )
+    };
+
+    (sent >= 0).then_some(socket)
+}
+
+/// Builds the `inet_diag_sockid` that identifies the socket between `local` and `remote`.
+fn socket_id(local: SocketAddr, remote: SocketAddr) -> InetDiagSockId {
+    fn ip_words(address: SocketAddr) -> [u32; 4] {
+        let mut words = [0; 4];
+        match address {
+            SocketAddr::V4(address) => words[0] = u32::from_ne_bytes(address.ip().octets()),
+            SocketAddr::V6(address) => {
+                for (word, bytes) in words.iter_mut().zip(address.ip().octets().chunks_exact(4)) {
+                    *word = u32::from_ne_bytes(bytes.try_into().unwrap_or_default());
+                }
+            }
+        }
+        words
+    }
+
+    InetDiagSockId {
+        sport: local.port().to_be(),
+        dport: remote.port().to_be(),
+        src: ip_words(local),
+        dst: ip_words(remote),
+        interface: 0,
+        cookie: [INET_DIAG_NOCOOKIE; 2],
+    }
+}
+
+/// Closes the socket between `local` and `remote` without touching the process that owns it,
+/// like `ss -K` does. This needs `CAP_NET_ADMIN`, and a kernel built with
+/// `CONFIG_INET_DIAG_DESTROY`.
+pub fn destroy_socket(
+    protocol: Protocol, local: SocketAddr, remote: SocketAddr,
+) -> error::Result<()> {
+    let request = Request {
+        header: request_header(SOCK_DESTROY, libc::NLM_F_ACK),
+        request: InetDiagReqV2 {
+            family: if local.is_ipv6() {
+                libc::AF_INET6
+            } else {
+                libc::AF_INET
+            } as u8,
+            protocol: match protocol {
+                Protocol::Tcp => libc::IPPROTO_TCP,
+                Protocol::Udp => libc::IPPROTO_UDP,
+            } as u8,
+            states: ALL_TCP_STATES,
+            id: socket_id(local, remote),
+            ..Default::default()
+        },
+    };
+    let socket = send_request(&request).ok_or_else(|| {
+        BottomError::GenericError("Could not talk to the kernel through netlink.".to_string())
+    })?;
+
+    let mut buffer = [0_u8; 256];
+    // SAFETY: The buffer pointer is valid for writes of its length.
+    let received = unsafe {
+        libc::recv(
+            socket.as_raw_fd(),
+            buffer.as_mut_ptr() as *mut libc::c_void,
+            buffer.len(),
+            0,
+        )
+    };
+    let received = usize::try_from(received).map_err(|_| std::io::Error::last_os_error())?;
+
+    match ack_error(&buffer[..received]) {
+        Some(0) | None => Ok(()),
+        Some(errno) => Err(BottomError::GenericError(match errno {
+            libc::EPERM => "Closing connections needs root.".to_string(),
+            libc::EOPNOTSUPP => {
+                "This kernel was built without support for closing connections.".to_string()
+            }
+            libc::ENOENT => "The connection has already closed.".to_string(),
+            _ => std::io::Error::from_raw_os_error(errno).to_string(),
+        })),
+    }
+}
+
+/// Returns the error number of a netlink acknowledgement, which is 0 on success.
+fn ack_error(bytes: &[u8]) -> Option<i32> {
+    const HEADER_LEN: usize = mem::size_of::<libc::nlmsghdr>();
+
+    if i32::from(read_u16(bytes, 4)?) != libc::NLMSG_ERROR {
+        return None;
+    }
+    let error = read_u32(bytes, HEADER_LEN)? as i32;
+    Some(-error)
+}
+
+pub(super) fn read_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_ne_bytes(
+        bytes.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+pub(super) fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_ne_bytes(
+        bytes.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_ne_bytes(
+        bytes.get(offset..offset + 8)?.try_into().ok()?,
+    ))
+}
+
+/// Netlink messages and attributes are padded to 4 bytes.
+pub(super) fn align(len: usize) -> usize {
+    (len + 3) & !3
+}
+
+/// Parses a batch of netlink messages into `sockets`, returning whether the dump is finished.
+fn parse_messages(mut bytes: &[u8], sockets: &mut Vec<NetlinkSocketInfo>) -> bool {
+    const HEADER_LEN: usize = mem::size_of::<libc::nlmsghdr>();
+
+    while let (Some(len), Some(kind)) = (read_u32(bytes, 0), read_u16(bytes, 4)) {
+        let len = len as usize;
+        if len < HEADER_LEN || len > bytes.len() {
+            return true;
+        }
+
+        match i32::from(kind) {
+            libc::NLMSG_DONE | libc::NLMSG_ERROR => return true,
+            _ => {
+                if let Some(socket) = parse_socket(&bytes[HEADER_LEN..len]) {
+                    sockets.push(socket);
+                }
+            }
+        }
+
+        bytes = bytes.get(align(len)..).unwrap_or_default();
+    }
+
+    false
+}
+
+/// Parses a single `inet_diag_msg` and its attributes.
+fn parse_socket(message: &[u8]) -> Option<NetlinkSocketInfo> {
+    let is_ipv6 = i32::from(*message.first()?) == libc::AF_INET6;
+    let address = |port_offset: usize, addr_offset: usize| -> Option<SocketAddr> {
+        let port = u16::from_be_bytes(message.get(port_offset..port_offset + 2)?.try_into().ok()?);
+        if is_ipv6 {
+            let ip: [u8; 16] = message
+                .get(addr_offset..addr_offset + 16)?
+                .try_into()
+                .ok()?;
+            Some(SocketAddr::V6(SocketAddrV6::new(
+                Ipv6Addr::from(ip),
+                port,
+                0,
+                0,
+            )))
+        } else {
+            let ip: [u8; 4] = message.get(addr_offset..addr_offset + 4)?.try_into().ok()?;
+            Some(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::from(ip), port)))
+        }
+    };
+
+    let local = address(4, 8)?;
+    let remote = address(6, 24)?;
+
+    let mut attributes = message.get(INET_DIAG_MSG_LEN..)?;
+    while let (Some(len), Some(kind)) = (read_u16(attributes, 0), read_u16(attributes, 2)) {
+        let len = usize::from(len);
+        if len < 4 || len > attributes.len() {
+            break;
+        }
+
+        if kind == INET_DIAG_INFO {
+            let tcp_info = &attributes[4..len];
+            return Some(NetlinkSocketInfo {
+                local,
+                remote,
+                rtt_ms: read_u32(tcp_info, TCPI_RTT_OFFSET)? as f32 / 1000.0,
+                retransmits: read_u32(tcp_info, TCPI_TOTAL_RETRANS_OFFSET)?,
+                bytes_sent: read_u64(tcp_info, TCPI_BYTES_ACKED_OFFSET),
+                bytes_received: read_u64(tcp_info, TCPI_BYTES_RECEIVED_OFFSET),
+                tx_per_sec: None,
+                rx_per_sec: None,
+            });
+        }
+
+        attributes = attributes.get(align(len)..).unwrap_or_default();
+    }
+
+    None
+}
+
+/// Remembers how many bytes each socket had moved at the last update, to work out how fast each
+/// one is sending and receiving.
+#[derive(Default)]
+pub struct TransferRateTracker {
+    previous: HashMap<(SocketAddr, SocketAddr), (Option<u64>, Option<u64>)>,
+    last_update: Option<Instant>,
+}
+
+impl TransferRateTracker {
+    /// Fills in the transfer rates of `sockets` from how much they moved since the last update.
+    /// Sockets that weren't around last time are left without a rate until the next one.
+    pub fn update(&mut self, sockets: &mut [NetlinkSocketInfo], now: Instant) {
+        let elapsed = self
+            .last_update
+            .map(|last_update| now.saturating_duration_since(last_update).as_secs_f64())
+            .filter(|elapsed| *elapsed > 0.0);
+
+        let rate = |current: Option<u64>, previous: Option<u64>| {
+            let elapsed = elapsed?;
+            Some((current?.saturating_sub(previous?) as f64 / elapsed) as u64)
+        };
+
+        for socket in sockets.iter_mut() {
+            if let Some((sent, received)) = self.previous.get(&(socket.local, socket.remote)) {
+                socket.tx_per_sec = rate(socket.bytes_sent, *sent);
+                socket.rx_per_sec = rate(socket.bytes_received, *received);
+            }
+        }
+
+        self.previous = sockets
+            .iter()
+            .map(|socket| {
+                (
+                    (socket.local, socket.remote),
+                    (socket.bytes_sent, socket.bytes_received),
+                )
+            })
+            .collect();
+        self.last_update = Some(now);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::*;
+
+    /// Builds a netlink message holding an `inet_diag_msg` for 10.0.0.2:443 -> 10.0.0.1:51000,
+    /// with a `tcp_info` attribute.
+    fn diag_message(rtt_us: u32, total_retrans: u32) -> Vec<u8> {
+        let mut diag = vec![0_u8; INET_DIAG_MSG_LEN];
+        diag[0] = libc::AF_INET as u8;
+        diag[4..6].copy_from_slice(&443_u16.to_be_bytes());
+        diag[6..8].copy_from_slice(&51000_u16.to_be_bytes());
+        diag[8..12].copy_from_slice(&[10, 0, 0, 2]);
+        diag[24..28].copy_from_slice(&[10, 0, 0, 1]);
+
+        let mut tcp_info = vec![0_u8; 136];
+        tcp_info[TCPI_RTT_OFFSET..TCPI_RTT_OFFSET + 4].copy_from_slice(&rtt_us.to_ne_bytes());
+        tcp_info[TCPI_TOTAL_RETRANS_OFFSET..TCPI_TOTAL_RETRANS_OFFSET + 4]
+            .copy_from_slice(&total_retrans.to_ne_bytes());
+        tcp_info[TCPI_BYTES_ACKED_OFFSET..TCPI_BYTES_ACKED_OFFSET + 8]
+            .copy_from_slice(&4096_u64.to_ne_bytes());
+        tcp_info[TCPI_BYTES_RECEIVED_OFFSET..TCPI_BYTES_RECEIVED_OFFSET + 8]
+            .copy_from_slice(&1024_u64.to_ne_bytes());
+
+        diag.extend(((tcp_info.len() + 4) as u16).to_ne_bytes());
+        diag.extend(INET_DIAG_INFO.to_ne_bytes());
+        diag.extend(tcp_info);
+
+        let mut message = Vec::new();
+        message.extend(((diag.len() + 16) as u32).to_ne_bytes());
+        message.extend(SOCK_DIAG_BY_FAMILY.to_ne_bytes());
+        message.extend([0_u8; 10]);
+        message.extend(diag);
+        message
+    }
+
+    #[test]
+    fn test_parse_messages() {
+        let mut bytes = diag_message(12_500, 3);
+
+        let mut done = vec![0_u8; 16];
+        done[0..4].copy_from_slice(&16_u32.to_ne_bytes());
+        done[4..6].copy_from_slice(&(libc::NLMSG_DONE as u16).to_ne_bytes());
+        bytes.extend(done);
+
+        let mut sockets = Vec::new();
+        assert!(parse_messages(&bytes, &mut sockets));
+        assert_eq!(
+            sockets,
+            vec![NetlinkSocketInfo {
+                local: "10.0.0.2:443".parse().unwrap(),
+                remote: "10.0.0.1:51000".parse().unwrap(),
+                rtt_ms: 12.5,
+                retransmits: 3,
+                bytes_sent: Some(4096),
+                bytes_received: Some(1024),
+                tx_per_sec: None,
+                rx_per_sec: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_messages_needs_more() {
+        let mut sockets = Vec::new();
+        assert!(!parse_messages(&diag_message(1000, 0), &mut sockets));
+        assert_eq!(sockets.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_ipv6_socket() {
+        let mut message = diag_message(1000, 0)[16..].to_vec();
+        message[0] = libc::AF_INET6 as u8;
+        message[8..24].copy_from_slice(&"::1".parse::<Ipv6Addr>().unwrap().octets());
+        message[24..40].copy_from_slice(&"::2".parse::<Ipv6Addr>().unwrap().octets());
+
+        let socket = parse_socket(&message).unwrap();
+        assert_eq!(socket.local, "[::1]:443".parse().unwrap());
+        assert_eq!(socket.remote, "[::2]:51000".parse().unwrap());
+    }
+
+    #[test]
+    fn test_transfer_rates() {
+        let mut message = diag_message(1000, 0)[16..].to_vec();
+        let mut tracker = TransferRateTracker::default();
+        let start = Instant::now();
+
+        let mut sockets = vec![parse_socket(&message).unwrap()];
+        tracker.update(&mut sockets, start);
+        assert_eq!(sockets[0].tx_per_sec, None);
+
+        // Two seconds later, the socket has sent another 8 KB and received another 2 KB.
+        let tcp_info = INET_DIAG_MSG_LEN + 4;
+        message[tcp_info + TCPI_BYTES_ACKED_OFFSET..tcp_info + TCPI_BYTES_ACKED_OFFSET + 8]
+            .copy_from_slice(&12288_u64.to_ne_bytes());
+        message[tcp_info + TCPI_BYTES_RECEIVED_OFFSET..tcp_info + TCPI_BYTES_RECEIVED_OFFSET + 8]
+            .copy_from_slice(&3072_u64.to_ne_bytes());
+        let mut sockets = vec![parse_socket(&message).unwrap()];
+        tracker.update(&mut sockets, start + Duration::from_secs(2));
+        assert_eq!(sockets[0].tx_per_sec, Some(4096));
+        assert_eq!(sockets[0].rx_per_sec, Some(1024));
+    }
+
+    #[test]
+    fn test_socket_id() {
+        let id = socket_id(
+            "10.0.0.2:443".parse().unwrap(),
+            "[::1]:51000".parse().unwrap(),
+        );
+        assert_eq!(id.sport.to_ne_bytes(), 443_u16.to_be_bytes());
+        assert_eq!(id.dport.to_ne_bytes(), 51000_u16.to_be_bytes());
+        assert_eq!(id.src, [u32::from_ne_bytes([10, 0, 0, 2]), 0, 0, 0]);
+        assert_eq!(id.dst[3], u32::from_ne_bytes([0, 0, 0, 1]));
+
+        let mut ack = vec![0_u8; 20];
+        ack[4..6].copy_from_slice(&(libc::NLMSG_ERROR as u16).to_ne_bytes());
+        ack[16..20].copy_from_slice(&(-libc::EPERM).to_ne_bytes());
+        assert_eq!(ack_error(&ack), Some(libc::EPERM));
+    }
+}
@@ -1,10 +1,13 @@
 //! Gets network data via sysinfo.
 
-use std::time::Instant;
+use std::{collections::HashMap, time::Instant};
 
 use crate::app::Filter;
 
-use super::NetworkHarvest;
+use super::{
+    tcp::{get_tcp_stats, get_total_tcp_connections},
+    InterfaceErrors, NetworkHarvest,
+};
 
 // TODO: Eventually make it so that this thing also takes individual usage into account, so we can show per-interface!
 pub fn get_network_data(
@@ -15,6 +18,8 @@ pub fn get_network_data(
 
     let mut total_rx: u64 = 0;
     let mut total_tx: u64 = 0;
+    let mut interface_totals = HashMap::new();
+    let mut interface_errors = HashMap::new();
 
     let networks = sys.networks();
     for (name, network) in networks {
@@ -25,8 +30,19 @@ pub fn get_network_data(
         };
 
         if to_keep {
-            total_rx += network.total_received() * 8;
-            total_tx += network.total_transmitted() * 8;
+            let rx = network.total_received() * 8;
+            let tx = network.total_transmitted() * 8;
+            total_rx += rx;
+            total_tx += tx;
+            interface_totals.insert(name.clone(), (rx, tx));
+            interface_errors.insert(
+                name.clone(),
+                get_interface_errors(
+                    name,
+                    network.total_errors_on_received(),
+                    network.total_errors_on_transmitted(),
+                ),
+            );
         }
     }
 
@@ -48,5 +64,44 @@ pub fn get_network_data(
         tx,
         total_rx,
         total_tx,
+        total_tcp_connections: get_total_tcp_connections(),
+        #[cfg(target_os = "linux")]
+        wifi_info: super::wireless::get_wifi_info(filter),
+        #[cfg(not(target_os = "linux"))]
+        wifi_info: Default::default(),
+        interface_totals,
+        interface_errors,
+        tcp_stats: get_tcp_stats(),
+    }
+}
+
+/// Adds the drop and collision counters from `/sys/class/net/<name>/statistics` to the error
+/// counters sysinfo reports.
+#[cfg(target_os = "linux")]
+fn get_interface_errors(name: &str, rx_errors: u64, tx_errors: u64) -> InterfaceErrors {
+    let statistics = std::path::Path::new("/sys/class/net")
+        .join(name)
+        .join("statistics");
+    let read = |counter: &str| {
+        std::fs::read_to_string(statistics.join(counter))
+            .ok()
+            .and_then(|value| value.trim().parse().ok())
+    };
+
+    InterfaceErrors {
+        rx_errors,
+        tx_errors,
+        rx_dropped: read("rx_dropped"),
+        tx_dropped: read("tx_dropped"),
+        collisions: read("collisions"),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn get_interface_errors(_name: &str, rx_errors: u64, tx_errors: u64) -> InterfaceErrors {
+    InterfaceErrors {
+        rx_errors,
+        tx_errors,
+        ..Default::default()
     }
 }
@@ -0,0 +1,289 @@
+//! Gets the total number of TCP connections on the system, as well as system-wide TCP/IP
+//! statistics.
+
+use super::TcpStats;
+
+/// Returns the number of TCP connections currently known to the kernel, across both IPv4 and IPv6.
+#[cfg(target_os = "linux")]
+pub fn get_total_tcp_connections() -> u64 {
+    ["/proc/net/tcp", "/proc/net/tcp6"]
+        .iter()
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .map(|contents| count_proc_net_tcp_entries(&contents))
+        .sum()
+}
+
+/// Returns the number of TCP connections currently known to the kernel, using the
+/// `net.inet.tcp.pcbcount` sysctl.
+#[cfg(target_os = "macos")]
+pub fn get_total_tcp_connections() -> u64 {
+    let mut count: libc::c_uint = 0;
+    let mut size = std::mem::size_of::<libc::c_uint>();
+
+    // SAFETY: The name is a valid nul-terminated string, and the output buffer is a properly
+    // sized and aligned `c_uint`, which is what this sysctl writes.
+    let result = unsafe {
+        libc::sysctlbyname(
+            b"net.inet.tcp.pcbcount\0".as_ptr() as *const libc::c_char,
+            &mut count as *mut libc::c_uint as *mut libc::c_void,
+            &mut size,
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+
+    if result == 0 {
+        count.into()
+    } else {
+        0
+    }
+}
+
+/// Fallback for platforms where we don't know how to count TCP connections.
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn get_total_tcp_connections() -> u64 {
+    0
+}
+
+/// Returns the TCP counters from `/proc/net/snmp`, along with the socket counts from
+/// `/proc/net/sockstat` if it can be read.
+#[cfg(target_os = "linux")]
+pub fn get_tcp_stats() -> Option<TcpStats> {
+    let mut stats = parse_proc_net_snmp(&std::fs::read_to_string("/proc/net/snmp").ok()?)?;
+    if let Ok(sockstat) = std::fs::read_to_string("/proc/net/sockstat") {
+        parse_proc_net_sockstat(&sockstat, &mut stats);
+    }
+
+    Some(stats)
+}
+
+/// Returns the TCP counters reported by `netstat -s -p tcp`.
+#[cfg(any(target_os = "macos", target_os = "freebsd"))]
+pub fn get_tcp_stats() -> Option<TcpStats> {
+    let output = std::process::Command::new("netstat")
+        .args(["-s", "-p", "tcp"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let mut stats = parse_netstat_tcp(&String::from_utf8_lossy(&output.stdout))?;
+    stats.curr_estab = get_total_tcp_connections();
+    Some(stats)
+}
+
+/// Fallback for platforms where we don't know how to get TCP statistics.
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "freebsd")))]
+pub fn get_tcp_stats() -> Option<TcpStats> {
+    None
+}
+
+/// Parses the `Tcp:` header and value lines of `/proc/net/snmp`.
+#[cfg(target_os = "linux")]
+fn parse_proc_net_snmp(contents: &str) -> Option<TcpStats> {
+    let mut lines = contents.lines().filter(|line| line.starts_with("Tcp:"));
+    let (header, values) = (lines.next()?, lines.next()?);
+
+    let mut stats = TcpStats::default();
+    for (name, value) in header
+        .split_whitespace()
+        .zip(values.split_whitespace())
+        .skip(1)
+    {
+        // MaxConn is -1 when there is no limit, which we don't use anyways.
+        let Ok(value) = value.parse::<u64>() else {
+            continue;
+        };
+        let field = match name {
+            "ActiveOpens" => &mut stats.active_opens,
+            "PassiveOpens" => &mut stats.passive_opens,
+            "AttemptFails" => &mut stats.attempt_fails,
+            "EstabResets" => &mut stats.estab_resets,
+            "CurrEstab" => &mut stats.curr_estab,
+            "InSegs" => &mut stats.in_segs,
+            "OutSegs" => &mut stats.out_segs,
+            "RetransSegs" => &mut stats.retrans_segs,
+            "InErrs" => &mut stats.in_errs,
+            "OutRsts" => &mut stats.out_rsts,
+            _ => continue,
+        };
+        *field = value;
+    }
+
+    Some(stats)
+}
+
+/// Fills in the socket counts from `/proc/net/sockstat`, which has lines like
+/// `TCP: inuse 4 orphan 0 tw 0 alloc 4 mem 0`.
+#[cfg(target_os = "linux")]
+fn parse_proc_net_sockstat(contents: &str, stats: &mut TcpStats) {
+    for line in contents.lines() {
+        let Some((protocol, counts)) = line.split_once(':') else {
+            continue;
+        };
+        let counts = counts.split_whitespace().collect::<Vec<_>>();
+        let count = |wanted: &str| {
+            counts
+                .chunks_exact(2)
+                .find(|pair| pair[0] == wanted)
+                .and_then(|pair| pair[1].parse::<u64>().ok())
+        };
+
+        match protocol {
+            "sockets" => stats.sockets_used = count("used"),
+            "TCP" => {
+                stats.tcp_sockets = count("inuse");
+                stats.time_wait_sockets = count("tw");
+            }
+            "UDP" => stats.udp_sockets = count("inuse"),
+            _ => {}
+        }
+    }
+}
+
+/// Parses the output of `netstat -s -p tcp` on BSD-likes, which has lines like
+/// `12345 packets sent` or `42 data packets (1234 bytes) retransmitted`.
+#[cfg(any(target_os = "macos", target_os = "freebsd", test))]
+fn parse_netstat_tcp(output: &str) -> Option<TcpStats> {
+    let mut stats = TcpStats::default();
+    let mut found_any = false;
+
+    for line in output.lines() {
+        let line = line.trim();
+        let Some((value, description)) = line.split_once(' ') else {
+            continue;
+        };
+        let Ok(value) = value.parse::<u64>() else {
+            continue;
+        };
+
+        // The number of drops is the closest there is to Linux's count of reset connections.
+        if let Some(drops) = description
+            .strip_prefix("connections closed (including ")
+            .and_then(|rest| rest.split(' ').next()?.parse::<u64>().ok())
+        {
+            if stats.estab_resets == 0 {
+                stats.estab_resets = drops;
+            }
+            found_any = true;
+            continue;
+        }
+
+        // Only the first of each is kept, as some descriptions are repeated in nested sections.
+        let field = if description.starts_with("packets sent") {
+            &mut stats.out_segs
+        } else if description.starts_with("packets received") {
+            &mut stats.in_segs
+        } else if description.starts_with("data packet") && description.ends_with("retransmitted") {
+            &mut stats.retrans_segs
+        } else if description.starts_with("discarded for bad checksum") {
+            &mut stats.in_errs
+        } else if description.starts_with("connection request") {
+            &mut stats.active_opens
+        } else if description.starts_with("connection accept") {
+            &mut stats.passive_opens
+        } else if description.starts_with("bad connection attempt") {
+            &mut stats.attempt_fails
+        } else {
+            continue;
+        };
+
+        if *field == 0 {
+            *field = value;
+        }
+        found_any = true;
+    }
+
+    found_any.then_some(stats)
+}
+
+/// Counts the socket entries in the contents of `/proc/net/tcp` or `/proc/net/tcp6`, skipping
+/// the header line.
+#[cfg(target_os = "linux")]
+fn count_proc_net_tcp_entries(contents: &str) -> u64 {
+    contents
+        .lines()
+        .skip(1)
+        .filter(|line| !line.trim().is_empty())
+        .count() as u64
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_count_proc_net_tcp_entries() {
+        use super::count_proc_net_tcp_entries;
+
+        let contents = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 00000000:1F90 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 12345 1 0000000000000000 100 0 0 10 0
+   1: 0100007F:0277 0100007F:A3C2 01 00000000:00000000 00:00000000 00000000     0        0 23456 1 0000000000000000 20 4 30 10 -1
+";
+
+        assert_eq!(count_proc_net_tcp_entries(contents), 2);
+        assert_eq!(count_proc_net_tcp_entries(""), 0);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_parse_proc_net_snmp() {
+        use super::{parse_proc_net_snmp, parse_proc_net_sockstat};
+
+        let snmp = "Ip: Forwarding DefaultTTL
+Ip: 1 64
+Tcp: RtoAlgorithm RtoMin RtoMax MaxConn ActiveOpens PassiveOpens AttemptFails EstabResets CurrEstab InSegs OutSegs RetransSegs InErrs OutRsts InCsumErrors
+Tcp: 1 200 120000 -1 290 235 9 82 2 41273 41827 12 1 37 0
+Udp: InDatagrams NoPorts
+Udp: 10 0
+";
+        let mut stats = parse_proc_net_snmp(snmp).unwrap();
+        assert_eq!(stats.active_opens, 290);
+        assert_eq!(stats.passive_opens, 235);
+        assert_eq!(stats.attempt_fails, 9);
+        assert_eq!(stats.estab_resets, 82);
+        assert_eq!(stats.curr_estab, 2);
+        assert_eq!(stats.out_segs, 41827);
+        assert_eq!(stats.retrans_segs, 12);
+        assert_eq!(stats.in_errs, 1);
+        assert_eq!(stats.out_rsts, 37);
+        assert!(parse_proc_net_snmp("Ip: Forwarding\nIp: 1\n").is_none());
+
+        let sockstat = "sockets: used 18
+TCP: inuse 4 orphan 0 tw 3 alloc 4 mem 0
+UDP: inuse 2 mem 0
+";
+        parse_proc_net_sockstat(sockstat, &mut stats);
+        assert_eq!(stats.sockets_used, Some(18));
+        assert_eq!(stats.tcp_sockets, Some(4));
+        assert_eq!(stats.time_wait_sockets, Some(3));
+        assert_eq!(stats.udp_sockets, Some(2));
+    }
+
+    #[test]
+    fn test_parse_netstat_tcp() {
+        use super::parse_netstat_tcp;
+
+        let output = "tcp:
+	41827 packets sent
+		12 data packets (3456 bytes) retransmitted
+	41273 packets received
+		1 discarded for bad checksums
+	290 connection requests
+	235 connection accepts
+	9 bad connection attempts
+	300 connections established (including accepts)
+	310 connections closed (including 82 drops)
+";
+        let stats = parse_netstat_tcp(output).unwrap();
+        assert_eq!(stats.out_segs, 41827);
+        assert_eq!(stats.retrans_segs, 12);
+        assert_eq!(stats.in_segs, 41273);
+        assert_eq!(stats.in_errs, 1);
+        assert_eq!(stats.active_opens, 290);
+        assert_eq!(stats.passive_opens, 235);
+        assert_eq!(stats.attempt_fails, 9);
+        assert_eq!(stats.estab_resets, 82);
+        assert!(parse_netstat_tcp("tcp:\n").is_none());
+    }
+}
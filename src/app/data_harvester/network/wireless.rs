@@ -0,0 +1,144 @@
+//! Wireless interface statistics from `/proc/net/wireless` and `nl80211`.
+
+use std::collections::HashMap;
+
+use crate::app::Filter;
+
+use super::{nl80211, WifiInfo};
+
+/// Returns the signal strength, link quality and link details of each connected wireless
+/// interface, keyed by interface name.
+pub fn get_wifi_info(filter: &Option<Filter>) -> HashMap<String, WifiInfo> {
+    let mut wifi_info = std::fs::read_to_string("/proc/net/wireless")
+        .map(|contents| parse_wireless(&contents))
+        .unwrap_or_default();
+    add_link_info(&mut wifi_info, nl80211::get_link_info());
+
+    if let Some(filter) = filter {
+        wifi_info.retain(|name, _| filter.keep_entry(name));
+    }
+
+    wifi_info
+}
+
+/// Adds what `nl80211` knows about each link to `wifi_info`. Kernels without wireless extensions
+/// have no `/proc/net/wireless`, in which case the link quality is worked out from the signal
+/// level the same way those do, on a scale of 0 to 70.
+fn add_link_info(wifi_info: &mut HashMap<String, WifiInfo>, links: Vec<nl80211::LinkInfo>) {
+    for link in links {
+        let info = match (wifi_info.get_mut(&link.name), link.signal_dbm) {
+            (Some(info), signal_dbm) => {
+                if let Some(signal_dbm) = signal_dbm {
+                    info.rssi_dbm = signal_dbm;
+                }
+                info
+            }
+            (None, Some(signal_dbm)) => wifi_info.entry(link.name).or_insert(WifiInfo {
+                rssi_dbm: signal_dbm,
+                link_quality: f64::from((signal_dbm + 110).clamp(0, 70)),
+                ..WifiInfo::default()
+            }),
+            // Not connected to anything.
+            (None, None) => continue,
+        };
+
+        info.ssid = link.ssid;
+        info.frequency_mhz = link.frequency_mhz;
+        info.tx_bitrate_kbps = link.tx_bitrate_kbps;
+        info.rx_bitrate_kbps = link.rx_bitrate_kbps;
+    }
+}
+
+/// Parses the contents of `/proc/net/wireless`, which starts with two header lines followed by
+/// one line per interface, e.g. ` wlan0: 0000   70.  -40.  -256  0  0  0  0  0  0`.
+fn parse_wireless(contents: &str) -> HashMap<String, WifiInfo> {
+    contents
+        .lines()
+        .skip(2)
+        .filter_map(|line| {
+            let (name, stats) = line.split_once(':')?;
+            let fields: Vec<&str> = stats.split_whitespace().collect();
+
+            // Values that were updated since the last read are suffixed with a '.'.
+            let field = |index: usize| fields.get(index)?.trim_end_matches('.').parse::<f64>().ok();
+
+            Some((
+                name.trim().to_string(),
+                WifiInfo {
+                    rssi_dbm: field(2)? as i32,
+                    link_quality: field(1)?,
+                    ..WifiInfo::default()
+                },
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_wireless() {
+        let contents = "\
+Inter-| sta-|   Quality        |   Discarded packets               | Missed | WE
+ face | tus | link level noise |  nwid  crypt   frag  retry   misc | beacon | 22
+ wlan0: 0000   54.  -56.  -256        0      0      0      0     12        0
+  wlp3s0: 0000   20   -90   -256        0      0      0      0      0        0
+";
+
+        let wifi_info = parse_wireless(contents);
+        assert_eq!(wifi_info.len(), 2);
+        assert_eq!(
+            wifi_info["wlan0"],
+            WifiInfo {
+                rssi_dbm: -56,
+                link_quality: 54.0,
+                ..WifiInfo::default()
+            }
+        );
+        assert_eq!(wifi_info["wlp3s0"].rssi_dbm, -90);
+    }
+
+    #[test]
+    fn test_add_link_info() {
+        let mut wifi_info = HashMap::new();
+        wifi_info.insert(
+            "wlan0".to_string(),
+            WifiInfo {
+                rssi_dbm: -60,
+                link_quality: 50.0,
+                ..WifiInfo::default()
+            },
+        );
+
+        add_link_info(
+            &mut wifi_info,
+            vec![
+                nl80211::LinkInfo {
+                    name: "wlan0".to_string(),
+                    ssid: Some("home".to_string()),
+                    signal_dbm: Some(-58),
+                    ..Default::default()
+                },
+                nl80211::LinkInfo {
+                    name: "wlan1".to_string(),
+                    signal_dbm: Some(-50),
+                    frequency_mhz: Some(2412),
+                    ..Default::default()
+                },
+                nl80211::LinkInfo {
+                    name: "wlan2".to_string(),
+                    ..Default::default()
+                },
+            ],
+        );
+
+        assert_eq!(wifi_info.len(), 2);
+        assert_eq!(wifi_info["wlan0"].rssi_dbm, -58);
+        assert_eq!(wifi_info["wlan0"].link_quality, 50.0);
+        assert_eq!(wifi_info["wlan0"].ssid.as_deref(), Some("home"));
+        assert_eq!(wifi_info["wlan1"].link_quality, 60.0);
+        assert_eq!(wifi_info["wlan1"].frequency_mhz, Some(2412));
+    }
+}
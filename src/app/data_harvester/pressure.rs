@@ -0,0 +1,130 @@
+//! Pressure stall information (PSI), read from `/proc/pressure` on Linux.
+//!
+//! The kernel reports how much of the time some, or all, non-idle tasks were stalled waiting on
+//! the CPU, memory, or I/O. This tends to show a server struggling well before its utilization
+//! does.
+
+/// The share of time tasks were stalled on a single resource, averaged over the last 10 seconds.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Pressure {
+    /// The percentage of time at least one task was stalled.
+    pub some: f64,
+    /// The percentage of time all non-idle tasks were stalled at once. Kernels before 5.13 don't
+    /// report this for the CPU.
+    pub full: Option<f64>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PressureHarvest {
+    pub cpu: Option<Pressure>,
+    pub memory: Option<Pressure>,
+    pub io: Option<Pressure>,
+}
+
+impl PressureHarvest {
+    pub fn get(&self, resource: PressureResource) -> Option<Pressure> {
+        match resource {
+            PressureResource::Cpu => self.cpu,
+            PressureResource::Memory => self.memory,
+            PressureResource::Io => self.io,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PressureResource {
+    Cpu,
+    Memory,
+    Io,
+}
+
+impl PressureResource {
+    pub const ALL: [PressureResource; 3] = [
+        PressureResource::Cpu,
+        PressureResource::Memory,
+        PressureResource::Io,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PressureResource::Cpu => "CPU",
+            PressureResource::Memory => "Mem",
+            PressureResource::Io => "I/O",
+        }
+    }
+}
+
+/// Returns the current pressure, or [`None`] if the kernel doesn't report it.
+#[cfg(target_os = "linux")]
+pub fn get_pressure() -> Option<PressureHarvest> {
+    let read = |resource| {
+        std::fs::read_to_string(format!("/proc/pressure/{resource}"))
+            .ok()
+            .and_then(|contents| parse_pressure(&contents))
+    };
+
+    let harvest = PressureHarvest {
+        cpu: read("cpu"),
+        memory: read("memory"),
+        io: read("io"),
+    };
+    (harvest != PressureHarvest::default()).then_some(harvest)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn get_pressure() -> Option<PressureHarvest> {
+    None
+}
+
+/// Parses a `/proc/pressure` file, which looks like:
+///
+/// ```text
+/// some avg10=1.53 avg60=0.87 avg300=0.31 total=4728913
+/// full avg10=0.00 avg60=0.00 avg300=0.00 total=0
+/// ```
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_pressure(contents: &str) -> Option<Pressure> {
+    let mut some = None;
+    let mut full = None;
+    for line in contents.lines() {
+        let mut fields = line.split_whitespace();
+        let kind = fields.next();
+        let avg10 = fields
+            .find_map(|field| field.strip_prefix("avg10="))
+            .and_then(|value| value.parse().ok());
+        match kind {
+            Some("some") => some = avg10,
+            Some("full") => full = avg10,
+            _ => {}
+        }
+    }
+
+    Some(Pressure { some: some?, full })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_pressure() {
+        assert_eq!(
+            parse_pressure(
+                "some avg10=1.53 avg60=0.87 avg300=0.31 total=4728913\n\
+                 full avg10=0.25 avg60=0.00 avg300=0.00 total=0\n"
+            ),
+            Some(Pressure {
+                some: 1.53,
+                full: Some(0.25),
+            })
+        );
+        assert_eq!(
+            parse_pressure("some avg10=12.00 avg60=0.87 avg300=0.31 total=4728913\n"),
+            Some(Pressure {
+                some: 12.0,
+                full: None,
+            })
+        );
+        assert_eq!(parse_pressure(""), None);
+    }
+}
@@ -7,6 +7,10 @@ cfg_if::cfg_if! {
     if #[cfg(target_os = "linux")] {
         pub mod linux;
         pub use self::linux::*;
+        pub mod io_priority;
+        pub use self::io_priority::*;
+        pub mod cgroup;
+        pub use self::cgroup::*;
     } else if #[cfg(target_os = "macos")] {
         pub mod macos;
         mod macos_freebsd;
@@ -28,8 +32,35 @@ cfg_if::cfg_if! {
     }
 }
 
+#[cfg(feature = "gpu")]
+pub mod gpu;
+
 use crate::Pid;
 
+/// Turns the bytes moved over `elapsed_secs` into a rate per second, which is 0 if no time has
+/// passed.
+pub(crate) fn bytes_per_sec(bytes: u64, elapsed_secs: f64) -> u64 {
+    if elapsed_secs > 0.0 {
+        (bytes as f64 / elapsed_secs) as u64
+    } else {
+        0
+    }
+}
+
+/// Returns the name of the user running bottom, as it's shown for their processes.
+pub fn current_user() -> Option<String> {
+    #[cfg(target_family = "unix")]
+    {
+        // SAFETY: getuid always succeeds and takes no arguments.
+        let uid = unsafe { libc::getuid() };
+        UserTable::default().get_uid_to_username_mapping(uid).ok()
+    }
+    #[cfg(not(target_family = "unix"))]
+    {
+        std::env::var("USERNAME").ok()
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct ProcessHarvest {
     /// The pid of the process.
@@ -74,6 +105,31 @@ pub struct ProcessHarvest {
 
     /// This is the process' user.
     pub user: std::borrow::Cow<'static, str>,
+
+    /// The nice value of the process, from -20 to 19, if it could be read. This is only used on
+    /// Unix platforms.
+    #[cfg(target_family = "unix")]
+    pub nice: Option<i32>,
+
+    /// GPU usage as a percentage, summed over every GPU the process runs on.
+    #[cfg(feature = "gpu")]
+    pub gpu_usage_percent: f64,
+
+    /// GPU memory usage as bytes.
+    #[cfg(feature = "gpu")]
+    pub gpu_mem_bytes: u64,
+
+    /// The I/O scheduling class of the process, if it could be read.
+    #[cfg(target_os = "linux")]
+    pub io_class: Option<IOClass>,
+
+    /// The level within the I/O scheduling class of the process, from 0 to 7.
+    #[cfg(target_os = "linux")]
+    pub io_priority: Option<u8>,
+
+    /// The container or systemd unit the process belongs to, resolved from its cgroup.
+    #[cfg(target_os = "linux")]
+    pub container: Option<String>,
     // TODO: Additional fields
     // pub rss_kb: u64,
     // pub virt_kb: u64,
@@ -88,5 +144,22 @@ impl ProcessHarvest {
         self.write_bytes_per_sec += rhs.write_bytes_per_sec;
         self.total_read_bytes += rhs.total_read_bytes;
         self.total_write_bytes += rhs.total_write_bytes;
+        #[cfg(feature = "gpu")]
+        {
+            self.gpu_usage_percent += rhs.gpu_usage_percent;
+            self.gpu_mem_bytes += rhs.gpu_mem_bytes;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_bytes_per_sec() {
+        assert_eq!(bytes_per_sec(1000, 0.5), 2000);
+        assert_eq!(bytes_per_sec(1000, 2.0), 500);
+        assert_eq!(bytes_per_sec(1000, 0.0), 0);
     }
 }
@@ -0,0 +1,182 @@
+//! Which container or systemd unit a process belongs to, from its cgroup path in
+//! `/proc/<pid>/cgroup`.
+
+use crate::Pid;
+
+/// The number of characters a container ID is shortened to, like `docker ps` does.
+const SHORT_CONTAINER_ID_LEN: usize = 12;
+
+/// Cgroup scope prefixes used by container runtimes, and the name shown for each.
+const CONTAINER_SCOPE_PREFIXES: [(&str, &str); 4] = [
+    ("docker-", "docker"),
+    ("libpod-", "podman"),
+    ("cri-containerd-", "containerd"),
+    ("crio-", "cri-o"),
+];
+
+/// Returns the container or systemd unit of a process, or [`None`] if it sits in the root cgroup
+/// or its cgroups couldn't be read.
+pub fn get_cgroup_unit(pid: Pid) -> Option<String> {
+    let contents = std::fs::read_to_string(format!("/proc/{pid}/cgroup")).ok()?;
+    parse_cgroup(&contents)
+}
+
+/// Picks the most telling cgroup path out of the contents of `/proc/<pid>/cgroup` and resolves it
+/// into a container or unit name.
+///
+/// Each line is `hierarchy:controllers:path`. The unified (v2) hierarchy is preferred, then the
+/// systemd one, then any other controller, skipping the root cgroup, since on hybrid setups a
+/// process can be at the root of some hierarchies only.
+fn parse_cgroup(contents: &str) -> Option<String> {
+    let mut paths = contents
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, ':');
+            let (_hierarchy, controllers, path) = (fields.next()?, fields.next()?, fields.next()?);
+            let rank = match controllers {
+                "" => 0,
+                "name=systemd" => 1,
+                _ => 2,
+            };
+
+            Some((rank, path))
+        })
+        .filter(|(_, path)| !path.trim_matches('/').is_empty())
+        .collect::<Vec<_>>();
+    paths.sort_by_key(|(rank, _)| *rank);
+
+    paths.first().map(|(_, path)| unit_from_path(path))
+}
+
+/// Resolves a cgroup path into the container it belongs to if there is one, like
+/// `docker:0123456789ab`, and otherwise the innermost systemd service or scope, like
+/// `nginx.service`. Paths set up by other tools fall back to their last part.
+fn unit_from_path(path: &str) -> String {
+    let parts = path
+        .split('/')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>();
+
+    for (index, part) in parts.iter().enumerate().rev() {
+        // With systemd, container runtimes put each container in a scope of its own, like
+        // `docker-<id>.scope`.
+        let scope = part.strip_suffix(".scope").unwrap_or(part);
+        for (prefix, runtime) in CONTAINER_SCOPE_PREFIXES {
+            if let Some(id) = scope.strip_prefix(prefix) {
+                // The monitor process of a container is in a scope of its own too.
+                if !id.starts_with("conmon-") {
+                    return container_name(runtime, id);
+                }
+            }
+        }
+
+        if let Some(name) = scope.strip_prefix("lxc.payload.") {
+            return container_name("lxc", name);
+        }
+
+        // Without systemd, containers are put under a directory named after the runtime, like
+        // `/docker/<id>`, or `/kubepods/<qos>/pod<uid>/<id>` under Kubernetes.
+        if index > 0 && is_container_id(part) {
+            let runtime = if parts.contains(&"kubepods") {
+                "k8s"
+            } else {
+                parts[index - 1]
+            };
+            return container_name(runtime, part);
+        }
+    }
+
+    parts
+        .iter()
+        .rev()
+        .find(|part| part.ends_with(".service") || part.ends_with(".scope"))
+        .or_else(|| parts.last())
+        .map(|part| part.to_string())
+        .unwrap_or_default()
+}
+
+fn container_name(runtime: &str, id: &str) -> String {
+    let id = if is_container_id(id) {
+        &id[..SHORT_CONTAINER_ID_LEN]
+    } else {
+        id
+    };
+
+    format!("{runtime}:{id}")
+}
+
+/// Whether a cgroup path part is a full container ID, which is 64 hexadecimal characters.
+fn is_container_id(part: &str) -> bool {
+    part.len() == 64 && part.bytes().all(|byte| byte.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const ID: &str = "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+
+    #[test]
+    fn test_container_units() {
+        assert_eq!(
+            parse_cgroup(&format!("0::/system.slice/docker-{ID}.scope\n")).as_deref(),
+            Some("docker:0123456789ab")
+        );
+        assert_eq!(
+            parse_cgroup(&format!(
+                "0::/kubepods.slice/kubepods-burstable.slice/kubepods-burstable-pod1234.slice/cri-containerd-{ID}.scope\n"
+            ))
+            .as_deref(),
+            Some("containerd:0123456789ab")
+        );
+        assert_eq!(
+            parse_cgroup(&format!("0::/machine.slice/libpod-{ID}.scope/container\n")).as_deref(),
+            Some("podman:0123456789ab")
+        );
+        assert_eq!(
+            parse_cgroup("0::/lxc.payload.web/system.slice/nginx.service\n").as_deref(),
+            Some("lxc:web")
+        );
+
+        // Cgroup v1, where the container directory is under one named after the runtime.
+        assert_eq!(
+            parse_cgroup(&format!(
+                "4:memory:/docker/{ID}\n1:name=systemd:/docker/{ID}\n"
+            ))
+            .as_deref(),
+            Some("docker:0123456789ab")
+        );
+        assert_eq!(
+            parse_cgroup(&format!("3:cpu,cpuacct:/kubepods/burstable/pod1234/{ID}\n")).as_deref(),
+            Some("k8s:0123456789ab")
+        );
+    }
+
+    #[test]
+    fn test_systemd_units() {
+        assert_eq!(
+            parse_cgroup("0::/system.slice/nginx.service\n").as_deref(),
+            Some("nginx.service")
+        );
+        assert_eq!(
+            parse_cgroup("0::/user.slice/user-1000.slice/user@1000.service/app.slice/app-firefox-1234.scope\n")
+                .as_deref(),
+            Some("app-firefox-1234.scope")
+        );
+        assert_eq!(
+            parse_cgroup("0::/init.scope\n").as_deref(),
+            Some("init.scope")
+        );
+
+        // Hybrid setups can leave the unified hierarchy at the root.
+        assert_eq!(
+            parse_cgroup("1:name=systemd:/system.slice/sshd.service\n0::/\n").as_deref(),
+            Some("sshd.service")
+        );
+        assert_eq!(
+            parse_cgroup("4:memory:/sandbox/job-1\n1:name=systemd:/\n0::/\n").as_deref(),
+            Some("job-1")
+        );
+        assert_eq!(parse_cgroup("1:name=systemd:/\n0::/\n"), None);
+    }
+}
@@ -25,13 +25,14 @@ struct ProcessRow {
 }
 
 pub fn get_process_data(
-    sys: &System, use_current_cpu_total: bool, unnormalized_cpu: bool, mem_total_kb: u64,
-    user_table: &mut UserTable,
+    sys: &System, use_current_cpu_total: bool, unnormalized_cpu: bool,
+    time_difference_in_secs: f64, mem_total_kb: u64, user_table: &mut UserTable,
 ) -> crate::utils::error::Result<Vec<ProcessHarvest>> {
     super::macos_freebsd::get_process_data(
         sys,
         use_current_cpu_total,
         unnormalized_cpu,
+        time_difference_in_secs,
         mem_total_kb,
         user_table,
         get_freebsd_process_cpu_usage,
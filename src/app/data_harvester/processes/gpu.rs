@@ -0,0 +1,135 @@
+//! Per-process GPU usage.
+
+use fxhash::FxHashMap;
+
+use super::ProcessHarvest;
+use crate::Pid;
+
+/// How much of the GPUs a single process is using, summed over every GPU it runs on.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GpuProcessUsage {
+    /// Utilization as a percentage, so a process busy on two GPUs can go over 100%.
+    pub usage_percent: f64,
+    pub mem_bytes: u64,
+}
+
+/// Tracks what is needed to only read the GPU samples taken since the last harvest.
+#[derive(Debug, Default)]
+pub struct GpuProcessTracker {
+    /// The timestamp of the latest utilization sample seen, per GPU index.
+    last_seen: FxHashMap<u32, u64>,
+}
+
+impl GpuProcessTracker {
+    /// Adds the GPU usage of each process in `processes`.
+    pub(crate) fn add_gpu_usage(&mut self, processes: &mut [ProcessHarvest]) {
+        // As we add more support, expand on this.
+
+        #[cfg(feature = "nvidia")]
+        apply_gpu_usage(processes, &self.get_nvidia_usage());
+    }
+
+    /// Returns the usage of processes on NVIDIA cards. Processes that were idle on a card since
+    /// the last harvest have no utilization samples for it, and so use 0% of it.
+    #[cfg(feature = "nvidia")]
+    fn get_nvidia_usage(&mut self) -> FxHashMap<Pid, GpuProcessUsage> {
+        use nvml_wrapper::enums::device::UsedGpuMemory;
+
+        use crate::data_harvester::nvidia::NVML_DATA;
+
+        let mut usage: FxHashMap<Pid, GpuProcessUsage> = FxHashMap::default();
+        let Ok(nvml) = &*NVML_DATA else {
+            return usage;
+        };
+        let Ok(num_gpu) = nvml.device_count() else {
+            return usage;
+        };
+
+        for i in 0..num_gpu {
+            let Ok(device) = nvml.device_by_index(i) else {
+                continue;
+            };
+
+            // A card can have several samples of a process buffered, of which the latest is used.
+            let mut latest: FxHashMap<u32, (u64, u32)> = FxHashMap::default();
+            if let Ok(samples) = device.process_utilization_stats(self.last_seen.get(&i).copied()) {
+                for sample in samples {
+                    let entry = latest.entry(sample.pid).or_default();
+                    if sample.timestamp >= entry.0 {
+                        *entry = (sample.timestamp, sample.sm_util);
+                    }
+                    let last_seen = self.last_seen.entry(i).or_default();
+                    *last_seen = (*last_seen).max(sample.timestamp);
+                }
+            }
+            for (pid, (_, sm_util)) in latest {
+                if let Ok(pid) = Pid::try_from(pid) {
+                    usage.entry(pid).or_default().usage_percent += f64::from(sm_util);
+                }
+            }
+
+            // A process can be listed as both a compute and a graphics process of the same card.
+            let mut mem: FxHashMap<u32, u64> = FxHashMap::default();
+            let compute = device.running_compute_processes().unwrap_or_default();
+            let graphics = device.running_graphics_processes().unwrap_or_default();
+            for process in compute.into_iter().chain(graphics) {
+                if let UsedGpuMemory::Used(bytes) = process.used_gpu_memory {
+                    let entry = mem.entry(process.pid).or_default();
+                    *entry = (*entry).max(bytes);
+                }
+            }
+            for (pid, bytes) in mem {
+                if let Ok(pid) = Pid::try_from(pid) {
+                    usage.entry(pid).or_default().mem_bytes += bytes;
+                }
+            }
+        }
+
+        usage
+    }
+}
+
+fn apply_gpu_usage(processes: &mut [ProcessHarvest], usage: &FxHashMap<Pid, GpuProcessUsage>) {
+    for process in processes {
+        let GpuProcessUsage {
+            usage_percent,
+            mem_bytes,
+        } = usage.get(&process.pid).copied().unwrap_or_default();
+        process.gpu_usage_percent = usage_percent;
+        process.gpu_mem_bytes = mem_bytes;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_apply_gpu_usage() {
+        let mut processes = vec![
+            ProcessHarvest {
+                pid: 10,
+                gpu_usage_percent: 5.0,
+                ..ProcessHarvest::default()
+            },
+            ProcessHarvest {
+                pid: 20,
+                ..ProcessHarvest::default()
+            },
+        ];
+        let mut usage = FxHashMap::default();
+        usage.insert(
+            20,
+            GpuProcessUsage {
+                usage_percent: 150.0,
+                mem_bytes: 2048,
+            },
+        );
+
+        apply_gpu_usage(&mut processes, &usage);
+        // A process with no samples since the last harvest is no longer using the GPU.
+        assert_eq!(processes[0].gpu_usage_percent, 0.0);
+        assert_eq!(processes[1].gpu_usage_percent, 150.0);
+        assert_eq!(processes[1].gpu_mem_bytes, 2048);
+    }
+}
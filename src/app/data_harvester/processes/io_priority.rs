@@ -0,0 +1,170 @@
+//! I/O scheduling class and priority of processes, through the `ioprio_get` and `ioprio_set`
+//! syscalls.
+
+use std::{fmt::Display, io};
+
+use crate::Pid;
+
+/// Targets a single process (or thread) in `ioprio_get` and `ioprio_set`.
+const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+
+/// The class is stored above the level in an I/O priority value.
+const IOPRIO_CLASS_SHIFT: i32 = 13;
+const IOPRIO_LEVEL_MASK: i32 = (1 << IOPRIO_CLASS_SHIFT) - 1;
+
+/// The highest (least important) level of the real-time and best-effort classes.
+pub const MAX_IO_PRIORITY_LEVEL: u8 = 7;
+
+/// An I/O scheduling class, as set by `ionice -c`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IOClass {
+    /// No class was set, so the process' CPU nice value decides its I/O priority.
+    #[default]
+    None,
+    RealTime,
+    BestEffort,
+    Idle,
+}
+
+impl IOClass {
+    pub const ALL: [IOClass; 4] = [
+        IOClass::None,
+        IOClass::RealTime,
+        IOClass::BestEffort,
+        IOClass::Idle,
+    ];
+
+    fn from_raw(raw: i32) -> Option<Self> {
+        Self::ALL.get(usize::try_from(raw).ok()?).copied()
+    }
+
+    fn to_raw(self) -> i32 {
+        self as i32
+    }
+
+    /// Whether the class takes a level; only real-time and best-effort do.
+    pub fn has_level(self) -> bool {
+        matches!(self, IOClass::RealTime | IOClass::BestEffort)
+    }
+
+    /// Returns the next class, wrapping around.
+    pub fn next(self) -> Self {
+        Self::ALL[(self as usize + 1) % Self::ALL.len()]
+    }
+
+    /// Returns the previous class, wrapping around.
+    pub fn prev(self) -> Self {
+        Self::ALL[(self as usize + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+}
+
+impl Display for IOClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            IOClass::None => "none",
+            IOClass::RealTime => "RT",
+            IOClass::BestEffort => "BE",
+            IOClass::Idle => "Idle",
+        })
+    }
+}
+
+/// Formats an I/O class and level the way the process widget shows them, e.g. `RT/4` or `Idle`.
+pub fn io_priority_string(io_class: Option<IOClass>, io_priority: Option<u8>) -> String {
+    match (io_class, io_priority) {
+        (Some(class), Some(level)) if class.has_level() => format!("{class}/{level}"),
+        (Some(class), _) => class.to_string(),
+        (None, _) => "N/A".to_string(),
+    }
+}
+
+fn decode(raw: i32) -> Option<(IOClass, u8)> {
+    let class = IOClass::from_raw(raw >> IOPRIO_CLASS_SHIFT)?;
+    let level = u8::try_from(raw & IOPRIO_LEVEL_MASK).ok()?;
+
+    Some((class, level))
+}
+
+fn encode(class: IOClass, level: u8) -> i32 {
+    (class.to_raw() << IOPRIO_CLASS_SHIFT) | i32::from(level)
+}
+
+/// Returns the I/O class and level of a process, or [`None`] if it can't be read.
+pub fn get_io_priority(pid: Pid) -> Option<(IOClass, u8)> {
+    // SAFETY: `ioprio_get` only takes integer arguments, and a failure is just a negative return.
+    let raw = unsafe { libc::syscall(libc::SYS_ioprio_get, IOPRIO_WHO_PROCESS, pid) };
+    if raw < 0 {
+        None
+    } else {
+        decode(raw as i32)
+    }
+}
+
+/// Sets the I/O class and level of a process. The level is ignored by classes without one.
+pub fn set_io_priority(pid: Pid, class: IOClass, level: u8) -> io::Result<()> {
+    if level > MAX_IO_PRIORITY_LEVEL {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("the I/O priority level must be between 0 and {MAX_IO_PRIORITY_LEVEL}."),
+        ));
+    }
+
+    let level = if class.has_level() { level } else { 0 };
+
+    // SAFETY: `ioprio_set` only takes integer arguments, and a failure is just a negative return.
+    let result = unsafe {
+        libc::syscall(
+            libc::SYS_ioprio_set,
+            IOPRIO_WHO_PROCESS,
+            pid,
+            encode(class, level),
+        )
+    };
+    if result < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_io_priority_encoding() {
+        // `ionice -c 2 -n 4` is stored as 0x4004.
+        assert_eq!(encode(IOClass::BestEffort, 4), 0x4004);
+        assert_eq!(decode(0x4004), Some((IOClass::BestEffort, 4)));
+        assert_eq!(decode(0x6000), Some((IOClass::Idle, 0)));
+        assert_eq!(decode(0x8000), None);
+
+        assert_eq!(io_priority_string(Some(IOClass::RealTime), Some(4)), "RT/4");
+        assert_eq!(
+            io_priority_string(Some(IOClass::BestEffort), Some(7)),
+            "BE/7"
+        );
+        assert_eq!(io_priority_string(Some(IOClass::Idle), Some(0)), "Idle");
+        assert_eq!(io_priority_string(Some(IOClass::None), Some(0)), "none");
+        assert_eq!(io_priority_string(None, None), "N/A");
+
+        assert_eq!(IOClass::Idle.next(), IOClass::None);
+        assert_eq!(IOClass::None.prev(), IOClass::Idle);
+    }
+
+    #[test]
+    fn test_own_io_priority() {
+        let pid = std::process::id() as Pid;
+        let (class, level) = get_io_priority(pid).unwrap();
+
+        // Lowering our own priority to best-effort at the lowest level is always allowed.
+        set_io_priority(pid, IOClass::BestEffort, MAX_IO_PRIORITY_LEVEL).unwrap();
+        assert_eq!(
+            get_io_priority(pid),
+            Some((IOClass::BestEffort, MAX_IO_PRIORITY_LEVEL))
+        );
+        assert!(set_io_priority(pid, IOClass::BestEffort, 8).is_err());
+
+        let _ = set_io_priority(pid, class, level);
+    }
+}
@@ -7,7 +7,7 @@ use fxhash::{FxHashMap, FxHashSet};
 use procfs::process::{Process, Stat};
 use sysinfo::{ProcessStatus, System};
 
-use super::{ProcessHarvest, UserTable};
+use super::{bytes_per_sec, get_cgroup_unit, get_io_priority, ProcessHarvest, UserTable};
 use crate::components::tui_widget::time_chart::Point;
 use crate::utils::error::{self, BottomError};
 use crate::Pid;
@@ -18,8 +18,8 @@ const MAX_STAT_NAME_LEN: usize = 15;
 
 #[derive(Debug, Clone, Default)]
 pub struct PrevProcDetails {
-    total_read_bytes: u64,
-    total_write_bytes: u64,
+    /// The bytes read and written as of the last harvest, or [`None`] for a process not seen yet.
+    io_totals: Option<(u64, u64)>,
     cpu_time: u64,
 }
 
@@ -116,7 +116,7 @@ fn get_linux_cpu_usage(
 
 fn read_proc(
     prev_proc: &PrevProcDetails, process: &Process, cpu_usage: f64, cpu_fraction: f64,
-    use_current_cpu_total: bool, time_difference_in_secs: u64, mem_total_kb: u64,
+    use_current_cpu_total: bool, time_difference_in_secs: f64, mem_total_kb: u64,
     user_table: &mut UserTable,
 ) -> error::Result<(ProcessHarvest, u64)> {
     let stat = process.stat()?;
@@ -172,18 +172,24 @@ fn read_proc(
         if let Ok(io) = process.io() {
             let total_read_bytes = io.read_bytes;
             let total_write_bytes = io.write_bytes;
-            let prev_total_read_bytes = prev_proc.total_read_bytes;
-            let prev_total_write_bytes = prev_proc.total_write_bytes;
 
-            let read_bytes_per_sec = total_read_bytes
-                .saturating_sub(prev_total_read_bytes)
-                .checked_div(time_difference_in_secs)
-                .unwrap_or(0);
-
-            let write_bytes_per_sec = total_write_bytes
-                .saturating_sub(prev_total_write_bytes)
-                .checked_div(time_difference_in_secs)
-                .unwrap_or(0);
+            // A process seen for the first time has nothing to compare against, rather than
+            // having done all of its I/O since the last harvest.
+            let (read_bytes_per_sec, write_bytes_per_sec) = prev_proc.io_totals.map_or(
+                (0, 0),
+                |(prev_total_read_bytes, prev_total_write_bytes)| {
+                    (
+                        bytes_per_sec(
+                            total_read_bytes.saturating_sub(prev_total_read_bytes),
+                            time_difference_in_secs,
+                        ),
+                        bytes_per_sec(
+                            total_write_bytes.saturating_sub(prev_total_write_bytes),
+                            time_difference_in_secs,
+                        ),
+                    )
+                },
+            );
 
             (
                 total_read_bytes,
@@ -196,6 +202,7 @@ fn read_proc(
         };
 
     let uid = process.uid()?;
+    let (io_class, io_priority) = get_io_priority(process.pid).unzip();
 
     Ok((
         ProcessHarvest {
@@ -216,6 +223,15 @@ fn read_proc(
                 .get_uid_to_username_mapping(uid)
                 .map(Into::into)
                 .unwrap_or_else(|_| "N/A".into()),
+            nice: i32::try_from(stat.nice).ok(),
+            io_class,
+            io_priority,
+            container: get_cgroup_unit(process.pid),
+            // Filled in by the collector, for every platform at once.
+            #[cfg(feature = "gpu")]
+            gpu_usage_percent: 0.0,
+            #[cfg(feature = "gpu")]
+            gpu_mem_bytes: 0,
         },
         new_process_times,
     ))
@@ -233,7 +249,7 @@ pub(crate) struct ProcHarvestOptions {
 
 pub(crate) fn get_process_data(
     sys: &System, prev_proc: PrevProc<'_>, pid_mapping: &mut FxHashMap<Pid, PrevProcDetails>,
-    proc_harvest_options: ProcHarvestOptions, time_difference_in_secs: u64, mem_total_kb: u64,
+    proc_harvest_options: ProcHarvestOptions, time_difference_in_secs: f64, mem_total_kb: u64,
     user_table: &mut UserTable,
 ) -> crate::utils::error::Result<Vec<ProcessHarvest>> {
     let ProcHarvestOptions {
@@ -284,8 +300,10 @@ pub(crate) fn get_process_data(
                             user_table,
                         ) {
                             prev_proc_details.cpu_time = new_process_times;
-                            prev_proc_details.total_read_bytes = process_harvest.total_read_bytes;
-                            prev_proc_details.total_write_bytes = process_harvest.total_write_bytes;
+                            prev_proc_details.io_totals = Some((
+                                process_harvest.total_read_bytes,
+                                process_harvest.total_write_bytes,
+                            ));
 
                             pids_to_clear.remove(&pid);
                             return Some(process_harvest);
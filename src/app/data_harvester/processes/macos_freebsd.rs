@@ -5,12 +5,13 @@ use std::io;
 
 use sysinfo::{CpuExt, PidExt, ProcessExt, ProcessStatus, System, SystemExt};
 
-use super::ProcessHarvest;
+use super::{bytes_per_sec, get_nice, ProcessHarvest};
 use crate::{data_harvester::processes::UserTable, utils::error::Result, Pid};
 
 pub fn get_process_data<F>(
-    sys: &System, use_current_cpu_total: bool, unnormalized_cpu: bool, mem_total_kb: u64,
-    user_table: &mut UserTable, backup_cpu_proc_usage: F,
+    sys: &System, use_current_cpu_total: bool, unnormalized_cpu: bool,
+    time_difference_in_secs: f64, mem_total_kb: u64, user_table: &mut UserTable,
+    backup_cpu_proc_usage: F,
 ) -> Result<Vec<ProcessHarvest>>
 where
     F: Fn(&[Pid]) -> io::Result<HashMap<Pid, f64>>,
@@ -95,8 +96,9 @@ where
             },
             mem_usage_bytes: process_val.memory(),
             cpu_usage_percent: process_cpu_usage,
-            read_bytes_per_sec: disk_usage.read_bytes,
-            write_bytes_per_sec: disk_usage.written_bytes,
+            // sysinfo gives the bytes moved since the last refresh.
+            read_bytes_per_sec: bytes_per_sec(disk_usage.read_bytes, time_difference_in_secs),
+            write_bytes_per_sec: bytes_per_sec(disk_usage.written_bytes, time_difference_in_secs),
             total_read_bytes: disk_usage.total_read_bytes,
             total_write_bytes: disk_usage.total_written_bytes,
             process_state,
@@ -109,6 +111,12 @@ where
                         .ok()
                 })
                 .unwrap_or_else(|| "N/A".into()),
+            nice: get_nice(pid).ok(),
+            // Filled in by the collector, for every platform at once.
+            #[cfg(feature = "gpu")]
+            gpu_usage_percent: 0.0,
+            #[cfg(feature = "gpu")]
+            gpu_mem_bytes: 0,
         });
     }
 
@@ -138,6 +146,7 @@ fn convert_process_status_to_char(status: ProcessStatus) -> char {
         ProcessStatus::Sleep => 'S',
         ProcessStatus::Idle => 'D',
         ProcessStatus::Zombie => 'Z',
+        ProcessStatus::Stop => 'T',
         _ => '?',
     }
 }
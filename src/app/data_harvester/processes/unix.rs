@@ -1,8 +1,10 @@
 //! Unix-specific parts of process collection.
 
+use std::io;
+
 use fxhash::FxHashMap;
 
-use crate::utils::error;
+use crate::{utils::error, Pid};
 
 #[derive(Debug, Default)]
 pub struct UserTable {
@@ -31,3 +33,118 @@ impl UserTable {
         }
     }
 }
+
+/// The lowest (most favourable) nice value.
+pub const MIN_NICE: i32 = -20;
+
+/// The highest (least favourable) nice value.
+pub const MAX_NICE: i32 = 19;
+
+/// Returns a pointer to the calling thread's `errno`.
+fn errno_location() -> *mut libc::c_int {
+    // SAFETY: Both functions just return the address of the thread-local errno.
+    unsafe {
+        #[cfg(target_os = "linux")]
+        {
+            libc::__errno_location()
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            libc::__error()
+        }
+    }
+}
+
+/// Gets the nice value of a process.
+pub fn get_nice(pid: Pid) -> io::Result<i32> {
+    // getpriority can return -1 as a valid nice value, so errno has to be cleared first to tell
+    // that apart from an error.
+    // SAFETY: errno_location points to this thread's errno, and getpriority takes no pointers.
+    let nice = unsafe {
+        *errno_location() = 0;
+        libc::getpriority(libc::PRIO_PROCESS as _, pid as libc::id_t)
+    };
+
+    match io::Error::last_os_error() {
+        err if nice == -1 && err.raw_os_error() != Some(0) => Err(err),
+        _ => Ok(nice),
+    }
+}
+
+/// Sets the nice value of a process, clamped to between [`MIN_NICE`] and [`MAX_NICE`].
+pub fn set_nice(pid: Pid, nice: i32) -> io::Result<()> {
+    let nice = nice.clamp(MIN_NICE, MAX_NICE);
+
+    // SAFETY: setpriority takes no pointers, and its result is checked.
+    if unsafe { libc::setpriority(libc::PRIO_PROCESS as _, pid as libc::id_t, nice) } == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+/// Changes the nice value of a process by `delta`, returning the new value. Lowering it below
+/// its current value usually needs root.
+pub fn renice(pid: Pid, delta: i32) -> io::Result<i32> {
+    let nice = (get_nice(pid)? + delta).clamp(MIN_NICE, MAX_NICE);
+    set_nice(pid, nice)?;
+
+    Ok(nice)
+}
+
+/// Stops a process with `SIGSTOP`, or continues a stopped one with `SIGCONT`. Unlike killing it,
+/// a stopped process keeps all of its memory and open files, and picks up where it left off.
+pub fn set_stopped(pid: Pid, stopped: bool) -> io::Result<()> {
+    let signal = if stopped {
+        libc::SIGSTOP
+    } else {
+        libc::SIGCONT
+    };
+
+    // SAFETY: kill takes no pointers, and its result is checked.
+    if unsafe { libc::kill(pid, signal) } == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_own_nice() {
+        let pid = std::process::id() as Pid;
+        let nice = get_nice(pid).unwrap();
+        assert!((MIN_NICE..=MAX_NICE).contains(&nice));
+
+        // Raising the nice value never needs any permissions.
+        assert_eq!(renice(pid, 1).unwrap(), (nice + 1).min(MAX_NICE));
+        assert_eq!(get_nice(pid).unwrap(), (nice + 1).min(MAX_NICE));
+    }
+
+    #[test]
+    fn test_stop_and_continue() {
+        let mut child = std::process::Command::new("sleep")
+            .arg("10")
+            .spawn()
+            .unwrap();
+        let pid = child.id() as Pid;
+
+        // SAFETY: waitpid only writes to the given status.
+        let wait = |options| unsafe {
+            let mut status = 0;
+            libc::waitpid(pid, &mut status, options);
+            status
+        };
+
+        set_stopped(pid, true).unwrap();
+        assert!(libc::WIFSTOPPED(wait(libc::WUNTRACED)));
+        set_stopped(pid, false).unwrap();
+        assert!(libc::WIFCONTINUED(wait(libc::WCONTINUED)));
+
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+}
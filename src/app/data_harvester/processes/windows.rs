@@ -2,10 +2,11 @@
 
 use sysinfo::{CpuExt, PidExt, ProcessExt, System, SystemExt, UserExt};
 
-use super::ProcessHarvest;
+use super::{bytes_per_sec, ProcessHarvest};
 
 pub fn get_process_data(
-    sys: &System, use_current_cpu_total: bool, unnormalized_cpu: bool, mem_total_kb: u64,
+    sys: &System, use_current_cpu_total: bool, unnormalized_cpu: bool,
+    time_difference_in_secs: f64, mem_total_kb: u64,
 ) -> crate::utils::error::Result<Vec<ProcessHarvest>> {
     let mut process_vector: Vec<ProcessHarvest> = Vec::new();
     let process_hashmap = sys.processes();
@@ -70,8 +71,9 @@ pub fn get_process_data(
             },
             mem_usage_bytes: process_val.memory(),
             cpu_usage_percent: process_cpu_usage,
-            read_bytes_per_sec: disk_usage.read_bytes,
-            write_bytes_per_sec: disk_usage.written_bytes,
+            // sysinfo gives the bytes moved since the last refresh.
+            read_bytes_per_sec: bytes_per_sec(disk_usage.read_bytes, time_difference_in_secs),
+            write_bytes_per_sec: bytes_per_sec(disk_usage.written_bytes, time_difference_in_secs),
             total_read_bytes: disk_usage.total_read_bytes,
             total_write_bytes: disk_usage.total_written_bytes,
             process_state,
@@ -79,6 +81,11 @@ pub fn get_process_data(
                 .user_id()
                 .and_then(|uid| sys.get_user_by_id(uid))
                 .map_or_else(|| "N/A".into(), |user| user.name().to_owned().into()),
+            // Filled in by the collector, for every platform at once.
+            #[cfg(feature = "gpu")]
+            gpu_usage_percent: 0.0,
+            #[cfg(feature = "gpu")]
+            gpu_mem_bytes: 0,
         });
     }
 
@@ -0,0 +1,252 @@
+//! Software RAID status, read from `/proc/mdstat` on Linux.
+
+/// A single Linux software RAID (md) array.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RaidHarvest {
+    /// The name of the array, like `md0`.
+    pub name: String,
+    /// The RAID level, like `raid1`. Inactive arrays don't report one.
+    pub level: Option<String>,
+    /// Whether the array is `active` or `inactive`, along with flags like `(auto-read-only)`.
+    pub state: String,
+    pub members: Vec<RaidMember>,
+    /// How many devices the array should have, and how many of them are working.
+    pub total_devices: Option<u32>,
+    pub working_devices: Option<u32>,
+    /// An ongoing resync, recovery, reshape or check.
+    pub sync: Option<RaidSync>,
+}
+
+impl RaidHarvest {
+    /// Whether the array is missing devices, or has any failed ones.
+    pub fn is_degraded(&self) -> bool {
+        let is_missing_devices = match (self.working_devices, self.total_devices) {
+            (Some(working_devices), Some(total_devices)) => working_devices < total_devices,
+            _ => false,
+        };
+
+        is_missing_devices || self.members.iter().any(|member| member.is_failed)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RaidMember {
+    /// The name of the device, like `sda1`.
+    pub name: String,
+    pub is_failed: bool,
+    pub is_spare: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RaidSync {
+    /// What is being done, like `resync` or `recovery`.
+    pub action: String,
+    /// How far along it is, in percent, or `None` if it hasn't started yet.
+    pub percent: Option<f64>,
+    /// The estimated time left, as reported by the kernel, like `12.3min`.
+    pub finish: Option<String>,
+}
+
+/// Returns every software RAID array on the system.
+#[cfg(target_os = "linux")]
+pub fn get_raid_arrays() -> Vec<RaidHarvest> {
+    std::fs::read_to_string("/proc/mdstat")
+        .map(|contents| parse_mdstat(&contents))
+        .unwrap_or_default()
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn get_raid_arrays() -> Vec<RaidHarvest> {
+    Vec::new()
+}
+
+/// Parses `/proc/mdstat`, which looks like:
+///
+/// ```text
+/// Personalities : [raid1] [raid6] [raid5] [raid4]
+/// md0 : active raid1 sdb1[1] sda1[0]
+///       1048512 blocks super 1.2 [2/2] [UU]
+///       [=>...................]  resync =  8.5% (89600/1048512) finish=0.1min speed=89600K/sec
+///
+/// md1 : active raid5 sdd1[3](F) sdc1[1] sdb1[0]
+///       2095104 blocks super 1.2 level 5, 512k chunk, algorithm 2 [3/2] [UU_]
+///
+/// unused devices: <none>
+/// ```
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_mdstat(contents: &str) -> Vec<RaidHarvest> {
+    let mut arrays: Vec<RaidHarvest> = Vec::new();
+
+    for line in contents.lines() {
+        if let Some((name, description)) = line.split_once(" : ") {
+            let name = name.trim();
+            if name.starts_with("md") {
+                arrays.push(parse_array_line(name, description));
+            }
+            continue;
+        }
+
+        // Everything else that is indented describes the last array.
+        let Some(array) = arrays.last_mut() else {
+            continue;
+        };
+        if !line.starts_with(char::is_whitespace) {
+            continue;
+        }
+
+        if let Some((total_devices, working_devices)) = parse_device_counts(line) {
+            array.total_devices = Some(total_devices);
+            array.working_devices = Some(working_devices);
+        } else if let Some(sync) = parse_sync_line(line) {
+            array.sync = Some(sync);
+        }
+    }
+
+    arrays
+}
+
+/// Parses the part of an array's first line after its name, like
+/// `active raid5 sdd1[3](F) sdc1[1] sdb1[0]`.
+fn parse_array_line(name: &str, description: &str) -> RaidHarvest {
+    let mut tokens = description.split_whitespace().peekable();
+    let mut state = tokens.next().unwrap_or_default().to_string();
+    while let Some(flag) = tokens.next_if(|token| token.starts_with('(')) {
+        state.push(' ');
+        state.push_str(flag);
+    }
+
+    // Members always have their index in brackets after their name, which a level never does.
+    let level = tokens
+        .next_if(|token| !token.contains('['))
+        .map(str::to_string);
+
+    let members = tokens
+        .filter_map(|token| {
+            let (name, flags) = token.split_once('[')?;
+            Some(RaidMember {
+                name: name.to_string(),
+                is_failed: flags.contains("(F)"),
+                is_spare: flags.contains("(S)"),
+            })
+        })
+        .collect();
+
+    RaidHarvest {
+        name: name.to_string(),
+        level,
+        state,
+        members,
+        ..Default::default()
+    }
+}
+
+/// Parses the `[3/2]` device counts out of an array's status line.
+fn parse_device_counts(line: &str) -> Option<(u32, u32)> {
+    line.split_whitespace().find_map(|token| {
+        let (total, working) = token
+            .strip_prefix('[')?
+            .strip_suffix(']')?
+            .split_once('/')?;
+        Some((total.parse().ok()?, working.parse().ok()?))
+    })
+}
+
+/// Parses a progress line like `[=>....]  resync =  8.5% (89600/1048512) finish=0.1min ...`, or
+/// one for a sync that hasn't started yet, like `resync=DELAYED`.
+fn parse_sync_line(line: &str) -> Option<RaidSync> {
+    const ACTIONS: [&str; 4] = ["resync", "recovery", "reshape", "check"];
+
+    let line = line.trim_start();
+    let line = match line.strip_prefix('[') {
+        Some(rest) => rest.split_once(']')?.1.trim_start(),
+        None => line,
+    };
+
+    let action = ACTIONS
+        .into_iter()
+        .find(|action| line.starts_with(action))?;
+    let rest = line[action.len()..]
+        .trim_start()
+        .strip_prefix('=')?
+        .trim_start();
+
+    let percent = rest
+        .split_whitespace()
+        .next()
+        .and_then(|percent| percent.strip_suffix('%'))
+        .and_then(|percent| percent.parse().ok());
+    let finish = rest
+        .split_whitespace()
+        .find_map(|token| token.strip_prefix("finish="))
+        .map(str::to_string);
+
+    Some(RaidSync {
+        action: action.to_string(),
+        percent,
+        finish,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_mdstat() {
+        let contents = "Personalities : [raid1] [raid6] [raid5] [raid4]
+md0 : active raid1 sdb1[1] sda1[0]
+      1048512 blocks super 1.2 [2/2] [UU]
+      [=>...................]  resync =  8.5% (89600/1048512) finish=0.1min speed=89600K/sec
+
+md1 : active (auto-read-only) raid5 sdd1[3](F) sdc1[1] sdb1[0] sde1[4](S)
+      2095104 blocks super 1.2 level 5, 512k chunk, algorithm 2 [3/2] [UU_]
+      	resync=DELAYED
+      bitmap: 0/1 pages [0KB], 65536KB chunk
+
+md2 : inactive sdf1[0](S)
+      1048576 blocks super 1.2
+
+unused devices: <none>
+";
+        let arrays = parse_mdstat(contents);
+        assert_eq!(arrays.len(), 3);
+
+        assert_eq!(arrays[0].name, "md0");
+        assert_eq!(arrays[0].level.as_deref(), Some("raid1"));
+        assert_eq!(arrays[0].state, "active");
+        assert_eq!(arrays[0].members.len(), 2);
+        assert_eq!(
+            (arrays[0].total_devices, arrays[0].working_devices),
+            (Some(2), Some(2))
+        );
+        assert_eq!(
+            arrays[0].sync,
+            Some(RaidSync {
+                action: "resync".to_string(),
+                percent: Some(8.5),
+                finish: Some("0.1min".to_string()),
+            })
+        );
+        assert!(!arrays[0].is_degraded());
+
+        assert_eq!(arrays[1].state, "active (auto-read-only)");
+        assert_eq!(arrays[1].level.as_deref(), Some("raid5"));
+        assert_eq!(
+            arrays[1].members[0],
+            RaidMember {
+                name: "sdd1".to_string(),
+                is_failed: true,
+                is_spare: false,
+            }
+        );
+        assert!(arrays[1].members[3].is_spare);
+        assert_eq!(arrays[1].sync.as_ref().unwrap().percent, None);
+        assert!(arrays[1].is_degraded());
+
+        assert_eq!(arrays[2].state, "inactive");
+        assert_eq!(arrays[2].level, None);
+        assert_eq!(arrays[2].members[0].name, "sdf1");
+        assert_eq!(arrays[2].total_devices, None);
+        assert!(!arrays[2].is_degraded());
+    }
+}
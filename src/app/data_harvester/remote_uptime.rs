@@ -0,0 +1,137 @@
+//! Checks on the uptime of other hosts, for the uptime widget.
+
+use std::{
+    process::{Command, Stdio},
+    thread,
+};
+
+/// How a remote host is checked.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RemoteUptimeMethod {
+    /// Reads `/proc/uptime` over SSH, which needs key-based authentication to be set up.
+    #[default]
+    Ssh,
+    /// Only checks whether the host answers a ping.
+    Ping,
+}
+
+impl std::str::FromStr for RemoteUptimeMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "ssh" => Ok(RemoteUptimeMethod::Ssh),
+            "ping" => Ok(RemoteUptimeMethod::Ping),
+            _ => Err(format!(
+                "'{s}' is not a valid remote uptime method, use 'ssh' or 'ping'."
+            )),
+        }
+    }
+}
+
+/// A host shown in the uptime widget alongside the local one.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RemoteHost {
+    /// The name shown in the widget.
+    pub name: String,
+    /// The address to ping, or the destination to pass to `ssh`, like `user@server`.
+    pub address: String,
+    pub method: RemoteUptimeMethod,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RemoteUptimeStatus {
+    /// The host is up, and has been for this many seconds.
+    Up(u64),
+    /// The host answers pings, which doesn't say how long it has been up, only how many seconds
+    /// it has kept answering them for while bottom was running.
+    Reachable(u64),
+    Unreachable,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteUptimeHarvest {
+    pub name: String,
+    pub status: RemoteUptimeStatus,
+}
+
+/// Checks every host at once, so one that has gone away only holds the others up for as long as
+/// its own timeout.
+pub fn get_remote_uptimes(hosts: &[RemoteHost]) -> Vec<RemoteUptimeHarvest> {
+    thread::scope(|scope| {
+        let checks = hosts
+            .iter()
+            .map(|host| scope.spawn(|| check_host(host)))
+            .collect::<Vec<_>>();
+
+        hosts
+            .iter()
+            .zip(checks)
+            .map(|(host, check)| RemoteUptimeHarvest {
+                name: host.name.clone(),
+                status: check.join().unwrap_or(RemoteUptimeStatus::Unreachable),
+            })
+            .collect()
+    })
+}
+
+fn check_host(host: &RemoteHost) -> RemoteUptimeStatus {
+    match host.method {
+        RemoteUptimeMethod::Ssh => Command::new("ssh")
+            .args(["-o", "BatchMode=yes", "-o", "ConnectTimeout=5"])
+            .arg(&host.address)
+            .arg("cat /proc/uptime")
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .and_then(|output| parse_proc_uptime(&String::from_utf8_lossy(&output.stdout)))
+            .map_or(RemoteUptimeStatus::Unreachable, RemoteUptimeStatus::Up),
+        RemoteUptimeMethod::Ping => {
+            let mut ping = Command::new("ping");
+            if cfg!(target_os = "windows") {
+                ping.args(["-n", "1", "-w", "2000"]);
+            } else {
+                ping.args(["-c", "1", "-W", "2"]);
+            }
+
+            let is_reachable = ping
+                .arg(&host.address)
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .map(|status| status.success())
+                .unwrap_or(false);
+
+            if is_reachable {
+                RemoteUptimeStatus::Reachable(0)
+            } else {
+                RemoteUptimeStatus::Unreachable
+            }
+        }
+    }
+}
+
+/// Parses the contents of `/proc/uptime`, e.g. `350735.47 234388.90`, where the first value is
+/// the uptime in seconds.
+fn parse_proc_uptime(contents: &str) -> Option<u64> {
+    let uptime: f64 = contents.split_whitespace().next()?.parse().ok()?;
+    Some(uptime as u64)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_proc_uptime() {
+        assert_eq!(parse_proc_uptime("350735.47 234388.90\n"), Some(350735));
+        assert_eq!(parse_proc_uptime("cat: /proc/uptime: No such file"), None);
+        assert_eq!(parse_proc_uptime(""), None);
+
+        assert_eq!("Ping".parse(), Ok(RemoteUptimeMethod::Ping));
+        assert!("telnet".parse::<RemoteUptimeMethod>().is_err());
+    }
+}
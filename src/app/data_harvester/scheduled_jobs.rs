@@ -0,0 +1,500 @@
+//! Scheduled jobs, meaning cron entries and systemd timers, along with when they next run.
+
+use std::collections::HashMap;
+
+use time::{Duration, OffsetDateTime, PrimitiveDateTime, Time};
+
+use crate::utils::json::JsonValue;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum JobKind {
+    #[default]
+    Cron,
+    Timer,
+}
+
+/// A single cron entry or systemd timer.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ScheduledJobHarvest {
+    pub kind: JobKind,
+    /// The schedule of a cron entry, like `0 3 * * *`, or the name of a timer, like
+    /// `logrotate.timer`.
+    pub schedule: String,
+    /// The command a cron entry runs, or the unit a timer activates.
+    pub command: String,
+    /// The user a cron entry runs as.
+    pub user: Option<String>,
+    /// When the job runs next, in seconds since the epoch.
+    pub next_run: Option<u64>,
+    /// When the job last ran, in seconds since the epoch. Cron doesn't keep track of this.
+    pub last_run: Option<u64>,
+    /// How the last run went, like `success` or `exit-code`. Only timers report this.
+    pub last_result: Option<String>,
+}
+
+impl ScheduledJobHarvest {
+    /// Whether the last run didn't succeed.
+    pub fn has_failed(&self) -> bool {
+        self.last_result
+            .as_deref()
+            .is_some_and(|result| result != "success")
+    }
+}
+
+/// Returns every systemd timer, followed by every cron entry that can be read.
+pub fn get_scheduled_jobs() -> Vec<ScheduledJobHarvest> {
+    let mut jobs = get_timers();
+    jobs.extend(get_cron_jobs());
+
+    jobs
+}
+
+#[cfg(target_os = "linux")]
+fn get_timers() -> Vec<ScheduledJobHarvest> {
+    fn systemctl<'a>(args: impl IntoIterator<Item = &'a str>) -> Option<String> {
+        let output = std::process::Command::new("systemctl")
+            .args(args)
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+
+        Some(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    let Some(list) = systemctl(["list-timers", "--all", "--output=json", "--no-pager"]) else {
+        return Vec::new();
+    };
+
+    let mut timers = parse_timer_list(&list);
+    let units = timers
+        .iter()
+        .filter(|timer| timer.last_run.is_some())
+        .map(|timer| timer.command.as_str())
+        .collect::<Vec<_>>();
+    if !units.is_empty() {
+        let args = ["show", "--property=Id,Result", "--no-pager"];
+        if let Some(show) = systemctl(args.into_iter().chain(units)) {
+            let mut results = parse_unit_results(&show);
+            for timer in timers.iter_mut().filter(|timer| timer.last_run.is_some()) {
+                timer.last_result = results.remove(&timer.command);
+            }
+        }
+    }
+
+    timers
+}
+
+#[cfg(not(target_os = "linux"))]
+fn get_timers() -> Vec<ScheduledJobHarvest> {
+    Vec::new()
+}
+
+/// Reads the system crontabs, and the crontab of every user if they can be read. Otherwise, only
+/// the crontab of the current user is read, through `crontab -l`.
+#[cfg(target_family = "unix")]
+fn get_cron_jobs() -> Vec<ScheduledJobHarvest> {
+    use std::{fs, path::PathBuf};
+
+    let now = OffsetDateTime::now_utc();
+    let now = now.to_offset(crate::utils::gen_util::local_offset(now.unix_timestamp()));
+
+    let mut system_crontabs = vec![PathBuf::from("/etc/crontab")];
+    if let Ok(entries) = fs::read_dir("/etc/cron.d") {
+        system_crontabs.extend(entries.flatten().map(|entry| entry.path()));
+    }
+
+    let mut jobs = Vec::new();
+    for path in system_crontabs {
+        if let Ok(crontab) = fs::read_to_string(path) {
+            jobs.extend(parse_crontab(&crontab, None, now));
+        }
+    }
+
+    // Debian keeps user crontabs in the first, and most others in the second.
+    let mut has_read_user_crontabs = false;
+    for dir in ["/var/spool/cron/crontabs", "/var/spool/cron"] {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if !entry.file_type().is_ok_and(|kind| kind.is_file()) {
+                continue;
+            }
+            if let Ok(crontab) = fs::read_to_string(entry.path()) {
+                has_read_user_crontabs = true;
+                let user = entry.file_name().to_string_lossy().into_owned();
+                jobs.extend(parse_crontab(&crontab, Some(&user), now));
+            }
+        }
+    }
+
+    if !has_read_user_crontabs {
+        if let Ok(output) = std::process::Command::new("crontab").arg("-l").output() {
+            if output.status.success() {
+                let user = std::env::var("USER").ok();
+                jobs.extend(parse_crontab(
+                    &String::from_utf8_lossy(&output.stdout),
+                    Some(user.as_deref().unwrap_or("")),
+                    now,
+                ));
+            }
+        }
+    }
+
+    jobs
+}
+
+#[cfg(not(target_family = "unix"))]
+fn get_cron_jobs() -> Vec<ScheduledJobHarvest> {
+    Vec::new()
+}
+
+/// Parses the output of `systemctl list-timers --output=json`, which has a `unit`, the unit it
+/// `activates`, and the `next` and `last` time it runs in microseconds since the epoch for each
+/// timer, with `0` or `null` for never.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_timer_list(list: &str) -> Vec<ScheduledJobHarvest> {
+    let Some(timers) = JsonValue::parse(list) else {
+        return Vec::new();
+    };
+
+    timers
+        .as_array()
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|timer| {
+            let time = |key| {
+                timer
+                    .get(key)
+                    .and_then(JsonValue::as_u64)
+                    .filter(|usec| *usec > 0)
+                    .map(|usec| usec / 1_000_000)
+            };
+
+            Some(ScheduledJobHarvest {
+                kind: JobKind::Timer,
+                schedule: timer.get("unit")?.as_str()?.to_string(),
+                command: timer
+                    .get("activates")
+                    .and_then(JsonValue::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+                user: None,
+                next_run: time("next"),
+                last_run: time("last"),
+                last_result: None,
+            })
+        })
+        .collect()
+}
+
+/// Parses the `Result` of each unit out of `systemctl show --property=Id,Result`, which lists the
+/// properties of each unit in turn, separated by an empty line.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_unit_results(show: &str) -> HashMap<String, String> {
+    show.split("\n\n")
+        .filter_map(|unit| {
+            let property = |name: &str| {
+                unit.lines()
+                    .find_map(|line| line.strip_prefix(name)?.strip_prefix('='))
+            };
+
+            Some((property("Id")?.to_string(), property("Result")?.to_string()))
+        })
+        .collect()
+}
+
+/// Parses the entries of a crontab. User crontabs are given the user they belong to, while
+/// system crontabs, like `/etc/crontab`, have a user field before each command instead.
+#[cfg_attr(not(target_family = "unix"), allow(dead_code))]
+fn parse_crontab(
+    crontab: &str, user: Option<&str>, now: OffsetDateTime,
+) -> Vec<ScheduledJobHarvest> {
+    /// Splits off the first whitespace-separated field of a line.
+    fn split_field(line: &str) -> Option<(&str, &str)> {
+        let line = line.trim_start();
+        let end = line.find(char::is_whitespace)?;
+        Some((&line[..end], line[end..].trim_start()))
+    }
+
+    crontab
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let (schedule, rest) = if line.starts_with('@') {
+                split_field(line)?
+            } else {
+                let mut rest = line;
+                for _ in 0..5 {
+                    rest = split_field(rest)?.1;
+                }
+                (line[..line.len() - rest.len()].trim_end(), rest)
+            };
+
+            // Lines setting variables, like `MAILTO=root`, aren't entries.
+            if schedule.contains('=') {
+                return None;
+            }
+
+            let (user, command) = match user {
+                Some(user) => (user, rest),
+                None => split_field(rest)?,
+            };
+
+            let next_run = match schedule {
+                "@reboot" => None,
+                _ => Some(CronSchedule::parse(schedule)?.next_after(now)?),
+            };
+
+            Some(ScheduledJobHarvest {
+                kind: JobKind::Cron,
+                schedule: schedule.to_string(),
+                command: command.to_string(),
+                user: (!user.is_empty()).then(|| user.to_string()),
+                next_run: next_run
+                    .and_then(|next_run| u64::try_from(next_run.unix_timestamp()).ok()),
+                last_run: None,
+                last_result: None,
+            })
+        })
+        .collect()
+}
+
+/// The times a cron entry runs at, with a bit set for each minute, hour, day, month and weekday
+/// it runs on.
+#[derive(Debug, PartialEq, Eq)]
+struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Whether the days or weekdays were given rather than `*`. When both are, a day matching
+    /// either one runs the job.
+    is_day_restricted: bool,
+    is_weekday_restricted: bool,
+}
+
+impl CronSchedule {
+    fn parse(schedule: &str) -> Option<Self> {
+        const MONTHS: [&str; 12] = [
+            "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+        ];
+        const WEEKDAYS: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+        let schedule = match schedule {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            schedule => schedule,
+        };
+
+        let fields = schedule.split_whitespace().collect::<Vec<_>>();
+        let [minutes, hours, days, months, weekdays] = fields.as_slice() else {
+            return None;
+        };
+
+        // Sunday can be either 0 or 7.
+        let mut weekday_bits = parse_cron_field(weekdays, 0, 7, &WEEKDAYS)?;
+        if weekday_bits & (1 << 7) != 0 {
+            weekday_bits |= 1;
+        }
+
+        Some(CronSchedule {
+            minutes: parse_cron_field(minutes, 0, 59, &[])?,
+            hours: parse_cron_field(hours, 0, 23, &[])?,
+            days: parse_cron_field(days, 1, 31, &[])?,
+            months: parse_cron_field(months, 1, 12, &MONTHS)?,
+            weekdays: weekday_bits,
+            is_day_restricted: !days.starts_with('*'),
+            is_weekday_restricted: !weekdays.starts_with('*'),
+        })
+    }
+
+    fn runs_on(&self, date: time::Date) -> bool {
+        let is_set = |bits: u64, value: u8| bits & (1 << value) != 0;
+        let day = is_set(self.days, date.day());
+        let weekday = is_set(self.weekdays, date.weekday().number_days_from_sunday());
+
+        is_set(self.months, date.month() as u8)
+            && if self.is_day_restricted && self.is_weekday_restricted {
+                day || weekday
+            } else {
+                day && weekday
+            }
+    }
+
+    /// Returns the first time after `after` that the entry runs, in the same offset.
+    fn next_after(&self, after: OffsetDateTime) -> Option<OffsetDateTime> {
+        let start = after.replace_second(0).ok()?.replace_nanosecond(0).ok()? + Duration::MINUTE;
+        let mut date = start.date();
+
+        // Entries only running on February 29th on a given weekday can be years apart.
+        for day in 0..366 * 28 {
+            if self.runs_on(date) {
+                let is_first_day = day == 0;
+                let first_hour = if is_first_day { start.hour() } else { 0 };
+                for hour in (first_hour..24).filter(|hour| self.hours & (1 << hour) != 0) {
+                    let first_minute = if is_first_day && hour == start.hour() {
+                        start.minute()
+                    } else {
+                        0
+                    };
+                    if let Some(minute) =
+                        (first_minute..60).find(|minute| self.minutes & (1 << minute) != 0)
+                    {
+                        let time = Time::from_hms(hour, minute, 0).ok()?;
+                        return Some(
+                            PrimitiveDateTime::new(date, time).assume_offset(after.offset()),
+                        );
+                    }
+                }
+            }
+            date = date.next_day()?;
+        }
+
+        None
+    }
+}
+
+/// Parses a field of a cron schedule into a bit for each value in it. A field is a list of
+/// values, ranges like `1-5`, or `*`, each optionally followed by a step like `/15`. Values can
+/// also be given by the first three letters of their name, if `names` has them.
+fn parse_cron_field(field: &str, min: u8, max: u8, names: &[&str]) -> Option<u64> {
+    let value = |value: &str| {
+        value.parse::<u8>().ok().or_else(|| {
+            let value = value.to_lowercase();
+            let index = names.iter().position(|name| *name == value)?;
+            Some(min + u8::try_from(index).ok()?)
+        })
+    };
+
+    let mut bits = 0;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<usize>().ok().filter(|step| *step > 0)?),
+            None => (part, 1),
+        };
+        let (start, end) = match range.split_once('-') {
+            _ if range == "*" => (min, max),
+            Some((start, end)) => (value(start)?, value(end)?),
+            // A single value with a step, like `5/10`, runs from it to the end of the range.
+            None if step > 1 => (value(range)?, max),
+            None => (value(range)?, value(range)?),
+        };
+        if start < min || end > max || start > end {
+            return None;
+        }
+
+        for value in (start..=end).step_by(step) {
+            bits |= 1 << value;
+        }
+    }
+
+    Some(bits)
+}
+
+#[cfg(test)]
+mod test {
+    use time::macros::datetime;
+
+    use super::*;
+
+    #[test]
+    fn test_cron_next_run() {
+        // A Thursday.
+        let now = datetime!(2026-10-15 13:37:42 UTC);
+        let next = |schedule: &str| CronSchedule::parse(schedule).unwrap().next_after(now);
+
+        assert_eq!(next("* * * * *"), Some(datetime!(2026-10-15 13:38 UTC)));
+        assert_eq!(next("*/15 * * * *"), Some(datetime!(2026-10-15 13:45 UTC)));
+        assert_eq!(next("0 3 * * *"), Some(datetime!(2026-10-16 03:00 UTC)));
+        assert_eq!(next("30 2 * * mon"), Some(datetime!(2026-10-19 02:30 UTC)));
+        assert_eq!(
+            next("0 0 1 jan-mar *"),
+            Some(datetime!(2027-01-01 00:00 UTC))
+        );
+        assert_eq!(next("@weekly"), Some(datetime!(2026-10-18 00:00 UTC)));
+
+        // With both a day and a weekday, either one runs the job.
+        assert_eq!(next("0 12 20 * 5"), Some(datetime!(2026-10-16 12:00 UTC)));
+        assert_eq!(next("0 12 20 * 7"), Some(datetime!(2026-10-18 12:00 UTC)));
+
+        assert_eq!(next("0 0 29 2 *"), Some(datetime!(2028-02-29 00:00 UTC)));
+        assert_eq!(next("0 0 31 2 *"), None);
+
+        assert!(CronSchedule::parse("60 * * * *").is_none());
+        assert!(CronSchedule::parse("* * * *").is_none());
+        assert!(CronSchedule::parse("*/0 * * * *").is_none());
+    }
+
+    #[test]
+    fn test_parse_crontab() {
+        let now = datetime!(2026-10-15 13:37 UTC);
+        let crontab = "SHELL=/bin/sh
+# m h dom mon dow user command
+17 *\t* * *\troot    cd / && run-parts --report /etc/cron.hourly
+@reboot root /usr/local/bin/on-boot.sh
+
+@daily   root   test -x /usr/sbin/logrotate || exit 0
+";
+        let jobs = parse_crontab(crontab, None, now);
+        assert_eq!(jobs.len(), 3);
+
+        assert_eq!(jobs[0].schedule, "17 *\t* * *");
+        assert_eq!(jobs[0].user.as_deref(), Some("root"));
+        assert_eq!(
+            jobs[0].command,
+            "cd / && run-parts --report /etc/cron.hourly"
+        );
+        assert_eq!(
+            jobs[0].next_run,
+            Some(datetime!(2026-10-15 14:17 UTC).unix_timestamp() as u64)
+        );
+
+        assert_eq!(jobs[1].schedule, "@reboot");
+        assert_eq!(jobs[1].next_run, None);
+        assert_eq!(jobs[2].command, "test -x /usr/sbin/logrotate || exit 0");
+
+        // User crontabs have no user field.
+        let jobs = parse_crontab(
+            "MAILTO=\"\"\n*/5 * * * * backup.sh --quiet\n",
+            Some("alice"),
+            now,
+        );
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].user.as_deref(), Some("alice"));
+        assert_eq!(jobs[0].command, "backup.sh --quiet");
+    }
+
+    #[test]
+    fn test_parse_timers() {
+        let list = r#"[{"next":1760590800000000,"left":1760590800000000,"last":1760504400123456,"passed":1760504400123456,"unit":"logrotate.timer","activates":"logrotate.service"},{"next":null,"left":null,"last":null,"passed":null,"unit":"fstrim.timer","activates":"fstrim.service"}]"#;
+        let timers = parse_timer_list(list);
+        assert_eq!(timers.len(), 2);
+        assert_eq!(timers[0].kind, JobKind::Timer);
+        assert_eq!(timers[0].schedule, "logrotate.timer");
+        assert_eq!(timers[0].command, "logrotate.service");
+        assert_eq!(timers[0].next_run, Some(1_760_590_800));
+        assert_eq!(timers[0].last_run, Some(1_760_504_400));
+        assert_eq!(timers[1].next_run, None);
+        assert_eq!(timers[1].last_run, None);
+
+        let show = "Id=logrotate.service\nResult=success\n\nResult=exit-code\nId=man-db.service\n";
+        let results = parse_unit_results(show);
+        assert_eq!(results["logrotate.service"], "success");
+        assert_eq!(results["man-db.service"], "exit-code");
+
+        let failed = ScheduledJobHarvest {
+            last_result: Some("exit-code".to_string()),
+            ..Default::default()
+        };
+        assert!(failed.has_failed());
+        assert!(!ScheduledJobHarvest::default().has_failed());
+    }
+}
@@ -21,7 +21,45 @@ use crate::app::Filter;
 #[derive(Default, Debug, Clone)]
 pub struct TempHarvest {
     pub name: String,
-    pub temperature: f32,
+    /// The reading, in the unit of [`TempHarvest::sensor_type`]. Temperatures are already in the
+    /// configured [`TemperatureType`].
+    pub value: f32,
+    pub sensor_type: SensorType,
+}
+
+/// What a sensor measures.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SensorType {
+    #[default]
+    Temperature,
+    /// Fan speed, in RPM.
+    Fan,
+    /// Voltage, in volts.
+    Voltage,
+    /// Power draw, in watts.
+    Power,
+}
+
+impl SensorType {
+    /// Returns the sensor type for a name from the `sensor_types` config option.
+    pub fn from_config_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "temp" | "temperature" => Some(SensorType::Temperature),
+            "fan" => Some(SensorType::Fan),
+            "voltage" | "in" => Some(SensorType::Voltage),
+            "power" => Some(SensorType::Power),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SensorType::Temperature => "Temp",
+            SensorType::Fan => "Fan",
+            SensorType::Voltage => "Volt",
+            SensorType::Power => "Power",
+        }
+    }
 }
 
 #[derive(Clone, Debug, Copy, PartialEq, Eq)]
@@ -37,6 +75,17 @@ impl Default for TemperatureType {
     }
 }
 
+impl TemperatureType {
+    /// Converts a temperature in Celsius to this unit.
+    pub fn convert_celsius(&self, celsius: f32) -> f32 {
+        match self {
+            TemperatureType::Celsius => celsius,
+            TemperatureType::Kelvin => convert_celsius_to_kelvin(celsius),
+            TemperatureType::Fahrenheit => convert_celsius_to_fahrenheit(celsius),
+        }
+    }
+}
+
 fn convert_celsius_to_kelvin(celsius: f32) -> f32 {
     celsius + 273.15
 }
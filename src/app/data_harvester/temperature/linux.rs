@@ -4,14 +4,51 @@ use std::{fs, path::Path};
 
 use anyhow::{anyhow, Result};
 
-use super::{is_temp_filtered, TempHarvest, TemperatureType};
+use super::{is_temp_filtered, SensorType, TempHarvest, TemperatureType};
 use crate::app::{
     data_harvester::temperature::{convert_celsius_to_fahrenheit, convert_celsius_to_kelvin},
     Filter,
 };
 
-/// Get temperature sensors from the linux sysfs interface `/sys/class/hwmon`.
-/// See [here](https://www.kernel.org/doc/Documentation/ABI/testing/sysfs-class-hwmon) for
+/// Returns what a hwmon file measures, if it is the input of a sensor (e.g. `temp1_input` or
+/// `fan2_input`). Some drivers, like amdgpu, only report an averaged power draw.
+fn hwmon_sensor_type(file_name: &str) -> Option<SensorType> {
+    let (channel, kind) = file_name.split_once('_')?;
+    let prefix = channel.trim_end_matches(|c: char| c.is_ascii_digit());
+    if prefix.len() == channel.len() {
+        // Not a numbered channel, like `temp_input`.
+        return None;
+    }
+
+    match (prefix, kind) {
+        ("temp", "input") => Some(SensorType::Temperature),
+        ("fan", "input") => Some(SensorType::Fan),
+        ("in", "input") => Some(SensorType::Voltage),
+        ("power", "input" | "average") => Some(SensorType::Power),
+        _ => None,
+    }
+}
+
+/// Whether a hwmon directory has any sensor of the given types. This only lists the directory, so
+/// it won't wake the device.
+fn has_hwmon_sensors(path: &Path, sensor_types: &[SensorType]) -> bool {
+    path.read_dir()
+        .map(|entries| {
+            entries.flatten().any(|entry| {
+                entry
+                    .file_name()
+                    .to_str()
+                    .and_then(hwmon_sensor_type)
+                    .map(|sensor_type| sensor_types.contains(&sensor_type))
+                    .unwrap_or(false)
+            })
+        })
+        .unwrap_or(false)
+}
+
+/// Get sensors from the linux sysfs interface `/sys/class/hwmon`, of which only those in
+/// `sensor_types` are read. See
+/// [here](https://www.kernel.org/doc/Documentation/ABI/testing/sysfs-class-hwmon) for
 /// details.
 ///
 /// This method will return `0` as the temperature for devices, such as GPUs,
@@ -26,7 +63,7 @@ use crate::app::{
 /// once this happens, the device will be *kept* on through the sensor
 /// reading, and not be able to re-enter ACPI D3cold.
 fn get_from_hwmon(
-    temp_type: &TemperatureType, filter: &Option<Filter>,
+    temp_type: &TemperatureType, sensor_types: &[SensorType], filter: &Option<Filter>,
 ) -> Result<Vec<TempHarvest>> {
     let mut temperature_vec: Vec<TempHarvest> = vec![];
     let path = Path::new("/sys/class/hwmon");
@@ -46,9 +83,8 @@ fn get_from_hwmon(
         let file = entry?;
         let mut file_path = file.path();
 
-        // hwmon includes many sensors, we only want ones with at least one temperature sensor
-        // Reading this file will wake the device, but we're only checking existence.
-        if !file_path.join("temp1_input").exists() {
+        // hwmon includes many sensors, we only want ones with at least one sensor we're after.
+        if !has_hwmon_sensors(&file_path, sensor_types) {
             // Note we also check for a `device` subdirectory (e.g. `/sys/class/hwmon/hwmon*/device/`).
             // This is needed for CentOS, which adds this extra `/device` directory. See:
             // - https://github.com/nicolargo/glances/issues/1060
@@ -56,7 +92,7 @@ fn get_from_hwmon(
             // - https://github.com/giampaolo/psutil/blob/642438375e685403b4cd60b0c0e25b80dd5a813d/psutil/_pslinux.py#L1316
             //
             // If it does match, then add the `device/` directory to the path.
-            if file_path.join("device/temp1_input").exists() {
+            if has_hwmon_sensors(&file_path.join("device"), sensor_types) {
                 file_path.push("device");
             } else {
                 continue;
@@ -86,7 +122,7 @@ fn get_from_hwmon(
             }
         };
 
-        // Enumerate the devices temperature sensors
+        // Enumerate the devices sensors
         for entry in file_path.read_dir()? {
             let file = entry?;
             let name = file.file_name();
@@ -94,12 +130,14 @@ fn get_from_hwmon(
             let name = name
                 .to_str()
                 .ok_or_else(|| anyhow!("temperature device filenames should be ASCII"))?;
-            // We only want temperature sensors, skip others early
-            if !(name.starts_with("temp") && name.ends_with("input")) {
-                continue;
-            }
+            // We only want the sensors we're after, skip others early
+            let sensor_type = match hwmon_sensor_type(name) {
+                Some(sensor_type) if sensor_types.contains(&sensor_type) => sensor_type,
+                _ => continue,
+            };
+            let (channel, _) = name.split_once('_').unwrap_or((name, ""));
             let temp = file.path();
-            let temp_label = file_path.join(name.replace("input", "label"));
+            let temp_label = file_path.join(format!("{channel}_label"));
             let temp_label = fs::read_to_string(temp_label).ok();
 
             // Do some messing around to get a more sensible name for sensors
@@ -152,6 +190,14 @@ fn get_from_hwmon(
                 }
             };
 
+            // Unlabelled fans and the like would otherwise all share the device's name, so they
+            // fall back to their channel instead (e.g. `fan2`).
+            let temp_label = match (temp_label, sensor_type) {
+                (None, SensorType::Temperature) => None,
+                (None, _) => Some(channel.to_string()),
+                (label, _) => label,
+            };
+
             let name = match (&human_hwmon_name, &temp_label) {
                 (Some(name), Some(label)) => format!("{}: {}", name.trim(), label.trim()),
                 (None, Some(label)) => label.to_string(),
@@ -165,7 +211,7 @@ fn get_from_hwmon(
                         let temp = temp.trim_end().parse::<f32>().map_err(|e| {
                             crate::utils::error::BottomError::ConversionError(e.to_string())
                         })?;
-                        temp / 1_000.0
+                        temp
                     } else {
                         // For some devices (e.g. iwlwifi), this file becomes empty when the device
                         // is disabled. In this case we skip the device.
@@ -175,13 +221,24 @@ fn get_from_hwmon(
                     0.0
                 };
 
+                // Temperatures and voltages are in thousandths, and power in millionths.
+                let value = match sensor_type {
+                    SensorType::Temperature => match temp_type {
+                        TemperatureType::Celsius => temp / 1_000.0,
+                        TemperatureType::Kelvin => convert_celsius_to_kelvin(temp / 1_000.0),
+                        TemperatureType::Fahrenheit => {
+                            convert_celsius_to_fahrenheit(temp / 1_000.0)
+                        }
+                    },
+                    SensorType::Fan => temp,
+                    SensorType::Voltage => temp / 1_000.0,
+                    SensorType::Power => temp / 1_000_000.0,
+                };
+
                 temperature_vec.push(TempHarvest {
                     name,
-                    temperature: match temp_type {
-                        TemperatureType::Celsius => temp,
-                        TemperatureType::Kelvin => convert_celsius_to_kelvin(temp),
-                        TemperatureType::Fahrenheit => convert_celsius_to_fahrenheit(temp),
-                    },
+                    value,
+                    sensor_type,
                 });
             }
         }
@@ -221,11 +278,12 @@ fn get_from_thermal_zone(
                     / 1_000.0;
                 temperatures.push(TempHarvest {
                     name,
-                    temperature: match temp_type {
+                    value: match temp_type {
                         TemperatureType::Celsius => temp,
                         TemperatureType::Kelvin => convert_celsius_to_kelvin(temp),
                         TemperatureType::Fahrenheit => convert_celsius_to_fahrenheit(temp),
                     },
+                    sensor_type: SensorType::Temperature,
                 });
             }
         }
@@ -234,21 +292,48 @@ fn get_from_thermal_zone(
     Ok(temperatures)
 }
 
-/// Gets temperature sensors and data.
+/// Gets sensors of the given types and their data.
 pub fn get_temperature_data(
-    temp_type: &TemperatureType, filter: &Option<Filter>,
+    temp_type: &TemperatureType, sensor_types: &[SensorType], filter: &Option<Filter>,
 ) -> Result<Option<Vec<TempHarvest>>> {
-    let mut temperature_vec: Vec<TempHarvest> = get_from_hwmon(temp_type, filter)?;
+    let mut temperature_vec: Vec<TempHarvest> = get_from_hwmon(temp_type, sensor_types, filter)?;
 
-    if temperature_vec.is_empty() {
-        // If it's empty, fall back to checking `thermal_zone*`.
-        temperature_vec = get_from_thermal_zone(temp_type, filter)?;
+    let wants_temperatures = sensor_types.contains(&SensorType::Temperature);
+    if wants_temperatures
+        && !temperature_vec
+            .iter()
+            .any(|sensor| sensor.sensor_type == SensorType::Temperature)
+    {
+        // If there are no temperatures, fall back to checking `thermal_zone*`.
+        temperature_vec.extend(get_from_thermal_zone(temp_type, filter)?);
     }
 
     #[cfg(feature = "nvidia")]
-    {
+    if wants_temperatures {
         super::nvidia::add_nvidia_data(&mut temperature_vec, temp_type, filter)?;
     }
 
     Ok(Some(temperature_vec))
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_hwmon_sensor_type() {
+        assert_eq!(
+            hwmon_sensor_type("temp1_input"),
+            Some(SensorType::Temperature)
+        );
+        assert_eq!(hwmon_sensor_type("fan12_input"), Some(SensorType::Fan));
+        assert_eq!(hwmon_sensor_type("in0_input"), Some(SensorType::Voltage));
+        assert_eq!(hwmon_sensor_type("power1_average"), Some(SensorType::Power));
+        assert_eq!(hwmon_sensor_type("power1_input"), Some(SensorType::Power));
+        assert_eq!(hwmon_sensor_type("temp1_label"), None);
+        assert_eq!(hwmon_sensor_type("fan1_min"), None);
+        assert_eq!(hwmon_sensor_type("intrusion0_alarm"), None);
+        assert_eq!(hwmon_sensor_type("temp_input"), None);
+        assert_eq!(hwmon_sensor_type("name"), None);
+    }
+}
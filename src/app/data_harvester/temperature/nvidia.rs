@@ -1,8 +1,8 @@
 use nvml_wrapper::enum_wrappers::device::TemperatureSensor;
 
 use super::{
-    convert_celsius_to_fahrenheit, convert_celsius_to_kelvin, is_temp_filtered, TempHarvest,
-    TemperatureType,
+    convert_celsius_to_fahrenheit, convert_celsius_to_kelvin, is_temp_filtered, SensorType,
+    TempHarvest, TemperatureType,
 };
 use crate::app::Filter;
 use crate::data_harvester::nvidia::NVML_DATA;
@@ -27,7 +27,11 @@ pub fn add_nvidia_data(
                                 }
                             };
 
-                            temperature_vec.push(TempHarvest { name, temperature });
+                            temperature_vec.push(TempHarvest {
+                                name,
+                                value: temperature,
+                                sensor_type: SensorType::Temperature,
+                            });
                         }
                     }
                 }
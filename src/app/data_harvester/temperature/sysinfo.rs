@@ -3,8 +3,8 @@
 use anyhow::Result;
 
 use super::{
-    convert_celsius_to_fahrenheit, convert_celsius_to_kelvin, is_temp_filtered, TempHarvest,
-    TemperatureType,
+    convert_celsius_to_fahrenheit, convert_celsius_to_kelvin, is_temp_filtered, SensorType,
+    TempHarvest, TemperatureType,
 };
 use crate::app::Filter;
 
@@ -22,13 +22,14 @@ pub fn get_temperature_data(
         if is_temp_filtered(filter, &name) {
             temperature_vec.push(TempHarvest {
                 name,
-                temperature: match temp_type {
+                value: match temp_type {
                     TemperatureType::Celsius => component.temperature(),
                     TemperatureType::Kelvin => convert_celsius_to_kelvin(component.temperature()),
                     TemperatureType::Fahrenheit => {
                         convert_celsius_to_fahrenheit(component.temperature())
                     }
                 },
+                sensor_type: SensorType::Temperature,
             });
         }
     }
@@ -50,11 +51,12 @@ pub fn get_temperature_data(
                     if let Some(temp) = temp.as_temperature() {
                         temperature_vec.push(TempHarvest {
                             name,
-                            temperature: match temp_type {
+                            value: match temp_type {
                                 TemperatureType::Celsius => temp.celsius(),
                                 TemperatureType::Kelvin => temp.kelvin(),
                                 TemperatureType::Fahrenheit => temp.fahrenheit(),
                             },
+                            sensor_type: SensorType::Temperature,
                         });
                     }
                 }
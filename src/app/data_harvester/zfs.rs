@@ -0,0 +1,220 @@
+//! ZFS pool status, as reported by the `zpool` command.
+
+/// A single ZFS pool.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ZfsPoolHarvest {
+    pub name: String,
+    /// The health of the pool, like `ONLINE` or `DEGRADED`.
+    pub health: String,
+    /// The size of the pool and how much of it is allocated and free, in bytes.
+    pub size: u64,
+    pub allocated: u64,
+    pub free: u64,
+    /// How fragmented the free space in the pool is, in percent. Pools with no free space
+    /// histogram don't report one.
+    pub fragmentation: Option<u64>,
+    /// How much of the pool is allocated, in percent.
+    pub capacity: Option<u64>,
+    /// The last or ongoing scrub or resilver.
+    pub scan: Option<ZfsScan>,
+}
+
+impl ZfsPoolHarvest {
+    /// Whether the pool is anything other than `ONLINE`.
+    pub fn is_unhealthy(&self) -> bool {
+        self.health != "ONLINE"
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ZfsScan {
+    /// The first line of the scan status, like `scrub repaired 0B in 00:01:23 with 0 errors on
+    /// Sun Oct 11 00:25:24 2026` or `scrub in progress since Sun Oct 11 00:24:01 2026`.
+    pub summary: String,
+    /// How far along an ongoing scrub or resilver is, in percent.
+    pub percent: Option<f64>,
+}
+
+/// Returns every imported ZFS pool on the system.
+#[cfg(feature = "zfs")]
+pub fn get_zfs_pools() -> Vec<ZfsPoolHarvest> {
+    fn zpool(args: &[&str]) -> Option<String> {
+        let output = std::process::Command::new("zpool")
+            .args(args)
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+
+        Some(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    let Some(list) = zpool(&["list", "-Hp", "-o", "name,size,alloc,free,frag,cap,health"]) else {
+        return Vec::new();
+    };
+
+    let mut pools = parse_zpool_list(&list);
+    if let Some(status) = zpool(&["status"]) {
+        let mut scans = parse_zpool_status(&status);
+        for pool in &mut pools {
+            pool.scan = scans.remove(&pool.name);
+        }
+    }
+
+    pools
+}
+
+#[cfg(not(feature = "zfs"))]
+pub fn get_zfs_pools() -> Vec<ZfsPoolHarvest> {
+    Vec::new()
+}
+
+/// Parses the output of `zpool list -Hp -o name,size,alloc,free,frag,cap,health`, which is one
+/// tab-separated line per pool, with unknown values written as `-`.
+#[cfg_attr(not(feature = "zfs"), allow(dead_code))]
+fn parse_zpool_list(list: &str) -> Vec<ZfsPoolHarvest> {
+    list.lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let name = fields.next()?.to_string();
+            let mut next_value = || fields.next().and_then(|field| field.parse::<u64>().ok());
+            let size = next_value().unwrap_or(0);
+            let allocated = next_value().unwrap_or(0);
+            let free = next_value().unwrap_or(0);
+            let fragmentation = next_value();
+            let capacity = next_value();
+            let health = fields.next()?.to_string();
+
+            Some(ZfsPoolHarvest {
+                name,
+                health,
+                size,
+                allocated,
+                free,
+                fragmentation,
+                capacity,
+                scan: None,
+            })
+        })
+        .collect()
+}
+
+/// Parses the scan status of each pool out of `zpool status`, which looks like:
+///
+/// ```text
+///   pool: tank
+///  state: ONLINE
+///   scan: scrub in progress since Sun Oct 11 00:24:01 2026
+///         1.23G scanned at 100M/s, 500M issued at 50M/s, 10.0G total
+///         0B repaired, 4.88% done, 00:03:10 to go
+/// config:
+/// ```
+#[cfg_attr(not(feature = "zfs"), allow(dead_code))]
+fn parse_zpool_status(status: &str) -> std::collections::HashMap<String, ZfsScan> {
+    let mut scans = std::collections::HashMap::new();
+    let mut pool: Option<&str> = None;
+    let mut scan: Option<ZfsScan> = None;
+
+    for line in status.lines() {
+        let trimmed = line.trim_start();
+        if let Some(name) = trimmed.strip_prefix("pool:") {
+            if let (Some(pool), Some(scan)) = (pool, scan.take()) {
+                scans.insert(pool.to_string(), scan);
+            }
+            pool = Some(name.trim());
+        } else if let Some(summary) = trimmed.strip_prefix("scan:") {
+            let summary = summary.trim();
+            if summary != "none requested" {
+                scan = Some(ZfsScan {
+                    summary: summary.to_string(),
+                    percent: None,
+                });
+            }
+        } else if let Some(current) = &mut scan {
+            // The progress of an ongoing scan is on the indented lines following it.
+            if trimmed.ends_with(':') || !line.starts_with(char::is_whitespace) {
+                if let (Some(pool), Some(scan)) = (pool, scan.take()) {
+                    scans.insert(pool.to_string(), scan);
+                }
+            } else if let Some((before, _)) = trimmed.split_once("% done") {
+                current.percent = before
+                    .rsplit(|c: char| c.is_whitespace() || c == ',')
+                    .next()
+                    .and_then(|percent| percent.parse().ok());
+            }
+        }
+    }
+
+    if let (Some(pool), Some(scan)) = (pool, scan) {
+        scans.insert(pool.to_string(), scan);
+    }
+
+    scans
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_zpool_list() {
+        let list = "tank\t10737418240\t5368709120\t5368709120\t12\t50\tONLINE
+backup\t1073741824\t0\t1073741824\t-\t0\tDEGRADED
+";
+        let pools = parse_zpool_list(list);
+        assert_eq!(pools.len(), 2);
+
+        assert_eq!(pools[0].name, "tank");
+        assert_eq!(pools[0].size, 10_737_418_240);
+        assert_eq!(pools[0].allocated, 5_368_709_120);
+        assert_eq!(pools[0].fragmentation, Some(12));
+        assert_eq!(pools[0].capacity, Some(50));
+        assert!(!pools[0].is_unhealthy());
+
+        assert_eq!(pools[1].fragmentation, None);
+        assert_eq!(pools[1].health, "DEGRADED");
+        assert!(pools[1].is_unhealthy());
+    }
+
+    #[test]
+    fn test_parse_zpool_status() {
+        let status = "  pool: tank
+ state: ONLINE
+  scan: scrub in progress since Sun Oct 11 00:24:01 2026
+\t1.23G scanned at 100M/s, 500M issued at 50M/s, 10.0G total
+\t0B repaired, 4.88% done, 00:03:10 to go
+config:
+
+\tNAME        STATE     READ WRITE CKSUM
+\ttank        ONLINE       0     0     0
+
+errors: No known data errors
+
+  pool: backup
+ state: ONLINE
+  scan: scrub repaired 0B in 00:01:23 with 0 errors on Sun Oct 11 00:25:24 2026
+config:
+
+  pool: scratch
+ state: ONLINE
+  scan: none requested
+config:
+";
+        let scans = parse_zpool_status(status);
+        assert_eq!(scans.len(), 2);
+        assert_eq!(
+            scans["tank"],
+            ZfsScan {
+                summary: "scrub in progress since Sun Oct 11 00:24:01 2026".to_string(),
+                percent: Some(4.88),
+            }
+        );
+        assert_eq!(
+            scans["backup"].summary,
+            "scrub repaired 0B in 00:01:23 with 0 errors on Sun Oct 11 00:25:24 2026"
+        );
+        assert_eq!(scans["backup"].percent, None);
+        assert!(!scans.contains_key("scratch"));
+    }
+}
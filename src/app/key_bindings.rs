@@ -0,0 +1,541 @@
+//! The keys for actions that can be bound to other keys in the `[keybindings]` section of the
+//! config, like to move one off a key another widget already uses.
+
+use std::{
+    collections::{HashMap, HashSet},
+    slice,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{
+    constants::MAX_KEY_TIMEOUT_IN_MILLISECONDS,
+    utils::error::{BottomError, Result},
+};
+
+/// Something that can be bound to keys.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    Search,
+    KillProcess,
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    JumpToFirst,
+    JumpToLast,
+    MoveWidgetLeft,
+    MoveWidgetRight,
+    MoveWidgetUp,
+    MoveWidgetDown,
+    /// Also what focuses a terminal widget, so typing goes to it.
+    ExpandWidget,
+    Help,
+    Freeze,
+    ResetData,
+    DumpSnapshot,
+    EditLayout,
+    ToggleTreeMode,
+    SortMenu,
+    ClearTerminal,
+    WrapTerminalOutput,
+    SaveTerminalOutput,
+    /// Opens a line at the bottom to run any of these by name, like `:quit`.
+    CommandLine,
+    /// Shows the layout tab at this index.
+    SwitchTab(usize),
+}
+
+/// Every action, with its name in the config and the keys bound to it unless the config says
+/// otherwise.
+pub const DEFAULT_KEY_BINDINGS: &[(Action, &str, &[&str])] = &[
+    (Action::Quit, "quit", &["q", "ctrl-c"]),
+    (Action::Search, "search", &["/", "ctrl-f"]),
+    (Action::KillProcess, "kill_process", &["f9", "d d"]),
+    (Action::MoveUp, "move_up", &["up", "k"]),
+    (Action::MoveDown, "move_down", &["down", "j"]),
+    (Action::MoveLeft, "move_left", &["left", "alt-h"]),
+    (Action::MoveRight, "move_right", &["right", "alt-l"]),
+    (Action::JumpToFirst, "jump_to_first", &["home", "g g"]),
+    (Action::JumpToLast, "jump_to_last", &["end", "G"]),
+    (
+        Action::MoveWidgetLeft,
+        "move_widget_left",
+        &["ctrl-left", "shift-left", "H", "A"],
+    ),
+    (
+        Action::MoveWidgetRight,
+        "move_widget_right",
+        &["ctrl-right", "shift-right", "L", "D"],
+    ),
+    (
+        Action::MoveWidgetUp,
+        "move_widget_up",
+        &["ctrl-up", "shift-up", "K", "W"],
+    ),
+    (
+        Action::MoveWidgetDown,
+        "move_widget_down",
+        &["ctrl-down", "shift-down", "J", "S"],
+    ),
+    (Action::ExpandWidget, "expand_widget", &["e"]),
+    (Action::Help, "help", &["?"]),
+    (Action::Freeze, "freeze", &["f"]),
+    (Action::ResetData, "reset_data", &["ctrl-r"]),
+    (Action::DumpSnapshot, "dump_snapshot", &["ctrl-s"]),
+    (Action::EditLayout, "edit_layout", &["ctrl-o"]),
+    (Action::ToggleTreeMode, "toggle_tree_mode", &["f5"]),
+    (Action::SortMenu, "sort_menu", &["f6"]),
+    (Action::ClearTerminal, "clear_terminal", &["f9"]),
+    (Action::WrapTerminalOutput, "wrap_terminal_output", &["f7"]),
+    (Action::SaveTerminalOutput, "save_terminal_output", &["f8"]),
+    (Action::CommandLine, "command_line", &[]),
+    (Action::SwitchTab(0), "switch_tab_1", &["alt-1"]),
+    (Action::SwitchTab(1), "switch_tab_2", &["alt-2"]),
+    (Action::SwitchTab(2), "switch_tab_3", &["alt-3"]),
+    (Action::SwitchTab(3), "switch_tab_4", &["alt-4"]),
+    (Action::SwitchTab(4), "switch_tab_5", &["alt-5"]),
+    (Action::SwitchTab(5), "switch_tab_6", &["alt-6"]),
+    (Action::SwitchTab(6), "switch_tab_7", &["alt-7"]),
+    (Action::SwitchTab(7), "switch_tab_8", &["alt-8"]),
+    (Action::SwitchTab(8), "switch_tab_9", &["alt-9"]),
+];
+
+/// What the vim preset binds differently from the defaults above.
+const VIM_KEY_BINDINGS: &[(Action, &[&str])] = &[
+    (Action::MoveLeft, &["left", "h"]),
+    (Action::MoveRight, &["right", "l"]),
+    (
+        Action::MoveWidgetLeft,
+        &["ctrl-left", "shift-left", "H", "ctrl-w h"],
+    ),
+    (
+        Action::MoveWidgetRight,
+        &["ctrl-right", "shift-right", "L", "ctrl-w l"],
+    ),
+    (
+        Action::MoveWidgetUp,
+        &["ctrl-up", "shift-up", "K", "ctrl-w k"],
+    ),
+    (
+        Action::MoveWidgetDown,
+        &["ctrl-down", "shift-down", "J", "ctrl-w j"],
+    ),
+    (Action::CommandLine, &[":"]),
+];
+
+/// A set of keys to start from, before what's in the `[keybindings]` section of the config.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KeyBindingPreset {
+    #[default]
+    Default,
+    /// Moving with `hjkl` and between widgets with `ctrl-w`, along with `:` to run commands.
+    Vim,
+}
+
+impl FromStr for KeyBindingPreset {
+    type Err = BottomError;
+
+    fn from_str(preset: &str) -> Result<Self> {
+        match preset.to_lowercase().as_str() {
+            "default" => Ok(KeyBindingPreset::Default),
+            "vim" => Ok(KeyBindingPreset::Vim),
+            _ => Err(BottomError::ConfigError(format!(
+                "\"{preset}\" is an invalid key binding preset, use \"<default|vim>\"."
+            ))),
+        }
+    }
+}
+
+impl Action {
+    /// Whether this only does anything in a terminal widget, where it takes precedence over
+    /// anything else on the same key.
+    pub fn is_terminal_action(&self) -> bool {
+        matches!(
+            self,
+            Action::ClearTerminal | Action::WrapTerminalOutput | Action::SaveTerminalOutput
+        )
+    }
+
+    /// Whether this moves around within a widget or dialog, which dialogs allow along with quitting.
+    pub fn is_movement(&self) -> bool {
+        matches!(
+            self,
+            Action::MoveUp
+                | Action::MoveDown
+                | Action::MoveLeft
+                | Action::MoveRight
+                | Action::JumpToFirst
+                | Action::JumpToLast
+        )
+    }
+
+    /// The action run by a command typed on the command line, which is its name in the config or
+    /// `q` for quitting.
+    pub fn from_command(command: &str) -> Option<Action> {
+        match command {
+            "q" | "q!" => Some(Action::Quit),
+            _ => DEFAULT_KEY_BINDINGS
+                .iter()
+                .find(|(_, name, _)| *name == command)
+                .map(|(action, _, _)| *action),
+        }
+    }
+}
+
+/// A key along with the modifiers held down with it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct KeyBinding {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let modifiers =
+            modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+
+        // Shift is already in the case of a letter, and isn't reported the same way everywhere.
+        if let KeyCode::Char(c) = code {
+            let c = if modifiers.contains(KeyModifiers::SHIFT) {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            };
+            KeyBinding {
+                code: KeyCode::Char(c),
+                modifiers: modifiers - KeyModifiers::SHIFT,
+            }
+        } else {
+            KeyBinding { code, modifiers }
+        }
+    }
+}
+
+impl FromStr for KeyBinding {
+    type Err = BottomError;
+
+    /// Parses keys like `q`, `F9`, `ctrl-left` or `shift-alt-up`.
+    fn from_str(key: &str) -> Result<Self> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = key;
+        while let Some((prefix, remainder)) = rest.split_once('-') {
+            let modifier = match prefix.to_lowercase().as_str() {
+                "ctrl" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => break,
+            };
+            // The key itself can be a dash, like `ctrl--`.
+            if remainder.is_empty() {
+                break;
+            }
+            modifiers |= modifier;
+            rest = remainder;
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_lowercase().as_str() {
+                "space" => KeyCode::Char(' '),
+                "enter" => KeyCode::Enter,
+                "esc" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backspace" => KeyCode::Backspace,
+                "delete" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n) if (1..=12).contains(&n) => KeyCode::F(n),
+                    _ => {
+                        return Err(BottomError::ConfigError(format!(
+                            "'{key}' is not a valid key."
+                        )))
+                    }
+                },
+            },
+        };
+
+        Ok(KeyBinding::new(code, modifiers))
+    }
+}
+
+/// The keys pressed so far towards a binding of several keys, like `g g`.
+#[derive(Debug, Default)]
+pub struct PendingKeys {
+    keys: Vec<KeyBinding>,
+    last_press: Option<Instant>,
+}
+
+/// Which actions each key, or sequence of keys, is bound to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyBindings {
+    table: HashMap<Vec<KeyBinding>, Vec<Action>>,
+    /// Every sequence that is the start of a longer one in `table`.
+    prefixes: HashSet<Vec<KeyBinding>>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        KeyBindings::new(KeyBindingPreset::Default, &HashMap::new())
+            .expect("the default keys are all valid")
+    }
+}
+
+impl KeyBindings {
+    /// Binds each action to its keys in `overrides`, keyed by the action's name, or to its keys in
+    /// `preset` if it isn't there. An empty list of keys leaves an action unbound. A binding can be
+    /// several keys pressed one after another, separated by spaces, like `g g`.
+    pub fn new(preset: KeyBindingPreset, overrides: &HashMap<String, Vec<String>>) -> Result<Self> {
+        if let Some(name) = overrides
+            .keys()
+            .find(|name| !DEFAULT_KEY_BINDINGS.iter().any(|(_, n, _)| n == name))
+        {
+            return Err(BottomError::ConfigError(format!(
+                "'{name}' is not an action that can be bound to keys."
+            )));
+        }
+
+        let preset_keys = |action: &Action| match preset {
+            KeyBindingPreset::Default => None,
+            KeyBindingPreset::Vim => VIM_KEY_BINDINGS
+                .iter()
+                .find(|(vim_action, _)| vim_action == action)
+                .map(|(_, keys)| keys.to_vec()),
+        };
+
+        let mut table: HashMap<Vec<KeyBinding>, Vec<Action>> = HashMap::new();
+        let mut prefixes = HashSet::new();
+        for (action, name, default_keys) in DEFAULT_KEY_BINDINGS {
+            let keys = match overrides.get(*name) {
+                Some(keys) => keys.iter().map(String::as_str).collect(),
+                None => preset_keys(action).unwrap_or_else(|| default_keys.to_vec()),
+            };
+            for key in keys {
+                let sequence = key
+                    .split_whitespace()
+                    .map(str::parse)
+                    .collect::<Result<Vec<KeyBinding>>>()?;
+                if sequence.is_empty() {
+                    return Err(BottomError::ConfigError(format!(
+                        "'{name}' can't be bound to an empty key."
+                    )));
+                }
+                for length in 1..sequence.len() {
+                    prefixes.insert(sequence[..length].to_vec());
+                }
+                table.entry(sequence).or_default().push(*action);
+            }
+        }
+
+        Ok(KeyBindings { table, prefixes })
+    }
+
+    /// The actions bound to the key pressed in `event`, in the order they're listed above. If it
+    /// finishes a sequence in `pending`, that sequence's actions are returned instead, while a key
+    /// that starts one is both remembered and looked up on its own, like `d` is still used by
+    /// other widgets when `d d` kills a process.
+    pub fn actions(&self, pending: &mut PendingKeys, event: &KeyEvent) -> Vec<Action> {
+        let key = KeyBinding::new(event.code, event.modifiers);
+        let now = Instant::now();
+        if pending.last_press.is_some_and(|last_press| {
+            now.duration_since(last_press) > Duration::from_millis(MAX_KEY_TIMEOUT_IN_MILLISECONDS)
+        }) {
+            pending.keys.clear();
+        }
+        pending.last_press = Some(now);
+
+        pending.keys.push(key);
+        if pending.keys.len() > 1 {
+            if let Some(actions) = self.table.get(&pending.keys) {
+                pending.keys.clear();
+                return actions.clone();
+            }
+        }
+        if !self.prefixes.contains(&pending.keys) {
+            pending.keys.clear();
+            if self.prefixes.contains(slice::from_ref(&key)) {
+                pending.keys.push(key);
+            }
+        }
+
+        self.table
+            .get(slice::from_ref(&key))
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn event(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn test_parse_keys() {
+        let parse = |key: &str| key.parse::<KeyBinding>().unwrap();
+
+        assert_eq!(
+            parse("q"),
+            KeyBinding::new(KeyCode::Char('q'), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            parse("F9"),
+            KeyBinding::new(KeyCode::F(9), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            parse("ctrl-alt-left"),
+            KeyBinding::new(KeyCode::Left, KeyModifiers::CONTROL | KeyModifiers::ALT)
+        );
+        assert_eq!(
+            parse("ctrl--"),
+            KeyBinding::new(KeyCode::Char('-'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            parse("-"),
+            KeyBinding::new(KeyCode::Char('-'), KeyModifiers::NONE)
+        );
+        assert_eq!(parse("shift-h"), parse("H"));
+        assert!("f13".parse::<KeyBinding>().is_err());
+        assert!("hyper-q".parse::<KeyBinding>().is_err());
+    }
+
+    #[test]
+    fn test_default_bindings() {
+        let bindings = KeyBindings::default();
+        let mut pending = PendingKeys::default();
+
+        assert_eq!(
+            bindings.actions(&mut pending, &event(KeyCode::F(9), KeyModifiers::NONE)),
+            [Action::KillProcess, Action::ClearTerminal]
+        );
+        // Terminals report shift with capital letters, or not.
+        assert_eq!(
+            bindings.actions(
+                &mut pending,
+                &event(KeyCode::Char('H'), KeyModifiers::SHIFT)
+            ),
+            [Action::MoveWidgetLeft]
+        );
+        assert_eq!(
+            bindings.actions(&mut pending, &event(KeyCode::Char('H'), KeyModifiers::NONE)),
+            [Action::MoveWidgetLeft]
+        );
+        assert!(bindings
+            .actions(&mut pending, &event(KeyCode::Char('q'), KeyModifiers::ALT))
+            .is_empty());
+        assert!(bindings
+            .actions(&mut pending, &event(KeyCode::Char(':'), KeyModifiers::NONE))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_key_sequences() {
+        let bindings = KeyBindings::default();
+        let mut pending = PendingKeys::default();
+        let mut press =
+            |c| bindings.actions(&mut pending, &event(KeyCode::Char(c), KeyModifiers::NONE));
+
+        assert!(press('g').is_empty());
+        assert_eq!(press('g'), [Action::JumpToFirst]);
+        // A finished sequence doesn't start the next one.
+        assert!(press('g').is_empty());
+        assert!(press('d').is_empty());
+        assert_eq!(press('d'), [Action::KillProcess]);
+
+        // Anything in between starts over.
+        assert!(press('d').is_empty());
+        assert_eq!(press('j'), [Action::MoveDown]);
+        assert!(press('d').is_empty());
+        assert!(press('g').is_empty());
+        assert_eq!(press('g'), [Action::JumpToFirst]);
+    }
+
+    #[test]
+    fn test_vim_preset() {
+        let bindings = KeyBindings::new(KeyBindingPreset::Vim, &HashMap::new()).unwrap();
+        let mut pending = PendingKeys::default();
+
+        assert_eq!(
+            bindings.actions(&mut pending, &event(KeyCode::Char('h'), KeyModifiers::NONE)),
+            [Action::MoveLeft]
+        );
+        assert_eq!(
+            bindings.actions(&mut pending, &event(KeyCode::Char(':'), KeyModifiers::NONE)),
+            [Action::CommandLine]
+        );
+        assert!(bindings
+            .actions(
+                &mut pending,
+                &event(KeyCode::Char('w'), KeyModifiers::CONTROL)
+            )
+            .is_empty());
+        assert_eq!(
+            bindings.actions(&mut pending, &event(KeyCode::Char('l'), KeyModifiers::NONE)),
+            [Action::MoveWidgetRight]
+        );
+        assert_eq!(
+            "Vim".parse::<KeyBindingPreset>().unwrap(),
+            KeyBindingPreset::Vim
+        );
+        assert!("emacs".parse::<KeyBindingPreset>().is_err());
+    }
+
+    #[test]
+    fn test_overridden_bindings() {
+        let overrides = HashMap::from([
+            ("clear_terminal".to_string(), vec!["ctrl-l".to_string()]),
+            ("help".to_string(), vec![]),
+        ]);
+        let bindings = KeyBindings::new(KeyBindingPreset::Default, &overrides).unwrap();
+        let mut pending = PendingKeys::default();
+
+        assert_eq!(
+            bindings.actions(&mut pending, &event(KeyCode::F(9), KeyModifiers::NONE)),
+            [Action::KillProcess]
+        );
+        assert_eq!(
+            bindings.actions(
+                &mut pending,
+                &event(KeyCode::Char('l'), KeyModifiers::CONTROL)
+            ),
+            [Action::ClearTerminal]
+        );
+        assert!(bindings
+            .actions(&mut pending, &event(KeyCode::Char('?'), KeyModifiers::NONE))
+            .is_empty());
+
+        let unknown = HashMap::from([("explode".to_string(), vec!["x".to_string()])]);
+        assert!(KeyBindings::new(KeyBindingPreset::Default, &unknown).is_err());
+        let empty = HashMap::from([("quit".to_string(), vec![" ".to_string()])]);
+        assert!(KeyBindings::new(KeyBindingPreset::Default, &empty).is_err());
+    }
+
+    #[test]
+    fn test_commands() {
+        assert_eq!(Action::from_command("q"), Some(Action::Quit));
+        assert_eq!(
+            Action::from_command("kill_process"),
+            Some(Action::KillProcess)
+        );
+        assert_eq!(
+            Action::from_command("switch_tab_2"),
+            Some(Action::SwitchTab(1))
+        );
+        assert_eq!(Action::from_command("explode"), None);
+    }
+}
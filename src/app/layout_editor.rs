@@ -0,0 +1,644 @@
+//! Editing the layout from within bottom, with the result written back into the `[[row]]` sections
+//! of the config, so nested rows, columns and ratios don't have to be worked out by hand.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+use toml_edit::{Document, Item};
+
+use crate::{
+    app::layout_manager::BottomWidgetType,
+    constants::{DEFAULT_BATTERY_LAYOUT, DEFAULT_LAYOUT},
+    options::{
+        layout_options::{FinalWidget, Row, RowChildren},
+        Config,
+    },
+    utils::error::{BottomError, Result},
+};
+
+/// The widget types that can be picked in the editor, in the order they are cycled through.
+const WIDGET_TYPES: [&str; 21] = [
+    "cpu",
+    "mem",
+    "net",
+    "proc",
+    "temp",
+    "disk",
+    "battery",
+    "terminal",
+    "uptime",
+    "connections",
+    "dashboard",
+    "discovery",
+    "containers",
+    "pressure",
+    "wifi",
+    "raid",
+    "zfs",
+    "cgroups",
+    "logs",
+    "jobs",
+    "empty",
+];
+
+/// Something in the layout that can be selected, by its indices from the outermost in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Entry {
+    Row(usize),
+    /// A widget directly in a row.
+    Widget(usize, usize),
+    /// A column of widgets in a row.
+    Column(usize, usize),
+    /// A widget in a column.
+    ColumnWidget(usize, usize, usize),
+}
+
+/// The rows of a layout being edited, and what is selected in them.
+pub struct LayoutEditor {
+    pub rows: Vec<Row>,
+    /// The index of the `[[tab]]` the rows belong to, if the config has tabs.
+    pub tab: Option<usize>,
+    config_path: PathBuf,
+    /// The index of the selected entry in [`LayoutEditor::entries`].
+    pub selected: usize,
+    /// Why the layout couldn't be saved, if it couldn't.
+    pub error: Option<String>,
+}
+
+/// The rows as they are written to the config.
+#[derive(Serialize)]
+struct LayoutRows<'a> {
+    row: &'a [Row],
+}
+
+impl LayoutEditor {
+    /// Starts editing the layout in the config at `config_path`, or the rows of the tab at
+    /// `tab_index` if it has tabs. Without a layout in the config, the default one is edited.
+    pub fn open(config_path: &Path, tab_index: usize, use_battery: bool) -> Result<Self> {
+        let config: Config = toml_edit::de::from_str(&read_config_text(config_path)?)?;
+        let (rows, tab) = match (config.tab, config.row) {
+            (Some(tabs), _) => {
+                let rows = tabs
+                    .into_iter()
+                    .nth(tab_index)
+                    .and_then(|tab| tab.row)
+                    .unwrap_or_default();
+                (rows, Some(tab_index))
+            }
+            (None, Some(rows)) => (rows, None),
+            (None, None) => {
+                let default_layout = if use_battery {
+                    DEFAULT_BATTERY_LAYOUT
+                } else {
+                    DEFAULT_LAYOUT
+                };
+                let rows = toml_edit::de::from_str::<Config>(default_layout)?
+                    .row
+                    .unwrap_or_default();
+                (rows, None)
+            }
+        };
+
+        Ok(LayoutEditor {
+            rows,
+            tab,
+            config_path: config_path.to_path_buf(),
+            selected: 0,
+            error: None,
+        })
+    }
+
+    /// Every row, widget and column in the order they are listed, which is top to bottom and
+    /// left to right.
+    pub fn entries(&self) -> Vec<Entry> {
+        let mut entries = Vec::new();
+        for (row_index, row) in self.rows.iter().enumerate() {
+            entries.push(Entry::Row(row_index));
+            for (child_index, child) in row.child.iter().flatten().enumerate() {
+                match child {
+                    RowChildren::Widget(_) => entries.push(Entry::Widget(row_index, child_index)),
+                    RowChildren::Col { child, .. } => {
+                        entries.push(Entry::Column(row_index, child_index));
+                        entries.extend(
+                            (0..child.len())
+                                .map(|index| Entry::ColumnWidget(row_index, child_index, index)),
+                        );
+                    }
+                }
+            }
+        }
+        entries
+    }
+
+    pub fn selected_entry(&self) -> Option<Entry> {
+        self.entries().get(self.selected).copied()
+    }
+
+    fn select_entry(&mut self, entry: Entry) {
+        self.selected = self
+            .entries()
+            .iter()
+            .position(|other| *other == entry)
+            .unwrap_or_default();
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.entries().len() {
+            self.selected += 1;
+        }
+    }
+
+    /// The widget at `entry`, if it is one.
+    pub fn widget(&self, entry: Entry) -> Option<&FinalWidget> {
+        match entry {
+            Entry::Widget(row, child) => match self.rows[row].child.as_ref()?.get(child)? {
+                RowChildren::Widget(widget) => Some(widget),
+                RowChildren::Col { .. } => None,
+            },
+            Entry::ColumnWidget(row, child, index) => {
+                match self.rows[row].child.as_ref()?.get(child)? {
+                    RowChildren::Col { child, .. } => child.get(index),
+                    RowChildren::Widget(_) => None,
+                }
+            }
+            Entry::Row(_) | Entry::Column(..) => None,
+        }
+    }
+
+    fn widget_mut(&mut self, entry: Entry) -> Option<&mut FinalWidget> {
+        match entry {
+            Entry::Widget(row, child) => match self.rows[row].child.as_mut()?.get_mut(child)? {
+                RowChildren::Widget(widget) => Some(widget),
+                RowChildren::Col { .. } => None,
+            },
+            Entry::ColumnWidget(row, child, index) => {
+                match self.rows[row].child.as_mut()?.get_mut(child)? {
+                    RowChildren::Col { child, .. } => child.get_mut(index),
+                    RowChildren::Widget(_) => None,
+                }
+            }
+            Entry::Row(_) | Entry::Column(..) => None,
+        }
+    }
+
+    /// The ratio of the row, column or widget at `entry`, which is 1 when unset.
+    pub fn ratio(&self, entry: Entry) -> u32 {
+        let ratio = match entry {
+            Entry::Row(row) => self.rows[row].ratio,
+            Entry::Column(row, child) => match self.rows[row].child.as_ref().map(|c| &c[child]) {
+                Some(RowChildren::Col { ratio, .. }) => *ratio,
+                _ => None,
+            },
+            Entry::Widget(..) | Entry::ColumnWidget(..) => {
+                self.widget(entry).and_then(|widget| widget.ratio)
+            }
+        };
+        ratio.unwrap_or(1)
+    }
+
+    fn ratio_mut(&mut self, entry: Entry) -> Option<&mut Option<u32>> {
+        match entry {
+            Entry::Row(row) => Some(&mut self.rows[row].ratio),
+            Entry::Column(row, child) => match self.rows[row].child.as_mut()?.get_mut(child)? {
+                RowChildren::Col { ratio, .. } => Some(ratio),
+                RowChildren::Widget(_) => None,
+            },
+            Entry::Widget(..) | Entry::ColumnWidget(..) => {
+                self.widget_mut(entry).map(|widget| &mut widget.ratio)
+            }
+        }
+    }
+
+    /// Grows or shrinks the selected entry's share of its parent by one, down to a ratio of 1.
+    pub fn resize_selected(&mut self, grow: bool) {
+        let Some(entry) = self.selected_entry() else {
+            return;
+        };
+        if let Some(ratio) = self.ratio_mut(entry) {
+            let current = ratio.unwrap_or(1);
+            *ratio = Some(if grow {
+                current + 1
+            } else {
+                current.saturating_sub(1).max(1)
+            });
+        }
+    }
+
+    /// Changes the selected widget to the next or previous widget type.
+    pub fn cycle_widget_type(&mut self, forward: bool) {
+        let Some(entry) = self.selected_entry() else {
+            return;
+        };
+        let Some(widget) = self.widget_mut(entry) else {
+            return;
+        };
+
+        // Battery widgets only exist with the battery feature.
+        let types = WIDGET_TYPES
+            .iter()
+            .filter(|name| name.parse::<BottomWidgetType>().is_ok())
+            .collect::<Vec<_>>();
+        let current = widget.widget_type.parse::<BottomWidgetType>().ok();
+        let next = match types
+            .iter()
+            .position(|name| name.parse::<BottomWidgetType>().ok() == current)
+        {
+            Some(index) if forward => (index + 1) % types.len(),
+            Some(index) => (index + types.len() - 1) % types.len(),
+            None => 0,
+        };
+        widget.widget_type = types[next].to_string();
+    }
+
+    /// Swaps the selected entry with the one before or after it in the same row, column or
+    /// layout.
+    pub fn move_selected(&mut self, up: bool) {
+        let Some(entry) = self.selected_entry() else {
+            return;
+        };
+        let swap = |len: usize, index: usize| {
+            if up {
+                index.checked_sub(1)
+            } else {
+                Some(index + 1).filter(|other| *other < len)
+            }
+        };
+
+        let moved = match entry {
+            Entry::Row(row) => swap(self.rows.len(), row).map(|other| {
+                self.rows.swap(row, other);
+                Entry::Row(other)
+            }),
+            Entry::Widget(row, child) | Entry::Column(row, child) => {
+                let children = self.rows[row].child.get_or_insert_with(Vec::new);
+                swap(children.len(), child).map(|other| {
+                    children.swap(child, other);
+                    match entry {
+                        Entry::Widget(..) => Entry::Widget(row, other),
+                        _ => Entry::Column(row, other),
+                    }
+                })
+            }
+            Entry::ColumnWidget(row, child, index) => {
+                match self.rows[row].child.as_mut().and_then(|c| c.get_mut(child)) {
+                    Some(RowChildren::Col { child: widgets, .. }) => swap(widgets.len(), index)
+                        .map(|other| {
+                            widgets.swap(index, other);
+                            Entry::ColumnWidget(row, child, other)
+                        }),
+                    _ => None,
+                }
+            }
+        };
+        if let Some(moved) = moved {
+            self.select_entry(moved);
+        }
+    }
+
+    /// Adds an empty widget after the selected one, or at the end of the selected row or column.
+    pub fn add_widget(&mut self) {
+        let Some(entry) = self.selected_entry() else {
+            self.add_row();
+            return;
+        };
+        let widget = new_widget();
+
+        let added = match entry {
+            Entry::Row(row) => {
+                let children = self.rows[row].child.get_or_insert_with(Vec::new);
+                children.push(RowChildren::Widget(widget));
+                Entry::Widget(row, children.len() - 1)
+            }
+            Entry::Widget(row, child) => {
+                let children = self.rows[row].child.get_or_insert_with(Vec::new);
+                children.insert(child + 1, RowChildren::Widget(widget));
+                Entry::Widget(row, child + 1)
+            }
+            Entry::Column(row, child) | Entry::ColumnWidget(row, child, _) => {
+                match self.rows[row].child.as_mut().and_then(|c| c.get_mut(child)) {
+                    Some(RowChildren::Col { child: widgets, .. }) => {
+                        let index = match entry {
+                            Entry::ColumnWidget(_, _, index) => index + 1,
+                            _ => widgets.len(),
+                        };
+                        widgets.insert(index, widget);
+                        Entry::ColumnWidget(row, child, index)
+                    }
+                    _ => return,
+                }
+            }
+        };
+        self.select_entry(added);
+    }
+
+    /// Adds a row with an empty widget after the selected row.
+    pub fn add_row(&mut self) {
+        let index = match self.selected_entry() {
+            Some(
+                Entry::Row(row)
+                | Entry::Widget(row, _)
+                | Entry::Column(row, _)
+                | Entry::ColumnWidget(row, ..),
+            ) => row + 1,
+            None => 0,
+        };
+        self.rows.insert(
+            index,
+            Row {
+                ratio: None,
+                child: Some(vec![RowChildren::Widget(new_widget())]),
+            },
+        );
+        self.select_entry(Entry::Widget(index, 0));
+    }
+
+    /// Puts the selected widget in a column of its own, so widgets can be stacked under it, or
+    /// takes the only widget of the selected column back out of it.
+    pub fn toggle_column(&mut self) {
+        let Some(entry) = self.selected_entry() else {
+            return;
+        };
+        let (row, child) = match entry {
+            Entry::Widget(row, child)
+            | Entry::Column(row, child)
+            | Entry::ColumnWidget(row, child, _) => (row, child),
+            Entry::Row(_) => return,
+        };
+        let Some(slot) = self.rows[row].child.as_mut().and_then(|c| c.get_mut(child)) else {
+            return;
+        };
+
+        let toggled = match slot {
+            RowChildren::Widget(widget) => {
+                let mut widget = widget.clone();
+                *slot = RowChildren::Col {
+                    ratio: widget.ratio.take(),
+                    child: vec![widget],
+                };
+                Entry::ColumnWidget(row, child, 0)
+            }
+            RowChildren::Col {
+                ratio,
+                child: widgets,
+            } if widgets.len() == 1 => {
+                let mut widget = widgets[0].clone();
+                widget.ratio = *ratio;
+                *slot = RowChildren::Widget(widget);
+                Entry::Widget(row, child)
+            }
+            RowChildren::Col { .. } => return,
+        };
+        self.select_entry(toggled);
+    }
+
+    /// Removes the selected entry, along with any row or column it leaves empty.
+    pub fn remove_selected(&mut self) {
+        let Some(entry) = self.selected_entry() else {
+            return;
+        };
+        match entry {
+            Entry::Row(row) => {
+                self.rows.remove(row);
+            }
+            Entry::Widget(row, child) | Entry::Column(row, child) => {
+                if let Some(children) = &mut self.rows[row].child {
+                    children.remove(child);
+                }
+            }
+            Entry::ColumnWidget(row, child, index) => {
+                if let Some(children) = &mut self.rows[row].child {
+                    if let RowChildren::Col { child: widgets, .. } = &mut children[child] {
+                        widgets.remove(index);
+                        if widgets.is_empty() {
+                            children.remove(child);
+                        }
+                    }
+                }
+            }
+        }
+        self.rows
+            .retain(|row| row.child.as_ref().is_some_and(|c| !c.is_empty()));
+        self.selected = self.selected.min(self.entries().len().saturating_sub(1));
+    }
+
+    /// Checks that the layout can be built, with at least one widget in it.
+    fn validate(&self) -> Result<()> {
+        let mut iter_id = 0;
+        let mut total_height_ratio = 0;
+        let mut default_widget_id = 1;
+        let mut default_widget_count = 0;
+        for row in &self.rows {
+            row.convert_row_to_bottom_row(
+                &mut iter_id,
+                &mut total_height_ratio,
+                &mut default_widget_id,
+                &None,
+                &mut default_widget_count,
+                false,
+            )?;
+        }
+
+        if iter_id == 0 {
+            return Err(BottomError::ConfigError(
+                "the layout needs at least one widget.".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Writes the rows into the config, replacing the ones there. The rest of the config is left
+    /// as it was.
+    pub fn save(&self) -> Result<()> {
+        self.validate()?;
+
+        let mut document = read_config_text(&self.config_path)?
+            .parse::<Document>()
+            .map_err(|err| BottomError::ConfigError(err.to_string()))?;
+        let rows = toml_edit::ser::to_document(&LayoutRows { row: &self.rows })
+            .map_err(|err| BottomError::ConfigError(err.to_string()))?
+            .remove("row")
+            .map(expand_tables)
+            .unwrap_or_default();
+
+        match self.tab {
+            Some(index) => {
+                match document
+                    .get_mut("tab")
+                    .and_then(Item::as_array_of_tables_mut)
+                    .and_then(|tabs| tabs.get_mut(index))
+                {
+                    Some(tab) => {
+                        tab.insert("row", rows);
+                    }
+                    None => {
+                        return Err(BottomError::ConfigError(
+                            "the tab being edited is no longer in the config.".to_string(),
+                        ))
+                    }
+                }
+            }
+            None => {
+                document.insert("row", rows);
+            }
+        }
+
+        std::fs::write(&self.config_path, document.to_string())?;
+        Ok(())
+    }
+}
+
+fn new_widget() -> FinalWidget {
+    FinalWidget {
+        ratio: None,
+        widget_type: "empty".to_string(),
+        default: None,
+        border_color: None,
+        title_color: None,
+    }
+}
+
+/// Reads the config, which is empty if it doesn't exist yet.
+fn read_config_text(config_path: &Path) -> Result<String> {
+    match std::fs::read_to_string(config_path) {
+        Ok(text) => Ok(text),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(String::new()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Turns arrays of inline tables into arrays of tables all the way down, so the rows are written
+/// as `[[row]]` and `[[row.child]]` sections.
+fn expand_tables(item: Item) -> Item {
+    match item.into_array_of_tables() {
+        Ok(mut tables) => {
+            for table in tables.iter_mut() {
+                for (_, value) in table.iter_mut() {
+                    *value = expand_tables(std::mem::take(value));
+                }
+            }
+            Item::ArrayOfTables(tables)
+        }
+        Err(item) => item,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn widget_types(editor: &LayoutEditor) -> Vec<String> {
+        editor
+            .entries()
+            .into_iter()
+            .filter_map(|entry| editor.widget(entry))
+            .map(|widget| widget.widget_type.clone())
+            .collect()
+    }
+
+    fn temp_config(name: &str, text: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "btm_layout_editor_{name}_{}.toml",
+            std::process::id()
+        ));
+        std::fs::write(&path, text).unwrap();
+        path
+    }
+
+    #[test]
+    fn editing() {
+        let path = temp_config("editing", "");
+        let mut editor = LayoutEditor::open(&path, 0, false).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            widget_types(&editor),
+            ["cpu", "mem", "temp", "disk", "net", "proc"]
+        );
+
+        // The first row only has the CPU widget.
+        editor.select_next();
+        assert_eq!(editor.selected_entry(), Some(Entry::Widget(0, 0)));
+        editor.add_widget();
+        editor.cycle_widget_type(true);
+        assert_eq!(
+            editor.widget(Entry::Widget(0, 1)).unwrap().widget_type,
+            "cpu"
+        );
+        editor.cycle_widget_type(false);
+        editor.cycle_widget_type(false);
+        assert_eq!(
+            editor.widget(Entry::Widget(0, 1)).unwrap().widget_type,
+            "jobs"
+        );
+        editor.move_selected(true);
+        assert_eq!(editor.selected_entry(), Some(Entry::Widget(0, 0)));
+        assert_eq!(widget_types(&editor)[..2], ["jobs", "cpu"]);
+
+        editor.resize_selected(true);
+        editor.resize_selected(true);
+        assert_eq!(editor.ratio(Entry::Widget(0, 0)), 3);
+        editor.toggle_column();
+        assert_eq!(editor.selected_entry(), Some(Entry::ColumnWidget(0, 0, 0)));
+        assert_eq!(editor.ratio(Entry::Column(0, 0)), 3);
+        assert_eq!(editor.ratio(Entry::ColumnWidget(0, 0, 0)), 1);
+        editor.toggle_column();
+        assert_eq!(editor.ratio(Entry::Widget(0, 0)), 3);
+
+        editor.remove_selected();
+        assert_eq!(widget_types(&editor)[..2], ["cpu", "mem"]);
+        editor.select_previous();
+        editor.remove_selected();
+        assert_eq!(editor.rows.len(), 2);
+        assert_eq!(editor.selected_entry(), Some(Entry::Row(0)));
+    }
+
+    #[test]
+    fn saving() {
+        let path = temp_config(
+            "saving",
+            "# Kept as is.\n[flags]\nrate = 1000\n\n\
+            [[tab]]\nname = \"main\"\n[[tab.row]]\n[[tab.row.child]]\ntype = \"cpu\"\n\n\
+            [[tab]]\nname = \"procs\"\n[[tab.row]]\n[[tab.row.child]]\ntype = \"proc\"\n",
+        );
+        let mut editor = LayoutEditor::open(&path, 1, false).unwrap();
+        assert_eq!(editor.tab, Some(1));
+        assert_eq!(widget_types(&editor), ["proc"]);
+
+        editor.select_next();
+        editor.add_row();
+        editor.cycle_widget_type(true);
+        editor.toggle_column();
+        editor.add_widget();
+        editor.save().unwrap();
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(text.starts_with("# Kept as is.\n[flags]\nrate = 1000\n"));
+        assert!(text.contains("[[tab.row.child.child]]"));
+        let config: Config = toml_edit::de::from_str(&text).unwrap();
+        let tabs = config.tab.unwrap();
+        assert_eq!(tabs[0].row.as_ref().unwrap().len(), 1);
+        let rows = tabs[1].row.as_ref().unwrap();
+        assert_eq!(rows.len(), 2);
+        match &rows[1].child.as_ref().unwrap()[0] {
+            RowChildren::Col { child, .. } => {
+                let types = child
+                    .iter()
+                    .map(|w| w.widget_type.as_str())
+                    .collect::<Vec<_>>();
+                assert_eq!(types, ["cpu", "empty"]);
+            }
+            RowChildren::Widget(_) => panic!("expected a column"),
+        }
+
+        // A layout without any widgets can't be saved.
+        editor.rows.clear();
+        assert!(editor.save().is_err());
+    }
+}
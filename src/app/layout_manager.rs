@@ -1,7 +1,9 @@
 use std::collections::BTreeMap;
 
+use tui::style::Style;
 use typed_builder::*;
 
+use crate::components::custom_widget;
 use crate::constants::DEFAULT_WIDGET_ID;
 use crate::error::{BottomError, Result};
 
@@ -13,6 +15,14 @@ pub struct BottomLayout {
     pub total_row_height_ratio: u32,
 }
 
+/// One of several named layouts that can be switched between at runtime, from the `[[tab]]`
+/// sections of the config. Without any, there is a single tab with no name.
+#[derive(Clone, Debug)]
+pub struct LayoutTab {
+    pub name: String,
+    pub layout: BottomLayout,
+}
+
 // Represents a start and end coordinate in some dimension.
 type LineSegment = (u32, u32);
 
@@ -21,6 +31,15 @@ type ColumnRowMappings = (u32, BTreeMap<LineSegment, WidgetMappings>);
 type ColumnMappings = (u32, BTreeMap<LineSegment, ColumnRowMappings>);
 
 impl BottomLayout {
+    /// Returns every widget in the layout, row by row.
+    pub fn widgets(&self) -> impl Iterator<Item = &BottomWidget> {
+        self.rows
+            .iter()
+            .flat_map(|row| &row.children)
+            .flat_map(|col| &col.children)
+            .flat_map(|col_row| &col_row.children)
+    }
+
     pub fn get_movement_mappings(&mut self) {
         #[allow(clippy::suspicious_operation_groupings)] // Have to enable this, clippy really doesn't like me doing this with tuples...
         fn is_intersecting(a: LineSegment, b: LineSegment) -> bool {
@@ -880,6 +899,14 @@ pub struct BottomWidget {
     /// Bottom right corner when drawn, for mouse click detection. (x, y)
     #[builder(default = None)]
     pub bottom_right_corner: Option<(u16, u16)>,
+
+    /// The border colour set for this widget in the layout, used in place of the global one.
+    #[builder(default = None)]
+    pub border_style: Option<Style>,
+
+    /// The title colour set for this widget in the layout, used in place of the global one.
+    #[builder(default = None)]
+    pub title_style: Option<Style>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
@@ -902,6 +929,19 @@ pub enum BottomWidgetType {
     Terminal,
     Uptime,
     Connections,
+    Dashboard,
+    Discovery,
+    Containers,
+    Pressure,
+    Wifi,
+    Raid,
+    ZfsPools,
+    Cgroups,
+    Logs,
+    ScheduledJobs,
+    /// A widget registered with [`register_widget`](crate::components::custom_widget::register_widget),
+    /// by its lowercased name.
+    Custom(String),
 }
 
 impl BottomWidgetType {
@@ -912,7 +952,7 @@ impl BottomWidgetType {
 
     pub fn is_widget_graph(&self) -> bool {
         use BottomWidgetType::*;
-        matches!(self, Cpu | Net | Mem)
+        matches!(self, Cpu | Net | Mem | Pressure | Wifi)
     }
 
     pub fn get_pretty_name(&self) -> &str {
@@ -928,6 +968,17 @@ impl BottomWidgetType {
             Terminal => "Terminal",
             Uptime => "Uptime",
             Connections => "Connections",
+            Dashboard => "Dashboard",
+            Discovery => "Discovery",
+            Containers => "Containers",
+            Pressure => "Pressure",
+            Wifi => "Wi-Fi",
+            Raid => "RAID",
+            ZfsPools => "ZFS Pools",
+            Cgroups => "Cgroups",
+            Logs => "Logs",
+            ScheduledJobs => "Scheduled Jobs",
+            Custom(name) => name,
             _ => "",
         }
     }
@@ -956,6 +1007,19 @@ impl std::str::FromStr for BottomWidgetType {
             "terminal" => Ok(BottomWidgetType::Terminal),
             "uptime" => Ok(BottomWidgetType::Uptime),
             "connections" => Ok(BottomWidgetType::Connections),
+            "dashboard" => Ok(BottomWidgetType::Dashboard),
+            "discovery" => Ok(BottomWidgetType::Discovery),
+            "containers" => Ok(BottomWidgetType::Containers),
+            "pressure" | "psi" => Ok(BottomWidgetType::Pressure),
+            "wifi" | "wireless" => Ok(BottomWidgetType::Wifi),
+            "raid" | "mdstat" => Ok(BottomWidgetType::Raid),
+            "zfs" | "zpool" => Ok(BottomWidgetType::ZfsPools),
+            "cgroups" | "cgroup" => Ok(BottomWidgetType::Cgroups),
+            "logs" | "log" | "journal" => Ok(BottomWidgetType::Logs),
+            "jobs" | "cron" | "timers" => Ok(BottomWidgetType::ScheduledJobs),
+            name if custom_widget::is_registered(name) => {
+                Ok(BottomWidgetType::Custom(name.to_string()))
+            }
             _ => {
                 if cfg!(feature = "battery") {
                     Err(BottomError::ConfigError(format!(
@@ -982,6 +1046,26 @@ Supported widget names:
 |          uptime          |
 +--------------------------+
 |        connections       |
++--------------------------+
+|         dashboard        |
++--------------------------+
+|         discovery        |
++--------------------------+
+|        containers        |
++--------------------------+
+|       pressure, psi      |
++--------------------------+
+|      wifi, wireless      |
++--------------------------+
+|       raid, mdstat       |
++--------------------------+
+|        zfs, zpool        |
++--------------------------+
+|     cgroups, cgroup      |
++--------------------------+
+|    logs, log, journal    |
++--------------------------+
+|    jobs, cron, timers    |
 +--------------------------+
                 ",
                         s
@@ -1009,6 +1093,26 @@ Supported widget names:
 |          uptime          |
 +--------------------------+
 |        connections       |
++--------------------------+
+|         dashboard        |
++--------------------------+
+|         discovery        |
++--------------------------+
+|        containers        |
++--------------------------+
+|       pressure, psi      |
++--------------------------+
+|      wifi, wireless      |
++--------------------------+
+|       raid, mdstat       |
++--------------------------+
+|        zfs, zpool        |
++--------------------------+
+|     cgroups, cgroup      |
++--------------------------+
+|    logs, log, journal    |
++--------------------------+
+|    jobs, cron, timers    |
 +--------------------------+
                 ",
                         s
@@ -1030,4 +1134,17 @@ pub struct UsedWidgets {
     pub use_temp: bool,
     pub use_battery: bool,
     pub use_terminal: bool,
+    pub use_uptime: bool,
+    /// Whether to collect sockets, and their TCP statistics, for the connections widget.
+    pub use_connections: bool,
+    pub use_dashboard: bool,
+    /// Whether to look for agents on the local network, for the discovery widget.
+    pub use_discovery: bool,
+    pub use_containers: bool,
+    pub use_pressure: bool,
+    pub use_wifi: bool,
+    pub use_raid: bool,
+    pub use_zfs_pools: bool,
+    pub use_cgroups: bool,
+    pub use_scheduled_jobs: bool,
 }
@@ -0,0 +1,250 @@
+//! This file is meant to house (OS specific) implementations on how to read the virtual address
+//! space of a process, similar to `pmap -x`.
+
+use std::io;
+
+use crate::Pid;
+
+/// A single mapped region of a process' virtual address space.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemMapEntry {
+    pub start: u64,
+    pub end: u64,
+    /// The permissions of the region, e.g. `r-xp`.
+    pub perms: String,
+    pub offset: u64,
+    pub device: String,
+    pub inode: u64,
+    /// The file backing the region, or a label like `[heap]`. Empty for anonymous mappings.
+    pub pathname: String,
+}
+
+impl MemMapEntry {
+    pub fn size(&self) -> u64 {
+        self.end.saturating_sub(self.start)
+    }
+}
+
+/// The overlay listing the memory map of a single process.
+#[derive(Debug, Default)]
+pub struct MemMapOverlay {
+    pub pid: Pid,
+    pub name: String,
+    pub entries: Vec<MemMapEntry>,
+    /// The index of the first entry shown.
+    pub scroll: usize,
+    /// Why the memory map couldn't be read, if it couldn't.
+    pub error: Option<String>,
+}
+
+impl MemMapOverlay {
+    /// Reads the memory map of a process into a new overlay.
+    pub fn new(pid: Pid, name: String) -> Self {
+        let (entries, error) = match read_mem_map(pid) {
+            Ok(entries) => (entries, None),
+            Err(err) => (Vec::new(), Some(err.to_string())),
+        };
+
+        MemMapOverlay {
+            pid,
+            name,
+            entries,
+            scroll: 0,
+            error,
+        }
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.scroll = self.scroll.saturating_sub(1);
+    }
+
+    pub fn scroll_down(&mut self) {
+        if self.scroll + 1 < self.entries.len() {
+            self.scroll += 1;
+        }
+    }
+
+    pub fn scroll_to_first(&mut self) {
+        self.scroll = 0;
+    }
+
+    pub fn scroll_to_last(&mut self) {
+        self.scroll = self.entries.len().saturating_sub(1);
+    }
+}
+
+/// Reads the memory map of a process from `/proc/<pid>/maps`.
+#[cfg(target_os = "linux")]
+pub fn read_mem_map(pid: Pid) -> io::Result<Vec<MemMapEntry>> {
+    let contents = std::fs::read_to_string(format!("/proc/{pid}/maps"))?;
+    Ok(parse_proc_maps(&contents))
+}
+
+/// Reads the memory map of a process from the output of `vmmap -wide <pid>`.
+#[cfg(target_os = "macos")]
+pub fn read_mem_map(pid: Pid) -> io::Result<Vec<MemMapEntry>> {
+    let output = std::process::Command::new("vmmap")
+        .arg("-wide")
+        .arg(pid.to_string())
+        .output()?;
+
+    if output.status.success() {
+        Ok(parse_vmmap(&String::from_utf8_lossy(&output.stdout)))
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::Other,
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ))
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn read_mem_map(_pid: Pid) -> io::Result<Vec<MemMapEntry>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "memory maps are not supported on this platform.",
+    ))
+}
+
+/// Parses the contents of `/proc/<pid>/maps`, which has one line per region, e.g.
+/// `55d0c0a00000-55d0c0a21000 rw-p 00000000 00:00 0          [heap]`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_proc_maps(contents: &str) -> Vec<MemMapEntry> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let (start, end) = fields.next()?.split_once('-')?;
+            let perms = fields.next()?;
+            let offset = fields.next()?;
+            let device = fields.next()?;
+            let inode = fields.next()?;
+
+            // The pathname is last, and may itself contain spaces.
+            let pathname = fields.collect::<Vec<_>>().join(" ");
+
+            Some(MemMapEntry {
+                start: u64::from_str_radix(start, 16).ok()?,
+                end: u64::from_str_radix(end, 16).ok()?,
+                perms: perms.to_string(),
+                offset: u64::from_str_radix(offset, 16).ok()?,
+                device: device.to_string(),
+                inode: inode.parse().ok()?,
+                pathname,
+            })
+        })
+        .collect()
+}
+
+/// Parses the output of `vmmap -wide`, where each region looks like
+/// `__TEXT    104a3c000-104a40000    [   16K    16K     0K     0K] r-x/r-x SM=COW    /usr/bin/foo`.
+/// Lines that don't describe a region, like headers and summaries, are skipped.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_vmmap(output: &str) -> Vec<MemMapEntry> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (region, rest) = line.split_once('[')?;
+            let (_, rest) = rest.split_once(']')?;
+
+            let mut region_fields = region.split_whitespace().collect::<Vec<_>>();
+            let (start, end) = region_fields.pop()?.split_once('-')?;
+            let region_type = region_fields.join(" ");
+
+            let mut fields = rest.split_whitespace();
+            let perms = fields.next()?.split('/').next()?;
+            let detail = fields
+                .skip_while(|field| field.starts_with("SM=") || field.starts_with("PURGE="))
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            Some(MemMapEntry {
+                start: u64::from_str_radix(start, 16).ok()?,
+                end: u64::from_str_radix(end, 16).ok()?,
+                perms: perms.to_string(),
+                pathname: if detail.is_empty() {
+                    region_type
+                } else {
+                    detail
+                },
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_proc_maps() {
+        let contents = "\
+55d0c0800000-55d0c0828000 r--p 00000000 08:01 1835052                    /usr/bin/my program
+55d0c0a00000-55d0c0a21000 rw-p 00000000 00:00 0                          [heap]
+7f1c2d000000-7f1c2d021000 rw-p 00000000 00:00 0
+";
+
+        let entries = parse_proc_maps(contents);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(
+            entries[0],
+            MemMapEntry {
+                start: 0x55d0c0800000,
+                end: 0x55d0c0828000,
+                perms: "r--p".to_string(),
+                offset: 0,
+                device: "08:01".to_string(),
+                inode: 1835052,
+                pathname: "/usr/bin/my program".to_string(),
+            }
+        );
+        assert_eq!(entries[1].pathname, "[heap]");
+        assert_eq!(entries[1].size(), 0x21000);
+        assert!(entries[2].pathname.is_empty());
+    }
+
+    #[test]
+    fn test_parse_vmmap() {
+        let output = "\
+Process:         foo [1234]
+
+==== Non-writable regions for process 1234
+REGION TYPE                    START - END         [ VSIZE  RSDNT  DIRTY   SWAP] PRT/MAX SHRMOD PURGE    REGION DETAIL
+__TEXT                      104a3c000-104a40000    [   16K    16K     0K     0K] r-x/r-x SM=COW          /usr/bin/foo
+MALLOC metadata             104a50000-104a54000    [   16K    16K    16K     0K] rw-/rwx SM=PRV
+
+==== Legend
+";
+
+        let entries = parse_vmmap(output);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].start, 0x104a3c000);
+        assert_eq!(entries[0].perms, "r-x");
+        assert_eq!(entries[0].pathname, "/usr/bin/foo");
+        assert_eq!(entries[1].pathname, "MALLOC metadata");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_own_mem_map() {
+        let entries = read_mem_map(std::process::id() as Pid).unwrap();
+        assert!(entries.iter().any(|entry| entry.pathname == "[stack]"));
+    }
+
+    #[test]
+    fn test_overlay_scrolling() {
+        let mut overlay = MemMapOverlay {
+            entries: vec![MemMapEntry::default(); 3],
+            ..Default::default()
+        };
+
+        overlay.scroll_up();
+        assert_eq!(overlay.scroll, 0);
+        overlay.scroll_to_last();
+        overlay.scroll_down();
+        assert_eq!(overlay.scroll, 2);
+        overlay.scroll_to_first();
+        assert_eq!(overlay.scroll, 0);
+    }
+}
@@ -0,0 +1,319 @@
+//! This file is meant to house (OS specific) implementations on how to read the details of a
+//! single process on demand, like its environment and open files, which are too costly to read
+//! for every process on each update.
+
+use std::{fmt::Display, io};
+
+use crate::{app::data_harvester::network::connections, Pid};
+
+/// A titled part of the details, like the environment, listing one item per line.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct DetailSection {
+    pub title: &'static str,
+    pub lines: Vec<String>,
+    /// Why this part couldn't be read, if it couldn't.
+    pub error: Option<String>,
+}
+
+impl DetailSection {
+    fn new<E: Display>(title: &'static str, lines: Result<Vec<String>, E>) -> Self {
+        match lines {
+            Ok(lines) => DetailSection {
+                title,
+                lines,
+                error: None,
+            },
+            Err(err) => DetailSection {
+                title,
+                lines: Vec::new(),
+                error: Some(err.to_string()),
+            },
+        }
+    }
+
+    /// The number of lines the section takes up when drawn, including its title.
+    pub fn height(&self) -> usize {
+        1 + self.lines.len().max(1)
+    }
+}
+
+/// The overlay showing the details of a single process.
+#[derive(Debug, Default)]
+pub struct ProcessDetailsOverlay {
+    pub pid: Pid,
+    pub name: String,
+    pub sections: Vec<DetailSection>,
+    /// The index of the first line shown.
+    pub scroll: usize,
+    /// How many lines fit in the overlay when it was last drawn, for paging.
+    pub page_height: usize,
+}
+
+impl ProcessDetailsOverlay {
+    /// Reads the details of a process into a new overlay.
+    pub fn new(pid: Pid, name: String) -> Self {
+        let info = read_process_info(pid);
+        let sections = vec![
+            DetailSection::new(
+                "Command line",
+                info.command_line.map(|args| {
+                    if args.is_empty() {
+                        Vec::new()
+                    } else {
+                        vec![args.join(" ")]
+                    }
+                }),
+            ),
+            DetailSection::new("Working directory", info.cwd.map(|cwd| vec![cwd])),
+            DetailSection::new("Environment", info.environment),
+            DetailSection::new(
+                "Open files",
+                read_open_files(pid).map(|files| {
+                    files
+                        .iter()
+                        .map(|(fd, target)| format!("{fd:>5}  {target}"))
+                        .collect()
+                }),
+            ),
+            DetailSection::new(
+                "Sockets",
+                connections::get_process_connections(pid).map(|sockets| {
+                    sockets
+                        .iter()
+                        .map(|socket| {
+                            format!(
+                                "{}  {} -> {}  {}",
+                                socket.protocol, socket.local, socket.remote, socket.state
+                            )
+                            .trim_end()
+                            .to_string()
+                        })
+                        .collect()
+                }),
+            ),
+        ];
+
+        ProcessDetailsOverlay {
+            pid,
+            name,
+            sections,
+            scroll: 0,
+            page_height: 0,
+        }
+    }
+
+    /// The total number of lines drawn for every section.
+    pub fn height(&self) -> usize {
+        self.sections.iter().map(DetailSection::height).sum()
+    }
+
+    pub fn scroll_up(&mut self, lines: usize) {
+        self.scroll = self.scroll.saturating_sub(lines);
+    }
+
+    pub fn scroll_down(&mut self, lines: usize) {
+        self.scroll = (self.scroll + lines).min(self.height().saturating_sub(1));
+    }
+
+    pub fn scroll_to_first(&mut self) {
+        self.scroll = 0;
+    }
+
+    pub fn scroll_to_last(&mut self) {
+        self.scroll = self.height().saturating_sub(1);
+    }
+}
+
+/// The parts of a process that the platform reports together.
+struct ProcessInfo {
+    command_line: io::Result<Vec<String>>,
+    cwd: io::Result<String>,
+    environment: io::Result<Vec<String>>,
+}
+
+/// Reads the command line, working directory and environment of a process from `/proc/<pid>`.
+#[cfg(target_os = "linux")]
+fn read_process_info(pid: Pid) -> ProcessInfo {
+    let read_list = |file: &str| {
+        std::fs::read(format!("/proc/{pid}/{file}")).map(|contents| split_nul(&contents))
+    };
+
+    ProcessInfo {
+        command_line: read_list("cmdline"),
+        cwd: std::fs::read_link(format!("/proc/{pid}/cwd"))
+            .map(|cwd| cwd.to_string_lossy().into_owned()),
+        environment: read_list("environ").map(|mut environment| {
+            environment.sort_unstable();
+            environment
+        }),
+    }
+}
+
+/// Reads the command line, working directory and environment of a process through sysinfo.
+#[cfg(not(target_os = "linux"))]
+fn read_process_info(pid: Pid) -> ProcessInfo {
+    use sysinfo::{PidExt, ProcessExt, System, SystemExt};
+
+    let mut sys = System::new();
+    let sys_pid = sysinfo::Pid::from_u32(pid as u32);
+    let process = if sys.refresh_process(sys_pid) {
+        sys.process(sys_pid)
+    } else {
+        None
+    };
+
+    let Some(process) = process else {
+        let missing = || io::Error::new(io::ErrorKind::NotFound, "the process no longer exists.");
+        return ProcessInfo {
+            command_line: Err(missing()),
+            cwd: Err(missing()),
+            environment: Err(missing()),
+        };
+    };
+
+    let mut environment = process.environ().to_vec();
+    environment.sort_unstable();
+
+    ProcessInfo {
+        command_line: Ok(process.cmd().to_vec()),
+        cwd: Ok(process.cwd().to_string_lossy().into_owned()),
+        environment: Ok(environment),
+    }
+}
+
+/// Reads the open file descriptors of a process and what they point to from `/proc/<pid>/fd`,
+/// ordered by descriptor.
+#[cfg(target_os = "linux")]
+fn read_open_files(pid: Pid) -> io::Result<Vec<(String, String)>> {
+    let mut files = std::fs::read_dir(format!("/proc/{pid}/fd"))?
+        .flatten()
+        .filter_map(|entry| {
+            let fd = entry.file_name().to_str()?.parse::<u32>().ok()?;
+            let target = std::fs::read_link(entry.path()).ok()?;
+            Some((fd, target.to_string_lossy().into_owned()))
+        })
+        .collect::<Vec<_>>();
+    files.sort_unstable();
+
+    Ok(files
+        .into_iter()
+        .map(|(fd, target)| (fd.to_string(), target))
+        .collect())
+}
+
+/// Reads the open files of a process from the output of `lsof -p <pid>`.
+#[cfg(any(target_os = "macos", target_os = "freebsd"))]
+fn read_open_files(pid: Pid) -> io::Result<Vec<(String, String)>> {
+    let output = std::process::Command::new("lsof")
+        .args(["-n", "-P", "-F", "fn", "-p"])
+        .arg(pid.to_string())
+        .output()?;
+
+    Ok(parse_lsof_files(&String::from_utf8_lossy(&output.stdout)))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "freebsd")))]
+fn read_open_files(_pid: Pid) -> io::Result<Vec<(String, String)>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "open files are not supported on this platform.",
+    ))
+}
+
+/// Splits a list of NUL-terminated strings, like `/proc/<pid>/cmdline`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn split_nul(contents: &[u8]) -> Vec<String> {
+    contents
+        .split(|byte| *byte == 0)
+        .filter(|part| !part.is_empty())
+        .map(|part| String::from_utf8_lossy(part).into_owned())
+        .collect()
+}
+
+/// Parses the field output of `lsof -F fn`, where an `f` line starts a file with its descriptor,
+/// like `f3` or `fcwd`, and the `n` line after it names the file. Other lines, like the `p` line
+/// starting the process, are skipped.
+#[cfg_attr(not(any(target_os = "macos", target_os = "freebsd")), allow(dead_code))]
+fn parse_lsof_files(output: &str) -> Vec<(String, String)> {
+    let mut files = Vec::new();
+    let mut fd = None;
+    for line in output.lines() {
+        if let Some(field) = line.strip_prefix('f') {
+            fd = Some(field.to_string());
+        } else if let Some(name) = line.strip_prefix('n') {
+            if let Some(fd) = fd.take() {
+                files.push((fd, name.to_string()));
+            }
+        }
+    }
+
+    files
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_split_nul() {
+        assert_eq!(
+            split_nul(b"/usr/bin/python3\0my script.py\0--verbose\0"),
+            ["/usr/bin/python3", "my script.py", "--verbose"]
+        );
+        assert!(split_nul(b"").is_empty());
+    }
+
+    #[test]
+    fn test_parse_lsof_files() {
+        let output = "p1234\nfcwd\nn/home/user\nftxt\nn/usr/bin/foo\nf3\nn127.0.0.1:8080\n";
+        assert_eq!(
+            parse_lsof_files(output),
+            [
+                ("cwd".to_string(), "/home/user".to_string()),
+                ("txt".to_string(), "/usr/bin/foo".to_string()),
+                ("3".to_string(), "127.0.0.1:8080".to_string()),
+            ]
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_own_process_details() {
+        let overlay = ProcessDetailsOverlay::new(std::process::id() as Pid, String::default());
+        let section = |title| {
+            overlay
+                .sections
+                .iter()
+                .find(|section| section.title == title)
+                .unwrap()
+        };
+
+        assert!(section("Environment").error.is_none());
+        assert_eq!(
+            section("Working directory").lines,
+            [std::env::current_dir().unwrap().to_string_lossy()]
+        );
+        assert!(section("Open files").error.is_none());
+    }
+
+    #[test]
+    fn test_overlay_scrolling() {
+        let mut overlay = ProcessDetailsOverlay {
+            sections: vec![
+                DetailSection::new::<String>("A", Ok(vec![String::default(); 3])),
+                DetailSection::new("B", Err("unreadable")),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(overlay.height(), 6);
+
+        overlay.scroll_up(1);
+        assert_eq!(overlay.scroll, 0);
+        overlay.scroll_down(10);
+        assert_eq!(overlay.scroll, 5);
+        overlay.scroll_to_first();
+        assert_eq!(overlay.scroll, 0);
+        overlay.scroll_to_last();
+        assert_eq!(overlay.scroll, 5);
+    }
+}
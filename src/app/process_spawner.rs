@@ -0,0 +1,43 @@
+//! This file is meant to house implementations on how to launch new processes.
+
+use std::{
+    process::{Command, Stdio},
+    thread,
+};
+
+/// Runs `command` through the system shell, detached from the terminal, and returns the PID of
+/// the shell running it.
+pub fn spawn_command(command: &str) -> std::io::Result<u32> {
+    #[cfg(target_family = "unix")]
+    let mut shell = Command::new("sh");
+    #[cfg(target_family = "unix")]
+    shell.arg("-c");
+
+    #[cfg(target_os = "windows")]
+    let mut shell = Command::new("cmd");
+    #[cfg(target_os = "windows")]
+    shell.arg("/C");
+
+    let mut child = shell
+        .arg(command)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    let pid = child.id();
+
+    // Wait on the child in the background so it's reaped once it exits.
+    thread::spawn(move || child.wait());
+
+    Ok(pid)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_spawn_command() {
+        assert!(spawn_command("exit 0").unwrap() > 0);
+    }
+}
@@ -0,0 +1,191 @@
+//! Saving everything bottom has collected and is showing to a file with a single key, to keep as
+//! evidence during an incident.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    app::{data_farmer::DataCollection, data_harvester::Data},
+    data_conversion::ConvertedData,
+    server::Snapshot,
+    utils::json::Json,
+};
+
+/// Returns the harvested data in the same shape `--serve` gives it at `/`, along with temperatures
+/// and what the widgets show after conversion.
+pub fn to_json(data: &DataCollection, converted: &ConvertedData) -> Json {
+    let mut snapshot = Snapshot::new(false);
+    snapshot.update(&harvested(data));
+    let Json::Object(mut entries) = snapshot.to_json() else {
+        unreachable!("a snapshot is always an object");
+    };
+
+    entries.push((
+        "temperatures",
+        Json::Array(
+            data.temp_harvest
+                .iter()
+                .map(|sensor| {
+                    Json::Object(vec![
+                        ("name", sensor.name.as_str().into()),
+                        ("type", sensor.sensor_type.name().into()),
+                        ("value", f64::from(sensor.value).into()),
+                    ])
+                })
+                .collect(),
+        ),
+    ));
+    entries.push(("converted", converted_json(converted)));
+
+    Json::Object(entries)
+}
+
+/// Gathers the latest harvest of everything the server knows how to write out.
+fn harvested(data: &DataCollection) -> Data {
+    Data {
+        cpu: Some(data.cpu_harvest.clone()),
+        load_avg: Some(data.load_avg_harvest),
+        memory: Some(data.memory_harvest.clone()),
+        swap: Some(data.swap_harvest.clone()),
+        network: Some(data.network_harvest.clone()),
+        list_of_processes: Some(
+            data.process_data
+                .process_harvest
+                .values()
+                .cloned()
+                .collect(),
+        ),
+        disks: Some(data.disk_harvest.clone()),
+        connections: Some(data.connection_harvest.clone()),
+        ..Data::default()
+    }
+}
+
+/// Returns the values as they are shown, rather than as they were harvested.
+fn converted_json(converted: &ConvertedData) -> Json {
+    let labels = |labels: &Option<(String, String)>| {
+        labels.as_ref().map_or(Json::Null, |(percent, usage)| {
+            Json::Object(vec![
+                ("percent", percent.as_str().into()),
+                ("usage", usage.as_str().into()),
+            ])
+        })
+    };
+
+    Json::Object(vec![
+        (
+            "network",
+            Json::Object(vec![
+                ("rx", converted.rx_display.as_str().into()),
+                ("tx", converted.tx_display.as_str().into()),
+                ("total_rx", converted.total_rx_display.as_str().into()),
+                ("total_tx", converted.total_tx_display.as_str().into()),
+            ]),
+        ),
+        ("memory", labels(&converted.mem_labels)),
+        ("swap", labels(&converted.swap_labels)),
+        (
+            "load_avg",
+            Json::Array(
+                converted
+                    .load_avg_data
+                    .iter()
+                    .map(|load| f64::from(*load).into())
+                    .collect(),
+            ),
+        ),
+        (
+            "disks",
+            Json::Array(
+                converted
+                    .disk_data
+                    .iter()
+                    .map(|disk| {
+                        Json::Object(vec![
+                            ("name", disk.name.as_str().into()),
+                            ("mount_point", disk.mount_point.as_str().into()),
+                            ("io_read", disk.io_read.as_str().into()),
+                            ("io_write", disk.io_write.as_str().into()),
+                            (
+                                "read_latency_ms",
+                                disk.read_latency_ms.map(f64::from).into(),
+                            ),
+                            (
+                                "write_latency_ms",
+                                disk.write_latency_ms.map(f64::from).into(),
+                            ),
+                        ])
+                    })
+                    .collect(),
+            ),
+        ),
+    ])
+}
+
+/// Writes a snapshot into `dir`, named after the current time, and returns its path.
+pub fn write_snapshot(dir: &Path, contents: &Json) -> io::Result<PathBuf> {
+    let timestamp = time::OffsetDateTime::now_utc()
+        .format(&time::macros::format_description!(
+            "[year][month][day]-[hour][minute][second]"
+        ))
+        .map_err(io::Error::other)?;
+    let path = dir.join(format!("btm-snapshot-{timestamp}.json"));
+    std::fs::write(&path, format!("{contents}\n"))?;
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{app::data_harvester::temperature::TempHarvest, utils::json::JsonValue};
+
+    #[test]
+    fn snapshot_has_everything() {
+        let data = DataCollection {
+            temp_harvest: vec![TempHarvest {
+                name: "CPU".to_string(),
+                value: 55.0,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let converted = ConvertedData {
+            rx_display: "1.5Kb".to_string(),
+            mem_labels: Some(("40%".to_string(), "3.2GiB/8.0GiB".to_string())),
+            ..Default::default()
+        };
+
+        let json = JsonValue::parse(&to_json(&data, &converted).to_string()).unwrap();
+        for key in [
+            "updated_at",
+            "cpu",
+            "memory",
+            "processes",
+            "network",
+            "disks",
+        ] {
+            assert!(json.get(key).is_some(), "{key} is missing");
+        }
+        assert_eq!(
+            json.path(&["temperatures"])
+                .and_then(JsonValue::as_array)
+                .and_then(|sensors| sensors[0].get("value"))
+                .and_then(JsonValue::as_f64),
+            Some(55.0)
+        );
+        assert_eq!(
+            json.path(&["converted", "network", "rx"])
+                .and_then(JsonValue::as_str),
+            Some("1.5Kb")
+        );
+        assert_eq!(
+            json.path(&["converted", "memory", "usage"])
+                .and_then(JsonValue::as_str),
+            Some("3.2GiB/8.0GiB")
+        );
+        assert_eq!(json.path(&["converted", "swap"]), Some(&JsonValue::Null));
+    }
+}
@@ -1,16 +1,30 @@
-use std::{collections::HashMap, ops::Range, time::Instant};
+use std::{
+    collections::HashMap,
+    io,
+    ops::Range,
+    path::{Path, PathBuf},
+    time::Instant,
+};
 
 use indexmap::IndexMap;
 use unicode_segmentation::{GraphemeCursor, GraphemeIncomplete, UnicodeSegmentation};
 
+#[cfg(target_os = "linux")]
+use crate::{
+    app::data_harvester::processes::{set_io_priority, IOClass, MAX_IO_PRIORITY_LEVEL},
+    Pid,
+};
 use crate::{
     app::{layout_manager::BottomWidgetType, query::*},
+    components::custom_widget::Widget,
     constants,
     utils::gen_util::str_width,
     widgets::{
-        BatteryWidgetState, ConnectionsWidgetState, CpuWidgetState, DiskTableWidget,
-        MemWidgetState, NetWidgetState, ProcWidgetState, TempWidgetState, TerminalWidgetState,
-        UptimeWidgetState,
+        BatteryWidgetState, CgroupWidgetState, ConnectionsWidgetState, ContainerWidgetState,
+        CpuWidgetState, DashboardWidgetState, DiscoveryWidgetState, DiskTableWidget,
+        LogWidgetState, MemWidgetState, NetWidgetState, PressureWidgetState, ProcWidgetState,
+        RaidWidgetState, ScheduledJobWidgetState, TempWidgetState, TerminalWidgetState,
+        UptimeWidgetState, WifiWidgetState, ZfsPoolWidgetState,
     },
 };
 
@@ -37,6 +51,13 @@ impl Default for KillSignal {
     }
 }
 
+#[derive(Default)]
+pub struct AppErrorLogState {
+    pub is_showing: bool,
+    /// Whether the log has already been shown, either automatically or by the user.
+    pub has_auto_shown: bool,
+}
+
 #[derive(Default)]
 pub struct AppDeleteDialogState {
     pub is_showing_dd: bool,
@@ -46,6 +67,216 @@ pub struct AppDeleteDialogState {
     pub keyboard_signal_select: usize,
     pub last_number_press: Option<Instant>,
     pub scroll_pos: usize,
+    /// Whether the signal picker was opened from a yes or no confirmation.
+    pub is_picking_signal: bool,
+}
+
+/// A line of text being typed, with a cursor.
+#[derive(Default)]
+pub struct LineInput {
+    pub text: String,
+    /// The byte offset of the cursor in `text`, always on a character boundary.
+    pub cursor_offset: usize,
+}
+
+impl LineInput {
+    pub fn insert_char(&mut self, c: char) {
+        self.text.insert(self.cursor_offset, c);
+        self.cursor_offset += c.len_utf8();
+    }
+
+    /// Deletes the character behind the cursor.
+    pub fn backspace(&mut self) {
+        if let Some(c) = self.text[..self.cursor_offset].chars().next_back() {
+            self.cursor_offset -= c.len_utf8();
+            self.text.remove(self.cursor_offset);
+        }
+    }
+
+    /// Deletes the character at the cursor.
+    pub fn delete(&mut self) {
+        if self.cursor_offset < self.text.len() {
+            self.text.remove(self.cursor_offset);
+        }
+    }
+
+    pub fn move_left(&mut self) {
+        if let Some(c) = self.text[..self.cursor_offset].chars().next_back() {
+            self.cursor_offset -= c.len_utf8();
+        }
+    }
+
+    pub fn move_right(&mut self) {
+        if let Some(c) = self.text[self.cursor_offset..].chars().next() {
+            self.cursor_offset += c.len_utf8();
+        }
+    }
+}
+
+/// The state of the dialog for running a new command from the process widget.
+#[derive(Default)]
+pub struct AppSpawnDialogState {
+    pub is_showing: bool,
+    pub input: LineInput,
+    /// The outcome of the last run, as either the new PID or an error message.
+    pub result: Option<Result<u32, String>>,
+}
+
+impl AppSpawnDialogState {
+    pub fn open(&mut self) {
+        *self = AppSpawnDialogState {
+            is_showing: true,
+            ..Default::default()
+        };
+    }
+
+    pub fn close(&mut self) {
+        *self = AppSpawnDialogState::default();
+    }
+}
+
+/// The state of the line at the bottom for running actions by name, like vim's command line.
+#[derive(Default)]
+pub struct AppCommandLineState {
+    pub is_showing: bool,
+    pub input: LineInput,
+    /// Why the last command couldn't be run, shown until the next key.
+    pub error: Option<String>,
+}
+
+impl AppCommandLineState {
+    pub fn open(&mut self) {
+        *self = AppCommandLineState {
+            is_showing: true,
+            ..Default::default()
+        };
+    }
+
+    pub fn close(&mut self) {
+        *self = AppCommandLineState::default();
+    }
+}
+
+/// The state of the dialog for changing the I/O class and level of processes, like `ionice`.
+#[cfg(target_os = "linux")]
+#[derive(Default)]
+pub struct AppIoPriorityDialogState {
+    pub is_showing: bool,
+    /// The name of the process (or group of processes) being changed.
+    pub name: String,
+    pub pids: Vec<Pid>,
+    pub class: IOClass,
+    /// The level within `class`, from 0 (most important) to 7.
+    pub level: u8,
+    /// The outcome of the last change, with an error message if it failed.
+    pub result: Option<Result<(), String>>,
+}
+
+#[cfg(target_os = "linux")]
+impl AppIoPriorityDialogState {
+    /// Opens the dialog, starting from the current class and level of the process.
+    pub fn open(
+        &mut self, name: String, pids: Vec<Pid>, class: Option<IOClass>, level: Option<u8>,
+    ) {
+        *self = AppIoPriorityDialogState {
+            is_showing: true,
+            name,
+            pids,
+            class: class.unwrap_or_default(),
+            level: level.unwrap_or(4).min(MAX_IO_PRIORITY_LEVEL),
+            result: None,
+        };
+    }
+
+    pub fn close(&mut self) {
+        *self = AppIoPriorityDialogState::default();
+    }
+
+    pub fn next_class(&mut self) {
+        self.class = self.class.next();
+    }
+
+    pub fn prev_class(&mut self) {
+        self.class = self.class.prev();
+    }
+
+    pub fn increase_level(&mut self) {
+        self.level = (self.level + 1).min(MAX_IO_PRIORITY_LEVEL);
+    }
+
+    pub fn decrease_level(&mut self) {
+        self.level = self.level.saturating_sub(1);
+    }
+
+    /// Sets the level from a digit key, ignoring anything outside of 0 to 7.
+    pub fn set_level(&mut self, c: char) {
+        if let Some(level) = c.to_digit(10) {
+            if level <= u32::from(MAX_IO_PRIORITY_LEVEL) {
+                self.level = level as u8;
+            }
+        }
+    }
+
+    /// Applies the selected class and level to every process.
+    pub fn apply(&mut self) {
+        self.result = Some(
+            self.pids
+                .iter()
+                .try_for_each(|pid| set_io_priority(*pid, self.class, self.level))
+                .map_err(|err| err.to_string()),
+        );
+    }
+}
+
+/// The state of the popup for picking a user whose processes are the only ones shown.
+#[derive(Default)]
+pub struct AppUserPickerState {
+    pub is_showing: bool,
+    /// The process widget the picked user is shown in.
+    pub widget_id: u64,
+    /// The users with running processes, sorted by name.
+    pub users: Vec<String>,
+    pub current_index: usize,
+}
+
+impl AppUserPickerState {
+    /// Opens the picker, starting on `current` if it's one of `users`.
+    pub fn open(&mut self, widget_id: u64, users: Vec<String>, current: Option<&str>) {
+        let current_index = current
+            .and_then(|current| users.iter().position(|user| user == current))
+            .unwrap_or(0);
+
+        *self = AppUserPickerState {
+            is_showing: true,
+            widget_id,
+            users,
+            current_index,
+        };
+    }
+
+    pub fn close(&mut self) {
+        *self = AppUserPickerState::default();
+    }
+
+    pub fn move_up(&mut self) {
+        self.current_index = self.current_index.saturating_sub(1);
+    }
+
+    pub fn move_down(&mut self) {
+        self.current_index = (self.current_index + 1).min(self.users.len().saturating_sub(1));
+    }
+
+    pub fn move_to_first(&mut self) {
+        self.current_index = 0;
+    }
+
+    pub fn move_to_last(&mut self) {
+        self.current_index = self.users.len().saturating_sub(1);
+    }
+
+    pub fn selected_user(&self) -> Option<&String> {
+        self.users.get(self.current_index)
+    }
 }
 
 pub struct AppHelpDialogState {
@@ -267,6 +498,20 @@ impl ProcState {
     pub fn get_widget_state(&self, widget_id: u64) -> Option<&ProcWidgetState> {
         self.widget_states.get(&widget_id)
     }
+
+    /// Returns the PID and name of the process highlighted in the first process widget, for other
+    /// widgets to refer to.
+    pub fn highlighted_process(&self) -> Option<(crate::Pid, String)> {
+        let mut widget_ids = self.widget_states.keys().collect::<Vec<_>>();
+        widget_ids.sort_unstable();
+
+        widget_ids.into_iter().find_map(|widget_id| {
+            self.widget_states[widget_id]
+                .table
+                .current_item()
+                .map(|item| (item.pid, item.id.to_string()))
+        })
+    }
 }
 
 pub struct NetState {
@@ -403,11 +648,42 @@ impl BatteryState {
 
 pub struct TerminalState {
     pub widget_states: HashMap<u64, TerminalWidgetState>,
+    /// Where command history is saved on exit, if it should be.
+    history_dir: Option<PathBuf>,
 }
 
 impl TerminalState {
     pub fn init(widget_states: HashMap<u64, TerminalWidgetState>) -> Self {
-        TerminalState { widget_states }
+        TerminalState {
+            widget_states,
+            history_dir: None,
+        }
+    }
+
+    fn history_path(dir: &Path, widget_id: u64) -> PathBuf {
+        dir.join(format!("terminal_history_{widget_id}"))
+    }
+
+    /// Loads the saved command history of every terminal widget from `dir`, and remembers it to
+    /// save them back to on exit.
+    pub fn load_history(&mut self, dir: &Path) -> io::Result<()> {
+        self.history_dir = Some(dir.to_path_buf());
+        for (widget_id, widget_state) in &mut self.widget_states {
+            widget_state.load_history(&Self::history_path(dir, *widget_id))?;
+        }
+
+        Ok(())
+    }
+
+    /// Saves the command history of every terminal widget, if it was loaded from somewhere.
+    pub fn save_history(&self) -> io::Result<()> {
+        if let Some(dir) = &self.history_dir {
+            for (widget_id, widget_state) in &self.widget_states {
+                widget_state.save_history(&Self::history_path(dir, *widget_id))?;
+            }
+        }
+
+        Ok(())
     }
 
     pub fn get_mut_widget_state(&mut self, widget_id: u64) -> Option<&mut TerminalWidgetState> {
@@ -455,6 +731,200 @@ impl ConnectionsState {
     }
 }
 
+pub struct DashboardState {
+    pub widget_states: HashMap<u64, DashboardWidgetState>,
+}
+
+impl DashboardState {
+    pub fn init(widget_states: HashMap<u64, DashboardWidgetState>) -> Self {
+        DashboardState { widget_states }
+    }
+
+    pub fn get_mut_widget_state(&mut self, widget_id: u64) -> Option<&mut DashboardWidgetState> {
+        self.widget_states.get_mut(&widget_id)
+    }
+
+    pub fn get_widget_state(&self, widget_id: u64) -> Option<&DashboardWidgetState> {
+        self.widget_states.get(&widget_id)
+    }
+}
+
+pub struct ContainerState {
+    pub widget_states: HashMap<u64, ContainerWidgetState>,
+}
+
+impl ContainerState {
+    pub fn init(widget_states: HashMap<u64, ContainerWidgetState>) -> Self {
+        ContainerState { widget_states }
+    }
+
+    pub fn get_mut_widget_state(&mut self, widget_id: u64) -> Option<&mut ContainerWidgetState> {
+        self.widget_states.get_mut(&widget_id)
+    }
+
+    pub fn get_widget_state(&self, widget_id: u64) -> Option<&ContainerWidgetState> {
+        self.widget_states.get(&widget_id)
+    }
+}
+
+pub struct PressureState {
+    pub widget_states: HashMap<u64, PressureWidgetState>,
+}
+
+impl PressureState {
+    pub fn init(widget_states: HashMap<u64, PressureWidgetState>) -> Self {
+        PressureState { widget_states }
+    }
+
+    pub fn get_mut_widget_state(&mut self, widget_id: u64) -> Option<&mut PressureWidgetState> {
+        self.widget_states.get_mut(&widget_id)
+    }
+
+    pub fn get_widget_state(&self, widget_id: u64) -> Option<&PressureWidgetState> {
+        self.widget_states.get(&widget_id)
+    }
+}
+
+pub struct WifiState {
+    pub widget_states: HashMap<u64, WifiWidgetState>,
+}
+
+impl WifiState {
+    pub fn init(widget_states: HashMap<u64, WifiWidgetState>) -> Self {
+        WifiState { widget_states }
+    }
+
+    pub fn get_mut_widget_state(&mut self, widget_id: u64) -> Option<&mut WifiWidgetState> {
+        self.widget_states.get_mut(&widget_id)
+    }
+
+    pub fn get_widget_state(&self, widget_id: u64) -> Option<&WifiWidgetState> {
+        self.widget_states.get(&widget_id)
+    }
+}
+
+pub struct RaidState {
+    pub widget_states: HashMap<u64, RaidWidgetState>,
+}
+
+impl RaidState {
+    pub fn init(widget_states: HashMap<u64, RaidWidgetState>) -> Self {
+        RaidState { widget_states }
+    }
+
+    pub fn get_mut_widget_state(&mut self, widget_id: u64) -> Option<&mut RaidWidgetState> {
+        self.widget_states.get_mut(&widget_id)
+    }
+
+    pub fn get_widget_state(&self, widget_id: u64) -> Option<&RaidWidgetState> {
+        self.widget_states.get(&widget_id)
+    }
+}
+
+pub struct ZfsPoolState {
+    pub widget_states: HashMap<u64, ZfsPoolWidgetState>,
+}
+
+impl ZfsPoolState {
+    pub fn init(widget_states: HashMap<u64, ZfsPoolWidgetState>) -> Self {
+        ZfsPoolState { widget_states }
+    }
+
+    pub fn get_mut_widget_state(&mut self, widget_id: u64) -> Option<&mut ZfsPoolWidgetState> {
+        self.widget_states.get_mut(&widget_id)
+    }
+
+    pub fn get_widget_state(&self, widget_id: u64) -> Option<&ZfsPoolWidgetState> {
+        self.widget_states.get(&widget_id)
+    }
+}
+
+pub struct CgroupState {
+    pub widget_states: HashMap<u64, CgroupWidgetState>,
+}
+
+impl CgroupState {
+    pub fn init(widget_states: HashMap<u64, CgroupWidgetState>) -> Self {
+        CgroupState { widget_states }
+    }
+
+    pub fn get_mut_widget_state(&mut self, widget_id: u64) -> Option<&mut CgroupWidgetState> {
+        self.widget_states.get_mut(&widget_id)
+    }
+
+    pub fn get_widget_state(&self, widget_id: u64) -> Option<&CgroupWidgetState> {
+        self.widget_states.get(&widget_id)
+    }
+}
+
+pub struct LogState {
+    pub widget_states: HashMap<u64, LogWidgetState>,
+}
+
+impl LogState {
+    pub fn init(widget_states: HashMap<u64, LogWidgetState>) -> Self {
+        LogState { widget_states }
+    }
+
+    pub fn get_mut_widget_state(&mut self, widget_id: u64) -> Option<&mut LogWidgetState> {
+        self.widget_states.get_mut(&widget_id)
+    }
+
+    pub fn get_widget_state(&self, widget_id: u64) -> Option<&LogWidgetState> {
+        self.widget_states.get(&widget_id)
+    }
+}
+
+pub struct CustomWidgetState {
+    pub widget_states: HashMap<u64, Box<dyn Widget>>,
+}
+
+impl CustomWidgetState {
+    pub fn init(widget_states: HashMap<u64, Box<dyn Widget>>) -> Self {
+        CustomWidgetState { widget_states }
+    }
+
+    pub fn get_mut_widget_state(&mut self, widget_id: u64) -> Option<&mut Box<dyn Widget>> {
+        self.widget_states.get_mut(&widget_id)
+    }
+}
+
+pub struct ScheduledJobState {
+    pub widget_states: HashMap<u64, ScheduledJobWidgetState>,
+}
+
+impl ScheduledJobState {
+    pub fn init(widget_states: HashMap<u64, ScheduledJobWidgetState>) -> Self {
+        ScheduledJobState { widget_states }
+    }
+
+    pub fn get_mut_widget_state(&mut self, widget_id: u64) -> Option<&mut ScheduledJobWidgetState> {
+        self.widget_states.get_mut(&widget_id)
+    }
+
+    pub fn get_widget_state(&self, widget_id: u64) -> Option<&ScheduledJobWidgetState> {
+        self.widget_states.get(&widget_id)
+    }
+}
+
+pub struct DiscoveryState {
+    pub widget_states: HashMap<u64, DiscoveryWidgetState>,
+}
+
+impl DiscoveryState {
+    pub fn init(widget_states: HashMap<u64, DiscoveryWidgetState>) -> Self {
+        DiscoveryState { widget_states }
+    }
+
+    pub fn get_mut_widget_state(&mut self, widget_id: u64) -> Option<&mut DiscoveryWidgetState> {
+        self.widget_states.get_mut(&widget_id)
+    }
+
+    pub fn get_widget_state(&self, widget_id: u64) -> Option<&DiscoveryWidgetState> {
+        self.widget_states.get(&widget_id)
+    }
+}
+
 #[derive(Default)]
 pub struct ParagraphScrollState {
     pub current_scroll_index: u16,
@@ -475,6 +945,72 @@ mod test {
         state.cursor_direction = CursorDirection::Left;
     }
 
+    #[test]
+    fn line_input_editing() {
+        let mut input = LineInput::default();
+        for c in "sleep 你5".chars() {
+            input.insert_char(c);
+        }
+        assert_eq!(input.cursor_offset, input.text.len());
+
+        input.move_left();
+        input.backspace();
+        assert_eq!(input.text, "sleep 5");
+
+        input.move_left();
+        input.move_left();
+        input.delete();
+        assert_eq!(input.text, "slee 5");
+        assert_eq!(input.cursor_offset, 4);
+
+        input.move_right();
+        input.move_right();
+        input.move_right();
+        assert_eq!(input.cursor_offset, input.text.len());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn io_priority_dialog_levels() {
+        let mut state = AppIoPriorityDialogState::default();
+        state.open("btm".to_string(), vec![1], Some(IOClass::Idle), None);
+        assert_eq!(state.level, 4);
+
+        state.next_class();
+        assert_eq!(state.class, IOClass::None);
+
+        state.set_level('9');
+        assert_eq!(state.level, 4);
+        state.set_level('7');
+        state.increase_level();
+        assert_eq!(state.level, 7);
+        state.set_level('0');
+        state.decrease_level();
+        assert_eq!(state.level, 0);
+
+        state.close();
+        assert!(!state.is_showing);
+    }
+
+    #[test]
+    fn user_picker_moves() {
+        let mut state = AppUserPickerState::default();
+        let users = vec!["alice".to_string(), "bob".to_string(), "root".to_string()];
+        state.open(3, users, Some("bob"));
+        assert_eq!(state.selected_user().unwrap(), "bob");
+
+        state.move_down();
+        state.move_down();
+        assert_eq!(state.selected_user().unwrap(), "root");
+        state.move_to_first();
+        state.move_up();
+        assert_eq!(state.selected_user().unwrap(), "alice");
+
+        state.close();
+        assert!(!state.is_showing);
+        assert!(state.selected_user().is_none());
+    }
+
     #[test]
     fn search_cursor_moves() {
         let mut state = AppSearchState::default();
@@ -1,4 +1,7 @@
-use std::str::FromStr;
+use std::{
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 use canvas_styling::*;
 use itertools::izip;
@@ -6,14 +9,14 @@ use tui::{
     backend::Backend,
     layout::{Constraint, Direction, Layout, Rect},
     text::{Span, Spans},
-    widgets::Paragraph,
+    widgets::{BorderType, Paragraph},
     Frame, Terminal,
 };
 
 use crate::{
     app::{
         self,
-        layout_manager::{BottomColRow, BottomLayout, BottomWidgetType},
+        layout_manager::{BottomColRow, BottomLayout, BottomWidgetType, LayoutTab},
         App,
     },
     constants::*,
@@ -21,6 +24,7 @@ use crate::{
     utils::error::BottomError,
 };
 
+mod ansi;
 pub mod canvas_styling;
 mod dialogs;
 mod drawing_utils;
@@ -57,9 +61,25 @@ impl FromStr for ColourScheme {
     }
 }
 
+/// How long an alerting widget's border stays in each colour while it blinks.
+pub const ALERT_BLINK_INTERVAL: Duration = Duration::from_millis(600);
+
+/// Whether an alerting widget's border is currently in its alert colour, which flips every
+/// [`ALERT_BLINK_INTERVAL`].
+pub fn is_alert_blink_on() -> bool {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since_epoch| {
+            (since_epoch.as_millis() / ALERT_BLINK_INTERVAL.as_millis()).is_multiple_of(2)
+        })
+        .unwrap_or(true)
+}
+
 /// Handles the canvas' state.
 pub struct Painter {
     pub colours: CanvasColours,
+    pub border_type: BorderType,
+    pub use_nerd_fonts: bool,
     height: u16,
     width: u16,
     styled_help_text: Vec<Spans<'static>>,
@@ -71,7 +91,9 @@ pub struct Painter {
     col_row_constraints: Vec<Vec<Vec<LayoutConstraint>>>,
     layout_constraints: Vec<Vec<Vec<Vec<LayoutConstraint>>>>,
     derived_widget_draw_locs: Vec<Vec<Vec<Vec<Rect>>>>,
-    widget_layout: BottomLayout,
+    /// The layout of each tab, of which only the current one is drawn.
+    widget_layouts: Vec<BottomLayout>,
+    current_tab: usize,
 }
 
 // Part of a temporary fix for https://github.com/ClementTsang/bottom/issues/896
@@ -82,9 +104,38 @@ enum LayoutConstraint {
 }
 
 impl Painter {
-    pub fn init(widget_layout: BottomLayout, colours: CanvasColours) -> anyhow::Result<Self> {
+    pub fn init(
+        layout_tabs: Vec<LayoutTab>, colours: CanvasColours,
+        app_config_fields: &app::AppConfigFields,
+    ) -> anyhow::Result<Self> {
+        let mut painter = Painter {
+            colours,
+            border_type: app_config_fields.border_type.into(),
+            use_nerd_fonts: app_config_fields.use_nerd_fonts,
+            height: 0,
+            width: 0,
+            styled_help_text: Vec::default(),
+            is_mac_os: cfg!(target_os = "macos"),
+            row_constraints: Vec::default(),
+            col_constraints: Vec::default(),
+            col_row_constraints: Vec::default(),
+            layout_constraints: Vec::default(),
+            widget_layouts: layout_tabs.into_iter().map(|tab| tab.layout).collect(),
+            current_tab: 0,
+            derived_widget_draw_locs: Vec::default(),
+        };
+
+        painter.init_layout_constraints();
+        painter.complete_painter_init();
+
+        Ok(painter)
+    }
+
+    /// Builds the constraints of the current tab's layout, which are then used to split up the
+    /// terminal whenever it is redrawn.
+    fn init_layout_constraints(&mut self) {
         // Now for modularity; we have to also initialize the base layouts!
-        // We want to do this ONCE and reuse; after this we can just construct
+        // We want to do this ONCE per layout and reuse; after this we can just construct
         // based on the console size.
 
         let mut row_constraints = Vec::new();
@@ -92,6 +143,7 @@ impl Painter {
         let mut col_row_constraints = Vec::new();
         let mut layout_constraints = Vec::new();
 
+        let widget_layout = &self.widget_layouts[self.current_tab];
         widget_layout.rows.iter().for_each(|row| {
             if row.canvas_handle_height {
                 row_constraints.push(LayoutConstraint::CanvasHandled);
@@ -152,35 +204,65 @@ impl Painter {
             col_constraints.push(new_col_constraints);
         });
 
-        let mut painter = Painter {
-            colours,
-            height: 0,
-            width: 0,
-            styled_help_text: Vec::default(),
-            is_mac_os: cfg!(target_os = "macos"),
-            row_constraints,
-            col_constraints,
-            col_row_constraints,
-            layout_constraints,
-            widget_layout,
-            derived_widget_draw_locs: Vec::default(),
-        };
-
-        painter.complete_painter_init();
+        self.row_constraints = row_constraints;
+        self.col_constraints = col_constraints;
+        self.col_row_constraints = col_row_constraints;
+        self.layout_constraints = layout_constraints;
+        self.derived_widget_draw_locs = Vec::default();
+    }
 
-        Ok(painter)
+    /// Replaces the corners of the bordered block drawn at `draw_loc` with nerd font glyphs, if
+    /// enabled.
+    pub fn draw_nerd_font_corners<B: Backend>(&self, f: &mut Frame<'_, B>, draw_loc: Rect) {
+        if self.use_nerd_fonts {
+            f.render_widget(drawing_utils::NerdFontCorners, draw_loc);
+        }
     }
 
-    /// Determines the border style.
-    pub fn get_border_style(&self, widget_id: u64, selected_widget_id: u64) -> tui::style::Style {
-        let is_on_widget = widget_id == selected_widget_id;
-        if is_on_widget {
+    /// Determines the border style, using the border colour set for the widget in the layout if
+    /// there is one.
+    pub fn get_border_style(&self, app_state: &App, widget_id: u64) -> tui::style::Style {
+        if widget_id == app_state.current_widget.widget_id {
             self.colours.highlighted_border_style
+        } else if let Some(alert_border_style) = self.get_alert_border_style(app_state, widget_id) {
+            alert_border_style
         } else {
-            self.colours.border_style
+            app_state
+                .widget_map
+                .get(&widget_id)
+                .and_then(|widget| widget.border_style)
+                .unwrap_or(self.colours.border_style)
         }
     }
 
+    /// Returns the border style of a widget that is over its alert threshold, which blinks
+    /// between the low battery colour and the usual border colour unless blinking is disabled.
+    pub fn get_alert_border_style(
+        &self, app_state: &App, widget_id: u64,
+    ) -> Option<tui::style::Style> {
+        if !app_state.alerting_widget_ids.contains(&widget_id) {
+            return None;
+        }
+
+        let is_blink_on = app_state.app_config_fields.disable_alert_blinking || is_alert_blink_on();
+
+        Some(if is_blink_on {
+            self.colours.low_battery_colour
+        } else {
+            self.colours.border_style
+        })
+    }
+
+    /// Determines the title style, using the title colour set for the widget in the layout if
+    /// there is one.
+    pub fn get_title_style(&self, app_state: &App, widget_id: u64) -> tui::style::Style {
+        app_state
+            .widget_map
+            .get(&widget_id)
+            .and_then(|widget| widget.title_style)
+            .unwrap_or(self.colours.widget_title_style)
+    }
+
     /// Must be run once before drawing, but after setting colours.
     /// This is to set some remaining styles and text.
     fn complete_painter_init(&mut self) {
@@ -215,12 +297,53 @@ impl Painter {
         self.styled_help_text = styled_help_spans.into_iter().map(Spans::from).collect();
     }
 
-    fn draw_frozen_indicator<B: Backend>(&self, f: &mut Frame<'_, B>, draw_loc: Rect) {
-        f.render_widget(
-            Paragraph::new(Span::styled(
+    /// Draws the line along the bottom, which lists the layout tabs if there are several, says
+    /// when bottom is frozen or a snapshot was just saved, and lists the alerts set up in the
+    /// config that are going off.
+    fn draw_status_line<B: Backend>(&self, f: &mut Frame<'_, B>, app_state: &App, draw_loc: Rect) {
+        let mut spans = Vec::new();
+        if app_state.tabs.len() > 1 {
+            for (index, tab) in app_state.tabs.iter().enumerate() {
+                spans.push(Span::styled(
+                    format!(" {}:{} ", index + 1, tab.name),
+                    if index == app_state.current_tab {
+                        self.colours.currently_selected_text_style
+                    } else {
+                        self.colours.text_style
+                    },
+                ));
+            }
+        }
+        if app_state.frozen_state.is_frozen() {
+            if !spans.is_empty() {
+                spans.push(Span::raw("  "));
+            }
+            spans.push(Span::styled(
                 "Frozen, press 'f' to unfreeze",
                 self.colours.currently_selected_text_style,
-            )),
+            ));
+        }
+        if let Some(path) = app_state.snapshot_notice() {
+            if !spans.is_empty() {
+                spans.push(Span::raw("  "));
+            }
+            spans.push(Span::styled(
+                format!("Saved snapshot to {path}"),
+                self.colours.text_style,
+            ));
+        }
+        for alert in app_state.alerts.triggered() {
+            if !spans.is_empty() {
+                spans.push(Span::raw("  "));
+            }
+            spans.push(Span::styled(
+                format!("Alert: {}", alert.describe()),
+                self.colours.low_battery_colour,
+            ));
+        }
+
+        f.render_widget(
+            Paragraph::new(Spans::from(spans)),
             Layout::default()
                 .horizontal_margin(1)
                 .constraints([Constraint::Length(1)])
@@ -233,8 +356,20 @@ impl Painter {
     ) -> error::Result<()> {
         use BottomWidgetType::*;
 
+        if self.current_tab != app_state.current_tab {
+            self.current_tab = app_state.current_tab;
+            self.init_layout_constraints();
+            app_state.is_force_redraw = true;
+        }
+
         terminal.draw(|f| {
-            let (terminal_size, frozen_draw_loc) = if app_state.frozen_state.is_frozen() {
+            // The command line takes the place of the status line while it's open.
+            let (terminal_size, status_draw_loc) = if app_state.tabs.len() > 1
+                || app_state.frozen_state.is_frozen()
+                || app_state.snapshot_notice().is_some()
+                || app_state.alerts.triggered().next().is_some()
+                || app_state.command_line_state.is_showing
+            {
                 let split_loc = Layout::default()
                     .constraints([Constraint::Min(0), Constraint::Length(1)])
                     .split(f.size());
@@ -309,10 +444,14 @@ impl Painter {
                     terminal_width * 50 / 100
                 };
 
-                let text_height = if cfg!(target_os = "windows")
-                    || !app_state.app_config_fields.is_advanced_kill
+                let text_height = if cfg!(target_os = "windows") || !app_state.is_choosing_signal()
                 {
-                    7
+                    // The hint for picking a signal instead takes up another line.
+                    if app_state.can_open_signal_picker() {
+                        8
+                    } else {
+                        7
+                    }
                 } else {
                     22
                 };
@@ -341,8 +480,8 @@ impl Painter {
                 app_state.delete_dialog_state.is_showing_dd =
                     self.draw_dd_dialog(f, dd_text, app_state, middle_dialog_chunk[1]);
             } else if app_state.is_expanded {
-                if let Some(frozen_draw_loc) = frozen_draw_loc {
-                    self.draw_frozen_indicator(f, frozen_draw_loc);
+                if let Some(status_draw_loc) = status_draw_loc {
+                    self.draw_status_line(f, app_state, status_draw_loc);
                 }
 
                 let rect = Layout::default()
@@ -419,13 +558,81 @@ impl Painter {
                         rect[0],
                         app_state.current_widget.widget_id,
                     ),
+                    Dashboard => self.draw_dashboard_table(
+                        f,
+                        app_state,
+                        rect[0],
+                        app_state.current_widget.widget_id,
+                    ),
+                    Discovery => self.draw_discovery_table(
+                        f,
+                        app_state,
+                        rect[0],
+                        app_state.current_widget.widget_id,
+                    ),
+                    Containers => self.draw_container_table(
+                        f,
+                        app_state,
+                        rect[0],
+                        app_state.current_widget.widget_id,
+                    ),
+                    Pressure => self.draw_pressure_graph(
+                        f,
+                        app_state,
+                        rect[0],
+                        app_state.current_widget.widget_id,
+                    ),
+                    Wifi => self.draw_wifi_graph(
+                        f,
+                        app_state,
+                        rect[0],
+                        app_state.current_widget.widget_id,
+                    ),
+                    Raid => self.draw_raid_table(
+                        f,
+                        app_state,
+                        rect[0],
+                        app_state.current_widget.widget_id,
+                    ),
+                    ZfsPools => self.draw_zfs_pool_table(
+                        f,
+                        app_state,
+                        rect[0],
+                        app_state.current_widget.widget_id,
+                    ),
+                    Cgroups => self.draw_cgroup_tree(
+                        f,
+                        app_state,
+                        rect[0],
+                        app_state.current_widget.widget_id,
+                    ),
+                    ScheduledJobs => self.draw_scheduled_job_table(
+                        f,
+                        app_state,
+                        rect[0],
+                        app_state.current_widget.widget_id,
+                    ),
+                    Logs => self.draw_log_display(
+                        f,
+                        app_state,
+                        rect[0],
+                        true,
+                        app_state.current_widget.widget_id,
+                    ),
+                    Custom(_) => self.draw_custom_widget(
+                        f,
+                        app_state,
+                        rect[0],
+                        true,
+                        app_state.current_widget.widget_id,
+                    ),
                     _ => {}
                 }
             } else if app_state.app_config_fields.use_basic_mode {
                 // Basic mode.  This basically removes all graphs but otherwise
                 // the same info.
-                if let Some(frozen_draw_loc) = frozen_draw_loc {
-                    self.draw_frozen_indicator(f, frozen_draw_loc);
+                if let Some(status_draw_loc) = status_draw_loc {
+                    self.draw_status_line(f, app_state, status_draw_loc);
                 }
 
                 let actual_cpu_data_len = app_state.converted_data.cpu_data.len().saturating_sub(1);
@@ -537,8 +744,8 @@ impl Painter {
                 }
             } else {
                 // Draws using the passed in (or default) layout.
-                if let Some(frozen_draw_loc) = frozen_draw_loc {
-                    self.draw_frozen_indicator(f, frozen_draw_loc);
+                if let Some(status_draw_loc) = status_draw_loc {
+                    self.draw_status_line(f, app_state, status_draw_loc);
                 }
 
                 if self.derived_widget_draw_locs.is_empty() || app_state.is_force_redraw {
@@ -702,7 +909,7 @@ impl Painter {
                         &self.col_constraints,
                         &self.col_row_constraints,
                         &self.layout_constraints,
-                        &self.widget_layout.rows
+                        &self.widget_layouts[self.current_tab].rows
                     )
                     .map(
                         |(
@@ -753,7 +960,7 @@ impl Painter {
                     )
                     .collect();
                 } else {
-                    self.widget_layout
+                    self.widget_layouts[self.current_tab]
                         .rows
                         .iter()
                         .flat_map(|row| &row.children)
@@ -769,6 +976,42 @@ impl Painter {
                         });
                 }
             }
+
+            if app_state.spawn_dialog_state.is_showing {
+                self.draw_spawn_dialog(f, app_state, terminal_size);
+            }
+
+            #[cfg(target_os = "linux")]
+            if app_state.io_priority_dialog_state.is_showing {
+                self.draw_io_priority_dialog(f, app_state, terminal_size);
+            }
+
+            if app_state.user_picker_state.is_showing {
+                self.draw_user_picker_dialog(f, app_state, terminal_size);
+            }
+
+            #[cfg(target_family = "unix")]
+            if app_state.mem_map_overlay.is_some() {
+                self.draw_mem_map_overlay(f, app_state, terminal_size);
+            }
+
+            if app_state.process_details_overlay.is_some() {
+                self.draw_process_details_overlay(f, app_state, terminal_size);
+            }
+
+            if app_state.layout_editor.is_some() {
+                self.draw_layout_editor(f, app_state, terminal_size);
+            }
+
+            if app_state.error_log_state.is_showing && !app_state.errors.is_empty() {
+                self.draw_error_log(f, app_state, terminal_size);
+            }
+
+            if let Some(status_draw_loc) = status_draw_loc {
+                if app_state.command_line_state.is_showing {
+                    self.draw_command_line(f, app_state, status_draw_loc);
+                }
+            }
         })?;
 
         if let Some(updated_current_widget) = app_state
@@ -832,6 +1075,46 @@ impl Painter {
                         *widget_draw_loc,
                         widget.widget_id,
                     ),
+                    Dashboard => {
+                        self.draw_dashboard_table(f, app_state, *widget_draw_loc, widget.widget_id)
+                    }
+                    Discovery => {
+                        self.draw_discovery_table(f, app_state, *widget_draw_loc, widget.widget_id)
+                    }
+                    Containers => {
+                        self.draw_container_table(f, app_state, *widget_draw_loc, widget.widget_id)
+                    }
+                    Pressure => {
+                        self.draw_pressure_graph(f, app_state, *widget_draw_loc, widget.widget_id)
+                    }
+                    Wifi => self.draw_wifi_graph(f, app_state, *widget_draw_loc, widget.widget_id),
+                    Raid => self.draw_raid_table(f, app_state, *widget_draw_loc, widget.widget_id),
+                    ZfsPools => {
+                        self.draw_zfs_pool_table(f, app_state, *widget_draw_loc, widget.widget_id)
+                    }
+                    Cgroups => {
+                        self.draw_cgroup_tree(f, app_state, *widget_draw_loc, widget.widget_id)
+                    }
+                    ScheduledJobs => self.draw_scheduled_job_table(
+                        f,
+                        app_state,
+                        *widget_draw_loc,
+                        widget.widget_id,
+                    ),
+                    Logs => self.draw_log_display(
+                        f,
+                        app_state,
+                        *widget_draw_loc,
+                        true,
+                        widget.widget_id,
+                    ),
+                    Custom(_) => self.draw_custom_widget(
+                        f,
+                        app_state,
+                        *widget_draw_loc,
+                        true,
+                        widget.widget_id,
+                    ),
                     _ => {}
                 }
             }
@@ -0,0 +1,172 @@
+//! Converting text with ANSI escape sequences, like the output of `ls --color`, into styled spans.
+
+use tui::{
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+};
+
+const ESC: char = '\x1b';
+const BEL: char = '\x07';
+
+/// The eight basic colours, in the order SGR codes 30-37 and 40-47 use.
+const BASIC_COLOURS: [Color; 8] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Gray,
+];
+
+/// The bright variants, for SGR codes 90-97 and 100-107.
+const BRIGHT_COLOURS: [Color; 8] = [
+    Color::DarkGray,
+    Color::LightRed,
+    Color::LightGreen,
+    Color::LightYellow,
+    Color::LightBlue,
+    Color::LightMagenta,
+    Color::LightCyan,
+    Color::White,
+];
+
+/// Converts a single line into spans, styling text after SGR ("Select Graphic Rendition")
+/// sequences and dropping every other escape sequence. Text starts in `base_style`, which is
+/// also what a reset goes back to.
+pub fn ansi_to_spans(line: &str, base_style: Style) -> Spans<'static> {
+    let mut spans = Vec::new();
+    let mut style = base_style;
+    let mut text = String::new();
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != ESC {
+            text.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            // CSI: parameters, then a final byte in '@'..='~' deciding what it does.
+            Some('[') => {
+                let mut params = String::new();
+                let mut final_byte = None;
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        final_byte = Some(c);
+                        break;
+                    }
+                    params.push(c);
+                }
+
+                if final_byte == Some('m') {
+                    if !text.is_empty() {
+                        spans.push(Span::styled(std::mem::take(&mut text), style));
+                    }
+                    style = apply_sgr(style, base_style, &params);
+                }
+            }
+            // OSC, like window titles or hyperlinks: ends with BEL or ESC '\'.
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == BEL || (c == ESC && chars.next_if_eq(&'\\').is_some()) {
+                        break;
+                    }
+                }
+            }
+            // Any other escape is a single character long.
+            _ => {}
+        }
+    }
+
+    if !text.is_empty() {
+        spans.push(Span::styled(text, style));
+    }
+
+    Spans::from(spans)
+}
+
+/// Applies the `;`-separated codes of an SGR sequence to `style`.
+fn apply_sgr(mut style: Style, base_style: Style, params: &str) -> Style {
+    let mut codes = params
+        .split(';')
+        .map(|code| code.parse::<u8>().unwrap_or(0));
+
+    while let Some(code) = codes.next() {
+        style = match code {
+            0 => base_style,
+            1 => style.add_modifier(Modifier::BOLD),
+            2 => style.add_modifier(Modifier::DIM),
+            3 => style.add_modifier(Modifier::ITALIC),
+            4 => style.add_modifier(Modifier::UNDERLINED),
+            5 => style.add_modifier(Modifier::SLOW_BLINK),
+            7 => style.add_modifier(Modifier::REVERSED),
+            9 => style.add_modifier(Modifier::CROSSED_OUT),
+            22 => style.remove_modifier(Modifier::BOLD | Modifier::DIM),
+            23 => style.remove_modifier(Modifier::ITALIC),
+            24 => style.remove_modifier(Modifier::UNDERLINED),
+            25 => style.remove_modifier(Modifier::SLOW_BLINK),
+            27 => style.remove_modifier(Modifier::REVERSED),
+            29 => style.remove_modifier(Modifier::CROSSED_OUT),
+            30..=37 => style.fg(BASIC_COLOURS[usize::from(code - 30)]),
+            38 => match extended_colour(&mut codes) {
+                Some(colour) => style.fg(colour),
+                None => style,
+            },
+            39 => style.fg(base_style.fg.unwrap_or(Color::Reset)),
+            40..=47 => style.bg(BASIC_COLOURS[usize::from(code - 40)]),
+            48 => match extended_colour(&mut codes) {
+                Some(colour) => style.bg(colour),
+                None => style,
+            },
+            49 => style.bg(base_style.bg.unwrap_or(Color::Reset)),
+            90..=97 => style.fg(BRIGHT_COLOURS[usize::from(code - 90)]),
+            100..=107 => style.bg(BRIGHT_COLOURS[usize::from(code - 100)]),
+            _ => style,
+        };
+    }
+
+    style
+}
+
+/// Reads the rest of a 256-colour (`5;n`) or true colour (`2;r;g;b`) code.
+fn extended_colour(codes: &mut impl Iterator<Item = u8>) -> Option<Color> {
+    match codes.next()? {
+        5 => Some(Color::Indexed(codes.next()?)),
+        2 => Some(Color::Rgb(codes.next()?, codes.next()?, codes.next()?)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_ansi_to_spans() {
+        let base = Style::default().fg(Color::Gray);
+        let spans = ansi_to_spans("a \x1b[1;31mred\x1b[0m b\x1b[K", base);
+
+        assert_eq!(
+            spans.0,
+            vec![
+                Span::styled("a ", base),
+                Span::styled("red", base.fg(Color::Red).add_modifier(Modifier::BOLD)),
+                Span::styled(" b", base),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extended_colours() {
+        let spans = ansi_to_spans(
+            "\x1b[38;5;208mx\x1b[48;2;1;2;3my\x1b]0;title\x07z",
+            Style::default(),
+        );
+
+        assert_eq!(spans.0[0].style.fg, Some(Color::Indexed(208)));
+        assert_eq!(spans.0[1].style.bg, Some(Color::Rgb(1, 2, 3)));
+        assert_eq!(spans.0[1].content, "yz");
+    }
+}
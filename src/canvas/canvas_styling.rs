@@ -10,7 +10,7 @@ use crate::{
     options::{Config, ConfigColours},
     utils::error,
 };
-mod colour_utils;
+pub mod colour_utils;
 
 pub struct CanvasColours {
     pub currently_selected_text_colour: Color,
@@ -36,6 +36,9 @@ pub struct CanvasColours {
     pub high_battery_colour: Style,
     pub medium_battery_colour: Style,
     pub low_battery_colour: Style,
+    pub low_latency_style: Style,
+    pub medium_latency_style: Style,
+    pub high_latency_style: Style,
     pub invalid_query_style: Style,
     pub disabled_text_style: Style,
 }
@@ -89,6 +92,9 @@ impl Default for CanvasColours {
             high_battery_colour: Style::default().fg(Color::Green),
             medium_battery_colour: Style::default().fg(Color::Yellow),
             low_battery_colour: Style::default().fg(Color::Red),
+            low_latency_style: Style::default().fg(Color::Green),
+            medium_latency_style: Style::default().fg(Color::Yellow),
+            high_latency_style: Style::default().fg(Color::Red),
             invalid_query_style: Style::default().fg(tui::style::Color::Red),
             disabled_text_style: Style::default().fg(Color::DarkGray),
         }
@@ -227,6 +233,21 @@ impl CanvasColours {
                 .context("Update 'low_battery_color' in your config file.")?;
         }
 
+        if let Some(low_latency_color) = &colours.low_latency_color {
+            self.set_low_latency_colour(low_latency_color)
+                .context("Update 'low_latency_color' in your config file.")?;
+        }
+
+        if let Some(medium_latency_color) = &colours.medium_latency_color {
+            self.set_medium_latency_colour(medium_latency_color)
+                .context("Update 'medium_latency_color' in your config file.")?;
+        }
+
+        if let Some(high_latency_color) = &colours.high_latency_color {
+            self.set_high_latency_colour(high_latency_color)
+                .context("Update 'high_latency_color' in your config file.")?;
+        }
+
         if let Some(disabled_text_color) = &colours.disabled_text_color {
             self.set_disabled_text_colour(disabled_text_color)
                 .context("Update 'disabled_text_color' in your config file.")?;
@@ -371,6 +392,21 @@ impl CanvasColours {
         self.low_battery_colour = str_to_fg(colour)?;
         Ok(())
     }
+
+    pub fn set_low_latency_colour(&mut self, colour: &str) -> error::Result<()> {
+        self.low_latency_style = str_to_fg(colour)?;
+        Ok(())
+    }
+
+    pub fn set_medium_latency_colour(&mut self, colour: &str) -> error::Result<()> {
+        self.medium_latency_style = str_to_fg(colour)?;
+        Ok(())
+    }
+
+    pub fn set_high_latency_colour(&mut self, colour: &str) -> error::Result<()> {
+        self.high_latency_style = str_to_fg(colour)?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -1,2 +1,12 @@
+pub mod command_line;
 pub mod dd_dialog;
+pub mod error_log;
 pub mod help_dialog;
+#[cfg(target_os = "linux")]
+pub mod io_priority_dialog;
+pub mod layout_editor;
+#[cfg(target_family = "unix")]
+pub mod mem_map_overlay;
+pub mod process_details_overlay;
+pub mod spawn_dialog;
+pub mod user_picker_dialog;
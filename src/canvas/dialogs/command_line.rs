@@ -0,0 +1,25 @@
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    terminal::Frame,
+    text::Span,
+    widgets::{Clear, Paragraph},
+};
+
+use crate::{app::App, canvas::Painter};
+
+impl Painter {
+    /// Draws the command line across `draw_loc`, which is the bottom line of the screen.
+    pub fn draw_command_line<B: Backend>(
+        &self, f: &mut Frame<'_, B>, app_state: &App, draw_loc: Rect,
+    ) {
+        let command_line_state = &app_state.command_line_state;
+        let line = match &command_line_state.error {
+            Some(error) => Span::styled(error.as_str(), self.colours.invalid_query_style).into(),
+            None => self.line_input_spans(":", &command_line_state.input),
+        };
+
+        f.render_widget(Clear, draw_loc);
+        f.render_widget(Paragraph::new(line), draw_loc);
+    }
+}
@@ -9,13 +9,16 @@ use tui::{
     widgets::{Block, Borders, Paragraph, Wrap},
 };
 
+#[cfg(target_family = "unix")]
+use crate::widgets::process_table::signals;
 use crate::{
     app::{App, KillSignal},
     canvas::Painter,
-    widgets::ProcWidgetMode,
+    widgets::{GroupBy, ProcWidgetMode},
 };
 
 const DD_BASE: &str = " Confirm Kill Process ── Esc to close ";
+const DD_CLOSE_BASE: &str = " Confirm Close Connection ── Esc to close ";
 const DD_ERROR_BASE: &str = " Error ── Esc to close ";
 
 impl Painter {
@@ -23,31 +26,65 @@ impl Painter {
         if let Some(dd_err) = &app_state.dd_err {
             return Some(Text::from(vec![
                 Spans::default(),
-                Spans::from("Failed to kill process."),
+                Spans::from(if app_state.get_to_close_connection().is_some() {
+                    "Failed to close connection."
+                } else {
+                    "Failed to kill process."
+                }),
                 Spans::from(dd_err.clone()),
                 Spans::from("Please press ENTER or ESC to close this dialog."),
             ]));
+        } else if let Some((protocol, local, remote)) = app_state.get_to_close_connection() {
+            return Some(Text::from(vec![
+                Spans::from(""),
+                Spans::from(format!(
+                    "Close {} connection {} -> {}?  Press ENTER to confirm.",
+                    protocol, local, remote
+                )),
+            ]));
         } else if let Some(to_kill_processes) = app_state.get_to_delete_processes() {
             if let Some(first_pid) = to_kill_processes.1.first() {
-                return Some(Text::from(vec![
+                let proc_widget_state = app_state
+                    .proc_state
+                    .widget_states
+                    .get(&app_state.current_widget.widget_id);
+
+                let mut lines = vec![
                     Spans::from(""),
-                    if app_state
-                        .proc_state
-                        .widget_states
-                        .get(&app_state.current_widget.widget_id)
-                        .map(|p| matches!(p.mode, ProcWidgetMode::Grouped))
+                    if proc_widget_state
+                        .map(|p| !p.selected_pids.is_empty())
                         .unwrap_or(false)
                     {
+                        Spans::from(format!(
+                            "Kill {} selected process{}?  Press ENTER to confirm.",
+                            to_kill_processes.1.len(),
+                            if to_kill_processes.1.len() == 1 {
+                                ""
+                            } else {
+                                "es"
+                            }
+                        ))
+                    } else if let Some(proc_widget_state) =
+                        proc_widget_state.filter(|p| matches!(p.mode, ProcWidgetMode::Grouped))
+                    {
+                        let group = match proc_widget_state.group_by {
+                            GroupBy::Name => "with the name",
+                            GroupBy::Executable => "running",
+                            #[cfg(target_os = "linux")]
+                            GroupBy::Container => "in",
+                        };
+
                         if to_kill_processes.1.len() != 1 {
                             Spans::from(format!(
-                                "Kill {} processes with the name \"{}\"?  Press ENTER to confirm.",
+                                "Kill {} processes {} \"{}\"?  Press ENTER to confirm.",
                                 to_kill_processes.1.len(),
+                                group,
                                 to_kill_processes.0
                             ))
                         } else {
                             Spans::from(format!(
-                                "Kill 1 process with the name \"{}\"?  Press ENTER to confirm.",
-                                to_kill_processes.0
+                                "Kill 1 process {} \"{}\"?  Press ENTER to confirm.",
+                                group, to_kill_processes.0
                             ))
                         }
                     } else {
@@ -56,7 +93,12 @@ impl Painter {
                             to_kill_processes.0, first_pid
                         ))
                     },
-                ]));
+                ];
+                if app_state.can_open_signal_picker() {
+                    lines.push(Spans::from("Press S to pick a different signal."));
+                }
+
+                return Some(Text::from(lines));
             }
         }
 
@@ -66,7 +108,7 @@ impl Painter {
     fn draw_dd_confirm_buttons<B: Backend>(
         &self, f: &mut Frame<'_, B>, button_draw_loc: &Rect, app_state: &mut App,
     ) {
-        if cfg!(target_os = "windows") || !app_state.app_config_fields.is_advanced_kill {
+        if cfg!(target_os = "windows") || !app_state.is_choosing_signal() {
             let (yes_button, no_button) = match app_state.delete_dialog_state.selected_signal {
                 KillSignal::Kill(_) => (
                     Span::styled("Yes", self.colours.currently_selected_text_style),
@@ -135,152 +177,6 @@ impl Painter {
         } else {
             #[cfg(target_family = "unix")]
             {
-                let signal_text;
-                #[cfg(target_os = "linux")]
-                {
-                    signal_text = vec![
-                        "0: Cancel",
-                        "1: HUP",
-                        "2: INT",
-                        "3: QUIT",
-                        "4: ILL",
-                        "5: TRAP",
-                        "6: ABRT",
-                        "7: BUS",
-                        "8: FPE",
-                        "9: KILL",
-                        "10: USR1",
-                        "11: SEGV",
-                        "12: USR2",
-                        "13: PIPE",
-                        "14: ALRM",
-                        "15: TERM",
-                        "16: STKFLT",
-                        "17: CHLD",
-                        "18: CONT",
-                        "19: STOP",
-                        "20: TSTP",
-                        "21: TTIN",
-                        "22: TTOU",
-                        "23: URG",
-                        "24: XCPU",
-                        "25: XFSZ",
-                        "26: VTALRM",
-                        "27: PROF",
-                        "28: WINCH",
-                        "29: IO",
-                        "30: PWR",
-                        "31: SYS",
-                        "34: RTMIN",
-                        "35: RTMIN+1",
-                        "36: RTMIN+2",
-                        "37: RTMIN+3",
-                        "38: RTMIN+4",
-                        "39: RTMIN+5",
-                        "40: RTMIN+6",
-                        "41: RTMIN+7",
-                        "42: RTMIN+8",
-                        "43: RTMIN+9",
-                        "44: RTMIN+10",
-                        "45: RTMIN+11",
-                        "46: RTMIN+12",
-                        "47: RTMIN+13",
-                        "48: RTMIN+14",
-                        "49: RTMIN+15",
-                        "50: RTMAX-14",
-                        "51: RTMAX-13",
-                        "52: RTMAX-12",
-                        "53: RTMAX-11",
-                        "54: RTMAX-10",
-                        "55: RTMAX-9",
-                        "56: RTMAX-8",
-                        "57: RTMAX-7",
-                        "58: RTMAX-6",
-                        "59: RTMAX-5",
-                        "60: RTMAX-4",
-                        "61: RTMAX-3",
-                        "62: RTMAX-2",
-                        "63: RTMAX-1",
-                        "64: RTMAX",
-                    ];
-                }
-                #[cfg(target_os = "macos")]
-                {
-                    signal_text = vec![
-                        "0: Cancel",
-                        "1: HUP",
-                        "2: INT",
-                        "3: QUIT",
-                        "4: ILL",
-                        "5: TRAP",
-                        "6: ABRT",
-                        "7: EMT",
-                        "8: FPE",
-                        "9: KILL",
-                        "10: BUS",
-                        "11: SEGV",
-                        "12: SYS",
-                        "13: PIPE",
-                        "14: ALRM",
-                        "15: TERM",
-                        "16: URG",
-                        "17: STOP",
-                        "18: TSTP",
-                        "19: CONT",
-                        "20: CHLD",
-                        "21: TTIN",
-                        "22: TTOU",
-                        "23: IO",
-                        "24: XCPU",
-                        "25: XFSZ",
-                        "26: VTALRM",
-                        "27: PROF",
-                        "28: WINCH",
-                        "29: INFO",
-                        "30: USR1",
-                        "31: USR2",
-                    ];
-                }
-                #[cfg(target_os = "freebsd")]
-                {
-                    signal_text = vec![
-                        "0: Cancel",
-                        "1: HUP",
-                        "2: INT",
-                        "3: QUIT",
-                        "4: ILL",
-                        "5: TRAP",
-                        "6: ABRT",
-                        "7: EMT",
-                        "8: FPE",
-                        "9: KILL",
-                        "10: BUS",
-                        "11: SEGV",
-                        "12: SYS",
-                        "13: PIPE",
-                        "14: ALRM",
-                        "15: TERM",
-                        "16: URG",
-                        "17: STOP",
-                        "18: TSTP",
-                        "19: CONT",
-                        "20: CHLD",
-                        "21: TTIN",
-                        "22: TTOU",
-                        "23: IO",
-                        "24: XCPU",
-                        "25: XFSZ",
-                        "26: VTALRM",
-                        "27: PROF",
-                        "28: WINCH",
-                        "29: INFO",
-                        "30: USR1",
-                        "31: USR2",
-                        "32: THR",
-                        "33: LIBRT",
-                    ];
-                }
-
                 let button_rect = Layout::default()
                     .direction(Direction::Horizontal)
                     .margin(1)
@@ -294,14 +190,8 @@ impl Painter {
                     )
                     .split(*button_draw_loc)[1];
 
-                let mut selected = match app_state.delete_dialog_state.selected_signal {
-                    KillSignal::Cancel => 0,
-                    KillSignal::Kill(signal) => signal,
-                };
-                // 32+33 are skipped
-                if selected > 31 {
-                    selected -= 2;
-                }
+                let selected =
+                    signals::picker_index(&app_state.delete_dialog_state.selected_signal);
 
                 let layout = Layout::default()
                     .direction(Direction::Vertical)
@@ -320,27 +210,37 @@ impl Painter {
                 };
                 let scroll_offset: usize = app_state.delete_dialog_state.scroll_pos;
 
-                let mut buttons = signal_text
-                    [scroll_offset + 1..min((layout.len()) + scroll_offset, signal_text.len())]
-                    .iter()
-                    .map(|text| Span::styled(*text, self.colours.text_style))
-                    .collect::<Vec<Span<'_>>>();
-                buttons.insert(0, Span::styled(signal_text[0], self.colours.text_style));
-                buttons[selected - scroll_offset] = Span::styled(
-                    signal_text[selected],
-                    self.colours.currently_selected_text_style,
+                // Cancelling always stays at the top, with the signals scrolling under it.
+                let indices = std::iter::once(0).chain(
+                    scroll_offset + 1
+                        ..min(layout.len() + scroll_offset, signals::SIGNALS.len() + 1),
                 );
+                let buttons = indices
+                    .clone()
+                    .map(|index| {
+                        let style = if index == selected {
+                            self.colours.currently_selected_text_style
+                        } else {
+                            self.colours.text_style
+                        };
+                        Span::styled(signals::picker_label(index), style)
+                    })
+                    .collect::<Vec<Span<'_>>>();
 
                 app_state.delete_dialog_state.button_positions = layout
                     .iter()
-                    .enumerate()
-                    .map(|(i, pos)| {
+                    .zip(indices)
+                    .map(|(pos, index)| {
+                        let signal = match signals::from_picker_index(index) {
+                            KillSignal::Cancel => 0,
+                            KillSignal::Kill(signal) => signal,
+                        };
                         (
                             pos.x,
                             pos.y,
                             pos.x + pos.width - 1,
                             pos.y + pos.height - 1,
-                            if i == 0 { 0 } else { scroll_offset } + i,
+                            signal,
                         )
                     })
                     .collect::<Vec<(u16, u16, u16, u16, usize)>>();
@@ -371,14 +271,19 @@ impl Painter {
                     ),
                 ])
             } else {
+                let (title, base) = if app_state.get_to_close_connection().is_some() {
+                    (" Confirm Close Connection ", DD_CLOSE_BASE)
+                } else {
+                    (" Confirm Kill Process ", DD_BASE)
+                };
                 Spans::from(vec![
-                    Span::styled(" Confirm Kill Process ", self.colours.widget_title_style),
+                    Span::styled(title, self.colours.widget_title_style),
                     Span::styled(
                         format!(
                             "─{}─ Esc to close ",
                             "─".repeat(
                                 usize::from(draw_loc.width)
-                                    .saturating_sub(DD_BASE.chars().count() + 2)
+                                    .saturating_sub(base.chars().count() + 2)
                             )
                         ),
                         self.colours.border_style,
@@ -393,20 +298,21 @@ impl Painter {
                             .title(dd_title)
                             .style(self.colours.border_style)
                             .borders(Borders::ALL)
-                            .border_style(self.colours.border_style),
+                            .border_style(self.colours.border_style)
+                            .border_type(self.border_type),
                     )
                     .style(self.colours.text_style)
                     .alignment(Alignment::Center)
                     .wrap(Wrap { trim: true }),
                 draw_loc,
             );
+            self.draw_nerd_font_corners(f, draw_loc);
 
-            let btn_height =
-                if cfg!(target_os = "windows") || !app_state.app_config_fields.is_advanced_kill {
-                    3
-                } else {
-                    20
-                };
+            let btn_height = if cfg!(target_os = "windows") || !app_state.is_choosing_signal() {
+                3
+            } else {
+                20
+            };
 
             // Now draw buttons if needed...
             let split_draw_loc = Layout::default()
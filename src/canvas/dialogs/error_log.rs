@@ -0,0 +1,83 @@
+use std::{cmp::min, time::Instant};
+
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    terminal::Frame,
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::{app::App, canvas::Painter};
+
+const ERROR_LOG_BASE: &str = " Errors ── Ctrl-e to close ";
+const ERROR_LOG_MAX_WIDTH: u16 = 80;
+const ERROR_LOG_MAX_HEIGHT: u16 = 10;
+
+impl Painter {
+    /// Draws the collection error log as a floating panel in the bottom right of `draw_loc`.
+    pub fn draw_error_log<B: Backend>(
+        &self, f: &mut Frame<'_, B>, app_state: &App, draw_loc: Rect,
+    ) {
+        let width = min(ERROR_LOG_MAX_WIDTH, draw_loc.width);
+        let height = min(
+            min(app_state.errors.len() as u16 + 2, ERROR_LOG_MAX_HEIGHT),
+            draw_loc.height,
+        );
+        let log_loc = Rect::new(
+            draw_loc.x + draw_loc.width - width,
+            draw_loc.y + draw_loc.height - height,
+            width,
+            height,
+        );
+
+        let title = Spans::from(vec![
+            Span::styled(" Errors ", self.colours.widget_title_style),
+            Span::styled(
+                format!(
+                    "─{}─ Ctrl-e to close ",
+                    "─".repeat(
+                        usize::from(width).saturating_sub(ERROR_LOG_BASE.chars().count() + 2)
+                    )
+                ),
+                self.colours.border_style,
+            ),
+        ]);
+
+        let block = Block::default()
+            .title(title)
+            .style(self.colours.border_style)
+            .borders(Borders::ALL)
+            .border_style(self.colours.highlighted_border_style)
+            .border_type(self.border_type);
+
+        let now = Instant::now();
+        let lines: Vec<Spans<'_>> = app_state
+            .errors
+            .iter()
+            .map(|error| {
+                Spans::from(vec![
+                    Span::styled(
+                        format!("{:>4}s ago ", now.duration_since(error.timestamp).as_secs()),
+                        self.colours.disabled_text_style,
+                    ),
+                    Span::styled(
+                        format!("{}: ", error.source),
+                        self.colours.invalid_query_style,
+                    ),
+                    Span::styled(error.message.as_str(), self.colours.text_style),
+                ])
+            })
+            .collect();
+
+        // Keep the newest errors, which are at the bottom, in view.
+        let scroll = (lines.len() as u16).saturating_sub(block.inner(log_loc).height);
+
+        f.render_widget(Clear, log_loc);
+        f.render_widget(
+            Paragraph::new(lines).block(block).scroll((scroll, 0)),
+            log_loc,
+        );
+        self.draw_nerd_font_corners(f, log_loc);
+    }
+}
@@ -36,7 +36,8 @@ impl Painter {
             .title(help_title)
             .style(self.colours.border_style)
             .borders(Borders::ALL)
-            .border_style(self.colours.border_style);
+            .border_style(self.colours.border_style)
+            .border_type(self.border_type);
 
         if app_state.should_get_widget_bounds() {
             app_state.help_dialog_state.height = block.inner(draw_loc).height;
@@ -104,5 +105,6 @@ impl Painter {
                 )),
             draw_loc,
         );
+        self.draw_nerd_font_corners(f, draw_loc);
     }
 }
@@ -0,0 +1,125 @@
+use std::cmp::min;
+
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    terminal::Frame,
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::{
+    app::{data_harvester::processes::IOClass, App},
+    canvas::Painter,
+};
+
+const IO_PRIORITY_DIALOG_BASE: &str = " I/O priority ── Esc to close ";
+const IO_PRIORITY_DIALOG_MAX_WIDTH: u16 = 50;
+const IO_PRIORITY_DIALOG_HEIGHT: u16 = 6;
+
+impl Painter {
+    /// Draws the dialog for changing the I/O class and level of processes as a floating box in
+    /// the middle of `draw_loc`.
+    pub fn draw_io_priority_dialog<B: Backend>(
+        &self, f: &mut Frame<'_, B>, app_state: &App, draw_loc: Rect,
+    ) {
+        let dialog_state = &app_state.io_priority_dialog_state;
+        let width = min(IO_PRIORITY_DIALOG_MAX_WIDTH, draw_loc.width);
+        let height = min(IO_PRIORITY_DIALOG_HEIGHT, draw_loc.height);
+        let dialog_loc = Rect::new(
+            draw_loc.x + (draw_loc.width - width) / 2,
+            draw_loc.y + (draw_loc.height - height) / 2,
+            width,
+            height,
+        );
+
+        let title = Spans::from(vec![
+            Span::styled(" I/O priority ", self.colours.widget_title_style),
+            Span::styled(
+                format!(
+                    "─{}─ Esc to close ",
+                    "─".repeat(
+                        usize::from(width)
+                            .saturating_sub(IO_PRIORITY_DIALOG_BASE.chars().count() + 2)
+                    )
+                ),
+                self.colours.border_style,
+            ),
+        ]);
+
+        let block = Block::default()
+            .title(title)
+            .style(self.colours.border_style)
+            .borders(Borders::ALL)
+            .border_style(self.colours.highlighted_border_style)
+            .border_type(self.border_type);
+
+        let process_line = Spans::from(vec![
+            Span::styled("Process: ", self.colours.table_header_style),
+            Span::styled(
+                if dialog_state.pids.len() > 1 {
+                    format!(
+                        "{} ({} processes)",
+                        dialog_state.name,
+                        dialog_state.pids.len()
+                    )
+                } else {
+                    dialog_state.name.clone()
+                },
+                self.colours.text_style,
+            ),
+        ]);
+
+        let mut class_line = vec![Span::styled("Class:   ", self.colours.table_header_style)];
+        for class in IOClass::ALL {
+            class_line.push(Span::styled(
+                format!(" {class} "),
+                if class == dialog_state.class {
+                    self.colours.currently_selected_text_style
+                } else {
+                    self.colours.text_style
+                },
+            ));
+        }
+
+        let level_line = Spans::from(vec![
+            Span::styled("Level:   ", self.colours.table_header_style),
+            Span::styled(
+                format!("{} (0-7)", dialog_state.level),
+                if dialog_state.class.has_level() {
+                    self.colours.text_style
+                } else {
+                    self.colours.disabled_text_style
+                },
+            ),
+        ]);
+
+        let result_line = match &dialog_state.result {
+            Some(Ok(())) => Spans::from(Span::styled(
+                "Done, Enter to close",
+                self.colours.text_style,
+            )),
+            Some(Err(err)) => Spans::from(Span::styled(
+                format!("Failed to set: {err}"),
+                self.colours.invalid_query_style,
+            )),
+            None => Spans::from(Span::styled(
+                "←/→ class, ↑/↓ or 0-7 level, Enter to apply",
+                self.colours.disabled_text_style,
+            )),
+        };
+
+        f.render_widget(Clear, dialog_loc);
+        f.render_widget(
+            Paragraph::new(vec![
+                process_line,
+                Spans::from(class_line),
+                level_line,
+                result_line,
+            ])
+            .block(block),
+            dialog_loc,
+        );
+        self.draw_nerd_font_corners(f, dialog_loc);
+    }
+}
@@ -0,0 +1,115 @@
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    terminal::Frame,
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+};
+
+use crate::{
+    app::{
+        layout_editor::{Entry, LayoutEditor},
+        App,
+    },
+    canvas::Painter,
+};
+
+const LAYOUT_EDITOR_HELP: &str = "Up/Down select, Shift-Up/Down move, Left/Right change widget, \
+    +/- resize, a add widget, r add row, c toggle column, d remove, Enter save";
+
+impl Painter {
+    /// Draws the rows, columns and widgets of the layout being edited over the whole of
+    /// `draw_loc`, with the keys to edit them underneath.
+    pub fn draw_layout_editor<B: Backend>(
+        &self, f: &mut Frame<'_, B>, app_state: &App, draw_loc: Rect,
+    ) {
+        let editor = match &app_state.layout_editor {
+            Some(editor) => editor,
+            None => return,
+        };
+
+        let title = match editor.tab.and_then(|index| app_state.tabs.get(index)) {
+            Some(tab) => format!(" Layout editor ({}) ", tab.name),
+            None => " Layout editor ".to_string(),
+        };
+        let block = Block::default()
+            .title(Spans::from(vec![
+                Span::styled(title, self.colours.widget_title_style),
+                Span::styled("─ Esc to close ", self.colours.border_style),
+            ]))
+            .style(self.colours.border_style)
+            .borders(Borders::ALL)
+            .border_style(self.colours.highlighted_border_style)
+            .border_type(self.border_type);
+        let inner = block.inner(draw_loc);
+
+        f.render_widget(Clear, draw_loc);
+        f.render_widget(block, draw_loc);
+
+        let mut footer = vec![Spans::from(Span::styled(
+            LAYOUT_EDITOR_HELP,
+            self.colours.disabled_text_style,
+        ))];
+        if let Some(error) = &editor.error {
+            footer.push(Spans::from(Span::styled(
+                format!("Unable to save: {error}"),
+                self.colours.invalid_query_style,
+            )));
+        }
+        let [list_loc, footer_loc]: [Rect; 2] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),
+                Constraint::Length(footer.len() as u16 + 2),
+            ])
+            .split(inner)
+            .try_into()
+            .unwrap_or([inner, Rect::default()]);
+
+        // Scroll just enough to keep the selected entry in view.
+        let scroll = editor
+            .selected
+            .saturating_sub(usize::from(list_loc.height).saturating_sub(1));
+        let lines = editor
+            .entries()
+            .into_iter()
+            .enumerate()
+            .skip(scroll)
+            .take(usize::from(list_loc.height))
+            .map(|(index, entry)| {
+                let style = if index == editor.selected {
+                    self.colours.currently_selected_text_style
+                } else {
+                    self.colours.text_style
+                };
+                Spans::from(Span::styled(entry_text(editor, entry), style))
+            })
+            .collect::<Vec<_>>();
+
+        f.render_widget(Paragraph::new(lines), list_loc);
+        f.render_widget(Paragraph::new(footer).wrap(Wrap { trim: true }), footer_loc);
+
+        self.draw_nerd_font_corners(f, draw_loc);
+    }
+}
+
+/// Describes an entry, indented by how deep it is in the layout.
+fn entry_text(editor: &LayoutEditor, entry: Entry) -> String {
+    let ratio = editor.ratio(entry);
+    match entry {
+        Entry::Row(row) => format!("Row {} ─ ratio {ratio}", row + 1),
+        Entry::Column(..) => format!("  Column ─ ratio {ratio}"),
+        Entry::Widget(..) | Entry::ColumnWidget(..) => {
+            let indent = if matches!(entry, Entry::Widget(..)) {
+                "  "
+            } else {
+                "    "
+            };
+            let widget_type = editor
+                .widget(entry)
+                .map(|widget| widget.widget_type.as_str())
+                .unwrap_or_default();
+            format!("{indent}{widget_type} ─ ratio {ratio}")
+        }
+    }
+}
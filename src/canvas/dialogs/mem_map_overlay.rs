@@ -0,0 +1,100 @@
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Rect},
+    terminal::Frame,
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, Paragraph, Row, Table},
+};
+
+use crate::{
+    app::{mem_map::MemMapOverlay, App},
+    canvas::Painter,
+    data_conversion::binary_byte_string,
+};
+
+const MEM_MAP_COLUMNS: [&str; 4] = ["Address", "Size", "Perms", "File"];
+
+impl Painter {
+    /// Draws the memory map of a process as a scrollable table over the whole of `draw_loc`.
+    pub fn draw_mem_map_overlay<B: Backend>(
+        &self, f: &mut Frame<'_, B>, app_state: &App, draw_loc: Rect,
+    ) {
+        let overlay = match &app_state.mem_map_overlay {
+            Some(overlay) => overlay,
+            None => return,
+        };
+
+        let title = Spans::from(vec![
+            Span::styled(
+                format!(
+                    " Memory map of {} ({}) ─ {} regions ",
+                    overlay.name,
+                    overlay.pid,
+                    overlay.entries.len()
+                ),
+                self.colours.widget_title_style,
+            ),
+            Span::styled("─ Esc to close ", self.colours.border_style),
+        ]);
+
+        let block = Block::default()
+            .title(title)
+            .style(self.colours.border_style)
+            .borders(Borders::ALL)
+            .border_style(self.colours.highlighted_border_style)
+            .border_type(self.border_type);
+
+        f.render_widget(Clear, draw_loc);
+
+        if let Some(error) = &overlay.error {
+            f.render_widget(
+                Paragraph::new(Span::styled(
+                    format!("Unable to read the memory map: {error}"),
+                    self.colours.invalid_query_style,
+                ))
+                .block(block),
+                draw_loc,
+            );
+        } else {
+            // Leave room for the borders and the header.
+            let visible_rows = usize::from(draw_loc.height.saturating_sub(3));
+
+            f.render_widget(
+                Table::new(mem_map_rows(overlay, visible_rows))
+                    .header(Row::new(MEM_MAP_COLUMNS).style(self.colours.table_header_style))
+                    .style(self.colours.text_style)
+                    .block(block)
+                    .widths(&[
+                        Constraint::Length(33),
+                        Constraint::Length(10),
+                        Constraint::Length(6),
+                        Constraint::Min(0),
+                    ]),
+                draw_loc,
+            );
+        }
+
+        self.draw_nerd_font_corners(f, draw_loc);
+    }
+}
+
+fn mem_map_rows(overlay: &MemMapOverlay, visible_rows: usize) -> Vec<Row<'_>> {
+    overlay
+        .entries
+        .iter()
+        .skip(overlay.scroll)
+        .take(visible_rows)
+        .map(|entry| {
+            Row::new(vec![
+                format!("{:016x}-{:016x}", entry.start, entry.end),
+                binary_byte_string(entry.size()),
+                entry.perms.clone(),
+                if entry.pathname.is_empty() {
+                    "[anon]".to_string()
+                } else {
+                    entry.pathname.clone()
+                },
+            ])
+        })
+        .collect()
+}
@@ -0,0 +1,88 @@
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    terminal::Frame,
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::{
+    app::{process_details::ProcessDetailsOverlay, App},
+    canvas::Painter,
+};
+
+impl Painter {
+    /// Draws the details of a process as a scrollable list of sections over the whole of
+    /// `draw_loc`.
+    pub fn draw_process_details_overlay<B: Backend>(
+        &self, f: &mut Frame<'_, B>, app_state: &mut App, draw_loc: Rect,
+    ) {
+        let overlay = match &mut app_state.process_details_overlay {
+            Some(overlay) => overlay,
+            None => return,
+        };
+
+        // Leave room for the borders.
+        overlay.page_height = usize::from(draw_loc.height.saturating_sub(2));
+
+        let title = Spans::from(vec![
+            Span::styled(
+                format!(" Details of {} ({}) ", overlay.name, overlay.pid),
+                self.colours.widget_title_style,
+            ),
+            Span::styled("─ Esc to close ", self.colours.border_style),
+        ]);
+
+        let block = Block::default()
+            .title(title)
+            .style(self.colours.border_style)
+            .borders(Borders::ALL)
+            .border_style(self.colours.highlighted_border_style)
+            .border_type(self.border_type);
+
+        f.render_widget(Clear, draw_loc);
+        f.render_widget(
+            Paragraph::new(self.process_details_lines(overlay))
+                .style(self.colours.text_style)
+                .block(block),
+            draw_loc,
+        );
+
+        self.draw_nerd_font_corners(f, draw_loc);
+    }
+
+    fn process_details_lines<'a>(&self, overlay: &'a ProcessDetailsOverlay) -> Vec<Spans<'a>> {
+        overlay
+            .sections
+            .iter()
+            .flat_map(|section| {
+                let title = if section.lines.len() > 1 {
+                    format!("{} ({})", section.title, section.lines.len())
+                } else {
+                    section.title.to_string()
+                };
+                let title = Spans::from(Span::styled(title, self.colours.table_header_style));
+                let lines = if let Some(error) = &section.error {
+                    vec![Spans::from(Span::styled(
+                        format!("  Unable to read: {error}"),
+                        self.colours.invalid_query_style,
+                    ))]
+                } else if section.lines.is_empty() {
+                    vec![Spans::from(Span::styled(
+                        "  None",
+                        self.colours.disabled_text_style,
+                    ))]
+                } else {
+                    section
+                        .lines
+                        .iter()
+                        .map(|line| Spans::from(format!("  {line}")))
+                        .collect()
+                };
+
+                std::iter::once(title).chain(lines)
+            })
+            .skip(overlay.scroll)
+            .collect()
+    }
+}
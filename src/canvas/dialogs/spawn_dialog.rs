@@ -0,0 +1,95 @@
+use std::cmp::min;
+
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    terminal::Frame,
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::{
+    app::{App, LineInput},
+    canvas::Painter,
+};
+
+const SPAWN_DIALOG_BASE: &str = " Run command ── Esc to close ";
+const SPAWN_DIALOG_MAX_WIDTH: u16 = 60;
+const SPAWN_DIALOG_HEIGHT: u16 = 4;
+
+impl Painter {
+    /// Draws the dialog for running a new command as a floating box in the middle of `draw_loc`.
+    pub fn draw_spawn_dialog<B: Backend>(
+        &self, f: &mut Frame<'_, B>, app_state: &App, draw_loc: Rect,
+    ) {
+        let spawn_dialog_state = &app_state.spawn_dialog_state;
+        let width = min(SPAWN_DIALOG_MAX_WIDTH, draw_loc.width);
+        let height = min(SPAWN_DIALOG_HEIGHT, draw_loc.height);
+        let dialog_loc = Rect::new(
+            draw_loc.x + (draw_loc.width - width) / 2,
+            draw_loc.y + (draw_loc.height - height) / 2,
+            width,
+            height,
+        );
+
+        let title = Spans::from(vec![
+            Span::styled(" Run command ", self.colours.widget_title_style),
+            Span::styled(
+                format!(
+                    "─{}─ Esc to close ",
+                    "─".repeat(
+                        usize::from(width).saturating_sub(SPAWN_DIALOG_BASE.chars().count() + 2)
+                    )
+                ),
+                self.colours.border_style,
+            ),
+        ]);
+
+        let block = Block::default()
+            .title(title)
+            .style(self.colours.border_style)
+            .borders(Borders::ALL)
+            .border_style(self.colours.highlighted_border_style)
+            .border_type(self.border_type);
+
+        let input_line = self.line_input_spans("> ", &spawn_dialog_state.input);
+        let result_line = match &spawn_dialog_state.result {
+            Some(Ok(pid)) => Spans::from(Span::styled(
+                format!("Started PID {pid}, Enter to close"),
+                self.colours.text_style,
+            )),
+            Some(Err(err)) => Spans::from(Span::styled(
+                format!("Failed to run: {err}"),
+                self.colours.invalid_query_style,
+            )),
+            None => Spans::from(Span::styled(
+                "Enter to run",
+                self.colours.disabled_text_style,
+            )),
+        };
+
+        f.render_widget(Clear, dialog_loc);
+        f.render_widget(
+            Paragraph::new(vec![input_line, result_line]).block(block),
+            dialog_loc,
+        );
+        self.draw_nerd_font_corners(f, dialog_loc);
+    }
+
+    /// Lays out text being typed after `prompt`, with the cursor highlighted.
+    pub(super) fn line_input_spans<'a>(&self, prompt: &'a str, input: &'a LineInput) -> Spans<'a> {
+        let (before_cursor, after_cursor) = input.text.split_at(input.cursor_offset);
+        let mut after_cursor = after_cursor.chars();
+        let cursor = after_cursor
+            .next()
+            .map(String::from)
+            .unwrap_or_else(|| " ".to_string());
+
+        Spans::from(vec![
+            Span::styled(prompt, self.colours.table_header_style),
+            Span::styled(before_cursor, self.colours.text_style),
+            Span::styled(cursor, self.colours.currently_selected_text_style),
+            Span::styled(after_cursor.as_str(), self.colours.text_style),
+        ])
+    }
+}
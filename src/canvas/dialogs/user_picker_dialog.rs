@@ -0,0 +1,89 @@
+use std::cmp::min;
+
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    terminal::Frame,
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::{app::App, canvas::Painter};
+
+const USER_PICKER_BASE: &str = " Show user ── Esc to close ";
+const USER_PICKER_MAX_WIDTH: u16 = 40;
+const USER_PICKER_MAX_HEIGHT: u16 = 14;
+
+impl Painter {
+    /// Draws the popup for picking whose processes to show as a floating list in the middle of
+    /// `draw_loc`.
+    pub fn draw_user_picker_dialog<B: Backend>(
+        &self, f: &mut Frame<'_, B>, app_state: &App, draw_loc: Rect,
+    ) {
+        let picker_state = &app_state.user_picker_state;
+        let width = min(USER_PICKER_MAX_WIDTH, draw_loc.width);
+        let height = min(
+            USER_PICKER_MAX_HEIGHT,
+            (picker_state.users.len().max(1) as u16).saturating_add(2),
+        )
+        .min(draw_loc.height);
+        let dialog_loc = Rect::new(
+            draw_loc.x + (draw_loc.width - width) / 2,
+            draw_loc.y + (draw_loc.height - height) / 2,
+            width,
+            height,
+        );
+
+        let title = Spans::from(vec![
+            Span::styled(" Show user ", self.colours.widget_title_style),
+            Span::styled(
+                format!(
+                    "─{}─ Esc to close ",
+                    "─".repeat(
+                        usize::from(width).saturating_sub(USER_PICKER_BASE.chars().count() + 2)
+                    )
+                ),
+                self.colours.border_style,
+            ),
+        ]);
+
+        let block = Block::default()
+            .title(title)
+            .style(self.colours.border_style)
+            .borders(Borders::ALL)
+            .border_style(self.colours.highlighted_border_style)
+            .border_type(self.border_type);
+
+        // Scroll just enough to keep the highlighted user in view.
+        let rows = usize::from(height.saturating_sub(2));
+        let start = (picker_state.current_index + 1).saturating_sub(rows);
+        let lines = if picker_state.users.is_empty() {
+            vec![Spans::from(Span::styled(
+                "No users",
+                self.colours.disabled_text_style,
+            ))]
+        } else {
+            picker_state
+                .users
+                .iter()
+                .enumerate()
+                .skip(start)
+                .take(rows)
+                .map(|(index, user)| {
+                    Spans::from(Span::styled(
+                        user.clone(),
+                        if index == picker_state.current_index {
+                            self.colours.currently_selected_text_style
+                        } else {
+                            self.colours.text_style
+                        },
+                    ))
+                })
+                .collect()
+        };
+
+        f.render_widget(Clear, dialog_loc);
+        f.render_widget(Paragraph::new(lines).block(block), dialog_loc);
+        self.draw_nerd_font_corners(f, dialog_loc);
+    }
+}
@@ -1,6 +1,63 @@
 use std::{cmp::min, time::Instant};
 
-use tui::layout::Rect;
+use concat_string::concat_string;
+use tui::{buffer::Buffer, layout::Rect, widgets::Widget};
+use unicode_width::UnicodeWidthStr;
+
+/// Replaces the box-drawing corners of a bordered block with nerd font glyphs. Cells that don't
+/// hold a corner, such as for blocks only bordered on the sides, are left alone.
+pub struct NerdFontCorners;
+
+impl NerdFontCorners {
+    const TOP_LEFT: &'static str = "\u{e0bc}";
+    const TOP_RIGHT: &'static str = "\u{e0be}";
+    const BOTTOM_LEFT: &'static str = "\u{e0b8}";
+    const BOTTOM_RIGHT: &'static str = "\u{e0ba}";
+
+    fn is_corner(symbol: &str) -> bool {
+        matches!(
+            symbol,
+            "┌" | "┐"
+                | "└"
+                | "┘"
+                | "╭"
+                | "╮"
+                | "╰"
+                | "╯"
+                | "╔"
+                | "╗"
+                | "╚"
+                | "╝"
+                | "┏"
+                | "┓"
+                | "┗"
+                | "┛"
+        )
+    }
+}
+
+impl Widget for NerdFontCorners {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width < 2 || area.height < 2 {
+            return;
+        }
+
+        let (left, right) = (area.left(), area.right() - 1);
+        let (top, bottom) = (area.top(), area.bottom() - 1);
+
+        for (x, y, glyph) in [
+            (left, top, Self::TOP_LEFT),
+            (right, top, Self::TOP_RIGHT),
+            (left, bottom, Self::BOTTOM_LEFT),
+            (right, bottom, Self::BOTTOM_RIGHT),
+        ] {
+            let cell = buf.get_mut(x, y);
+            if Self::is_corner(&cell.symbol) {
+                cell.set_symbol(glyph);
+            }
+        }
+    }
+}
 
 /// Calculate how many bars are to be drawn within basic mode's components.
 pub fn calculate_basic_use_bars(use_percentage: f64, num_bars_available: usize) -> usize {
@@ -30,11 +87,57 @@ pub fn should_hide_x_label(
     }
 }
 
+/// Joins an ancestry chain with " > ", dropping the oldest ancestors (and marking that with "…")
+/// until it fits in `width` columns.
+pub fn ancestry_breadcrumb(chain: &[String], width: usize) -> String {
+    let breadcrumb = chain.join(" > ");
+    if breadcrumb.width() <= width {
+        return breadcrumb;
+    }
+
+    for start in 1..chain.len() {
+        let breadcrumb = concat_string!("… > ", chain[start..].join(" > "));
+        if breadcrumb.width() <= width || start == chain.len() - 1 {
+            return breadcrumb;
+        }
+    }
+
+    breadcrumb
+}
+
 #[cfg(test)]
 mod test {
 
     use super::*;
 
+    #[test]
+    fn test_nerd_font_corners() {
+        use tui::widgets::{Block, BorderType, Borders};
+
+        let area = Rect::new(0, 0, 4, 3);
+
+        let mut buf = Buffer::empty(area);
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .render(area, &mut buf);
+        NerdFontCorners.render(area, &mut buf);
+        assert_eq!(buf.get(0, 0).symbol, NerdFontCorners::TOP_LEFT);
+        assert_eq!(buf.get(3, 0).symbol, NerdFontCorners::TOP_RIGHT);
+        assert_eq!(buf.get(0, 2).symbol, NerdFontCorners::BOTTOM_LEFT);
+        assert_eq!(buf.get(3, 2).symbol, NerdFontCorners::BOTTOM_RIGHT);
+        assert_eq!(buf.get(1, 0).symbol, "─");
+
+        // Side borders have no corners to replace.
+        let mut buf = Buffer::empty(area);
+        Block::default()
+            .borders(Borders::LEFT | Borders::RIGHT)
+            .render(area, &mut buf);
+        NerdFontCorners.render(area, &mut buf);
+        assert_eq!(buf.get(0, 0).symbol, "│");
+        assert_eq!(buf.get(3, 2).symbol, "│");
+    }
+
     #[test]
     fn test_calculate_basic_use_bars() {
         // Testing various breakpoints and edge cases.
@@ -49,6 +152,18 @@ mod test {
         assert_eq!(calculate_basic_use_bars(150.0, 15), 15);
     }
 
+    #[test]
+    fn test_ancestry_breadcrumb() {
+        let chain = ["1(init)", "20(sshd)", "300(bash)"].map(String::from);
+
+        assert_eq!(
+            ancestry_breadcrumb(&chain, 40),
+            "1(init) > 20(sshd) > 300(bash)"
+        );
+        assert_eq!(ancestry_breadcrumb(&chain, 25), "… > 20(sshd) > 300(bash)");
+        assert_eq!(ancestry_breadcrumb(&chain, 5), "… > 300(bash)");
+    }
+
     #[test]
     fn test_should_hide_x_label() {
         use std::time::{Duration, Instant};
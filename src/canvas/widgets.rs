@@ -1,13 +1,24 @@
 pub mod basic_table_arrows;
 pub mod battery_display;
+pub mod cgroup_tree;
 pub mod connections_table;
+pub mod container_table;
 pub mod cpu_basic;
 pub mod cpu_graph;
+pub mod custom_display;
+pub mod dashboard_table;
+pub mod discovery_table;
 pub mod disk_table;
+pub mod log_display;
 pub mod mem_basic;
 pub mod network_basic;
 pub mod network_graph;
+pub mod pressure_graph;
 pub mod process_table;
+pub mod raid_table;
+pub mod scheduled_job_table;
 pub mod temperature_table;
 pub mod terminal_display;
 pub mod uptime_display;
+pub mod wifi_graph;
+pub mod zfs_pool_table;
@@ -1,15 +1,19 @@
 use tui::{
     backend::Backend,
     layout::{Constraint, Direction, Layout, Rect},
+    symbols::Marker,
     terminal::Frame,
     text::{Span, Spans},
     widgets::{Block, Borders, Cell, Paragraph, Row, Table},
 };
-use unicode_segmentation::UnicodeSegmentation;
 
 use crate::{
     app::App,
-    canvas::{drawing_utils::calculate_basic_use_bars, Painter},
+    canvas::{
+        drawing_utils::{calculate_basic_use_bars, should_hide_x_label},
+        Painter,
+    },
+    components::time_graph::{GraphData, TimeGraph},
     constants::*,
     data_conversion::BatteryDuration,
 };
@@ -19,48 +23,36 @@ impl Painter {
         &self, f: &mut Frame<'_, B>, app_state: &mut App, draw_loc: Rect, draw_border: bool,
         widget_id: u64,
     ) {
+        if app_state.is_expanded {
+            self.draw_battery_graph(f, app_state, draw_loc, widget_id);
+            return;
+        }
+
+        let border_style = self.get_border_style(app_state, widget_id);
+        let title_style = self.get_title_style(app_state, widget_id);
         if let Some(battery_widget_state) =
             app_state.battery_state.widget_states.get_mut(&widget_id)
         {
             let is_on_widget = widget_id == app_state.current_widget.widget_id;
-            let border_style = if is_on_widget {
-                self.colours.highlighted_border_style
-            } else {
-                self.colours.border_style
-            };
             let table_gap = if draw_loc.height < TABLE_GAP_HEIGHT_LIMIT {
                 0
             } else {
                 app_state.app_config_fields.table_gap
             };
 
-            let title = if app_state.is_expanded {
-                const TITLE_BASE: &str = " Battery ── Esc to go back ";
-                Spans::from(vec![
-                    Span::styled(" Battery ", self.colours.widget_title_style),
-                    Span::styled(
-                        format!(
-                            "─{}─ Esc to go back ",
-                            "─".repeat(usize::from(draw_loc.width).saturating_sub(
-                                UnicodeSegmentation::graphemes(TITLE_BASE, true).count() + 2
-                            ))
-                        ),
-                        border_style,
-                    ),
-                ])
-            } else {
-                Spans::from(Span::styled(" Battery ", self.colours.widget_title_style))
-            };
+            let title = Spans::from(Span::styled(" Battery ", title_style));
 
             let battery_block = if draw_border {
                 Block::default()
                     .title(title)
                     .borders(Borders::ALL)
                     .border_style(border_style)
+                    .border_type(self.border_type)
             } else if is_on_widget {
                 Block::default()
                     .borders(SIDE_BORDERS)
                     .border_style(self.colours.highlighted_border_style)
+                    .border_type(self.border_type)
             } else {
                 Block::default().borders(Borders::NONE)
             };
@@ -88,28 +80,6 @@ impl Painter {
                     charge_percentage,
                 );
 
-                fn long_time(secs: i64) -> String {
-                    let time = time::Duration::seconds(secs);
-                    let num_minutes = time.whole_minutes() - time.whole_hours() * 60;
-                    let num_seconds = time.whole_seconds() - time.whole_minutes() * 60;
-                    format!(
-                        "{} hour{}, {} minute{}, {} second{}",
-                        time.whole_hours(),
-                        if time.whole_hours() == 1 { "" } else { "s" },
-                        num_minutes,
-                        if num_minutes == 1 { "" } else { "s" },
-                        num_seconds,
-                        if num_seconds == 1 { "" } else { "s" },
-                    )
-                }
-
-                fn short_time(secs: i64) -> String {
-                    let time = time::Duration::seconds(secs);
-                    let num_minutes = time.whole_minutes() - time.whole_hours() * 60;
-                    let num_seconds = time.whole_seconds() - time.whole_minutes() * 60;
-                    format!("{}h {}m {}s", time.whole_hours(), num_minutes, num_seconds,)
-                }
-
                 let mut battery_rows = Vec::with_capacity(4);
                 battery_rows.push(Row::new(vec![
                     Cell::from("Charge %").style(self.colours.text_style),
@@ -177,6 +147,111 @@ impl Painter {
                     margined_draw_loc,
                 );
             }
+
+            self.draw_nerd_font_corners(f, margined_draw_loc);
+        }
+    }
+
+    /// Draws the power consumption history of the selected battery, used in place of the table
+    /// while the widget is expanded.
+    fn draw_battery_graph<B: Backend>(
+        &self, f: &mut Frame<'_, B>, app_state: &mut App, draw_loc: Rect, widget_id: u64,
+    ) {
+        let border_style = self.get_border_style(app_state, widget_id);
+        let title_style = self.get_title_style(app_state, widget_id);
+        if let Some(battery_widget_state) =
+            app_state.battery_state.widget_states.get_mut(&widget_id)
+        {
+            let time_end = app_state.app_config_fields.default_time_value;
+            let x_bounds = [0, time_end];
+            let hide_x_labels = should_hide_x_label(
+                app_state.app_config_fields.hide_time,
+                app_state.app_config_fields.autohide_time,
+                &mut None,
+                draw_loc,
+            );
+
+            // Leave some headroom above the highest reading so the line doesn't hug the border.
+            let max_watts = battery_widget_state.max_power() + 2.0;
+            let y_bounds = [0.0, max_watts];
+            let y_labels = ["0W".into(), format!("{max_watts:.0}W").into()];
+
+            let battery_details = app_state
+                .converted_data
+                .battery_data
+                .get(battery_widget_state.currently_selected_battery_index);
+
+            let power_name = battery_details
+                .map(|battery_details| format!("Power: {}", battery_details.watt_consumption));
+            let duration_name = battery_details.and_then(|battery_details| {
+                match battery_details.battery_duration {
+                    BatteryDuration::ToEmpty(secs) => {
+                        Some(format!("To empty: {}", short_time(secs)))
+                    }
+                    BatteryDuration::ToFull(secs) => Some(format!("To full: {}", short_time(secs))),
+                    BatteryDuration::Unknown => None,
+                }
+            });
+
+            let mut points = vec![GraphData {
+                points: battery_widget_state.power_history.make_contiguous(),
+                style: self.colours.high_battery_colour,
+                name: power_name.map(Into::into),
+                dashed: false,
+            }];
+            if let Some(duration_name) = duration_name {
+                points.push(GraphData {
+                    points: &[],
+                    style: self.colours.text_style,
+                    name: Some(duration_name.into()),
+                    dashed: false,
+                });
+            }
+
+            let marker = if app_state.app_config_fields.use_dot {
+                Marker::Dot
+            } else {
+                Marker::Braille
+            };
+
+            TimeGraph {
+                x_bounds,
+                hide_x_labels,
+                y_bounds,
+                y_labels: &y_labels,
+                graph_style: self.colours.graph_style,
+                border_style,
+                border_type: self.border_type,
+                title: " Battery ".into(),
+                is_expanded: true,
+                title_style,
+                legend_constraints: Some((Constraint::Ratio(1, 1), Constraint::Ratio(3, 4))),
+                marker,
+            }
+            .draw_time_graph(f, draw_loc, &points);
+            self.draw_nerd_font_corners(f, draw_loc);
         }
     }
 }
+
+fn long_time(secs: i64) -> String {
+    let time = time::Duration::seconds(secs);
+    let num_minutes = time.whole_minutes() - time.whole_hours() * 60;
+    let num_seconds = time.whole_seconds() - time.whole_minutes() * 60;
+    format!(
+        "{} hour{}, {} minute{}, {} second{}",
+        time.whole_hours(),
+        if time.whole_hours() == 1 { "" } else { "s" },
+        num_minutes,
+        if num_minutes == 1 { "" } else { "s" },
+        num_seconds,
+        if num_seconds == 1 { "" } else { "s" },
+    )
+}
+
+fn short_time(secs: i64) -> String {
+    let time = time::Duration::seconds(secs);
+    let num_minutes = time.whole_minutes() - time.whole_hours() * 60;
+    let num_seconds = time.whole_seconds() - time.whole_minutes() * 60;
+    format!("{}h {}m {}s", time.whole_hours(), num_minutes, num_seconds,)
+}
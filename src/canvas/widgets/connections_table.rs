@@ -1,4 +1,10 @@
-use tui::{backend::Backend, layout::Rect, terminal::Frame};
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    terminal::Frame,
+    text::{Span, Spans},
+    widgets::{Clear, Paragraph},
+};
 
 use crate::{
     app,
@@ -23,6 +29,9 @@ impl Painter {
                 force_redraw: app_state.is_force_redraw,
                 recalculate_column_widths,
                 selection_state: SelectionState::new(app_state.is_expanded, is_on_widget),
+                footer_height: 0,
+                is_frozen: app_state.frozen_widget_ids.contains(&widget_id),
+                alert_border_style: None,
             };
 
             connections_widget_state.table.draw(
@@ -31,6 +40,36 @@ impl Painter {
                 app_state.widget_map.get_mut(&widget_id),
                 self,
             );
+
+            if let Some(input) = &connections_widget_state.port_filter_input {
+                self.draw_port_filter_input(f, input, draw_loc);
+            }
+        }
+    }
+
+    /// Draws the port filter input over the last row inside the widget's border.
+    fn draw_port_filter_input<B: Backend>(
+        &self, f: &mut Frame<'_, B>, input: &str, draw_loc: Rect,
+    ) {
+        if draw_loc.width < 3 || draw_loc.height < 3 {
+            return;
         }
+
+        let input_loc = Rect::new(
+            draw_loc.x + 1,
+            draw_loc.y + draw_loc.height - 2,
+            draw_loc.width - 2,
+            1,
+        );
+
+        f.render_widget(Clear, input_loc);
+        f.render_widget(
+            Paragraph::new(Spans::from(vec![
+                Span::styled("Port: ", self.colours.table_header_style),
+                Span::styled(input, self.colours.text_style),
+                Span::styled(" ", self.colours.currently_selected_text_style),
+            ])),
+            input_loc,
+        );
     }
 }
@@ -38,7 +38,8 @@ impl Painter {
                 f.render_widget(
                     Block::default()
                         .borders(SIDE_BORDERS)
-                        .border_style(self.colours.highlighted_border_style),
+                        .border_style(self.colours.highlighted_border_style)
+                        .border_type(self.border_type),
                     draw_loc,
                 );
             }
@@ -58,8 +59,8 @@ impl Painter {
                     CpuWidgetData::All => unreachable!(),
                     CpuWidgetData::Entry {
                         data_type,
-                        data: _,
                         last_entry,
+                        ..
                     } => {
                         let (outer, style) = match data_type {
                             CpuDataType::Avg => ("AVG".to_string(), self.colours.avg_colour_style),
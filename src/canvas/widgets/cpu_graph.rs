@@ -148,6 +148,7 @@ impl Painter {
                                 points: &data[..],
                                 style,
                                 name: None,
+                                dashed: false,
                             })
                         }
                     }
@@ -168,6 +169,7 @@ impl Painter {
                 points: &data[..],
                 style,
                 name: None,
+                dashed: false,
             }]
         } else {
             vec![]
@@ -180,9 +182,11 @@ impl Painter {
         const Y_BOUNDS: [f64; 2] = [0.0, 100.5];
         const Y_LABELS: [Cow<'static, str>; 2] = [Cow::Borrowed("  0%"), Cow::Borrowed("100%")];
 
+        let border_style = self.get_border_style(app_state, widget_id);
+        let title_style = self.get_title_style(app_state, widget_id);
+
         if let Some(cpu_widget_state) = app_state.cpu_state.widget_states.get_mut(&widget_id) {
             let cpu_data = &app_state.converted_data.cpu_data;
-            let border_style = self.get_border_style(widget_id, app_state.current_widget.widget_id);
             let x_bounds = [0, cpu_widget_state.current_display_time];
             let hide_x_labels = should_hide_x_label(
                 app_state.app_config_fields.hide_time,
@@ -223,13 +227,15 @@ impl Painter {
                 y_labels: &Y_LABELS,
                 graph_style: self.colours.graph_style,
                 border_style,
+                border_type: self.border_type,
                 title,
                 is_expanded: app_state.is_expanded,
-                title_style: self.colours.widget_title_style,
+                title_style,
                 legend_constraints: None,
                 marker,
             }
             .draw_time_graph(f, draw_loc, &points);
+            self.draw_nerd_font_corners(f, draw_loc);
         }
     }
 
@@ -237,6 +243,7 @@ impl Painter {
         &self, f: &mut Frame<'_, B>, app_state: &mut App, draw_loc: Rect, widget_id: u64,
     ) {
         let recalculate_column_widths = app_state.should_get_widget_bounds();
+        let alert_border_style = self.get_alert_border_style(app_state, widget_id);
         if let Some(cpu_widget_state) = app_state.cpu_state.widget_states.get_mut(&(widget_id - 1))
         {
             // TODO: This line (and the one above, see caller) is pretty dumb but I guess needed for now. Refactor if possible!
@@ -249,6 +256,9 @@ impl Painter {
                 force_redraw: app_state.is_force_redraw,
                 recalculate_column_widths,
                 selection_state: SelectionState::new(app_state.is_expanded, is_on_widget),
+                footer_height: 0,
+                is_frozen: false,
+                alert_border_style,
             };
 
             cpu_widget_state.table.draw(
@@ -0,0 +1,103 @@
+use tui::{
+    backend::Backend,
+    buffer::Buffer,
+    layout::Rect,
+    terminal::Frame,
+    text::{Span, Spans},
+    widgets::{Block, Borders},
+};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{
+    app::App,
+    canvas::{canvas_styling::CanvasColours, Painter},
+    components::custom_widget::Widget,
+    constants::*,
+};
+
+/// Lets a custom widget be rendered like a tui widget, as [`Frame`] doesn't hand out its buffer.
+struct CustomWidgetRender<'a> {
+    custom_widget: &'a mut dyn Widget,
+    colours: &'a CanvasColours,
+    is_selected: bool,
+}
+
+impl tui::widgets::Widget for CustomWidgetRender<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        self.custom_widget
+            .draw(buf, area, self.colours, self.is_selected);
+    }
+}
+
+impl Painter {
+    pub fn draw_custom_widget<B: Backend>(
+        &self, f: &mut Frame<'_, B>, app_state: &mut App, draw_loc: Rect, draw_border: bool,
+        widget_id: u64,
+    ) {
+        let should_get_widget_bounds = app_state.should_get_widget_bounds();
+        let border_style = self.get_border_style(app_state, widget_id);
+        let title_style = self.get_title_style(app_state, widget_id);
+        let is_on_widget = widget_id == app_state.current_widget.widget_id;
+        let is_expanded = app_state.is_expanded;
+        if let Some(custom_widget) = app_state
+            .custom_widget_state
+            .get_mut_widget_state(widget_id)
+        {
+            let title_base = format!(" {} ", custom_widget.title());
+            let title = if is_expanded {
+                let title_width = UnicodeSegmentation::graphemes(
+                    format!("{title_base}── Esc to go back ").as_str(),
+                    true,
+                )
+                .count();
+                Spans::from(vec![
+                    Span::styled(title_base, title_style),
+                    Span::styled(
+                        format!(
+                            "─{}─ Esc to go back ",
+                            "─".repeat(usize::from(draw_loc.width).saturating_sub(title_width + 2))
+                        ),
+                        border_style,
+                    ),
+                ])
+            } else {
+                Spans::from(Span::styled(title_base, title_style))
+            };
+
+            let custom_block = if draw_border {
+                Block::default()
+                    .title(title)
+                    .borders(Borders::ALL)
+                    .border_style(border_style)
+                    .border_type(self.border_type)
+            } else if is_on_widget {
+                Block::default()
+                    .borders(SIDE_BORDERS)
+                    .border_style(self.colours.highlighted_border_style)
+                    .border_type(self.border_type)
+            } else {
+                Block::default().borders(Borders::NONE)
+            };
+
+            let inner = custom_block.inner(draw_loc);
+            f.render_widget(custom_block, draw_loc);
+            f.render_widget(
+                CustomWidgetRender {
+                    custom_widget: custom_widget.as_mut(),
+                    colours: &self.colours,
+                    is_selected: is_on_widget,
+                },
+                inner,
+            );
+            self.draw_nerd_font_corners(f, draw_loc);
+
+            if should_get_widget_bounds {
+                if let Some(widget) = app_state.widget_map.get_mut(&widget_id) {
+                    widget.top_left_corner = Some((draw_loc.x, draw_loc.y));
+                    widget.bottom_right_corner =
+                        Some((draw_loc.x + draw_loc.width, draw_loc.y + draw_loc.height));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::Style,
+    terminal::Frame,
+    text::{Span, Spans},
+    widgets::{Block, Borders, Paragraph},
+};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{
+    app::App,
+    canvas::Painter,
+    constants::*,
+    widgets::{LogSeverity, LogWidgetState},
+};
+
+impl Painter {
+    pub fn draw_log_display<B: Backend>(
+        &self, f: &mut Frame<'_, B>, app_state: &mut App, draw_loc: Rect, draw_border: bool,
+        widget_id: u64,
+    ) {
+        let should_get_widget_bounds = app_state.should_get_widget_bounds();
+        let border_style = self.get_border_style(app_state, widget_id);
+        let title_style = self.get_title_style(app_state, widget_id);
+        let is_on_widget = widget_id == app_state.current_widget.widget_id;
+        let is_expanded = app_state.is_expanded;
+        if let Some(log_widget_state) = app_state.log_state.widget_states.get_mut(&widget_id) {
+            let title_base = log_title(log_widget_state);
+            let title = if is_expanded {
+                let title_width = UnicodeSegmentation::graphemes(
+                    format!("{title_base}── Esc to go back ").as_str(),
+                    true,
+                )
+                .count();
+                Spans::from(vec![
+                    Span::styled(title_base, title_style),
+                    Span::styled(
+                        format!(
+                            "─{}─ Esc to go back ",
+                            "─".repeat(usize::from(draw_loc.width).saturating_sub(title_width + 2))
+                        ),
+                        border_style,
+                    ),
+                ])
+            } else {
+                Spans::from(Span::styled(title_base, title_style))
+            };
+
+            let log_block = if draw_border {
+                Block::default()
+                    .title(title)
+                    .borders(Borders::ALL)
+                    .border_style(border_style)
+                    .border_type(self.border_type)
+            } else if is_on_widget {
+                Block::default()
+                    .borders(SIDE_BORDERS)
+                    .border_style(self.colours.highlighted_border_style)
+                    .border_type(self.border_type)
+            } else {
+                Block::default().borders(Borders::NONE)
+            };
+
+            // The search takes up the last row while it is being typed or applied.
+            let inner = log_block.inner(draw_loc);
+            let is_showing_search =
+                log_widget_state.is_typing_search || !log_widget_state.search_query.is_empty();
+            let height = usize::from(inner.height).saturating_sub(usize::from(is_showing_search));
+
+            let lines = log_widget_state
+                .shown_lines()
+                .map(|line| {
+                    Spans::from(Span::styled(
+                        line.text.clone(),
+                        self.severity_style(line.severity),
+                    ))
+                })
+                .collect::<Vec<_>>();
+            let mut contents = log_widget_state.scrollback.rows(
+                lines.into_iter(),
+                height,
+                usize::from(inner.width),
+                Spans::from(Span::styled(
+                    "<End reached>",
+                    self.colours.currently_selected_text_style,
+                )),
+            );
+            if is_showing_search {
+                let cursor = if log_widget_state.is_typing_search {
+                    "_"
+                } else {
+                    ""
+                };
+                contents.push(Spans::from(Span::styled(
+                    format!("/{}{cursor}", log_widget_state.search_query),
+                    self.colours.currently_selected_text_style,
+                )));
+            }
+
+            f.render_widget(Paragraph::new(contents).block(log_block), draw_loc);
+            self.draw_nerd_font_corners(f, draw_loc);
+
+            if should_get_widget_bounds {
+                if let Some(widget) = app_state.widget_map.get_mut(&widget_id) {
+                    widget.top_left_corner = Some((draw_loc.x, draw_loc.y));
+                    widget.bottom_right_corner =
+                        Some((draw_loc.x + draw_loc.width, draw_loc.y + draw_loc.height));
+                }
+            }
+        }
+    }
+
+    fn severity_style(&self, severity: LogSeverity) -> Style {
+        match severity {
+            LogSeverity::Error => self.colours.low_battery_colour,
+            LogSeverity::Warning => self.colours.medium_battery_colour,
+            LogSeverity::Info => self.colours.text_style,
+            LogSeverity::Debug => self.colours.disabled_text_style,
+        }
+    }
+}
+
+/// The title of a log widget, which says when it is paused and how many lines came in since.
+fn log_title(log_widget_state: &LogWidgetState) -> String {
+    if log_widget_state.is_paused {
+        format!(
+            " Logs ── Paused, {} new ",
+            log_widget_state.paused_line_count()
+        )
+    } else {
+        " Logs ".to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::widgets::LogLine;
+
+    #[test]
+    fn test_log_title() {
+        let mut state = LogWidgetState::new(10);
+        assert_eq!(log_title(&state), " Logs ");
+
+        state.toggle_pause();
+        state.push_lines(vec![LogLine::plain("one")]);
+        assert_eq!(log_title(&state), " Logs ── Paused, 1 new ");
+    }
+}
@@ -15,16 +15,12 @@ impl Painter {
     ) {
         let mut draw_widgets: Vec<Gauge<'_>> = Vec::new();
 
-        let is_on_widget = widget_id == app_state.current_widget.widget_id;
-        let border_style = if is_on_widget {
-            self.colours.highlighted_border_style
-        } else {
-            self.colours.border_style
-        };
+        let border_style = self.get_border_style(app_state, widget_id);
+        let title_style = self.get_title_style(app_state, widget_id);
         let title = if app_state.is_expanded {
             const TITLE_BASE: &str = " Memory ── Esc to go back ";
             Spans::from(vec![
-                Span::styled(" Memory ", self.colours.widget_title_style),
+                Span::styled(" Memory ", title_style),
                 Span::styled(
                     format!(
                         "─{}─ Esc to go back ",
@@ -36,16 +32,18 @@ impl Painter {
                 ),
             ])
         } else {
-            Spans::from(Span::styled(" Memory ", self.colours.widget_title_style))
+            Spans::from(Span::styled(" Memory ", title_style))
         };
 
         f.render_widget(
             Block::default()
                 .title(title)
                 .borders(Borders::ALL)
-                .border_style(border_style),
+                .border_style(border_style)
+                .border_type(self.border_type),
             draw_loc,
         );
+        self.draw_nerd_font_corners(f, draw_loc);
 
         let ram_percentage = app_state.converted_data.mem_data.use_percent.unwrap_or(0.0);
 
@@ -33,7 +33,8 @@ impl Painter {
             f.render_widget(
                 Block::default()
                     .borders(SIDE_BORDERS)
-                    .border_style(self.colours.highlighted_border_style),
+                    .border_style(self.colours.highlighted_border_style)
+                    .border_type(self.border_type),
                 draw_loc,
             );
         }
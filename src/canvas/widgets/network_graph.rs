@@ -1,42 +1,119 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    time::Instant,
+};
+
+use itertools::Itertools;
 use tui::{
     backend::Backend,
     layout::{Constraint, Direction, Layout, Rect},
     symbols::Marker,
     terminal::Frame,
-    text::Text,
+    text::{Span, Text},
     widgets::{Block, Borders, Row, Table},
 };
 
 use crate::{
-    app::{App, AxisScaling},
+    app::{
+        data_harvester::network::{InterfaceErrors, TcpStats},
+        App, AxisScaling,
+    },
     canvas::{drawing_utils::should_hide_x_label, Painter},
     components::{
         time_graph::{GraphData, TimeGraph},
         tui_widget::time_chart::Point,
     },
+    data_conversion::{network_rate_string, scale_network_rate},
     units::data_units::DataUnit,
     utils::gen_util::*,
+    widgets::{InterfaceErrorRates, TcpStatsRates},
 };
 
+/// How many segments the peak reference lines are split into, so they can be drawn dashed.
+const PEAK_LINE_SEGMENTS: usize = 40;
+
+/// The size of the TCP/IP statistics panel, including its borders.
+const TCP_STATS_WIDTH: u16 = 36;
+const TCP_STATS_HEIGHT: u16 = 10;
+
+/// The width of the interface error panel, including its borders.
+const INTERFACE_ERRORS_WIDTH: u16 = 40;
+
 impl Painter {
     pub fn draw_network<B: Backend>(
         &self, f: &mut Frame<'_, B>, app_state: &mut App, draw_loc: Rect, widget_id: u64,
     ) {
+        let (show_tcp_stats, show_interface_errors) = app_state
+            .net_state
+            .widget_states
+            .get(&widget_id)
+            .map(|state| (state.show_tcp_stats, state.show_interface_errors))
+            .unwrap_or_default();
+        let graph_loc = if show_tcp_stats || show_interface_errors {
+            let panel_width = if show_interface_errors {
+                INTERFACE_ERRORS_WIDTH
+            } else {
+                TCP_STATS_WIDTH
+            };
+
+            // Put the panels to the side if there's room for both, and below the graph otherwise.
+            let is_beside = draw_loc.width >= panel_width * 2;
+            let (direction, constraints) = if is_beside {
+                (
+                    Direction::Horizontal,
+                    [Constraint::Min(0), Constraint::Length(panel_width)],
+                )
+            } else {
+                (
+                    Direction::Vertical,
+                    [Constraint::Min(0), Constraint::Length(TCP_STATS_HEIGHT)],
+                )
+            };
+            let chunks = Layout::default()
+                .direction(direction)
+                .constraints(constraints)
+                .split(draw_loc);
+
+            match (show_tcp_stats, show_interface_errors) {
+                (true, true) => {
+                    // Stacked if beside the graph, and side by side if below it.
+                    let panels = if is_beside {
+                        Layout::default()
+                            .direction(Direction::Vertical)
+                            .constraints([Constraint::Length(TCP_STATS_HEIGHT), Constraint::Min(0)])
+                            .split(chunks[1])
+                    } else {
+                        Layout::default()
+                            .direction(Direction::Horizontal)
+                            .constraints([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)])
+                            .split(chunks[1])
+                    };
+                    self.draw_tcp_stats(f, app_state, panels[0], widget_id);
+                    self.draw_interface_errors(f, app_state, panels[1], widget_id);
+                }
+                (true, false) => self.draw_tcp_stats(f, app_state, chunks[1], widget_id),
+                _ => self.draw_interface_errors(f, app_state, chunks[1], widget_id),
+            }
+            chunks[0]
+        } else {
+            draw_loc
+        };
+
         if app_state.app_config_fields.use_old_network_legend {
             const LEGEND_HEIGHT: u16 = 4;
             let network_chunk = Layout::default()
                 .direction(Direction::Vertical)
                 .margin(0)
                 .constraints([
-                    Constraint::Length(draw_loc.height.saturating_sub(LEGEND_HEIGHT)),
+                    Constraint::Length(graph_loc.height.saturating_sub(LEGEND_HEIGHT)),
                     Constraint::Length(LEGEND_HEIGHT),
                 ])
-                .split(draw_loc);
+                .split(graph_loc);
 
             self.draw_network_graph(f, app_state, network_chunk[0], widget_id, true);
             self.draw_network_labels(f, app_state, network_chunk[1], widget_id);
         } else {
-            self.draw_network_graph(f, app_state, draw_loc, widget_id, false);
+            self.draw_network_graph(f, app_state, graph_loc, widget_id, false);
         }
 
         if app_state.should_get_widget_bounds() {
@@ -55,11 +132,13 @@ impl Painter {
         &self, f: &mut Frame<'_, B>, app_state: &mut App, draw_loc: Rect, widget_id: u64,
         hide_legend: bool,
     ) {
+        let border_style = self.get_border_style(app_state, widget_id);
+        let title_style = self.get_title_style(app_state, widget_id);
+
         if let Some(network_widget_state) = app_state.net_state.widget_states.get_mut(&widget_id) {
             let network_data_rx = &app_state.converted_data.network_data_rx;
             let network_data_tx = &app_state.converted_data.network_data_tx;
             let time_start = -(network_widget_state.current_display_time as f64);
-            let border_style = self.get_border_style(widget_id, app_state.current_widget.widget_id);
             let x_bounds = [0, network_widget_state.current_display_time];
             let hide_x_labels = should_hide_x_label(
                 app_state.app_config_fields.hide_time,
@@ -82,6 +161,24 @@ impl Painter {
                 app_state.app_config_fields.network_use_binary_prefix,
             );
 
+            let scale_type = &app_state.app_config_fields.network_scale_type;
+            let unit_type = &app_state.app_config_fields.network_unit_type;
+            let use_binary_prefix = app_state.app_config_fields.network_use_binary_prefix;
+            let peaks = [
+                network_widget_state.peak_rx_bps,
+                network_widget_state.peak_tx_bps,
+            ]
+            .map(|peak_bps| {
+                (peak_bps > 0.0)
+                    .then(|| scale_network_rate(peak_bps, scale_type, unit_type, use_binary_prefix))
+            });
+
+            // Scale to fit the peaks as well, so they stay visible once they've scrolled off the graph.
+            let max_entry = peaks
+                .iter()
+                .flatten()
+                .fold(max_entry, |acc, peak| acc.max(*peak));
+
             let (max_range, labels) = adjust_network_data_point(
                 max_entry,
                 &app_state.app_config_fields.network_scale_type,
@@ -98,18 +195,30 @@ impl Painter {
                 (Constraint::Ratio(1, 1), Constraint::Ratio(3, 4))
             };
 
+            // The connection count is normalized to fit the byte-rate axis; the actual value is
+            // shown in the legend instead.
+            let show_connection_count =
+                app_state.is_expanded && app_state.app_config_fields.show_connection_count;
+            let connection_points = if show_connection_count {
+                get_connection_count_points(&network_widget_state.tcp_connections, max_range)
+            } else {
+                Vec::new()
+            };
+
             // TODO: Add support for clicking on legend to only show that value on chart.
-            let points = if app_state.app_config_fields.use_old_network_legend && !hide_legend {
+            let mut points = if app_state.app_config_fields.use_old_network_legend && !hide_legend {
                 vec![
                     GraphData {
                         points: network_data_rx,
                         style: self.colours.rx_style,
                         name: Some(format!("RX: {:7}", app_state.converted_data.rx_display).into()),
+                        dashed: false,
                     },
                     GraphData {
                         points: network_data_tx,
                         style: self.colours.tx_style,
                         name: Some(format!("TX: {:7}", app_state.converted_data.tx_display).into()),
+                        dashed: false,
                     },
                     GraphData {
                         points: &[],
@@ -118,6 +227,7 @@ impl Painter {
                             format!("Total RX: {:7}", app_state.converted_data.total_rx_display)
                                 .into(),
                         ),
+                        dashed: false,
                     },
                     GraphData {
                         points: &[],
@@ -126,6 +236,7 @@ impl Painter {
                             format!("Total TX: {:7}", app_state.converted_data.total_tx_display)
                                 .into(),
                         ),
+                        dashed: false,
                     },
                 ]
             } else {
@@ -134,15 +245,83 @@ impl Painter {
                         points: network_data_rx,
                         style: self.colours.rx_style,
                         name: Some((&app_state.converted_data.rx_display).into()),
+                        dashed: false,
                     },
                     GraphData {
                         points: network_data_tx,
                         style: self.colours.tx_style,
                         name: Some((&app_state.converted_data.tx_display).into()),
+                        dashed: false,
                     },
                 ]
             };
 
+            let [peak_rx_points, peak_tx_points] =
+                peaks.map(|peak| peak.map(|peak| get_peak_line_points(peak, time_start)));
+            for (peak_points, peak_bps, style) in [
+                (
+                    &peak_rx_points,
+                    network_widget_state.peak_rx_bps,
+                    self.colours.rx_style,
+                ),
+                (
+                    &peak_tx_points,
+                    network_widget_state.peak_tx_bps,
+                    self.colours.tx_style,
+                ),
+            ] {
+                if let Some(peak_points) = peak_points {
+                    points.push(GraphData {
+                        points: peak_points,
+                        style,
+                        name: Some(
+                            format!(
+                                "Peak: {}",
+                                network_rate_string(peak_bps, unit_type, use_binary_prefix)
+                            )
+                            .into(),
+                        ),
+                        dashed: true,
+                    });
+                }
+            }
+
+            if show_connection_count {
+                let current_connections = network_widget_state
+                    .tcp_connections
+                    .back()
+                    .map(|(_, count)| *count)
+                    .unwrap_or(0);
+
+                points.push(GraphData {
+                    points: &connection_points,
+                    style: self.colours.text_style,
+                    name: Some(format!("TCP: {current_connections}").into()),
+                    dashed: true,
+                });
+            }
+
+            let wifi_info = &app_state.data_collection.network_harvest.wifi_info;
+            for name in wifi_info.keys().sorted() {
+                let info = &wifi_info[name];
+                let style = if info.rssi_dbm > -60 {
+                    self.colours.low_latency_style
+                } else if info.rssi_dbm > -75 {
+                    self.colours.medium_latency_style
+                } else {
+                    self.colours.high_latency_style
+                };
+
+                points.push(GraphData {
+                    points: &[],
+                    style,
+                    name: Some(
+                        format!("{name}: {} dBm ({:.0})", info.rssi_dbm, info.link_quality).into(),
+                    ),
+                    dashed: false,
+                });
+            }
+
             let marker = if app_state.app_config_fields.use_dot {
                 Marker::Dot
             } else {
@@ -156,16 +335,121 @@ impl Painter {
                 y_labels: &y_labels,
                 graph_style: self.colours.graph_style,
                 border_style,
+                border_type: self.border_type,
                 title: " Network ".into(),
                 is_expanded: app_state.is_expanded,
-                title_style: self.colours.widget_title_style,
+                title_style,
                 legend_constraints: Some(legend_constraints),
                 marker,
             }
             .draw_time_graph(f, draw_loc, &points);
+            self.draw_nerd_font_corners(f, draw_loc);
         }
     }
 
+    fn draw_tcp_stats<B: Backend>(
+        &self, f: &mut Frame<'_, B>, app_state: &mut App, draw_loc: Rect, widget_id: u64,
+    ) {
+        let border_style = self.get_border_style(app_state, widget_id);
+        let rows = match app_state.net_state.widget_states.get(&widget_id) {
+            Some(state) => match &state.tcp_stats {
+                Some((_, stats)) => {
+                    let rates = state.tcp_stats_rates.clone().unwrap_or_default();
+                    let retransmit_style = if rates.retransmit_ratio < 0.01 {
+                        self.colours.low_latency_style
+                    } else if rates.retransmit_ratio < 0.05 {
+                        self.colours.medium_latency_style
+                    } else {
+                        self.colours.high_latency_style
+                    };
+
+                    tcp_stats_rows(stats, &rates)
+                        .into_iter()
+                        .enumerate()
+                        .map(|(index, (name, value))| {
+                            let style = if index == 0 {
+                                retransmit_style
+                            } else {
+                                self.colours.text_style
+                            };
+                            Row::new(vec![Text::raw(name), Text::styled(value, style)])
+                        })
+                        .collect()
+                }
+                None => vec![Row::new(vec![Text::raw("Not available")])],
+            },
+            None => Vec::new(),
+        };
+
+        f.render_widget(
+            Table::new(rows)
+                .block(
+                    Block::default()
+                        .title(Span::styled(" TCP/IP ", self.colours.widget_title_style))
+                        .borders(Borders::ALL)
+                        .border_style(border_style)
+                        .border_type(self.border_type),
+                )
+                .style(self.colours.text_style)
+                .widths(&[Constraint::Length(9), Constraint::Min(0)]),
+            draw_loc,
+        );
+        self.draw_nerd_font_corners(f, draw_loc);
+    }
+
+    fn draw_interface_errors<B: Backend>(
+        &self, f: &mut Frame<'_, B>, app_state: &mut App, draw_loc: Rect, widget_id: u64,
+    ) {
+        const HEADERS: [&str; 4] = ["Iface", "Errors", "Drops", "Coll"];
+
+        let border_style = self.get_border_style(app_state, widget_id);
+        let rows = match app_state.net_state.widget_states.get(&widget_id) {
+            Some(state) => match &state.interface_errors {
+                Some((_, errors)) => interface_error_rows(errors, &state.interface_error_rates)
+                    .into_iter()
+                    .map(|(cells, rates)| {
+                        // Interfaces that are still racking up errors or drops stand out.
+                        let style = if rates.errors > 0.0 {
+                            self.colours.high_latency_style
+                        } else if rates.drops > 0.0 {
+                            self.colours.medium_latency_style
+                        } else {
+                            self.colours.text_style
+                        };
+                        Row::new(cells).style(style)
+                    })
+                    .collect(),
+                None => Vec::new(),
+            },
+            None => Vec::new(),
+        };
+
+        f.render_widget(
+            Table::new(rows)
+                .header(Row::new(HEADERS.to_vec()).style(self.colours.table_header_style))
+                .block(
+                    Block::default()
+                        .title(Span::styled(
+                            " Interfaces ",
+                            self.colours.widget_title_style,
+                        ))
+                        .borders(Borders::ALL)
+                        .border_style(border_style)
+                        .border_type(self.border_type),
+                )
+                .style(self.colours.text_style)
+                .widths(&[
+                    // Whatever the borders, counters and gaps between columns leave.
+                    Constraint::Length(draw_loc.width.saturating_sub(2 + 9 + 9 + 5 + 3)),
+                    Constraint::Length(9),
+                    Constraint::Length(9),
+                    Constraint::Length(5),
+                ]),
+            draw_loc,
+        );
+        self.draw_nerd_font_corners(f, draw_loc);
+    }
+
     fn draw_network_labels<B: Backend>(
         &self, f: &mut Frame<'_, B>, app_state: &mut App, draw_loc: Rect, widget_id: u64,
     ) {
@@ -188,13 +472,12 @@ impl Painter {
         f.render_widget(
             Table::new(total_network)
                 .header(Row::new(NETWORK_HEADERS.to_vec()).style(self.colours.table_header_style))
-                .block(Block::default().borders(Borders::ALL).border_style(
-                    if app_state.current_widget.widget_id == widget_id {
-                        self.colours.highlighted_border_style
-                    } else {
-                        self.colours.border_style
-                    },
-                ))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_style(self.get_border_style(app_state, widget_id))
+                        .border_type(self.border_type),
+                )
                 .style(self.colours.text_style)
                 .widths(
                     &((std::iter::repeat(draw_loc.width.saturating_sub(2) / 4))
@@ -204,7 +487,97 @@ impl Painter {
                 ),
             draw_loc,
         );
+        self.draw_nerd_font_corners(f, draw_loc);
+    }
+}
+
+/// Returns the name and value of each line of the TCP/IP statistics panel, with the retransmits
+/// first.
+fn tcp_stats_rows(stats: &TcpStats, rates: &TcpStatsRates) -> Vec<(&'static str, String)> {
+    let mut rows = vec![
+        (
+            "Retrans",
+            format!(
+                "{:.1}/s ({:.1}%)",
+                rates.retransmits,
+                rates.retransmit_ratio * 100.0
+            ),
+        ),
+        (
+            "Resets",
+            format!(
+                "{:.1}/s ({} total)",
+                rates.resets,
+                stats.estab_resets + stats.out_rsts
+            ),
+        ),
+        (
+            "Opens",
+            format!(
+                "{:.1}/s out, {:.1}/s in",
+                rates.active_opens, rates.passive_opens
+            ),
+        ),
+        (
+            "Failed",
+            format!(
+                "{:.1}/s ({} total)",
+                rates.attempt_fails, stats.attempt_fails
+            ),
+        ),
+        (
+            "Errors",
+            format!("{:.1}/s ({} total)", rates.in_errs, stats.in_errs),
+        ),
+        ("Estab", stats.curr_estab.to_string()),
+    ];
+
+    if let Some(sockets_used) = stats.sockets_used {
+        let mut sockets = sockets_used.to_string();
+        let details = [
+            ("tcp", stats.tcp_sockets),
+            ("udp", stats.udp_sockets),
+            ("tw", stats.time_wait_sockets),
+        ]
+        .into_iter()
+        .filter_map(|(name, count)| Some(format!("{name} {}", count?)))
+        .join(", ");
+        if !details.is_empty() {
+            sockets.push_str(&format!(" ({details})"));
+        }
+        rows.push(("Sockets", sockets));
     }
+
+    rows
+}
+
+/// Returns the cells of each line of the interface error panel, sorted by interface name, along
+/// with how fast that interface's errors and drops are going up. Errors and drops are shown as
+/// `rx/tx`, and counters the platform doesn't report as `-`.
+fn interface_error_rows(
+    errors: &HashMap<String, InterfaceErrors>, rates: &HashMap<String, InterfaceErrorRates>,
+) -> Vec<([String; 4], InterfaceErrorRates)> {
+    let optional = |count: Option<u64>| count.map_or_else(|| "-".to_string(), |c| c.to_string());
+
+    errors
+        .iter()
+        .sorted_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(name, errors)| {
+            (
+                [
+                    name.clone(),
+                    format!("{}/{}", errors.rx_errors, errors.tx_errors),
+                    format!(
+                        "{}/{}",
+                        optional(errors.rx_dropped),
+                        optional(errors.tx_dropped)
+                    ),
+                    optional(errors.collisions),
+                ],
+                rates.get(name).cloned().unwrap_or_default(),
+            )
+        })
+        .collect()
 }
 
 /// Returns the max data point and time given a time.
@@ -529,3 +902,107 @@ fn adjust_network_data_point(
         }
     }
 }
+
+/// Converts the TCP connection count history into points, with the counts scaled so that the
+/// largest one sits at `max_range`.
+fn get_connection_count_points(
+    tcp_connections: &VecDeque<(Instant, u64)>, max_range: f64,
+) -> Vec<Point> {
+    let Some((last_instant, _)) = tcp_connections.back() else {
+        return Vec::new();
+    };
+    let max_count = tcp_connections
+        .iter()
+        .map(|(_, count)| *count)
+        .max()
+        .unwrap_or(0)
+        .max(1) as f64;
+
+    tcp_connections
+        .iter()
+        .map(|(instant, count)| {
+            let time_from_start = -(last_instant.duration_since(*instant).as_millis() as f64);
+            (time_from_start, *count as f64 / max_count * max_range)
+        })
+        .collect()
+}
+
+/// Returns a horizontal line at `value` across the visible time range.
+fn get_peak_line_points(value: f64, time_start: f64) -> Vec<Point> {
+    (0..=PEAK_LINE_SEGMENTS)
+        .map(|index| {
+            let time = time_start * (1.0 - index as f64 / PEAK_LINE_SEGMENTS as f64);
+            (time, value)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_tcp_stats_rows() {
+        let mut stats = TcpStats {
+            estab_resets: 3,
+            out_rsts: 4,
+            curr_estab: 2,
+            ..TcpStats::default()
+        };
+        let rates = TcpStatsRates {
+            retransmits: 1.5,
+            retransmit_ratio: 0.025,
+            ..TcpStatsRates::default()
+        };
+
+        let rows = tcp_stats_rows(&stats, &rates);
+        assert_eq!(rows.len(), 6);
+        assert_eq!(rows[0], ("Retrans", "1.5/s (2.5%)".to_string()));
+        assert_eq!(rows[1], ("Resets", "0.0/s (7 total)".to_string()));
+        assert_eq!(rows[5], ("Estab", "2".to_string()));
+
+        stats.sockets_used = Some(18);
+        stats.tcp_sockets = Some(4);
+        stats.time_wait_sockets = Some(0);
+        let rows = tcp_stats_rows(&stats, &rates);
+        assert_eq!(rows[6], ("Sockets", "18 (tcp 4, tw 0)".to_string()));
+    }
+
+    #[test]
+    fn test_interface_error_rows() {
+        let errors = HashMap::from([
+            (
+                "eth0".to_string(),
+                InterfaceErrors {
+                    rx_errors: 3,
+                    tx_errors: 1,
+                    rx_dropped: Some(12),
+                    tx_dropped: Some(0),
+                    collisions: Some(0),
+                },
+            ),
+            (
+                "en0".to_string(),
+                InterfaceErrors {
+                    rx_errors: 0,
+                    tx_errors: 0,
+                    ..InterfaceErrors::default()
+                },
+            ),
+        ]);
+        let rates = HashMap::from([(
+            "eth0".to_string(),
+            InterfaceErrorRates {
+                errors: 0.5,
+                drops: 0.0,
+            },
+        )]);
+
+        let rows = interface_error_rows(&errors, &rates);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].0, ["en0", "0/0", "-/-", "-"].map(String::from));
+        assert_eq!(rows[0].1, InterfaceErrorRates::default());
+        assert_eq!(rows[1].0, ["eth0", "3/1", "12/0", "0"].map(String::from));
+        assert_eq!(rows[1].1.errors, 0.5);
+    }
+}
@@ -0,0 +1,105 @@
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Rect},
+    symbols::Marker,
+    terminal::Frame,
+};
+
+use crate::{
+    app::{data_harvester::pressure::PressureResource, App},
+    canvas::{drawing_utils::should_hide_x_label, Painter},
+    components::time_graph::{GraphData, TimeGraph},
+};
+
+/// The y-axis is stretched to the first of these, in percent, that fits what's shown. Stalls are
+/// usually a few percent at most, which a fixed 100% axis would flatten out.
+const Y_AXIS_MAXES: [f64; 4] = [10.0, 25.0, 50.0, 100.0];
+
+impl Painter {
+    pub fn draw_pressure_graph<B: Backend>(
+        &self, f: &mut Frame<'_, B>, app_state: &mut App, draw_loc: Rect, widget_id: u64,
+    ) {
+        let border_style = self.get_border_style(app_state, widget_id);
+        let title_style = self.get_title_style(app_state, widget_id);
+
+        if let Some(pressure_widget_state) =
+            app_state.pressure_state.widget_states.get_mut(&widget_id)
+        {
+            let time_start = -(pressure_widget_state.current_display_time as f64);
+            let x_bounds = [0, pressure_widget_state.current_display_time];
+            let hide_x_labels = should_hide_x_label(
+                app_state.app_config_fields.hide_time,
+                app_state.app_config_fields.autohide_time,
+                &mut pressure_widget_state.autohide_timer,
+                draw_loc,
+            );
+
+            let pressure_data = &app_state.converted_data.pressure_data;
+            let max_entry = pressure_data
+                .iter()
+                .flat_map(|series| &series.points)
+                .filter(|(time, _)| *time >= time_start)
+                .fold(0.0_f64, |acc, (_, value)| acc.max(*value));
+            let max_range = Y_AXIS_MAXES
+                .into_iter()
+                .find(|max| max_entry <= *max)
+                .unwrap_or(100.0);
+            let y_labels = ["0%".into(), format!("{max_range:.0}%").into()];
+
+            // Each resource has its own colour, with stalls of every task dashed.
+            let points = pressure_data
+                .iter()
+                .map(|series| GraphData {
+                    points: &series.points,
+                    style: match series.resource {
+                        PressureResource::Cpu => self.colours.avg_colour_style,
+                        PressureResource::Memory => self.colours.ram_style,
+                        PressureResource::Io => self.colours.rx_style,
+                    },
+                    name: Some(
+                        format!(
+                            "{} {}: {:.1}%",
+                            series.resource.name(),
+                            if series.is_full { "full" } else { "some" },
+                            series.latest
+                        )
+                        .into(),
+                    ),
+                    dashed: series.is_full,
+                })
+                .collect::<Vec<_>>();
+
+            let marker = if app_state.app_config_fields.use_dot {
+                Marker::Dot
+            } else {
+                Marker::Braille
+            };
+
+            TimeGraph {
+                x_bounds,
+                hide_x_labels,
+                y_bounds: [0.0, max_range],
+                y_labels: &y_labels,
+                graph_style: self.colours.graph_style,
+                border_style,
+                border_type: self.border_type,
+                title: " Pressure ".into(),
+                is_expanded: app_state.is_expanded,
+                title_style,
+                legend_constraints: Some((Constraint::Ratio(1, 1), Constraint::Ratio(3, 4))),
+                marker,
+            }
+            .draw_time_graph(f, draw_loc, &points);
+            self.draw_nerd_font_corners(f, draw_loc);
+        }
+
+        if app_state.should_get_widget_bounds() {
+            // Update draw loc in widget map
+            if let Some(widget) = app_state.widget_map.get_mut(&widget_id) {
+                widget.top_left_corner = Some((draw_loc.x, draw_loc.y));
+                widget.bottom_right_corner =
+                    Some((draw_loc.x + draw_loc.width, draw_loc.y + draw_loc.height));
+            }
+        }
+    }
+}
@@ -10,9 +10,10 @@ use unicode_segmentation::UnicodeSegmentation;
 
 use crate::{
     app::{App, AppSearchState},
-    canvas::Painter,
+    canvas::{drawing_utils::ancestry_breadcrumb, Painter},
     components::data_table::{DrawInfo, SelectionState},
     constants::*,
+    widgets::ProcWidgetState,
 };
 
 const SORT_MENU_WIDTH: u16 = 7;
@@ -78,11 +79,25 @@ impl Painter {
 
             let is_on_widget = widget_id == app_state.current_widget.widget_id;
 
+            let ancestry_chain = proc_widget_state
+                .table
+                .current_item()
+                .map(|process| {
+                    ProcWidgetState::get_ancestry_chain(
+                        process.pid,
+                        &app_state.data_collection.process_data,
+                    )
+                })
+                .unwrap_or_default();
+
             let draw_info = DrawInfo {
                 loc: draw_loc,
                 force_redraw: app_state.is_force_redraw,
                 recalculate_column_widths,
                 selection_state: SelectionState::new(app_state.is_expanded, is_on_widget),
+                footer_height: u16::from(!ancestry_chain.is_empty()),
+                is_frozen: app_state.frozen_widget_ids.contains(&widget_id),
+                alert_border_style: None,
             };
 
             proc_widget_state.table.draw(
@@ -91,6 +106,20 @@ impl Painter {
                 app_state.widget_map.get_mut(&widget_id),
                 self,
             );
+
+            let inner_rect = proc_widget_state.table.state.inner_rect;
+            if !ancestry_chain.is_empty() && inner_rect.width > 0 && inner_rect.height > 2 {
+                let breadcrumb_loc =
+                    Rect::new(inner_rect.x, inner_rect.bottom() - 1, inner_rect.width, 1);
+
+                f.render_widget(
+                    Paragraph::new(Span::styled(
+                        ancestry_breadcrumb(&ancestry_chain, inner_rect.width.into()),
+                        self.colours.border_style,
+                    )),
+                    breadcrumb_loc,
+                );
+            }
         }
     }
 
@@ -145,6 +174,7 @@ impl Painter {
             }
         }
 
+        let border_style = self.get_border_style(app_state, widget_id);
         if let Some(proc_widget_state) =
             app_state.proc_state.widget_states.get_mut(&(widget_id - 1))
         {
@@ -205,12 +235,18 @@ impl Painter {
                 self.colours.text_style
             };
 
+            let cmdline_style = if proc_widget_state.proc_search.is_searching_cmdline {
+                self.colours.currently_selected_text_style
+            } else {
+                self.colours.text_style
+            };
+
             // TODO: [MOUSE] Mouse support for these in search
             // TODO: [MOVEMENT] Movement support for these in search
-            let (case, whole, regex) = if self.is_mac_os {
-                ("Case(F1)", "Whole(F2)", "Regex(F3)")
+            let (case, whole, regex, cmdline) = if self.is_mac_os {
+                ("Case(F1)", "Whole(F2)", "Regex(F3)", "Cmd(F4)")
             } else {
-                ("Case(Alt+C)", "Whole(Alt+W)", "Regex(Alt+R)")
+                ("Case(Alt+C)", "Whole(Alt+W)", "Regex(Alt+R)", "Cmd(Alt+A)")
             };
             let option_text = Spans::from(vec![
                 Span::styled(case, case_style),
@@ -218,6 +254,8 @@ impl Painter {
                 Span::styled(whole, whole_word_style),
                 Span::raw("  "),
                 Span::styled(regex, regex_style),
+                Span::raw("  "),
+                Span::styled(cmdline, cmdline_style),
             ]);
 
             search_text.push(Spans::from(Span::styled(
@@ -233,10 +271,8 @@ impl Painter {
             let current_border_style =
                 if proc_widget_state.proc_search.search_state.is_invalid_search {
                     self.colours.invalid_query_style
-                } else if is_on_widget {
-                    self.colours.highlighted_border_style
                 } else {
-                    self.colours.border_style
+                    border_style
                 };
 
             let title = Span::styled(
@@ -256,10 +292,12 @@ impl Painter {
                     .title(title)
                     .borders(Borders::ALL)
                     .border_style(current_border_style)
+                    .border_type(self.border_type)
             } else if is_on_widget {
                 Block::default()
                     .borders(SIDE_BORDERS)
                     .border_style(current_border_style)
+                    .border_type(self.border_type)
             } else {
                 Block::default().borders(Borders::NONE)
             };
@@ -277,6 +315,7 @@ impl Painter {
                     .alignment(Alignment::Left),
                 margined_draw_loc,
             );
+            self.draw_nerd_font_corners(f, margined_draw_loc);
 
             if app_state.should_get_widget_bounds() {
                 // Update draw loc in widget map
@@ -308,6 +347,9 @@ impl Painter {
                 force_redraw: app_state.is_force_redraw,
                 recalculate_column_widths,
                 selection_state: SelectionState::new(app_state.is_expanded, is_on_widget),
+                footer_height: 0,
+                is_frozen: false,
+                alert_border_style: None,
             };
 
             pws.sort_table.draw(
@@ -9,7 +9,11 @@ use tui::{
 };
 use unicode_segmentation::UnicodeSegmentation;
 
-use crate::{app::App, canvas::Painter, constants::*};
+use crate::{
+    app::App,
+    canvas::{ansi::ansi_to_spans, Painter},
+    constants::*,
+};
 
 impl Painter {
     pub fn draw_terminal_display<B: Backend>(
@@ -17,20 +21,17 @@ impl Painter {
         widget_id: u64,
     ) {
         let should_get_widget_bounds = app_state.should_get_widget_bounds();
+        let border_style = self.get_border_style(app_state, widget_id);
+        let title_style = self.get_title_style(app_state, widget_id);
         if let Some(terminal_widget_state) =
             app_state.terminal_state.widget_states.get_mut(&widget_id)
         {
             let is_on_widget = widget_id == app_state.current_widget.widget_id;
-            let border_style = if is_on_widget {
-                self.colours.highlighted_border_style
-            } else {
-                self.colours.border_style
-            };
 
             let title = if app_state.is_expanded {
                 const TITLE_BASE: &str = " Terminal ── Esc to go back ";
                 Spans::from(vec![
-                    Span::styled(" Terminal ", self.colours.widget_title_style),
+                    Span::styled(" Terminal ", title_style),
                     Span::styled(
                         format!(
                             "─{}─ Esc to go back ",
@@ -42,7 +43,7 @@ impl Painter {
                     ),
                 ])
             } else {
-                Spans::from(Span::styled(" Terminal ", self.colours.widget_title_style))
+                Spans::from(Span::styled(" Terminal ", title_style))
             };
 
             let terminal_block = if draw_border {
@@ -50,60 +51,51 @@ impl Painter {
                     .title(title)
                     .borders(Borders::ALL)
                     .border_style(border_style)
+                    .border_type(self.border_type)
             } else if is_on_widget {
                 Block::default()
                     .borders(SIDE_BORDERS)
                     .border_style(self.colours.highlighted_border_style)
+                    .border_type(self.border_type)
             } else {
                 Block::default().borders(Borders::NONE)
             };
 
-            let mut contents = Vec::new();
-            let mut offset = terminal_widget_state.offset;
             let stdout_height = (draw_loc.height - 3) as usize;
-            for line in terminal_widget_state.stdout.lines().rev() {
-                if offset > 0 {
-                    if contents.len() == stdout_height {
-                        terminal_widget_state.offset = offset;
-                        offset = 0;
-                    } else {
-                        offset -= 1;
-                        continue;
-                    }
-                }
-                contents.push(Spans::from(Span::styled(
-                    line,
-                    if line.starts_with('$') {
-                        self.colours.highlighted_border_style
-                    } else {
-                        self.colours.text_style
-                    },
-                )));
-                if contents.len() == stdout_height {
-                    break;
+            let stdout_width = usize::from(terminal_block.inner(draw_loc).width);
+            let lines = terminal_widget_state.stdout.lines().rev().map(|line| {
+                if line.starts_with('$') {
+                    Spans::from(Span::styled(
+                        line.to_string(),
+                        self.colours.highlighted_border_style,
+                    ))
+                } else {
+                    ansi_to_spans(line, self.colours.text_style)
                 }
-            }
-            contents.reverse();
-            if terminal_widget_state.offset > 0 && contents.len() < stdout_height {
-                terminal_widget_state.offset -= 1;
-                contents.push(Spans::from(Span::styled(
+            });
+            let mut contents = terminal_widget_state.scrollback.rows(
+                lines,
+                stdout_height,
+                stdout_width,
+                Spans::from(Span::styled(
                     "<End reached>",
                     self.colours.currently_selected_text_style,
-                )));
-            }
-            while contents.len() < stdout_height {
-                contents.push(Spans::from(Span::styled("", self.colours.text_style)));
-            }
+                )),
+            );
             contents.push(Spans::from(Span::styled(
                 format!(
-                    "Input: {}",
-                    if terminal_widget_state.is_working {
-                        String::from("<Elaborating...>")
+                    "{}$ {}",
+                    terminal_widget_state.cwd.display(),
+                    if terminal_widget_state.is_working
+                        && !(cfg!(not(target_family = "unix")) && app_state.is_expanded)
+                    {
+                        if app_state.is_expanded {
+                            String::from("<Running, keys are sent to the command, Ctrl+C cancels>")
+                        } else {
+                            String::from("<Elaborating... Ctrl+C cancels>")
+                        }
                     } else if app_state.is_expanded {
-                        let input = terminal_widget_state.current_input();
-                        let cursor = input.len() - terminal_widget_state.input_offset;
-                        let left = &input[..cursor];
-                        let right = &input[cursor..];
+                        let (left, right) = terminal_widget_state.input_around_cursor();
                         if right.is_empty() {
                             left.to_string()
                         } else {
@@ -121,6 +113,7 @@ impl Painter {
             )));
 
             f.render_widget(Paragraph::new(contents).block(terminal_block), draw_loc);
+            self.draw_nerd_font_corners(f, draw_loc);
 
             if should_get_widget_bounds {
                 if let Some(widget) = app_state.widget_map.get_mut(&widget_id) {
@@ -1,8 +1,8 @@
-use std::{fs::File, io::Write};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use tui::{
     backend::Backend,
-    layout::{Constraint, Rect},
+    layout::{Constraint, Direction, Layout, Rect},
     terminal::Frame,
     text::{Span, Spans},
     widgets::{Block, Borders, Row, Table},
@@ -10,7 +10,12 @@ use tui::{
 use unicode_segmentation::UnicodeSegmentation;
 use uptime_lib;
 
-use crate::{app::App, canvas::Painter, constants::*};
+use crate::{
+    app::{data_harvester::remote_uptime::RemoteUptimeStatus, App},
+    canvas::Painter,
+    constants::*,
+    utils::gen_util::format_uptime,
+};
 
 #[rustfmt::skip]
 const NUMBERS: [&str; 10] = [
@@ -52,15 +57,12 @@ impl Painter {
         widget_id: u64,
     ) {
         let is_on_widget = widget_id == app_state.current_widget.widget_id;
-        let border_style = if is_on_widget {
-            self.colours.highlighted_border_style
-        } else {
-            self.colours.border_style
-        };
+        let border_style = self.get_border_style(app_state, widget_id);
+        let title_style = self.get_title_style(app_state, widget_id);
         let title = if app_state.is_expanded {
             const TITLE_BASE: &str = " Uptime ── Esc to go back ";
             Spans::from(vec![
-                Span::styled(" Uptime ", self.colours.widget_title_style),
+                Span::styled(" Uptime ", title_style),
                 Span::styled(
                     format!(
                         "─{}─ Esc to go back ",
@@ -72,24 +74,27 @@ impl Painter {
                 ),
             ])
         } else {
-            Spans::from(Span::styled(" Uptime ", self.colours.widget_title_style))
+            Spans::from(Span::styled(" Uptime ", title_style))
         };
         let terminal_block = if draw_border {
             Block::default()
                 .title(title)
                 .borders(Borders::ALL)
                 .border_style(border_style)
+                .border_type(self.border_type)
         } else if is_on_widget {
             Block::default()
                 .borders(SIDE_BORDERS)
                 .border_style(self.colours.highlighted_border_style)
+                .border_type(self.border_type)
         } else {
             Block::default().borders(Borders::NONE)
         };
         let mut upper = String::with_capacity(6);
         let mut middle = String::with_capacity(6);
         let mut bottom = String::with_capacity(6);
-        let mut seconds = uptime_lib::get().unwrap().as_secs();
+        let uptime = uptime_lib::get().unwrap().as_secs();
+        let mut seconds = uptime;
         let days = seconds / 60 / 60 / 24;
         seconds -= days * 60 * 60 * 24;
         let hours = seconds / 60 / 60;
@@ -102,22 +107,73 @@ impl Painter {
             middle += number.next().unwrap();
             upper += number.next().unwrap();
         }
-        let streak = app_state
+        let uptime_widget_state = app_state
             .uptime_state
-            .get_widget_state(widget_id)
-            .unwrap()
-            .streak;
-        if days > streak {
-            app_state
-                .uptime_state
-                .get_mut_widget_state(widget_id)
-                .unwrap()
-                .streak = days;
-            File::create("/home/felix/.config/bottom/days")
-                .unwrap()
-                .write_all(days.to_string().as_bytes())
-                .unwrap();
-        }
+            .get_mut_widget_state(widget_id)
+            .unwrap();
+        // Failing to save the streak or boots shouldn't get in the way of showing them.
+        let _ = uptime_widget_state.update_streak(days);
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|now| now.as_secs())
+            .unwrap_or_default();
+        let _ = uptime_widget_state.record_boot(now, uptime);
+        let streak = uptime_widget_state.streak;
+        let total_uptime = format_uptime(uptime_widget_state.total_uptime());
+        let next_milestone = app_state
+            .app_config_fields
+            .uptime_milestones
+            .iter()
+            .filter(|milestone| **milestone > days)
+            .min()
+            .map(|milestone| format!("{milestone} days"))
+            .unwrap_or_else(|| "None".to_string());
+
+        let remote_rows = app_state
+            .data_collection
+            .remote_uptime_harvest
+            .iter()
+            .map(|harvest| {
+                let (status, uptime) = match harvest.status {
+                    RemoteUptimeStatus::Up(uptime) => (format_uptime(uptime), Some(uptime)),
+                    RemoteUptimeStatus::Reachable(uptime) => (
+                        format!("Reachable for {}", format_uptime(uptime)),
+                        Some(uptime),
+                    ),
+                    RemoteUptimeStatus::Unreachable => ("Unreachable".to_string(), None),
+                };
+                if let Some(uptime) = uptime {
+                    let _ = uptime_widget_state
+                        .update_remote_streak(&harvest.name, uptime / 60 / 60 / 24);
+                }
+                let streak = uptime_widget_state
+                    .remote_streaks
+                    .get(&harvest.name)
+                    .map(|streak| format!("{streak} days"))
+                    .unwrap_or_else(|| "N/A".to_string());
+
+                Row::new([harvest.name.clone(), status, streak]).style(self.colours.text_style)
+            })
+            .collect::<Vec<_>>();
+
+        let inner_loc = terminal_block.inner(draw_loc);
+        f.render_widget(terminal_block, draw_loc);
+        let remote_height = if remote_rows.is_empty() {
+            0
+        } else {
+            remote_rows.len() as u16 + 3
+        };
+        let uptime_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(5),
+                Constraint::Length(remote_height),
+                Constraint::Min(0),
+            ])
+            .split(inner_loc);
+        let (digits_loc, remote_loc, boots_loc) =
+            (uptime_chunks[0], uptime_chunks[1], uptime_chunks[2]);
+
         f.render_widget(
             Table::new(vec![
                 Row::new(["Days ", &upper, "Hours", &hours.to_string()])
@@ -126,17 +182,62 @@ impl Painter {
                     .style(self.colours.text_style),
                 Row::new(["", &bottom, "Seconds", &seconds.to_string()])
                     .style(self.colours.text_style),
-                Row::new(["Longest streak", &format!("{streak} days"), "", ""]),
+                Row::new([
+                    "Longest streak",
+                    &format!("{streak} days"),
+                    "Next milestone",
+                    &next_milestone,
+                ]),
+                Row::new(["Total uptime", &total_uptime, "", ""]),
             ])
-            .block(terminal_block)
             .widths(&[
                 Constraint::Percentage(25),
                 Constraint::Percentage(25),
                 Constraint::Percentage(25),
                 Constraint::Percentage(25),
             ]),
-            draw_loc,
+            digits_loc,
         );
+
+        if !remote_rows.is_empty() {
+            f.render_widget(
+                Table::new(remote_rows)
+                    .header(
+                        Row::new(["Host", "Uptime", "Longest streak"])
+                            .style(self.colours.table_header_style)
+                            .bottom_margin(1),
+                    )
+                    .widths(&[
+                        Constraint::Percentage(34),
+                        Constraint::Percentage(33),
+                        Constraint::Percentage(33),
+                    ]),
+                remote_loc,
+            );
+        }
+
+        // Previous boots fill whatever room is left below, most recent first.
+        let boot_rows = uptime_widget_state
+            .previous_boots()
+            .take(usize::from(boots_loc.height.saturating_sub(2)))
+            .map(|boot| {
+                Row::new([format_boot_time(boot.boot_time), format_uptime(boot.uptime)])
+                    .style(self.colours.text_style)
+            })
+            .collect::<Vec<_>>();
+        if !boot_rows.is_empty() {
+            f.render_widget(
+                Table::new(boot_rows)
+                    .header(
+                        Row::new(["Previous boots", "Uptime"])
+                            .style(self.colours.table_header_style)
+                            .bottom_margin(1),
+                    )
+                    .widths(&[Constraint::Percentage(50), Constraint::Percentage(50)]),
+                boots_loc,
+            );
+        }
+        self.draw_nerd_font_corners(f, draw_loc);
         /*if let Some(terminal_widget_state) =
             app_state.terminal_state.widget_states.get_mut(&widget_id)
         {
@@ -245,3 +346,30 @@ impl Painter {
         }*/
     }
 }
+
+/// Formats a Unix timestamp as a UTC date and time, e.g. `2023-04-01 13:37 UTC`.
+fn format_boot_time(boot_time: u64) -> String {
+    i64::try_from(boot_time)
+        .ok()
+        .and_then(|boot_time| time::OffsetDateTime::from_unix_timestamp(boot_time).ok())
+        .and_then(|boot_time| {
+            boot_time
+                .format(&time::macros::format_description!(
+                    "[year]-[month]-[day] [hour]:[minute] UTC"
+                ))
+                .ok()
+        })
+        .unwrap_or_else(|| "N/A".to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_boot_formatting() {
+        assert_eq!(format_boot_time(1_680_356_220), "2023-04-01 13:37 UTC");
+        assert_eq!(format_uptime(3 * 86400 + 4 * 3600 + 5 * 60 + 6), "3d 4h 5m");
+        assert_eq!(format_uptime(59), "0m");
+    }
+}
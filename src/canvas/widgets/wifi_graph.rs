@@ -0,0 +1,125 @@
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Rect},
+    symbols::Marker,
+    terminal::Frame,
+};
+
+use crate::{
+    app::{data_harvester::network::WifiInfo, App},
+    canvas::{drawing_utils::should_hide_x_label, Painter},
+    components::time_graph::{GraphData, TimeGraph},
+};
+
+/// The range of the y-axis, in dBm. Anything under -100 dBm is unusable, and anything over -20 dBm
+/// is about as good as it gets.
+const Y_BOUNDS: [f64; 2] = [-100.0, -20.0];
+
+impl Painter {
+    pub fn draw_wifi_graph<B: Backend>(
+        &self, f: &mut Frame<'_, B>, app_state: &mut App, draw_loc: Rect, widget_id: u64,
+    ) {
+        let border_style = self.get_border_style(app_state, widget_id);
+        let title_style = self.get_title_style(app_state, widget_id);
+
+        if let Some(wifi_widget_state) = app_state.wifi_state.widget_states.get_mut(&widget_id) {
+            let x_bounds = [0, wifi_widget_state.current_display_time];
+            let hide_x_labels = should_hide_x_label(
+                app_state.app_config_fields.hide_time,
+                app_state.app_config_fields.autohide_time,
+                &mut wifi_widget_state.autohide_timer,
+                draw_loc,
+            );
+            let y_labels = [
+                format!("{:.0}dBm", Y_BOUNDS[0]).into(),
+                format!("{:.0}dBm", Y_BOUNDS[1]).into(),
+            ];
+
+            let points = app_state
+                .converted_data
+                .wifi_data
+                .iter()
+                .zip(self.colours.cpu_colour_styles.iter().cycle())
+                .map(|(series, style)| GraphData {
+                    points: &series.points,
+                    style: *style,
+                    name: Some(wifi_legend(&series.name, &series.info).into()),
+                    dashed: false,
+                })
+                .collect::<Vec<_>>();
+
+            let marker = if app_state.app_config_fields.use_dot {
+                Marker::Dot
+            } else {
+                Marker::Braille
+            };
+
+            TimeGraph {
+                x_bounds,
+                hide_x_labels,
+                y_bounds: Y_BOUNDS,
+                y_labels: &y_labels,
+                graph_style: self.colours.graph_style,
+                border_style,
+                border_type: self.border_type,
+                title: " Wi-Fi ".into(),
+                is_expanded: app_state.is_expanded,
+                title_style,
+                legend_constraints: Some((Constraint::Ratio(1, 1), Constraint::Ratio(3, 4))),
+                marker,
+            }
+            .draw_time_graph(f, draw_loc, &points);
+            self.draw_nerd_font_corners(f, draw_loc);
+        }
+
+        if app_state.should_get_widget_bounds() {
+            // Update draw loc in widget map
+            if let Some(widget) = app_state.widget_map.get_mut(&widget_id) {
+                widget.top_left_corner = Some((draw_loc.x, draw_loc.y));
+                widget.bottom_right_corner =
+                    Some((draw_loc.x + draw_loc.width, draw_loc.y + draw_loc.height));
+            }
+        }
+    }
+}
+
+/// Returns the legend entry of an interface, e.g. `wlan0 (home): -56 dBm, 5.2 GHz, 867 Mbit/s`.
+/// Details that aren't known are left out.
+fn wifi_legend(name: &str, info: &WifiInfo) -> String {
+    let mut legend = match &info.ssid {
+        Some(ssid) => format!("{name} ({ssid}): {} dBm", info.rssi_dbm),
+        None => format!("{name}: {} dBm", info.rssi_dbm),
+    };
+    if let Some(frequency_mhz) = info.frequency_mhz {
+        legend.push_str(&format!(", {:.1} GHz", f64::from(frequency_mhz) / 1000.0));
+    }
+    if let Some(tx_bitrate_kbps) = info.tx_bitrate_kbps {
+        legend.push_str(&format!(
+            ", {:.0} Mbit/s",
+            f64::from(tx_bitrate_kbps) / 1000.0
+        ));
+    }
+    legend
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_wifi_legend() {
+        let mut info = WifiInfo {
+            rssi_dbm: -56,
+            ..WifiInfo::default()
+        };
+        assert_eq!(wifi_legend("wlan0", &info), "wlan0: -56 dBm");
+
+        info.ssid = Some("home".to_string());
+        info.frequency_mhz = Some(5180);
+        info.tx_bitrate_kbps = Some(866_700);
+        assert_eq!(
+            wifi_legend("wlan0", &info),
+            "wlan0 (home): -56 dBm, 5.2 GHz, 867 Mbit/s"
+        );
+    }
+}
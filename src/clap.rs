@@ -233,6 +233,18 @@ pub fn build_app() -> Command<'static> {
             If it doesn't exist, one is created.",
         );
 
+    let generate_config = Arg::new("generate_config")
+        .long("generate-config")
+        .takes_value(true)
+        .min_values(0)
+        .value_name("FILE")
+        .help("Writes a commented default config file and exits.")
+        .long_help(
+            "Writes a config file with every setting commented out and set to its default, \
+            along with a description of each, then exits. Writes to stdout if no file or '-' \
+            is given.",
+        );
+
     // TODO: Fix this, its broken in the manpage
     let color = Arg::new("color")
         .long("color")
@@ -369,6 +381,56 @@ use CPU (3) as the default instead.
         .help("The timespan of data kept.")
         .long_help("How much data is stored at once in terms of time. Takes in human-readable time spans (e.g. 10m, 1h), with a minimum of 1 minute. Note higher values will take up more memory. Defaults to 10 minutes.");
 
+    let serve = Arg::new("serve")
+        .long("serve")
+        .takes_value(true)
+        .value_name("ADDR")
+        .help("Serves the collected data as JSON over HTTP.")
+        .long_help("Serves the latest collected data as JSON over HTTP on the given address, like 127.0.0.1:9000, while the interface keeps running. The CPU, memory, process, network, disk and connection data is collected even if no widget shows it. Each is available on its own, like /cpu, or all together on /.");
+
+    let agent = Arg::new("agent")
+        .long("agent")
+        .takes_value(true)
+        .value_name("ADDR")
+        .conflicts_with_all(&["connect", "serve"])
+        .help("Streams the collected data to viewers, without an interface.")
+        .long_help("Runs without an interface, only collecting data and streaming it to any bottom connected with --connect on the given address, like 0.0.0.0:9100. Everything is collected, so the viewer can show any widget.");
+
+    let headless = Arg::new("headless")
+        .long("headless")
+        .conflicts_with("connect")
+        .help("Runs without an interface, only serving the collected data.")
+        .long_help("Runs without setting up the terminal at all, only collecting data and serving it with --serve until stopped, which suits running bottom as a service. Collection errors are written to stderr. An agent started with --agent always runs like this.");
+
+    let allow_commands = Arg::new("allow_commands")
+        .long("allow_commands")
+        .help("Lets viewers run commands on this machine through the terminal widget.")
+        .long_help("With --agent, lets a bottom connected with --connect run commands typed into its terminal widget on this machine, through the shell set for the terminal widget here. Needs a token set with --agent_token or in the config file, and commands that don't carry the same token are refused. Commands and the token are sent unencrypted, so this is only allowed on a loopback address unless --allow_network_commands is also set.");
+
+    let allow_network_commands = Arg::new("allow_network_commands")
+        .long("allow_network_commands")
+        .help("Lets --allow_commands be used on an address other machines can reach.")
+        .long_help("--allow_commands is refused unless the agent only listens on a loopback address, like 127.0.0.1:9100, which viewers reach through a tunnel like SSH. This allows it on any address instead, where the token and commands cross the network unencrypted.");
+
+    let agent_token = Arg::new("agent_token")
+        .long("agent_token")
+        .takes_value(true)
+        .value_name("TOKEN")
+        .help("The secret that commands sent to an agent have to carry.")
+        .long_help("The secret shared by an agent started with --allow_commands and the viewers allowed to run commands on it. The agent refuses commands that don't carry the same token, and a viewer sends its own along with each command. Setting 'agent_token' in the config file keeps it out of the process list.");
+
+    let advertise = Arg::new("advertise")
+        .long("advertise")
+        .help("Advertises the agent on the local network with mDNS.")
+        .long_help("With --agent, answers mDNS queries for the _bottom._tcp service, so a bottom with a discovery widget on the same network lists this agent and can switch over to it.");
+
+    let connect = Arg::new("connect")
+        .long("connect")
+        .takes_value(true)
+        .value_name("HOST:PORT")
+        .help("Shows the data of a bottom running with --agent elsewhere.")
+        .long_help("Shows the data collected by a bottom running with --agent on another machine, like server.lan:9100, instead of collecting it locally. Processes and connections shown can't be killed, as they aren't on this machine.");
+
     const VERSION: &str = match option_env!("NIGHTLY_VERSION") {
         Some(nightly_version) => nightly_version,
         None => crate_version!(),
@@ -394,6 +456,7 @@ use CPU (3) as the default instead.
         .arg(case_sensitive)
         .arg(process_command)
         .arg(config_location)
+        .arg(generate_config)
         .arg(color)
         .arg(mem_as_value)
         .arg(default_time_value)
@@ -420,6 +483,14 @@ use CPU (3) as the default instead.
         .arg(use_old_network_legend)
         .arg(whole_word)
         .arg(retention)
+        .arg(serve)
+        .arg(headless)
+        .arg(agent)
+        .arg(allow_commands)
+        .arg(allow_network_commands)
+        .arg(agent_token)
+        .arg(advertise)
+        .arg(connect)
         .arg(expanded_on_startup);
 
     #[cfg(feature = "battery")]
@@ -1,3 +1,6 @@
+pub mod custom_widget;
 pub mod data_table;
+pub mod scrollback;
 pub mod time_graph;
+pub mod tree_prefix;
 pub mod tui_widget;
@@ -0,0 +1,127 @@
+//! Widgets that aren't built into bottom. One is registered under a name with [`register_widget`]
+//! before bottom is started with [`run`](crate::run), and can then be put in the layout by that
+//! name like any other widget.
+
+use std::{collections::HashMap, sync::Mutex};
+
+use crossterm::event::KeyEvent;
+use once_cell::sync::Lazy;
+use tui::{buffer::Buffer, layout::Rect};
+
+use crate::{
+    app::{data_farmer::DataCollection, layout_manager::BottomWidgetType},
+    canvas::canvas_styling::CanvasColours,
+    error::{BottomError, Result},
+};
+
+/// A widget that isn't built into bottom. bottom draws its border and title, and gives it
+/// harvested data and keys while it is selected.
+pub trait Widget {
+    /// The title shown in the widget's border.
+    fn title(&self) -> String;
+
+    /// Draws the widget in `area`, which is inside its border.
+    fn draw(&mut self, buf: &mut Buffer, area: Rect, colours: &CanvasColours, is_selected: bool);
+
+    /// Handles a key pressed while the widget is selected, returning whether it was used. Keys that
+    /// aren't used go on to do whatever they are bound to.
+    fn handle_key(&mut self, _event: &KeyEvent) -> bool {
+        false
+    }
+
+    /// Takes in the data collected so far, each time new data is harvested while not frozen.
+    fn ingest_data(&mut self, _data: &DataCollection) {}
+}
+
+/// Creates a new instance of a custom widget, once for each time it appears in the layout.
+pub type WidgetFactory = fn() -> Box<dyn Widget>;
+
+static REGISTERED_WIDGETS: Lazy<Mutex<HashMap<String, WidgetFactory>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Registers a custom widget under `name`, which is not case-sensitive, replacing any widget
+/// registered under it before. Names used by built-in widgets can't be taken.
+pub fn register_widget(name: &str, factory: WidgetFactory) -> Result<()> {
+    if !matches!(name.parse(), Err(_) | Ok(BottomWidgetType::Custom(_))) {
+        return Err(BottomError::ConfigError(format!(
+            "\"{name}\" is already the name of a built-in widget."
+        )));
+    }
+    REGISTERED_WIDGETS
+        .lock()
+        .unwrap()
+        .insert(name.to_lowercase(), factory);
+    Ok(())
+}
+
+/// Returns whether a custom widget is registered under `name`.
+pub fn is_registered(name: &str) -> bool {
+    REGISTERED_WIDGETS
+        .lock()
+        .unwrap()
+        .contains_key(&name.to_lowercase())
+}
+
+/// Creates the custom widget registered under `name`, if there is one.
+pub fn create_widget(name: &str) -> Option<Box<dyn Widget>> {
+    let factory = *REGISTERED_WIDGETS
+        .lock()
+        .unwrap()
+        .get(&name.to_lowercase())?;
+    Some(factory())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Counter {
+        updates: usize,
+    }
+
+    impl Widget for Counter {
+        fn title(&self) -> String {
+            "Counter".to_string()
+        }
+
+        fn draw(&mut self, buf: &mut Buffer, area: Rect, colours: &CanvasColours, _: bool) {
+            buf.set_string(area.x, area.y, self.updates.to_string(), colours.text_style);
+        }
+
+        fn ingest_data(&mut self, _data: &DataCollection) {
+            self.updates += 1;
+        }
+    }
+
+    fn counter() -> Box<dyn Widget> {
+        Box::new(Counter { updates: 0 })
+    }
+
+    #[test]
+    fn registered_widgets() {
+        assert!("test_counter".parse::<BottomWidgetType>().is_err());
+        assert!(create_widget("test_counter").is_none());
+
+        register_widget("Test_Counter", counter).unwrap();
+        assert!(is_registered("test_counter"));
+        assert_eq!(
+            "TEST_COUNTER".parse::<BottomWidgetType>().unwrap(),
+            BottomWidgetType::Custom("test_counter".to_string())
+        );
+
+        let mut widget = create_widget("test_counter").unwrap();
+        widget.ingest_data(&DataCollection::default());
+        let area = Rect::new(0, 0, 3, 1);
+        let mut buf = Buffer::empty(area);
+        widget.draw(&mut buf, area, &CanvasColours::default(), false);
+        assert_eq!(buf.get(0, 0).symbol, "1");
+        assert_eq!(widget.title(), "Counter");
+    }
+
+    #[test]
+    fn built_in_names_are_taken() {
+        assert!(register_widget("cpu", counter).is_err());
+        assert!(register_widget("Memory", counter).is_err());
+        assert!(!is_registered("cpu"));
+    }
+}
@@ -15,6 +15,9 @@ pub use state::{DataTableState, ScrollDirection};
 pub mod draw;
 pub use draw::*;
 
+pub mod scrollbar;
+pub use scrollbar::Scrollbar;
+
 pub mod data_type;
 pub use data_type::*;
 
@@ -139,6 +142,11 @@ impl<DataType: DataToCell<H>, H: ColumnHeader, S: SortType, C: DataTableColumn<H
         self.data.get(self.state.current_index)
     }
 
+    /// Returns all the data currently in the table.
+    pub fn data(&self) -> &[DataType] {
+        &self.data
+    }
+
     /// Returns tui-rs' internal selection.
     pub fn tui_selected(&self) -> Option<usize> {
         self.state.table_state.selected()
@@ -180,6 +188,7 @@ mod test {
             left_to_right: false,
             is_basic: false,
             show_table_scroll_position: true,
+            show_scrollbar: true,
             show_current_entry_when_unfocused: false,
         };
         let styling = DataTableStyling::default();
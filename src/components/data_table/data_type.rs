@@ -18,6 +18,14 @@ where
         row
     }
 
+    /// Apply styling to a generated cell of the given column.
+    ///
+    /// The default implementation just returns the `text` that is passed in.
+    #[inline(always)]
+    fn style_cell<'a>(&self, text: Text<'a>, _column: &H, _painter: &Painter) -> Text<'a> {
+        text
+    }
+
     /// Returns the desired column widths in light of having seen data.
     fn column_widths<C: DataTableColumn<H>>(data: &[Self], columns: &[C]) -> Vec<u16>
     where
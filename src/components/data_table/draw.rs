@@ -7,15 +7,16 @@ use concat_string::concat_string;
 use tui::{
     backend::Backend,
     layout::{Constraint, Direction, Layout, Rect},
+    style::Style,
     text::{Span, Spans, Text},
-    widgets::{Block, Borders, Row, Table},
+    widgets::{Block, BorderType, Borders, Row, Table},
     Frame,
 };
 use unicode_segmentation::UnicodeSegmentation;
 
 use super::{
-    CalculateColumnWidths, ColumnHeader, ColumnWidthBounds, DataTable, DataTableColumn, DataToCell,
-    SortType,
+    CalculateColumnWidths, ColumnHeader, ColumnWidthBounds, DataTable, DataTableColumn,
+    DataTableStyling, DataToCell, Scrollbar, SortType,
 };
 use crate::{
     app::layout_manager::BottomWidget,
@@ -47,6 +48,12 @@ pub struct DrawInfo {
     pub force_redraw: bool,
     pub recalculate_column_widths: bool,
     pub selection_state: SelectionState,
+    /// How many rows at the bottom of the inner area to leave free for the caller to draw in.
+    pub footer_height: u16,
+    /// Whether this widget is frozen on its own, which is marked in the title.
+    pub is_frozen: bool,
+    /// The border style to use while this widget is over its alert threshold and not selected.
+    pub alert_border_style: Option<Style>,
 }
 
 impl DrawInfo {
@@ -67,20 +74,22 @@ where
     S: SortType,
     C: DataTableColumn<H>,
 {
-    fn block<'a>(&self, draw_info: &'a DrawInfo, data_len: usize) -> Block<'a> {
+    fn block<'a>(
+        &self, draw_info: &'a DrawInfo, styling: &DataTableStyling, data_len: usize,
+        border_type: BorderType,
+    ) -> Block<'a> {
         let border_style = match draw_info.selection_state {
-            SelectionState::NotSelected => self.styling.border_style,
-            SelectionState::Selected | SelectionState::Expanded => {
-                self.styling.highlighted_border_style
-            }
+            SelectionState::NotSelected => styling.border_style,
+            SelectionState::Selected | SelectionState::Expanded => styling.highlighted_border_style,
         };
 
         if !self.props.is_basic {
             let block = Block::default()
                 .borders(Borders::ALL)
-                .border_style(border_style);
+                .border_style(border_style)
+                .border_type(border_type);
 
-            if let Some(title) = self.generate_title(draw_info, data_len) {
+            if let Some(title) = self.generate_title(draw_info, styling, data_len) {
                 block.title(title)
             } else {
                 block
@@ -90,6 +99,7 @@ where
             Block::default()
                 .borders(SIDE_BORDERS)
                 .border_style(border_style)
+                .border_type(border_type)
         } else {
             Block::default().borders(Borders::NONE)
         }
@@ -97,16 +107,22 @@ where
 
     /// Generates a title, given the available space.
     pub fn generate_title<'a>(
-        &self, draw_info: &'a DrawInfo, total_items: usize,
+        &self, draw_info: &'a DrawInfo, styling: &DataTableStyling, total_items: usize,
     ) -> Option<Spans<'a>> {
         self.props.title.as_ref().map(|title| {
             let current_index = self.state.current_index.saturating_add(1);
             let draw_loc = draw_info.loc;
-            let title_style = self.styling.title_style;
+            let title_style = styling.title_style;
             let border_style = if draw_info.is_on_widget() {
-                self.styling.highlighted_border_style
+                styling.highlighted_border_style
+            } else {
+                styling.border_style
+            };
+
+            let title = if draw_info.is_frozen {
+                concat_string!(title, "[F] ")
             } else {
-                self.styling.border_style
+                title.to_string()
             };
 
             let title = if self.props.show_table_scroll_position {
@@ -117,10 +133,10 @@ where
                 if title_string.len() + 2 <= draw_loc.width.into() {
                     title_string
                 } else {
-                    title.to_string()
+                    title
                 }
             } else {
-                title.to_string()
+                title
             };
 
             if draw_info.is_expanded() {
@@ -151,7 +167,11 @@ where
             .direction(Direction::Horizontal)
             .split(draw_loc)[0];
 
-        let block = self.block(draw_info, self.data.len());
+        let mut styling = self.styling.with_overrides(widget.as_deref());
+        if let Some(alert_border_style) = draw_info.alert_border_style {
+            styling.border_style = alert_border_style;
+        }
+        let block = self.block(draw_info, &styling, self.data.len(), painter.border_type);
 
         let (inner_width, inner_height) = {
             let inner_rect = block.inner(margined_draw_loc);
@@ -162,8 +182,26 @@ where
         if inner_width == 0 || inner_height == 0 {
             f.render_widget(block, margined_draw_loc);
         } else {
+            let show_header = inner_height > 1;
+            let header_height = u16::from(show_header);
+            let table_gap = if !show_header || draw_loc.height < TABLE_GAP_HEIGHT_LIMIT {
+                0
+            } else {
+                self.props.table_gap
+            };
+            let num_rows = usize::from(
+                inner_height.saturating_sub(table_gap + header_height + draw_info.footer_height),
+            );
+
+            // The scrollbar takes up the rightmost column, so the widths need to be recalculated
+            // whenever it appears or disappears.
+            let show_scrollbar = self.props.show_scrollbar && self.data.len() > num_rows;
+            let table_width = inner_width - u16::from(show_scrollbar);
+
             // Calculate widths
-            if draw_info.recalculate_column_widths {
+            if draw_info.recalculate_column_widths || show_scrollbar != self.state.has_scrollbar {
+                self.state.has_scrollbar = show_scrollbar;
+
                 let col_widths = DataType::column_widths(&self.data, &self.columns);
 
                 self.columns
@@ -182,7 +220,7 @@ where
 
                 self.state.calculated_widths = self
                     .columns
-                    .calculate_column_widths(inner_width, self.props.left_to_right);
+                    .calculate_column_widths(table_width, self.props.left_to_right);
 
                 // Update draw loc in widget map
                 if let Some(widget) = widget {
@@ -192,21 +230,11 @@ where
                 }
             }
 
-            let show_header = inner_height > 1;
-            let header_height = u16::from(show_header);
-            let table_gap = if !show_header || draw_loc.height < TABLE_GAP_HEIGHT_LIMIT {
-                0
-            } else {
-                self.props.table_gap
-            };
-
             let columns = &self.columns;
             if !self.data.is_empty() || !self.first_draw {
                 self.first_draw = false; // TODO: Doing it this way is fine, but it could be done better (e.g. showing custom no results/entries message)
 
                 let rows = {
-                    let num_rows =
-                        usize::from(inner_height.saturating_sub(table_gap + header_height));
                     self.state
                         .get_start_position(num_rows, draw_info.force_redraw);
                     let start = self.state.display_start_index;
@@ -221,7 +249,9 @@ where
                                 .iter()
                                 .zip(&self.state.calculated_widths)
                                 .filter_map(|(column, &width)| {
-                                    data_row.to_cell(column.inner(), width)
+                                    data_row.to_cell(column.inner(), width).map(|cell| {
+                                        data_row.style_cell(cell, column.inner(), painter)
+                                    })
                                 }),
                         );
 
@@ -232,21 +262,21 @@ where
                 let headers = self
                     .sort_type
                     .build_header(columns, &self.state.calculated_widths)
-                    .style(self.styling.header_style)
+                    .style(styling.header_style)
                     .bottom_margin(table_gap);
 
                 let widget = {
                     let highlight_style = if draw_info.is_on_widget()
                         || self.props.show_current_entry_when_unfocused
                     {
-                        self.styling.highlighted_text_style
+                        styling.highlighted_text_style
                     } else {
-                        self.styling.text_style
+                        styling.text_style
                     };
                     let mut table = Table::new(rows)
                         .block(block)
                         .highlight_style(highlight_style)
-                        .style(self.styling.text_style);
+                        .style(styling.text_style);
 
                     if show_header {
                         table = table.header(headers);
@@ -274,13 +304,40 @@ where
                     margined_draw_loc,
                     table_state,
                 );
+
+                if show_scrollbar {
+                    let inner_rect = self.state.inner_rect;
+                    let border_style = if draw_info.is_on_widget() {
+                        styling.highlighted_border_style
+                    } else {
+                        styling.border_style
+                    };
+
+                    f.render_widget(
+                        Scrollbar {
+                            scroll_position: self.state.display_start_index,
+                            visible_rows: num_rows,
+                            total_rows: self.data.len(),
+                            track_style: styling.border_style,
+                            thumb_style: border_style,
+                        },
+                        Rect::new(
+                            inner_rect.right() - 1,
+                            inner_rect.y + header_height + table_gap,
+                            1,
+                            num_rows as u16,
+                        ),
+                    );
+                }
             } else {
                 let table = Table::new(once(Row::new(Text::raw("No data"))))
                     .block(block)
-                    .style(self.styling.text_style)
+                    .style(styling.text_style)
                     .widths(&[Constraint::Percentage(100)]);
                 f.render_widget(table, margined_draw_loc);
             }
         }
+
+        painter.draw_nerd_font_corners(f, margined_draw_loc);
     }
 }
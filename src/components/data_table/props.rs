@@ -16,6 +16,9 @@ pub struct DataTableProps {
     /// Whether to show the table scroll position.
     pub show_table_scroll_position: bool,
 
+    /// Whether to show a scrollbar if not all rows fit.
+    pub show_scrollbar: bool,
+
     /// Whether to show the current entry as highlighted when not focused.
     pub show_current_entry_when_unfocused: bool,
 }
@@ -0,0 +1,98 @@
+use std::cmp::{max, min};
+
+use tui::{buffer::Buffer, layout::Rect, style::Style, widgets::Widget};
+
+/// A one-column-wide vertical scrollbar, showing which part of a table's rows are visible.
+pub struct Scrollbar {
+    /// The index of the first visible row.
+    pub scroll_position: usize,
+
+    /// How many rows fit on screen.
+    pub visible_rows: usize,
+
+    /// How many rows there are in total.
+    pub total_rows: usize,
+
+    pub track_style: Style,
+    pub thumb_style: Style,
+}
+
+impl Scrollbar {
+    const TRACK: &'static str = "│";
+    const THUMB: &'static str = "█";
+
+    /// Returns the offset and height of the thumb, in rows.
+    fn thumb(&self) -> (usize, usize) {
+        if self.total_rows == 0 {
+            return (0, self.visible_rows);
+        }
+
+        let height = min(
+            self.visible_rows,
+            max(1, self.visible_rows * self.visible_rows / self.total_rows),
+        );
+        let offset = min(
+            self.scroll_position * self.visible_rows / self.total_rows,
+            self.visible_rows - height,
+        );
+
+        (offset, height)
+    }
+}
+
+impl Widget for Scrollbar {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width == 0 {
+            return;
+        }
+
+        let (thumb_offset, thumb_height) = self.thumb();
+        let thumb_rows = thumb_offset..thumb_offset + thumb_height;
+
+        for (row, y) in (area.top()..area.bottom()).enumerate() {
+            if thumb_rows.contains(&row) {
+                buf.set_string(area.left(), y, Self::THUMB, self.thumb_style);
+            } else {
+                buf.set_string(area.left(), y, Self::TRACK, self.track_style);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn scrollbar(scroll_position: usize, visible_rows: usize, total_rows: usize) -> Scrollbar {
+        Scrollbar {
+            scroll_position,
+            visible_rows,
+            total_rows,
+            track_style: Style::default(),
+            thumb_style: Style::default(),
+        }
+    }
+
+    #[test]
+    fn test_thumb() {
+        assert_eq!(scrollbar(0, 10, 100).thumb(), (0, 1));
+        assert_eq!(scrollbar(50, 10, 100).thumb(), (5, 1));
+        assert_eq!(scrollbar(0, 10, 20).thumb(), (0, 5));
+        assert_eq!(scrollbar(10, 10, 20).thumb(), (5, 5));
+
+        // The thumb never runs past the end of the track.
+        assert_eq!(scrollbar(19, 10, 20).thumb(), (5, 5));
+    }
+
+    #[test]
+    fn test_render() {
+        let area = Rect::new(0, 0, 1, 4);
+        let mut buf = Buffer::empty(area);
+        scrollbar(4, 4, 8).render(area, &mut buf);
+
+        let symbols = (0..4)
+            .map(|y| buf.get(0, y).symbol.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(symbols, vec!["│", "│", "█", "█"]);
+    }
+}
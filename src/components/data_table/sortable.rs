@@ -410,6 +410,7 @@ mod test {
                 left_to_right: false,
                 is_basic: false,
                 show_table_scroll_position: true,
+                show_scrollbar: true,
                 show_current_entry_when_unfocused: false,
             };
 
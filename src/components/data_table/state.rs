@@ -34,6 +34,9 @@ pub struct DataTableState {
 
     /// The current inner [`Rect`].
     pub inner_rect: Rect,
+
+    /// Whether the scrollbar was shown on the last draw, which takes up a column.
+    pub has_scrollbar: bool,
 }
 
 impl Default for DataTableState {
@@ -45,6 +48,7 @@ impl Default for DataTableState {
             calculated_widths: vec![],
             table_state: TableState::default(),
             inner_rect: Rect::default(),
+            has_scrollbar: false,
         }
     }
 }
@@ -1,8 +1,8 @@
 use tui::style::Style;
 
-use crate::canvas::canvas_styling::CanvasColours;
+use crate::{app::layout_manager::BottomWidget, canvas::canvas_styling::CanvasColours};
 
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct DataTableStyling {
     pub header_style: Style,
     pub border_style: Style,
@@ -23,4 +23,20 @@ impl DataTableStyling {
             title_style: colours.widget_title_style,
         }
     }
+
+    /// Returns this styling with the border and title colours set for `widget` in the layout
+    /// applied, if there are any.
+    pub fn with_overrides(&self, widget: Option<&BottomWidget>) -> Self {
+        let mut styling = self.clone();
+        if let Some(widget) = widget {
+            if let Some(border_style) = widget.border_style {
+                styling.border_style = border_style;
+            }
+            if let Some(title_style) = widget.title_style {
+                styling.title_style = title_style;
+            }
+        }
+
+        styling
+    }
 }
@@ -0,0 +1,231 @@
+//! Scrolling through lines of output that grow at the bottom, like a terminal's, where each line
+//! may wrap onto several rows or be scrolled horizontally instead.
+
+use tui::{
+    style::Style,
+    text::{Span, Spans},
+};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::utils::gen_util::str_width;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Scrollback {
+    /// How many rows the output is scrolled up from the bottom.
+    pub offset: usize,
+    /// Whether long lines wrap onto more rows, rather than being scrolled horizontally.
+    pub wrap_output: bool,
+    /// How many columns the output is scrolled right, when not wrapping.
+    pub horizontal_offset: usize,
+}
+
+impl Default for Scrollback {
+    fn default() -> Self {
+        Self {
+            offset: 0,
+            wrap_output: true,
+            horizontal_offset: 0,
+        }
+    }
+}
+
+impl Scrollback {
+    /// Switches between wrapping long lines and scrolling them horizontally.
+    pub fn toggle_wrap(&mut self) {
+        self.wrap_output = !self.wrap_output;
+        self.horizontal_offset = 0;
+        self.offset = 0;
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.offset += 1;
+    }
+
+    pub fn scroll_down(&mut self) {
+        self.offset = self.offset.saturating_sub(1);
+    }
+
+    pub fn scroll_left(&mut self) {
+        if !self.wrap_output {
+            self.horizontal_offset = self.horizontal_offset.saturating_sub(1);
+        }
+    }
+
+    pub fn scroll_right(&mut self) {
+        if !self.wrap_output {
+            self.horizontal_offset += 1;
+        }
+    }
+
+    /// Returns exactly `height` rows to show, given the lines from newest to oldest. Scrolling
+    /// past the oldest line shows `end_marker` under it, and steps the offset back towards it.
+    pub fn rows(
+        &mut self, lines: impl Iterator<Item = Spans<'static>>, height: usize, width: usize,
+        end_marker: Spans<'static>,
+    ) -> Vec<Spans<'static>> {
+        // Each line may take up several rows when wrapped, and the offset counts rows.
+        let wrap_output = self.wrap_output;
+        let horizontal_offset = self.horizontal_offset;
+        let rows = lines.flat_map(|spans| {
+            if wrap_output {
+                wrap_spans(spans, width)
+                    .into_iter()
+                    .rev()
+                    .collect::<Vec<_>>()
+            } else {
+                vec![skip_columns(spans, horizontal_offset)]
+            }
+        });
+
+        let mut contents = Vec::with_capacity(height);
+        let mut offset = self.offset;
+        for row in rows {
+            if offset > 0 {
+                if contents.len() == height {
+                    self.offset = offset;
+                    offset = 0;
+                } else {
+                    offset -= 1;
+                    continue;
+                }
+            }
+            contents.push(row);
+            if contents.len() == height {
+                break;
+            }
+        }
+        contents.reverse();
+        if self.offset > 0 && contents.len() < height {
+            self.offset -= 1;
+            contents.push(end_marker);
+        }
+        while contents.len() < height {
+            contents.push(Spans::from(Span::styled("", Style::default())));
+        }
+
+        contents
+    }
+}
+
+/// Splits a line into rows no wider than `width` columns, keeping each span's style.
+fn wrap_spans(spans: Spans<'static>, width: usize) -> Vec<Spans<'static>> {
+    let width = width.max(1);
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut row_width = 0;
+
+    for span in spans.0 {
+        let mut text = String::new();
+        for grapheme in span.content.graphemes(true) {
+            let grapheme_width = str_width(grapheme);
+            if row_width > 0 && row_width + grapheme_width > width {
+                if !text.is_empty() {
+                    row.push(Span::styled(std::mem::take(&mut text), span.style));
+                }
+                rows.push(Spans::from(std::mem::take(&mut row)));
+                row_width = 0;
+            }
+            text.push_str(grapheme);
+            row_width += grapheme_width;
+        }
+        if !text.is_empty() {
+            row.push(Span::styled(text, span.style));
+        }
+    }
+    rows.push(Spans::from(row));
+
+    rows
+}
+
+/// Drops the first `columns` columns of a line, for scrolling it horizontally.
+fn skip_columns(spans: Spans<'static>, columns: usize) -> Spans<'static> {
+    let mut skipped = 0;
+
+    Spans::from(
+        spans
+            .0
+            .into_iter()
+            .filter_map(|span| {
+                let text = span
+                    .content
+                    .graphemes(true)
+                    .skip_while(|grapheme| {
+                        let skip = skipped < columns;
+                        if skip {
+                            skipped += str_width(grapheme);
+                        }
+                        skip
+                    })
+                    .collect::<String>();
+                (!text.is_empty()).then(|| Span::styled(text, span.style))
+            })
+            .collect::<Vec<_>>(),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use tui::style::Color;
+
+    use super::*;
+
+    #[test]
+    fn test_wrap_spans() {
+        let red = Style::default().fg(Color::Red);
+        let spans = Spans::from(vec![Span::raw("abc"), Span::styled("defg", red)]);
+
+        let rows = wrap_spans(spans.clone(), 3);
+        assert_eq!(
+            rows,
+            vec![
+                Spans::from(vec![Span::raw("abc")]),
+                Spans::from(vec![Span::styled("def", red)]),
+                Spans::from(vec![Span::styled("g", red)]),
+            ]
+        );
+        assert_eq!(wrap_spans(Spans::default(), 3).len(), 1);
+
+        // Wide characters aren't split across rows.
+        assert_eq!(wrap_spans(Spans::from("日本語"), 5).len(), 2);
+
+        assert_eq!(
+            skip_columns(spans, 4),
+            Spans::from(vec![Span::styled("efg", red)])
+        );
+    }
+
+    #[test]
+    fn test_scrollback_rows() {
+        let lines = || ["one", "two", "three"].into_iter().rev().map(Spans::from);
+        let text = |rows: Vec<Spans<'static>>| {
+            rows.iter()
+                .map(|row| {
+                    row.0
+                        .iter()
+                        .map(|span| span.content.as_ref())
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let mut scrollback = Scrollback::default();
+        assert_eq!(
+            text(scrollback.rows(lines(), 2, 10, Spans::from("<End>"))),
+            ["two", "three"]
+        );
+
+        scrollback.scroll_up();
+        assert_eq!(
+            text(scrollback.rows(lines(), 2, 10, Spans::from("<End>"))),
+            ["one", "two"]
+        );
+
+        // Scrolling past the oldest line shows the end marker, and steps the offset back.
+        scrollback.scroll_up();
+        assert_eq!(
+            text(scrollback.rows(lines(), 2, 10, Spans::from("<End>"))),
+            ["one", "<End>"]
+        );
+        assert_eq!(scrollback.offset, 1);
+    }
+}
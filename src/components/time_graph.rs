@@ -7,7 +7,7 @@ use tui::{
     style::Style,
     symbols::Marker,
     text::{Span, Spans},
-    widgets::{Block, Borders, GraphType},
+    widgets::{Block, BorderType, Borders, GraphType},
     Frame,
 };
 use unicode_segmentation::UnicodeSegmentation;
@@ -19,6 +19,7 @@ pub struct GraphData<'a> {
     pub points: &'a [Point],
     pub style: Style,
     pub name: Option<Cow<'a, str>>,
+    pub dashed: bool,
 }
 
 pub struct TimeGraph<'a> {
@@ -40,6 +41,9 @@ pub struct TimeGraph<'a> {
     /// The border style.
     pub border_style: Style,
 
+    /// The border type.
+    pub border_type: BorderType,
+
     /// The graph title.
     pub title: Cow<'a, str>,
 
@@ -136,7 +140,8 @@ impl<'a> TimeGraph<'a> {
         let block = Block::default()
             .title(self.generate_title(draw_loc))
             .borders(Borders::ALL)
-            .border_style(self.border_style);
+            .border_style(self.border_style)
+            .border_type(self.border_type);
 
         f.render_widget(
             TimeChart::new(data)
@@ -160,12 +165,14 @@ fn create_dataset<'a>(data: &'a GraphData<'a>) -> Dataset<'a> {
         points,
         style,
         name,
+        dashed,
     } = data;
 
     let dataset = Dataset::default()
         .style(*style)
         .data(points)
-        .graph_type(GraphType::Line);
+        .graph_type(GraphType::Line)
+        .dashed(*dashed);
 
     if let Some(name) = name {
         dataset.name(name.as_ref())
@@ -183,6 +190,7 @@ mod test {
         style::{Color, Style},
         symbols::Marker,
         text::{Span, Spans},
+        widgets::BorderType,
     };
 
     use super::TimeGraph;
@@ -203,6 +211,7 @@ mod test {
             y_labels: &Y_LABELS,
             graph_style: Style::default().fg(Color::Red),
             border_style: Style::default().fg(Color::Blue),
+            border_type: BorderType::Plain,
             is_expanded: false,
             title_style: Style::default().fg(Color::Cyan),
             legend_constraints: None,
@@ -0,0 +1,82 @@
+//! Branch prefixes for drawing a tree inside a table column, like:
+//!
+//! ```text
+//! init
+//! ├─ systemd-journal
+//! ├─ + sshd
+//! └─ login
+//!    └─ bash
+//! ```
+
+use const_format::formatcp;
+
+const BRANCH_END: char = '└';
+const BRANCH_VERTICAL: char = '│';
+const BRANCH_SPLIT: char = '├';
+const BRANCH_HORIZONTAL: char = '─';
+
+/// Tracks the prefixes of each level of a tree while it is walked depth-first.
+#[derive(Default)]
+pub struct TreePrefixes {
+    prefixes: Vec<&'static str>,
+}
+
+impl TreePrefixes {
+    /// Returns the prefix of an entry at the current depth. Collapsed entries get a `+` to show
+    /// that they have hidden children.
+    pub fn entry(&self, is_last: bool, is_collapsed: bool) -> String {
+        let collapsed = if is_collapsed { "+ " } else { "" };
+
+        if self.prefixes.is_empty() {
+            collapsed.to_string()
+        } else {
+            format!(
+                "{}{}{} {collapsed}",
+                self.prefixes.join(""),
+                if is_last { BRANCH_END } else { BRANCH_SPLIT },
+                BRANCH_HORIZONTAL
+            )
+        }
+    }
+
+    /// Descends into the children of the last entry.
+    pub fn push(&mut self, is_last: bool) {
+        if self.prefixes.is_empty() {
+            self.prefixes.push("");
+        } else {
+            self.prefixes.push(if is_last {
+                "   "
+            } else {
+                formatcp!("{}  ", BRANCH_VERTICAL)
+            });
+        }
+    }
+
+    /// Goes back up to the parent level.
+    pub fn pop(&mut self) {
+        self.prefixes.pop();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_tree_prefixes() {
+        let mut prefixes = TreePrefixes::default();
+        assert_eq!(prefixes.entry(true, false), "");
+        assert_eq!(prefixes.entry(true, true), "+ ");
+
+        prefixes.push(true);
+        assert_eq!(prefixes.entry(false, false), "├─ ");
+        assert_eq!(prefixes.entry(false, true), "├─ + ");
+
+        prefixes.push(true);
+        assert_eq!(prefixes.entry(true, false), "   └─ ");
+
+        prefixes.pop();
+        prefixes.push(false);
+        assert_eq!(prefixes.entry(true, false), "│  └─ ");
+    }
+}
@@ -82,6 +82,8 @@ pub struct Dataset<'a> {
     graph_type: GraphType,
     /// Style used to plot this dataset
     style: Style,
+    /// Whether to only draw every other line segment, if drawing a line graph
+    dashed: bool,
 }
 
 impl<'a> Default for Dataset<'a> {
@@ -91,6 +93,7 @@ impl<'a> Default for Dataset<'a> {
             data: &[],
             graph_type: GraphType::Scatter,
             style: Style::default(),
+            dashed: false,
         }
     }
 }
@@ -119,6 +122,11 @@ impl<'a> Dataset<'a> {
         self.style = style;
         self
     }
+
+    pub fn dashed(mut self, dashed: bool) -> Dataset<'a> {
+        self.dashed = dashed;
+        self
+    }
 }
 
 /// A container that holds all the infos about where to display each elements of the chart (axis,
@@ -477,7 +485,14 @@ impl<'a> Widget for TimeChart<'a> {
                     }
 
                     if let GraphType::Line = dataset.graph_type {
-                        for data in data_slice.windows(2) {
+                        for (index, data) in data_slice.windows(2).enumerate() {
+                            // Keep the gaps anchored to the data rather than the slice start, so
+                            // dashes stay put as the graph scrolls. They only shift when old
+                            // points are cleaned from the front of the data.
+                            if dataset.dashed && (start_index + index) % 2 == 1 {
+                                continue;
+                            }
+
                             ctx.draw(&Line {
                                 x1: data[0].0,
                                 y1: data[0].1,
@@ -12,10 +12,33 @@ pub const TIME_CHANGE_MILLISECONDS: u64 = 15 * 1000; // How much to increment ea
 pub const AUTOHIDE_TIMEOUT_MILLISECONDS: u64 = 5000; // 5 seconds to autohide
 
 pub const TICK_RATE_IN_MILLISECONDS: u64 = 200;
+// How often the config file is checked for changes
+pub const CONFIG_POLL_RATE_IN_MILLISECONDS: u64 = 1000;
 // How fast the screen refreshes
 pub const DEFAULT_REFRESH_RATE_IN_MILLISECONDS: u64 = 1000;
+pub const DEFAULT_COLLECTION_TIMEOUT_MILLISECONDS: u64 = 1000;
+pub const DEFAULT_SLOW_REFRESH_RATE_IN_MILLISECONDS: u64 = 5000;
+
+/// The program and arguments terminal widget commands are run with, followed by the command.
+#[cfg(target_family = "unix")]
+pub const DEFAULT_TERMINAL_SHELL: [&str; 2] = ["bash", "-c"];
+#[cfg(target_os = "windows")]
+pub const DEFAULT_TERMINAL_SHELL: [&str; 2] = ["cmd", "/C"];
+
+/// How many past commands each terminal widget remembers by default.
+pub const DEFAULT_TERMINAL_HISTORY_SIZE: usize = 500;
+pub const DEFAULT_LOG_MAX_LINES: usize = 1000;
+
+// When widget borders start blinking to draw attention
+pub const DEFAULT_CPU_ALERT_THRESHOLD: u64 = 90; // Average CPU usage, in percent
+pub const DEFAULT_TEMP_ALERT_THRESHOLD: u64 = 80; // In Celsius
+pub const DEFAULT_DISK_ALERT_THRESHOLD: u64 = 95; // Disk usage, in percent
+pub const DEFAULT_INODE_ALERT_THRESHOLD: u64 = 90; // Inode usage, in percent
 pub const MAX_KEY_TIMEOUT_IN_MILLISECONDS: u64 = 1000;
 
+// How many collection errors are kept around at once
+pub const MAX_COLLECTION_ERRORS: usize = 20;
+
 // Limits for when we should stop showing table gaps/labels (anything less means not shown)
 pub const TABLE_GAP_HEIGHT_LIMIT: u16 = 7;
 pub const TIME_LABEL_HEIGHT_LIMIT: u16 = 7;
@@ -28,6 +51,53 @@ pub static DEFAULT_HEADER_STYLE: Lazy<tui::style::Style> =
     Lazy::new(|| tui::style::Style::default().fg(tui::style::Color::LightBlue));
 
 // Colour profiles
+/// The colours used when no colour scheme is set, as they would be written in the config file.
+pub static DEFAULT_COLOUR_PALETTE: Lazy<ConfigColours> = Lazy::new(|| ConfigColours {
+    table_header_color: Some("LightBlue".into()),
+    all_cpu_color: Some("Green".into()),
+    avg_cpu_color: Some("Red".into()),
+    cpu_core_colors: Some(vec![
+        "LightMagenta".into(),
+        "LightYellow".into(),
+        "LightCyan".into(),
+        "LightGreen".into(),
+        "LightBlue".into(),
+        "Cyan".into(),
+        "Green".into(),
+        "Blue".into(),
+    ]),
+    ram_color: Some("LightMagenta".into()),
+    swap_color: Some("LightYellow".into()),
+    arc_color: Some("LightCyan".into()),
+    gpu_core_colors: Some(vec![
+        "LightGreen".into(),
+        "LightBlue".into(),
+        "LightRed".into(),
+        "Cyan".into(),
+        "Green".into(),
+        "Blue".into(),
+        "Red".into(),
+    ]),
+    rx_color: Some("LightMagenta".into()),
+    tx_color: Some("LightYellow".into()),
+    rx_total_color: Some("LightCyan".into()),
+    tx_total_color: Some("LightGreen".into()),
+    border_color: Some("Gray".into()),
+    highlighted_border_color: Some("LightBlue".into()),
+    disabled_text_color: Some("DarkGray".into()),
+    text_color: Some("Gray".into()),
+    selected_text_color: Some("Black".into()),
+    selected_bg_color: Some("LightBlue".into()),
+    widget_title_color: Some("Gray".into()),
+    graph_color: Some("Gray".into()),
+    high_battery_color: Some("Green".into()),
+    medium_battery_color: Some("Yellow".into()),
+    low_battery_color: Some("Red".into()),
+    low_latency_color: Some("Green".into()),
+    medium_latency_color: Some("Yellow".into()),
+    high_latency_color: Some("Red".into()),
+});
+
 pub static DEFAULT_LIGHT_MODE_COLOUR_PALETTE: Lazy<ConfigColours> = Lazy::new(|| ConfigColours {
     text_color: Some("black".into()),
     border_color: Some("black".into()),
@@ -117,6 +187,9 @@ pub static GRUVBOX_COLOUR_PALETTE: Lazy<ConfigColours> = Lazy::new(|| ConfigColo
     high_battery_color: Some("#98971a".into()),
     medium_battery_color: Some("#fabd2f".into()),
     low_battery_color: Some("#fb4934".into()),
+    low_latency_color: Some("#98971a".into()),
+    medium_latency_color: Some("#fabd2f".into()),
+    high_latency_color: Some("#fb4934".into()),
 });
 
 pub static GRUVBOX_LIGHT_COLOUR_PALETTE: Lazy<ConfigColours> = Lazy::new(|| ConfigColours {
@@ -172,6 +245,9 @@ pub static GRUVBOX_LIGHT_COLOUR_PALETTE: Lazy<ConfigColours> = Lazy::new(|| Conf
     high_battery_color: Some("#98971a".into()),
     medium_battery_color: Some("#d79921".into()),
     low_battery_color: Some("#cc241d".into()),
+    low_latency_color: Some("#98971a".into()),
+    medium_latency_color: Some("#d79921".into()),
+    high_latency_color: Some("#cc241d".into()),
 });
 
 pub static NORD_COLOUR_PALETTE: Lazy<ConfigColours> = Lazy::new(|| ConfigColours {
@@ -215,6 +291,9 @@ pub static NORD_COLOUR_PALETTE: Lazy<ConfigColours> = Lazy::new(|| ConfigColours
     high_battery_color: Some("#a3be8c".into()),
     medium_battery_color: Some("#ebcb8b".into()),
     low_battery_color: Some("#bf616a".into()),
+    low_latency_color: Some("#a3be8c".into()),
+    medium_latency_color: Some("#ebcb8b".into()),
+    high_latency_color: Some("#bf616a".into()),
 });
 
 pub static NORD_LIGHT_COLOUR_PALETTE: Lazy<ConfigColours> = Lazy::new(|| ConfigColours {
@@ -258,6 +337,9 @@ pub static NORD_LIGHT_COLOUR_PALETTE: Lazy<ConfigColours> = Lazy::new(|| ConfigC
     high_battery_color: Some("#a3be8c".into()),
     medium_battery_color: Some("#ebcb8b".into()),
     low_battery_color: Some("#bf616a".into()),
+    low_latency_color: Some("#a3be8c".into()),
+    medium_latency_color: Some("#ebcb8b".into()),
+    high_latency_color: Some("#bf616a".into()),
 });
 
 // Help text
@@ -276,12 +358,16 @@ pub const HELP_CONTENTS_TEXT: [&str; 10] = [
 
 // TODO [Help]: Search in help?
 // TODO [Help]: Move to using tables for easier formatting?
-pub const GENERAL_HELP_TEXT: [&str; 32] = [
+pub const GENERAL_HELP_TEXT: [&str; 37] = [
     "1 - General",
     "q, Ctrl-c        Quit",
     "Esc              Close dialog windows, search, widgets, or exit expanded mode",
     "Ctrl-r           Reset display and any collected data",
     "f                Freeze/unfreeze updating with new data",
+    "F                Freeze/unfreeze only the selected table widget",
+    "Ctrl-e           Show/hide the collection error log",
+    "Ctrl-s           Save a snapshot of all collected data",
+    "Ctrl-o           Edit the layout and save it to the config file",
     "Ctrl-Left,       ",
     "Shift-Left,      Move widget selection left",
     "H, A             ",
@@ -302,6 +388,7 @@ pub const GENERAL_HELP_TEXT: [&str; 32] = [
     "gg               Jump to the first entry",
     "G                Jump to the last entry",
     "e                Toggle expanding the currently selected widget",
+    "Alt-1 to Alt-9   Switch to a layout tab",
     "+                Zoom in on chart (decrease time range)",
     "-                Zoom out on chart (increase time range)",
     "=                Reset zoom",
@@ -316,25 +403,41 @@ pub const CPU_HELP_TEXT: [&str; 2] = [
     "Mouse scroll     Scrolling over an CPU core/average shows only that entry on the chart",
 ];
 
-pub const PROCESS_HELP_TEXT: [&str; 15] = [
+pub const PROCESS_HELP_TEXT: [&str; 31] = [
     "3 - Process widget",
-    "dd, F9           Kill the selected process",
+    "dd, F9           Kill the selected process, or all marked processes",
+    "Space            Mark/unmark the selected process",
+    "Ctrl-a           Mark all processes matching the current search",
+    "Esc              Unmark all marked processes",
     "c                Sort by CPU usage, press again to reverse",
     "m                Sort by memory usage, press again to reverse",
     "p                Sort by PID name, press again to reverse",
     "n                Sort by process name, press again to reverse",
     "Tab              Group/un-group processes with the same name",
+    "a                Aggregate/un-aggregate every instance of the same program into one row",
+    "C                Group/un-group processes by container or systemd unit (Linux only)",
+    "U                Cycle between all users, only your processes and a user picked from a list",
     "Ctrl-f, /        Open process search widget",
     "P                Toggle between showing the full command or just the process name",
     "s, F6            Open process sort widget",
     "I                Invert current sort",
+    "N                Run a new command through the shell",
+    "Enter            Show the command line, environment, open files and sockets of the selected process",
+    "i                Change the I/O priority of the selected process (Linux only)",
+    "F7               Lower the nice value of the selected process, raising its priority (Unix only)",
+    "F8, Shift-F7     Raise the nice value of the selected process, lowering its priority (Unix only)",
+    "z                Stop the selected process with SIGSTOP, pausing it (Unix only)",
+    "Z                Continue the selected process with SIGCONT (Unix only)",
+    "M                Show the memory map of the selected process (Unix only)",
+    "x                Save the table as it is shown to a CSV file",
+    "X                Save the table as it is shown to a JSON file",
     "%                Toggle between values and percentages for memory usage",
     "t, F5            Toggle tree mode",
     "+, -, click      Collapse/expand a branch while in tree mode",
     "click on header  Sorts the entries by that column, click again to invert the sort",
 ];
 
-pub const SEARCH_HELP_TEXT: [&str; 48] = [
+pub const SEARCH_HELP_TEXT: [&str; 49] = [
     "4 - Process search widget",
     "Esc              Close the search widget (retains the filter)",
     "Ctrl-a           Skip to the start of the search query",
@@ -347,6 +450,7 @@ pub const SEARCH_HELP_TEXT: [&str; 48] = [
     "Alt-c, F1        Toggle matching case",
     "Alt-w, F2        Toggle matching the entire word",
     "Alt-r, F3        Toggle using regex",
+    "Alt-a, F4        Toggle also matching the full command line",
     "Left, Alt-h      Move cursor left",
     "Right, Alt-l     Move cursor right",
     "",
@@ -493,199 +597,18 @@ pub const DEFAULT_BATTERY_LAYOUT: &str = r##"
 
 // Config and flags
 pub const DEFAULT_CONFIG_FILE_PATH: &str = "bottom/bottom.toml";
+pub const DEFAULT_UPTIME_STREAK_FILE: &str = "bottom/days";
+
+/// The uptimes, in days, at which the uptime widget's border blinks by default.
+pub const DEFAULT_UPTIME_MILESTONES: [u64; 3] = [30, 100, 365];
+
+/// How long hostnames in the connections widget are cached for, in seconds.
+pub const DEFAULT_HOSTNAME_TTL_SECS: u64 = 300;
 
 // TODO: Eventually deprecate this.
-pub const CONFIG_TEXT: &str = r##"# This is a default config file for bottom.  All of the settings are commented
+pub const CONFIG_TOP_HEAD: &str = r##"# This is a default config file for bottom.  All of the settings are commented
 # out by default; if you wish to change them uncomment and modify as you see
 # fit.
-
-# This group of options represents a command-line flag/option.  Flags explicitly
-# added when running (ie: btm -a) will override this config file if an option
-# is also set here.
-
-[flags]
-# Whether to hide the average cpu entry.
-#hide_avg_cpu = false
-# Whether to use dot markers rather than braille.
-#dot_marker = false
-# The update rate of the application.
-#rate = 1000
-# Whether to put the CPU legend to the left.
-#left_legend = false
-# Whether to set CPU% on a process to be based on the total CPU or just current usage.
-#current_usage = false
-# Whether to set CPU% on a process to be based on the total CPU or per-core CPU% (not divided by the number of cpus).
-#unnormalized_cpu = false
-# Whether to group processes with the same name together by default.
-#group_processes = false
-# Whether to make process searching case sensitive by default.
-#case_sensitive = false
-# Whether to make process searching look for matching the entire word by default.
-#whole_word = false
-# Whether to make process searching use regex by default.
-#regex = false
-# Defaults to Celsius.  Temperature is one of:
-#temperature_type = "k"
-#temperature_type = "f"
-#temperature_type = "c"
-#temperature_type = "kelvin"
-#temperature_type = "fahrenheit"
-#temperature_type = "celsius"
-# The default time interval (in milliseconds).
-#default_time_value = 60000
-# The time delta on each zoom in/out action (in milliseconds).
-#time_delta = 15000
-# Hides the time scale.
-#hide_time = false
-# Override layout default widget
-#default_widget_type = "proc"
-#default_widget_count = 1
-# Expand selected widget upon starting the app
-#expanded_on_startup = true
-# Use basic mode
-#basic = false
-# Use the old network legend style
-#use_old_network_legend = false
-# Remove space in tables
-#hide_table_gap = false
-# Show the battery widgets
-#battery = false
-# Disable mouse clicks
-#disable_click = false
-# Built-in themes.  Valid values are "default", "default-light", "gruvbox", "gruvbox-light", "nord", "nord-light"
-#color = "default"
-# Show memory values in the processes widget as values by default
-#mem_as_value = false
-# Show tree mode by default in the processes widget.
-#tree = false
-# Shows an indicator in table widgets tracking where in the list you are.
-#show_table_scroll_position = false
-# Show processes as their commands by default in the process widget.
-#process_command = false
-# Displays the network widget with binary prefixes.
-#network_use_binary_prefix = false
-# Displays the network widget using bytes.
-#network_use_bytes = false
-# Displays the network widget with a log scale.
-#network_use_log = false
-# Hides advanced options to stop a process on Unix-like systems.
-#disable_advanced_kill = false
-# Shows GPU(s) memory
-#enable_gpu_memory = false
-# How much data is stored at once in terms of time.
-#retention = "10m"
-
-# These are all the components that support custom theming.  Note that colour support
-# will depend on terminal support.
-
-#[colors] # Uncomment if you want to use custom colors
-# Represents the colour of table headers (processes, CPU, disks, temperature).
-#table_header_color="LightBlue"
-# Represents the colour of the label each widget has.
-#widget_title_color="Gray"
-# Represents the average CPU color.
-#avg_cpu_color="Red"
-# Represents the colour the core will use in the CPU legend and graph.
-#cpu_core_colors=["LightMagenta", "LightYellow", "LightCyan", "LightGreen", "LightBlue", "LightRed", "Cyan", "Green", "Blue", "Red"]
-# Represents the colour RAM will use in the memory legend and graph.
-#ram_color="LightMagenta"
-# Represents the colour SWAP will use in the memory legend and graph.
-#swap_color="LightYellow"
-# Represents the colour ARC will use in the memory legend and graph.
-#arc_color="LightCyan"
-# Represents the colour the GPU will use in the memory legend and graph.
-#gpu_core_colors=["LightGreen", "LightBlue", "LightRed", "Cyan", "Green", "Blue", "Red"]
-# Represents the colour rx will use in the network legend and graph.
-#rx_color="LightCyan"
-# Represents the colour tx will use in the network legend and graph.
-#tx_color="LightGreen"
-# Represents the colour of the border of unselected widgets.
-#border_color="Gray"
-# Represents the colour of the border of selected widgets.
-#highlighted_border_color="LightBlue"
-# Represents the colour of most text.
-#text_color="Gray"
-# Represents the colour of text that is selected.
-#selected_text_color="Black"
-# Represents the background colour of text that is selected.
-#selected_bg_color="LightBlue"
-# Represents the colour of the lines and text of the graph.
-#graph_color="Gray"
-# Represents the colours of the battery based on charge
-#high_battery_color="green"
-#medium_battery_color="yellow"
-#low_battery_color="red"
-
-# Layout - layouts follow a pattern like this:
-# [[row]] represents a row in the application.
-# [[row.child]] represents either a widget or a column.
-# [[row.child.child]] represents a widget.
-#
-# All widgets must have the type value set to one of ["cpu", "mem", "proc", "net", "temp", "disk", "empty"].
-# All layout components have a ratio value - if this is not set, then it defaults to 1.
-# The default widget layout:
-#[[row]]
-#  ratio=30
-#  [[row.child]]
-#  type="cpu"
-#[[row]]
-#    ratio=40
-#    [[row.child]]
-#      ratio=4
-#      type="mem"
-#    [[row.child]]
-#      ratio=3
-#      [[row.child.child]]
-#        type="temp"
-#      [[row.child.child]]
-#        type="disk"
-#[[row]]
-#  ratio=30
-#  [[row.child]]
-#    type="net"
-#  [[row.child]]
-#    type="proc"
-#    default=true
-
-
-# Filters - you can hide specific temperature sensors, network interfaces, and disks using filters.  This is admittedly
-# a bit hard to use as of now, and there is a planned in-app interface for managing this in the future:
-#[disk_filter]
-#is_list_ignored = true
-#list = ["/dev/sda\\d+", "/dev/nvme0n1p2"]
-#regex = true
-#case_sensitive = false
-#whole_word = false
-
-#[mount_filter]
-#is_list_ignored = true
-#list = ["/mnt/.*", "/boot"]
-#regex = true
-#case_sensitive = false
-#whole_word = false
-
-#[temp_filter]
-#is_list_ignored = true
-#list = ["cpu", "wifi"]
-#regex = false
-#case_sensitive = false
-#whole_word = false
-
-#[net_filter]
-#is_list_ignored = true
-#list = ["virbr0.*"]
-#regex = true
-#case_sensitive = false
-#whole_word = false
-"##;
-
-pub const CONFIG_TOP_HEAD: &str = r##"# This is bottom's config file.
-# Values in this config file will change when changed in the interface.
-# You can also manually change these values.
-# Be aware that contents of this file will be overwritten if something is
-# changed in the application; you can disable writing via the
-# --no_write flag or no_write config option.
-
 "##;
 
 pub const CONFIG_DISPLAY_OPTIONS_HEAD: &str = r##"
@@ -697,7 +620,8 @@ pub const CONFIG_DISPLAY_OPTIONS_HEAD: &str = r##"
 pub const CONFIG_COLOUR_HEAD: &str = r##"
 # These options represent colour values for various parts of bottom.  Note that colour support
 # will ultimately depend on the terminal - for example, the Terminal for macOS does NOT like
-# custom colours and it may glitch out.
+# custom colours and it may glitch out.  Colours can be a name, a hex code ("#ff0000"), or an
+# RGB value ("255, 0, 0").
 "##;
 
 pub const CONFIG_LAYOUT_HEAD: &str = r##"
@@ -708,10 +632,23 @@ pub const CONFIG_LAYOUT_HEAD: &str = r##"
 #
 # All widgets must have the valid type value set to one of ["cpu", "mem", "proc", "net", "temp", "disk", "empty"].
 # All layout components have a ratio value - if this is not set, then it defaults to 1.
+# The default widget layout:
+"##;
+
+pub const CONFIG_TERMINAL_HEAD: &str = r##"
+# These options represent how the terminal widget runs commands. The shell is a list of the program
+# and its arguments, with the command to run added to the end.
+"##;
+
+pub const CONFIG_KEYBINDINGS_HEAD: &str = r##"
+# These options represent which keys do what, for the actions that can be bound to other keys. Each
+# action is bound to a list of keys like "q", "F9", "ctrl-left" or "shift-alt-up", replacing its
+# default keys, and an empty list unbinds it.
 "##;
 
 pub const CONFIG_FILTER_HEAD: &str = r##"
-# These options represent disabled entries for the temperature and disk widgets.
+# These options represent hidden entries for the disk, temperature and network widgets.  Each filter
+# is a list of patterns to hide, or to only show if is_list_ignored is false.
 "##;
 
 #[cfg(test)]
@@ -1,21 +1,30 @@
 //! This mainly concerns converting collected data into things that the canvas
 //! can actually handle.
 
-use std::process::Command;
+use std::{collections::HashMap, net::SocketAddr, time::Duration};
 
 use kstring::KString;
 
 use crate::components::tui_widget::time_chart::Point;
 use crate::units::data_units::DataUnit;
 use crate::utils::gen_util::*;
-use crate::widgets::{DiskWidgetData, TempWidgetData};
+use crate::widgets::{DiskWidgetData, TempRowKind, TempWidgetData};
 use crate::{
     app::{
         data_farmer::DataCollection,
-        data_harvester::{cpu::CpuDataType, memory::MemHarvest, temperature::TemperatureType},
-        AxisScaling,
+        data_harvester::{
+            cpu::{CpuDataType, CpuFrequency},
+            memory::MemHarvest,
+            network::{hostnames::HostnameResolver, NetlinkSocketInfo, WifiInfo},
+            pressure::PressureResource,
+            temperature::TemperatureType,
+        },
+        AppConfigFields, AxisScaling,
+    },
+    widgets::{
+        CgroupWidgetData, ConnectionsWidgetData, ContainerWidgetData, RaidWidgetData,
+        ScheduledJobWidgetData, ZfsPoolWidgetData,
     },
-    widgets::ConnectionsWidgetData,
 };
 
 #[derive(Debug)]
@@ -65,6 +74,7 @@ pub enum CpuWidgetData {
         /// A point here represents time (x) and value (y).
         data: Vec<Point>,
         last_entry: f64,
+        frequency: Option<CpuFrequency>,
     },
 }
 
@@ -97,17 +107,35 @@ pub struct ConvertedData {
     pub disk_data: Vec<DiskWidgetData>,
     pub temp_data: Vec<TempWidgetData>,
     pub connections_data: Vec<ConnectionsWidgetData>,
+    pub container_data: Vec<ContainerWidgetData>,
+    pub raid_data: Vec<RaidWidgetData>,
+    pub zfs_pool_data: Vec<ZfsPoolWidgetData>,
+    pub cgroup_data: Vec<CgroupWidgetData>,
+    pub scheduled_job_data: Vec<ScheduledJobWidgetData>,
+    pub pressure_data: Vec<ConvertedPressureData>,
+    pub wifi_data: Vec<ConvertedWifiData>,
+    /// Looks up the hostnames of remote addresses, if enabled.
+    pub hostname_resolver: Option<HostnameResolver>,
+    /// Looks up where remote addresses are, if any databases are set.
+    #[cfg(feature = "geoip")]
+    pub geoip: Option<crate::app::data_harvester::network::geoip::GeoIpLookup>,
 }
 
 impl ConvertedData {
     // TODO: Can probably heavily reduce this step to avoid clones.
-    pub fn ingest_disk_data(&mut self, data: &DataCollection) {
+    pub fn ingest_disk_data(&mut self, data: &DataCollection, inode_alert_threshold: u64) {
         self.disk_data.clear();
 
         data.disk_harvest
             .iter()
             .zip(&data.io_labels)
-            .for_each(|(disk, (io_read, io_write))| {
+            .enumerate()
+            .for_each(|(itx, (disk, (io_read, io_write)))| {
+                let ((read_latency_ms, write_latency_ms), _) = data
+                    .io_latencies_and_prev
+                    .get(itx)
+                    .copied()
+                    .unwrap_or_default();
                 let summed_total_bytes = match (disk.used_space, disk.free_space) {
                     (Some(used), Some(free)) => Some(used + free),
                     _ => None,
@@ -122,6 +150,16 @@ impl ConvertedData {
                     summed_total_bytes,
                     io_read: io_read.into(),
                     io_write: io_write.into(),
+                    read_latency_ms,
+                    write_latency_ms,
+                    mount_type: disk.mount_type,
+                    stale: disk.stale,
+                    io_scheduler: disk.io_scheduler.clone(),
+                    used_inodes: disk.used_inodes,
+                    total_inodes: disk.total_inodes,
+                    inode_alert: disk
+                        .inode_usage_percent()
+                        .is_some_and(|percent| percent > inode_alert_threshold as f64),
                 });
             });
 
@@ -134,44 +172,206 @@ impl ConvertedData {
         data.temp_harvest.iter().for_each(|temp_harvest| {
             self.temp_data.push(TempWidgetData {
                 sensor: KString::from_ref(&temp_harvest.name),
-                temperature_value: temp_harvest.temperature.ceil() as u64,
+                value: temp_harvest.value,
+                sensor_type: temp_harvest.sensor_type,
                 temperature_type,
+                kind: TempRowKind::Sensor,
             });
         });
 
         self.temp_data.shrink_to_fit();
     }
 
-    pub fn ingest_connections_data(&mut self) {
+    pub fn ingest_container_data(&mut self, data: &DataCollection) {
+        self.container_data.clear();
+
+        self.container_data
+            .extend(
+                data.container_harvest
+                    .iter()
+                    .map(|container| ContainerWidgetData {
+                        name: container.name.clone(),
+                        image: container.image.clone(),
+                        status: container.status.clone(),
+                        is_running: container.is_running(),
+                        cpu_percent: container.cpu_percent,
+                        mem_bytes: container.mem_bytes,
+                        rx_bytes_per_sec: container.rx_bytes_per_sec,
+                        tx_bytes_per_sec: container.tx_bytes_per_sec,
+                    }),
+            );
+
+        self.container_data.shrink_to_fit();
+    }
+
+    pub fn ingest_raid_data(&mut self, data: &DataCollection) {
+        self.raid_data.clear();
+
+        self.raid_data
+            .extend(data.raid_harvest.iter().map(|array| RaidWidgetData {
+                name: array.name.clone(),
+                level: array.level.clone(),
+                state: array.state.clone(),
+                members: array.members.clone(),
+                total_devices: array.total_devices,
+                working_devices: array.working_devices,
+                sync: array.sync.clone(),
+                is_degraded: array.is_degraded(),
+            }));
+
+        self.raid_data.shrink_to_fit();
+    }
+
+    pub fn ingest_zfs_pool_data(&mut self, data: &DataCollection) {
+        self.zfs_pool_data.clear();
+
+        self.zfs_pool_data
+            .extend(data.zfs_pool_harvest.iter().map(|pool| ZfsPoolWidgetData {
+                name: pool.name.clone(),
+                health: pool.health.clone(),
+                size: pool.size,
+                allocated: pool.allocated,
+                free: pool.free,
+                fragmentation: pool.fragmentation,
+                capacity: pool.capacity,
+                scan: pool.scan.clone(),
+                is_unhealthy: pool.is_unhealthy(),
+            }));
+
+        self.zfs_pool_data.shrink_to_fit();
+    }
+
+    pub fn ingest_cgroup_data(&mut self, data: &DataCollection) {
+        self.cgroup_data.clear();
+
+        self.cgroup_data
+            .extend(data.cgroup_harvest.iter().map(|cgroup| {
+                CgroupWidgetData::new(
+                    cgroup.path.clone(),
+                    cgroup.name().to_string(),
+                    cgroup.parent_path().map(str::to_string),
+                    cgroup.cpu_percent,
+                    cgroup.mem_bytes,
+                    cgroup.mem_limit_bytes,
+                    cgroup.tasks,
+                )
+            }));
+
+        self.cgroup_data.shrink_to_fit();
+    }
+
+    pub fn ingest_scheduled_job_data(&mut self, data: &DataCollection) {
+        self.scheduled_job_data.clear();
+
+        self.scheduled_job_data
+            .extend(
+                data.scheduled_job_harvest
+                    .iter()
+                    .map(|job| ScheduledJobWidgetData {
+                        kind: job.kind,
+                        schedule: job.schedule.clone(),
+                        command: job.command.clone(),
+                        user: job.user.clone(),
+                        next_run: job.next_run,
+                        last_run: job.last_run,
+                        last_result: job.last_result.clone(),
+                        has_failed: job.has_failed(),
+                    }),
+            );
+
+        self.scheduled_job_data.shrink_to_fit();
+    }
+
+    pub fn ingest_connections_data(&mut self, data: &DataCollection, config: &AppConfigFields) {
         self.connections_data.clear();
-        let output = String::from_utf8(
-            Command::new("netstat")
-                .args(["-a", "-t", "-u", "-n", "-p", "-4"])
-                .output()
-                .unwrap()
-                .stdout,
-        )
-        .unwrap();
-        for line in output.lines().skip(2) {
-            let mut fields = line.split_ascii_whitespace().skip(3);
-            let local_address = fields.next().unwrap().to_string();
-            let remote_address = fields.next().unwrap().to_string();
-            let mut status = fields.next().unwrap().to_string();
-            let name = match fields.next() {
-                Some(name) => name.to_string(),
-                None => {
-                    let name = status;
-                    status = String::from("UDP");
-                    name
-                }
-            };
-            self.connections_data.push(ConnectionsWidgetData {
-                name,
-                local_address,
-                remote_address,
-                status,
-            })
+
+        let connection_stats: HashMap<(SocketAddr, SocketAddr), &NetlinkSocketInfo> = data
+            .connection_stats
+            .iter()
+            .map(|stats| ((stats.local, stats.remote), stats))
+            .collect();
+
+        let normalise = |address: SocketAddr| {
+            if config.collapse_ipv4_mapped {
+                collapse_ipv4_mapped(address)
+            } else {
+                address
+            }
+        };
+        if config.resolve_hostnames && self.hostname_resolver.is_none() {
+            self.hostname_resolver = Some(HostnameResolver::new(Duration::from_secs(
+                config.hostname_ttl_secs,
+            )));
         }
+        let hostname_resolver = self
+            .hostname_resolver
+            .as_ref()
+            .filter(|_| config.resolve_hostnames);
+        #[cfg(feature = "geoip")]
+        let geoip = {
+            use crate::app::data_harvester::network::geoip::GeoIpLookup;
+
+            if self.geoip.is_none()
+                && (config.geoip_country_db.is_some() || config.geoip_asn_db.is_some())
+            {
+                self.geoip = Some(GeoIpLookup::open(
+                    config.geoip_country_db.as_deref(),
+                    config.geoip_asn_db.as_deref(),
+                ));
+            }
+            self.geoip.as_ref()
+        };
+        let format_address = |address: SocketAddr| {
+            abbreviate_address(&normalise(address).to_string(), config.max_address_length)
+        };
+        // Until its hostname has been looked up, a remote address is shown as it is.
+        let format_remote_address = |address: SocketAddr| {
+            let address = normalise(address);
+            match hostname_resolver
+                .filter(|_| !address.ip().is_unspecified())
+                .and_then(|resolver| resolver.hostname(address.ip()))
+            {
+                Some(hostname) => abbreviate_address(
+                    &format!("{}:{}", hostname, address.port()),
+                    config.max_address_length,
+                ),
+                None => format_address(address),
+            }
+        };
+
+        self.connections_data.extend(
+            data.connection_harvest
+                .iter()
+                .filter(|connection| {
+                    !(config.hide_ipv6_connections && normalise(connection.local).is_ipv6())
+                })
+                .map(|connection| {
+                    let stats = connection_stats.get(&(connection.local, connection.remote));
+
+                    ConnectionsWidgetData {
+                        name: match (connection.pid, &connection.name) {
+                            (Some(pid), Some(name)) => format!("{pid}/{name}"),
+                            (Some(pid), None) => pid.to_string(),
+                            _ => "-".to_string(),
+                        },
+                        pid: connection.pid,
+                        local: connection.local,
+                        remote: connection.remote,
+                        local_address: format_address(connection.local),
+                        remote_address: format_remote_address(connection.remote),
+                        protocol: connection.protocol,
+                        status: connection.state.clone(),
+                        rtt_ms: stats.map(|stats| stats.rtt_ms),
+                        retransmits: stats.map(|stats| stats.retransmits),
+                        #[cfg(feature = "geoip")]
+                        location: geoip
+                            .filter(|_| !connection.remote.ip().is_unspecified())
+                            .and_then(|geoip| geoip.location(normalise(connection.remote).ip())),
+                        rx_per_sec: stats.and_then(|stats| stats.rx_per_sec),
+                        tx_per_sec: stats.and_then(|stats| stats.tx_per_sec),
+                    }
+                }),
+        );
     }
 
     pub fn ingest_cpu_data(&mut self, current_data: &DataCollection) {
@@ -190,6 +390,7 @@ impl ConvertedData {
                             data_type: data.data_type,
                             data: vec![],
                             last_entry: *cpu_usage,
+                            frequency: data.frequency.clone(),
                         })
                         .collect::<Vec<CpuWidgetData>>(),
                 );
@@ -197,17 +398,19 @@ impl ConvertedData {
                 self.cpu_data
                     .iter_mut()
                     .skip(1)
-                    .zip(&data.cpu_data)
-                    .for_each(|(mut cpu, cpu_usage)| match &mut cpu {
+                    .zip(data.cpu_data.iter().zip(&current_data.cpu_harvest))
+                    .for_each(|(mut cpu, (cpu_usage, harvest))| match &mut cpu {
                         CpuWidgetData::All => unreachable!(),
                         CpuWidgetData::Entry {
                             data_type: _,
                             data,
                             last_entry,
+                            frequency,
                         } => {
                             // A bit faster to just update all the times, so we just clear the vector.
                             data.clear();
                             *last_entry = *cpu_usage;
+                            *frequency = harvest.frequency.clone();
                         }
                     });
             }
@@ -218,11 +421,7 @@ impl ConvertedData {
         for (itx, mut cpu) in &mut self.cpu_data.iter_mut().skip(1).enumerate() {
             match &mut cpu {
                 CpuWidgetData::All => unreachable!(),
-                CpuWidgetData::Entry {
-                    data_type: _,
-                    data,
-                    last_entry: _,
-                } => {
+                CpuWidgetData::Entry { data, .. } => {
                     for (time, timed_data) in &current_data.timed_data_vec {
                         let time_start: f64 =
                             (current_time.duration_since(*time).as_millis() as f64).floor();
@@ -325,30 +524,15 @@ pub fn get_rx_tx_data_points(
     for (time, data) in &current_data.timed_data_vec {
         let time_from_start: f64 = (current_time.duration_since(*time).as_millis() as f64).floor();
 
-        let (rx_data, tx_data) = match network_scale_type {
-            AxisScaling::Log => {
-                if network_use_binary_prefix {
-                    match network_unit_type {
-                        DataUnit::Byte => {
-                            // As dividing by 8 is equal to subtracting 4 in base 2!
-                            ((data.rx_data).log2() - 4.0, (data.tx_data).log2() - 4.0)
-                        }
-                        DataUnit::Bit => ((data.rx_data).log2(), (data.tx_data).log2()),
-                    }
-                } else {
-                    match network_unit_type {
-                        DataUnit::Byte => {
-                            ((data.rx_data / 8.0).log10(), (data.tx_data / 8.0).log10())
-                        }
-                        DataUnit::Bit => ((data.rx_data).log10(), (data.tx_data).log10()),
-                    }
-                }
-            }
-            AxisScaling::Linear => match network_unit_type {
-                DataUnit::Byte => (data.rx_data / 8.0, data.tx_data / 8.0),
-                DataUnit::Bit => (data.rx_data, data.tx_data),
-            },
+        let scale = |bits_per_second| {
+            scale_network_rate(
+                bits_per_second,
+                network_scale_type,
+                network_unit_type,
+                network_use_binary_prefix,
+            )
         };
+        let (rx_data, tx_data) = (scale(data.rx_data), scale(data.tx_data));
 
         rx.push((-time_from_start, rx_data));
         tx.push((-time_from_start, tx_data));
@@ -360,6 +544,52 @@ pub fn get_rx_tx_data_points(
     (rx, tx)
 }
 
+/// Scales a network rate, in bits per second, to the units used on the network graph's y-axis.
+pub fn scale_network_rate(
+    bits_per_second: f64, network_scale_type: &AxisScaling, network_unit_type: &DataUnit,
+    network_use_binary_prefix: bool,
+) -> f64 {
+    match network_scale_type {
+        AxisScaling::Log => {
+            if network_use_binary_prefix {
+                match network_unit_type {
+                    DataUnit::Byte => {
+                        // As dividing by 8 is equal to subtracting 4 in base 2!
+                        bits_per_second.log2() - 4.0
+                    }
+                    DataUnit::Bit => bits_per_second.log2(),
+                }
+            } else {
+                match network_unit_type {
+                    DataUnit::Byte => (bits_per_second / 8.0).log10(),
+                    DataUnit::Bit => bits_per_second.log10(),
+                }
+            }
+        }
+        AxisScaling::Linear => match network_unit_type {
+            DataUnit::Byte => bits_per_second / 8.0,
+            DataUnit::Bit => bits_per_second,
+        },
+    }
+}
+
+/// Formats a network rate, in bits per second, for display, e.g. "842.0MB/s".
+pub fn network_rate_string(
+    bits_per_second: f64, network_unit_type: &DataUnit, network_use_binary_prefix: bool,
+) -> String {
+    let (quantity, unit) = match network_unit_type {
+        DataUnit::Byte => (bits_per_second as u64 / 8, "B/s"),
+        DataUnit::Bit => (bits_per_second as u64, "b/s"),
+    };
+    let (value, unit) = if network_use_binary_prefix {
+        get_binary_prefix(quantity, unit)
+    } else {
+        get_decimal_prefix(quantity, unit)
+    };
+
+    format!("{:.1}{}", value, unit)
+}
+
 pub fn convert_network_data_points(
     current_data: &DataCollection, need_four_points: bool, network_scale_type: &AxisScaling,
     network_unit_type: &DataUnit, network_use_binary_prefix: bool,
@@ -619,6 +849,99 @@ pub fn convert_arc_data_points(
     result
 }
 
+#[derive(Debug)]
+pub struct ConvertedPressureData {
+    pub resource: PressureResource,
+    /// Whether this is the share of time all tasks were stalled, rather than some.
+    pub is_full: bool,
+    pub points: Vec<Point>,
+    pub latest: f64,
+}
+
+/// Returns the some and full stall percentages of each resource over time, skipping any the
+/// kernel doesn't report.
+pub fn convert_pressure_data_points(
+    current_data: &crate::app::data_farmer::DataCollection,
+) -> Vec<ConvertedPressureData> {
+    let current_time = current_data.current_instant;
+
+    PressureResource::ALL
+        .into_iter()
+        .flat_map(|resource| [(resource, false), (resource, true)])
+        .filter_map(|(resource, is_full)| {
+            let mut points: Vec<Point> = Vec::new();
+            for (time, data) in &current_data.timed_data_vec {
+                let pressure = data.pressure_data.and_then(|harvest| harvest.get(resource));
+                if let Some(value) = pressure.and_then(|pressure| {
+                    if is_full {
+                        pressure.full
+                    } else {
+                        Some(pressure.some)
+                    }
+                }) {
+                    let time_from_start: f64 =
+                        (current_time.duration_since(*time).as_millis() as f64).floor();
+                    points.push((-time_from_start, value));
+                }
+
+                if *time == current_time {
+                    break;
+                }
+            }
+
+            let latest = points.last()?.1;
+            Some(ConvertedPressureData {
+                resource,
+                is_full,
+                points,
+                latest,
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug)]
+pub struct ConvertedWifiData {
+    pub name: String,
+    /// The signal level over time, in dBm.
+    pub points: Vec<Point>,
+    pub info: WifiInfo,
+}
+
+/// Returns the signal level of each connected wireless interface over time, ordered by name.
+pub fn convert_wifi_data_points(
+    current_data: &crate::app::data_farmer::DataCollection,
+) -> Vec<ConvertedWifiData> {
+    let current_time = current_data.current_instant;
+    let wifi_info = &current_data.network_harvest.wifi_info;
+    let mut names: Vec<&String> = wifi_info.keys().collect();
+    names.sort();
+
+    names
+        .into_iter()
+        .map(|name| {
+            let mut points: Vec<Point> = Vec::new();
+            for (time, data) in &current_data.timed_data_vec {
+                if let Some(signal) = data.wifi_data.get(name) {
+                    let time_from_start: f64 =
+                        (current_time.duration_since(*time).as_millis() as f64).floor();
+                    points.push((-time_from_start, *signal));
+                }
+
+                if *time == current_time {
+                    break;
+                }
+            }
+
+            ConvertedWifiData {
+                name: name.clone(),
+                points,
+                info: wifi_info[name].clone(),
+            }
+        })
+        .collect()
+}
+
 #[cfg(feature = "gpu")]
 #[derive(Default, Debug)]
 pub struct ConvertedGpuData {
@@ -710,10 +1033,86 @@ pub fn convert_gpu_data(
     }
 }
 
+/// Turns an IPv4-mapped IPv6 address, like `[::ffff:10.0.0.1]:80`, into the IPv4 address it
+/// stands for. Any other address is left alone.
+fn collapse_ipv4_mapped(address: SocketAddr) -> SocketAddr {
+    match address {
+        SocketAddr::V6(v6) => match v6.ip().to_ipv4_mapped() {
+            Some(ipv4) => SocketAddr::from((ipv4, v6.port())),
+            None => address,
+        },
+        SocketAddr::V4(_) => address,
+    }
+}
+
+/// Shortens an address longer than `max_length` characters by cutting out the middle of the IP,
+/// so the port stays readable. A `max_length` of 0 means there is no limit.
+fn abbreviate_address(address: &str, max_length: usize) -> String {
+    let length = address.chars().count();
+    if max_length == 0 || length <= max_length {
+        return address.to_string();
+    }
+
+    let (ip, port) = match address.rfind(':') {
+        Some(index) => address.split_at(index),
+        None => (address, ""),
+    };
+    let ip: Vec<char> = ip.chars().collect();
+
+    // Room for the ellipsis, and whatever is left of the IP around it.
+    let Some(kept) = max_length
+        .checked_sub(port.chars().count() + 1)
+        .filter(|kept| *kept >= 2)
+    else {
+        return ip.iter().take(max_length).collect();
+    };
+    let head = kept - kept / 2;
+    let tail = kept / 2;
+
+    format!(
+        "{}…{}{}",
+        ip[..head].iter().collect::<String>(),
+        ip[ip.len() - tail..].iter().collect::<String>(),
+        port
+    )
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
+    #[test]
+    fn test_scale_network_rate() {
+        let linear = AxisScaling::Linear;
+        let log = AxisScaling::Log;
+
+        assert_eq!(
+            scale_network_rate(800.0, &linear, &DataUnit::Byte, false),
+            100.0
+        );
+        assert_eq!(
+            scale_network_rate(800.0, &linear, &DataUnit::Bit, false),
+            800.0
+        );
+        assert_eq!(
+            scale_network_rate(8000.0, &log, &DataUnit::Byte, false),
+            3.0
+        );
+        assert_eq!(scale_network_rate(8192.0, &log, &DataUnit::Byte, true), 9.0);
+    }
+
+    #[test]
+    fn test_network_rate_string() {
+        assert_eq!(
+            network_rate_string(8_000_000.0, &DataUnit::Byte, false),
+            "1.0MB/s".to_string()
+        );
+        assert_eq!(
+            network_rate_string(2048.0, &DataUnit::Bit, true),
+            "2.0Kib/s".to_string()
+        );
+    }
+
     #[test]
     fn test_binary_byte_string() {
         assert_eq!(binary_byte_string(0), "0B".to_string());
@@ -773,4 +1172,22 @@ mod test {
             "10.4TB/s".to_string()
         );
     }
+
+    #[test]
+    fn test_address_formatting() {
+        assert_eq!(
+            collapse_ipv4_mapped("[::ffff:10.0.0.1]:80".parse().unwrap()),
+            "10.0.0.1:80".parse().unwrap()
+        );
+        assert_eq!(
+            collapse_ipv4_mapped("[::1]:80".parse().unwrap()),
+            "[::1]:80".parse().unwrap()
+        );
+
+        let address = "[2001:db8:85a3::8a2e:370:7334]:443";
+        assert_eq!(abbreviate_address(address, 0), address);
+        assert_eq!(abbreviate_address(address, 40), address);
+        assert_eq!(abbreviate_address(address, 16), "[2001:…7334]:443");
+        assert_eq!(abbreviate_address(address, 5), "[2001");
+    }
 }
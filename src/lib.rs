@@ -18,9 +18,10 @@ extern crate log;
 use std::{
     boxed::Box,
     fs,
-    io::{stderr, stdout, Read, Write},
+    io::{stderr, stdout, Write},
+    panic::{self, AssertUnwindSafe},
     path::PathBuf,
-    process::{Command, Stdio},
+    process::ExitStatus,
     sync::Mutex,
     sync::{
         mpsc::{Receiver, Sender},
@@ -33,6 +34,7 @@ use std::{
 use app::{
     data_harvester,
     frozen_state::FrozenState,
+    key_bindings::Action,
     layout_manager::{UsedWidgets, WidgetDirection},
     App,
 };
@@ -48,12 +50,13 @@ use crossterm::{
 use data_conversion::*;
 use options::*;
 use utils::error;
-use widgets::UnsafeTerminalWidgetState;
+use widgets::TerminalCommandExecutor;
 
 pub mod app;
 pub mod utils {
     pub mod error;
     pub mod gen_util;
+    pub mod json;
     pub mod logging;
 }
 pub mod canvas;
@@ -62,9 +65,15 @@ pub mod components;
 pub mod constants;
 pub mod data_conversion;
 pub mod options;
+pub mod remote;
+mod runner;
+pub mod server;
+pub mod sinks;
 pub mod units;
 pub mod widgets;
 
+pub use runner::run;
+
 #[cfg(target_family = "windows")]
 pub type Pid = usize;
 
@@ -78,7 +87,44 @@ pub enum BottomEvent {
     MouseInput(MouseEvent),
     PasteEvent(String),
     Update(Box<data_harvester::Data>),
+    CollectionError(data_harvester::CollectionError),
     Clean,
+    /// The config file was written to, so it should be read again.
+    ConfigChanged,
+    /// Output from a terminal widget's running command.
+    TerminalOutput {
+        widget_id: u64,
+        chunk: Vec<u8>,
+    },
+    /// A terminal widget's command started.
+    TerminalStarted {
+        widget_id: u64,
+        started: widgets::StartedCommand,
+    },
+    /// A terminal widget's command ran past the timeout.
+    TerminalTimedOut {
+        widget_id: u64,
+        command: widgets::CommandId,
+        timeout_secs: u64,
+    },
+    /// A terminal widget's command exited, or couldn't be started if there is no command. The note
+    /// is shown before the exit status, like why the command couldn't be started.
+    TerminalFinished {
+        widget_id: u64,
+        command: Option<widgets::CommandId>,
+        status: Option<ExitStatus>,
+        elapsed: Duration,
+        note: Option<String>,
+    },
+    /// New lines followed by a log widget.
+    LogLines {
+        widget_id: u64,
+        lines: Vec<widgets::LogLine>,
+    },
+    /// The agents found on the local network changed.
+    AgentsDiscovered(Vec<remote::discovery::DiscoveredAgent>),
+    /// The data shown now comes from the agent at this address.
+    Connected(String),
 }
 
 #[derive(Debug)]
@@ -87,6 +133,11 @@ pub enum ThreadControlEvent {
     UpdateConfig(Box<app::AppConfigFields>),
     UpdateUsedWidgets(Box<UsedWidgets>),
     UpdateUpdateTime(u64),
+    UpdateFilters(Box<app::DataFilters>),
+    /// Show the data of the agent at this address instead.
+    Connect(String),
+    /// Hand every update from now on to another sink as well.
+    AddSink(Box<dyn sinks::Sink>),
 }
 
 pub fn handle_mouse_event(event: MouseEvent, app: &mut App) {
@@ -112,34 +163,96 @@ pub fn handle_mouse_event(event: MouseEvent, app: &mut App) {
 
 pub fn handle_key_event_or_break(
     event: KeyEvent,
-    app: &'static Mutex<Option<App>>,
+    app: &mut App,
     reset_sender: &Sender<ThreadControlEvent>,
     sender: &Sender<BottomEvent>, //termination_ctrl_cvar: Arc<Condvar>,
 ) -> bool {
-    let current_widget_id = app
-        .lock()
-        .unwrap()
-        .as_ref()
-        .unwrap()
-        .current_widget
-        .widget_id;
-    let mut app_lock = app.lock().unwrap();
-    let app_mut = app_lock.as_mut().unwrap();
-    let terminal_widget_state = app_mut
-        .terminal_state
-        .widget_states
-        .get_mut(&current_widget_id);
+    let current_widget_id = app.current_widget.widget_id;
+    // Commands run on the agent instead, when connected to one.
+    let remote_address = app.app_config_fields.remote_address.clone();
+    let actions = app
+        .app_config_fields
+        .key_bindings
+        .actions(&mut app.pending_keys, &event);
+    // Printable keys bound to actions are still typed where there is something to type into.
+    let is_printable = matches!(event.code, KeyCode::Char(_))
+        && !event
+            .modifiers
+            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+
+    if app.command_line_state.is_showing {
+        return handle_command_line_key(event, app, reset_sender);
+    }
+    if app.layout_editor.is_some() {
+        handle_layout_editor_key(event, app);
+        return false;
+    }
+
+    let terminal_widget_state = app.terminal_state.widget_states.get_mut(&current_widget_id);
     if let Some(terminal_widget_state) = terminal_widget_state {
+        if terminal_widget_state.is_working
+            && event.code == KeyCode::Char('c')
+            && event.modifiers.contains(KeyModifiers::CONTROL)
+        {
+            terminal_widget_state.cancel();
+            return false;
+        }
+
+        // While a command runs, keystrokes go to it rather than to bottom, apart from Esc and
+        // scrolling.
+        #[cfg(target_family = "unix")]
+        if app.is_expanded
+            && terminal_widget_state.is_working
+            && remote_address.is_none()
+            && event.code != KeyCode::Esc
+        {
+            if let Some(input) = widgets::key_to_pty_input(&event) {
+                terminal_widget_state.write_to_command(&input);
+                return false;
+            }
+        }
+
+        if let Some(&action) = actions
+            .iter()
+            .find(|action| action.is_terminal_action() && !(is_printable && app.is_expanded))
+        {
+            return perform_action(action, app, reset_sender);
+        }
+
         if !event.modifiers.contains(KeyModifiers::CONTROL) {
             match event.code {
-                KeyCode::End => terminal_widget_state.offset = 0,
-                KeyCode::PageUp => terminal_widget_state.offset += 1,
-                KeyCode::PageDown if terminal_widget_state.offset > 0 => {
-                    terminal_widget_state.offset -= 1
+                KeyCode::End => terminal_widget_state.scrollback.offset = 0,
+                KeyCode::Left
+                    if event.modifiers.contains(KeyModifiers::SHIFT)
+                        && !terminal_widget_state.scrollback.wrap_output =>
+                {
+                    terminal_widget_state.scrollback.scroll_left();
+                }
+                KeyCode::Right
+                    if event.modifiers.contains(KeyModifiers::SHIFT)
+                        && !terminal_widget_state.scrollback.wrap_output =>
+                {
+                    terminal_widget_state.scrollback.scroll_right();
                 }
-                KeyCode::Esc => app_mut.is_expanded = false,
-                _ if app_mut.is_expanded && !terminal_widget_state.is_working => {
+                KeyCode::PageUp => terminal_widget_state.scrollback.scroll_up(),
+                KeyCode::PageDown if terminal_widget_state.scrollback.offset > 0 => {
+                    terminal_widget_state.scrollback.scroll_down()
+                }
+                KeyCode::Esc => app.is_expanded = false,
+                // Without a pseudo-terminal, input is typed as usual and sent a line at a time.
+                _ if app.is_expanded
+                    && (!terminal_widget_state.is_working
+                        || remote_address.is_some()
+                        || cfg!(not(target_family = "unix"))) =>
+                {
                     match event.code {
+                        KeyCode::Enter if terminal_widget_state.is_working => {
+                            terminal_widget_state.send_input_line();
+                        }
+                        KeyCode::Enter if terminal_widget_state.try_save_action() => {}
+                        KeyCode::Enter
+                            if remote_address.is_none()
+                                && terminal_widget_state.try_change_directory() => {}
                         KeyCode::Up
                             if {
                                 terminal_widget_state.selected_input
@@ -151,98 +264,52 @@ pub fn handle_key_event_or_break(
                         KeyCode::Down if terminal_widget_state.selected_input > 0 => {
                             terminal_widget_state.selected_input -= 1;
                         }
-                        KeyCode::Left
-                            if {
-                                terminal_widget_state.input_offset
-                                    < terminal_widget_state.current_input().len()
-                            } =>
-                        {
-                            terminal_widget_state.input_offset += 1
-                        }
-                        KeyCode::Right if terminal_widget_state.input_offset > 0 => {
-                            terminal_widget_state.input_offset -= 1
+                        KeyCode::Left => terminal_widget_state.move_cursor_left(),
+                        KeyCode::Right => terminal_widget_state.move_cursor_right(),
+                        KeyCode::Tab => {
+                            let process = app.proc_state.highlighted_process();
+                            terminal_widget_state.expand_alias(
+                                &app.app_config_fields.terminal_aliases,
+                                process.as_ref(),
+                            );
                         }
                         KeyCode::Enter if !terminal_widget_state.stdin.is_empty() => {
+                            let process = app.proc_state.highlighted_process();
+                            terminal_widget_state.expand_alias(
+                                &app.app_config_fields.terminal_aliases,
+                                process.as_ref(),
+                            );
                             terminal_widget_state.is_working = true;
                             terminal_widget_state.input_offset = 0;
-                            drop(app_lock);
+                            let command = terminal_widget_state.take_input();
+                            let shell = app.app_config_fields.terminal_shell.clone();
+                            let agent_token = app
+                                .app_config_fields
+                                .agent_token
+                                .clone()
+                                .unwrap_or_default();
+                            let cwd = terminal_widget_state.cwd.clone();
                             {
-                                let mut t = UnsafeTerminalWidgetState {
+                                let mut t = TerminalCommandExecutor {
                                     id: current_widget_id,
-                                    app,
-                                    sender,
+                                    sender: sender.clone(),
+                                    timeout_secs: app.app_config_fields.terminal_timeout_secs,
                                 };
                                 thread::spawn(move || {
-                                    let command = t.stdin();
-                                    let mut output = Command::new("bash")
-                                        .args(["-c", &command])
-                                        .stdin(Stdio::null())
-                                        .stdout(Stdio::piped())
-                                        .stderr(Stdio::piped())
-                                        .spawn()
-                                        .unwrap();
-                                    while output.try_wait().unwrap().is_none() {
-                                        let mut buf = [0];
-                                        if output.stdout.as_mut().unwrap().read(&mut buf).unwrap()
-                                            > 0
-                                        {
-                                            t.append_output(&buf);
-                                        }
+                                    if let Some(address) = remote_address {
+                                        t.run_remote(&address, &command, &agent_token);
+                                        return;
                                     }
-                                    let mut end = Vec::new();
-                                    output.stdout.unwrap().read_to_end(&mut end).unwrap();
-                                    output.stderr.unwrap().read_to_end(&mut end).unwrap();
-                                    t.append_output(&end);
-                                    t.limit_output();
-                                    t.finish();
+                                    #[cfg(target_family = "unix")]
+                                    t.run_in_pty(&shell, &command, &cwd);
+                                    #[cfg(not(target_family = "unix"))]
+                                    t.run_piped(&shell, &command, &cwd);
                                 });
                             }
                         }
-                        KeyCode::Backspace => {
-                            let index = terminal_widget_state.selected_input;
-                            let Some(input) = terminal_widget_state.stdin.get_mut(index) else {
-                                return false;
-                            };
-                            if let Some(offset) = {
-                                if input.is_empty() {
-                                    None
-                                } else if input.len() > terminal_widget_state.input_offset {
-                                    Some(input.len() - terminal_widget_state.input_offset - 1)
-                                } else {
-                                    None
-                                }
-                            } {
-                                input.remove(offset);
-                            }
-                        }
-                        KeyCode::Char(c) if c.is_ascii() => {
-                            let index = terminal_widget_state.selected_input;
-                            if let Some(stdin) = terminal_widget_state.stdin.get_mut(index) {
-                                stdin.insert(stdin.len() - terminal_widget_state.input_offset, c);
-                            }
-                        }
-                        KeyCode::Delete => {
-                            let index = terminal_widget_state.selected_input;
-                            let Some(input) = terminal_widget_state.stdin.get_mut(index) else {
-                                return false;
-                            };
-                            if let Some(offset) = {
-                                if terminal_widget_state.input_offset == 0 {
-                                    None
-                                } else if input.len() >= terminal_widget_state.input_offset {
-                                    Some(input.len() - terminal_widget_state.input_offset)
-                                } else {
-                                    None
-                                }
-                            } {
-                                input.remove(offset);
-                                terminal_widget_state.input_offset -= 1;
-                            }
-                        }
-                        KeyCode::F(9) => {
-                            terminal_widget_state.stdout.clear();
-                            terminal_widget_state.offset = 0;
-                        }
+                        KeyCode::Backspace => terminal_widget_state.remove_before_cursor(),
+                        KeyCode::Char(c) => terminal_widget_state.insert_char(c),
+                        KeyCode::Delete => terminal_widget_state.remove_after_cursor(),
                         KeyCode::F(10) => {
                             /*return handle_key_event_or_break(
                                 KeyEvent::new(KeyCode::Char('~'), event.modifiers),
@@ -260,92 +327,115 @@ pub fn handle_key_event_or_break(
             }
         }
     }
+
+    if let Some(log_widget_state) = app.log_state.get_mut_widget_state(current_widget_id) {
+        if log_widget_state.handle_key(&event) {
+            return false;
+        }
+    }
+    if !app.is_in_dialog() {
+        if let Some(custom_widget) = app
+            .custom_widget_state
+            .get_mut_widget_state(current_widget_id)
+        {
+            if custom_widget.handle_key(&event) {
+                return false;
+            }
+        }
+    }
     // debug!("KeyEvent: {:?}", event);
 
-    if event.modifiers.is_empty() {
-        // Required catch for searching - otherwise you couldn't search with q.
-        if event.code == KeyCode::Char('q') && !app_mut.is_in_search_widget() {
-            return true;
+    for action in actions {
+        // Only terminal widgets have these, which handle them above.
+        if action.is_terminal_action() {
+            continue;
         }
+        // Quitting and moving around work in dialogs, unlike the rest, which would otherwise act
+        // on what's behind them.
+        if is_printable
+            && (app.is_typing()
+                || (app.is_in_dialog() && action != Action::Quit && !action.is_movement()))
+        {
+            continue;
+        }
+
+        return perform_action(action, app, reset_sender);
+    }
+
+    if event.modifiers.is_empty() {
         match event.code {
-            KeyCode::End => app_mut.skip_to_last(),
-            KeyCode::Home => app_mut.skip_to_first(),
-            KeyCode::Up => app_mut.on_up_key(),
-            KeyCode::Down => app_mut.on_down_key(),
-            KeyCode::Left => app_mut.on_left_key(),
-            KeyCode::Right => app_mut.on_right_key(),
             /*KeyCode::Char('r') => {
                 termination_ctrl_cvar.notify_all();
                 return false;
             }*/
-            KeyCode::Char(caught_char) => app_mut.on_char_key(caught_char),
-            KeyCode::Esc => app_mut.on_esc(),
-            KeyCode::Enter => app_mut.on_enter(),
-            KeyCode::Tab => app_mut.on_tab(),
-            KeyCode::Backspace => app_mut.on_backspace(),
-            KeyCode::Delete => app_mut.on_delete(),
-            KeyCode::F(1) => app_mut.toggle_ignore_case(),
-            KeyCode::F(2) => app_mut.toggle_search_whole_word(),
-            KeyCode::F(3) => app_mut.toggle_search_regex(),
-            KeyCode::F(5) => app_mut.toggle_tree_mode(),
-            KeyCode::F(6) => app_mut.toggle_sort_menu(),
-            KeyCode::F(9) => app_mut.start_killing_process(),
-            KeyCode::PageDown => app_mut.on_page_down(),
-            KeyCode::PageUp => app_mut.on_page_up(),
+            KeyCode::Char(caught_char) => app.on_char_key(caught_char),
+            KeyCode::Esc => app.on_esc(),
+            KeyCode::Enter => {
+                // Picking a discovered agent switches the data shown over to it.
+                if let Some(agent) = app
+                    .discovery_state
+                    .get_widget_state(current_widget_id)
+                    .and_then(|discovery| discovery.selected_agent())
+                {
+                    let _ =
+                        reset_sender.send(ThreadControlEvent::Connect(agent.address.to_string()));
+                } else {
+                    app.on_enter()
+                }
+            }
+            KeyCode::Tab => app.on_tab(),
+            KeyCode::Backspace => app.on_backspace(),
+            KeyCode::Delete => app.on_delete(),
+            KeyCode::F(1) => app.toggle_ignore_case(),
+            KeyCode::F(2) => app.toggle_search_whole_word(),
+            KeyCode::F(3) => app.toggle_search_regex(),
+            KeyCode::F(4) => app.toggle_search_cmdline(),
+            #[cfg(target_family = "unix")]
+            KeyCode::F(7) => app.renice_process(-1),
+            #[cfg(target_family = "unix")]
+            KeyCode::F(8) => app.renice_process(1),
+            KeyCode::PageDown => app.on_page_down(),
+            KeyCode::PageUp => app.on_page_up(),
             _ => {}
         }
     } else {
         // Otherwise, track the modifier as well...
         if let KeyModifiers::ALT = event.modifiers {
             match event.code {
-                KeyCode::Char('c') | KeyCode::Char('C') => app_mut.toggle_ignore_case(),
-                KeyCode::Char('w') | KeyCode::Char('W') => app_mut.toggle_search_whole_word(),
-                KeyCode::Char('r') | KeyCode::Char('R') => app_mut.toggle_search_regex(),
+                KeyCode::Char('c') | KeyCode::Char('C') => app.toggle_ignore_case(),
+                KeyCode::Char('w') | KeyCode::Char('W') => app.toggle_search_whole_word(),
+                KeyCode::Char('r') | KeyCode::Char('R') => app.toggle_search_regex(),
+                KeyCode::Char('a') | KeyCode::Char('A') => app.toggle_search_cmdline(),
                 // KeyCode::Char('b') | KeyCode::Char('B') => todo!(),
                 // KeyCode::Char('f') | KeyCode::Char('F') => todo!(),
-                KeyCode::Char('h') => app_mut.on_left_key(),
-                KeyCode::Char('l') => app_mut.on_right_key(),
                 _ => {}
             }
         } else if let KeyModifiers::CONTROL = event.modifiers {
-            if event.code == KeyCode::Char('c') {
-                return true;
-            }
-
             match event.code {
-                KeyCode::Char('f') => app_mut.on_slash(),
-                KeyCode::Left => app_mut.move_widget_selection(&WidgetDirection::Left),
-                KeyCode::Right => app_mut.move_widget_selection(&WidgetDirection::Right),
-                KeyCode::Up => app_mut.move_widget_selection(&WidgetDirection::Up),
-                KeyCode::Down => app_mut.move_widget_selection(&WidgetDirection::Down),
-                KeyCode::Char('r') => {
-                    if reset_sender.send(ThreadControlEvent::Reset).is_ok() {
-                        app_mut.reset();
-                    }
-                }
-                KeyCode::Char('a') => app_mut.skip_cursor_beginning(),
-                KeyCode::Char('e') => app_mut.skip_cursor_end(),
-                KeyCode::Char('u') if app_mut.is_in_search_widget() => app_mut.clear_search(),
-                KeyCode::Char('w') => app_mut.clear_previous_word(),
-                KeyCode::Char('h') => app_mut.on_backspace(),
-                KeyCode::Char('d') => app_mut.scroll_half_page_down(),
-                KeyCode::Char('u') => app_mut.scroll_half_page_up(),
+                KeyCode::Char('a') if app.is_in_search_widget() => app.skip_cursor_beginning(),
+                KeyCode::Char('a') => app.select_all_processes(),
+                KeyCode::Char('e') if app.is_in_search_widget() => app.skip_cursor_end(),
+                KeyCode::Char('e') => app.toggle_error_log(),
+                KeyCode::Char('u') if app.is_in_search_widget() => app.clear_search(),
+                KeyCode::Char('w') => app.clear_previous_word(),
+                KeyCode::Char('h') => app.on_backspace(),
+                KeyCode::Char('d') => app.scroll_half_page_down(),
+                KeyCode::Char('u') => app.scroll_half_page_up(),
                 // KeyCode::Char('j') => {}, // Move down
                 // KeyCode::Char('k') => {}, // Move up
                 // KeyCode::Char('h') => {}, // Move right
                 // KeyCode::Char('l') => {}, // Move left
                 // Can't do now, CTRL+BACKSPACE doesn't work and graphemes
                 // are hard to iter while truncating last (eloquently).
-                // KeyCode::Backspace => app_mut.skip_word_backspace(),
+                // KeyCode::Backspace => app.skip_word_backspace(),
                 _ => {}
             }
         } else if let KeyModifiers::SHIFT = event.modifiers {
             match event.code {
-                KeyCode::Left => app_mut.move_widget_selection(&WidgetDirection::Left),
-                KeyCode::Right => app_mut.move_widget_selection(&WidgetDirection::Right),
-                KeyCode::Up => app_mut.move_widget_selection(&WidgetDirection::Up),
-                KeyCode::Down => app_mut.move_widget_selection(&WidgetDirection::Down),
-                KeyCode::Char(caught_char) => app_mut.on_char_key(caught_char),
+                // Like htop, Shift-F7 is an alternative to F8.
+                #[cfg(target_family = "unix")]
+                KeyCode::F(7) => app.renice_process(1),
+                KeyCode::Char(caught_char) => app.on_char_key(caught_char),
                 _ => {}
             }
         }
@@ -353,6 +443,140 @@ pub fn handle_key_event_or_break(
     false
 }
 
+/// Does what `action` is bound to, returning whether bottom should quit.
+fn perform_action(
+    action: Action, app: &mut App, reset_sender: &Sender<ThreadControlEvent>,
+) -> bool {
+    match action {
+        Action::Quit => return true,
+        Action::Search => app.on_slash(),
+        Action::KillProcess => app.start_killing_process(),
+        Action::MoveUp => app.on_up_key(),
+        Action::MoveDown => app.on_down_key(),
+        Action::MoveLeft => app.on_left_key(),
+        Action::MoveRight => app.on_right_key(),
+        Action::JumpToFirst => app.skip_to_first(),
+        Action::JumpToLast => app.skip_to_last(),
+        Action::MoveWidgetLeft => app.move_widget_selection(&WidgetDirection::Left),
+        Action::MoveWidgetRight => app.move_widget_selection(&WidgetDirection::Right),
+        Action::MoveWidgetUp => app.move_widget_selection(&WidgetDirection::Up),
+        Action::MoveWidgetDown => app.move_widget_selection(&WidgetDirection::Down),
+        Action::ExpandWidget => app.toggle_expand_widget(),
+        Action::Help => app.open_help(),
+        Action::Freeze => app.toggle_freeze(),
+        Action::ResetData => {
+            if reset_sender.send(ThreadControlEvent::Reset).is_ok() {
+                app.reset();
+            }
+        }
+        Action::DumpSnapshot => app.dump_snapshot(),
+        Action::EditLayout => app.open_layout_editor(),
+        Action::ToggleTreeMode => app.toggle_tree_mode(),
+        Action::SortMenu => app.toggle_sort_menu(),
+        Action::ClearTerminal | Action::WrapTerminalOutput | Action::SaveTerminalOutput => {
+            if let Some(terminal_widget_state) = app
+                .terminal_state
+                .get_mut_widget_state(app.current_widget.widget_id)
+            {
+                match action {
+                    Action::ClearTerminal => {
+                        terminal_widget_state.stdout.clear();
+                        terminal_widget_state.scrollback.offset = 0;
+                    }
+                    Action::WrapTerminalOutput => terminal_widget_state.scrollback.toggle_wrap(),
+                    _ => terminal_widget_state.save_output_to_default(),
+                }
+            }
+        }
+        Action::CommandLine => app.command_line_state.open(),
+        Action::SwitchTab(index) => app.switch_tab(index),
+    }
+    app.reset_multi_tap_keys();
+
+    false
+}
+
+/// Types into the command line, and runs the action named in it on enter.
+fn handle_command_line_key(
+    event: KeyEvent, app: &mut App, reset_sender: &Sender<ThreadControlEvent>,
+) -> bool {
+    let command_line_state = &mut app.command_line_state;
+    if command_line_state.error.is_some() {
+        command_line_state.close();
+        return false;
+    }
+
+    let input = &mut command_line_state.input;
+    match event.code {
+        KeyCode::Esc => command_line_state.close(),
+        KeyCode::Char('c') if event.modifiers.contains(KeyModifiers::CONTROL) => {
+            command_line_state.close()
+        }
+        KeyCode::Backspace if input.text.is_empty() => command_line_state.close(),
+        KeyCode::Backspace => input.backspace(),
+        KeyCode::Delete => input.delete(),
+        KeyCode::Left => input.move_left(),
+        KeyCode::Right => input.move_right(),
+        KeyCode::Char(c)
+            if !event
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+        {
+            input.insert_char(c)
+        }
+        KeyCode::Enter => {
+            let command = std::mem::take(&mut input.text);
+            let command = command.trim();
+            command_line_state.close();
+            if let Some(action) = Action::from_command(command) {
+                return perform_action(action, app, reset_sender);
+            } else if !command.is_empty() {
+                command_line_state.open();
+                command_line_state.error = Some(format!("Not a command: {command}"));
+            }
+        }
+        _ => {}
+    }
+
+    false
+}
+
+/// Edits the layout in the layout editor, saving it to the config on enter. Once saved, the config
+/// is reloaded with the new layout.
+fn handle_layout_editor_key(event: KeyEvent, app: &mut App) {
+    let Some(editor) = &mut app.layout_editor else {
+        return;
+    };
+    let is_shifted = event.modifiers.contains(KeyModifiers::SHIFT);
+    app.is_force_redraw = true;
+
+    match event.code {
+        KeyCode::Esc | KeyCode::Char('q') => app.layout_editor = None,
+        KeyCode::Char('c') if event.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.layout_editor = None
+        }
+        KeyCode::Enter | KeyCode::Char('s') => match editor.save() {
+            Ok(()) => app.layout_editor = None,
+            Err(err) => editor.error = Some(err.to_string()),
+        },
+        KeyCode::Up if is_shifted => editor.move_selected(true),
+        KeyCode::Down if is_shifted => editor.move_selected(false),
+        KeyCode::Char('K') => editor.move_selected(true),
+        KeyCode::Char('J') => editor.move_selected(false),
+        KeyCode::Up | KeyCode::Char('k') => editor.select_previous(),
+        KeyCode::Down | KeyCode::Char('j') => editor.select_next(),
+        KeyCode::Left | KeyCode::Char('h') => editor.cycle_widget_type(false),
+        KeyCode::Right | KeyCode::Char('l') => editor.cycle_widget_type(true),
+        KeyCode::Char('+') | KeyCode::Char('=') => editor.resize_selected(true),
+        KeyCode::Char('-') => editor.resize_selected(false),
+        KeyCode::Char('a') => editor.add_widget(),
+        KeyCode::Char('r') => editor.add_row(),
+        KeyCode::Char('c') => editor.toggle_column(),
+        KeyCode::Char('d') | KeyCode::Delete => editor.remove_selected(),
+        _ => {}
+    }
+}
+
 pub fn read_config(config_location: Option<&String>) -> error::Result<Option<PathBuf>> {
     let config_path = if let Some(conf_loc) = config_location {
         Some(PathBuf::from(conf_loc.as_str()))
@@ -399,7 +623,7 @@ pub fn create_or_get_config(config_path: &Option<PathBuf>) -> error::Result<Conf
                 fs::create_dir_all(parent_path)?;
             }
             // fs::File::create(path)?.write_all(CONFIG_TOP_HEAD.as_bytes())?;
-            fs::File::create(path)?.write_all(CONFIG_TEXT.as_bytes())?;
+            fs::File::create(path)?.write_all(generate_annotated_config().as_bytes())?;
             Ok(Config::default())
         }
     } else {
@@ -449,14 +673,171 @@ pub fn check_if_terminal() {
     }
 }
 
+/// Eats newly harvested data and, if not frozen, converts it into what the widgets need to draw.
+pub fn process_update(app: &mut App, data: Box<data_harvester::Data>) {
+    app.data_collection.eat_data(data);
+    app.update_alerts();
+
+    if app.frozen_state.is_frozen() {
+        return;
+    }
+
+    let data_collection = &app.data_collection;
+
+    for custom_widget in app.custom_widget_state.widget_states.values_mut() {
+        custom_widget.ingest_data(data_collection);
+    }
+
+    // Network
+    if app.used_widgets.use_net {
+        let network_data = convert_network_data_points(
+            data_collection,
+            app.app_config_fields.use_basic_mode || app.app_config_fields.use_old_network_legend,
+            &app.app_config_fields.network_scale_type,
+            &app.app_config_fields.network_unit_type,
+            app.app_config_fields.network_use_binary_prefix,
+        );
+        app.converted_data.network_data_rx = network_data.rx;
+        app.converted_data.network_data_tx = network_data.tx;
+        app.converted_data.rx_display = network_data.rx_display;
+        app.converted_data.tx_display = network_data.tx_display;
+        if let Some(total_rx_display) = network_data.total_rx_display {
+            app.converted_data.total_rx_display = total_rx_display;
+        }
+        if let Some(total_tx_display) = network_data.total_tx_display {
+            app.converted_data.total_tx_display = total_tx_display;
+        }
+
+        let current_instant = data_collection.current_instant;
+        let network_harvest = &data_collection.network_harvest;
+        for net_widget_state in app.net_state.widget_states.values_mut() {
+            net_widget_state
+                .push_tcp_connections(current_instant, network_harvest.total_tcp_connections);
+            net_widget_state.update_peaks(network_harvest.rx as f64, network_harvest.tx as f64);
+            if let Some(tcp_stats) = &network_harvest.tcp_stats {
+                net_widget_state.update_tcp_stats(current_instant, tcp_stats.clone());
+            }
+            net_widget_state
+                .update_interface_errors(current_instant, network_harvest.interface_errors.clone());
+        }
+    }
+
+    // Disk
+    if app.used_widgets.use_disk {
+        app.converted_data
+            .ingest_disk_data(data_collection, app.app_config_fields.inode_alert_threshold);
+
+        for disk in app.disk_state.widget_states.values_mut() {
+            disk.force_data_update();
+        }
+    }
+
+    // Temperatures
+    if app.used_widgets.use_temp {
+        app.converted_data
+            .ingest_temp_data(data_collection, app.app_config_fields.temperature_type);
+
+        for temp in app.temp_state.widget_states.values_mut() {
+            temp.force_data_update();
+        }
+    }
+
+    if !app.connections_state.widget_states.is_empty() {
+        app.converted_data
+            .ingest_connections_data(data_collection, &app.app_config_fields);
+    }
+
+    if !app.container_state.widget_states.is_empty() {
+        app.converted_data.ingest_container_data(data_collection);
+    }
+
+    // RAID
+    if !app.raid_state.widget_states.is_empty() {
+        app.converted_data.ingest_raid_data(data_collection);
+    }
+
+    // ZFS pools
+    if !app.zfs_pool_state.widget_states.is_empty() {
+        app.converted_data.ingest_zfs_pool_data(data_collection);
+    }
+
+    // Cgroups
+    if !app.cgroup_state.widget_states.is_empty() {
+        app.converted_data.ingest_cgroup_data(data_collection);
+    }
+
+    // Scheduled jobs
+    if !app.scheduled_job_state.widget_states.is_empty() {
+        app.converted_data
+            .ingest_scheduled_job_data(data_collection);
+    }
+
+    // Pressure
+    if !app.pressure_state.widget_states.is_empty() {
+        app.converted_data.pressure_data = convert_pressure_data_points(data_collection);
+    }
+
+    // Wi-Fi
+    if !app.wifi_state.widget_states.is_empty() {
+        app.converted_data.wifi_data = convert_wifi_data_points(data_collection);
+    }
+
+    // Memory
+    if app.used_widgets.use_mem {
+        app.converted_data.mem_data = data_collection.memory_harvest.clone();
+        app.converted_data.swap_data = data_collection.swap_harvest.clone();
+
+        let (memory_labels, swap_labels) = convert_mem_labels(data_collection);
+        app.converted_data.mem_labels = memory_labels;
+        app.converted_data.swap_labels = swap_labels;
+    }
+
+    // CPU
+    if app.used_widgets.use_cpu {
+        app.converted_data.ingest_cpu_data(data_collection);
+        app.converted_data.load_avg_data = data_collection.load_avg_harvest;
+    }
+
+    // Processes
+    if app.used_widgets.use_proc {
+        for proc in app.proc_state.widget_states.values_mut() {
+            proc.force_data_update();
+        }
+    }
+
+    // Battery
+    #[cfg(feature = "battery")]
+    {
+        if app.used_widgets.use_battery {
+            app.converted_data.battery_data = convert_battery_harvest(data_collection);
+
+            let current_instant = data_collection.current_instant;
+            for battery_widget_state in app.battery_state.widget_states.values_mut() {
+                let battery_index = battery_widget_state.currently_selected_battery_index;
+                if let Some(battery_harvest) = data_collection.battery_harvest.get(battery_index) {
+                    battery_widget_state.push_power_history(
+                        current_instant,
+                        battery_index,
+                        battery_harvest.power_consumption_rate_watts,
+                    );
+                }
+            }
+        }
+    }
+
+    update_data(app);
+}
+
 pub fn update_data(app: &mut App) {
     let data_source = match &app.frozen_state {
         FrozenState::NotFrozen => &app.data_collection,
         FrozenState::Frozen(data) => data,
     };
 
-    for proc in app.proc_state.widget_states.values_mut() {
-        if proc.force_update_data {
+    let frozen_widget_ids = &app.frozen_widget_ids;
+
+    for (widget_id, proc) in app.proc_state.widget_states.iter_mut() {
+        if proc.force_update_data && !frozen_widget_ids.contains(widget_id) {
             proc.ingest_data(data_source);
             proc.force_update_data = false;
         }
@@ -479,8 +860,8 @@ pub fn update_data(app: &mut App) {
     }
     {
         let data = &app.converted_data.temp_data;
-        for temp in app.temp_state.widget_states.values_mut() {
-            if temp.force_update_data {
+        for (widget_id, temp) in app.temp_state.widget_states.iter_mut() {
+            if temp.force_update_data && !frozen_widget_ids.contains(widget_id) {
                 temp.ingest_data(data);
                 temp.force_update_data = false;
             }
@@ -488,16 +869,68 @@ pub fn update_data(app: &mut App) {
     }
     {
         let data = &app.converted_data.disk_data;
-        for disk in app.disk_state.widget_states.values_mut() {
-            if disk.force_update_data {
+        for (widget_id, disk) in app.disk_state.widget_states.iter_mut() {
+            if disk.force_update_data && !frozen_widget_ids.contains(widget_id) {
                 disk.ingest_data(data);
                 disk.force_update_data = false;
             }
         }
     }
     {
-        for connections in app.connections_state.widget_states.values_mut() {
-            connections.ingest_data(&app.converted_data.connections_data)
+        for (widget_id, connections) in app.connections_state.widget_states.iter_mut() {
+            if !frozen_widget_ids.contains(widget_id) {
+                connections.ingest_data(&app.converted_data.connections_data)
+            }
+        }
+    }
+    {
+        for (widget_id, containers) in app.container_state.widget_states.iter_mut() {
+            if !frozen_widget_ids.contains(widget_id) {
+                containers.ingest_data(&app.converted_data.container_data)
+            }
+        }
+    }
+    {
+        for (widget_id, raid) in app.raid_state.widget_states.iter_mut() {
+            if !frozen_widget_ids.contains(widget_id) {
+                raid.ingest_data(&app.converted_data.raid_data)
+            }
+        }
+    }
+    {
+        for (widget_id, zfs_pools) in app.zfs_pool_state.widget_states.iter_mut() {
+            if !frozen_widget_ids.contains(widget_id) {
+                zfs_pools.ingest_data(&app.converted_data.zfs_pool_data)
+            }
+        }
+    }
+    {
+        for (widget_id, cgroups) in app.cgroup_state.widget_states.iter_mut() {
+            if !frozen_widget_ids.contains(widget_id) {
+                cgroups.ingest_data(&app.converted_data.cgroup_data)
+            }
+        }
+    }
+    {
+        for (widget_id, scheduled_jobs) in app.scheduled_job_state.widget_states.iter_mut() {
+            if !frozen_widget_ids.contains(widget_id) {
+                scheduled_jobs.ingest_data(&app.converted_data.scheduled_job_data)
+            }
+        }
+    }
+    {
+        for (widget_id, dashboard) in app.dashboard_state.widget_states.iter_mut() {
+            if !frozen_widget_ids.contains(widget_id) {
+                dashboard.ingest_data(&data_source.agent_harvest)
+            }
+        }
+    }
+    {
+        let connected_address = app.app_config_fields.remote_address.as_deref();
+        for (widget_id, discovery) in app.discovery_state.widget_states.iter_mut() {
+            if !frozen_widget_ids.contains(widget_id) {
+                discovery.ingest_data(&app.discovered_agents, connected_address)
+            }
         }
     }
 
@@ -528,6 +961,8 @@ pub fn update_data(app: &mut App) {
         app.converted_data.network_data_tx = tx;
         app.net_state.force_update = None;
     }
+
+    app.update_alerting_widgets();
 }
 
 pub fn create_input_thread(
@@ -593,29 +1028,82 @@ pub fn create_input_thread(
     })
 }
 
+/// Checks the config file for changes every so often, as editors save in too many different ways
+/// to reliably catch otherwise.
+pub fn create_config_watcher_thread(
+    sender: Sender<BottomEvent>, termination_ctrl_lock: Arc<Mutex<bool>>, config_path: PathBuf,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        let mut watcher = ConfigWatcher::new(config_path);
+
+        loop {
+            if let Ok(is_terminated) = termination_ctrl_lock.try_lock() {
+                if *is_terminated {
+                    drop(is_terminated);
+                    break;
+                }
+            }
+
+            thread::sleep(Duration::from_millis(CONFIG_POLL_RATE_IN_MILLISECONDS));
+            if watcher.has_changed() && sender.send(BottomEvent::ConfigChanged).is_err() {
+                break;
+            }
+        }
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn create_collection_thread(
     sender: Sender<BottomEvent>, control_receiver: Receiver<ThreadControlEvent>,
     termination_ctrl_lock: Arc<Mutex<bool>>, termination_ctrl_cvar: Arc<Condvar>,
     app_config_fields: &app::AppConfigFields, filters: app::DataFilters,
-    used_widget_set: UsedWidgets,
+    used_widget_set: UsedWidgets, mut sinks: sinks::Sinks,
+    remote_source: Option<remote::RemoteSource>,
 ) -> JoinHandle<()> {
     let temp_type = app_config_fields.temperature_type;
+    let sensor_types = app_config_fields.sensor_types.clone();
     let use_current_cpu_total = app_config_fields.use_current_cpu_total;
     let unnormalized_cpu = app_config_fields.unnormalized_cpu;
     let show_average_cpu = app_config_fields.show_average_cpu;
+    let collect_cpu_frequency =
+        app_config_fields.show_cpu_frequency || app_config_fields.highlight_throttled_cpus;
     let update_rate_in_milliseconds = app_config_fields.update_rate_in_milliseconds;
+    let collection_timeout_ms = app_config_fields.collection_timeout_ms;
+    let slow_update_rate_in_milliseconds = app_config_fields.slow_update_rate_in_milliseconds;
+    let uptime_hosts = app_config_fields.uptime_hosts.clone();
+    let dashboard_agents = app_config_fields.dashboard_agents.clone();
 
     thread::spawn(move || {
+        if let Some(remote_source) = remote_source {
+            receive_remote_data(
+                remote_source,
+                sender,
+                termination_ctrl_lock,
+                termination_ctrl_cvar,
+                update_rate_in_milliseconds,
+                sinks,
+                control_receiver,
+            );
+            return;
+        }
+
         let mut data_state = data_harvester::DataCollector::new(filters);
 
         data_state.set_data_collection(used_widget_set);
         data_state.set_temperature_type(temp_type);
+        data_state.set_sensor_types(sensor_types);
         data_state.set_use_current_cpu_total(use_current_cpu_total);
         data_state.set_unnormalized_cpu(unnormalized_cpu);
         data_state.set_show_average_cpu(show_average_cpu);
+        data_state.set_collect_cpu_frequency(collect_cpu_frequency);
+        data_state.set_collection_timeout(Duration::from_millis(collection_timeout_ms));
+        data_state.set_slow_interval(Duration::from_millis(slow_update_rate_in_milliseconds));
+        data_state.set_remote_hosts(uptime_hosts);
+        data_state.set_agent_addresses(dashboard_agents);
 
         data_state.init();
 
+        let mut update_time = update_rate_in_milliseconds;
         loop {
             // Check once at the very top...
             if let Ok(is_terminated) = termination_ctrl_lock.try_lock() {
@@ -626,8 +1114,7 @@ pub fn create_collection_thread(
                 }
             }
 
-            let mut update_time = update_rate_in_milliseconds;
-            if let Ok(message) = control_receiver.try_recv() {
+            while let Ok(message) = control_receiver.try_recv() {
                 // trace!("Received message in collection thread: {:?}", message);
                 match message {
                     ThreadControlEvent::Reset => {
@@ -635,10 +1122,23 @@ pub fn create_collection_thread(
                     }
                     ThreadControlEvent::UpdateConfig(app_config_fields) => {
                         data_state.set_temperature_type(app_config_fields.temperature_type);
+                        data_state.set_sensor_types(app_config_fields.sensor_types.clone());
                         data_state
                             .set_use_current_cpu_total(app_config_fields.use_current_cpu_total);
-                        data_state.set_unnormalized_cpu(unnormalized_cpu);
+                        data_state.set_unnormalized_cpu(app_config_fields.unnormalized_cpu);
                         data_state.set_show_average_cpu(app_config_fields.show_average_cpu);
+                        data_state.set_collect_cpu_frequency(
+                            app_config_fields.show_cpu_frequency
+                                || app_config_fields.highlight_throttled_cpus,
+                        );
+                        data_state.set_collection_timeout(Duration::from_millis(
+                            app_config_fields.collection_timeout_ms,
+                        ));
+                        data_state.set_slow_interval(Duration::from_millis(
+                            app_config_fields.slow_update_rate_in_milliseconds,
+                        ));
+                        data_state.set_remote_hosts(app_config_fields.uptime_hosts.clone());
+                        data_state.set_agent_addresses(app_config_fields.dashboard_agents.clone());
                     }
                     ThreadControlEvent::UpdateUsedWidgets(used_widget_set) => {
                         data_state.set_data_collection(*used_widget_set);
@@ -646,11 +1146,61 @@ pub fn create_collection_thread(
                     ThreadControlEvent::UpdateUpdateTime(new_time) => {
                         update_time = new_time;
                     }
+                    ThreadControlEvent::UpdateFilters(filters) => {
+                        data_state.set_filters(*filters);
+                    }
+                    ThreadControlEvent::Connect(address) => {
+                        match remote::RemoteSource::connect(&address) {
+                            Ok(remote_source) => {
+                                if sender.send(BottomEvent::Connected(address)).is_ok() {
+                                    receive_remote_data(
+                                        remote_source,
+                                        sender,
+                                        termination_ctrl_lock,
+                                        termination_ctrl_cvar,
+                                        update_rate_in_milliseconds,
+                                        sinks,
+                                        control_receiver,
+                                    );
+                                }
+                                return;
+                            }
+                            Err(err) => {
+                                let error = data_harvester::CollectionError::new("Remote", err);
+                                if sender.send(BottomEvent::CollectionError(error)).is_err() {
+                                    break;
+                                }
+                            }
+                        }
+                    }
+                    ThreadControlEvent::AddSink(sink) => {
+                        sinks.register(sink);
+                    }
                 }
             }
 
-            // TODO: [OPT] this feels like it might not be totally optimal. Hm.
-            futures::executor::block_on(data_state.update_data());
+            // Anything that panics outside of the collector workers is caught here too, as
+            // otherwise the thread would die silently and the UI would stop updating.
+            let (errors, has_panicked) = match panic::catch_unwind(AssertUnwindSafe(|| {
+                data_state.update_data()
+            })) {
+                Ok(errors) => (errors, false),
+                Err(payload) => {
+                    let error =
+                        data_harvester::CollectionError::from_panic("Collection", payload.as_ref());
+
+                    #[cfg(feature = "log")]
+                    error!("Data collection {}", error.message);
+
+                    (vec![error], true)
+                }
+            };
+
+            for error in errors {
+                if sender.send(BottomEvent::CollectionError(error)).is_err() {
+                    break;
+                }
+            }
 
             // Yet another check to bail if needed...
             if let Ok(is_terminated) = termination_ctrl_lock.try_lock() {
@@ -661,11 +1211,11 @@ pub fn create_collection_thread(
                 }
             }
 
-            let event = BottomEvent::Update(Box::from(data_state.data));
-            data_state.data = data_harvester::Data::default();
-            if sender.send(event).is_err() {
-                break;
+            // Partially harvested data is dropped rather than shown.
+            if !has_panicked {
+                sinks.push(&data_state.data);
             }
+            data_state.data = data_harvester::Data::default();
 
             if let Ok((is_terminated, _wait_timeout_result)) = termination_ctrl_cvar.wait_timeout(
                 termination_ctrl_lock.lock().unwrap(),
@@ -679,3 +1229,66 @@ pub fn create_collection_thread(
         }
     })
 }
+
+/// Passes on updates from an agent on another machine in place of collecting them, for
+/// `--connect`. Losing the agent is reported like any other collection error, and reconnecting is
+/// tried every update interval until it is back.
+fn receive_remote_data(
+    mut remote_source: remote::RemoteSource, sender: Sender<BottomEvent>,
+    termination_ctrl_lock: Arc<Mutex<bool>>, termination_ctrl_cvar: Arc<Condvar>,
+    update_rate_in_milliseconds: u64, mut sinks: sinks::Sinks,
+    control_receiver: Receiver<ThreadControlEvent>,
+) {
+    loop {
+        if let Ok(is_terminated) = termination_ctrl_lock.try_lock() {
+            if *is_terminated {
+                break;
+            }
+        }
+
+        // Another agent may be picked from the discovery widget at any time. Anything that only
+        // changes local collection doesn't apply.
+        match control_receiver.try_recv() {
+            Ok(ThreadControlEvent::Connect(address)) => {
+                match remote::RemoteSource::connect(&address) {
+                    Ok(new_source) => {
+                        remote_source = new_source;
+                        if sender.send(BottomEvent::Connected(address)).is_err() {
+                            break;
+                        }
+                    }
+                    Err(err) => {
+                        let error = data_harvester::CollectionError::new("Remote", err);
+                        if sender.send(BottomEvent::CollectionError(error)).is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+            Ok(ThreadControlEvent::AddSink(sink)) => sinks.register(sink),
+            _ => {}
+        }
+
+        match remote_source.receive() {
+            Ok(Some((_info, data))) => sinks.push(&data),
+            Ok(None) => {}
+            Err(err) => {
+                let error = data_harvester::CollectionError::new("Remote", err);
+                if sender.send(BottomEvent::CollectionError(error)).is_err() {
+                    break;
+                }
+
+                if let Ok((is_terminated, _wait_timeout_result)) = termination_ctrl_cvar
+                    .wait_timeout(
+                        termination_ctrl_lock.lock().unwrap(),
+                        Duration::from_millis(update_rate_in_milliseconds),
+                    )
+                {
+                    if *is_terminated {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
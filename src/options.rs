@@ -2,8 +2,10 @@ use std::{
     borrow::Cow,
     collections::{HashMap, HashSet},
     convert::TryInto,
+    net::SocketAddr,
+    path::{Path, PathBuf},
     str::FromStr,
-    time::{Duration, Instant},
+    time::{Duration, Instant, SystemTime},
 };
 
 use clap::ArgMatches;
@@ -17,15 +19,29 @@ use starship_battery::Manager;
 use typed_builder::*;
 
 use crate::{
-    app::{filter::Filter, layout_manager::*, *},
+    app::{
+        alerts::{AlertAction, AlertMetric, AlertRule, Alerts, Comparison},
+        data_harvester::remote_uptime::{RemoteHost, RemoteUptimeMethod},
+        filter::Filter,
+        key_bindings::{KeyBindingPreset, KeyBindings, DEFAULT_KEY_BINDINGS},
+        layout_manager::*,
+        *,
+    },
     canvas::{canvas_styling::CanvasColours, ColourScheme},
+    components::{
+        custom_widget::{self, Widget},
+        data_table::ColumnHeader,
+    },
     constants::*,
     units::data_units::DataUnit,
     utils::error::{self, BottomError},
     widgets::{
-        BatteryWidgetState, ConnectionsWidgetState, CpuWidgetState, DiskTableWidget,
-        MemWidgetState, NetWidgetState, ProcWidgetMode, ProcWidgetState, TempWidgetState,
-        TerminalWidgetState, UptimeWidgetState,
+        BatteryWidgetState, CgroupWidgetState, ConnectionsWidgetState, ContainerWidgetState,
+        CpuWidgetState, DashboardWidgetState, DiscoveryWidgetState, DiskTableWidget,
+        LogWidgetState, MemWidgetState, NetWidgetState, PressureWidgetState, ProcColumn,
+        ProcWidgetMode, ProcWidgetState, RaidWidgetState, ScheduledJobWidgetState, TempWidgetState,
+        TerminalWidgetState, UptimeWidgetState, WifiWidgetState, ZfsPoolWidgetState,
+        DEFAULT_PROCESS_COLUMNS,
     },
 };
 
@@ -38,10 +54,19 @@ pub struct Config {
     pub flags: Option<ConfigFlags>,
     pub colors: Option<ConfigColours>,
     pub row: Option<Vec<Row>>,
+    pub tab: Option<Vec<Tab>>,
     pub disk_filter: Option<IgnoreList>,
     pub mount_filter: Option<IgnoreList>,
     pub temp_filter: Option<IgnoreList>,
     pub net_filter: Option<IgnoreList>,
+    pub terminal: Option<ConfigTerminal>,
+    pub uptime: Option<ConfigUptime>,
+    pub connections: Option<ConfigConnections>,
+    pub server: Option<ConfigServer>,
+    pub dashboard: Option<ConfigDashboard>,
+    pub logs: Option<ConfigLogs>,
+    pub keybindings: Option<HashMap<String, Vec<String>>>,
+    pub alert: Option<Vec<ConfigAlert>>,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, TypedBuilder)]
@@ -81,6 +106,28 @@ pub struct ConfigFlags {
     pub network_use_log: Option<bool>,
     pub network_use_binary_prefix: Option<bool>,
     pub enable_gpu_memory: Option<bool>,
+    pub show_connection_count: Option<bool>,
+    pub collection_timeout_ms: Option<u64>,
+    pub slow_rate: Option<u64>,
+    pub border_type: Option<String>,
+    pub use_nerd_fonts: Option<bool>,
+    pub show_scrollbar: Option<bool>,
+    pub group_sensors: Option<bool>,
+    pub sensor_group_prefixes: Option<Vec<String>>,
+    pub sensor_types: Option<Vec<String>>,
+    pub show_cpu_frequency: Option<bool>,
+    pub highlight_throttled_cpus: Option<bool>,
+    pub process_columns: Option<Vec<String>>,
+    pub process_export_dir: Option<String>,
+    pub snapshot_dir: Option<String>,
+    pub cpu_alert_threshold: Option<u64>,
+    pub temp_alert_threshold: Option<u64>,
+    pub disk_alert_threshold: Option<u64>,
+    pub inode_alert_threshold: Option<u64>,
+    pub disable_alert_blinking: Option<bool>,
+    pub keybinding_preset: Option<String>,
+    pub disable_terminal_colors: Option<bool>,
+    pub agent_token: Option<String>,
     #[serde(with = "humantime_serde")]
     #[serde(default)]
     pub retention: Option<Duration>,
@@ -111,6 +158,9 @@ pub struct ConfigColours {
     pub high_battery_color: Option<Cow<'static, str>>,
     pub medium_battery_color: Option<Cow<'static, str>>,
     pub low_battery_color: Option<Cow<'static, str>>,
+    pub low_latency_color: Option<Cow<'static, str>>,
+    pub medium_latency_color: Option<Cow<'static, str>>,
+    pub high_latency_color: Option<Cow<'static, str>>,
 }
 
 impl ConfigColours {
@@ -129,6 +179,69 @@ fn default_as_true() -> bool {
     true
 }
 
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ConfigTerminal {
+    pub shell: Option<Vec<String>>,
+    pub history_size: Option<usize>,
+    pub save_history: Option<bool>,
+    pub timeout_secs: Option<u64>,
+    pub aliases: Option<HashMap<String, String>>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ConfigUptime {
+    pub streak_file: Option<String>,
+    pub milestones: Option<Vec<u64>>,
+    pub hosts: Option<Vec<ConfigUptimeHost>>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ConfigConnections {
+    pub hide_ipv6: Option<bool>,
+    pub collapse_ipv4_mapped: Option<bool>,
+    pub max_address_length: Option<usize>,
+    pub resolve_hostnames: Option<bool>,
+    pub hostname_ttl_secs: Option<u64>,
+    pub close_instead_of_kill: Option<bool>,
+    pub geoip_country_db: Option<String>,
+    pub geoip_asn_db: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ConfigDashboard {
+    pub agents: Option<Vec<String>>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ConfigLogs {
+    pub file: Option<String>,
+    pub max_lines: Option<usize>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ConfigServer {
+    pub prometheus: Option<bool>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ConfigAlert {
+    pub metric: String,
+    pub comparison: Option<String>,
+    pub threshold: f64,
+    #[serde(with = "humantime_serde")]
+    #[serde(default)]
+    pub duration: Option<Duration>,
+    pub action: Option<String>,
+    pub command: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ConfigUptimeHost {
+    pub name: Option<String>,
+    pub address: String,
+    pub method: Option<String>,
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct IgnoreList {
     #[serde(default = "default_as_true")]
@@ -167,9 +280,8 @@ macro_rules! is_flag_enabled {
 }
 
 pub fn build_app(
-    matches: &ArgMatches, config: &mut Config, widget_layout: &BottomLayout,
-    default_widget_id: u64, default_widget_type_option: &Option<BottomWidgetType>,
-    colours: &CanvasColours,
+    matches: &ArgMatches, config: &mut Config, layout_tabs: &[LayoutTab], default_widget_id: u64,
+    default_widget_type_option: &Option<BottomWidgetType>, colours: &CanvasColours,
 ) -> Result<App> {
     use BottomWidgetType::*;
 
@@ -199,6 +311,17 @@ pub fn build_app(
     let mut terminal_state_map: HashMap<u64, TerminalWidgetState> = HashMap::new();
     let mut uptime_state_map: HashMap<u64, UptimeWidgetState> = HashMap::new();
     let mut connection_state_map: HashMap<u64, ConnectionsWidgetState> = HashMap::new();
+    let mut dashboard_state_map: HashMap<u64, DashboardWidgetState> = HashMap::new();
+    let mut discovery_state_map: HashMap<u64, DiscoveryWidgetState> = HashMap::new();
+    let mut container_state_map: HashMap<u64, ContainerWidgetState> = HashMap::new();
+    let mut pressure_state_map: HashMap<u64, PressureWidgetState> = HashMap::new();
+    let mut wifi_state_map: HashMap<u64, WifiWidgetState> = HashMap::new();
+    let mut raid_state_map: HashMap<u64, RaidWidgetState> = HashMap::new();
+    let mut zfs_pool_state_map: HashMap<u64, ZfsPoolWidgetState> = HashMap::new();
+    let mut cgroup_state_map: HashMap<u64, CgroupWidgetState> = HashMap::new();
+    let mut scheduled_job_state_map: HashMap<u64, ScheduledJobWidgetState> = HashMap::new();
+    let mut log_state_map: HashMap<u64, LogWidgetState> = HashMap::new();
+    let mut custom_widget_state_map: HashMap<u64, Box<dyn Widget>> = HashMap::new();
 
     let autohide_timer = if autohide_time {
         Some(Instant::now())
@@ -208,7 +331,7 @@ pub fn build_app(
 
     let mut initial_widget_id: u64 = default_widget_id;
     let mut initial_widget_type = Proc;
-    let is_custom_layout = config.row.is_some();
+    let is_custom_layout = config.row.is_some() || config.tab.is_some();
     let mut used_widget_set = HashSet::new();
 
     let show_memory_as_values = is_flag_enabled!(mem_as_value, matches, config);
@@ -226,6 +349,8 @@ pub fn build_app(
         temperature_type: get_temperature(matches, config)
             .context("Update 'temperature_type' in your config file.")?,
         show_average_cpu: get_show_average_cpu(matches, config),
+        show_cpu_frequency: get_show_cpu_frequency(config),
+        highlight_throttled_cpus: get_highlight_throttled_cpus(config),
         use_dot: is_flag_enabled!(dot_marker, matches, config),
         left_legend: is_flag_enabled!(left_legend, matches, config),
         use_current_cpu_total: is_flag_enabled!(current_usage, matches, config),
@@ -245,130 +370,316 @@ pub fn build_app(
         network_scale_type,
         network_unit_type,
         network_use_binary_prefix,
+        show_connection_count: get_show_connection_count(config),
+        collection_timeout_ms: get_collection_timeout_ms(config),
+        slow_update_rate_in_milliseconds: get_slow_update_rate_in_milliseconds(config),
+        border_type: get_border_type(config)
+            .context("Update 'border_type' in your config file.")?,
+        use_nerd_fonts: get_use_nerd_fonts(config),
+        show_scrollbar: get_show_scrollbar(config),
+        group_sensors: get_group_sensors(config),
+        sensor_group_prefixes: get_sensor_group_prefixes(config),
+        sensor_types: get_sensor_types(config)
+            .context("Update 'sensor_types' in your config file.")?,
+        process_columns: get_process_columns(config)
+            .context("Update 'process_columns' in your config file.")?,
+        process_export_dir: get_process_export_dir(config),
+        snapshot_dir: get_snapshot_dir(config),
+        config_path: crate::read_config(matches.get_one::<String>("config_location"))
+            .ok()
+            .flatten(),
+        cpu_alert_threshold: get_cpu_alert_threshold(config)
+            .context("Update 'cpu_alert_threshold' in your config file.")?,
+        temp_alert_threshold: get_temp_alert_threshold(config),
+        disk_alert_threshold: get_disk_alert_threshold(config)
+            .context("Update 'disk_alert_threshold' in your config file.")?,
+        inode_alert_threshold: get_inode_alert_threshold(config)
+            .context("Update 'inode_alert_threshold' in your config file.")?,
+        disable_alert_blinking: get_disable_alert_blinking(config),
+        alert_rules: get_alert_rules(config).context("Update 'alert' in your config file.")?,
+        disable_terminal_colors: get_disable_terminal_colors(config),
+        terminal_shell: get_terminal_shell(config)
+            .context("Update 'shell' in your config file.")?,
+        terminal_history_size: get_terminal_history_size(config),
+        save_terminal_history: get_save_terminal_history(config),
+        terminal_timeout_secs: get_terminal_timeout_secs(config),
+        uptime_streak_file: get_uptime_streak_file(config),
+        uptime_milestones: config
+            .uptime
+            .as_ref()
+            .and_then(|uptime| uptime.milestones.clone())
+            .unwrap_or_else(|| DEFAULT_UPTIME_MILESTONES.to_vec()),
+        hide_ipv6_connections: config
+            .connections
+            .as_ref()
+            .and_then(|connections| connections.hide_ipv6)
+            .unwrap_or(false),
+        collapse_ipv4_mapped: config
+            .connections
+            .as_ref()
+            .and_then(|connections| connections.collapse_ipv4_mapped)
+            .unwrap_or(true),
+        max_address_length: config
+            .connections
+            .as_ref()
+            .and_then(|connections| connections.max_address_length)
+            .unwrap_or(0),
+        resolve_hostnames: config
+            .connections
+            .as_ref()
+            .and_then(|connections| connections.resolve_hostnames)
+            .unwrap_or(false),
+        hostname_ttl_secs: config
+            .connections
+            .as_ref()
+            .and_then(|connections| connections.hostname_ttl_secs)
+            .unwrap_or(DEFAULT_HOSTNAME_TTL_SECS),
+        close_instead_of_kill: config
+            .connections
+            .as_ref()
+            .and_then(|connections| connections.close_instead_of_kill)
+            .unwrap_or(false),
+        geoip_country_db: get_geoip_db(
+            config
+                .connections
+                .as_ref()
+                .and_then(|connections| connections.geoip_country_db.as_deref()),
+        )
+        .context("Update 'connections.geoip_country_db' in your config file.")?,
+        geoip_asn_db: get_geoip_db(
+            config
+                .connections
+                .as_ref()
+                .and_then(|connections| connections.geoip_asn_db.as_deref()),
+        )
+        .context("Update 'connections.geoip_asn_db' in your config file.")?,
+        serve_address: get_socket_address(matches, "serve")
+            .context("Update '--serve' to an address like 127.0.0.1:9000.")?,
+        agent_address: get_socket_address(matches, "agent")
+            .context("Update '--agent' to an address like 0.0.0.0:9100.")?,
+        headless: matches.contains_id("headless"),
+        agent_allows_commands: get_agent_allows_commands(matches, config)
+            .context("Check '--allow_commands' and the agent token.")?,
+        agent_token: get_agent_token(matches, config),
+        agent_advertises: matches.contains_id("advertise"),
+        remote_address: matches.get_one::<String>("connect").cloned(),
+        serve_prometheus: config
+            .server
+            .as_ref()
+            .and_then(|server| server.prometheus)
+            .unwrap_or(false),
+        uptime_hosts: get_uptime_hosts(config)
+            .context("Update 'uptime.hosts' in your config file.")?,
+        log_file: config
+            .logs
+            .as_ref()
+            .and_then(|logs| logs.file.as_ref())
+            .map(PathBuf::from),
+        log_max_lines: config
+            .logs
+            .as_ref()
+            .and_then(|logs| logs.max_lines)
+            .unwrap_or(DEFAULT_LOG_MAX_LINES),
+        dashboard_agents: get_dashboard_agents(config)
+            .context("Update 'dashboard.agents' in your config file.")?,
+        terminal_aliases: config
+            .terminal
+            .as_ref()
+            .and_then(|terminal| terminal.aliases.clone())
+            .unwrap_or_default(),
+        key_bindings: KeyBindings::new(
+            get_keybinding_preset(config)
+                .context("Update 'keybinding_preset' in your config file.")?,
+            &config.keybindings.clone().unwrap_or_default(),
+        )
+        .context("Update 'keybindings' in your config file.")?,
         retention_ms,
     };
 
-    for row in &widget_layout.rows {
-        for col in &row.children {
-            for col_row in &col.children {
-                for widget in &col_row.children {
-                    widget_map.insert(widget.widget_id, widget.clone());
-                    if let Some(default_widget_type) = &default_widget_type_option {
-                        if !is_custom_layout || use_basic_mode {
-                            match widget.widget_type {
-                                BasicCpu => {
-                                    if let Cpu = *default_widget_type {
-                                        initial_widget_id = widget.widget_id;
-                                        initial_widget_type = Cpu;
-                                    }
-                                }
-                                BasicMem => {
-                                    if let Mem = *default_widget_type {
-                                        initial_widget_id = widget.widget_id;
-                                        initial_widget_type = Cpu;
-                                    }
-                                }
-                                BasicNet => {
-                                    if let Net = *default_widget_type {
-                                        initial_widget_id = widget.widget_id;
-                                        initial_widget_type = Cpu;
-                                    }
-                                }
-                                _ => {
-                                    if *default_widget_type == widget.widget_type {
-                                        initial_widget_id = widget.widget_id;
-                                        initial_widget_type = widget.widget_type.clone();
-                                    }
-                                }
-                            }
+    for widget in layout_tabs.iter().flat_map(|tab| tab.layout.widgets()) {
+        widget_map.insert(widget.widget_id, widget.clone());
+        if let Some(default_widget_type) = &default_widget_type_option {
+            if !is_custom_layout || use_basic_mode {
+                match widget.widget_type {
+                    BasicCpu => {
+                        if let Cpu = *default_widget_type {
+                            initial_widget_id = widget.widget_id;
+                            initial_widget_type = Cpu;
                         }
                     }
-
-                    used_widget_set.insert(widget.widget_type.clone());
-
-                    match widget.widget_type {
-                        Cpu => {
-                            cpu_state_map.insert(
-                                widget.widget_id,
-                                CpuWidgetState::new(
-                                    &app_config_fields,
-                                    default_time_value,
-                                    autohide_timer,
-                                    colours,
-                                ),
-                            );
-                        }
-                        Mem => {
-                            mem_state_map.insert(
-                                widget.widget_id,
-                                MemWidgetState::init(default_time_value, autohide_timer),
-                            );
-                        }
-                        Net => {
-                            net_state_map.insert(
-                                widget.widget_id,
-                                NetWidgetState::init(default_time_value, autohide_timer),
-                            );
-                        }
-                        Proc => {
-                            let mode = if is_grouped {
-                                ProcWidgetMode::Grouped
-                            } else if is_default_tree {
-                                ProcWidgetMode::Tree {
-                                    collapsed_pids: Default::default(),
-                                }
-                            } else {
-                                ProcWidgetMode::Normal
-                            };
-
-                            proc_state_map.insert(
-                                widget.widget_id,
-                                ProcWidgetState::new(
-                                    &app_config_fields,
-                                    mode,
-                                    is_case_sensitive,
-                                    is_match_whole_word,
-                                    is_use_regex,
-                                    show_memory_as_values,
-                                    is_default_command,
-                                    colours,
-                                ),
-                            );
+                    BasicMem => {
+                        if let Mem = *default_widget_type {
+                            initial_widget_id = widget.widget_id;
+                            initial_widget_type = Cpu;
                         }
-                        Disk => {
-                            disk_state_map.insert(
-                                widget.widget_id,
-                                DiskTableWidget::new(&app_config_fields, colours),
-                            );
-                        }
-                        Temp => {
-                            temp_state_map.insert(
-                                widget.widget_id,
-                                TempWidgetState::new(&app_config_fields, colours),
-                            );
-                        }
-                        Battery => {
-                            battery_state_map
-                                .insert(widget.widget_id, BatteryWidgetState::default());
-                        }
-                        Terminal => {
-                            terminal_state_map
-                                .insert(widget.widget_id, TerminalWidgetState::default());
-                        }
-                        Uptime => {
-                            uptime_state_map.insert(widget.widget_id, UptimeWidgetState::default());
+                    }
+                    BasicNet => {
+                        if let Net = *default_widget_type {
+                            initial_widget_id = widget.widget_id;
+                            initial_widget_type = Cpu;
                         }
-                        Connections => {
-                            connection_state_map.insert(
-                                widget.widget_id,
-                                ConnectionsWidgetState::new(&app_config_fields, colours),
-                            );
+                    }
+                    _ => {
+                        if *default_widget_type == widget.widget_type {
+                            initial_widget_id = widget.widget_id;
+                            initial_widget_type = widget.widget_type.clone();
                         }
-                        _ => {}
                     }
                 }
             }
         }
+
+        used_widget_set.insert(widget.widget_type.clone());
+
+        match &widget.widget_type {
+            Cpu => {
+                cpu_state_map.insert(
+                    widget.widget_id,
+                    CpuWidgetState::new(
+                        &app_config_fields,
+                        default_time_value,
+                        autohide_timer,
+                        colours,
+                    ),
+                );
+            }
+            Mem => {
+                mem_state_map.insert(
+                    widget.widget_id,
+                    MemWidgetState::init(default_time_value, autohide_timer),
+                );
+            }
+            Net => {
+                net_state_map.insert(
+                    widget.widget_id,
+                    NetWidgetState::init(default_time_value, autohide_timer),
+                );
+            }
+            Pressure => {
+                pressure_state_map.insert(
+                    widget.widget_id,
+                    PressureWidgetState::init(default_time_value, autohide_timer),
+                );
+            }
+            Wifi => {
+                wifi_state_map.insert(
+                    widget.widget_id,
+                    WifiWidgetState::init(default_time_value, autohide_timer),
+                );
+            }
+            Proc => {
+                let mode = if is_grouped {
+                    ProcWidgetMode::Grouped
+                } else if is_default_tree {
+                    ProcWidgetMode::Tree {
+                        collapsed_pids: Default::default(),
+                    }
+                } else {
+                    ProcWidgetMode::Normal
+                };
+
+                proc_state_map.insert(
+                    widget.widget_id,
+                    ProcWidgetState::new(
+                        &app_config_fields,
+                        mode,
+                        is_case_sensitive,
+                        is_match_whole_word,
+                        is_use_regex,
+                        show_memory_as_values,
+                        is_default_command,
+                        colours,
+                    ),
+                );
+            }
+            Disk => {
+                disk_state_map.insert(
+                    widget.widget_id,
+                    DiskTableWidget::new(&app_config_fields, colours),
+                );
+            }
+            Temp => {
+                temp_state_map.insert(
+                    widget.widget_id,
+                    TempWidgetState::new(&app_config_fields, colours),
+                );
+            }
+            Battery => {
+                battery_state_map.insert(widget.widget_id, BatteryWidgetState::default());
+            }
+            Terminal => {
+                terminal_state_map.insert(
+                    widget.widget_id,
+                    TerminalWidgetState::new(app_config_fields.terminal_history_size),
+                );
+            }
+            Uptime => {
+                uptime_state_map.insert(
+                    widget.widget_id,
+                    UptimeWidgetState::new(app_config_fields.uptime_streak_file.clone()),
+                );
+            }
+            Connections => {
+                connection_state_map.insert(
+                    widget.widget_id,
+                    ConnectionsWidgetState::new(&app_config_fields, colours),
+                );
+            }
+            Dashboard => {
+                dashboard_state_map.insert(
+                    widget.widget_id,
+                    DashboardWidgetState::new(&app_config_fields, colours),
+                );
+            }
+            Discovery => {
+                discovery_state_map.insert(
+                    widget.widget_id,
+                    DiscoveryWidgetState::new(&app_config_fields, colours),
+                );
+            }
+            Raid => {
+                raid_state_map.insert(
+                    widget.widget_id,
+                    RaidWidgetState::new(&app_config_fields, colours),
+                );
+            }
+            ZfsPools => {
+                zfs_pool_state_map.insert(
+                    widget.widget_id,
+                    ZfsPoolWidgetState::new(&app_config_fields, colours),
+                );
+            }
+            Cgroups => {
+                cgroup_state_map.insert(
+                    widget.widget_id,
+                    CgroupWidgetState::new(&app_config_fields, colours),
+                );
+            }
+            ScheduledJobs => {
+                scheduled_job_state_map.insert(
+                    widget.widget_id,
+                    ScheduledJobWidgetState::new(&app_config_fields, colours),
+                );
+            }
+            Logs => {
+                log_state_map.insert(
+                    widget.widget_id,
+                    LogWidgetState::new(app_config_fields.log_max_lines),
+                );
+            }
+            Custom(name) => {
+                if let Some(custom_widget) = custom_widget::create_widget(name) {
+                    custom_widget_state_map.insert(widget.widget_id, custom_widget);
+                }
+            }
+            Containers => {
+                container_state_map.insert(
+                    widget.widget_id,
+                    ContainerWidgetState::new(&app_config_fields, colours),
+                );
+            }
+            _ => {}
+        }
     }
 
     let basic_table_widget_state = if use_basic_mode {
@@ -396,17 +707,52 @@ pub fn build_app(
         None
     };
 
-    let use_mem = used_widget_set.get(&Mem).is_some() || used_widget_set.get(&BasicMem).is_some();
+    // Everything the server has an endpoint for is collected when serving, shown or not, and an
+    // agent collects everything a viewer could show.
+    let is_agent = app_config_fields.agent_address.is_some();
+    let is_serving = is_agent || app_config_fields.serve_address.is_some();
+    // Alerts need their metrics collected even when no widget shows them.
+    let is_alerting_on = |metrics: &[AlertMetric]| {
+        app_config_fields
+            .alert_rules
+            .iter()
+            .any(|rule| metrics.contains(&rule.metric))
+    };
+    let use_mem = is_serving
+        || used_widget_set.get(&Mem).is_some()
+        || used_widget_set.get(&BasicMem).is_some()
+        || is_alerting_on(&[AlertMetric::Memory, AlertMetric::Swap]);
     let used_widgets = UsedWidgets {
-        use_cpu: used_widget_set.get(&Cpu).is_some() || used_widget_set.get(&BasicCpu).is_some(),
+        use_cpu: is_serving
+            || used_widget_set.get(&Cpu).is_some()
+            || used_widget_set.get(&BasicCpu).is_some()
+            || is_alerting_on(&[AlertMetric::Cpu, AlertMetric::Load]),
         use_mem,
         use_gpu: use_mem && get_enable_gpu_memory(matches, config),
-        use_net: used_widget_set.get(&Net).is_some() || used_widget_set.get(&BasicNet).is_some(),
-        use_proc: used_widget_set.get(&Proc).is_some(),
-        use_disk: used_widget_set.get(&Disk).is_some(),
-        use_temp: used_widget_set.get(&Temp).is_some(),
-        use_battery: used_widget_set.get(&Battery).is_some(),
+        use_net: is_serving
+            || used_widget_set.get(&Net).is_some()
+            || used_widget_set.get(&BasicNet).is_some()
+            || is_alerting_on(&[AlertMetric::Rx, AlertMetric::Tx]),
+        use_proc: is_serving || used_widget_set.get(&Proc).is_some(),
+        use_disk: is_serving
+            || used_widget_set.get(&Disk).is_some()
+            || is_alerting_on(&[AlertMetric::Disk]),
+        use_temp: is_agent
+            || used_widget_set.get(&Temp).is_some()
+            || is_alerting_on(&[AlertMetric::Temperature]),
+        use_battery: is_agent || used_widget_set.get(&Battery).is_some(),
         use_terminal: used_widget_set.get(&Terminal).is_some(),
+        use_uptime: is_agent || used_widget_set.contains(&Uptime),
+        use_connections: is_serving || used_widget_set.contains(&Connections),
+        use_dashboard: used_widget_set.contains(&Dashboard),
+        use_discovery: used_widget_set.contains(&Discovery),
+        use_containers: used_widget_set.contains(&Containers),
+        use_pressure: is_agent || used_widget_set.contains(&Pressure),
+        use_wifi: used_widget_set.contains(&Wifi),
+        use_raid: is_agent || used_widget_set.contains(&Raid),
+        use_zfs_pools: is_agent || used_widget_set.contains(&ZfsPools),
+        use_cgroups: is_agent || used_widget_set.contains(&Cgroups),
+        use_scheduled_jobs: is_agent || used_widget_set.contains(&ScheduledJobs),
     };
 
     let disk_filter =
@@ -418,7 +764,36 @@ pub fn build_app(
     let net_filter =
         get_ignore_list(&config.net_filter).context("Update 'net_filter' in your config file")?;
 
+    let alerts = Alerts::new(&app_config_fields.alert_rules);
+
+    // Each tab starts on the default widget if it has it, or on its first widget otherwise.
+    let has_initial_widget = |tab: &LayoutTab| {
+        tab.layout
+            .widgets()
+            .any(|widget| widget.widget_id == initial_widget_id)
+    };
+    let tabs = layout_tabs
+        .iter()
+        .map(|tab| AppTab {
+            name: tab.name.clone(),
+            widget_id: if has_initial_widget(tab) {
+                initial_widget_id
+            } else {
+                tab.layout
+                    .widgets()
+                    .find(|widget| !matches!(widget.widget_type, Empty | ProcSort | ProcSearch))
+                    .or_else(|| tab.layout.widgets().next())
+                    .map_or(initial_widget_id, |widget| widget.widget_id)
+            },
+        })
+        .collect();
+    let current_tab = layout_tabs
+        .iter()
+        .position(has_initial_widget)
+        .unwrap_or_default();
+
     Ok(App::builder()
+        .alerts(alerts)
         .app_config_fields(app_config_fields)
         .cpu_state(CpuState::init(cpu_state_map))
         .mem_state(MemState::init(mem_state_map))
@@ -429,10 +804,23 @@ pub fn build_app(
         .battery_state(BatteryState::init(battery_state_map))
         .terminal_state(TerminalState::init(terminal_state_map))
         .connections_state(ConnectionsState::init(connection_state_map))
+        .dashboard_state(DashboardState::init(dashboard_state_map))
+        .discovery_state(DiscoveryState::init(discovery_state_map))
+        .container_state(ContainerState::init(container_state_map))
+        .pressure_state(PressureState::init(pressure_state_map))
+        .wifi_state(WifiState::init(wifi_state_map))
+        .raid_state(RaidState::init(raid_state_map))
+        .zfs_pool_state(ZfsPoolState::init(zfs_pool_state_map))
+        .cgroup_state(CgroupState::init(cgroup_state_map))
+        .scheduled_job_state(ScheduledJobState::init(scheduled_job_state_map))
+        .log_state(LogState::init(log_state_map))
+        .custom_widget_state(CustomWidgetState::init(custom_widget_state_map))
         .uptime_state(UptimeState::init(uptime_state_map))
         .basic_table_widget_state(basic_table_widget_state)
         .current_widget(widget_map.get(&initial_widget_id).unwrap().clone()) // TODO: [UNWRAP] - many of the unwraps are fine (like this one) but do a once-over and/or switch to expect?
         .widget_map(widget_map)
+        .tabs(tabs)
+        .current_tab(current_tab)
         .used_widgets(used_widgets)
         .is_expanded(expanded_upon_startup && !use_basic_mode)
         .filters(DataFilters {
@@ -444,24 +832,39 @@ pub fn build_app(
         .build())
 }
 
+/// Builds the layout of each tab, which is a single unnamed one unless there are `[[tab]]`
+/// sections. Widget IDs are unique across every tab.
 pub fn get_widget_layout(
     matches: &ArgMatches, config: &Config,
-) -> error::Result<(BottomLayout, u64, Option<BottomWidgetType>)> {
+) -> error::Result<(Vec<LayoutTab>, u64, Option<BottomWidgetType>)> {
     let left_legend = is_flag_enabled!(left_legend, matches, config);
 
     let (default_widget_type, mut default_widget_count) =
         get_default_widget_and_count(matches, config)?;
     let mut default_widget_id = 1;
 
-    let bottom_layout = if is_flag_enabled!(basic, matches, config) {
+    let layout_tabs = if is_flag_enabled!(basic, matches, config) {
         default_widget_id = DEFAULT_WIDGET_ID;
 
-        BottomLayout::init_basic_default(get_use_battery(matches, config))
+        vec![LayoutTab {
+            name: String::new(),
+            layout: BottomLayout::init_basic_default(get_use_battery(matches, config)),
+        }]
     } else {
         let ref_row: Vec<Row>; // Required to handle reference
-        let rows = match &config.row {
-            Some(r) => r,
-            None => {
+        let tabs: Vec<(&str, &[Row])> = match (&config.row, &config.tab) {
+            (Some(_), Some(_)) => {
+                return Err(error::BottomError::ConfigError(
+                    "please put the '[[row]]' sections under '[[tab]]' sections when using tabs, as '[[tab.row]]'."
+                        .to_string(),
+                ));
+            }
+            (None, Some(tabs)) => tabs
+                .iter()
+                .map(|tab| (tab.name.as_str(), tab.row.as_deref().unwrap_or_default()))
+                .collect(),
+            (Some(rows), None) => vec![("", rows)],
+            (None, None) => {
                 // This cannot (like it really shouldn't) fail!
                 ref_row = toml_edit::de::from_str::<Config>(if get_use_battery(matches, config) {
                     DEFAULT_BATTERY_LAYOUT
@@ -470,44 +873,61 @@ pub fn get_widget_layout(
                 })?
                 .row
                 .unwrap();
-                &ref_row
+                vec![("", &ref_row)]
             }
         };
-
-        let mut iter_id = 0; // A lazy way of forcing unique IDs *shrugs*
-        let mut total_height_ratio = 0;
-
-        let mut ret_bottom_layout = BottomLayout {
-            rows: rows
-                .iter()
-                .map(|row| {
-                    row.convert_row_to_bottom_row(
-                        &mut iter_id,
-                        &mut total_height_ratio,
-                        &mut default_widget_id,
-                        &default_widget_type,
-                        &mut default_widget_count,
-                        left_legend,
-                    )
-                })
-                .collect::<error::Result<Vec<_>>>()?,
-            total_row_height_ratio: total_height_ratio,
-        };
-
-        // Confirm that we have at least ONE widget left - if not, error out!
-        if iter_id > 0 {
-            ret_bottom_layout.get_movement_mappings();
-            // debug!("Bottom layout: {:#?}", ret_bottom_layout);
-
-            ret_bottom_layout
-        } else {
+        if tabs.is_empty() {
             return Err(error::BottomError::ConfigError(
-                "please have at least one widget under the '[[row]]' section.".to_string(),
+                "please have at least one '[[tab]]' section.".to_string(),
             ));
         }
+
+        let mut iter_id = 0; // A lazy way of forcing unique IDs *shrugs*
+        tabs.into_iter()
+            .map(|(name, rows)| {
+                let first_id = iter_id;
+                let mut total_height_ratio = 0;
+
+                let mut ret_bottom_layout = BottomLayout {
+                    rows: rows
+                        .iter()
+                        .map(|row| {
+                            row.convert_row_to_bottom_row(
+                                &mut iter_id,
+                                &mut total_height_ratio,
+                                &mut default_widget_id,
+                                &default_widget_type,
+                                &mut default_widget_count,
+                                left_legend,
+                            )
+                        })
+                        .collect::<error::Result<Vec<_>>>()?,
+                    total_row_height_ratio: total_height_ratio,
+                };
+
+                // Confirm that we have at least ONE widget left - if not, error out!
+                if iter_id > first_id {
+                    ret_bottom_layout.get_movement_mappings();
+                    // debug!("Bottom layout: {:#?}", ret_bottom_layout);
+
+                    Ok(LayoutTab {
+                        name: name.to_string(),
+                        layout: ret_bottom_layout,
+                    })
+                } else if name.is_empty() {
+                    Err(error::BottomError::ConfigError(
+                        "please have at least one widget under the '[[row]]' section.".to_string(),
+                    ))
+                } else {
+                    Err(error::BottomError::ConfigError(format!(
+                        "please have at least one widget under the '[[tab.row]]' sections of the \"{name}\" tab."
+                    )))
+                }
+            })
+            .collect::<error::Result<Vec<_>>>()?
     };
 
-    Ok((bottom_layout, default_widget_id, default_widget_type))
+    Ok((layout_tabs, default_widget_id, default_widget_type))
 }
 
 fn get_update_rate_in_milliseconds(matches: &ArgMatches, config: &Config) -> error::Result<u64> {
@@ -734,6 +1154,484 @@ fn get_enable_gpu_memory(matches: &ArgMatches, config: &Config) -> bool {
     false
 }
 
+/// Whether to overlay the TCP connection count on the network graph, which defaults to true.
+fn get_show_connection_count(config: &Config) -> bool {
+    if let Some(flags) = &config.flags {
+        if let Some(show_connection_count) = flags.show_connection_count {
+            return show_connection_count;
+        }
+    }
+
+    true
+}
+
+/// How long a single collector may run before it is skipped for an update.
+fn get_collection_timeout_ms(config: &Config) -> u64 {
+    if let Some(flags) = &config.flags {
+        if let Some(collection_timeout_ms) = flags.collection_timeout_ms {
+            return collection_timeout_ms;
+        }
+    }
+
+    DEFAULT_COLLECTION_TIMEOUT_MILLISECONDS
+}
+
+/// How often slowly changing sources like disks and temperatures are harvested.
+fn get_slow_update_rate_in_milliseconds(config: &Config) -> u64 {
+    if let Some(flags) = &config.flags {
+        if let Some(slow_rate) = flags.slow_rate {
+            return slow_rate;
+        }
+    }
+
+    DEFAULT_SLOW_REFRESH_RATE_IN_MILLISECONDS
+}
+
+fn get_border_type(config: &Config) -> error::Result<BorderType> {
+    if let Some(flags) = &config.flags {
+        if let Some(border_type) = &flags.border_type {
+            return match border_type.to_lowercase().as_str() {
+                "plain" => Ok(BorderType::Plain),
+                "rounded" => Ok(BorderType::Rounded),
+                "double" => Ok(BorderType::Double),
+                "thick" => Ok(BorderType::Thick),
+                _ => Err(BottomError::ConfigError(format!(
+                    "\"{}\" is an invalid border type, use \"<plain|rounded|double|thick>\".",
+                    border_type
+                ))),
+            };
+        }
+    }
+
+    Ok(BorderType::Plain)
+}
+
+/// Whether to draw widget corners with nerd font glyphs, which defaults to false.
+fn get_use_nerd_fonts(config: &Config) -> bool {
+    if let Some(flags) = &config.flags {
+        if let Some(use_nerd_fonts) = flags.use_nerd_fonts {
+            return use_nerd_fonts;
+        }
+    }
+
+    false
+}
+
+fn get_show_scrollbar(config: &Config) -> bool {
+    if let Some(flags) = &config.flags {
+        if let Some(show_scrollbar) = flags.show_scrollbar {
+            return show_scrollbar;
+        }
+    }
+
+    true
+}
+
+fn get_show_cpu_frequency(config: &Config) -> bool {
+    if let Some(flags) = &config.flags {
+        if let Some(show_cpu_frequency) = flags.show_cpu_frequency {
+            return show_cpu_frequency;
+        }
+    }
+
+    false
+}
+
+fn get_highlight_throttled_cpus(config: &Config) -> bool {
+    if let Some(flags) = &config.flags {
+        if let Some(highlight_throttled_cpus) = flags.highlight_throttled_cpus {
+            return highlight_throttled_cpus;
+        }
+    }
+
+    false
+}
+
+fn get_group_sensors(config: &Config) -> bool {
+    if let Some(flags) = &config.flags {
+        if let Some(group_sensors) = flags.group_sensors {
+            return group_sensors;
+        }
+    }
+
+    false
+}
+
+fn get_sensor_group_prefixes(config: &Config) -> Vec<String> {
+    if let Some(flags) = &config.flags {
+        if let Some(sensor_group_prefixes) = &flags.sensor_group_prefixes {
+            return sensor_group_prefixes.clone();
+        }
+    }
+
+    Vec::new()
+}
+
+fn get_sensor_types(
+    config: &Config,
+) -> error::Result<Vec<data_harvester::temperature::SensorType>> {
+    use data_harvester::temperature::SensorType;
+
+    if let Some(flags) = &config.flags {
+        if let Some(sensor_types) = &flags.sensor_types {
+            if sensor_types.is_empty() {
+                return Err(BottomError::ConfigError(
+                    "sensor_types must contain at least one sensor type.".to_string(),
+                ));
+            }
+
+            let mut types = Vec::with_capacity(sensor_types.len());
+            for name in sensor_types {
+                match SensorType::from_config_name(name) {
+                    Some(sensor_type) if !types.contains(&sensor_type) => types.push(sensor_type),
+                    Some(_) => {}
+                    None => {
+                        return Err(BottomError::ConfigError(format!(
+                            "\"{}\" is an invalid sensor type, use one of \"<temp|fan|voltage|power>\".",
+                            name
+                        )));
+                    }
+                }
+            }
+
+            return Ok(types);
+        }
+    }
+
+    Ok(vec![SensorType::Temperature])
+}
+
+/// Returns the configured process columns, or an empty list to use the default columns.
+fn get_process_columns(config: &Config) -> error::Result<Vec<String>> {
+    if let Some(flags) = &config.flags {
+        if let Some(process_columns) = &flags.process_columns {
+            if process_columns.is_empty() {
+                return Err(BottomError::ConfigError(
+                    "process_columns must contain at least one column.".to_string(),
+                ));
+            }
+
+            if let Some(name) = process_columns
+                .iter()
+                .find(|name| ProcColumn::from_config_name(name).is_none())
+            {
+                return Err(BottomError::ConfigError(format!(
+                    "\"{}\" is an invalid process column, use one of \"<pid|name|command|cpu%|mem%|mem|r/s|w/s|t.read|t.write|user|state>\".",
+                    name
+                )));
+            }
+
+            return Ok(process_columns.clone());
+        }
+    }
+
+    Ok(Vec::new())
+}
+
+/// The directory process table exports are saved in, which defaults to the current one.
+fn get_process_export_dir(config: &Config) -> PathBuf {
+    config
+        .flags
+        .as_ref()
+        .and_then(|flags| flags.process_export_dir.as_ref())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+/// The directory snapshots are saved in, which defaults to the current one.
+fn get_snapshot_dir(config: &Config) -> PathBuf {
+    config
+        .flags
+        .as_ref()
+        .and_then(|flags| flags.snapshot_dir.as_ref())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+/// The keys to start from before the `[keybindings]` section, which defaults to bottom's own.
+fn get_keybinding_preset(config: &Config) -> error::Result<KeyBindingPreset> {
+    match config
+        .flags
+        .as_ref()
+        .and_then(|flags| flags.keybinding_preset.as_ref())
+    {
+        Some(preset) => preset.parse(),
+        None => Ok(KeyBindingPreset::Default),
+    }
+}
+
+/// The average CPU usage, in percent, past which CPU widgets are marked as alerting.
+fn get_cpu_alert_threshold(config: &Config) -> error::Result<u64> {
+    if let Some(flags) = &config.flags {
+        if let Some(cpu_alert_threshold) = flags.cpu_alert_threshold {
+            return get_percentage_threshold(cpu_alert_threshold);
+        }
+    }
+
+    Ok(DEFAULT_CPU_ALERT_THRESHOLD)
+}
+
+/// The temperature, in Celsius, past which temperature widgets are marked as alerting.
+fn get_temp_alert_threshold(config: &Config) -> u64 {
+    if let Some(flags) = &config.flags {
+        if let Some(temp_alert_threshold) = flags.temp_alert_threshold {
+            return temp_alert_threshold;
+        }
+    }
+
+    DEFAULT_TEMP_ALERT_THRESHOLD
+}
+
+/// The usage of any disk, in percent, past which disk widgets are marked as alerting.
+fn get_disk_alert_threshold(config: &Config) -> error::Result<u64> {
+    if let Some(flags) = &config.flags {
+        if let Some(disk_alert_threshold) = flags.disk_alert_threshold {
+            return get_percentage_threshold(disk_alert_threshold);
+        }
+    }
+
+    Ok(DEFAULT_DISK_ALERT_THRESHOLD)
+}
+
+/// The inode usage of any disk, in percent, past which its row is coloured red and disk widgets
+/// are marked as alerting.
+fn get_inode_alert_threshold(config: &Config) -> error::Result<u64> {
+    if let Some(flags) = &config.flags {
+        if let Some(inode_alert_threshold) = flags.inode_alert_threshold {
+            return get_percentage_threshold(inode_alert_threshold);
+        }
+    }
+
+    Ok(DEFAULT_INODE_ALERT_THRESHOLD)
+}
+
+fn get_percentage_threshold(threshold: u64) -> error::Result<u64> {
+    if threshold > 100 {
+        Err(BottomError::ConfigError(
+            "set your alert threshold to be at most 100.".to_string(),
+        ))
+    } else {
+        Ok(threshold)
+    }
+}
+
+/// Whether to keep alerting widget borders a steady colour instead of blinking, which defaults
+/// to false.
+fn get_disable_alert_blinking(config: &Config) -> bool {
+    if let Some(flags) = &config.flags {
+        if let Some(disable_alert_blinking) = flags.disable_alert_blinking {
+            return disable_alert_blinking;
+        }
+    }
+
+    false
+}
+
+fn get_disable_terminal_colors(config: &Config) -> bool {
+    if let Some(flags) = &config.flags {
+        if let Some(disable_terminal_colors) = flags.disable_terminal_colors {
+            return disable_terminal_colors;
+        }
+    }
+
+    false
+}
+
+fn get_terminal_shell(config: &Config) -> error::Result<Vec<String>> {
+    if let Some(shell) = config
+        .terminal
+        .as_ref()
+        .and_then(|terminal| terminal.shell.as_ref())
+    {
+        if shell.is_empty() {
+            return Err(BottomError::ConfigError(
+                "shell must contain at least the program to run.".to_string(),
+            ));
+        }
+
+        return Ok(shell.clone());
+    }
+
+    Ok(DEFAULT_TERMINAL_SHELL.map(String::from).to_vec())
+}
+
+fn get_terminal_history_size(config: &Config) -> usize {
+    config
+        .terminal
+        .as_ref()
+        .and_then(|terminal| terminal.history_size)
+        .unwrap_or(DEFAULT_TERMINAL_HISTORY_SIZE)
+}
+
+fn get_save_terminal_history(config: &Config) -> bool {
+    config
+        .terminal
+        .as_ref()
+        .and_then(|terminal| terminal.save_history)
+        .unwrap_or(true)
+}
+
+fn get_terminal_timeout_secs(config: &Config) -> u64 {
+    config
+        .terminal
+        .as_ref()
+        .and_then(|terminal| terminal.timeout_secs)
+        .unwrap_or(0)
+}
+
+/// The file the uptime streak is kept in, which defaults to one in bottom's config directory.
+fn get_uptime_streak_file(config: &Config) -> Option<PathBuf> {
+    match config
+        .uptime
+        .as_ref()
+        .and_then(|uptime| uptime.streak_file.as_ref())
+    {
+        Some(streak_file) => Some(PathBuf::from(streak_file)),
+        None => dirs::config_dir().map(|config_dir| config_dir.join(DEFAULT_UPTIME_STREAK_FILE)),
+    }
+}
+
+/// The alerts set up under `[[alert]]`. An alert goes off as soon as its metric is past the
+/// threshold unless given a duration, and runs its command if it has one and no other action.
+fn get_alert_rules(config: &Config) -> error::Result<Vec<AlertRule>> {
+    config
+        .alert
+        .iter()
+        .flatten()
+        .map(|alert| {
+            let action = alert
+                .action
+                .as_deref()
+                .unwrap_or(if alert.command.is_some() {
+                    "command"
+                } else {
+                    "show"
+                });
+            Ok(AlertRule {
+                metric: alert.metric.parse().map_err(BottomError::ConfigError)?,
+                comparison: match &alert.comparison {
+                    Some(comparison) => comparison.parse().map_err(BottomError::ConfigError)?,
+                    None => Comparison::default(),
+                },
+                threshold: alert.threshold,
+                duration: alert.duration.unwrap_or_default(),
+                action: AlertAction::new(action, alert.command.as_deref())
+                    .map_err(BottomError::ConfigError)?,
+            })
+        })
+        .collect()
+}
+
+/// The other hosts to show in uptime widgets, each named after its address unless given a name.
+fn get_uptime_hosts(config: &Config) -> error::Result<Vec<RemoteHost>> {
+    config
+        .uptime
+        .as_ref()
+        .and_then(|uptime| uptime.hosts.as_ref())
+        .map(|hosts| {
+            hosts
+                .iter()
+                .map(|host| {
+                    Ok(RemoteHost {
+                        name: host.name.clone().unwrap_or_else(|| host.address.clone()),
+                        address: host.address.clone(),
+                        method: match &host.method {
+                            Some(method) => method.parse().map_err(BottomError::ConfigError)?,
+                            None => RemoteUptimeMethod::default(),
+                        },
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_else(|| Ok(Vec::new()))
+}
+
+/// The agents for the dashboard widget, which each need a port to connect to.
+fn get_dashboard_agents(config: &Config) -> error::Result<Vec<String>> {
+    let agents = config
+        .dashboard
+        .as_ref()
+        .and_then(|dashboard| dashboard.agents.clone())
+        .unwrap_or_default();
+
+    for agent in &agents {
+        let has_port = agent
+            .rsplit_once(':')
+            .is_some_and(|(host, port)| !host.is_empty() && port.parse::<u16>().is_ok());
+        if !has_port {
+            return Err(BottomError::ConfigError(format!(
+                "'{agent}' is not an agent address like host:port."
+            )));
+        }
+    }
+
+    Ok(agents)
+}
+
+fn get_socket_address(matches: &ArgMatches, arg: &str) -> error::Result<Option<SocketAddr>> {
+    matches
+        .get_one::<String>(arg)
+        .map(|address| {
+            address.parse().map_err(|_| {
+                BottomError::ConfigError(format!(
+                    "'{address}' is not a valid address to listen on."
+                ))
+            })
+        })
+        .transpose()
+}
+
+/// The secret shared by an agent and its viewers, from `--agent_token` or the config file. An empty
+/// token counts as none.
+fn get_agent_token(matches: &ArgMatches, config: &Config) -> Option<String> {
+    matches
+        .get_one::<String>("agent_token")
+        .or_else(|| {
+            config
+                .flags
+                .as_ref()
+                .and_then(|flags| flags.agent_token.as_ref())
+        })
+        .filter(|token| !token.is_empty())
+        .cloned()
+}
+
+/// Whether an agent runs viewers' commands, which it only does with a token to check them against,
+/// and only on a loopback address unless commands over the network are allowed too.
+fn get_agent_allows_commands(matches: &ArgMatches, config: &Config) -> error::Result<bool> {
+    let allows_commands = matches.contains_id("allow_commands");
+    if !allows_commands {
+        return Ok(false);
+    }
+
+    if get_agent_token(matches, config).is_none() {
+        return Err(BottomError::ConfigError(
+            "--allow_commands needs a token that viewers have to send along with their commands."
+                .to_string(),
+        ));
+    }
+    let is_on_network =
+        get_socket_address(matches, "agent")?.is_some_and(|address| !address.ip().is_loopback());
+    if is_on_network && !matches.contains_id("allow_network_commands") {
+        return Err(BottomError::ConfigError(
+            "--allow_commands only works on a loopback address, as commands are sent unencrypted, \
+            unless --allow_network_commands is also set."
+                .to_string(),
+        ));
+    }
+    Ok(true)
+}
+
+/// A MaxMind database to look up remote addresses in, which has to exist if it is set.
+fn get_geoip_db(path: Option<&str>) -> error::Result<Option<PathBuf>> {
+    match path {
+        Some(path) if !Path::new(path).is_file() => {
+            Err(BottomError::ConfigError(format!("'{path}' is not a file.")))
+        }
+        path => Ok(path.map(PathBuf::from)),
+    }
+}
+
 fn get_ignore_list(ignore_list: &Option<IgnoreList>) -> error::Result<Option<Filter>> {
     if let Some(ignore_list) = ignore_list {
         let list: Result<Vec<_>, _> = ignore_list
@@ -842,14 +1740,524 @@ fn get_retention_ms(matches: &ArgMatches, config: &Config) -> error::Result<u64>
     }
 }
 
+/// Notices when the config file was written to, so it can be applied without a restart.
+pub struct ConfigWatcher {
+    path: PathBuf,
+    last_stamp: Option<(SystemTime, u64)>,
+}
+
+impl ConfigWatcher {
+    pub fn new(path: PathBuf) -> Self {
+        let last_stamp = Self::stamp(&path);
+        ConfigWatcher { path, last_stamp }
+    }
+
+    fn stamp(path: &Path) -> Option<(SystemTime, u64)> {
+        let metadata = std::fs::metadata(path).ok()?;
+        Some((metadata.modified().ok()?, metadata.len()))
+    }
+
+    /// Whether the file changed since this was last called. A missing file doesn't count, as
+    /// editors that save by replacing the file briefly remove it.
+    pub fn has_changed(&mut self) -> bool {
+        match Self::stamp(&self.path) {
+            Some(stamp) if Some(stamp) != self.last_stamp => {
+                self.last_stamp = Some(stamp);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// A one-line description of every config file flag, used to annotate the generated config.
+const FLAG_DESCRIPTIONS: &[(&str, &str)] = &[
+    ("hide_avg_cpu", "Whether to hide the average cpu entry."),
+    ("dot_marker", "Whether to use dot markers rather than braille."),
+    ("temperature_type", "The temperature unit, one of \"c\", \"f\", \"k\", \"celsius\", \"fahrenheit\" or \"kelvin\"."),
+    ("rate", "The update rate of the application in milliseconds, at least 250."),
+    ("left_legend", "Whether to put the CPU legend to the left."),
+    ("current_usage", "Whether to set CPU% on a process to be based on the total CPU or just current usage."),
+    ("unnormalized_cpu", "Whether to show process CPU% without dividing it by the number of cores."),
+    ("group_processes", "Whether to group processes with the same name together by default."),
+    ("case_sensitive", "Whether to make process searching case sensitive by default."),
+    ("whole_word", "Whether to make process searching look for matching the entire word by default."),
+    ("regex", "Whether to make process searching use regex by default."),
+    ("basic", "Whether to use basic mode."),
+    ("default_time_value", "The default time interval of graphs in milliseconds, at least 30000."),
+    ("time_delta", "The time delta on each zoom in/out action in milliseconds, at least 1000."),
+    ("autohide_time", "Whether to hide the time scale of graphs shortly after zooming."),
+    ("hide_time", "Whether to hide the time scale of graphs."),
+    ("default_widget_type", "The type of widget selected on startup, one of the layout widget types."),
+    ("default_widget_count", "Which widget of default_widget_type is selected on startup, starting from 1."),
+    ("expanded_on_startup", "Whether to expand the selected widget upon starting the app."),
+    ("use_old_network_legend", "Whether to use the old network legend style."),
+    ("hide_table_gap", "Whether to remove the space below table headers."),
+    ("battery", "Whether to show the battery widget."),
+    ("disable_click", "Whether to disable mouse clicks."),
+    ("no_write", "Whether to never write to this config file."),
+    ("color", "The built-in theme, one of \"default\", \"default-light\", \"gruvbox\", \"gruvbox-light\", \"nord\" or \"nord-light\"."),
+    ("mem_as_value", "Whether to show memory values in the process widget as values by default."),
+    ("tree", "Whether to show tree mode by default in the process widget."),
+    ("show_table_scroll_position", "Whether to show where in the list you are in table widgets."),
+    ("process_command", "Whether to show processes as their commands by default in the process widget."),
+    ("disable_advanced_kill", "Whether to hide advanced options to stop a process on Unix-like systems."),
+    ("network_use_bytes", "Whether to display the network widget using bytes."),
+    ("network_use_log", "Whether to display the network widget with a log scale."),
+    ("network_use_binary_prefix", "Whether to display the network widget with binary prefixes."),
+    ("enable_gpu_memory", "Whether to show GPU memory."),
+    ("show_connection_count", "Whether to overlay the TCP connection count on the expanded network graph."),
+    ("collection_timeout_ms", "How long, in milliseconds, a single collector can run before it is skipped for an update."),
+    ("slow_rate", "How often, in milliseconds, temperatures, disks, containers, RAID arrays, ZFS pools and cgroups are harvested."),
+    ("border_type", "The kind of line used for widget borders, one of \"plain\", \"rounded\", \"double\" or \"thick\"."),
+    ("use_nerd_fonts", "Whether to draw widget corners with nerd font glyphs."),
+    ("show_scrollbar", "Whether to show a scrollbar in tables with more entries than can fit on screen."),
+    ("group_sensors", "Whether to group temperature sensors by the hardware they belong to."),
+    ("sensor_group_prefixes", "Sensor name prefixes to group temperature sensors by, before falling back to the first word of the name."),
+    ("show_cpu_frequency", "Whether to show the clock and scaling governor of each core in the CPU widget's legend."),
+    ("highlight_throttled_cpus", "Whether to highlight cores in the CPU widget's legend that were thermally throttled since the last update."),
+    ("sensor_types", "Which kinds of sensors the temperature widget shows, from \"temp\", \"fan\", \"voltage\" and \"power\"."),
+    ("process_columns", "Which columns the process widget shows, and in what order."),
+    ("process_export_dir", "The directory process table exports are saved in, relative to where bottom was started."),
+    ("snapshot_dir", "The directory snapshots of all collected data are saved in, relative to where bottom was started."),
+    ("cpu_alert_threshold", "The average CPU usage, in percent, past which the CPU widget's border blinks."),
+    ("temp_alert_threshold", "The temperature, in Celsius, past which the temperature widget's border blinks."),
+    ("disk_alert_threshold", "The usage of any disk, in percent, past which the disk widget's border blinks."),
+    ("inode_alert_threshold", "The inode usage of any disk, in percent, past which its row turns red and the disk widget's border blinks."),
+    ("disable_alert_blinking", "Whether to keep the border of an alerting widget a steady colour instead of blinking."),
+    ("keybinding_preset", "The keys to start from before [keybindings], one of \"default\" or \"vim\"."),
+    ("disable_terminal_colors", "Whether to strip colours from the output of terminal widget commands."),
+    ("agent_token", "The secret an agent started with --allow_commands and its viewers share, which commands have to carry."),
+    ("retention", "How much data is stored at once in terms of time, at least 1m."),
+];
+
+/// A one-line description of every terminal option, used to annotate the generated config.
+const TERMINAL_DESCRIPTIONS: &[(&str, &str)] = &[
+    ("shell", "The shell commands are run through, which depends on the platform."),
+    ("history_size", "How many past commands each terminal widget remembers."),
+    ("save_history", "Whether to save each terminal widget's past commands next to the config file when exiting."),
+    ("timeout_secs", "How many seconds commands may run before they are killed, or 0 for no limit."),
+];
+
+/// A one-line description of every config file colour, used to annotate the generated config.
+const COLOUR_DESCRIPTIONS: &[(&str, &str)] = &[
+    ("table_header_color", "The colour of table headers."),
+    (
+        "all_cpu_color",
+        "The colour of the \"All\" entry in the CPU legend.",
+    ),
+    ("avg_cpu_color", "The colour of the average CPU entry."),
+    (
+        "cpu_core_colors",
+        "The colours cores will use in the CPU legend and graph.",
+    ),
+    (
+        "ram_color",
+        "The colour RAM will use in the memory legend and graph.",
+    ),
+    (
+        "swap_color",
+        "The colour SWAP will use in the memory legend and graph.",
+    ),
+    (
+        "arc_color",
+        "The colour ARC will use in the memory legend and graph.",
+    ),
+    (
+        "gpu_core_colors",
+        "The colours GPUs will use in the memory legend and graph.",
+    ),
+    (
+        "rx_color",
+        "The colour rx will use in the network legend and graph.",
+    ),
+    (
+        "tx_color",
+        "The colour tx will use in the network legend and graph.",
+    ),
+    (
+        "rx_total_color",
+        "The colour of the total rx in basic mode.",
+    ),
+    (
+        "tx_total_color",
+        "The colour of the total tx in basic mode.",
+    ),
+    (
+        "border_color",
+        "The colour of the border of unselected widgets.",
+    ),
+    (
+        "highlighted_border_color",
+        "The colour of the border of selected widgets.",
+    ),
+    ("disabled_text_color", "The colour of disabled text."),
+    ("text_color", "The colour of most text."),
+    (
+        "selected_text_color",
+        "The colour of text that is selected.",
+    ),
+    (
+        "selected_bg_color",
+        "The background colour of text that is selected.",
+    ),
+    (
+        "widget_title_color",
+        "The colour of the label each widget has.",
+    ),
+    ("graph_color", "The colour of the lines and text of graphs."),
+    (
+        "high_battery_color",
+        "The colour of the battery when mostly charged.",
+    ),
+    (
+        "medium_battery_color",
+        "The colour of the battery when half charged.",
+    ),
+    (
+        "low_battery_color",
+        "The colour of the battery when almost empty, also used for alerting widget borders.",
+    ),
+    (
+        "low_latency_color",
+        "The colour of fast disk read/write latencies.",
+    ),
+    (
+        "medium_latency_color",
+        "The colour of slow disk read/write latencies.",
+    ),
+    (
+        "high_latency_color",
+        "The colour of very slow disk read/write latencies.",
+    ),
+];
+
+/// Returns what every flag is treated as when it isn't set. Every field is listed, so adding a
+/// flag without a default here fails to compile.
+fn default_flags() -> ConfigFlags {
+    ConfigFlags {
+        hide_avg_cpu: Some(false),
+        dot_marker: Some(false),
+        temperature_type: Some("celsius".to_string()),
+        rate: Some(DEFAULT_REFRESH_RATE_IN_MILLISECONDS),
+        left_legend: Some(false),
+        current_usage: Some(false),
+        unnormalized_cpu: Some(false),
+        group_processes: Some(false),
+        case_sensitive: Some(false),
+        whole_word: Some(false),
+        regex: Some(false),
+        basic: Some(false),
+        default_time_value: Some(DEFAULT_TIME_MILLISECONDS),
+        time_delta: Some(TIME_CHANGE_MILLISECONDS),
+        autohide_time: Some(false),
+        hide_time: Some(false),
+        default_widget_type: Some("proc".to_string()),
+        default_widget_count: Some(1),
+        expanded_on_startup: Some(false),
+        use_old_network_legend: Some(false),
+        hide_table_gap: Some(false),
+        battery: Some(false),
+        disable_click: Some(false),
+        no_write: Some(false),
+        color: Some("default".to_string()),
+        mem_as_value: Some(false),
+        tree: Some(false),
+        show_table_scroll_position: Some(false),
+        process_command: Some(false),
+        disable_advanced_kill: Some(false),
+        network_use_bytes: Some(false),
+        network_use_log: Some(false),
+        network_use_binary_prefix: Some(false),
+        enable_gpu_memory: Some(false),
+        show_connection_count: Some(true),
+        collection_timeout_ms: Some(DEFAULT_COLLECTION_TIMEOUT_MILLISECONDS),
+        slow_rate: Some(DEFAULT_SLOW_REFRESH_RATE_IN_MILLISECONDS),
+        border_type: Some("plain".to_string()),
+        use_nerd_fonts: Some(false),
+        show_scrollbar: Some(true),
+        group_sensors: Some(false),
+        sensor_group_prefixes: Some(Vec::new()),
+        sensor_types: Some(vec!["temp".to_string()]),
+        show_cpu_frequency: Some(false),
+        highlight_throttled_cpus: Some(false),
+        process_columns: Some(
+            DEFAULT_PROCESS_COLUMNS
+                .iter()
+                .map(|column| column.text().to_string())
+                .collect(),
+        ),
+        process_export_dir: Some(".".to_string()),
+        snapshot_dir: Some(".".to_string()),
+        cpu_alert_threshold: Some(DEFAULT_CPU_ALERT_THRESHOLD),
+        temp_alert_threshold: Some(DEFAULT_TEMP_ALERT_THRESHOLD),
+        disk_alert_threshold: Some(DEFAULT_DISK_ALERT_THRESHOLD),
+        inode_alert_threshold: Some(DEFAULT_INODE_ALERT_THRESHOLD),
+        disable_alert_blinking: Some(false),
+        keybinding_preset: Some("default".to_string()),
+        disable_terminal_colors: Some(false),
+        agent_token: None,
+        retention: Some(Duration::from_secs(10 * 60)),
+    }
+}
+
+/// Writes each field of `table` as a commented out `key = value` line, preceded by its
+/// description and type.
+fn write_annotated_table<T: Serialize>(
+    config_text: &mut String, table: &T, descriptions: &[(&str, &str)],
+) {
+    let document =
+        toml_edit::ser::to_document(table).expect("config tables should always serialize");
+
+    for (key, value) in document.iter() {
+        let description = descriptions
+            .iter()
+            .find(|(name, _)| *name == key)
+            .map(|(_, description)| *description)
+            .unwrap_or_default();
+
+        config_text.push_str(&format!("# {description} ({})\n", value.type_name()));
+        config_text.push_str(&format!("#{key} = {}\n", value.to_string().trim()));
+    }
+}
+
+/// Generates a config file with every setting commented out and set to its default, along with
+/// a description of each.
+pub fn generate_annotated_config() -> String {
+    let mut config_text = String::from(CONFIG_TOP_HEAD);
+
+    config_text.push_str(CONFIG_DISPLAY_OPTIONS_HEAD);
+    config_text.push_str("[flags]\n");
+    write_annotated_table(&mut config_text, &default_flags(), FLAG_DESCRIPTIONS);
+
+    config_text.push_str(CONFIG_COLOUR_HEAD);
+    config_text.push_str("#[colors]\n");
+    write_annotated_table(
+        &mut config_text,
+        &*DEFAULT_COLOUR_PALETTE,
+        COLOUR_DESCRIPTIONS,
+    );
+
+    config_text.push_str(CONFIG_LAYOUT_HEAD);
+    for line in DEFAULT_LAYOUT.trim().lines() {
+        config_text.push_str(&format!("#{line}\n"));
+    }
+
+    let example_filter = |list: &[&str], regex: bool| IgnoreList {
+        is_list_ignored: true,
+        list: list.iter().map(ToString::to_string).collect(),
+        regex,
+        case_sensitive: false,
+        whole_word: false,
+    };
+    let example_filters = [
+        (
+            "disk_filter",
+            example_filter(&["/dev/sda\\d+", "/dev/nvme0n1p2"], true),
+        ),
+        ("mount_filter", example_filter(&["/mnt/.*", "/boot"], true)),
+        ("temp_filter", example_filter(&["cpu", "wifi"], false)),
+        ("net_filter", example_filter(&["virbr0.*"], true)),
+    ];
+
+    config_text.push_str(CONFIG_TERMINAL_HEAD);
+    config_text.push_str("#[terminal]\n");
+    let default_terminal = ConfigTerminal {
+        shell: Some(DEFAULT_TERMINAL_SHELL.map(String::from).to_vec()),
+        history_size: Some(DEFAULT_TERMINAL_HISTORY_SIZE),
+        save_history: Some(true),
+        timeout_secs: Some(0),
+        aliases: None,
+    };
+    write_annotated_table(&mut config_text, &default_terminal, TERMINAL_DESCRIPTIONS);
+    config_text.push_str(
+        "# Short names for long commands, where {pid} and {selected} are the PID and name of the process\n\
+        # highlighted in the process widget.\n\
+        #[terminal.aliases]\n\
+        #logs = \"journalctl -f -n 100\"\n\
+        #trace = \"strace -f -p {pid}\"\n\n",
+    );
+
+    config_text.push_str(CONFIG_KEYBINDINGS_HEAD);
+    config_text.push_str("#[keybindings]\n");
+    for (_, name, keys) in DEFAULT_KEY_BINDINGS {
+        let keys = keys
+            .iter()
+            .map(|key| format!("\"{key}\""))
+            .collect::<Vec<_>>();
+        config_text.push_str(&format!("#{name} = [{}]\n", keys.join(", ")));
+    }
+    config_text.push('\n');
+
+    config_text.push_str(
+        "# Alerts go off once a metric has been past its threshold for the duration, if one is given.\n\
+        # They are listed along the bottom, and the borders of widgets showing the metric blink. The\n\
+        # metric is one of \"cpu\", \"mem\", \"swap\", \"temp\", \"disk\", \"load\", \"rx\" or \"tx\", and the\n\
+        # action one of \"show\" (the default), \"bell\" or \"command\", which runs the given command.\n\
+        #[[alert]]\n\
+        #metric = \"cpu\"\n\
+        #comparison = \">\"\n\
+        #threshold = 90.0\n\
+        #duration = \"30s\"\n\
+        #action = \"bell\"\n\
+        #[[alert]]\n\
+        #metric = \"disk\"\n\
+        #threshold = 95.0\n\
+        #command = \"notify-send 'A disk is almost full'\"\n\n",
+    );
+
+    config_text.push_str("#[uptime]\n");
+    let default_uptime = ConfigUptime {
+        streak_file: get_uptime_streak_file(&Config::default())
+            .map(|path| path.to_string_lossy().to_string()),
+        milestones: Some(DEFAULT_UPTIME_MILESTONES.to_vec()),
+        hosts: None,
+    };
+    write_annotated_table(
+        &mut config_text,
+        &default_uptime,
+        &[
+            (
+                "streak_file",
+                "The file the uptime widget keeps its longest streak and past boots in.",
+            ),
+            (
+                "milestones",
+                "Uptimes, in days, on which the uptime widget's border blinks for the day.",
+            ),
+        ],
+    );
+    config_text.push_str(
+        "# Other hosts to show in the uptime widget. Hosts checked over \"ssh\" (the default) show their\n\
+        # uptime, but need key-based authentication, while those checked with \"ping\" only show how long\n\
+        # they have been reachable for.\n\
+        #[[uptime.hosts]]\n\
+        #name = \"backup\"\n\
+        #address = \"admin@backup.lan\"\n\
+        #[[uptime.hosts]]\n\
+        #address = \"192.168.1.1\"\n\
+        #method = \"ping\"\n\n",
+    );
+
+    config_text.push_str("#[connections]\n");
+    let default_connections = ConfigConnections {
+        hide_ipv6: Some(false),
+        collapse_ipv4_mapped: Some(true),
+        max_address_length: Some(0),
+        resolve_hostnames: Some(false),
+        hostname_ttl_secs: Some(DEFAULT_HOSTNAME_TTL_SECS),
+        close_instead_of_kill: Some(false),
+        geoip_country_db: None,
+        geoip_asn_db: None,
+    };
+    write_annotated_table(
+        &mut config_text,
+        &default_connections,
+        &[
+            ("hide_ipv6", "Whether to hide IPv6 sockets in the connections widget."),
+            (
+                "collapse_ipv4_mapped",
+                "Whether to show IPv4-mapped IPv6 addresses, like ::ffff:10.0.0.1, as plain IPv4 addresses.",
+            ),
+            (
+                "max_address_length",
+                "Addresses longer than this are shortened in the middle, keeping the port. 0 means no limit.",
+            ),
+            (
+                "resolve_hostnames",
+                "Whether to show the hostnames of remote addresses, looked up in the background.",
+            ),
+            (
+                "hostname_ttl_secs",
+                "How long, in seconds, a looked up hostname is kept before it is looked up again.",
+            ),
+            (
+                "close_instead_of_kill",
+                "Whether killing a connection closes just the socket rather than its process. Linux only, and needs root.",
+            ),
+        ],
+    );
+    config_text.push_str(
+        "# MaxMind databases, like the free GeoLite2 Country and ASN ones, to show where remote addresses\n\
+        # are in the connections widget. This needs bottom to be built with the \"geoip\" feature.\n\
+        #geoip_country_db = \"/usr/share/GeoIP/GeoLite2-Country.mmdb\"\n\
+        #geoip_asn_db = \"/usr/share/GeoIP/GeoLite2-ASN.mmdb\"\n",
+    );
+
+    config_text.push_str(
+        "\n# Agents, started with --agent, to show in the dashboard widget.\n\
+        #[dashboard]\n\
+        #agents = [\"rack1.lan:9100\", \"rack2.lan:9100\"]\n",
+    );
+
+    config_text.push_str("\n#[logs]\n");
+    write_annotated_table(
+        &mut config_text,
+        &ConfigLogs {
+            file: None,
+            max_lines: Some(DEFAULT_LOG_MAX_LINES),
+        },
+        &[("max_lines", "How many lines each log widget keeps.")],
+    );
+    config_text.push_str(
+        "# A file for log widgets to follow, rather than the systemd journal.\n\
+        #file = \"/var/log/syslog\"\n",
+    );
+
+    config_text.push_str("\n#[server]\n");
+    write_annotated_table(
+        &mut config_text,
+        &ConfigServer {
+            prometheus: Some(false),
+        },
+        &[(
+            "prometheus",
+            "Whether to also serve Prometheus metrics at /metrics when running with --serve.",
+        )],
+    );
+
+    config_text.push_str(CONFIG_FILTER_HEAD);
+    for (name, filter) in example_filters {
+        let filter = toml_edit::ser::to_document(&filter).expect("filters should always serialize");
+        config_text.push_str(&format!("#[{name}]\n"));
+        for (key, value) in filter.iter() {
+            config_text.push_str(&format!("#{key} = {}\n", value.to_string().trim()));
+        }
+        config_text.push('\n');
+    }
+
+    config_text
+}
+
 #[cfg(test)]
 mod test {
 
+    use std::{collections::HashMap, time::Duration};
+
     use clap::ArgMatches;
 
-    use crate::{app::App, canvas::canvas_styling::CanvasColours};
+    use crate::{
+        app::{
+            alerts::{AlertAction, Comparison},
+            data_farmer::DataCollection,
+            data_harvester::{
+                cpu::{CpuData, CpuDataType},
+                remote_uptime::RemoteUptimeMethod,
+                temperature::TempHarvest,
+            },
+            layout_manager::BottomWidgetType,
+            App,
+        },
+        canvas::canvas_styling::CanvasColours,
+        components::custom_widget::{register_widget, Widget},
+    };
 
-    use super::{get_color_scheme, get_widget_layout, Config};
+    use super::{
+        default_flags, generate_annotated_config, get_agent_allows_commands, get_agent_token,
+        get_alert_rules, get_color_scheme, get_terminal_shell, get_uptime_hosts, get_widget_layout,
+        Config, ConfigWatcher, COLOUR_DESCRIPTIONS, DEFAULT_COLOUR_PALETTE, DEFAULT_TERMINAL_SHELL,
+        FLAG_DESCRIPTIONS,
+    };
 
     fn create_app(mut config: Config, matches: ArgMatches) -> App {
         let (layout, id, ty) = get_widget_layout(&matches, &config).unwrap();
@@ -864,17 +2272,27 @@ mod test {
     #[test]
     fn verify_cli_options_build() {
         let app = crate::clap::build_app();
+        // `--allow_commands` can only be set along with a token.
+        let token_config =
+            || -> Config { toml_edit::de::from_str("[flags]\nagent_token = \"token\"").unwrap() };
 
         let default_app = {
             let app = app.clone();
-            let config = Config::default();
+            let config = token_config();
             let matches = app.get_matches_from([""]);
 
             create_app(config, matches)
         };
 
-        // Skip battery since it's tricky to test depending on the platform testing.
-        let skip = ["help", "version", "celsius", "battery"];
+        // Skip battery since it's tricky to test depending on the platform testing, and
+        // allow_network_commands since it only changes whether --allow_commands is accepted.
+        let skip = [
+            "help",
+            "version",
+            "celsius",
+            "battery",
+            "allow_network_commands",
+        ];
 
         for arg in app.get_arguments().collect::<Vec<_>>() {
             let arg_name = arg
@@ -889,7 +2307,7 @@ mod test {
 
                 let arguments = vec!["btm", &arg];
                 let app = app.clone();
-                let config = Config::default();
+                let config = token_config();
                 let matches = app.get_matches_from(arguments);
 
                 let testing_app = create_app(config, matches);
@@ -908,4 +2326,433 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn terminal_shell() {
+        assert_eq!(
+            get_terminal_shell(&Config::default()).unwrap(),
+            DEFAULT_TERMINAL_SHELL.map(String::from).to_vec()
+        );
+
+        let config: Config =
+            toml_edit::de::from_str("[terminal]\nshell = [\"zsh\", \"-c\"]").unwrap();
+        assert_eq!(get_terminal_shell(&config).unwrap(), vec!["zsh", "-c"]);
+
+        let config: Config = toml_edit::de::from_str("[terminal]\nshell = []").unwrap();
+        assert!(get_terminal_shell(&config).is_err());
+    }
+
+    #[test]
+    fn uptime_hosts() {
+        assert!(get_uptime_hosts(&Config::default()).unwrap().is_empty());
+
+        let config: Config = toml_edit::de::from_str(
+            "[[uptime.hosts]]\naddress = \"10.0.0.2\"\nmethod = \"ping\"\n\
+            [[uptime.hosts]]\nname = \"nas\"\naddress = \"me@nas\"",
+        )
+        .unwrap();
+        let hosts = get_uptime_hosts(&config).unwrap();
+        assert_eq!(hosts[0].name, "10.0.0.2");
+        assert_eq!(hosts[0].method, RemoteUptimeMethod::Ping);
+        assert_eq!(hosts[1].name, "nas");
+        assert_eq!(hosts[1].method, RemoteUptimeMethod::Ssh);
+
+        let config: Config = toml_edit::de::from_str(
+            "[[uptime.hosts]]\naddress = \"10.0.0.2\"\nmethod = \"telnet\"",
+        )
+        .unwrap();
+        assert!(get_uptime_hosts(&config).is_err());
+    }
+
+    /// Uncomments every setting in a generated config, leaving the descriptions alone.
+    fn uncomment_generated_config(config_text: &str) -> String {
+        config_text
+            .lines()
+            .map(|line| match line.strip_prefix('#') {
+                Some(setting)
+                    if setting.contains('=')
+                        || (setting.trim().starts_with('[') && setting.trim().ends_with(']')) =>
+                {
+                    setting
+                }
+                _ => line,
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn generated_config_is_described() {
+        let flags = toml_edit::ser::to_document(&default_flags()).unwrap();
+        for (key, _) in flags.iter() {
+            assert!(
+                FLAG_DESCRIPTIONS.iter().any(|(name, _)| *name == key),
+                "missing a description for the {key} flag"
+            );
+        }
+
+        let colours = toml_edit::ser::to_document(&*DEFAULT_COLOUR_PALETTE).unwrap();
+        for (key, _) in colours.iter() {
+            assert!(
+                COLOUR_DESCRIPTIONS.iter().any(|(name, _)| *name == key),
+                "missing a description for the {key} colour"
+            );
+        }
+    }
+
+    #[test]
+    fn generated_config_matches_defaults() {
+        let config_text = generate_annotated_config();
+
+        // As generated, everything but the empty flags table is commented out.
+        let config: Config = toml_edit::de::from_str(&config_text).unwrap();
+        assert!(config.flags.unwrap().process_columns.is_none());
+        assert!(config.colors.is_none() && config.row.is_none());
+
+        let uncommented: Config =
+            toml_edit::de::from_str(&uncomment_generated_config(&config_text)).unwrap();
+        assert!(uncommented.flags.is_some() && uncommented.colors.is_some());
+        assert_eq!(uncommented.row.as_ref().map(Vec::len), Some(3));
+        assert!(uncommented.disk_filter.is_some() && uncommented.net_filter.is_some());
+        assert!(uncommented.terminal.is_some());
+
+        // Filters aren't defaults, and the listed process columns are what an unset list falls
+        // back to, so drop them before comparing.
+        let mut uncommented = uncommented;
+        if let Some(flags) = &mut uncommented.flags {
+            assert_eq!(flags.process_columns.as_ref().map(Vec::len), Some(10));
+            flags.process_columns = None;
+        }
+        if let Some(terminal) = &mut uncommented.terminal {
+            assert_eq!(terminal.aliases.as_ref().map(HashMap::len), Some(2));
+            terminal.aliases = None;
+        }
+        if let Some(uptime) = &mut uncommented.uptime {
+            assert_eq!(uptime.hosts.as_ref().map(Vec::len), Some(2));
+            uptime.hosts = None;
+        }
+        if let Some(connections) = &mut uncommented.connections {
+            assert!(connections.geoip_country_db.is_some() && connections.geoip_asn_db.is_some());
+            connections.geoip_country_db = None;
+            connections.geoip_asn_db = None;
+        }
+        if let Some(dashboard) = &mut uncommented.dashboard {
+            assert_eq!(dashboard.agents.as_ref().map(Vec::len), Some(2));
+            dashboard.agents = None;
+        }
+        if let Some(logs) = &mut uncommented.logs {
+            assert!(logs.file.is_some());
+            logs.file = None;
+        }
+        assert_eq!(uncommented.alert.as_ref().map(Vec::len), Some(2));
+        let uncommented = Config {
+            alert: None,
+            disk_filter: None,
+            mount_filter: None,
+            temp_filter: None,
+            net_filter: None,
+            ..uncommented
+        };
+
+        let app = crate::clap::build_app();
+        let default_app = create_app(Config::default(), app.clone().get_matches_from([""]));
+        let generated_app = create_app(uncommented, app.get_matches_from([""]));
+
+        assert_eq!(
+            default_app.app_config_fields,
+            generated_app.app_config_fields
+        );
+        assert!(default_app
+            .proc_state
+            .widget_states
+            .iter()
+            .zip(generated_app.proc_state.widget_states.iter())
+            .all(|(a, b)| a.1.test_equality(b.1)));
+    }
+
+    #[test]
+    fn alerting_widgets() {
+        let matches = crate::clap::build_app().get_matches_from([""]);
+        let mut app = create_app(Config::default(), matches);
+
+        app.data_collection.cpu_harvest = vec![CpuData {
+            data_type: CpuDataType::Avg,
+            cpu_usage: 95.0,
+            frequency: None,
+        }];
+        app.data_collection.temp_harvest = vec![TempHarvest {
+            name: "cpu".to_string(),
+            value: 50.0,
+            ..TempHarvest::default()
+        }];
+        app.update_alerting_widgets();
+
+        let alerting_types = app
+            .alerting_widget_ids
+            .iter()
+            .map(|widget_id| app.widget_map[widget_id].widget_type.clone())
+            .collect::<Vec<_>>();
+        assert!(alerting_types.contains(&BottomWidgetType::Cpu));
+        assert!(alerting_types.contains(&BottomWidgetType::CpuLegend));
+        assert!(!alerting_types.contains(&BottomWidgetType::Temp));
+    }
+
+    #[test]
+    fn config_alerts() {
+        let config: Config = toml_edit::de::from_str(
+            "[[alert]]\nmetric = \"mem\"\nthreshold = 80.0\n\
+            [[alert]]\nmetric = \"swap\"\ncomparison = \"<\"\nthreshold = 1.0\nduration = \"1m\"\n\
+            command = \"true\"",
+        )
+        .unwrap();
+        let rules = get_alert_rules(&config).unwrap();
+        assert_eq!(rules[0].comparison, Comparison::Above);
+        assert_eq!(rules[0].action, AlertAction::Show);
+        assert_eq!(rules[1].duration, Duration::from_secs(60));
+        assert_eq!(rules[1].action, AlertAction::Command("true".to_string()));
+
+        let matches = crate::clap::build_app().get_matches_from([""]);
+        let mut app = create_app(config, matches);
+        app.data_collection.memory_harvest.use_percent = Some(85.0);
+        app.data_collection.swap_harvest.use_percent = Some(0.0);
+        app.update_alerts();
+        app.update_alerting_widgets();
+
+        assert_eq!(app.alerts.triggered().count(), 1);
+        assert!(app
+            .alerting_widget_ids
+            .iter()
+            .any(|widget_id| app.widget_map[widget_id].widget_type == BottomWidgetType::Mem));
+
+        // A rule below `cpu_alert_threshold` still flashes the widgets showing the CPU.
+        let config: Config =
+            toml_edit::de::from_str("[[alert]]\nmetric = \"cpu\"\nthreshold = 50.0").unwrap();
+        let matches = crate::clap::build_app().get_matches_from([""]);
+        let mut app = create_app(config, matches);
+        app.data_collection.cpu_harvest = vec![CpuData {
+            data_type: CpuDataType::Avg,
+            cpu_usage: 60.0,
+            frequency: None,
+        }];
+        app.update_alerts();
+        app.update_alerting_widgets();
+
+        let cpu_widget_ids = app
+            .widget_map
+            .values()
+            .filter(|widget| {
+                matches!(
+                    widget.widget_type,
+                    BottomWidgetType::Cpu | BottomWidgetType::CpuLegend
+                )
+            })
+            .map(|widget| widget.widget_id)
+            .collect::<Vec<_>>();
+        assert!(!cpu_widget_ids.is_empty());
+        assert!(cpu_widget_ids
+            .iter()
+            .all(|widget_id| app.alerting_widget_ids.contains(widget_id)));
+
+        for invalid in [
+            "metric = \"gpu\"\nthreshold = 1.0",
+            "metric = \"cpu\"\ncomparison = \"=\"\nthreshold = 1.0",
+            "metric = \"cpu\"\nthreshold = 1.0\naction = \"command\"",
+        ] {
+            let config: Config = toml_edit::de::from_str(&format!("[[alert]]\n{invalid}")).unwrap();
+            assert!(get_alert_rules(&config).is_err());
+        }
+    }
+
+    struct UpdateCounter {
+        updates: usize,
+    }
+
+    impl Widget for UpdateCounter {
+        fn title(&self) -> String {
+            format!("{} updates", self.updates)
+        }
+
+        fn draw(
+            &mut self, _: &mut tui::buffer::Buffer, _: tui::layout::Rect, _: &CanvasColours,
+            _: bool,
+        ) {
+        }
+
+        fn ingest_data(&mut self, _data: &DataCollection) {
+            self.updates += 1;
+        }
+    }
+
+    #[test]
+    fn custom_widget_layout() {
+        register_widget("update_counter", || Box::new(UpdateCounter { updates: 0 })).unwrap();
+        let config: Config = toml_edit::de::from_str(
+            "[[row]]\n[[row.child]]\ntype = \"cpu\"\n[[row.child]]\ntype = \"Update_Counter\"",
+        )
+        .unwrap();
+        let matches = crate::clap::build_app().get_matches_from([""]);
+        let mut app = create_app(config, matches);
+
+        let widget_id = app
+            .widget_map
+            .values()
+            .find(|widget| {
+                widget.widget_type == BottomWidgetType::Custom("update_counter".to_string())
+            })
+            .unwrap()
+            .widget_id;
+        assert_eq!(app.custom_widget_state.widget_states.len(), 1);
+
+        crate::process_update(&mut app, Box::default());
+        crate::process_update(&mut app, Box::default());
+        let widget = app
+            .custom_widget_state
+            .get_mut_widget_state(widget_id)
+            .unwrap();
+        assert_eq!(widget.title(), "2 updates");
+    }
+
+    #[test]
+    fn reload_keeps_widget_states() {
+        let config = || -> Config {
+            toml_edit::de::from_str(
+                "[[row]]\n[[row.child]]\ntype = \"cpu\"\n[[row.child]]\ntype = \"proc\"",
+            )
+            .unwrap()
+        };
+        let matches = crate::clap::build_app().get_matches_from([""]);
+        let mut app = create_app(config(), matches.clone());
+        let proc_widget = app
+            .widget_map
+            .values()
+            .find(|widget| widget.widget_type == BottomWidgetType::Proc)
+            .unwrap()
+            .clone();
+        app.proc_state
+            .widget_states
+            .get_mut(&proc_widget.widget_id)
+            .unwrap()
+            .selected_pids
+            .insert(1);
+        app.frozen_widget_ids.insert(proc_widget.widget_id);
+        app.current_widget = proc_widget.clone();
+
+        let mut reloaded = create_app(config(), matches.clone());
+        reloaded.carry_over(&mut app);
+        assert!(reloaded.proc_state.widget_states[&proc_widget.widget_id]
+            .selected_pids
+            .contains(&1));
+        assert!(reloaded.frozen_widget_ids.contains(&proc_widget.widget_id));
+        assert_eq!(reloaded.current_widget.widget_id, proc_widget.widget_id);
+
+        // Without the process widget, nothing of it is kept.
+        let config: Config =
+            toml_edit::de::from_str("[[row]]\n[[row.child]]\ntype = \"cpu\"").unwrap();
+        let mut without_proc = create_app(config, matches);
+        without_proc.carry_over(&mut reloaded);
+        assert!(without_proc.frozen_widget_ids.is_empty());
+        assert_ne!(
+            without_proc.current_widget.widget_type,
+            BottomWidgetType::Proc
+        );
+    }
+
+    #[test]
+    fn layout_tabs() {
+        let config: Config = toml_edit::de::from_str(
+            "[[tab]]\nname = \"overview\"\n[[tab.row]]\n[[tab.row.child]]\ntype = \"cpu\"\n\
+            [[tab]]\nname = \"processes\"\n[[tab.row]]\n[[tab.row.child]]\ntype = \"mem\"\n\
+            [[tab.row.child]]\ntype = \"proc\"\ndefault = true",
+        )
+        .unwrap();
+        let matches = crate::clap::build_app().get_matches_from([""]);
+        let (layout_tabs, _, _) = get_widget_layout(&matches, &config).unwrap();
+        assert_eq!(layout_tabs.len(), 2);
+        let tab_ids = |index: usize| {
+            layout_tabs[index]
+                .layout
+                .widgets()
+                .map(|widget| widget.widget_id)
+                .collect::<Vec<_>>()
+        };
+        assert!(tab_ids(0).iter().all(|id| !tab_ids(1).contains(id)));
+
+        let mut app = create_app(config, matches);
+        let names = app
+            .tabs
+            .iter()
+            .map(|tab| tab.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["overview", "processes"]);
+        assert_eq!(app.current_tab, 1);
+        assert_eq!(app.current_widget.widget_type, BottomWidgetType::Proc);
+        assert!(app.used_widgets.use_cpu && app.used_widgets.use_proc);
+
+        app.switch_tab(0);
+        assert_eq!(app.current_tab, 0);
+        assert_eq!(app.current_widget.widget_type, BottomWidgetType::Cpu);
+        app.switch_tab(5);
+        assert_eq!(app.current_tab, 0);
+        app.switch_tab(1);
+        assert_eq!(app.current_widget.widget_type, BottomWidgetType::Proc);
+
+        for invalid in [
+            "[[row]]\n[[row.child]]\ntype = \"cpu\"\n[[tab]]\nname = \"a\"",
+            "[[tab]]\nname = \"empty\"",
+            "tab = []",
+        ] {
+            let config: Config = toml_edit::de::from_str(invalid).unwrap();
+            let matches = crate::clap::build_app().get_matches_from([""]);
+            assert!(get_widget_layout(&matches, &config).is_err());
+        }
+    }
+
+    #[test]
+    fn agent_commands_need_a_token() {
+        let app = crate::clap::build_app();
+        let matches =
+            app.clone()
+                .get_matches_from(["", "--agent", "127.0.0.1:9100", "--allow_commands"]);
+        assert!(get_agent_allows_commands(&matches, &Config::default()).is_err());
+
+        let config: Config = toml_edit::de::from_str("[flags]\nagent_token = \"\"").unwrap();
+        assert!(get_agent_allows_commands(&matches, &config).is_err());
+
+        let config: Config = toml_edit::de::from_str("[flags]\nagent_token = \"s3\"").unwrap();
+        assert!(get_agent_allows_commands(&matches, &config).unwrap());
+
+        // Other machines can only send commands when that is asked for as well.
+        let network_args = ["", "--agent", "0.0.0.0:9100", "--allow_commands"];
+        let matches = app.clone().get_matches_from(network_args);
+        assert!(get_agent_allows_commands(&matches, &config).is_err());
+        let matches = app
+            .clone()
+            .get_matches_from(network_args.into_iter().chain(["--allow_network_commands"]));
+        assert!(get_agent_allows_commands(&matches, &config).unwrap());
+        assert_eq!(get_agent_token(&matches, &config).as_deref(), Some("s3"));
+
+        let matches = app.get_matches_from(["", "--agent_token", "cli"]);
+        assert_eq!(get_agent_token(&matches, &config).as_deref(), Some("cli"));
+        assert!(!get_agent_allows_commands(&matches, &config).unwrap());
+    }
+
+    #[test]
+    fn config_watcher() {
+        let path = std::env::temp_dir().join(format!("btm_config_watch_{}", std::process::id()));
+        std::fs::write(&path, "[flags]\n").unwrap();
+        let mut watcher = ConfigWatcher::new(path.clone());
+        assert!(!watcher.has_changed());
+
+        std::fs::write(&path, "[flags]\nrate = 500\n").unwrap();
+        assert!(watcher.has_changed());
+        assert!(!watcher.has_changed());
+
+        // Being replaced only counts once the new file is there.
+        std::fs::remove_file(&path).unwrap();
+        assert!(!watcher.has_changed());
+        std::fs::write(&path, "[flags]\n").unwrap();
+        assert!(watcher.has_changed());
+
+        std::fs::remove_file(&path).unwrap();
+    }
 }
@@ -1,8 +1,17 @@
 use serde::{Deserialize, Serialize};
+use tui::style::Style;
 
 use crate::app::layout_manager::*;
+use crate::canvas::canvas_styling::colour_utils::str_to_fg;
 use crate::error::Result;
 
+/// A named layout under a `[[tab]]` section, made of rows just like the `[[row]]` sections.
+#[derive(Clone, Deserialize, Debug, Serialize)]
+pub struct Tab {
+    pub name: String,
+    pub row: Option<Vec<Row>>,
+}
+
 /// Represents a row.  This has a length of some sort (optional) and a vector
 /// of children.
 #[derive(Clone, Deserialize, Debug, Serialize)]
@@ -34,6 +43,7 @@ impl Row {
                         let width_ratio = widget.ratio.unwrap_or(1);
                         total_col_ratio += width_ratio;
                         let widget_type = widget.widget_type.parse::<BottomWidgetType>()?;
+                        let (border_style, title_style) = widget.styles()?;
 
                         if let Some(default_widget_type_val) = default_widget_type {
                             if *default_widget_type_val == widget_type && *default_widget_count > 0
@@ -63,6 +73,8 @@ impl Row {
                                             .total_widget_ratio(20)
                                             .children(vec![
                                                 BottomWidget::builder()
+                                                    .border_style(border_style)
+                                                    .title_style(title_style)
                                                     .width_ratio(3)
                                                     .widget_type(BottomWidgetType::CpuLegend)
                                                     .widget_id(*iter_id)
@@ -73,6 +85,8 @@ impl Row {
                                                     )))
                                                     .build(),
                                                 BottomWidget::builder()
+                                                    .border_style(border_style)
+                                                    .title_style(title_style)
                                                     .width_ratio(17)
                                                     .widget_type(BottomWidgetType::Cpu)
                                                     .widget_id(cpu_id)
@@ -85,12 +99,16 @@ impl Row {
                                             .total_widget_ratio(20)
                                             .children(vec![
                                                 BottomWidget::builder()
+                                                    .border_style(border_style)
+                                                    .title_style(title_style)
                                                     .width_ratio(17)
                                                     .widget_type(BottomWidgetType::Cpu)
                                                     .widget_id(cpu_id)
                                                     .flex_grow(true)
                                                     .build(),
                                                 BottomWidget::builder()
+                                                    .border_style(border_style)
+                                                    .title_style(title_style)
                                                     .width_ratio(3)
                                                     .widget_type(BottomWidgetType::CpuLegend)
                                                     .widget_id(*iter_id)
@@ -116,6 +134,8 @@ impl Row {
                                         BottomColRow::builder()
                                             .children(vec![
                                                 BottomWidget::builder()
+                                                    .border_style(border_style)
+                                                    .title_style(title_style)
                                                     .widget_type(BottomWidgetType::ProcSort)
                                                     .widget_id(*iter_id)
                                                     .canvas_handle_width(true)
@@ -126,6 +146,8 @@ impl Row {
                                                     .width_ratio(1)
                                                     .build(),
                                                 BottomWidget::builder()
+                                                    .border_style(border_style)
+                                                    .title_style(title_style)
                                                     .widget_type(BottomWidgetType::Proc)
                                                     .widget_id(proc_id)
                                                     .width_ratio(2)
@@ -136,6 +158,8 @@ impl Row {
                                             .build(),
                                         BottomColRow::builder()
                                             .children(vec![BottomWidget::builder()
+                                                .border_style(border_style)
+                                                .title_style(title_style)
                                                 .widget_type(BottomWidgetType::ProcSearch)
                                                 .widget_id(proc_search_id)
                                                 .parent_reflector(Some((WidgetDirection::Up, 1)))
@@ -149,6 +173,8 @@ impl Row {
                                 .col_width_ratio(width_ratio)
                                 .children(vec![BottomColRow::builder()
                                     .children(vec![BottomWidget::builder()
+                                        .border_style(border_style)
+                                        .title_style(title_style)
                                         .widget_type(widget_type)
                                         .widget_id(*iter_id)
                                         .build()])
@@ -166,6 +192,7 @@ impl Row {
 
                         for widget in child {
                             let widget_type = widget.widget_type.parse::<BottomWidgetType>()?;
+                            let (border_style, title_style) = widget.styles()?;
                             *iter_id += 1;
                             let col_row_height_ratio = widget.ratio.unwrap_or(1);
                             total_col_row_ratio += col_row_height_ratio;
@@ -199,6 +226,8 @@ impl Row {
                                                 .total_widget_ratio(20)
                                                 .children(vec![
                                                     BottomWidget::builder()
+                                                        .border_style(border_style)
+                                                        .title_style(title_style)
                                                         .width_ratio(3)
                                                         .widget_type(BottomWidgetType::CpuLegend)
                                                         .widget_id(*iter_id)
@@ -209,6 +238,8 @@ impl Row {
                                                         )))
                                                         .build(),
                                                     BottomWidget::builder()
+                                                        .border_style(border_style)
+                                                        .title_style(title_style)
                                                         .width_ratio(17)
                                                         .widget_type(BottomWidgetType::Cpu)
                                                         .widget_id(cpu_id)
@@ -224,12 +255,16 @@ impl Row {
                                                 .total_widget_ratio(20)
                                                 .children(vec![
                                                     BottomWidget::builder()
+                                                        .border_style(border_style)
+                                                        .title_style(title_style)
                                                         .width_ratio(17)
                                                         .widget_type(BottomWidgetType::Cpu)
                                                         .widget_id(cpu_id)
                                                         .flex_grow(true)
                                                         .build(),
                                                     BottomWidget::builder()
+                                                        .border_style(border_style)
+                                                        .title_style(title_style)
                                                         .width_ratio(3)
                                                         .widget_type(BottomWidgetType::CpuLegend)
                                                         .widget_id(*iter_id)
@@ -253,6 +288,8 @@ impl Row {
                                         BottomColRow::builder()
                                             .children(vec![
                                                 BottomWidget::builder()
+                                                    .border_style(border_style)
+                                                    .title_style(title_style)
                                                     .widget_type(BottomWidgetType::ProcSort)
                                                     .widget_id(*iter_id)
                                                     .canvas_handle_width(true)
@@ -263,6 +300,8 @@ impl Row {
                                                     .width_ratio(1)
                                                     .build(),
                                                 BottomWidget::builder()
+                                                    .border_style(border_style)
+                                                    .title_style(title_style)
                                                     .widget_type(BottomWidgetType::Proc)
                                                     .widget_id(proc_id)
                                                     .width_ratio(2)
@@ -276,6 +315,8 @@ impl Row {
                                         BottomColRow::builder()
                                             .col_row_height_ratio(col_row_height_ratio)
                                             .children(vec![BottomWidget::builder()
+                                                .border_style(border_style)
+                                                .title_style(title_style)
                                                 .widget_type(BottomWidgetType::ProcSearch)
                                                 .widget_id(proc_search_id)
                                                 .parent_reflector(Some((WidgetDirection::Up, 1)))
@@ -288,6 +329,8 @@ impl Row {
                                     BottomColRow::builder()
                                         .col_row_height_ratio(col_row_height_ratio)
                                         .children(vec![BottomWidget::builder()
+                                            .border_style(border_style)
+                                            .title_style(title_style)
                                             .widget_type(widget_type)
                                             .widget_id(*iter_id)
                                             .build()])
@@ -352,4 +395,18 @@ pub struct FinalWidget {
     #[serde(rename = "type")]
     pub widget_type: String,
     pub default: Option<bool>,
+    pub border_color: Option<String>,
+    pub title_color: Option<String>,
+}
+
+impl FinalWidget {
+    /// Parses the border and title colours set for this widget, if any.
+    fn styles(&self) -> Result<(Option<Style>, Option<Style>)> {
+        let parse_colour = |colour: &Option<String>| colour.as_deref().map(str_to_fg).transpose();
+
+        Ok((
+            parse_colour(&self.border_color)?,
+            parse_colour(&self.title_color)?,
+        ))
+    }
 }
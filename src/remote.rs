@@ -0,0 +1,423 @@
+//! Monitoring another machine: an agent that only harvests data and streams it over TCP, for
+//! `--agent`, and the source a viewer reads that data from, for `--connect`.
+//!
+//! When a viewer connects, the agent first sends [`HANDSHAKE`], and the viewer replies with a
+//! frame holding its [`Request`]. For updates, the agent then sends a frame for every update, with
+//! the [`AgentInfo`] and [`Data`]. A frame is its length as a big-endian `u32`, followed by the
+//! message in the [`wire`] format.
+
+mod command;
+pub mod discovery;
+mod wire;
+
+use std::{
+    io::{ErrorKind, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    sync::{
+        mpsc::{self, SyncSender, TrySendError},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use sysinfo::{System, SystemExt};
+
+pub use self::command::{exit_status, CommandAccess, RemoteCommand};
+use self::wire::Wire;
+use crate::{
+    app::data_harvester::Data,
+    sinks::Sink,
+    utils::error::{self, BottomError},
+};
+
+/// Sent by an agent to every viewer that connects. The last byte is the version of the wire
+/// format, so an agent and a viewer that can't understand each other say so up front.
+const HANDSHAKE: &[u8; 8] = b"BTMAGNT\x11";
+
+/// Frames larger than this are treated as malformed rather than allocated for.
+const MAX_FRAME_LEN: usize = 64 * 1024 * 1024;
+
+/// The largest [`Request`] an agent reads from a viewer. It comes before anything is known about
+/// the viewer, so it is kept small enough that opening connections can't use up memory.
+const MAX_REQUEST_LEN: usize = 16 * 1024;
+
+/// How long a viewer waits for the handshake, and an agent waits on a slow viewer.
+const IO_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long a viewer blocks on reading before checking whether it should stop.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// What a viewer asks of an agent, right after the handshake.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Request {
+    /// Every update, for `--connect` and the dashboard widget.
+    Updates,
+    /// Runs a command line typed into the terminal widget, see [`RemoteCommand`]. The token has to
+    /// match the agent's `--agent_token` for the command to run.
+    Command { command: String, token: String },
+}
+
+/// What an agent sends back while running a viewer's command.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CommandEvent {
+    Output(Vec<u8>),
+    /// The command exited, with its raw exit status if it could be started at all.
+    Exited(Option<i32>),
+}
+
+/// What a viewer knows about the machine an agent runs on, besides the harvested data.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AgentInfo {
+    pub hostname: String,
+    pub uptime_secs: u64,
+}
+
+impl AgentInfo {
+    fn current(hostname: &str) -> Self {
+        AgentInfo {
+            hostname: hostname.to_string(),
+            uptime_secs: uptime_lib::get()
+                .map(|uptime| uptime.as_secs())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+fn encode_frame(message: &impl Wire) -> Vec<u8> {
+    let mut frame = vec![0; 4];
+    message.encode(&mut frame);
+    let len = (frame.len() - 4) as u32;
+    frame[..4].copy_from_slice(&len.to_be_bytes());
+    frame
+}
+
+/// Reads a whole frame from `stream`, waiting as long as its read timeout allows. Frames longer
+/// than `max_len` are malformed.
+fn read_message<T: Wire>(stream: &mut impl Read, peer: &str, max_len: usize) -> error::Result<T> {
+    let mut len = [0; 4];
+    stream.read_exact(&mut len)?;
+    let len = u32::from_be_bytes(len) as usize;
+    if len > max_len {
+        return Err(malformed(peer));
+    }
+
+    let mut payload = vec![0; len];
+    stream.read_exact(&mut payload)?;
+    let mut payload = payload.as_slice();
+    T::decode(&mut payload)
+        .filter(|_| payload.is_empty())
+        .ok_or_else(|| malformed(peer))
+}
+
+/// Hands frames to the thread writing to a viewer, see [`add_viewer`].
+type Viewers = Mutex<Vec<SyncSender<Arc<Vec<u8>>>>>;
+
+/// Streams every update it is given to the viewers connected to an agent.
+pub struct AgentSink {
+    hostname: String,
+    viewers: Arc<Viewers>,
+}
+
+impl Sink for AgentSink {
+    fn push(&mut self, data: &Data) -> bool {
+        let frame = Arc::new(encode_frame(&(
+            AgentInfo::current(&self.hostname),
+            data.clone(),
+        )));
+        if let Ok(mut viewers) = self.viewers.lock() {
+            // Writing never blocks collection. A viewer still writing the last frame misses this
+            // one, and one that has gone away is dropped.
+            viewers.retain(|viewer| match viewer.try_send(frame.clone()) {
+                Ok(()) | Err(TrySendError::Full(_)) => true,
+                Err(TrySendError::Disconnected(_)) => false,
+            });
+        }
+
+        true
+    }
+}
+
+/// Streams frames to `stream` on a thread of its own, until writing to it fails.
+fn add_viewer(mut stream: TcpStream, viewers: &Viewers) {
+    let (sender, receiver) = mpsc::sync_channel::<Arc<Vec<u8>>>(1);
+    thread::spawn(move || {
+        for frame in receiver {
+            if stream.write_all(&frame).is_err() {
+                break;
+            }
+        }
+    });
+    if let Ok(mut viewers) = viewers.lock() {
+        viewers.push(sender);
+    }
+}
+
+/// Starts accepting viewers on `address`, returning the sink that streams updates to them.
+/// Binding happens right away, so a taken address is reported before anything else starts.
+/// Viewers' commands are run as `commands` allows, or refused if it doesn't.
+pub fn spawn_agent(
+    address: SocketAddr, commands: Option<CommandAccess>,
+) -> error::Result<AgentSink> {
+    let listener = TcpListener::bind(address)?;
+    let viewers: Arc<Viewers> = Arc::default();
+
+    {
+        let viewers = viewers.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let (viewers, commands) = (viewers.clone(), commands.clone());
+                // A viewer that doesn't say what it wants in time is dropped.
+                thread::spawn(move || accept_viewer(stream, &viewers, commands.as_ref()));
+            }
+        });
+    }
+
+    Ok(AgentSink {
+        hostname: System::new().host_name().unwrap_or_default(),
+        viewers,
+    })
+}
+
+/// Greets a new viewer and does what it asks, either adding it to the viewers streamed to or
+/// running its command.
+fn accept_viewer(
+    mut stream: TcpStream, viewers: &Viewers, commands: Option<&CommandAccess>,
+) -> error::Result<()> {
+    let peer = stream.peer_addr()?.to_string();
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.write_all(HANDSHAKE)?;
+
+    match read_message(&mut stream, &peer, MAX_REQUEST_LEN)? {
+        Request::Updates => add_viewer(stream, viewers),
+        Request::Command { command, token } => {
+            command::serve_command(stream, commands, &command, &token)?
+        }
+    }
+
+    Ok(())
+}
+
+/// Connects to the agent at `address`, like `host:port`, and asks it for `request`.
+fn open_connection(address: &str, request: &Request) -> error::Result<TcpStream> {
+    let socket_address = address
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| BottomError::GenericError(format!("'{address}' has no addresses.")))?;
+    let mut stream = TcpStream::connect_timeout(&socket_address, IO_TIMEOUT)?;
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+
+    let mut handshake = [0; HANDSHAKE.len()];
+    stream.read_exact(&mut handshake)?;
+    if handshake[..7] != HANDSHAKE[..7] {
+        return Err(BottomError::GenericError(format!(
+            "'{address}' is not a bottom agent."
+        )));
+    } else if handshake != *HANDSHAKE {
+        return Err(BottomError::GenericError(format!(
+            "The agent at '{address}' is a different version of bottom."
+        )));
+    }
+
+    stream.write_all(&encode_frame(request))?;
+    Ok(stream)
+}
+
+/// Data harvested by an agent on another machine, read in place of local collection.
+pub struct RemoteSource {
+    address: String,
+    stream: Option<TcpStream>,
+    buffer: Vec<u8>,
+}
+
+impl RemoteSource {
+    /// Connects to the agent at `address`, like `host:port`. Failing to connect here is an
+    /// error, while losing the connection later is retried by [`RemoteSource::receive`].
+    pub fn connect(address: &str) -> error::Result<Self> {
+        let mut source = RemoteSource {
+            address: address.to_string(),
+            stream: None,
+            buffer: Vec::new(),
+        };
+        source.reconnect()?;
+        Ok(source)
+    }
+
+    fn reconnect(&mut self) -> error::Result<()> {
+        self.stream = None;
+        self.buffer.clear();
+
+        let stream = open_connection(&self.address, &Request::Updates)?;
+        stream.set_read_timeout(Some(POLL_INTERVAL))?;
+        self.stream = Some(stream);
+        Ok(())
+    }
+
+    /// Waits a short while for the next update from the agent, returning [`None`] if there wasn't
+    /// one yet. After an error, the next call tries to connect again.
+    pub fn receive(&mut self) -> error::Result<Option<(AgentInfo, Data)>> {
+        if self.stream.is_none() {
+            self.reconnect()?;
+        }
+
+        let result = self.read_frame();
+        if result.is_err() {
+            self.stream = None;
+        }
+        result
+    }
+
+    fn read_frame(&mut self) -> error::Result<Option<(AgentInfo, Data)>> {
+        loop {
+            if let Some(update) = self.take_frame()? {
+                return Ok(Some(update));
+            }
+
+            let Some(stream) = &mut self.stream else {
+                return Ok(None);
+            };
+            let mut chunk = [0; 64 * 1024];
+            match stream.read(&mut chunk) {
+                Ok(0) => {
+                    return Err(BottomError::GenericError(format!(
+                        "The agent at '{}' closed the connection.",
+                        self.address
+                    )))
+                }
+                Ok(read) => self.buffer.extend_from_slice(&chunk[..read]),
+                Err(err) if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                    return Ok(None)
+                }
+                Err(err) if err.kind() == ErrorKind::Interrupted => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+    }
+
+    /// Removes the first complete frame from the buffer, if there is one.
+    fn take_frame(&mut self) -> error::Result<Option<(AgentInfo, Data)>> {
+        let Some(len) = self.buffer.first_chunk::<4>() else {
+            return Ok(None);
+        };
+        let len = u32::from_be_bytes(*len) as usize;
+        if len > MAX_FRAME_LEN {
+            return Err(malformed(&self.address));
+        } else if self.buffer.len() < 4 + len {
+            return Ok(None);
+        }
+
+        let mut payload = &self.buffer[4..4 + len];
+        let update = <(AgentInfo, Data)>::decode(&mut payload)
+            .filter(|_| payload.is_empty())
+            .ok_or_else(|| malformed(&self.address))?;
+        self.buffer.drain(..4 + len);
+        Ok(Some(update))
+    }
+}
+
+fn malformed(address: &str) -> BottomError {
+    BottomError::GenericError(format!("The agent at '{address}' sent malformed data."))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::app::data_harvester::memory::MemHarvest;
+
+    #[test]
+    fn test_agent_to_viewer() {
+        // Bind to find a free port, then let the agent take it.
+        let address = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let mut sink = spawn_agent(address, None).unwrap();
+        let mut source = RemoteSource::connect(&address.to_string()).unwrap();
+
+        // The viewer is only streamed to once it has been accepted, so keep sending until an
+        // update arrives.
+        let data = (0..50)
+            .find_map(|_| {
+                assert!(sink.push(&Data {
+                    memory: Some(MemHarvest {
+                        total_kib: 2,
+                        used_kib: 1,
+                        use_percent: Some(50.0),
+                    }),
+                    ..Data::default()
+                }));
+                source.receive().unwrap()
+            })
+            .expect("an update should arrive");
+        assert_eq!(data.1.memory.unwrap().used_kib, 1);
+        assert!(data.0.uptime_secs > 0);
+    }
+
+    #[test]
+    fn test_slow_viewers_dont_block() {
+        let (stalled, _stalled_frames) = mpsc::sync_channel(1);
+        let (gone, gone_frames) = mpsc::sync_channel(1);
+        drop(gone_frames);
+        let mut sink = AgentSink {
+            hostname: "rack1".to_string(),
+            viewers: Arc::new(Mutex::new(vec![stalled, gone])),
+        };
+
+        // The stalled viewer only misses frames, while the one that went away is dropped.
+        for _ in 0..3 {
+            assert!(sink.push(&Data::default()));
+        }
+        assert_eq!(sink.viewers.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_take_frame() {
+        let mut source = RemoteSource {
+            address: "agent".to_string(),
+            stream: None,
+            buffer: Vec::new(),
+        };
+        let info = AgentInfo {
+            hostname: "rack1".to_string(),
+            uptime_secs: 60,
+        };
+        let frame = encode_frame(&(
+            info.clone(),
+            Data {
+                load_avg: Some([1.0, 2.0, 3.0]),
+                ..Data::default()
+            },
+        ));
+
+        // Half a frame isn't anything yet, and two frames come out one at a time.
+        source.buffer.extend_from_slice(&frame[..frame.len() / 2]);
+        assert!(source.take_frame().unwrap().is_none());
+        source.buffer.extend_from_slice(&frame[frame.len() / 2..]);
+        source.buffer.extend_from_slice(&frame);
+        let (first_info, first_data) = source.take_frame().unwrap().unwrap();
+        assert_eq!(first_info, info);
+        assert_eq!(first_data.load_avg, Some([1.0, 2.0, 3.0]));
+        assert!(source.take_frame().unwrap().is_some());
+        assert!(source.buffer.is_empty());
+
+        source.buffer.extend_from_slice(&[0, 0, 0, 1, 0xff]);
+        assert!(source.take_frame().is_err());
+    }
+
+    #[test]
+    fn test_request_len_is_capped() {
+        let long = encode_frame(&Request::Command {
+            command: "a".repeat(MAX_REQUEST_LEN),
+            token: String::new(),
+        });
+        assert!(read_message::<Request>(&mut long.as_slice(), "viewer", MAX_REQUEST_LEN).is_err());
+
+        let frame = encode_frame(&Request::Updates);
+        assert_eq!(
+            read_message::<Request>(&mut frame.as_slice(), "viewer", MAX_REQUEST_LEN).unwrap(),
+            Request::Updates
+        );
+    }
+}
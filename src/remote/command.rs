@@ -0,0 +1,279 @@
+//! Running the terminal widget's commands on an agent's machine.
+//!
+//! A viewer asks for a command with [`Request::Command`] on a connection of its own. The agent
+//! sends back a [`CommandEvent`] for each piece of output and one when the command exits, while
+//! anything the viewer writes goes to the command's input. Hanging up kills the command.
+//!
+//! Commands only run on an agent started with `--allow_commands`, which also needs an
+//! `--agent_token` that every request has to carry.
+
+use std::{
+    io::{self, Read, Write},
+    net::{Shutdown, TcpStream},
+    process::{Command, ExitStatus, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    thread,
+};
+
+use super::{encode_frame, open_connection, read_message, CommandEvent, Request, MAX_FRAME_LEN};
+use crate::{utils::error, widgets::terminal_widget::kill_process_tree};
+
+/// What an agent started with `--allow_commands` runs viewers' commands with.
+#[derive(Clone, Debug)]
+pub struct CommandAccess {
+    /// The shell commands are run through, like `["sh", "-c"]`.
+    pub shell: Vec<String>,
+    /// The secret a viewer has to send along with a command for it to run.
+    pub token: String,
+}
+
+impl CommandAccess {
+    /// Compares `token` with the agent's own in constant time, so how long the comparison takes
+    /// says nothing about how much of a guess was right.
+    fn accepts(&self, token: &str) -> bool {
+        let (expected, token) = (self.token.as_bytes(), token.as_bytes());
+        let mut difference = expected.len() ^ token.len();
+        for (index, expected_byte) in expected.iter().enumerate() {
+            let byte = token.get(index).copied().unwrap_or_default();
+            difference |= usize::from(expected_byte ^ byte);
+        }
+        difference == 0
+    }
+}
+
+/// A command running on an agent, started by a viewer's terminal widget.
+pub struct RemoteCommand {
+    address: String,
+    stream: TcpStream,
+}
+
+impl RemoteCommand {
+    /// Asks the agent at `address`, like `host:port`, to run `command` through its shell, with
+    /// `token` matching the agent's `--agent_token`.
+    pub fn start(address: &str, command: &str, token: &str) -> error::Result<Self> {
+        let request = Request::Command {
+            command: command.to_string(),
+            token: token.to_string(),
+        };
+        let stream = open_connection(address, &request)?;
+        // A command can go as long as it likes without output.
+        stream.set_read_timeout(None)?;
+
+        Ok(RemoteCommand {
+            address: address.to_string(),
+            stream,
+        })
+    }
+
+    /// Another handle to the connection, which sends input to the command when written to, and
+    /// kills it when shut down.
+    pub fn try_clone_stream(&self) -> io::Result<TcpStream> {
+        self.stream.try_clone()
+    }
+
+    /// Waits for the command's next output, or for it to exit.
+    pub fn next_event(&mut self) -> error::Result<CommandEvent> {
+        read_message(&mut self.stream, &self.address, MAX_FRAME_LEN)
+    }
+}
+
+/// Turns an exit status sent by an agent back into an [`ExitStatus`].
+pub fn exit_status(raw: i32) -> ExitStatus {
+    #[cfg(target_family = "unix")]
+    return std::os::unix::process::ExitStatusExt::from_raw(raw);
+    #[cfg(target_family = "windows")]
+    return std::os::windows::process::ExitStatusExt::from_raw(raw as u32);
+}
+
+fn raw_exit_status(status: ExitStatus) -> Option<i32> {
+    #[cfg(target_family = "unix")]
+    return Some(std::os::unix::process::ExitStatusExt::into_raw(status));
+    #[cfg(not(target_family = "unix"))]
+    return status.code();
+}
+
+/// Runs a viewer's command through the agent's shell, sending its output and exit back over
+/// `stream`. The command is refused without `commands`, as the agent wasn't started with
+/// `--allow_commands`, and when `token` doesn't match the agent's.
+pub(super) fn serve_command(
+    stream: TcpStream, commands: Option<&CommandAccess>, command: &str, token: &str,
+) -> io::Result<()> {
+    let events = Mutex::new(stream.try_clone()?);
+    let send = |event: CommandEvent| -> io::Result<()> {
+        events.lock().unwrap().write_all(&encode_frame(&event))
+    };
+
+    let shell = match commands {
+        Some(commands) if commands.accepts(token) => &commands.shell,
+        _ => {
+            let refusal: &[u8] = if commands.is_none() {
+                b"This agent doesn't run commands, unless started with --allow_commands.\n"
+            } else {
+                b"The agent refused the command, as its --agent_token doesn't match this one.\n"
+            };
+            send(CommandEvent::Output(refusal.to_vec()))?;
+            return send(CommandEvent::Exited(None));
+        }
+    };
+
+    let mut builder = Command::new(&shell[0]);
+    builder
+        .args(&shell[1..])
+        .arg(command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    // Leading its own process group lets the command be killed along with anything it starts.
+    #[cfg(target_family = "unix")]
+    std::os::unix::process::CommandExt::process_group(&mut builder, 0);
+
+    let mut child = match builder.spawn() {
+        Ok(child) => child,
+        Err(err) => {
+            send(CommandEvent::Output(
+                format!("Unable to run the command: {err}\n").into_bytes(),
+            ))?;
+            return send(CommandEvent::Exited(None));
+        }
+    };
+    let pid = child.id();
+    let (mut stdin, stdout, stderr) =
+        (child.stdin.take(), child.stdout.take(), child.stderr.take());
+
+    let mut input = stream.try_clone()?;
+    input.set_read_timeout(None)?;
+    let exited = AtomicBool::new(false);
+
+    thread::scope(|scope| {
+        let (send, exited) = (&send, &exited);
+
+        // Input is passed along until the viewer hangs up, which cancels the command.
+        scope.spawn(move || {
+            let mut buf = [0; 1024];
+            while let Ok(read @ 1..) = input.read(&mut buf) {
+                if let Some(stdin) = &mut stdin {
+                    let _ = stdin.write_all(&buf[..read]);
+                }
+            }
+            if !exited.load(Ordering::SeqCst) {
+                kill_process_tree(pid);
+            }
+        });
+
+        let outputs = [
+            stdout.map(|stdout| Box::new(stdout) as Box<dyn Read + Send>),
+            stderr.map(|stderr| Box::new(stderr) as Box<dyn Read + Send>),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|mut output| {
+            scope.spawn(move || {
+                let mut buf = [0; 4096];
+                while let Ok(read @ 1..) = output.read(&mut buf) {
+                    if send(CommandEvent::Output(buf[..read].to_vec())).is_err() {
+                        break;
+                    }
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+        for output in outputs {
+            let _ = output.join();
+        }
+
+        let status = child.wait().ok().and_then(raw_exit_status);
+        exited.store(true, Ordering::SeqCst);
+        // The viewer may have hung up already, in which case there's no one to tell.
+        let _ = send(CommandEvent::Exited(status));
+        let _ = stream.shutdown(Shutdown::Read);
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::TcpListener;
+
+    use super::*;
+    use crate::remote::accept_viewer;
+
+    /// Starts an agent that runs commands through `sh` for viewers with the token `secret`.
+    fn spawn_command_agent() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            let commands = CommandAccess {
+                shell: vec!["sh".to_string(), "-c".to_string()],
+                token: "secret".to_string(),
+            };
+            for stream in listener.incoming().flatten() {
+                let commands = commands.clone();
+                thread::spawn(move || accept_viewer(stream, &Mutex::default(), Some(&commands)));
+            }
+        });
+        address.to_string()
+    }
+
+    fn wait_for_exit(command: &mut RemoteCommand) -> (Vec<u8>, Option<i32>) {
+        let mut output = Vec::new();
+        loop {
+            match command.next_event().unwrap() {
+                CommandEvent::Output(chunk) => output.extend(chunk),
+                CommandEvent::Exited(status) => return (output, status),
+            }
+        }
+    }
+
+    #[cfg(target_family = "unix")]
+    #[test]
+    fn test_remote_command() {
+        let address = spawn_command_agent();
+        let mut command =
+            RemoteCommand::start(&address, "read line; echo got $line; exit 3", "secret").unwrap();
+        command
+            .try_clone_stream()
+            .unwrap()
+            .write_all(b"hi\n")
+            .unwrap();
+
+        let (output, status) = wait_for_exit(&mut command);
+        assert_eq!(output, b"got hi\n");
+        assert_eq!(
+            status.map(exit_status).and_then(|status| status.code()),
+            Some(3)
+        );
+    }
+
+    #[cfg(target_family = "unix")]
+    #[test]
+    fn test_remote_command_needs_token() {
+        let address = spawn_command_agent();
+        let marker = std::env::temp_dir().join(format!("btm_token_test_{}", std::process::id()));
+
+        for token in ["", "secre", "secret!", "wrong!"] {
+            let mut command =
+                RemoteCommand::start(&address, &format!("touch '{}'", marker.display()), token)
+                    .unwrap();
+            let (output, status) = wait_for_exit(&mut command);
+            assert!(String::from_utf8_lossy(&output).contains("--agent_token"));
+            assert_eq!(status, None);
+        }
+        assert!(!marker.exists(), "a command ran without the right token");
+    }
+
+    #[test]
+    fn test_token_comparison() {
+        let commands = CommandAccess {
+            shell: Vec::new(),
+            token: "secret".to_string(),
+        };
+        assert!(commands.accepts("secret"));
+        assert!(!commands.accepts("secret\0"));
+        assert!(!commands.accepts("Secret"));
+        assert!(!commands.accepts(""));
+    }
+}
@@ -0,0 +1,409 @@
+//! Finding agents on the local network with multicast DNS, so a viewer can connect to one without
+//! knowing its address.
+//!
+//! An agent started with `--advertise` answers queries for [`SERVICE`] with its hostname and the
+//! port it streams on. A viewer asks every [`QUERY_INTERVAL`], and takes each agent's address from
+//! where its answer came from, so agents don't need to work out their own addresses.
+
+use std::{
+    collections::HashMap,
+    io,
+    net::{Ipv4Addr, SocketAddr, UdpSocket},
+    sync::mpsc::Sender,
+    thread,
+    time::{Duration, Instant},
+};
+
+use sysinfo::{System, SystemExt};
+
+use crate::BottomEvent;
+
+/// The service agents are advertised as.
+const SERVICE: &str = "_bottom._tcp.local";
+
+const MDNS_GROUP: Ipv4Addr = Ipv4Addr::new(224, 0, 0, 251);
+const MDNS_PORT: u16 = 5353;
+
+/// How often a viewer asks for agents.
+const QUERY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How long an agent stays listed after it last answered.
+const EXPIRY: Duration = Duration::from_secs(30);
+
+/// How long, in seconds, answers may be cached for.
+const RECORD_TTL: u32 = 120;
+
+const TYPE_PTR: u16 = 12;
+const TYPE_SRV: u16 = 33;
+const TYPE_ANY: u16 = 255;
+const CLASS_IN: u16 = 1;
+
+/// Set on the class of a question to ask for the answer to be sent straight back.
+const UNICAST_RESPONSE: u16 = 0x8000;
+
+/// Set in the header flags of a response.
+const FLAG_RESPONSE: u16 = 0x8000;
+const FLAG_AUTHORITATIVE: u16 = 0x0400;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiscoveredAgent {
+    pub hostname: String,
+    pub address: SocketAddr,
+}
+
+/// Answers viewers looking for agents on a thread of its own, for as long as bottom runs.
+pub fn advertise(port: u16) -> io::Result<()> {
+    let hostname = System::new()
+        .host_name()
+        .unwrap_or_else(|| "bottom".to_string());
+    let socket = bind_mdns_socket()?;
+    socket.join_multicast_v4(&MDNS_GROUP, &Ipv4Addr::UNSPECIFIED)?;
+
+    thread::spawn(move || {
+        let mut buf = [0; 9000];
+        while let Ok((len, from)) = socket.recv_from(&mut buf) {
+            let Some(id) = query_id(&buf[..len]) else {
+                continue;
+            };
+
+            // Other responders only listen on the group, while a viewer asks from a port of its
+            // own and waits for the answer there.
+            let (id, to) = if from.port() == MDNS_PORT {
+                (0, SocketAddr::from((MDNS_GROUP, MDNS_PORT)))
+            } else {
+                (id, from)
+            };
+            let _ = socket.send_to(&build_response(id, &hostname, port), to);
+        }
+    });
+
+    Ok(())
+}
+
+/// Looks for agents on a thread of its own, sending the agents found to the main loop whenever
+/// they change. Stops once the main loop does.
+pub fn spawn_browser(sender: Sender<BottomEvent>) -> io::Result<()> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))?;
+    socket.set_multicast_ttl_v4(255)?;
+    socket.set_read_timeout(Some(Duration::from_secs(1)))?;
+
+    thread::spawn(move || {
+        let query = build_query();
+        let mut last_query: Option<Instant> = None;
+        let mut seen: HashMap<SocketAddr, (String, Instant)> = HashMap::new();
+        let mut last_sent: Option<Vec<DiscoveredAgent>> = None;
+        let mut buf = [0; 9000];
+
+        loop {
+            if last_query.is_none_or(|at| at.elapsed() >= QUERY_INTERVAL) {
+                // Without a route for multicast there's nothing to find, but it may come up later.
+                let _ = socket.send_to(&query, (MDNS_GROUP, MDNS_PORT));
+                last_query = Some(Instant::now());
+            }
+
+            if let Ok((len, from)) = socket.recv_from(&mut buf) {
+                for (hostname, port) in parse_response(&buf[..len]) {
+                    let address = SocketAddr::new(from.ip(), port);
+                    seen.insert(address, (hostname, Instant::now()));
+                }
+            }
+            seen.retain(|_, (_, at)| at.elapsed() < EXPIRY);
+
+            let mut agents: Vec<DiscoveredAgent> = seen
+                .iter()
+                .map(|(address, (hostname, _))| DiscoveredAgent {
+                    hostname: hostname.clone(),
+                    address: *address,
+                })
+                .collect();
+            agents.sort_by(|a, b| (&a.hostname, a.address).cmp(&(&b.hostname, b.address)));
+
+            if last_sent.as_ref() != Some(&agents) {
+                if sender
+                    .send(BottomEvent::AgentsDiscovered(agents.clone()))
+                    .is_err()
+                {
+                    break;
+                }
+                last_sent = Some(agents);
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Binds to the mDNS port, sharing it with any other responder running on the machine.
+#[cfg(target_family = "unix")]
+fn bind_mdns_socket() -> io::Result<UdpSocket> {
+    use std::{mem, os::fd::FromRawFd, ptr};
+
+    fn check(result: libc::c_int) -> io::Result<libc::c_int> {
+        if result < 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(result)
+        }
+    }
+
+    // SAFETY: The new descriptor is owned by the socket right away, so it is closed on any error.
+    // The option and address pointers are valid for the duration of each call, with their sizes.
+    unsafe {
+        let fd = check(libc::socket(libc::AF_INET, libc::SOCK_DGRAM, 0))?;
+        let socket = UdpSocket::from_raw_fd(fd);
+
+        let enable: libc::c_int = 1;
+        for option in [libc::SO_REUSEADDR, libc::SO_REUSEPORT] {
+            check(libc::setsockopt(
+                fd,
+                libc::SOL_SOCKET,
+                option,
+                ptr::addr_of!(enable).cast(),
+                mem::size_of::<libc::c_int>() as libc::socklen_t,
+            ))?;
+        }
+
+        let mut address: libc::sockaddr_in = mem::zeroed();
+        address.sin_family = libc::AF_INET as libc::sa_family_t;
+        address.sin_port = MDNS_PORT.to_be();
+        #[cfg(any(target_os = "macos", target_os = "freebsd"))]
+        {
+            address.sin_len = mem::size_of::<libc::sockaddr_in>() as u8;
+        }
+        check(libc::bind(
+            fd,
+            ptr::addr_of!(address).cast(),
+            mem::size_of::<libc::sockaddr_in>() as libc::socklen_t,
+        ))?;
+
+        Ok(socket)
+    }
+}
+
+#[cfg(not(target_family = "unix"))]
+fn bind_mdns_socket() -> io::Result<UdpSocket> {
+    UdpSocket::bind((Ipv4Addr::UNSPECIFIED, MDNS_PORT))
+}
+
+fn write_u16(out: &mut Vec<u8>, value: u16) {
+    out.extend_from_slice(&value.to_be_bytes());
+}
+
+fn write_name(out: &mut Vec<u8>, name: &str) {
+    for label in name.split('.') {
+        out.push(label.len() as u8);
+        out.extend_from_slice(label.as_bytes());
+    }
+    out.push(0);
+}
+
+/// Writes a header with the given counts of questions and answers.
+fn write_header(out: &mut Vec<u8>, id: u16, flags: u16, questions: u16, answers: u16) {
+    for value in [id, flags, questions, answers, 0, 0] {
+        write_u16(out, value);
+    }
+}
+
+fn write_record(out: &mut Vec<u8>, name: &str, record_type: u16, data: &[u8]) {
+    write_name(out, name);
+    write_u16(out, record_type);
+    write_u16(out, CLASS_IN);
+    out.extend_from_slice(&RECORD_TTL.to_be_bytes());
+    write_u16(out, data.len() as u16);
+    out.extend_from_slice(data);
+}
+
+/// A hostname as a single DNS label, which can't hold dots or go past 63 bytes.
+fn host_label(hostname: &str) -> String {
+    let mut label = hostname.replace('.', "-");
+    while label.len() > 63 {
+        label.pop();
+    }
+    label
+}
+
+fn build_query() -> Vec<u8> {
+    let mut query = Vec::new();
+    write_header(&mut query, 0, 0, 1, 0);
+    write_name(&mut query, SERVICE);
+    write_u16(&mut query, TYPE_PTR);
+    write_u16(&mut query, CLASS_IN | UNICAST_RESPONSE);
+    query
+}
+
+/// The answer to a query: the agent's name under [`SERVICE`], and the port it streams on. The
+/// question is repeated, as simple resolvers expect it back.
+fn build_response(id: u16, hostname: &str, port: u16) -> Vec<u8> {
+    let label = host_label(hostname);
+    let instance = format!("{label}.{SERVICE}");
+
+    let mut response = Vec::new();
+    write_header(&mut response, id, FLAG_RESPONSE | FLAG_AUTHORITATIVE, 1, 2);
+    write_name(&mut response, SERVICE);
+    write_u16(&mut response, TYPE_PTR);
+    write_u16(&mut response, CLASS_IN);
+
+    let mut data = Vec::new();
+    write_name(&mut data, &instance);
+    write_record(&mut response, SERVICE, TYPE_PTR, &data);
+
+    let mut data = Vec::new();
+    for value in [0, 0, port] {
+        write_u16(&mut data, value);
+    }
+    write_name(&mut data, &format!("{label}.local"));
+    write_record(&mut response, &instance, TYPE_SRV, &data);
+
+    response
+}
+
+fn read_u16(packet: &[u8], pos: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(*packet.get(pos..)?.first_chunk()?))
+}
+
+/// Reads the name at `pos`, following compression pointers, returning it and where it ends.
+fn read_name(packet: &[u8], mut pos: usize) -> Option<(String, usize)> {
+    let mut labels = Vec::new();
+    let mut end = None;
+    let mut jumps = 0;
+
+    loop {
+        let len = usize::from(*packet.get(pos)?);
+        if len & 0xC0 == 0xC0 {
+            // A pointer can't be followed forever, in case it loops.
+            jumps += 1;
+            if jumps > 16 {
+                return None;
+            }
+            end.get_or_insert(pos + 2);
+            pos = ((len & 0x3F) << 8) | usize::from(*packet.get(pos + 1)?);
+        } else if len == 0 {
+            end.get_or_insert(pos + 1);
+            break;
+        } else {
+            let label = packet.get(pos + 1..pos + 1 + len)?;
+            labels.push(String::from_utf8_lossy(label).into_owned());
+            pos += 1 + len;
+        }
+    }
+
+    Some((labels.join("."), end?))
+}
+
+/// Returns the ID of a query that asks for agents, or [`None`] if it's anything else.
+fn query_id(packet: &[u8]) -> Option<u16> {
+    let id = read_u16(packet, 0)?;
+    if read_u16(packet, 2)? & FLAG_RESPONSE != 0 {
+        return None;
+    }
+
+    let mut pos = 12;
+    for _ in 0..read_u16(packet, 4)? {
+        let (name, end) = read_name(packet, pos)?;
+        let question_type = read_u16(packet, end)?;
+        pos = end + 4;
+        if name.eq_ignore_ascii_case(SERVICE) && matches!(question_type, TYPE_PTR | TYPE_ANY) {
+            return Some(id);
+        }
+    }
+
+    None
+}
+
+/// Returns the hostname and port of every agent in a response.
+fn parse_response(packet: &[u8]) -> Vec<(String, u16)> {
+    let mut instances = Vec::new();
+    let mut ports = HashMap::new();
+
+    let mut parse = || -> Option<()> {
+        if read_u16(packet, 2)? & FLAG_RESPONSE == 0 {
+            return None;
+        }
+
+        let mut pos = 12;
+        for _ in 0..read_u16(packet, 4)? {
+            pos = read_name(packet, pos)?.1 + 4;
+        }
+
+        let records = [6, 8, 10]
+            .into_iter()
+            .map(|offset| read_u16(packet, offset).map(usize::from))
+            .sum::<Option<usize>>()?;
+        for _ in 0..records {
+            let (name, end) = read_name(packet, pos)?;
+            let record_type = read_u16(packet, end)?;
+            let data_len = usize::from(read_u16(packet, end + 8)?);
+            let data = end + 10;
+            pos = data + data_len;
+            // A record cut short by a truncated packet isn't trusted.
+            packet.get(data..pos)?;
+
+            match record_type {
+                TYPE_PTR if name.eq_ignore_ascii_case(SERVICE) => {
+                    instances.push(read_name(packet, data)?.0);
+                }
+                TYPE_SRV => {
+                    ports.insert(name.to_lowercase(), read_u16(packet, data + 4)?);
+                }
+                _ => {}
+            }
+        }
+
+        Some(())
+    };
+    // Whatever was read before a malformed part is still used.
+    let _ = parse();
+
+    instances
+        .into_iter()
+        .filter_map(|instance| {
+            let port = *ports.get(&instance.to_lowercase())?;
+            let (hostname, _) = instance.split_once('.')?;
+            Some((hostname.to_string(), port))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_query_and_response() {
+        let query = build_query();
+        assert_eq!(query_id(&query), Some(0));
+        assert!(parse_response(&query).is_empty());
+
+        let response = build_response(7, "rack1.lan", 9100);
+        assert_eq!(query_id(&response), None);
+        assert_eq!(parse_response(&response), [("rack1-lan".to_string(), 9100)]);
+        assert_eq!(read_u16(&response, 0), Some(7));
+
+        // A response cut short doesn't have the agent's port yet.
+        assert!(parse_response(&response[..response.len() - 4]).is_empty());
+    }
+
+    #[test]
+    fn test_read_compressed_name() {
+        let mut packet = vec![0; 12];
+        write_name(&mut packet, SERVICE);
+        // "rack1" followed by a pointer to the service name at the start of the packet.
+        packet.extend_from_slice(&[5, b'r', b'a', b'c', b'k', b'1', 0xC0, 12]);
+        let start = 12 + SERVICE.len() + 2;
+
+        assert_eq!(
+            read_name(&packet, start),
+            Some((format!("rack1.{SERVICE}"), packet.len()))
+        );
+
+        // A pointer to itself never ends.
+        let looping = [0xC0, 0];
+        assert_eq!(read_name(&looping, 0), None);
+    }
+
+    #[test]
+    fn test_host_label() {
+        assert_eq!(host_label("a.b"), "a-b");
+        assert_eq!(host_label(&"x".repeat(70)).len(), 63);
+    }
+}
@@ -0,0 +1,895 @@
+//! The wire format for harvested data sent from an agent to a viewer.
+//!
+//! Everything is written in a fixed order with no field names, as both ends are built from the
+//! same source. Numbers are big-endian, lengths are `u32`s, and an [`Option`] is a `0` or `1` byte
+//! followed by its value. Fields that only exist on some platforms or with some features are
+//! always written as an [`Option`], so an agent and a viewer can differ in either.
+
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    time::Instant,
+};
+
+use crate::{
+    app::data_harvester::{
+        cgroups::CgroupHarvest,
+        containers::ContainerHarvest,
+        cpu::{CpuData, CpuDataType, CpuFrequency},
+        disks::{DiskHarvest, IoData, IoLatencyCounters, MountType},
+        memory::MemHarvest,
+        network::{
+            connections::Protocol, ConnectionHarvest, InterfaceErrors, NetlinkSocketInfo,
+            NetworkHarvest, TcpStats, WifiInfo,
+        },
+        pressure::{Pressure, PressureHarvest},
+        processes::ProcessHarvest,
+        raid::{RaidHarvest, RaidMember, RaidSync},
+        remote_uptime::{RemoteUptimeHarvest, RemoteUptimeStatus},
+        scheduled_jobs::{JobKind, ScheduledJobHarvest},
+        temperature::{SensorType, TempHarvest},
+        zfs::{ZfsPoolHarvest, ZfsScan},
+        Data,
+    },
+    Pid,
+};
+
+use super::{AgentInfo, CommandEvent, Request};
+
+/// A value that can be sent to a viewer.
+pub trait Wire: Sized {
+    fn encode(&self, out: &mut Vec<u8>);
+
+    /// Reads a value from the start of `input`, advancing past it. Returns [`None`] if `input`
+    /// doesn't start with a valid value.
+    fn decode(input: &mut &[u8]) -> Option<Self>;
+}
+
+fn take<const N: usize>(input: &mut &[u8]) -> Option<[u8; N]> {
+    let (bytes, rest) = input.split_first_chunk::<N>()?;
+    *input = rest;
+    Some(*bytes)
+}
+
+macro_rules! wire_number {
+    ($($ty:ty),*) => {
+        $(
+            impl Wire for $ty {
+                fn encode(&self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_be_bytes());
+                }
+
+                fn decode(input: &mut &[u8]) -> Option<Self> {
+                    take(input).map(<$ty>::from_be_bytes)
+                }
+            }
+        )*
+    };
+}
+
+wire_number!(u8, u16, u32, u64, i32, i64, f32, f64);
+
+/// Writes the fields of a struct in order, for structs without platform-specific fields.
+macro_rules! wire_struct {
+    ($ty:ty { $($field:ident),* $(,)? }) => {
+        impl Wire for $ty {
+            fn encode(&self, out: &mut Vec<u8>) {
+                $(self.$field.encode(out);)*
+            }
+
+            fn decode(input: &mut &[u8]) -> Option<Self> {
+                Some(Self {
+                    $($field: Wire::decode(input)?,)*
+                })
+            }
+        }
+    };
+}
+
+/// Writes an enum without fields as the index of its variant.
+macro_rules! wire_enum {
+    ($ty:ident { $($variant:ident),* $(,)? }) => {
+        impl Wire for $ty {
+            fn encode(&self, out: &mut Vec<u8>) {
+                let variants = [$($ty::$variant),*];
+                let index = variants.iter().position(|variant| variant == self).unwrap_or(0);
+                (index as u8).encode(out);
+            }
+
+            fn decode(input: &mut &[u8]) -> Option<Self> {
+                let variants = [$($ty::$variant),*];
+                variants.get(usize::from(u8::decode(input)?)).copied()
+            }
+        }
+    };
+}
+
+impl Wire for usize {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (*self as u64).encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        usize::try_from(u64::decode(input)?).ok()
+    }
+}
+
+impl Wire for bool {
+    fn encode(&self, out: &mut Vec<u8>) {
+        u8::from(*self).encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        match u8::decode(input)? {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+}
+
+impl Wire for char {
+    fn encode(&self, out: &mut Vec<u8>) {
+        u32::from(*self).encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        char::from_u32(u32::decode(input)?)
+    }
+}
+
+impl Wire for String {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (self.len() as u32).encode(out);
+        out.extend_from_slice(self.as_bytes());
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        let len = u32::decode(input)? as usize;
+        if input.len() < len {
+            return None;
+        }
+        let (bytes, rest) = input.split_at(len);
+        *input = rest;
+        String::from_utf8(bytes.to_vec()).ok()
+    }
+}
+
+impl<T: Wire> Wire for Option<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            Some(value) => {
+                true.encode(out);
+                value.encode(out);
+            }
+            None => false.encode(out),
+        }
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        if bool::decode(input)? {
+            T::decode(input).map(Some)
+        } else {
+            Some(None)
+        }
+    }
+}
+
+impl<T: Wire> Wire for Vec<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (self.len() as u32).encode(out);
+        for value in self {
+            value.encode(out);
+        }
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        let len = u32::decode(input)?;
+        // Every value takes at least a byte, so a length longer than what's left is malformed,
+        // and shouldn't be allocated for.
+        if len as usize > input.len() {
+            return None;
+        }
+        (0..len).map(|_| T::decode(input)).collect()
+    }
+}
+
+impl<K: Wire + Eq + Hash, V: Wire> Wire for HashMap<K, V> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (self.len() as u32).encode(out);
+        for (key, value) in self {
+            key.encode(out);
+            value.encode(out);
+        }
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        let len = u32::decode(input)?;
+        if len as usize > input.len() {
+            return None;
+        }
+        (0..len)
+            .map(|_| Some((K::decode(input)?, V::decode(input)?)))
+            .collect()
+    }
+}
+
+impl<A: Wire, B: Wire> Wire for (A, B) {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.0.encode(out);
+        self.1.encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some((A::decode(input)?, B::decode(input)?))
+    }
+}
+
+impl<T: Wire + Copy + Default, const N: usize> Wire for [T; N] {
+    fn encode(&self, out: &mut Vec<u8>) {
+        for value in self {
+            value.encode(out);
+        }
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        let mut values = [T::default(); N];
+        for value in &mut values {
+            *value = T::decode(input)?;
+        }
+        Some(values)
+    }
+}
+
+impl Wire for SocketAddr {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self.ip() {
+            IpAddr::V4(ip) => {
+                4u8.encode(out);
+                ip.octets().encode(out);
+            }
+            IpAddr::V6(ip) => {
+                6u8.encode(out);
+                ip.octets().encode(out);
+            }
+        }
+        self.port().encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        let ip = match u8::decode(input)? {
+            4 => IpAddr::V4(Ipv4Addr::from(<[u8; 4]>::decode(input)?)),
+            6 => IpAddr::V6(Ipv6Addr::from(<[u8; 16]>::decode(input)?)),
+            _ => return None,
+        };
+        Some(SocketAddr::new(ip, u16::decode(input)?))
+    }
+}
+
+/// Pids are a different type on each platform, so they are always sent as a `u64`.
+fn encode_pid(pid: Pid, out: &mut Vec<u8>) {
+    (pid as u64).encode(out);
+}
+
+fn decode_pid(input: &mut &[u8]) -> Option<Pid> {
+    Pid::try_from(u64::decode(input)?).ok()
+}
+
+impl Wire for CpuDataType {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            CpuDataType::Avg => None,
+            CpuDataType::Cpu(index) => Some(*index),
+        }
+        .encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(match Option::<usize>::decode(input)? {
+            Some(index) => CpuDataType::Cpu(index),
+            None => CpuDataType::Avg,
+        })
+    }
+}
+
+impl Wire for RemoteUptimeStatus {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            RemoteUptimeStatus::Up(secs) => {
+                0u8.encode(out);
+                secs.encode(out);
+            }
+            RemoteUptimeStatus::Reachable(secs) => {
+                1u8.encode(out);
+                secs.encode(out);
+            }
+            RemoteUptimeStatus::Unreachable => 2u8.encode(out),
+        }
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        match u8::decode(input)? {
+            0 => u64::decode(input).map(RemoteUptimeStatus::Up),
+            1 => u64::decode(input).map(RemoteUptimeStatus::Reachable),
+            2 => Some(RemoteUptimeStatus::Unreachable),
+            _ => None,
+        }
+    }
+}
+
+impl Wire for Request {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            Request::Updates => 0u8.encode(out),
+            Request::Command { command, token } => {
+                1u8.encode(out);
+                command.encode(out);
+                token.encode(out);
+            }
+        }
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        match u8::decode(input)? {
+            0 => Some(Request::Updates),
+            1 => Some(Request::Command {
+                command: String::decode(input)?,
+                token: String::decode(input)?,
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl Wire for CommandEvent {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            CommandEvent::Output(output) => {
+                0u8.encode(out);
+                output.encode(out);
+            }
+            CommandEvent::Exited(status) => {
+                1u8.encode(out);
+                status.encode(out);
+            }
+        }
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        match u8::decode(input)? {
+            0 => Vec::decode(input).map(CommandEvent::Output),
+            1 => Option::decode(input).map(CommandEvent::Exited),
+            _ => None,
+        }
+    }
+}
+
+wire_enum!(MountType {
+    Local,
+    Nfs,
+    Cifs,
+    Tmpfs,
+    Other
+});
+wire_enum!(Protocol { Tcp, Udp });
+wire_enum!(JobKind { Cron, Timer });
+wire_enum!(SensorType {
+    Temperature,
+    Fan,
+    Voltage,
+    Power
+});
+
+wire_struct!(CpuData {
+    data_type,
+    cpu_usage,
+    frequency
+});
+wire_struct!(CpuFrequency {
+    mhz,
+    governor,
+    is_throttled
+});
+wire_struct!(MemHarvest {
+    total_kib,
+    used_kib,
+    use_percent
+});
+wire_struct!(TempHarvest {
+    name,
+    value,
+    sensor_type
+});
+wire_struct!(WifiInfo {
+    rssi_dbm,
+    link_quality,
+    ssid,
+    frequency_mhz,
+    tx_bitrate_kbps,
+    rx_bitrate_kbps
+});
+wire_struct!(NetworkHarvest {
+    rx,
+    tx,
+    total_rx,
+    total_tx,
+    total_tcp_connections,
+    wifi_info,
+    interface_totals,
+    interface_errors,
+    tcp_stats,
+});
+wire_struct!(InterfaceErrors {
+    rx_errors,
+    tx_errors,
+    rx_dropped,
+    tx_dropped,
+    collisions,
+});
+wire_struct!(TcpStats {
+    active_opens,
+    passive_opens,
+    attempt_fails,
+    estab_resets,
+    curr_estab,
+    in_segs,
+    out_segs,
+    retrans_segs,
+    in_errs,
+    out_rsts,
+    sockets_used,
+    tcp_sockets,
+    udp_sockets,
+    time_wait_sockets,
+});
+wire_struct!(NetlinkSocketInfo {
+    local,
+    remote,
+    rtt_ms,
+    retransmits,
+    bytes_sent,
+    bytes_received,
+    tx_per_sec,
+    rx_per_sec,
+});
+wire_struct!(DiskHarvest {
+    name,
+    mount_point,
+    free_space,
+    used_space,
+    total_space,
+    mount_type,
+    stale,
+    io_scheduler,
+    used_inodes,
+    total_inodes,
+});
+wire_struct!(IoLatencyCounters {
+    reads_completed,
+    time_spent_reading_ms,
+    writes_completed,
+    time_spent_writing_ms,
+});
+wire_struct!(IoData {
+    read_bytes,
+    write_bytes,
+    latency_counters
+});
+wire_struct!(RemoteUptimeHarvest { name, status });
+wire_struct!(ContainerHarvest {
+    id,
+    name,
+    image,
+    state,
+    status,
+    cpu_percent,
+    mem_bytes,
+    rx_bytes_per_sec,
+    tx_bytes_per_sec,
+});
+wire_struct!(Pressure { some, full });
+wire_struct!(PressureHarvest { cpu, memory, io });
+wire_struct!(RaidHarvest {
+    name,
+    level,
+    state,
+    members,
+    total_devices,
+    working_devices,
+    sync,
+});
+wire_struct!(RaidMember {
+    name,
+    is_failed,
+    is_spare,
+});
+wire_struct!(RaidSync {
+    action,
+    percent,
+    finish,
+});
+wire_struct!(ZfsPoolHarvest {
+    name,
+    health,
+    size,
+    allocated,
+    free,
+    fragmentation,
+    capacity,
+    scan,
+});
+wire_struct!(ZfsScan { summary, percent });
+wire_struct!(CgroupHarvest {
+    path,
+    cpu_percent,
+    mem_bytes,
+    mem_limit_bytes,
+    tasks,
+});
+wire_struct!(ScheduledJobHarvest {
+    kind,
+    schedule,
+    command,
+    user,
+    next_run,
+    last_run,
+    last_result,
+});
+wire_struct!(AgentInfo {
+    hostname,
+    uptime_secs
+});
+
+#[cfg(feature = "battery")]
+wire_struct!(crate::app::data_harvester::batteries::BatteryHarvest {
+    charge_percent,
+    secs_until_full,
+    secs_until_empty,
+    power_consumption_rate_watts,
+    health_percent,
+});
+
+impl Wire for ConnectionHarvest {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.protocol.encode(out);
+        self.local.encode(out);
+        self.remote.encode(out);
+        self.state.encode(out);
+        self.pid.map(|pid| pid as u64).encode(out);
+        self.name.encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(ConnectionHarvest {
+            protocol: Wire::decode(input)?,
+            local: Wire::decode(input)?,
+            remote: Wire::decode(input)?,
+            state: Wire::decode(input)?,
+            pid: match Option::<u64>::decode(input)? {
+                Some(pid) => Some(Pid::try_from(pid).ok()?),
+                None => None,
+            },
+            name: Wire::decode(input)?,
+        })
+    }
+}
+
+impl Wire for ProcessHarvest {
+    fn encode(&self, out: &mut Vec<u8>) {
+        encode_pid(self.pid, out);
+        self.parent_pid.map(|pid| pid as u64).encode(out);
+        self.cpu_usage_percent.encode(out);
+        self.mem_usage_percent.encode(out);
+        self.mem_usage_bytes.encode(out);
+        self.name.encode(out);
+        self.command.encode(out);
+        self.read_bytes_per_sec.encode(out);
+        self.write_bytes_per_sec.encode(out);
+        self.total_read_bytes.encode(out);
+        self.total_write_bytes.encode(out);
+        self.process_state.0.encode(out);
+        self.process_state.1.encode(out);
+        self.user.to_string().encode(out);
+
+        #[cfg(target_family = "unix")]
+        let uid = self.uid;
+        #[cfg(not(target_family = "unix"))]
+        let uid = None::<u32>;
+        uid.encode(out);
+
+        #[cfg(target_family = "unix")]
+        let nice = self.nice;
+        #[cfg(not(target_family = "unix"))]
+        let nice = None::<i32>;
+        nice.encode(out);
+
+        #[cfg(target_os = "linux")]
+        let io_priority = self
+            .io_class
+            .map(|class| (class as u8, self.io_priority.unwrap_or_default()));
+        #[cfg(not(target_os = "linux"))]
+        let io_priority = None::<(u8, u8)>;
+        io_priority.encode(out);
+
+        #[cfg(target_os = "linux")]
+        let container = self.container.clone();
+        #[cfg(not(target_os = "linux"))]
+        let container = None::<String>;
+        container.encode(out);
+
+        #[cfg(feature = "gpu")]
+        let gpu = (self.gpu_usage_percent, self.gpu_mem_bytes);
+        #[cfg(not(feature = "gpu"))]
+        let gpu = (0.0_f64, 0_u64);
+        gpu.encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        let pid = decode_pid(input)?;
+        let parent_pid = match Option::<u64>::decode(input)? {
+            Some(pid) => Some(Pid::try_from(pid).ok()?),
+            None => None,
+        };
+        let cpu_usage_percent = Wire::decode(input)?;
+        let mem_usage_percent = Wire::decode(input)?;
+        let mem_usage_bytes = Wire::decode(input)?;
+        let name = Wire::decode(input)?;
+        let command = Wire::decode(input)?;
+        let read_bytes_per_sec = Wire::decode(input)?;
+        let write_bytes_per_sec = Wire::decode(input)?;
+        let total_read_bytes = Wire::decode(input)?;
+        let total_write_bytes = Wire::decode(input)?;
+        let process_state = (Wire::decode(input)?, Wire::decode(input)?);
+        let user = String::decode(input)?.into();
+        #[allow(unused_variables)]
+        let uid = Option::<u32>::decode(input)?;
+        #[allow(unused_variables)]
+        let nice = Option::<i32>::decode(input)?;
+        #[allow(unused_variables)]
+        let io_priority = Option::<(u8, u8)>::decode(input)?;
+        #[allow(unused_variables)]
+        let container = Option::<String>::decode(input)?;
+        #[allow(unused_variables)]
+        let gpu = <(f64, u64)>::decode(input)?;
+
+        Some(ProcessHarvest {
+            pid,
+            parent_pid,
+            cpu_usage_percent,
+            mem_usage_percent,
+            mem_usage_bytes,
+            name,
+            command,
+            read_bytes_per_sec,
+            write_bytes_per_sec,
+            total_read_bytes,
+            total_write_bytes,
+            process_state,
+            #[cfg(target_family = "unix")]
+            uid,
+            user,
+            #[cfg(target_family = "unix")]
+            nice,
+            #[cfg(target_os = "linux")]
+            io_class: io_priority.and_then(|(class, _)| {
+                crate::app::data_harvester::processes::IOClass::ALL
+                    .get(usize::from(class))
+                    .copied()
+            }),
+            #[cfg(target_os = "linux")]
+            io_priority: io_priority.map(|(_, level)| level),
+            #[cfg(target_os = "linux")]
+            container,
+            #[cfg(feature = "gpu")]
+            gpu_usage_percent: gpu.0,
+            #[cfg(feature = "gpu")]
+            gpu_mem_bytes: gpu.1,
+        })
+    }
+}
+
+impl Wire for Data {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.cpu.encode(out);
+        self.load_avg.encode(out);
+        self.memory.encode(out);
+        self.swap.encode(out);
+        self.temperature_sensors.encode(out);
+        self.network.encode(out);
+        self.list_of_processes.encode(out);
+        self.disks.encode(out);
+        self.io.encode(out);
+        self.connections.encode(out);
+        self.connection_stats.encode(out);
+        self.remote_uptime.encode(out);
+        self.containers.encode(out);
+        self.pressure.encode(out);
+        self.raid.encode(out);
+        self.zfs_pools.encode(out);
+        self.cgroups.encode(out);
+        self.scheduled_jobs.encode(out);
+
+        // Batteries are skipped over by a viewer built without them, so they are sent with their
+        // length in bytes.
+        #[cfg(feature = "battery")]
+        let batteries = self.list_of_batteries.as_ref().map(|batteries| {
+            let mut bytes = Vec::new();
+            batteries.encode(&mut bytes);
+            bytes
+        });
+        #[cfg(not(feature = "battery"))]
+        let batteries = None::<Vec<u8>>;
+        batteries.encode(out);
+
+        #[cfg(feature = "zfs")]
+        let arc = self.arc.clone();
+        #[cfg(not(feature = "zfs"))]
+        let arc = None::<MemHarvest>;
+        arc.encode(out);
+
+        #[cfg(feature = "gpu")]
+        let gpu = self.gpu.clone();
+        #[cfg(not(feature = "gpu"))]
+        let gpu = None::<Vec<(String, MemHarvest)>>;
+        gpu.encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        // Everything else is behind a feature, so there's nothing left to default without them.
+        #[allow(unused_mut, clippy::needless_update)]
+        let mut data = Data {
+            last_collection_time: Instant::now(),
+            cpu: Wire::decode(input)?,
+            load_avg: Wire::decode(input)?,
+            memory: Wire::decode(input)?,
+            swap: Wire::decode(input)?,
+            temperature_sensors: Wire::decode(input)?,
+            network: Wire::decode(input)?,
+            list_of_processes: Wire::decode(input)?,
+            disks: Wire::decode(input)?,
+            io: Wire::decode(input)?,
+            connections: Wire::decode(input)?,
+            connection_stats: Wire::decode(input)?,
+            remote_uptime: Wire::decode(input)?,
+            containers: Wire::decode(input)?,
+            pressure: Wire::decode(input)?,
+            raid: Wire::decode(input)?,
+            zfs_pools: Wire::decode(input)?,
+            cgroups: Wire::decode(input)?,
+            scheduled_jobs: Wire::decode(input)?,
+            ..Data::default()
+        };
+
+        #[allow(unused_variables)]
+        let batteries = Option::<Vec<u8>>::decode(input)?;
+        #[cfg(feature = "battery")]
+        {
+            data.list_of_batteries = match batteries {
+                Some(bytes) => Some(Wire::decode(&mut bytes.as_slice())?),
+                None => None,
+            };
+        }
+
+        #[allow(unused_variables)]
+        let arc = Option::<MemHarvest>::decode(input)?;
+        #[cfg(feature = "zfs")]
+        {
+            data.arc = arc;
+        }
+
+        #[allow(unused_variables)]
+        let gpu = Option::<Vec<(String, MemHarvest)>>::decode(input)?;
+        #[cfg(feature = "gpu")]
+        {
+            data.gpu = gpu;
+        }
+
+        Some(data)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn round_trip<T: Wire>(value: &T) -> T {
+        let mut bytes = Vec::new();
+        value.encode(&mut bytes);
+        let mut input = bytes.as_slice();
+        let decoded = T::decode(&mut input).expect("value should decode");
+        assert!(input.is_empty());
+        decoded
+    }
+
+    #[test]
+    fn test_round_trip_values() {
+        assert_eq!(round_trip(&"héllo".to_string()), "héllo");
+        assert_eq!(round_trip(&Some(-5i64)), Some(-5));
+        assert_eq!(round_trip(&None::<u8>), None);
+        assert_eq!(round_trip(&[0.5f32, 1.0, 1.5]), [0.5, 1.0, 1.5]);
+
+        let addresses: Vec<SocketAddr> = vec![
+            "10.0.0.1:22".parse().unwrap(),
+            "[2001:db8::1]:443".parse().unwrap(),
+        ];
+        assert_eq!(round_trip(&addresses), addresses);
+
+        let map = HashMap::from([("eth0".to_string(), (1u64, 2u64))]);
+        assert_eq!(round_trip(&map), map);
+
+        let request = Request::Command {
+            command: "ls -l".to_string(),
+            token: "secret".to_string(),
+        };
+        assert_eq!(round_trip(&request), request);
+        assert_eq!(round_trip(&Request::Updates), Request::Updates);
+        let output = CommandEvent::Output(b"total 0\n".to_vec());
+        assert_eq!(round_trip(&output), output);
+        assert_eq!(
+            round_trip(&CommandEvent::Exited(Some(256))),
+            CommandEvent::Exited(Some(256))
+        );
+    }
+
+    #[test]
+    fn test_round_trip_data() {
+        let data = Data {
+            load_avg: Some([0.1, 0.2, 0.3]),
+            memory: Some(MemHarvest {
+                total_kib: 2048,
+                used_kib: 1024,
+                use_percent: Some(50.0),
+            }),
+            list_of_processes: Some(vec![ProcessHarvest {
+                pid: 42,
+                parent_pid: Some(1),
+                name: "init".to_string(),
+                process_state: ("Sleeping".to_string(), 'S'),
+                user: "root".into(),
+                #[cfg(target_family = "unix")]
+                nice: Some(-5),
+                #[cfg(target_os = "linux")]
+                container: Some("docker:0123456789ab".to_string()),
+                ..ProcessHarvest::default()
+            }]),
+            connections: Some(vec![ConnectionHarvest {
+                protocol: Protocol::Udp,
+                local: "0.0.0.0:53".parse().unwrap(),
+                remote: "0.0.0.0:0".parse().unwrap(),
+                state: String::new(),
+                pid: None,
+                name: None,
+            }]),
+            ..Data::default()
+        };
+
+        let decoded = round_trip(&data);
+        assert_eq!(decoded.load_avg, data.load_avg);
+        assert_eq!(decoded.memory.unwrap().used_kib, 1024);
+        assert_eq!(decoded.connections, data.connections);
+        assert!(decoded.cpu.is_none());
+
+        let process = &decoded.list_of_processes.unwrap()[0];
+        assert_eq!(process.pid, 42);
+        assert_eq!(process.parent_pid, Some(1));
+        assert_eq!(process.name, "init");
+        assert_eq!(process.process_state, ("Sleeping".to_string(), 'S'));
+        assert_eq!(process.user, "root");
+        #[cfg(target_family = "unix")]
+        assert_eq!(process.nice, Some(-5));
+        #[cfg(target_os = "linux")]
+        assert_eq!(process.container.as_deref(), Some("docker:0123456789ab"));
+    }
+
+    #[test]
+    fn test_decode_malformed() {
+        let mut bytes = Vec::new();
+        "hello".to_string().encode(&mut bytes);
+
+        // Cut off partway through, and with a length that claims more than is there.
+        assert!(String::decode(&mut &bytes[..3]).is_none());
+        assert!(Vec::<u8>::decode(&mut [0xff, 0xff, 0xff, 0xff].as_slice()).is_none());
+        assert!(bool::decode(&mut [2].as_slice()).is_none());
+    }
+}
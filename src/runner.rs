@@ -0,0 +1,573 @@
+//! Everything bottom does once its arguments are parsed, started with [`run`]. The binary is only
+//! a call to it, so programs built on the library can register their own widgets and then start
+//! bottom the same way.
+
+use std::{
+    collections::HashMap,
+    io::stdout,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc, Condvar, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    app::{self, data_harvester::CollectionError},
+    canvas::{self, canvas_styling::CanvasColours},
+    check_if_terminal, cleanup_terminal,
+    constants::*,
+    create_collection_thread, create_config_watcher_thread, create_input_thread,
+    create_or_get_config, handle_key_event_or_break, handle_mouse_event,
+    options::*,
+    process_update, read_config, remote, server,
+    sinks::{EventSink, Sinks},
+    try_drawing, update_data,
+    widgets::{self, LogFollower, LogSource},
+    BottomEvent, ThreadControlEvent,
+};
+use ::clap::ArgMatches;
+use anyhow::{Context, Result};
+use crossterm::{
+    event::{EnableBracketedPaste, EnableMouseCapture},
+    execute,
+    terminal::{enable_raw_mode, EnterAlternateScreen},
+};
+use tui::{backend::CrosstermBackend, Terminal};
+
+/// Runs bottom with the given arguments until it is quit. Custom widgets have to be registered
+/// with [`register_widget`](crate::components::custom_widget::register_widget) before this, to be
+/// put in the layout.
+pub fn run(matches: ArgMatches) -> Result<()> {
+    // Write out a default config file if asked to, without starting the app.
+    if matches.contains_id("generate_config") {
+        let config_text = generate_annotated_config();
+        match matches.get_one::<String>("generate_config") {
+            Some(path) if path != "-" => std::fs::write(path, config_text)
+                .with_context(|| format!("Unable to write the config file to '{path}'."))?,
+            _ => print!("{config_text}"),
+        }
+        return Ok(());
+    }
+
+    // Read from config file.
+    let config_path = read_config(matches.get_one::<String>("config_location"))
+        .context("Unable to access the given config file location.")?;
+    let mut config: Config = create_or_get_config(&config_path)
+        .context("Unable to properly parse or create the config file.")?;
+
+    // Get widget layout separately
+    let (layout_tabs, default_widget_id, default_widget_type_option) =
+        get_widget_layout(&matches, &config)
+            .context("Found an issue while trying to build the widget layout.")?;
+
+    // FIXME: Should move this into build app or config
+    let colours = {
+        let colour_scheme = get_color_scheme(&matches, &config)?;
+        CanvasColours::new(colour_scheme, &config)?
+    };
+
+    // Create "app" struct, which will control most of the program and store settings/state
+    let mut app = build_app(
+        &matches,
+        &mut config,
+        &layout_tabs,
+        default_widget_id,
+        &default_widget_type_option,
+        &colours,
+    )?;
+
+    // Terminal widget history lives next to the config file.
+    if app.app_config_fields.save_terminal_history {
+        if let Some(config_dir) = config_path.as_ref().and_then(|path| path.parent()) {
+            app.terminal_state
+                .load_history(config_dir)
+                .context("Unable to read the terminal widget history.")?;
+        }
+    }
+
+    // An agent only collects and streams data, so it never sets up the interface.
+    if let Some(address) = app.app_config_fields.agent_address {
+        // Building the app fails if commands are allowed without a token.
+        let commands = app
+            .app_config_fields
+            .agent_token
+            .clone()
+            .filter(|_| app.app_config_fields.agent_allows_commands)
+            .map(|token| remote::CommandAccess {
+                shell: app.app_config_fields.terminal_shell.clone(),
+                token,
+            });
+        let agent_sink = remote::spawn_agent(address, commands)
+            .with_context(|| format!("Unable to stream on '{address}'."))?;
+        if app.app_config_fields.agent_advertises {
+            remote::discovery::advertise(address.port())
+                .context("Unable to advertise the agent with mDNS.")?;
+        }
+
+        #[allow(clippy::mutex_atomic)]
+        let thread_termination_lock = Arc::new(Mutex::new(false));
+        let (sender, receiver) = mpsc::channel();
+        let (_collection_thread_ctrl_sender, collection_thread_ctrl_receiver) = mpsc::channel();
+        let _collection_thread = create_collection_thread(
+            sender,
+            collection_thread_ctrl_receiver,
+            thread_termination_lock,
+            Arc::new(Condvar::new()),
+            &app.app_config_fields,
+            app.filters.clone(),
+            app.used_widgets.clone(),
+            Sinks::new(vec![Box::new(agent_sink)]),
+            None,
+        );
+        println!("Streaming collected data on {address}.");
+
+        // Updates go straight to the viewers, so only collection errors come back here, and an
+        // agent has nowhere to show them.
+        for _ in receiver {}
+        return Ok(());
+    }
+
+    // Without an interface, only serve the collected data until stopped, like under a service
+    // manager where no one attaches a terminal.
+    if app.app_config_fields.headless {
+        return run_headless(app);
+    }
+
+    // Show the data of an agent elsewhere instead, if asked to. Connecting happens before the
+    // interface starts, so an unreachable agent is reported like any other startup error.
+    let remote_source = match &app.app_config_fields.remote_address {
+        Some(address) => Some(
+            remote::RemoteSource::connect(address)
+                .with_context(|| format!("Unable to connect to '{address}'."))?,
+        ),
+        None => None,
+    };
+
+    // Create painter and set colours.
+    let mut painter = canvas::Painter::init(layout_tabs, colours, &app.app_config_fields)?;
+
+    // Check if the current environment is in a terminal.
+    check_if_terminal();
+
+    // Create termination mutex and cvar
+    #[allow(clippy::mutex_atomic)]
+    let thread_termination_lock = Arc::new(Mutex::new(false));
+    let thread_termination_cvar = Arc::new(Condvar::new());
+
+    // Set up input handling
+    let (sender, receiver) = mpsc::channel();
+    let _input_thread = create_input_thread(sender.clone(), thread_termination_lock.clone());
+
+    // Apply the config file again whenever it is saved.
+    if let Some(path) = &config_path {
+        let _config_watcher_thread = create_config_watcher_thread(
+            sender.clone(),
+            thread_termination_lock.clone(),
+            path.clone(),
+        );
+    }
+
+    // Look for agents on the local network, if something shows them.
+    if app.used_widgets.use_discovery {
+        remote::discovery::spawn_browser(sender.clone())
+            .context("Unable to look for agents with mDNS.")?;
+    }
+
+    // Follow the logs shown by log widgets.
+    let mut log_followers = HashMap::new();
+    follow_logs(&app, &mut log_followers, &sender);
+
+    // Cleaning loop
+    let _cleaning_thread = {
+        let lock = thread_termination_lock.clone();
+        let cvar = thread_termination_cvar.clone();
+        let cleaning_sender = sender.clone();
+        let offset_wait_time = app.app_config_fields.retention_ms + 60000;
+        thread::spawn(move || {
+            loop {
+                let result = cvar.wait_timeout(
+                    lock.lock().unwrap(),
+                    Duration::from_millis(offset_wait_time),
+                );
+                if let Ok(result) = result {
+                    if *(result.0) {
+                        break;
+                    }
+                }
+                if cleaning_sender.send(BottomEvent::Clean).is_err() {
+                    // debug!("Failed to send cleaning sender...");
+                    break;
+                }
+            }
+        })
+    };
+
+    // Serve the collected data, if asked to. This binds before the interface starts, so a taken
+    // address is reported like any other startup error.
+    let (serve_address, serve_prometheus) = (
+        app.app_config_fields.serve_address,
+        app.app_config_fields.serve_prometheus,
+    );
+    let mut sinks = Sinks::new(vec![Box::new(EventSink(sender.clone()))]);
+    if let Some(address) = serve_address {
+        let snapshot = Arc::new(Mutex::new(server::Snapshot::new(serve_prometheus)));
+        server::spawn_server(address, snapshot.clone())
+            .with_context(|| format!("Unable to serve on '{address}'."))?;
+        sinks.register(Box::new(snapshot));
+    }
+
+    // Event loop
+    let (collection_thread_ctrl_sender, collection_thread_ctrl_receiver) = mpsc::channel();
+    let _collection_thread = create_collection_thread(
+        sender.clone(),
+        collection_thread_ctrl_receiver,
+        thread_termination_lock.clone(),
+        thread_termination_cvar.clone(),
+        &app.app_config_fields,
+        app.filters.clone(),
+        app.used_widgets.clone(),
+        sinks,
+        remote_source,
+    );
+
+    // Set up up tui and crossterm
+    let mut stdout_val = stdout();
+    execute!(
+        stdout_val,
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        EnableBracketedPaste
+    )?;
+    enable_raw_mode()?;
+
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout_val))?;
+    terminal.clear()?;
+    terminal.hide_cursor()?;
+
+    #[cfg(target_os = "freebsd")]
+    let _stderr_fd = {
+        // A really ugly band-aid to suppress stderr warnings on FreeBSD due to sysinfo.
+        use std::fs::OpenOptions;
+
+        use filedescriptor::{FileDescriptor, StdioDescriptor};
+
+        let path = OpenOptions::new().write(true).open("/dev/null")?;
+        FileDescriptor::redirect_stdio(&path, StdioDescriptor::Stderr)?
+    };
+
+    // Set termination hook
+    let is_terminated = Arc::new(AtomicBool::new(false));
+    let ist_clone = is_terminated.clone();
+    ctrlc::set_handler(move || {
+        ist_clone.store(true, Ordering::SeqCst);
+    })?;
+    let mut first_run = true;
+    // The phase alerting borders were last redrawn in, see below.
+    let mut drawn_blink_phase = None;
+
+    while !is_terminated.load(Ordering::SeqCst) {
+        // TODO: Would be good to instead use a mix of is_terminated check + recv. Probably use a termination event instead.
+        if let Ok(recv) = receiver.recv_timeout(Duration::from_millis(TICK_RATE_IN_MILLISECONDS)) {
+            match recv {
+                BottomEvent::Resize => {
+                    try_drawing(&mut terminal, &mut app, &mut painter)?;
+                    // FIXME: This is bugged with frozen?
+                }
+                BottomEvent::KeyInput(event) => {
+                    if handle_key_event_or_break(
+                        event,
+                        &mut app,
+                        &collection_thread_ctrl_sender,
+                        &sender,
+                        //thread_termination_cvar.clone(),
+                    ) {
+                        break;
+                    }
+                    update_data(&mut app);
+                    try_drawing(&mut terminal, &mut app, &mut painter)?;
+                }
+                BottomEvent::MouseInput(event) => {
+                    handle_mouse_event(event, &mut app);
+                    update_data(&mut app);
+                    try_drawing(&mut terminal, &mut app, &mut painter)?;
+                }
+                BottomEvent::PasteEvent(paste) => {
+                    app.handle_paste(paste);
+                    update_data(&mut app);
+                    try_drawing(&mut terminal, &mut app, &mut painter)?;
+                }
+                BottomEvent::Update(data) => {
+                    process_update(&mut app, data);
+
+                    // This thing is required as otherwise, some widgets can't draw correctly w/o
+                    // some data (or they need to be re-drawn).
+                    if first_run {
+                        first_run = false;
+                        app.is_force_redraw = true;
+                    }
+
+                    if !app.frozen_state.is_frozen() {
+                        try_drawing(&mut terminal, &mut app, &mut painter)?;
+                    }
+                }
+                BottomEvent::CollectionError(error) => {
+                    app.push_collection_error(error);
+
+                    if !app.frozen_state.is_frozen() {
+                        try_drawing(&mut terminal, &mut app, &mut painter)?;
+                    }
+                }
+                BottomEvent::TerminalOutput { widget_id, chunk } => {
+                    let keep_colours = !app.app_config_fields.disable_terminal_colors;
+                    if let Some(t) = app.terminal_state.get_mut_widget_state(widget_id) {
+                        t.append_output(&chunk, keep_colours);
+                    }
+                    try_drawing(&mut terminal, &mut app, &mut painter)?;
+                }
+                BottomEvent::TerminalStarted { widget_id, started } => {
+                    if let Some(t) = app.terminal_state.get_mut_widget_state(widget_id) {
+                        t.start(started);
+                    }
+                }
+                BottomEvent::TerminalTimedOut {
+                    widget_id,
+                    command,
+                    timeout_secs,
+                } => {
+                    if let Some(t) = app.terminal_state.get_mut_widget_state(widget_id) {
+                        if t.time_out(command) {
+                            t.stdout += &format!("Timed out after {timeout_secs}s\n");
+                            try_drawing(&mut terminal, &mut app, &mut painter)?;
+                        }
+                    }
+                }
+                BottomEvent::TerminalFinished {
+                    widget_id,
+                    command,
+                    status,
+                    elapsed,
+                    note,
+                } => {
+                    let keep_colours = !app.app_config_fields.disable_terminal_colors;
+                    if let Some(t) = app.terminal_state.get_mut_widget_state(widget_id) {
+                        // A cancelled command has already freed the widget.
+                        if t.release(command) {
+                            if let Some(note) = note {
+                                t.append_output(note.as_bytes(), keep_colours);
+                            }
+                            t.finish(status, elapsed, keep_colours);
+                        }
+                    }
+                    try_drawing(&mut terminal, &mut app, &mut painter)?;
+                }
+                BottomEvent::LogLines { widget_id, lines } => {
+                    if let Some(log_widget_state) = app.log_state.get_mut_widget_state(widget_id) {
+                        log_widget_state.push_lines(lines);
+                    }
+
+                    if !app.frozen_state.is_frozen() {
+                        try_drawing(&mut terminal, &mut app, &mut painter)?;
+                    }
+                }
+                BottomEvent::AgentsDiscovered(agents) => {
+                    app.discovered_agents = agents;
+                    update_data(&mut app);
+                    try_drawing(&mut terminal, &mut app, &mut painter)?;
+                }
+                BottomEvent::Connected(address) => {
+                    // What was collected so far belongs to the previous machine.
+                    app.app_config_fields.remote_address = Some(address);
+                    app.reset();
+                    update_data(&mut app);
+                    try_drawing(&mut terminal, &mut app, &mut painter)?;
+                }
+                BottomEvent::ConfigChanged => {
+                    match reload_config(&matches, &config_path, &mut app, &mut painter) {
+                        Ok(()) => {
+                            follow_logs(&app, &mut log_followers, &sender);
+                            let config_fields = &app.app_config_fields;
+                            for event in [
+                                ThreadControlEvent::UpdateConfig(Box::new(config_fields.clone())),
+                                ThreadControlEvent::UpdateUsedWidgets(Box::new(
+                                    app.used_widgets.clone(),
+                                )),
+                                ThreadControlEvent::UpdateUpdateTime(
+                                    config_fields.update_rate_in_milliseconds,
+                                ),
+                                ThreadControlEvent::UpdateFilters(Box::new(app.filters.clone())),
+                            ] {
+                                let _ = collection_thread_ctrl_sender.send(event);
+                            }
+                        }
+                        Err(err) => {
+                            // The config is likely mid-edit, so keep going with the last one that
+                            // worked.
+                            app.push_collection_error(CollectionError::new(
+                                "Config",
+                                format!("{err:#}"),
+                            ));
+                            app.error_log_state.is_showing = true;
+                        }
+                    }
+                    update_data(&mut app);
+                    app.is_force_redraw = true;
+                    try_drawing(&mut terminal, &mut app, &mut painter)?;
+                }
+                BottomEvent::Clean => {
+                    let retention_ms = app.app_config_fields.retention_ms;
+                    app.data_collection.clean_data(retention_ms);
+                    for net_widget_state in app.net_state.widget_states.values_mut() {
+                        net_widget_state.clean_tcp_connections(retention_ms);
+                    }
+                    for battery_widget_state in app.battery_state.widget_states.values_mut() {
+                        battery_widget_state.clean_power_history(retention_ms);
+                    }
+                }
+            }
+        }
+
+        // Nothing else is drawn between events, so alerting borders are redrawn each time they
+        // should blink, however often the data updates.
+        let is_blinking =
+            !app.alerting_widget_ids.is_empty() && !app.app_config_fields.disable_alert_blinking;
+        let blink_phase = is_blinking.then(canvas::is_alert_blink_on);
+        if blink_phase.is_some() && blink_phase != drawn_blink_phase {
+            try_drawing(&mut terminal, &mut app, &mut painter)?;
+        }
+        drawn_blink_phase = blink_phase;
+    }
+
+    // I think doing it in this order is safe...
+
+    *thread_termination_lock.lock().unwrap() = true;
+
+    thread_termination_cvar.notify_all();
+
+    cleanup_terminal(&mut terminal)?;
+
+    app.terminal_state
+        .save_history()
+        .context("Unable to save the terminal widget history.")?;
+
+    Ok(())
+}
+
+/// Follows the logs of every log widget that isn't followed already, and stops following those of
+/// widgets no longer in the layout, or that follow a log that is no longer set.
+fn follow_logs(
+    app: &app::App, followers: &mut HashMap<u64, LogFollower>, sender: &mpsc::Sender<BottomEvent>,
+) {
+    let source = match &app.app_config_fields.log_file {
+        Some(path) => LogSource::File(path.clone()),
+        None => LogSource::Journal,
+    };
+    followers.retain(|widget_id, follower| {
+        app.log_state.widget_states.contains_key(widget_id) && follower.source == source
+    });
+    for widget_id in app.log_state.widget_states.keys() {
+        followers
+            .entry(*widget_id)
+            .or_insert_with(|| widgets::spawn_follower(source.clone(), *widget_id, sender.clone()));
+    }
+}
+
+/// Builds the app and painter again from the config file, keeping what was collected so far, any
+/// errors, and the state of the widgets still in the layout. Anything only set up on startup, like
+/// serving or running as an agent, keeps its old settings until a restart.
+fn reload_config(
+    matches: &ArgMatches, config_path: &Option<PathBuf>, app: &mut app::App,
+    painter: &mut canvas::Painter,
+) -> Result<()> {
+    let mut config =
+        create_or_get_config(config_path).context("Unable to parse the config file")?;
+    let (layout_tabs, default_widget_id, default_widget_type_option) =
+        get_widget_layout(matches, &config).context("Unable to build the widget layout")?;
+    let colours = {
+        let colour_scheme = get_color_scheme(matches, &config)?;
+        CanvasColours::new(colour_scheme, &config)?
+    };
+    let mut new_app = build_app(
+        matches,
+        &mut config,
+        &layout_tabs,
+        default_widget_id,
+        &default_widget_type_option,
+        &colours,
+    )?;
+    if new_app.app_config_fields.save_terminal_history {
+        if let Some(config_dir) = config_path.as_ref().and_then(|path| path.parent()) {
+            new_app
+                .terminal_state
+                .load_history(config_dir)
+                .context("Unable to read the terminal widget history.")?;
+        }
+    }
+    let new_painter = canvas::Painter::init(layout_tabs, colours, &new_app.app_config_fields)?;
+
+    new_app.carry_over(app);
+
+    *app = new_app;
+    *painter = new_painter;
+
+    Ok(())
+}
+
+/// Collects data for the server until interrupted, without ever touching the terminal. Collection
+/// errors go to stderr instead of being shown in a widget.
+fn run_headless(app: app::App) -> Result<()> {
+    let Some(address) = app.app_config_fields.serve_address else {
+        anyhow::bail!(
+            "There is nothing to do without an interface, so use --headless with --serve."
+        );
+    };
+    let snapshot = Arc::new(Mutex::new(server::Snapshot::new(
+        app.app_config_fields.serve_prometheus,
+    )));
+    server::spawn_server(address, snapshot.clone())
+        .with_context(|| format!("Unable to serve on '{address}'."))?;
+
+    #[allow(clippy::mutex_atomic)]
+    let thread_termination_lock = Arc::new(Mutex::new(false));
+    let thread_termination_cvar = Arc::new(Condvar::new());
+    let (sender, receiver) = mpsc::channel();
+    let (_collection_thread_ctrl_sender, collection_thread_ctrl_receiver) = mpsc::channel();
+    let _collection_thread = create_collection_thread(
+        sender,
+        collection_thread_ctrl_receiver,
+        thread_termination_lock.clone(),
+        thread_termination_cvar.clone(),
+        &app.app_config_fields,
+        app.filters.clone(),
+        app.used_widgets.clone(),
+        Sinks::new(vec![Box::new(snapshot)]),
+        None,
+    );
+
+    let is_terminated = Arc::new(AtomicBool::new(false));
+    let ist_clone = is_terminated.clone();
+    ctrlc::set_handler(move || {
+        ist_clone.store(true, Ordering::SeqCst);
+    })?;
+
+    println!("Serving collected data on {address}.");
+    // An error that keeps happening, like a missing sensor, is only written once.
+    let mut last_errors: HashMap<String, String> = HashMap::new();
+    while !is_terminated.load(Ordering::SeqCst) {
+        if let Ok(BottomEvent::CollectionError(error)) =
+            receiver.recv_timeout(Duration::from_millis(TICK_RATE_IN_MILLISECONDS))
+        {
+            if last_errors.get(&error.source) != Some(&error.message) {
+                eprintln!("{}: {}", error.source, error.message);
+                last_errors.insert(error.source, error.message);
+            }
+        }
+    }
+
+    *thread_termination_lock.lock().unwrap() = true;
+    thread_termination_cvar.notify_all();
+
+    Ok(())
+}
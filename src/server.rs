@@ -0,0 +1,414 @@
+//! A small HTTP server that serves the latest harvested data as JSON, for `--serve`.
+//!
+//! The collection thread keeps a [`Snapshot`] up to date, and the server answers each request from
+//! whatever it holds at the time. Every endpoint only handles `GET`, and closes the connection once
+//! it has answered. With `[server] prometheus = true`, the data is also served in the Prometheus
+//! text format at `/metrics`.
+
+mod metrics;
+
+use std::{
+    collections::BTreeMap,
+    io::{BufRead, BufReader, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{Arc, Mutex},
+    thread::{self, JoinHandle},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    app::data_harvester::{cpu::CpuDataType, disks::MountType, Data},
+    sinks::Sink,
+    utils::{error, json::Json},
+};
+
+/// How long a client has to send each part of its request, or take each part of the response,
+/// before it is dropped.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How much of a request is read, which is plenty for a `GET` and its headers. Anything past it is
+/// ignored, so a header that never ends can't use up memory.
+const MAX_REQUEST_LEN: u64 = 8 * 1024;
+
+const JSON_CONTENT_TYPE: &str = "application/json";
+
+/// The latest value of each endpoint. Parts of the data that weren't harvested in an update keep
+/// their previous value.
+#[derive(Default)]
+pub struct Snapshot {
+    endpoints: BTreeMap<&'static str, Json>,
+    /// When the snapshot was last updated, in seconds since the Unix epoch.
+    updated_at: Option<f64>,
+    /// The Prometheus metrics, if they are served.
+    metrics: Option<metrics::Metrics>,
+}
+
+pub type SharedSnapshot = Arc<Mutex<Snapshot>>;
+
+impl Sink for SharedSnapshot {
+    fn push(&mut self, data: &Data) -> bool {
+        // A poisoned snapshot can't be served anymore either.
+        self.lock()
+            .map(|mut snapshot| snapshot.update(data))
+            .is_ok()
+    }
+}
+
+impl Snapshot {
+    pub fn new(prometheus: bool) -> Self {
+        Self {
+            metrics: prometheus.then(metrics::Metrics::default),
+            ..Self::default()
+        }
+    }
+
+    pub fn update(&mut self, data: &Data) {
+        if let Some(metrics) = &mut self.metrics {
+            metrics.update(data);
+        }
+
+        if let Some(cpu) = &data.cpu {
+            let cores = cpu
+                .iter()
+                .map(|cpu| {
+                    Json::Object(vec![
+                        (
+                            "name",
+                            match cpu.data_type {
+                                CpuDataType::Avg => "avg".into(),
+                                CpuDataType::Cpu(index) => Json::String(format!("cpu{index}")),
+                            },
+                        ),
+                        ("usage_percent", cpu.cpu_usage.into()),
+                    ])
+                })
+                .collect();
+            let load_avg = data.load_avg.map_or(Json::Null, |load_avg| {
+                Json::Array(
+                    load_avg
+                        .iter()
+                        .map(|load| f64::from(*load).into())
+                        .collect(),
+                )
+            });
+            self.endpoints.insert(
+                "cpu",
+                Json::Object(vec![("cores", Json::Array(cores)), ("load_avg", load_avg)]),
+            );
+        }
+
+        if data.memory.is_some() || data.swap.is_some() {
+            let memory = |memory: &Option<crate::app::data_harvester::memory::MemHarvest>| {
+                memory.as_ref().map_or(Json::Null, |memory| {
+                    Json::Object(vec![
+                        ("total_bytes", (memory.total_kib * 1024).into()),
+                        ("used_bytes", (memory.used_kib * 1024).into()),
+                        ("used_percent", memory.use_percent.into()),
+                    ])
+                })
+            };
+            self.endpoints.insert(
+                "memory",
+                Json::Object(vec![
+                    ("memory", memory(&data.memory)),
+                    ("swap", memory(&data.swap)),
+                ]),
+            );
+        }
+
+        if let Some(processes) = &data.list_of_processes {
+            let processes = processes
+                .iter()
+                .map(|process| {
+                    Json::Object(vec![
+                        ("pid", (process.pid as u64).into()),
+                        (
+                            "parent_pid",
+                            process.parent_pid.map(|pid| pid as u64).into(),
+                        ),
+                        ("name", process.name.as_str().into()),
+                        ("command", process.command.as_str().into()),
+                        ("user", process.user.as_ref().into()),
+                        ("state", process.process_state.0.as_str().into()),
+                        ("cpu_percent", process.cpu_usage_percent.into()),
+                        ("mem_percent", process.mem_usage_percent.into()),
+                        ("mem_bytes", process.mem_usage_bytes.into()),
+                        ("read_bytes_per_sec", process.read_bytes_per_sec.into()),
+                        ("write_bytes_per_sec", process.write_bytes_per_sec.into()),
+                    ])
+                })
+                .collect();
+            self.endpoints.insert("processes", Json::Array(processes));
+        }
+
+        if let Some(network) = &data.network {
+            self.endpoints.insert(
+                "network",
+                Json::Object(vec![
+                    ("rx_bits_per_sec", network.rx.into()),
+                    ("tx_bits_per_sec", network.tx.into()),
+                    ("total_rx_bits", network.total_rx.into()),
+                    ("total_tx_bits", network.total_tx.into()),
+                    ("tcp_connections", network.total_tcp_connections.into()),
+                ]),
+            );
+        }
+
+        if let Some(disks) = &data.disks {
+            let disks = disks
+                .iter()
+                .map(|disk| {
+                    Json::Object(vec![
+                        ("name", disk.name.as_str().into()),
+                        ("mount_point", disk.mount_point.as_str().into()),
+                        (
+                            "mount_type",
+                            match disk.mount_type {
+                                MountType::Local => "local",
+                                MountType::Nfs => "nfs",
+                                MountType::Cifs => "cifs",
+                                MountType::Tmpfs => "tmpfs",
+                                MountType::Other => "other",
+                            }
+                            .into(),
+                        ),
+                        ("total_bytes", disk.total_space.into()),
+                        ("used_bytes", disk.used_space.into()),
+                        ("free_bytes", disk.free_space.into()),
+                        ("stale", Json::Bool(disk.stale)),
+                    ])
+                })
+                .collect();
+            self.endpoints.insert("disks", Json::Array(disks));
+        }
+
+        if let Some(connections) = &data.connections {
+            let connections = connections
+                .iter()
+                .map(|connection| {
+                    Json::Object(vec![
+                        ("protocol", connection.protocol.to_string().as_str().into()),
+                        ("local", connection.local.to_string().as_str().into()),
+                        ("remote", connection.remote.to_string().as_str().into()),
+                        ("state", connection.state.as_str().into()),
+                        ("pid", connection.pid.map(|pid| pid as u64).into()),
+                        ("process", connection.name.as_deref().into()),
+                    ])
+                })
+                .collect();
+            self.endpoints
+                .insert("connections", Json::Array(connections));
+        }
+
+        self.updated_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .ok()
+            .map(|now| now.as_secs_f64());
+    }
+
+    /// Every endpoint at once, as served at `/`.
+    pub fn to_json(&self) -> Json {
+        let mut entries = vec![("updated_at", self.updated_at.into())];
+        entries.extend(
+            self.endpoints
+                .iter()
+                .map(|(name, value)| (*name, value.clone())),
+        );
+        Json::Object(entries)
+    }
+
+    /// The content type and body for a request to `path`, or [`None`] if there is no such
+    /// endpoint.
+    fn render(&self, path: &str) -> Option<(&'static str, String)> {
+        match path.trim_end_matches('/') {
+            "" => Some((JSON_CONTENT_TYPE, self.to_json().to_string())),
+            "/metrics" => self
+                .metrics
+                .as_ref()
+                .map(|metrics| (metrics::CONTENT_TYPE, metrics.render())),
+            path => {
+                let name = path.strip_prefix('/')?;
+                ENDPOINTS.contains(&name).then(|| {
+                    let body = self
+                        .endpoints
+                        .get(name)
+                        .cloned()
+                        .unwrap_or(Json::Null)
+                        .to_string();
+                    (JSON_CONTENT_TYPE, body)
+                })
+            }
+        }
+    }
+}
+
+/// Everything that can be asked for on its own, besides `/` for all of it at once.
+const ENDPOINTS: [&str; 6] = [
+    "cpu",
+    "memory",
+    "processes",
+    "network",
+    "disks",
+    "connections",
+];
+
+/// Starts serving `snapshot` on `address`. Binding happens right away, so a taken address is
+/// reported before anything else starts.
+pub fn spawn_server(
+    address: SocketAddr, snapshot: SharedSnapshot,
+) -> error::Result<JoinHandle<()>> {
+    let listener = TcpListener::bind(address)?;
+
+    Ok(thread::spawn(move || accept_clients(listener, &snapshot)))
+}
+
+/// Answers each client that connects to `listener` on a thread of its own, so a slow one doesn't
+/// hold up the others.
+fn accept_clients(listener: TcpListener, snapshot: &SharedSnapshot) {
+    for stream in listener.incoming().flatten() {
+        let snapshot = snapshot.clone();
+        // A client that misbehaves only loses its own request.
+        thread::spawn(move || handle_request(stream, &snapshot));
+    }
+}
+
+fn handle_request(mut stream: TcpStream, snapshot: &SharedSnapshot) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+
+    let mut reader = BufReader::new((&stream).take(MAX_REQUEST_LEN));
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // The headers are read and dropped, as nothing in them changes the response.
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 0 && header.trim_end() != "" {
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let (status, (content_type, body)) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some(path)) => {
+            let path = path.split('?').next().unwrap_or_default();
+            let body = snapshot
+                .lock()
+                .ok()
+                .and_then(|snapshot| snapshot.render(path));
+            match body {
+                Some(body) => ("200 OK", body),
+                None => ("404 Not Found", error_body("no such endpoint")),
+            }
+        }
+        (Some(_), Some(_)) => (
+            "405 Method Not Allowed",
+            error_body("only GET is supported"),
+        ),
+        _ => ("400 Bad Request", error_body("malformed request")),
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
+
+fn error_body(message: &str) -> (&'static str, String) {
+    (
+        JSON_CONTENT_TYPE,
+        Json::Object(vec![("error", message.into())]).to_string(),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::app::data_harvester::memory::MemHarvest;
+
+    #[test]
+    fn test_json_display() {
+        let json = Json::Object(vec![
+            ("name", "a \"quoted\"\nline".into()),
+            (
+                "values",
+                Json::Array(vec![1.5.into(), f64::NAN.into(), Json::Bool(true)]),
+            ),
+            ("missing", None::<u64>.into()),
+        ]);
+        assert_eq!(
+            json.to_string(),
+            r#"{"name":"a \"quoted\"\nline","values":[1.5,null,true],"missing":null}"#
+        );
+    }
+
+    #[test]
+    fn test_serve_snapshot() {
+        let snapshot = SharedSnapshot::default();
+        snapshot.lock().unwrap().update(&Data {
+            memory: Some(MemHarvest {
+                total_kib: 2,
+                used_kib: 1,
+                use_percent: Some(50.0),
+            }),
+            ..Data::default()
+        });
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let server_snapshot = snapshot.clone();
+        thread::spawn(move || accept_clients(listener, &server_snapshot));
+
+        // A client that never finishes its request doesn't hold up anyone else's.
+        let mut stalled = TcpStream::connect(address).unwrap();
+        stalled.write_all(b"GET /memory HTTP/1.1\r\n").unwrap();
+
+        let get = |path: &str| {
+            let mut stream = TcpStream::connect(address).unwrap();
+            write!(stream, "GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+
+        let response = get("/memory");
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.ends_with(
+            r#"{"memory":{"total_bytes":2048,"used_bytes":1024,"used_percent":50},"swap":null}"#
+        ));
+
+        // Endpoints without data yet are null, and unknown ones don't exist.
+        assert!(get("/cpu").ends_with("\r\n\r\nnull"));
+        assert!(get("/nope").starts_with("HTTP/1.1 404"));
+        assert!(get("/").contains(r#""memory":{"#));
+        // Metrics are only there when asked for.
+        assert!(get("/metrics").starts_with("HTTP/1.1 404"));
+        drop(stalled);
+
+        // Reading stops at the limit, rather than waiting for a header that never ends.
+        let mut request = b"GET /memory HTTP/1.1\r\nX-Long: ".to_vec();
+        request.resize(MAX_REQUEST_LEN as usize, b'a');
+        let mut stream = TcpStream::connect(address).unwrap();
+        stream.write_all(&request).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+    }
+
+    #[test]
+    fn test_render_metrics_endpoint() {
+        let mut snapshot = Snapshot::new(true);
+        snapshot.update(&Data {
+            memory: Some(MemHarvest {
+                total_kib: 2,
+                used_kib: 1,
+                use_percent: Some(50.0),
+            }),
+            ..Data::default()
+        });
+
+        let (content_type, body) = snapshot.render("/metrics").unwrap();
+        assert_eq!(content_type, metrics::CONTENT_TYPE);
+        assert!(body.contains("bottom_memory_total_bytes 2048\n"));
+        assert_eq!(snapshot.render("/memory").unwrap().0, JSON_CONTENT_TYPE);
+    }
+}
@@ -0,0 +1,267 @@
+//! Harvested data in the Prometheus text exposition format, served at `/metrics`.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write,
+};
+
+use crate::app::data_harvester::{cpu::CpuDataType, Data};
+
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// The latest metric families of each part of the data. Like the JSON endpoints, a part that
+/// wasn't harvested in an update keeps its previous value.
+#[derive(Default)]
+pub struct Metrics {
+    families: BTreeMap<&'static str, String>,
+}
+
+impl Metrics {
+    pub fn update(&mut self, data: &Data) {
+        if let Some(cpu) = &data.cpu {
+            let mut text = String::new();
+            family(
+                &mut text,
+                "bottom_cpu_usage_percent",
+                "gauge",
+                "Usage of each CPU core, in percent.",
+                cpu.iter().filter_map(|cpu| match cpu.data_type {
+                    CpuDataType::Avg => None,
+                    CpuDataType::Cpu(index) => {
+                        Some((vec![("cpu", index.to_string())], cpu.cpu_usage))
+                    }
+                }),
+            );
+            if let Some(load_avg) = data.load_avg {
+                family(
+                    &mut text,
+                    "bottom_load_average",
+                    "gauge",
+                    "System load average.",
+                    ["1m", "5m", "15m"]
+                        .into_iter()
+                        .zip(load_avg)
+                        .map(|(period, load)| (vec![("period", period.to_string())], load.into())),
+                );
+            }
+            self.families.insert("cpu", text);
+        }
+
+        if data.memory.is_some() || data.swap.is_some() {
+            let mut text = String::new();
+            for (kind, memory) in [("memory", &data.memory), ("swap", &data.swap)] {
+                if let Some(memory) = memory {
+                    family(
+                        &mut text,
+                        &format!("bottom_{kind}_total_bytes"),
+                        "gauge",
+                        &format!("Total {kind}, in bytes."),
+                        [(vec![], (memory.total_kib * 1024) as f64)],
+                    );
+                    family(
+                        &mut text,
+                        &format!("bottom_{kind}_used_bytes"),
+                        "gauge",
+                        &format!("Used {kind}, in bytes."),
+                        [(vec![], (memory.used_kib * 1024) as f64)],
+                    );
+                }
+            }
+            self.families.insert("memory", text);
+        }
+
+        if let Some(io) = &data.io {
+            let io = sorted(
+                io.iter()
+                    .filter_map(|(device, io)| Some((device, io.as_ref()?))),
+            );
+            let mut text = String::new();
+            family(
+                &mut text,
+                "bottom_disk_read_bytes_total",
+                "counter",
+                "Bytes read from each disk.",
+                io.iter().map(|(device, io)| {
+                    (vec![("device", device.to_string())], io.read_bytes as f64)
+                }),
+            );
+            family(
+                &mut text,
+                "bottom_disk_written_bytes_total",
+                "counter",
+                "Bytes written to each disk.",
+                io.iter().map(|(device, io)| {
+                    (vec![("device", device.to_string())], io.write_bytes as f64)
+                }),
+            );
+            self.families.insert("disk", text);
+        }
+
+        if let Some(network) = &data.network {
+            let interfaces = sorted(network.interface_totals.iter());
+            let mut text = String::new();
+            family(
+                &mut text,
+                "bottom_network_receive_bytes_total",
+                "counter",
+                "Bytes received by each network interface.",
+                interfaces.iter().map(|(interface, (rx, _))| {
+                    (vec![("interface", interface.to_string())], (*rx / 8) as f64)
+                }),
+            );
+            family(
+                &mut text,
+                "bottom_network_transmit_bytes_total",
+                "counter",
+                "Bytes transmitted by each network interface.",
+                interfaces.iter().map(|(interface, (_, tx))| {
+                    (vec![("interface", interface.to_string())], (*tx / 8) as f64)
+                }),
+            );
+            family(
+                &mut text,
+                "bottom_tcp_connections",
+                "gauge",
+                "Open TCP connections.",
+                [(vec![], network.total_tcp_connections as f64)],
+            );
+            self.families.insert("network", text);
+        }
+
+        if let Some(processes) = &data.list_of_processes {
+            let mut states: HashMap<&str, usize> = HashMap::new();
+            for process in processes {
+                *states.entry(process.process_state.0.as_str()).or_default() += 1;
+            }
+            let mut text = String::new();
+            family(
+                &mut text,
+                "bottom_processes",
+                "gauge",
+                "Processes in each state.",
+                sorted(states.into_iter())
+                    .into_iter()
+                    .map(|(state, count)| (vec![("state", state.to_string())], count as f64)),
+            );
+            self.families.insert("processes", text);
+        }
+    }
+
+    pub fn render(&self) -> String {
+        self.families.values().map(String::as_str).collect()
+    }
+}
+
+/// Writes one metric family, with its `HELP` and `TYPE` lines and a sample per set of labels.
+fn family(
+    text: &mut String, name: &str, kind: &str, help: &str,
+    samples: impl IntoIterator<Item = (Vec<(&'static str, String)>, f64)>,
+) {
+    let _ = writeln!(text, "# HELP {name} {help}");
+    let _ = writeln!(text, "# TYPE {name} {kind}");
+    for (labels, value) in samples {
+        text.push_str(name);
+        if !labels.is_empty() {
+            let labels: Vec<String> = labels
+                .iter()
+                .map(|(label, value)| format!("{label}=\"{}\"", escape_label(value)))
+                .collect();
+            let _ = write!(text, "{{{}}}", labels.join(","));
+        }
+        let _ = writeln!(text, " {}", format_value(value));
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "+Inf" } else { "-Inf" }.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Hash map entries ordered by key, so samples come out the same way every time.
+fn sorted<K: Ord, V>(entries: impl Iterator<Item = (K, V)>) -> Vec<(K, V)> {
+    let mut entries: Vec<_> = entries.collect();
+    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+    entries
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::app::data_harvester::{
+        cpu::CpuData, disks::IoData, memory::MemHarvest, network::NetworkHarvest,
+    };
+
+    #[test]
+    fn test_escape_label() {
+        assert_eq!(escape_label(r#"a "b"\c"#), r#"a \"b\"\\c"#);
+        assert_eq!(escape_label("line\nbreak"), r"line\nbreak");
+    }
+
+    #[test]
+    fn test_render_metrics() {
+        let mut metrics = Metrics::default();
+        metrics.update(&Data {
+            cpu: Some(vec![
+                CpuData {
+                    data_type: CpuDataType::Avg,
+                    cpu_usage: 30.0,
+                    frequency: None,
+                },
+                CpuData {
+                    data_type: CpuDataType::Cpu(0),
+                    cpu_usage: 12.5,
+                    frequency: None,
+                },
+            ]),
+            memory: Some(MemHarvest {
+                total_kib: 2,
+                used_kib: 1,
+                use_percent: Some(50.0),
+            }),
+            io: Some(HashMap::from([
+                (
+                    "sda".to_string(),
+                    Some(IoData {
+                        read_bytes: 100,
+                        write_bytes: 200,
+                        latency_counters: None,
+                    }),
+                ),
+                ("sdb".to_string(), None),
+            ])),
+            network: Some(NetworkHarvest {
+                total_tcp_connections: 3,
+                interface_totals: HashMap::from([("eth0".to_string(), (80, 160))]),
+                ..NetworkHarvest::default()
+            }),
+            ..Data::default()
+        });
+
+        let text = metrics.render();
+        assert!(text.contains(
+            "# TYPE bottom_cpu_usage_percent gauge\nbottom_cpu_usage_percent{cpu=\"0\"} 12.5\n"
+        ));
+        assert!(!text.contains("cpu=\"avg\""));
+        assert!(text.contains("bottom_memory_total_bytes 2048\n"));
+        assert!(text.contains("bottom_memory_used_bytes 1024\n"));
+        assert!(!text.contains("bottom_swap"));
+        assert!(text.contains("bottom_disk_read_bytes_total{device=\"sda\"} 100\n"));
+        assert!(text.contains("bottom_disk_written_bytes_total{device=\"sda\"} 200\n"));
+        assert!(!text.contains("sdb"));
+        assert!(text.contains("bottom_network_receive_bytes_total{interface=\"eth0\"} 10\n"));
+        assert!(text.contains("bottom_network_transmit_bytes_total{interface=\"eth0\"} 20\n"));
+        assert!(text.contains("bottom_tcp_connections 3\n"));
+    }
+}
@@ -0,0 +1,80 @@
+//! Where the collection thread's updates go.
+//!
+//! Each update is handed to every registered [`Sink`] in turn: the interface, the `--serve`
+//! server's snapshot, and the viewers of an agent are all sinks. Sinks are given to
+//! [`create_collection_thread`](crate::create_collection_thread) up front, or registered later
+//! with [`ThreadControlEvent::AddSink`](crate::ThreadControlEvent::AddSink).
+
+use std::{fmt, sync::mpsc::Sender};
+
+use crate::{app::data_harvester::Data, BottomEvent};
+
+/// Something that takes every update collected.
+pub trait Sink: Send {
+    /// Takes the latest update. Returns `false` once the sink has gone away for good, after which
+    /// it is dropped.
+    fn push(&mut self, data: &Data) -> bool;
+}
+
+impl fmt::Debug for dyn Sink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Sink")
+    }
+}
+
+/// Sends updates to the interface as [`BottomEvent::Update`]s.
+pub struct EventSink(pub Sender<BottomEvent>);
+
+impl Sink for EventSink {
+    fn push(&mut self, data: &Data) -> bool {
+        self.0
+            .send(BottomEvent::Update(Box::new(data.clone())))
+            .is_ok()
+    }
+}
+
+/// The sinks of the collection thread.
+#[derive(Default)]
+pub struct Sinks {
+    sinks: Vec<Box<dyn Sink>>,
+}
+
+impl Sinks {
+    pub fn new(sinks: Vec<Box<dyn Sink>>) -> Self {
+        Sinks { sinks }
+    }
+
+    pub fn register(&mut self, sink: Box<dyn Sink>) {
+        self.sinks.push(sink);
+    }
+
+    /// Hands `data` to every sink, dropping the ones that have gone away.
+    pub fn push(&mut self, data: &Data) {
+        self.sinks.retain_mut(|sink| sink.push(data));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::mpsc;
+
+    use super::*;
+
+    #[test]
+    fn test_closed_sinks_are_dropped() {
+        let (sender, receiver) = mpsc::channel();
+        let (closed_sender, _) = mpsc::channel();
+        let mut sinks = Sinks::new(vec![
+            Box::new(EventSink(sender)),
+            Box::new(EventSink(closed_sender)),
+        ]);
+
+        sinks.push(&Data::default());
+        assert_eq!(sinks.sinks.len(), 1);
+        assert!(matches!(receiver.try_recv(), Ok(BottomEvent::Update(_))));
+
+        drop(receiver);
+        sinks.push(&Data::default());
+        assert!(sinks.sinks.is_empty());
+    }
+}
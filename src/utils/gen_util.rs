@@ -201,6 +201,39 @@ pub fn partial_ordering_desc<T: std::cmp::PartialOrd>(a: T, b: T) -> Ordering {
     partial_ordering(a, b).reverse()
 }
 
+/// Formats an uptime in seconds as days, hours and minutes, e.g. `3d 4h 5m`.
+pub fn format_uptime(uptime: u64) -> String {
+    let days = uptime / (60 * 60 * 24);
+    let hours = uptime / (60 * 60) % 24;
+    let minutes = uptime / 60 % 60;
+
+    if days > 0 {
+        format!("{days}d {hours}h {minutes}m")
+    } else if hours > 0 {
+        format!("{hours}h {minutes}m")
+    } else {
+        format!("{minutes}m")
+    }
+}
+
+/// Returns the local timezone's offset from UTC at a Unix timestamp, or UTC if it isn't known.
+#[cfg(target_family = "unix")]
+pub fn local_offset(timestamp: i64) -> time::UtcOffset {
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    let offset = if unsafe { libc::localtime_r(&(timestamp as libc::time_t), &mut tm) }.is_null() {
+        0
+    } else {
+        tm.tm_gmtoff
+    };
+
+    time::UtcOffset::from_whole_seconds(offset as i32).unwrap_or(time::UtcOffset::UTC)
+}
+
+#[cfg(not(target_family = "unix"))]
+pub fn local_offset(_timestamp: i64) -> time::UtcOffset {
+    time::UtcOffset::UTC
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -0,0 +1,358 @@
+//! A small JSON reader and writer, for the APIs and tools that only speak JSON.
+
+use std::{
+    fmt::{self, Write as _},
+    str::Chars,
+};
+
+/// A parsed JSON value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+    /// Parses `input`, which must hold a single value and nothing else but whitespace.
+    pub fn parse(input: &str) -> Option<JsonValue> {
+        let mut parser = Parser {
+            chars: input.chars(),
+            depth: 0,
+        };
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        parser.chars.as_str().is_empty().then_some(value)
+    }
+
+    /// The value of `key`, if this is an object that has it.
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(entries) => entries
+                .iter()
+                .find(|(entry_key, _)| entry_key == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// Follows `keys` through nested objects.
+    pub fn path(&self, keys: &[&str]) -> Option<&JsonValue> {
+        keys.iter().try_fold(self, |value, key| value.get(key))
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            JsonValue::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            JsonValue::Number(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        self.as_f64()
+            .filter(|value| *value >= 0.0)
+            .map(|value| value as u64)
+    }
+
+    pub fn as_array(&self) -> Option<&[JsonValue]> {
+        match self {
+            JsonValue::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&[(String, JsonValue)]> {
+        match self {
+            JsonValue::Object(entries) => Some(entries),
+            _ => None,
+        }
+    }
+}
+
+/// Values nested deeper than this are treated as malformed, rather than risking the stack.
+const MAX_DEPTH: usize = 128;
+
+struct Parser<'a> {
+    chars: Chars<'a>,
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        self.chars = self.chars.as_str().trim_start().chars();
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.clone().next()
+    }
+
+    fn eat(&mut self, expected: char) -> Option<()> {
+        self.skip_whitespace();
+        (self.chars.next()? == expected).then_some(())
+    }
+
+    fn literal(&mut self, literal: &str, value: JsonValue) -> Option<JsonValue> {
+        let rest = self.chars.as_str().strip_prefix(literal)?;
+        self.chars = rest.chars();
+        Some(value)
+    }
+
+    fn value(&mut self) -> Option<JsonValue> {
+        self.skip_whitespace();
+        match self.peek()? {
+            'n' => self.literal("null", JsonValue::Null),
+            't' => self.literal("true", JsonValue::Bool(true)),
+            'f' => self.literal("false", JsonValue::Bool(false)),
+            '"' => self.string().map(JsonValue::String),
+            '[' => self.nested(Self::array),
+            '{' => self.nested(Self::object),
+            _ => self.number(),
+        }
+    }
+
+    fn nested(&mut self, parse: fn(&mut Self) -> Option<JsonValue>) -> Option<JsonValue> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return None;
+        }
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn array(&mut self) -> Option<JsonValue> {
+        self.eat('[')?;
+        let mut values = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.chars.next();
+            return Some(JsonValue::Array(values));
+        }
+
+        loop {
+            values.push(self.value()?);
+            self.skip_whitespace();
+            match self.chars.next()? {
+                ',' => {}
+                ']' => return Some(JsonValue::Array(values)),
+                _ => return None,
+            }
+        }
+    }
+
+    fn object(&mut self) -> Option<JsonValue> {
+        self.eat('{')?;
+        let mut entries = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.chars.next();
+            return Some(JsonValue::Object(entries));
+        }
+
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.eat(':')?;
+            entries.push((key, self.value()?));
+            self.skip_whitespace();
+            match self.chars.next()? {
+                ',' => {}
+                '}' => return Some(JsonValue::Object(entries)),
+                _ => return None,
+            }
+        }
+    }
+
+    fn string(&mut self) -> Option<String> {
+        if self.chars.next()? != '"' {
+            return None;
+        }
+
+        let mut value = String::new();
+        loop {
+            match self.chars.next()? {
+                '"' => return Some(value),
+                '\\' => match self.chars.next()? {
+                    '"' => value.push('"'),
+                    '\\' => value.push('\\'),
+                    '/' => value.push('/'),
+                    'b' => value.push('\u{8}'),
+                    'f' => value.push('\u{c}'),
+                    'n' => value.push('\n'),
+                    'r' => value.push('\r'),
+                    't' => value.push('\t'),
+                    'u' => {
+                        let high = self.hex_escape()?;
+                        let c = if (0xD800..0xDC00).contains(&high) {
+                            // The other half of a surrogate pair follows as its own escape.
+                            self.chars.next().filter(|c| *c == '\\')?;
+                            self.chars.next().filter(|c| *c == 'u')?;
+                            let low = self.hex_escape()?;
+                            char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00))
+                        } else {
+                            char::from_u32(high)
+                        };
+                        value.push(c.unwrap_or(char::REPLACEMENT_CHARACTER));
+                    }
+                    _ => return None,
+                },
+                c => value.push(c),
+            }
+        }
+    }
+
+    fn hex_escape(&mut self) -> Option<u32> {
+        let rest = self.chars.as_str();
+        let digits = rest.get(..4)?;
+        self.chars = rest[4..].chars();
+        u32::from_str_radix(digits, 16).ok()
+    }
+
+    fn number(&mut self) -> Option<JsonValue> {
+        let rest = self.chars.as_str();
+        let len = rest
+            .find(|c: char| !matches!(c, '0'..='9' | '-' | '+' | '.' | 'e' | 'E'))
+            .unwrap_or(rest.len());
+        self.chars = rest[len..].chars();
+        rest[..len].parse().ok().map(JsonValue::Number)
+    }
+}
+
+/// A JSON value to be written out, with keys fixed ahead of time.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(&'static str, Json)>),
+}
+
+impl From<u64> for Json {
+    fn from(value: u64) -> Self {
+        Json::Number(value as f64)
+    }
+}
+
+impl From<f64> for Json {
+    fn from(value: f64) -> Self {
+        Json::Number(value)
+    }
+}
+
+impl From<&str> for Json {
+    fn from(value: &str) -> Self {
+        Json::String(value.to_string())
+    }
+}
+
+impl<T: Into<Json>> From<Option<T>> for Json {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Json::Null, Into::into)
+    }
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Json::Null => f.write_str("null"),
+            Json::Bool(value) => write!(f, "{value}"),
+            // JSON has no way to write NaN or infinity.
+            Json::Number(value) if !value.is_finite() => f.write_str("null"),
+            Json::Number(value) => write!(f, "{value}"),
+            Json::String(value) => write_string(f, value),
+            Json::Array(values) => {
+                f.write_char('[')?;
+                for (index, value) in values.iter().enumerate() {
+                    if index > 0 {
+                        f.write_char(',')?;
+                    }
+                    write!(f, "{value}")?;
+                }
+                f.write_char(']')
+            }
+            Json::Object(entries) => {
+                f.write_char('{')?;
+                for (index, (key, value)) in entries.iter().enumerate() {
+                    if index > 0 {
+                        f.write_char(',')?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{value}")?;
+                }
+                f.write_char('}')
+            }
+        }
+    }
+}
+
+/// Writes `value` as a quoted JSON string, escaping whatever needs to be.
+pub fn write_string<W: fmt::Write>(w: &mut W, value: &str) -> fmt::Result {
+    w.write_char('"')?;
+    for c in value.chars() {
+        match c {
+            '"' => w.write_str("\\\"")?,
+            '\\' => w.write_str("\\\\")?,
+            '\n' => w.write_str("\\n")?,
+            '\r' => w.write_str("\\r")?,
+            '\t' => w.write_str("\\t")?,
+            c if c.is_control() => write!(w, "\\u{:04x}", c as u32)?,
+            c => w.write_char(c)?,
+        }
+    }
+    w.write_char('"')
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_json() {
+        let value = JsonValue::parse(
+            r#" {"name": "web\né😀", "ports": [80, 443.5, -1e3], "up": true,
+                "stats": {"cpu": {"total": 12}}, "none": null, "empty": {}} "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            value.get("name").and_then(JsonValue::as_str),
+            Some("web\né😀")
+        );
+        assert_eq!(
+            value
+                .get("ports")
+                .and_then(JsonValue::as_array)
+                .map(|ports| ports.iter().filter_map(JsonValue::as_f64).collect()),
+            Some(vec![80.0, 443.5, -1000.0])
+        );
+        assert_eq!(value.get("up"), Some(&JsonValue::Bool(true)));
+        assert_eq!(
+            value
+                .path(&["stats", "cpu", "total"])
+                .and_then(JsonValue::as_u64),
+            Some(12)
+        );
+        assert_eq!(value.get("none"), Some(&JsonValue::Null));
+        assert_eq!(value.get("missing"), None);
+    }
+
+    #[test]
+    fn test_parse_malformed_json() {
+        for input in ["", "{", "[1,]", r#"{"a" 1}"#, "nul", "1 2", r#""\x""#] {
+            assert_eq!(JsonValue::parse(input), None, "{input}");
+        }
+        assert_eq!(JsonValue::parse(&"[".repeat(1000)), None);
+    }
+}
@@ -16,14 +16,44 @@ pub use net_graph::*;
 pub mod mem_graph;
 pub use mem_graph::*;
 
+pub mod pressure_graph;
+pub use pressure_graph::*;
+
+pub mod wifi_graph;
+pub use wifi_graph::*;
+
 pub mod battery_widget;
 pub use battery_widget::*;
 
 pub mod terminal_widget;
 pub use terminal_widget::*;
 
+pub mod log_widget;
+pub use log_widget::*;
+
 pub mod uptime_widget;
 pub use uptime_widget::*;
 
 pub mod connections_table;
 pub use connections_table::*;
+
+pub mod container_table;
+pub use container_table::*;
+
+pub mod raid_table;
+pub use raid_table::*;
+
+pub mod zfs_pool_table;
+pub use zfs_pool_table::*;
+
+pub mod cgroup_tree;
+pub use cgroup_tree::*;
+
+pub mod scheduled_job_table;
+pub use scheduled_job_table::*;
+
+pub mod dashboard_table;
+pub use dashboard_table::*;
+
+pub mod discovery_table;
+pub use discovery_table::*;
@@ -1,5 +1,82 @@
+use std::{collections::VecDeque, time::Instant};
+
 #[derive(Default)]
 pub struct BatteryWidgetState {
     pub currently_selected_battery_index: usize,
     pub tab_click_locs: Option<Vec<((u16, u16), (u16, u16))>>,
+    /// History of the selected battery's power consumption, as (time offset in milliseconds, watts).
+    /// The newest entry is at a time offset of 0, with older entries going negative.
+    pub power_history: VecDeque<(f64, f64)>,
+    /// The battery index and time of the last power history entry.
+    last_power_entry: Option<(usize, Instant)>,
+}
+
+impl BatteryWidgetState {
+    /// Records a new power consumption entry for the battery at `battery_index`. The history is
+    /// restarted if a different battery was recorded last.
+    pub fn push_power_history(&mut self, instant: Instant, battery_index: usize, watts: f64) {
+        match self.last_power_entry {
+            Some((last_index, last_instant)) if last_index == battery_index => {
+                let elapsed = instant.duration_since(last_instant).as_millis() as f64;
+                for (time, _) in self.power_history.iter_mut() {
+                    *time -= elapsed;
+                }
+            }
+            _ => self.power_history.clear(),
+        }
+
+        self.power_history.push_back((0.0, watts));
+        self.last_power_entry = Some((battery_index, instant));
+    }
+
+    /// Removes any power history entries older than `max_time_millis`.
+    pub fn clean_power_history(&mut self, max_time_millis: u64) {
+        let max_time_millis = max_time_millis as f64;
+
+        while let Some((time, _)) = self.power_history.front() {
+            if -time > max_time_millis {
+                self.power_history.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Returns the highest wattage in the power history, or 0 if there is none.
+    pub fn max_power(&self) -> f64 {
+        self.power_history
+            .iter()
+            .fold(0.0, |acc, (_, watts)| f64::max(acc, *watts))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_power_history_offsets() {
+        let mut state = BatteryWidgetState::default();
+        let start = Instant::now();
+
+        state.push_power_history(start, 0, 5.0);
+        state.push_power_history(start + Duration::from_millis(1000), 0, 7.5);
+        assert_eq!(
+            state.power_history.iter().copied().collect::<Vec<_>>(),
+            vec![(-1000.0, 5.0), (0.0, 7.5)]
+        );
+        assert_eq!(state.max_power(), 7.5);
+
+        state.clean_power_history(500);
+        assert_eq!(state.power_history.len(), 1);
+
+        // Switching batteries restarts the history.
+        state.push_power_history(start + Duration::from_millis(2000), 1, 3.0);
+        assert_eq!(
+            state.power_history.iter().copied().collect::<Vec<_>>(),
+            vec![(0.0, 3.0)]
+        );
+    }
 }
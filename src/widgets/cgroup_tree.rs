@@ -0,0 +1,306 @@
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+};
+
+use tui::text::Text;
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    app::AppConfigFields,
+    canvas::canvas_styling::CanvasColours,
+    components::{
+        data_table::{
+            ColumnHeader, DataTableColumn, DataTableProps, DataTableStyling, DataToCell,
+            SortColumn, SortDataTable, SortDataTableProps, SortOrder, SortsRow,
+        },
+        tree_prefix::TreePrefixes,
+    },
+    data_conversion::binary_byte_string,
+    utils::gen_util::{sort_partial_fn, truncate_to_text},
+};
+
+#[derive(Clone, Debug)]
+pub struct CgroupWidgetData {
+    pub path: String,
+    pub name: String,
+    pub parent_path: Option<String>,
+    pub cpu_percent: Option<f64>,
+    pub mem_bytes: Option<u64>,
+    pub mem_limit_bytes: Option<u64>,
+    pub tasks: Option<u64>,
+    /// The branch drawn before the name, which is set when the tree is built.
+    prefix: String,
+}
+
+impl CgroupWidgetData {
+    pub fn new(
+        path: String, name: String, parent_path: Option<String>, cpu_percent: Option<f64>,
+        mem_bytes: Option<u64>, mem_limit_bytes: Option<u64>, tasks: Option<u64>,
+    ) -> Self {
+        CgroupWidgetData {
+            path,
+            name,
+            parent_path,
+            cpu_percent,
+            mem_bytes,
+            mem_limit_bytes,
+            tasks,
+            prefix: String::default(),
+        }
+    }
+
+    fn prefixed_name(&self) -> String {
+        format!("{}{}", self.prefix, self.name)
+    }
+}
+
+pub enum CgroupWidgetColumn {
+    Name,
+    Cpu,
+    Mem,
+    MemLimit,
+    Tasks,
+}
+
+impl ColumnHeader for CgroupWidgetColumn {
+    fn text(&self) -> Cow<'static, str> {
+        match self {
+            CgroupWidgetColumn::Name => "Cgroup".into(),
+            CgroupWidgetColumn::Cpu => "CPU%".into(),
+            CgroupWidgetColumn::Mem => "Mem".into(),
+            CgroupWidgetColumn::MemLimit => "Limit".into(),
+            CgroupWidgetColumn::Tasks => "Tasks".into(),
+        }
+    }
+}
+
+impl DataToCell<CgroupWidgetColumn> for CgroupWidgetData {
+    fn to_cell<'a>(
+        &'a self, column: &CgroupWidgetColumn, calculated_width: u16,
+    ) -> Option<Text<'a>> {
+        if calculated_width == 0 {
+            return None;
+        }
+
+        let text = match column {
+            CgroupWidgetColumn::Name => Some(self.prefixed_name()),
+            CgroupWidgetColumn::Cpu => self.cpu_percent.map(|cpu| format!("{cpu:.1}%")),
+            CgroupWidgetColumn::Mem => self.mem_bytes.map(binary_byte_string),
+            CgroupWidgetColumn::MemLimit => self.mem_limit_bytes.map(binary_byte_string),
+            CgroupWidgetColumn::Tasks => self.tasks.map(|tasks| tasks.to_string()),
+        };
+
+        Some(truncate_to_text(
+            text.as_deref().unwrap_or(""),
+            calculated_width,
+        ))
+    }
+
+    fn column_widths<C: DataTableColumn<CgroupWidgetColumn>>(
+        data: &[CgroupWidgetData], _columns: &[C],
+    ) -> Vec<u16>
+    where
+        Self: Sized,
+    {
+        let mut widths = vec![0, 6, 8, 8, 5];
+        for row in data {
+            widths[0] = widths[0].max(row.prefixed_name().width() as u16);
+        }
+
+        widths
+    }
+}
+
+impl SortsRow for CgroupWidgetColumn {
+    type DataType = CgroupWidgetData;
+
+    fn sort_data(&self, data: &mut [Self::DataType], descending: bool) {
+        match self {
+            CgroupWidgetColumn::Name => {
+                data.sort_by(|a, b| sort_partial_fn(descending)(&a.name, &b.name));
+            }
+            CgroupWidgetColumn::Cpu => {
+                data.sort_by(|a, b| sort_partial_fn(descending)(a.cpu_percent, b.cpu_percent));
+            }
+            CgroupWidgetColumn::Mem => {
+                data.sort_by(|a, b| sort_partial_fn(descending)(a.mem_bytes, b.mem_bytes));
+            }
+            CgroupWidgetColumn::MemLimit => {
+                data.sort_by(|a, b| {
+                    sort_partial_fn(descending)(a.mem_limit_bytes, b.mem_limit_bytes)
+                });
+            }
+            CgroupWidgetColumn::Tasks => {
+                data.sort_by(|a, b| sort_partial_fn(descending)(a.tasks, b.tasks));
+            }
+        }
+    }
+}
+
+pub struct CgroupWidgetState {
+    pub table: SortDataTable<CgroupWidgetData, CgroupWidgetColumn>,
+    /// The paths of the cgroups whose children are hidden.
+    pub collapsed_paths: HashSet<String>,
+}
+
+impl CgroupWidgetState {
+    pub fn new(config: &AppConfigFields, colours: &CanvasColours) -> Self {
+        let columns = [
+            SortColumn::soft(CgroupWidgetColumn::Name, Some(0.6)),
+            SortColumn::hard(CgroupWidgetColumn::Cpu, 8).default_descending(),
+            SortColumn::hard(CgroupWidgetColumn::Mem, 8).default_descending(),
+            SortColumn::hard(CgroupWidgetColumn::MemLimit, 8).default_descending(),
+            SortColumn::hard(CgroupWidgetColumn::Tasks, 6).default_descending(),
+        ];
+
+        let props = SortDataTableProps {
+            inner: DataTableProps {
+                title: Some(" Cgroups ".into()),
+                table_gap: config.table_gap,
+                left_to_right: true,
+                is_basic: config.use_basic_mode,
+                show_table_scroll_position: config.show_table_scroll_position,
+                show_scrollbar: config.show_scrollbar,
+                show_current_entry_when_unfocused: false,
+            },
+            sort_index: 1,
+            order: SortOrder::Descending,
+        };
+
+        let styling = DataTableStyling::from_colours(colours);
+
+        Self {
+            table: SortDataTable::new_sortable(columns, props, styling),
+            collapsed_paths: HashSet::default(),
+        }
+    }
+
+    /// Collapses the selected cgroup if it is expanded, and expands it otherwise.
+    pub fn toggle_current_branch(&mut self) {
+        if let Some(cgroup) = self.table.current_item() {
+            let path = cgroup.path.clone();
+            if !self.collapsed_paths.remove(&path) {
+                self.collapsed_paths.insert(path);
+            }
+        }
+    }
+
+    /// Lays the cgroups out as a tree, with the siblings of each branch sorted by the selected
+    /// column.
+    pub fn ingest_data(&mut self, cgroups: &[CgroupWidgetData]) {
+        let paths: HashSet<&str> = cgroups.iter().map(|cgroup| cgroup.path.as_str()).collect();
+        let mut children: HashMap<String, Vec<CgroupWidgetData>> = HashMap::new();
+        let mut roots = Vec::new();
+        for cgroup in cgroups {
+            match cgroup
+                .parent_path
+                .as_deref()
+                .filter(|parent| paths.contains(parent))
+            {
+                Some(parent) => children
+                    .entry(parent.to_string())
+                    .or_default()
+                    .push(cgroup.clone()),
+                None => roots.push(cgroup.clone()),
+            }
+        }
+
+        let mut data = Vec::with_capacity(cgroups.len());
+        self.add_branch(
+            roots,
+            &mut children,
+            &mut TreePrefixes::default(),
+            &mut data,
+        );
+
+        self.table.set_data(data);
+    }
+
+    fn add_branch(
+        &self, mut siblings: Vec<CgroupWidgetData>,
+        children: &mut HashMap<String, Vec<CgroupWidgetData>>, prefixes: &mut TreePrefixes,
+        data: &mut Vec<CgroupWidgetData>,
+    ) {
+        if let Some(column) = self.table.columns.get(self.table.sort_index()) {
+            column.sort_by(&mut siblings, self.table.order());
+        }
+
+        let count = siblings.len();
+        for (index, mut cgroup) in siblings.into_iter().enumerate() {
+            let is_last = index + 1 == count;
+            let branch_children = children.remove(&cgroup.path);
+            let is_collapsed =
+                branch_children.is_some() && self.collapsed_paths.contains(&cgroup.path);
+
+            cgroup.prefix = prefixes.entry(is_last, is_collapsed);
+            data.push(cgroup);
+
+            if let Some(branch_children) = branch_children.filter(|_| !is_collapsed) {
+                prefixes.push(is_last);
+                self.add_branch(branch_children, children, prefixes, data);
+                prefixes.pop();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn cgroup(path: &str, parent_path: Option<&str>, cpu_percent: f64) -> CgroupWidgetData {
+        CgroupWidgetData::new(
+            path.to_string(),
+            path.rsplit('/')
+                .next()
+                .filter(|name| !name.is_empty())
+                .unwrap_or(path)
+                .to_string(),
+            parent_path.map(str::to_string),
+            Some(cpu_percent),
+            None,
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_cgroup_tree() {
+        let cgroups = [
+            cgroup("/", None, 50.0),
+            cgroup("/system.slice", Some("/"), 10.0),
+            cgroup("/system.slice/sshd.service", Some("/system.slice"), 1.0),
+            cgroup("/system.slice/cron.service", Some("/system.slice"), 2.0),
+            cgroup("/user.slice", Some("/"), 30.0),
+        ];
+
+        let mut state =
+            CgroupWidgetState::new(&AppConfigFields::default(), &CanvasColours::default());
+        state.ingest_data(&cgroups);
+        let names = |state: &CgroupWidgetState| {
+            state
+                .table
+                .data()
+                .iter()
+                .map(CgroupWidgetData::prefixed_name)
+                .collect::<Vec<_>>()
+        };
+
+        // Siblings are sorted by CPU usage, highest first.
+        assert_eq!(
+            names(&state),
+            [
+                "/",
+                "├─ user.slice",
+                "└─ system.slice",
+                "   ├─ cron.service",
+                "   └─ sshd.service",
+            ]
+        );
+
+        state.collapsed_paths.insert("/system.slice".to_string());
+        state.ingest_data(&cgroups);
+        assert_eq!(names(&state), ["/", "├─ user.slice", "└─ + system.slice"]);
+    }
+}
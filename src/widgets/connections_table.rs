@@ -1,39 +1,112 @@
-use std::{borrow::Cow, cmp::max};
+use std::{borrow::Cow, cmp::max, net::SocketAddr};
 
 use tui::text::Text;
 
 use crate::{
-    app::AppConfigFields,
-    canvas::canvas_styling::CanvasColours,
+    app::{data_harvester::network::connections::Protocol, AppConfigFields},
+    canvas::{canvas_styling::CanvasColours, Painter},
     components::data_table::{
         ColumnHeader, DataTableColumn, DataTableProps, DataTableStyling, DataToCell, SortColumn,
         SortDataTable, SortDataTableProps, SortOrder, SortsRow,
     },
+    data_conversion::dec_bytes_per_second_string,
     utils::gen_util::{sort_partial_fn, truncate_to_text},
+    Pid,
 };
 
 #[derive(Clone, Debug)]
 pub struct ConnectionsWidgetData {
     pub name: String,
+    pub pid: Option<Pid>,
+    pub protocol: Protocol,
+    /// The socket's actual addresses, which the shown ones may have been shortened from.
+    pub local: SocketAddr,
+    pub remote: SocketAddr,
     pub local_address: String,
     pub remote_address: String,
     pub status: String,
+    pub rtt_ms: Option<f32>,
+    pub retransmits: Option<u32>,
+    /// Where the remote address is, from the GeoIP databases.
+    #[cfg(feature = "geoip")]
+    pub location: Option<String>,
+    /// Bytes per second.
+    pub rx_per_sec: Option<u64>,
+    pub tx_per_sec: Option<u64>,
 }
 
+impl ConnectionsWidgetData {
+    /// Whether either end of this connection uses `port`.
+    fn uses_port(&self, port: u16) -> bool {
+        let suffix = format!(":{}", port);
+        self.local_address.ends_with(&suffix) || self.remote_address.ends_with(&suffix)
+    }
+
+    /// Whether this socket is waiting for connections, which for UDP means it isn't connected to
+    /// anything.
+    fn is_listening(&self) -> bool {
+        match self.protocol {
+            Protocol::Tcp => self.status == "LISTEN",
+            Protocol::Udp => self.status.is_empty(),
+        }
+    }
+
+    /// The name of the process that owns this socket, if it is known.
+    pub fn process_name(&self) -> Option<&str> {
+        self.name.split_once('/').map(|(_, name)| name)
+    }
+
+    fn rtt_string(&self) -> String {
+        match self.rtt_ms {
+            Some(rtt_ms) => format!("{:.1}ms", rtt_ms),
+            None => "N/A".to_string(),
+        }
+    }
+
+    fn rate_string(rate: Option<u64>) -> String {
+        match rate {
+            Some(rate) => dec_bytes_per_second_string(rate),
+            None => "N/A".to_string(),
+        }
+    }
+
+    fn retransmits_string(&self) -> String {
+        match self.retransmits {
+            Some(retransmits) => retransmits.to_string(),
+            None => "N/A".to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ConnectionsWidgetColumn {
     Name,
+    Protocol,
     LocalAddress,
     RemoteAddress,
     Status,
+    RxRate,
+    TxRate,
+    Rtt,
+    Retransmit,
+    #[cfg(feature = "geoip")]
+    Location,
 }
 
 impl ColumnHeader for ConnectionsWidgetColumn {
     fn text(&self) -> Cow<'static, str> {
         match self {
             ConnectionsWidgetColumn::Name => "PID/Name".into(),
+            ConnectionsWidgetColumn::Protocol => "Proto".into(),
             ConnectionsWidgetColumn::LocalAddress => "Local Address".into(),
             ConnectionsWidgetColumn::RemoteAddress => "Remote Address".into(),
             ConnectionsWidgetColumn::Status => "Status".into(),
+            ConnectionsWidgetColumn::RxRate => "RX/s".into(),
+            ConnectionsWidgetColumn::TxRate => "TX/s".into(),
+            ConnectionsWidgetColumn::Rtt => "RTT".into(),
+            ConnectionsWidgetColumn::Retransmit => "Retrans".into(),
+            #[cfg(feature = "geoip")]
+            ConnectionsWidgetColumn::Location => "Location".into(),
         }
     }
 }
@@ -47,31 +120,67 @@ impl DataToCell<ConnectionsWidgetColumn> for ConnectionsWidgetData {
         }
 
         Some(truncate_to_text(
-            match column {
-                ConnectionsWidgetColumn::Name => &self.name,
-                ConnectionsWidgetColumn::LocalAddress => &self.local_address,
-                ConnectionsWidgetColumn::RemoteAddress => &self.remote_address,
-                ConnectionsWidgetColumn::Status => &self.status,
+            &match column {
+                ConnectionsWidgetColumn::Name => Cow::Borrowed(&self.name),
+                ConnectionsWidgetColumn::Protocol => Cow::Owned(self.protocol.to_string()),
+                ConnectionsWidgetColumn::LocalAddress => Cow::Borrowed(&self.local_address),
+                ConnectionsWidgetColumn::RemoteAddress => Cow::Borrowed(&self.remote_address),
+                ConnectionsWidgetColumn::Status => Cow::Borrowed(&self.status),
+                ConnectionsWidgetColumn::RxRate => Cow::Owned(Self::rate_string(self.rx_per_sec)),
+                ConnectionsWidgetColumn::TxRate => Cow::Owned(Self::rate_string(self.tx_per_sec)),
+                ConnectionsWidgetColumn::Rtt => Cow::Owned(self.rtt_string()),
+                ConnectionsWidgetColumn::Retransmit => Cow::Owned(self.retransmits_string()),
+                #[cfg(feature = "geoip")]
+                ConnectionsWidgetColumn::Location => {
+                    Cow::Owned(self.location.clone().unwrap_or_else(|| "-".to_string()))
+                }
             },
             calculated_width,
         ))
     }
 
+    fn style_cell<'a>(
+        &self, mut text: Text<'a>, column: &ConnectionsWidgetColumn, painter: &Painter,
+    ) -> Text<'a> {
+        if let (ConnectionsWidgetColumn::Rtt, Some(rtt_ms)) = (column, self.rtt_ms) {
+            text.patch_style(if rtt_ms < 20.0 {
+                painter.colours.low_latency_style
+            } else if rtt_ms < 100.0 {
+                painter.colours.medium_latency_style
+            } else {
+                painter.colours.high_latency_style
+            });
+        }
+
+        text
+    }
+
     fn column_widths<C: DataTableColumn<ConnectionsWidgetColumn>>(
         data: &[ConnectionsWidgetData], _columns: &[C],
     ) -> Vec<u16>
     where
         Self: Sized,
     {
-        let mut widths = vec![0; 4];
+        let mut widths = vec![0; 5];
 
         data.iter().for_each(|row| {
             widths[0] = max(widths[0], row.name.len() as u16);
-            widths[1] = max(widths[1], row.local_address.len() as u16);
-            widths[2] = max(widths[2], row.remote_address.len() as u16);
-            widths[3] = max(widths[3], row.status.len() as u16);
+            widths[2] = max(widths[2], row.local_address.chars().count() as u16);
+            widths[3] = max(widths[3], row.remote_address.chars().count() as u16);
+            widths[4] = max(widths[4], row.status.len() as u16);
         });
 
+        #[cfg(feature = "geoip")]
+        {
+            widths.resize(10, 0);
+            widths[9] = data
+                .iter()
+                .filter_map(|row| row.location.as_ref())
+                .map(|location| location.chars().count() as u16)
+                .max()
+                .unwrap_or(0);
+        }
+
         widths
     }
 }
@@ -99,6 +208,9 @@ impl SortsRow for ConnectionsWidgetColumn {
                     )
                 });
             }
+            ConnectionsWidgetColumn::Protocol => {
+                data.sort_by(move |a, b| sort_partial_fn(descending)(&a.protocol, &b.protocol));
+            }
             ConnectionsWidgetColumn::LocalAddress => {
                 data.sort_by(move |a, b| {
                     sort_partial_fn(descending)(&a.local_address, &b.local_address)
@@ -112,21 +224,54 @@ impl SortsRow for ConnectionsWidgetColumn {
             ConnectionsWidgetColumn::Status => {
                 data.sort_by(move |a, b| sort_partial_fn(descending)(&a.status, &b.status));
             }
+            ConnectionsWidgetColumn::RxRate => {
+                data.sort_by(move |a, b| sort_partial_fn(descending)(&a.rx_per_sec, &b.rx_per_sec));
+            }
+            ConnectionsWidgetColumn::TxRate => {
+                data.sort_by(move |a, b| sort_partial_fn(descending)(&a.tx_per_sec, &b.tx_per_sec));
+            }
+            ConnectionsWidgetColumn::Rtt => {
+                data.sort_by(move |a, b| sort_partial_fn(descending)(&a.rtt_ms, &b.rtt_ms));
+            }
+            ConnectionsWidgetColumn::Retransmit => {
+                data.sort_by(move |a, b| {
+                    sort_partial_fn(descending)(&a.retransmits, &b.retransmits)
+                });
+            }
+            #[cfg(feature = "geoip")]
+            ConnectionsWidgetColumn::Location => {
+                data.sort_by(move |a, b| sort_partial_fn(descending)(&a.location, &b.location));
+            }
         }
     }
 }
 
 pub struct ConnectionsWidgetState {
     pub table: SortDataTable<ConnectionsWidgetData, ConnectionsWidgetColumn>,
+    /// Only show connections on this port, if set.
+    pub port_filter: Option<u16>,
+    /// What has been typed into the port filter input, if it is open.
+    pub port_filter_input: Option<String>,
+    /// Only show sockets of this protocol, if set.
+    pub protocol_filter: Option<Protocol>,
+    /// Whether to show listening sockets, one row per port and process, rather than connections.
+    pub is_listening_view: bool,
 }
 
 impl ConnectionsWidgetState {
     pub fn new(config: &AppConfigFields, colours: &CanvasColours) -> Self {
         let columns = [
             SortColumn::soft(ConnectionsWidgetColumn::Name, None),
+            SortColumn::hard(ConnectionsWidgetColumn::Protocol, 7),
             SortColumn::soft(ConnectionsWidgetColumn::LocalAddress, None),
             SortColumn::soft(ConnectionsWidgetColumn::RemoteAddress, None),
             SortColumn::soft(ConnectionsWidgetColumn::Status, None),
+            SortColumn::hard(ConnectionsWidgetColumn::RxRate, 10).default_descending(),
+            SortColumn::hard(ConnectionsWidgetColumn::TxRate, 10).default_descending(),
+            SortColumn::hard(ConnectionsWidgetColumn::Rtt, 9).default_descending(),
+            SortColumn::hard(ConnectionsWidgetColumn::Retransmit, 8).default_descending(),
+            #[cfg(feature = "geoip")]
+            SortColumn::soft(ConnectionsWidgetColumn::Location, None),
         ];
 
         let props = SortDataTableProps {
@@ -136,6 +281,7 @@ impl ConnectionsWidgetState {
                 left_to_right: false,
                 is_basic: config.use_basic_mode,
                 show_table_scroll_position: config.show_table_scroll_position,
+                show_scrollbar: config.show_scrollbar,
                 show_current_entry_when_unfocused: false,
             },
             sort_index: 0,
@@ -146,14 +292,289 @@ impl ConnectionsWidgetState {
 
         Self {
             table: SortDataTable::new_sortable(columns, props, styling),
+            port_filter: None,
+            port_filter_input: None,
+            protocol_filter: None,
+            is_listening_view: false,
+        }
+    }
+
+    pub fn is_port_filter_input_open(&self) -> bool {
+        self.port_filter_input.is_some()
+    }
+
+    /// Opens the port filter input, starting from the current filter.
+    pub fn open_port_filter_input(&mut self) {
+        self.port_filter_input = Some(
+            self.port_filter
+                .map(|port| port.to_string())
+                .unwrap_or_default(),
+        );
+    }
+
+    /// Closes the port filter input. The filter follows what was typed, so closing an empty input
+    /// clears it.
+    pub fn close_port_filter_input(&mut self) {
+        self.port_filter_input = None;
+    }
+
+    /// Adds a digit to the port filter input, ignoring it if it would not make a valid port.
+    pub fn push_port_filter_digit(&mut self, digit: char) {
+        if let Some(input) = &mut self.port_filter_input {
+            if digit.is_ascii_digit() && format!("{}{}", input, digit).parse::<u16>().is_ok() {
+                input.push(digit);
+                self.update_port_filter();
+            }
+        }
+    }
+
+    pub fn pop_port_filter_digit(&mut self) {
+        if let Some(input) = &mut self.port_filter_input {
+            input.pop();
+            self.update_port_filter();
         }
     }
 
+    fn update_port_filter(&mut self) {
+        self.port_filter = self
+            .port_filter_input
+            .as_ref()
+            .and_then(|input| input.parse().ok());
+        self.update_title();
+    }
+
+    /// Switches between showing TCP sockets, UDP sockets, and both.
+    pub fn cycle_protocol_filter(&mut self) {
+        self.protocol_filter = match self.protocol_filter {
+            None => Some(Protocol::Tcp),
+            Some(Protocol::Tcp) => Some(Protocol::Udp),
+            Some(Protocol::Udp) => None,
+        };
+        self.update_title();
+    }
+
+    /// Switches between showing connections and listening sockets. Columns that only matter for
+    /// connections are hidden while showing listening sockets.
+    pub fn toggle_listening_view(&mut self) {
+        self.is_listening_view = !self.is_listening_view;
+
+        for column in self.table.columns.iter_mut() {
+            column.is_hidden = self.is_listening_view
+                && !matches!(
+                    column.inner(),
+                    ConnectionsWidgetColumn::Name
+                        | ConnectionsWidgetColumn::Protocol
+                        | ConnectionsWidgetColumn::LocalAddress
+                );
+        }
+        if self
+            .table
+            .columns
+            .get(self.table.sort_index())
+            .is_some_and(|column| column.is_hidden)
+        {
+            self.table.set_sort_index(0);
+        }
+
+        self.update_title();
+    }
+
+    /// Lists the active filters in the title.
+    fn update_title(&mut self) {
+        let mut title = String::from(if self.is_listening_view {
+            " Listening "
+        } else {
+            " Connections "
+        });
+        if let Some(protocol) = self.protocol_filter {
+            title.push_str(&format!("[{}] ", protocol));
+        }
+        if let Some(port) = self.port_filter {
+            title.push_str(&format!("[port:{}] ", port));
+        }
+
+        self.table.props.title = Some(title.into());
+    }
+
     pub fn ingest_data(&mut self, data: &[ConnectionsWidgetData]) {
-        let mut data = data.to_vec();
+        let mut data = data
+            .iter()
+            .filter(|row| self.port_filter.is_none_or(|port| row.uses_port(port)))
+            .filter(|row| {
+                self.protocol_filter
+                    .is_none_or(|protocol| row.protocol == protocol)
+            })
+            .filter(|row| row.is_listening() == self.is_listening_view)
+            .cloned()
+            .collect::<Vec<_>>();
+        if self.is_listening_view {
+            data = group_by_port(data);
+        }
         if let Some(column) = self.table.columns.get(self.table.sort_index()) {
             column.sort_by(&mut data, self.table.order());
         }
         self.table.set_data(data);
     }
 }
+
+/// Merges listening sockets that share a protocol, port and process, like one listening on both
+/// `0.0.0.0:22` and `[::]:22`, into a single row listing every address.
+fn group_by_port(data: Vec<ConnectionsWidgetData>) -> Vec<ConnectionsWidgetData> {
+    let mut grouped: Vec<ConnectionsWidgetData> = Vec::new();
+    for row in data {
+        match grouped.iter_mut().find(|group| {
+            group.protocol == row.protocol
+                && group.local.port() == row.local.port()
+                && group.pid == row.pid
+        }) {
+            Some(group) => {
+                if !group
+                    .local_address
+                    .split(", ")
+                    .any(|address| address == row.local_address)
+                {
+                    group.local_address.push_str(", ");
+                    group.local_address.push_str(&row.local_address);
+                }
+            }
+            None => grouped.push(row),
+        }
+    }
+
+    grouped
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::components::data_table::DataTableColumn;
+
+    fn connection(local_address: &str, remote_address: &str) -> ConnectionsWidgetData {
+        ConnectionsWidgetData {
+            name: "1/test".to_string(),
+            pid: Some(1),
+            protocol: Protocol::Tcp,
+            local: local_address.parse().unwrap(),
+            remote: remote_address.parse().unwrap(),
+            local_address: local_address.to_string(),
+            remote_address: remote_address.to_string(),
+            status: "ESTABLISHED".to_string(),
+            rtt_ms: None,
+            retransmits: None,
+            #[cfg(feature = "geoip")]
+            location: None,
+            rx_per_sec: None,
+            tx_per_sec: None,
+        }
+    }
+
+    #[test]
+    fn test_uses_port() {
+        assert!(connection("127.0.0.1:443", "10.0.0.2:51000").uses_port(443));
+        assert!(connection("127.0.0.1:51000", "[::1]:443").uses_port(443));
+        assert!(!connection("127.0.0.1:4430", "10.0.0.2:51000").uses_port(443));
+        assert!(!connection("127.0.0.1:8443", "10.0.0.2:51000").uses_port(443));
+    }
+
+    #[test]
+    fn test_protocol_filter() {
+        let mut state =
+            ConnectionsWidgetState::new(&AppConfigFields::default(), &CanvasColours::default());
+        let udp = ConnectionsWidgetData {
+            protocol: Protocol::Udp,
+            ..connection("0.0.0.0:53", "0.0.0.0:0")
+        };
+        let data = [connection("127.0.0.1:443", "10.0.0.2:51000"), udp];
+
+        state.cycle_protocol_filter();
+        state.ingest_data(&data);
+        assert_eq!(state.table.data().len(), 1);
+        assert_eq!(state.table.data()[0].protocol, Protocol::Tcp);
+
+        state.cycle_protocol_filter();
+        state.ingest_data(&data);
+        assert_eq!(state.table.data()[0].protocol, Protocol::Udp);
+
+        state.cycle_protocol_filter();
+        state.ingest_data(&data);
+        assert_eq!(state.table.data().len(), 2);
+    }
+
+    #[test]
+    fn test_sort_by_transfer_rate() {
+        let mut state =
+            ConnectionsWidgetState::new(&AppConfigFields::default(), &CanvasColours::default());
+        let data = [
+            connection("127.0.0.1:443", "10.0.0.2:51000"),
+            ConnectionsWidgetData {
+                rx_per_sec: Some(100),
+                ..connection("127.0.0.1:22", "10.0.0.3:51000")
+            },
+            ConnectionsWidgetData {
+                rx_per_sec: Some(50_000),
+                ..connection("127.0.0.1:80", "10.0.0.4:51000")
+            },
+        ];
+
+        let rx_index = state
+            .table
+            .columns
+            .iter()
+            .position(|column| matches!(column.inner(), ConnectionsWidgetColumn::RxRate))
+            .unwrap();
+        state.table.set_sort_index(rx_index);
+        state.ingest_data(&data);
+
+        let rates = state
+            .table
+            .data()
+            .iter()
+            .map(|row| row.rx_per_sec)
+            .collect::<Vec<_>>();
+        assert_eq!(rates, vec![Some(50_000), Some(100), None]);
+    }
+
+    #[test]
+    fn test_listening_view() {
+        let mut state =
+            ConnectionsWidgetState::new(&AppConfigFields::default(), &CanvasColours::default());
+        let listening = |local_address: &str, pid| ConnectionsWidgetData {
+            status: "LISTEN".to_string(),
+            pid: Some(pid),
+            ..connection(local_address, "0.0.0.0:0")
+        };
+        let data = [
+            connection("10.0.0.1:22", "10.0.0.2:51000"),
+            listening("0.0.0.0:22", 1),
+            listening("[::]:22", 1),
+            listening("127.0.0.1:8080", 2),
+            ConnectionsWidgetData {
+                protocol: Protocol::Udp,
+                status: String::new(),
+                ..connection("0.0.0.0:53", "0.0.0.0:0")
+            },
+        ];
+
+        state.ingest_data(&data);
+        assert_eq!(state.table.data().len(), 1);
+
+        state.toggle_listening_view();
+        state.ingest_data(&data);
+        let mut addresses = state
+            .table
+            .data()
+            .iter()
+            .map(|row| row.local_address.as_str())
+            .collect::<Vec<_>>();
+        addresses.sort_unstable();
+        assert_eq!(
+            addresses,
+            vec!["0.0.0.0:22, [::]:22", "0.0.0.0:53", "127.0.0.1:8080"]
+        );
+        assert!(state
+            .table
+            .columns
+            .iter()
+            .any(|column| *column.inner() == ConnectionsWidgetColumn::Status && column.is_hidden));
+    }
+}
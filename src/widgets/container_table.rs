@@ -0,0 +1,230 @@
+use std::borrow::Cow;
+
+use tui::{text::Text, widgets::Row};
+
+use crate::{
+    app::AppConfigFields,
+    canvas::{canvas_styling::CanvasColours, Painter},
+    components::data_table::{
+        ColumnHeader, DataTableColumn, DataTableProps, DataTableStyling, DataToCell, SortColumn,
+        SortDataTable, SortDataTableProps, SortOrder, SortsRow,
+    },
+    data_conversion::{binary_byte_string, dec_bytes_per_second_string},
+    utils::gen_util::{sort_partial_fn, truncate_to_text},
+};
+
+#[derive(Clone, Debug)]
+pub struct ContainerWidgetData {
+    pub name: String,
+    pub image: String,
+    pub status: String,
+    pub is_running: bool,
+    pub cpu_percent: Option<f64>,
+    pub mem_bytes: Option<u64>,
+    pub rx_bytes_per_sec: Option<u64>,
+    pub tx_bytes_per_sec: Option<u64>,
+}
+
+pub enum ContainerWidgetColumn {
+    Name,
+    Image,
+    Status,
+    Cpu,
+    Mem,
+    Rx,
+    Tx,
+}
+
+impl ColumnHeader for ContainerWidgetColumn {
+    fn text(&self) -> Cow<'static, str> {
+        match self {
+            ContainerWidgetColumn::Name => "Name".into(),
+            ContainerWidgetColumn::Image => "Image".into(),
+            ContainerWidgetColumn::Status => "Status".into(),
+            ContainerWidgetColumn::Cpu => "CPU%".into(),
+            ContainerWidgetColumn::Mem => "Mem".into(),
+            ContainerWidgetColumn::Rx => "Rx/s".into(),
+            ContainerWidgetColumn::Tx => "Tx/s".into(),
+        }
+    }
+}
+
+impl DataToCell<ContainerWidgetColumn> for ContainerWidgetData {
+    fn to_cell<'a>(
+        &'a self, column: &ContainerWidgetColumn, calculated_width: u16,
+    ) -> Option<Text<'a>> {
+        if calculated_width == 0 {
+            return None;
+        }
+
+        let text = match column {
+            ContainerWidgetColumn::Name => Some(self.name.clone()),
+            ContainerWidgetColumn::Image => Some(self.image.clone()),
+            ContainerWidgetColumn::Status => Some(self.status.clone()),
+            ContainerWidgetColumn::Cpu => self.cpu_percent.map(|cpu| format!("{cpu:.1}%")),
+            ContainerWidgetColumn::Mem => self.mem_bytes.map(binary_byte_string),
+            ContainerWidgetColumn::Rx => self.rx_bytes_per_sec.map(dec_bytes_per_second_string),
+            ContainerWidgetColumn::Tx => self.tx_bytes_per_sec.map(dec_bytes_per_second_string),
+        };
+
+        // A stopped container has no usage, rather than an unknown one.
+        let missing = if self.is_running { "N/A" } else { "" };
+        Some(truncate_to_text(
+            text.as_deref().unwrap_or(missing),
+            calculated_width,
+        ))
+    }
+
+    fn style_row<'a>(&self, row: Row<'a>, painter: &Painter) -> Row<'a> {
+        if self.is_running {
+            row
+        } else {
+            row.style(painter.colours.disabled_text_style)
+        }
+    }
+
+    fn column_widths<C: DataTableColumn<ContainerWidgetColumn>>(
+        data: &[ContainerWidgetData], _columns: &[C],
+    ) -> Vec<u16>
+    where
+        Self: Sized,
+    {
+        let mut widths = vec![0, 0, 0, 6, 8, 9, 9];
+        for row in data {
+            widths[0] = widths[0].max(row.name.len() as u16);
+            widths[1] = widths[1].max(row.image.len() as u16);
+            widths[2] = widths[2].max(row.status.len() as u16);
+        }
+
+        widths
+    }
+}
+
+impl SortsRow for ContainerWidgetColumn {
+    type DataType = ContainerWidgetData;
+
+    fn sort_data(&self, data: &mut [Self::DataType], descending: bool) {
+        match self {
+            ContainerWidgetColumn::Name => {
+                data.sort_by(|a, b| sort_partial_fn(descending)(&a.name, &b.name));
+            }
+            ContainerWidgetColumn::Image => {
+                data.sort_by(|a, b| sort_partial_fn(descending)(&a.image, &b.image));
+            }
+            ContainerWidgetColumn::Status => {
+                data.sort_by(|a, b| sort_partial_fn(descending)(&a.status, &b.status));
+            }
+            ContainerWidgetColumn::Cpu => {
+                data.sort_by(|a, b| sort_partial_fn(descending)(a.cpu_percent, b.cpu_percent));
+            }
+            ContainerWidgetColumn::Mem => {
+                data.sort_by(|a, b| sort_partial_fn(descending)(a.mem_bytes, b.mem_bytes));
+            }
+            ContainerWidgetColumn::Rx => {
+                data.sort_by(|a, b| {
+                    sort_partial_fn(descending)(a.rx_bytes_per_sec, b.rx_bytes_per_sec)
+                });
+            }
+            ContainerWidgetColumn::Tx => {
+                data.sort_by(|a, b| {
+                    sort_partial_fn(descending)(a.tx_bytes_per_sec, b.tx_bytes_per_sec)
+                });
+            }
+        }
+    }
+}
+
+pub struct ContainerWidgetState {
+    pub table: SortDataTable<ContainerWidgetData, ContainerWidgetColumn>,
+}
+
+impl ContainerWidgetState {
+    pub fn new(config: &AppConfigFields, colours: &CanvasColours) -> Self {
+        let columns = [
+            SortColumn::soft(ContainerWidgetColumn::Name, Some(0.25)),
+            SortColumn::soft(ContainerWidgetColumn::Image, Some(0.25)),
+            SortColumn::soft(ContainerWidgetColumn::Status, Some(0.2)),
+            SortColumn::hard(ContainerWidgetColumn::Cpu, 6).default_descending(),
+            SortColumn::hard(ContainerWidgetColumn::Mem, 8).default_descending(),
+            SortColumn::hard(ContainerWidgetColumn::Rx, 9).default_descending(),
+            SortColumn::hard(ContainerWidgetColumn::Tx, 9).default_descending(),
+        ];
+
+        let props = SortDataTableProps {
+            inner: DataTableProps {
+                title: Some(" Containers ".into()),
+                table_gap: config.table_gap,
+                left_to_right: true,
+                is_basic: config.use_basic_mode,
+                show_table_scroll_position: config.show_table_scroll_position,
+                show_scrollbar: config.show_scrollbar,
+                show_current_entry_when_unfocused: false,
+            },
+            sort_index: 3,
+            order: SortOrder::Descending,
+        };
+
+        let styling = DataTableStyling::from_colours(colours);
+
+        Self {
+            table: SortDataTable::new_sortable(columns, props, styling),
+        }
+    }
+
+    pub fn ingest_data(&mut self, containers: &[ContainerWidgetData]) {
+        let mut data = containers.to_vec();
+        if let Some(column) = self.table.columns.get(self.table.sort_index()) {
+            column.sort_by(&mut data, self.table.order());
+        }
+
+        self.table.set_data(data);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn container(name: &str, cpu_percent: Option<f64>) -> ContainerWidgetData {
+        ContainerWidgetData {
+            name: name.to_string(),
+            image: "nginx:1.25".to_string(),
+            status: "Up 3 hours".to_string(),
+            is_running: cpu_percent.is_some(),
+            cpu_percent,
+            mem_bytes: cpu_percent.map(|_| 64 * 1024 * 1024),
+            rx_bytes_per_sec: None,
+            tx_bytes_per_sec: None,
+        }
+    }
+
+    fn cell(data: &ContainerWidgetData, column: ContainerWidgetColumn) -> String {
+        data.to_cell(&column, 20)
+            .map(|text| {
+                text.lines[0]
+                    .0
+                    .iter()
+                    .map(|span| span.content.to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_container_cells() {
+        let mut data = vec![
+            container("db", None),
+            container("web", Some(12.34)),
+            container("cache", Some(50.0)),
+        ];
+        ContainerWidgetColumn::Cpu.sort_data(&mut data, true);
+        assert_eq!(data[0].name, "cache");
+        assert_eq!(data[2].name, "db");
+
+        assert_eq!(cell(&data[1], ContainerWidgetColumn::Cpu), "12.3%");
+        assert_eq!(cell(&data[1], ContainerWidgetColumn::Mem), "64MiB");
+        assert_eq!(cell(&data[1], ContainerWidgetColumn::Rx), "N/A");
+        // A stopped container's usage is left blank.
+        assert_eq!(cell(&data[2], ContainerWidgetColumn::Cpu), "");
+    }
+}
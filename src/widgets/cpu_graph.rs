@@ -4,7 +4,10 @@ use concat_string::concat_string;
 use tui::{style::Style, text::Text, widgets::Row};
 
 use crate::{
-    app::{data_harvester::cpu::CpuDataType, AppConfigFields},
+    app::{
+        data_harvester::cpu::{CpuDataType, CpuFrequency},
+        AppConfigFields,
+    },
     canvas::{canvas_styling::CanvasColours, Painter},
     components::data_table::{
         Column, ColumnHeader, DataTable, DataTableColumn, DataTableProps, DataTableStyling,
@@ -40,6 +43,8 @@ impl CpuWidgetStyling {
 pub enum CpuWidgetColumn {
     CPU,
     Use,
+    Freq,
+    Governor,
 }
 
 impl ColumnHeader for CpuWidgetColumn {
@@ -47,6 +52,8 @@ impl ColumnHeader for CpuWidgetColumn {
         match self {
             CpuWidgetColumn::CPU => "CPU".into(),
             CpuWidgetColumn::Use => "Use%".into(),
+            CpuWidgetColumn::Freq => "Freq".into(),
+            CpuWidgetColumn::Governor => "Gov".into(),
         }
     }
 }
@@ -56,20 +63,32 @@ pub enum CpuWidgetTableData {
     Entry {
         data_type: CpuDataType,
         last_entry: f64,
+        frequency: Option<CpuFrequency>,
+        /// Whether to highlight this core as thermally throttled.
+        is_throttled: bool,
     },
 }
 
 impl CpuWidgetTableData {
-    pub fn from_cpu_widget_data(data: &CpuWidgetData) -> CpuWidgetTableData {
+    pub fn from_cpu_widget_data(
+        data: &CpuWidgetData, highlight_throttled: bool,
+    ) -> CpuWidgetTableData {
         match data {
             CpuWidgetData::All => CpuWidgetTableData::All,
             CpuWidgetData::Entry {
                 data_type,
                 data: _,
                 last_entry,
+                frequency,
             } => CpuWidgetTableData::Entry {
                 data_type: *data_type,
                 last_entry: *last_entry,
+                frequency: frequency.clone(),
+                is_throttled: highlight_throttled
+                    && frequency
+                        .as_ref()
+                        .map(|frequency| frequency.is_throttled)
+                        .unwrap_or(false),
             },
         }
     }
@@ -89,11 +108,13 @@ impl DataToCell<CpuWidgetColumn> for CpuWidgetTableData {
         match &self {
             CpuWidgetTableData::All => match column {
                 CpuWidgetColumn::CPU => Some("All".into()),
-                CpuWidgetColumn::Use => None,
+                CpuWidgetColumn::Use | CpuWidgetColumn::Freq | CpuWidgetColumn::Governor => None,
             },
             CpuWidgetTableData::Entry {
                 data_type,
                 last_entry,
+                frequency,
+                ..
             } => {
                 if calculated_width == 0 {
                     None
@@ -119,6 +140,16 @@ impl DataToCell<CpuWidgetColumn> for CpuWidgetTableData {
                             &format!("{:.0}%", last_entry.round()),
                             calculated_width,
                         )),
+                        CpuWidgetColumn::Freq => frequency.as_ref().map(|frequency| {
+                            truncate_to_text(
+                                &format!("{:.1}GHz", frequency.mhz as f64 / 1000.0),
+                                calculated_width,
+                            )
+                        }),
+                        CpuWidgetColumn::Governor => frequency
+                            .as_ref()
+                            .and_then(|frequency| frequency.governor.as_deref())
+                            .map(|governor| truncate_to_text(governor, calculated_width)),
                     }
                 }
             }
@@ -129,10 +160,7 @@ impl DataToCell<CpuWidgetColumn> for CpuWidgetTableData {
     fn style_row<'a>(&self, row: Row<'a>, painter: &Painter) -> Row<'a> {
         let style = match self {
             CpuWidgetTableData::All => painter.colours.all_colour_style,
-            CpuWidgetTableData::Entry {
-                data_type,
-                last_entry: _,
-            } => match data_type {
+            CpuWidgetTableData::Entry { data_type, .. } => match data_type {
                 CpuDataType::Avg => painter.colours.avg_colour_style,
                 CpuDataType::Cpu(index) => {
                     painter.colours.cpu_colour_styles
@@ -144,13 +172,35 @@ impl DataToCell<CpuWidgetColumn> for CpuWidgetTableData {
         row.style(style)
     }
 
-    fn column_widths<C: DataTableColumn<CpuWidgetColumn>>(
-        _data: &[Self], _columns: &[C],
-    ) -> Vec<u16>
+    fn style_cell<'a>(
+        &self, mut text: Text<'a>, column: &CpuWidgetColumn, painter: &Painter,
+    ) -> Text<'a> {
+        if let (
+            CpuWidgetTableData::Entry {
+                is_throttled: true, ..
+            },
+            CpuWidgetColumn::CPU | CpuWidgetColumn::Freq,
+        ) = (self, column)
+        {
+            text.patch_style(painter.colours.high_latency_style);
+        }
+
+        text
+    }
+
+    fn column_widths<C: DataTableColumn<CpuWidgetColumn>>(_data: &[Self], columns: &[C]) -> Vec<u16>
     where
         Self: Sized,
     {
-        vec![1, 3]
+        columns
+            .iter()
+            .map(|column| match column.inner() {
+                CpuWidgetColumn::CPU => 1,
+                CpuWidgetColumn::Use => 3,
+                CpuWidgetColumn::Freq => 6,
+                CpuWidgetColumn::Governor => 3,
+            })
+            .collect()
     }
 }
 
@@ -161,6 +211,7 @@ pub struct CpuWidgetState {
     pub autohide_timer: Option<Instant>,
     pub table: DataTable<CpuWidgetTableData, CpuWidgetColumn>,
     pub styling: CpuWidgetStyling,
+    highlight_throttled: bool,
 }
 
 impl CpuWidgetState {
@@ -168,10 +219,19 @@ impl CpuWidgetState {
         config: &AppConfigFields, current_display_time: u64, autohide_timer: Option<Instant>,
         colours: &CanvasColours,
     ) -> Self {
-        const COLUMNS: [Column<CpuWidgetColumn>; 2] = [
-            Column::soft(CpuWidgetColumn::CPU, Some(0.5)),
-            Column::soft(CpuWidgetColumn::Use, Some(0.5)),
-        ];
+        let columns = if config.show_cpu_frequency {
+            vec![
+                Column::soft(CpuWidgetColumn::CPU, Some(0.25)),
+                Column::soft(CpuWidgetColumn::Use, Some(0.25)),
+                Column::soft(CpuWidgetColumn::Freq, Some(0.25)),
+                Column::soft(CpuWidgetColumn::Governor, Some(0.25)),
+            ]
+        } else {
+            vec![
+                Column::soft(CpuWidgetColumn::CPU, Some(0.5)),
+                Column::soft(CpuWidgetColumn::Use, Some(0.5)),
+            ]
+        };
 
         let props = DataTableProps {
             title: None,
@@ -179,6 +239,7 @@ impl CpuWidgetState {
             left_to_right: false,
             is_basic: false,
             show_table_scroll_position: false, // TODO: Should this be possible?
+            show_scrollbar: false,
             show_current_entry_when_unfocused: true,
         };
 
@@ -189,15 +250,18 @@ impl CpuWidgetState {
             is_legend_hidden: false,
             show_avg: config.show_average_cpu,
             autohide_timer,
-            table: DataTable::new(COLUMNS, props, styling),
+            table: DataTable::new(columns, props, styling),
             styling: CpuWidgetStyling::from_colours(colours),
+            highlight_throttled: config.highlight_throttled_cpus,
         }
     }
 
     pub fn update_table(&mut self, data: &[CpuWidgetData]) {
         self.table.set_data(
             data.iter()
-                .map(CpuWidgetTableData::from_cpu_widget_data)
+                .map(|data| {
+                    CpuWidgetTableData::from_cpu_widget_data(data, self.highlight_throttled)
+                })
                 .collect(),
         );
     }
@@ -0,0 +1,286 @@
+use std::borrow::Cow;
+
+use tui::{text::Text, widgets::Row};
+
+use crate::{
+    app::{data_harvester::agents::AgentHarvest, AppConfigFields},
+    canvas::{canvas_styling::CanvasColours, Painter},
+    components::data_table::{
+        ColumnHeader, DataTableColumn, DataTableProps, DataTableStyling, DataToCell, SortColumn,
+        SortDataTable, SortDataTableProps, SortOrder, SortsRow,
+    },
+    utils::gen_util::{format_uptime, sort_partial_fn, truncate_to_text},
+};
+
+#[derive(Clone, Debug)]
+pub struct DashboardWidgetData {
+    pub agent: AgentHarvest,
+}
+
+impl DashboardWidgetData {
+    /// The agent's hostname, or the address it was reached at if it hasn't said.
+    pub fn host(&self) -> &str {
+        self.agent
+            .hostname
+            .as_deref()
+            .unwrap_or(&self.agent.address)
+    }
+
+    fn is_down(&self) -> bool {
+        self.agent.error.is_some()
+    }
+
+    fn load(&self) -> Option<String> {
+        self.agent
+            .load_avg
+            .map(|[one, five, fifteen]| format!("{one:.2} {five:.2} {fifteen:.2}"))
+    }
+}
+
+pub enum DashboardWidgetColumn {
+    Host,
+    Cpu,
+    Mem,
+    Load,
+    Uptime,
+}
+
+impl ColumnHeader for DashboardWidgetColumn {
+    fn text(&self) -> Cow<'static, str> {
+        match self {
+            DashboardWidgetColumn::Host => "Host".into(),
+            DashboardWidgetColumn::Cpu => "CPU%".into(),
+            DashboardWidgetColumn::Mem => "Mem%".into(),
+            DashboardWidgetColumn::Load => "Load".into(),
+            DashboardWidgetColumn::Uptime => "Uptime".into(),
+        }
+    }
+}
+
+impl DataToCell<DashboardWidgetColumn> for DashboardWidgetData {
+    fn to_cell<'a>(
+        &'a self, column: &DashboardWidgetColumn, calculated_width: u16,
+    ) -> Option<Text<'a>> {
+        if calculated_width == 0 {
+            return None;
+        }
+
+        // A host that can't be reached has nothing current to show.
+        if self.is_down() {
+            return Some(match column {
+                DashboardWidgetColumn::Host => truncate_to_text(self.host(), calculated_width),
+                DashboardWidgetColumn::Uptime => truncate_to_text("down", calculated_width),
+                _ => Text::default(),
+            });
+        }
+
+        let percent = |value: Option<f64>| value.map(|value| format!("{value:.1}%"));
+        let text = match column {
+            DashboardWidgetColumn::Host => Some(self.host().to_string()),
+            DashboardWidgetColumn::Cpu => percent(self.agent.cpu_percent),
+            DashboardWidgetColumn::Mem => percent(self.agent.mem_percent),
+            DashboardWidgetColumn::Load => self.load(),
+            DashboardWidgetColumn::Uptime => self.agent.uptime_secs.map(format_uptime),
+        };
+
+        Some(truncate_to_text(
+            &text.unwrap_or_else(|| "N/A".to_string()),
+            calculated_width,
+        ))
+    }
+
+    fn style_row<'a>(&self, row: Row<'a>, painter: &Painter) -> Row<'a> {
+        if self.is_down() {
+            row.style(painter.colours.disabled_text_style)
+        } else {
+            row
+        }
+    }
+
+    fn column_widths<C: DataTableColumn<DashboardWidgetColumn>>(
+        data: &[DashboardWidgetData], _columns: &[C],
+    ) -> Vec<u16>
+    where
+        Self: Sized,
+    {
+        let host_width = data
+            .iter()
+            .map(|row| row.host().len() as u16)
+            .max()
+            .unwrap_or(0);
+
+        vec![host_width, 6, 6, 14, 11]
+    }
+}
+
+impl SortsRow for DashboardWidgetColumn {
+    type DataType = DashboardWidgetData;
+
+    fn sort_data(&self, data: &mut [Self::DataType], descending: bool) {
+        match self {
+            DashboardWidgetColumn::Host => {
+                data.sort_by(|a, b| sort_partial_fn(descending)(a.host(), b.host()));
+            }
+            DashboardWidgetColumn::Cpu => {
+                data.sort_by(|a, b| {
+                    sort_partial_fn(descending)(a.agent.cpu_percent, b.agent.cpu_percent)
+                });
+            }
+            DashboardWidgetColumn::Mem => {
+                data.sort_by(|a, b| {
+                    sort_partial_fn(descending)(a.agent.mem_percent, b.agent.mem_percent)
+                });
+            }
+            DashboardWidgetColumn::Load => {
+                data.sort_by(|a, b| {
+                    sort_partial_fn(descending)(
+                        a.agent.load_avg.map(|load| load[0]),
+                        b.agent.load_avg.map(|load| load[0]),
+                    )
+                });
+            }
+            DashboardWidgetColumn::Uptime => {
+                data.sort_by(|a, b| {
+                    sort_partial_fn(descending)(a.agent.uptime_secs, b.agent.uptime_secs)
+                });
+            }
+        }
+    }
+}
+
+pub struct DashboardWidgetState {
+    pub table: SortDataTable<DashboardWidgetData, DashboardWidgetColumn>,
+}
+
+impl DashboardWidgetState {
+    pub fn new(config: &AppConfigFields, colours: &CanvasColours) -> Self {
+        let columns = [
+            SortColumn::soft(DashboardWidgetColumn::Host, Some(0.4)),
+            SortColumn::hard(DashboardWidgetColumn::Cpu, 6).default_descending(),
+            SortColumn::hard(DashboardWidgetColumn::Mem, 6).default_descending(),
+            SortColumn::hard(DashboardWidgetColumn::Load, 14).default_descending(),
+            SortColumn::hard(DashboardWidgetColumn::Uptime, 11).default_descending(),
+        ];
+
+        let props = SortDataTableProps {
+            inner: DataTableProps {
+                title: Some(" Hosts ".into()),
+                table_gap: config.table_gap,
+                left_to_right: true,
+                is_basic: config.use_basic_mode,
+                show_table_scroll_position: config.show_table_scroll_position,
+                show_scrollbar: config.show_scrollbar,
+                show_current_entry_when_unfocused: false,
+            },
+            sort_index: 0,
+            order: SortOrder::Ascending,
+        };
+
+        let styling = DataTableStyling::from_colours(colours);
+
+        Self {
+            table: SortDataTable::new_sortable(columns, props, styling),
+        }
+    }
+
+    pub fn ingest_data(&mut self, agents: &[AgentHarvest]) {
+        let mut data: Vec<_> = agents
+            .iter()
+            .map(|agent| DashboardWidgetData {
+                agent: agent.clone(),
+            })
+            .collect();
+        if let Some(column) = self.table.columns.get(self.table.sort_index()) {
+            column.sort_by(&mut data, self.table.order());
+        }
+
+        self.table.set_data(data);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn agent(hostname: Option<&str>, cpu_percent: f64) -> AgentHarvest {
+        AgentHarvest {
+            address: "10.0.0.1:9100".to_string(),
+            hostname: hostname.map(str::to_string),
+            cpu_percent: Some(cpu_percent),
+            ..AgentHarvest::default()
+        }
+    }
+
+    #[test]
+    fn test_dashboard_cells() {
+        let up = DashboardWidgetData {
+            agent: AgentHarvest {
+                load_avg: Some([1.0, 0.5, 0.25]),
+                uptime_secs: Some(90_000),
+                ..agent(Some("rack1"), 12.34)
+            },
+        };
+        let cell = |data: &DashboardWidgetData, column| {
+            data.to_cell(&column, 20)
+                .map(|text| text.lines[0].0[0].content.to_string())
+        };
+
+        assert_eq!(
+            cell(&up, DashboardWidgetColumn::Host).as_deref(),
+            Some("rack1")
+        );
+        assert_eq!(
+            cell(&up, DashboardWidgetColumn::Cpu).as_deref(),
+            Some("12.3%")
+        );
+        assert_eq!(
+            cell(&up, DashboardWidgetColumn::Mem).as_deref(),
+            Some("N/A")
+        );
+        assert_eq!(
+            cell(&up, DashboardWidgetColumn::Load).as_deref(),
+            Some("1.00 0.50 0.25")
+        );
+        assert_eq!(
+            cell(&up, DashboardWidgetColumn::Uptime).as_deref(),
+            Some("1d 1h 0m")
+        );
+
+        // An agent that hasn't been reached yet goes by its address, and shows nothing stale.
+        let down = DashboardWidgetData {
+            agent: AgentHarvest {
+                error: Some("Connection refused".to_string()),
+                ..agent(None, 50.0)
+            },
+        };
+        assert_eq!(
+            cell(&down, DashboardWidgetColumn::Host).as_deref(),
+            Some("10.0.0.1:9100")
+        );
+        assert_eq!(
+            cell(&down, DashboardWidgetColumn::Uptime).as_deref(),
+            Some("down")
+        );
+        assert!(down
+            .to_cell(&DashboardWidgetColumn::Cpu, 20)
+            .unwrap()
+            .lines
+            .is_empty());
+    }
+
+    #[test]
+    fn test_sort_by_cpu() {
+        let mut data = vec![
+            DashboardWidgetData {
+                agent: agent(Some("a"), 10.0),
+            },
+            DashboardWidgetData {
+                agent: agent(Some("b"), 90.0),
+            },
+        ];
+
+        let column = SortColumn::hard(DashboardWidgetColumn::Cpu, 6).default_descending();
+        column.inner().sort_data(&mut data, true);
+        assert_eq!(data[0].host(), "b");
+    }
+}
@@ -0,0 +1,185 @@
+use std::borrow::Cow;
+
+use tui::{text::Text, widgets::Row};
+
+use crate::{
+    app::AppConfigFields,
+    canvas::{canvas_styling::CanvasColours, Painter},
+    components::data_table::{
+        ColumnHeader, DataTableColumn, DataTableProps, DataTableStyling, DataToCell, SortColumn,
+        SortDataTable, SortDataTableProps, SortOrder, SortsRow,
+    },
+    remote::discovery::DiscoveredAgent,
+    utils::gen_util::{sort_partial_fn, truncate_to_text},
+};
+
+#[derive(Clone, Debug)]
+pub struct DiscoveryWidgetData {
+    pub agent: DiscoveredAgent,
+    /// Whether this is the agent currently shown.
+    pub is_connected: bool,
+}
+
+pub enum DiscoveryWidgetColumn {
+    Host,
+    Address,
+}
+
+impl ColumnHeader for DiscoveryWidgetColumn {
+    fn text(&self) -> Cow<'static, str> {
+        match self {
+            DiscoveryWidgetColumn::Host => "Host".into(),
+            DiscoveryWidgetColumn::Address => "Address".into(),
+        }
+    }
+}
+
+impl DataToCell<DiscoveryWidgetColumn> for DiscoveryWidgetData {
+    fn to_cell<'a>(
+        &'a self, column: &DiscoveryWidgetColumn, calculated_width: u16,
+    ) -> Option<Text<'a>> {
+        if calculated_width == 0 {
+            return None;
+        }
+
+        Some(match column {
+            DiscoveryWidgetColumn::Host => truncate_to_text(&self.agent.hostname, calculated_width),
+            DiscoveryWidgetColumn::Address => {
+                truncate_to_text(&self.agent.address.to_string(), calculated_width)
+            }
+        })
+    }
+
+    fn style_row<'a>(&self, row: Row<'a>, painter: &Painter) -> Row<'a> {
+        if self.is_connected {
+            row.style(painter.colours.currently_selected_text_style)
+        } else {
+            row
+        }
+    }
+
+    fn column_widths<C: DataTableColumn<DiscoveryWidgetColumn>>(
+        data: &[DiscoveryWidgetData], _columns: &[C],
+    ) -> Vec<u16>
+    where
+        Self: Sized,
+    {
+        let mut widths = vec![0; 2];
+        for row in data {
+            widths[0] = widths[0].max(row.agent.hostname.len() as u16);
+            widths[1] = widths[1].max(row.agent.address.to_string().len() as u16);
+        }
+
+        widths
+    }
+}
+
+impl SortsRow for DiscoveryWidgetColumn {
+    type DataType = DiscoveryWidgetData;
+
+    fn sort_data(&self, data: &mut [Self::DataType], descending: bool) {
+        match self {
+            DiscoveryWidgetColumn::Host => {
+                data.sort_by(|a, b| {
+                    sort_partial_fn(descending)(&a.agent.hostname, &b.agent.hostname)
+                });
+            }
+            DiscoveryWidgetColumn::Address => {
+                data.sort_by(|a, b| sort_partial_fn(descending)(a.agent.address, b.agent.address));
+            }
+        }
+    }
+}
+
+/// Agents found on the local network, any of which can be picked to show instead.
+pub struct DiscoveryWidgetState {
+    pub table: SortDataTable<DiscoveryWidgetData, DiscoveryWidgetColumn>,
+}
+
+impl DiscoveryWidgetState {
+    pub fn new(config: &AppConfigFields, colours: &CanvasColours) -> Self {
+        let columns = [
+            SortColumn::soft(DiscoveryWidgetColumn::Host, Some(0.5)),
+            SortColumn::soft(DiscoveryWidgetColumn::Address, Some(0.5)),
+        ];
+
+        let props = SortDataTableProps {
+            inner: DataTableProps {
+                title: Some(" Agents ".into()),
+                table_gap: config.table_gap,
+                left_to_right: true,
+                is_basic: config.use_basic_mode,
+                show_table_scroll_position: config.show_table_scroll_position,
+                show_scrollbar: config.show_scrollbar,
+                show_current_entry_when_unfocused: false,
+            },
+            sort_index: 0,
+            order: SortOrder::Ascending,
+        };
+
+        let styling = DataTableStyling::from_colours(colours);
+
+        Self {
+            table: SortDataTable::new_sortable(columns, props, styling),
+        }
+    }
+
+    /// The agent to connect to on enter.
+    pub fn selected_agent(&self) -> Option<&DiscoveredAgent> {
+        self.table
+            .current_item()
+            .map(|discovery_widget_data| &discovery_widget_data.agent)
+    }
+
+    /// Lists `agents`, marking the one at `connected_address`, like `host:port`, if any.
+    pub fn ingest_data(&mut self, agents: &[DiscoveredAgent], connected_address: Option<&str>) {
+        let mut data: Vec<_> = agents
+            .iter()
+            .map(|agent| DiscoveryWidgetData {
+                agent: agent.clone(),
+                is_connected: connected_address == Some(agent.address.to_string().as_str()),
+            })
+            .collect();
+        if let Some(column) = self.table.columns.get(self.table.sort_index()) {
+            column.sort_by(&mut data, self.table.order());
+        }
+
+        self.table.set_data(data);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_discovery_rows() {
+        let agents = [
+            DiscoveredAgent {
+                hostname: "rack2".to_string(),
+                address: "10.0.0.2:9100".parse().unwrap(),
+            },
+            DiscoveredAgent {
+                hostname: "rack1".to_string(),
+                address: "10.0.0.1:9100".parse().unwrap(),
+            },
+        ];
+        let mut data: Vec<_> = agents
+            .iter()
+            .map(|agent| DiscoveryWidgetData {
+                agent: agent.clone(),
+                is_connected: false,
+            })
+            .collect();
+
+        DiscoveryWidgetColumn::Host.sort_data(&mut data, false);
+        assert_eq!(data[0].agent.hostname, "rack1");
+        assert_eq!(
+            data[1]
+                .to_cell(&DiscoveryWidgetColumn::Address, 20)
+                .map(|text| text.lines[0].0[0].content.to_string())
+                .as_deref(),
+            Some("10.0.0.2:9100")
+        );
+    }
+}
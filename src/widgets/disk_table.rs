@@ -4,13 +4,13 @@ use kstring::KString;
 use tui::text::Text;
 
 use crate::{
-    app::AppConfigFields,
-    canvas::canvas_styling::CanvasColours,
+    app::{data_harvester::disks::MountType, AppConfigFields},
+    canvas::{canvas_styling::CanvasColours, Painter},
     components::data_table::{
         ColumnHeader, DataTableColumn, DataTableProps, DataTableStyling, DataToCell, SortColumn,
         SortDataTable, SortDataTableProps, SortOrder, SortsRow,
     },
-    utils::gen_util::{get_decimal_bytes, sort_partial_fn, truncate_to_text},
+    utils::gen_util::{get_decimal_bytes, get_decimal_prefix, sort_partial_fn, truncate_to_text},
 };
 
 #[derive(Clone, Debug)]
@@ -23,6 +23,15 @@ pub struct DiskWidgetData {
     pub summed_total_bytes: Option<u64>,
     pub io_read: KString,
     pub io_write: KString,
+    pub read_latency_ms: Option<f32>,
+    pub write_latency_ms: Option<f32>,
+    pub mount_type: MountType,
+    pub stale: bool,
+    pub io_scheduler: Option<String>,
+    pub used_inodes: Option<u64>,
+    pub total_inodes: Option<u64>,
+    /// Whether the inode usage is past the alert threshold, which colours the whole row.
+    pub inode_alert: bool,
 }
 
 impl DiskWidgetData {
@@ -90,6 +99,47 @@ impl DiskWidgetData {
             None => "N/A".into(),
         }
     }
+
+    pub fn mount_point_string(&self) -> Cow<'_, str> {
+        if self.stale {
+            format!("{} [stale]", self.mount_point).into()
+        } else {
+            self.mount_point.as_str().into()
+        }
+    }
+
+    pub fn inodes_string(&self) -> KString {
+        if let (Some(used_inodes), Some(total_inodes)) = (self.used_inodes, self.total_inodes) {
+            let (used, used_prefix) = get_decimal_prefix(used_inodes, "");
+            let (total, total_prefix) = get_decimal_prefix(total_inodes, "");
+            format!("{used:.1}{used_prefix}/{total:.1}{total_prefix}").into()
+        } else {
+            "N/A".into()
+        }
+    }
+
+    pub fn inode_percent(&self) -> Option<f64> {
+        match (self.used_inodes, self.total_inodes) {
+            (Some(used_inodes), Some(total_inodes)) if total_inodes > 0 => {
+                Some(used_inodes as f64 / total_inodes as f64 * 100_f64)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn inode_percent_string(&self) -> KString {
+        match self.inode_percent() {
+            Some(val) => format!("{:.1}%", val).into(),
+            None => "N/A".into(),
+        }
+    }
+
+    fn latency_string(latency_ms: Option<f32>) -> KString {
+        match latency_ms {
+            Some(val) => format!("{:.1}ms", val).into(),
+            None => "N/A".into(),
+        }
+    }
 }
 
 pub enum DiskWidgetColumn {
@@ -102,6 +152,11 @@ pub enum DiskWidgetColumn {
     FreePercent,
     IoRead,
     IoWrite,
+    ReadLatency,
+    WriteLatency,
+    Inodes,
+    InodePercent,
+    Scheduler,
 }
 
 impl ColumnHeader for DiskWidgetColumn {
@@ -116,6 +171,11 @@ impl ColumnHeader for DiskWidgetColumn {
             DiskWidgetColumn::Total => "Total(t)",
             DiskWidgetColumn::IoRead => "R/s(r)",
             DiskWidgetColumn::IoWrite => "W/s(w)",
+            DiskWidgetColumn::ReadLatency => "R Lat",
+            DiskWidgetColumn::WriteLatency => "W Lat",
+            DiskWidgetColumn::Inodes => "Inodes",
+            DiskWidgetColumn::InodePercent => "Inode%",
+            DiskWidgetColumn::Scheduler => "Sched",
         }
         .into()
     }
@@ -129,7 +189,9 @@ impl DataToCell<DiskWidgetColumn> for DiskWidgetData {
 
         let text = match column {
             DiskWidgetColumn::Disk => truncate_to_text(&self.name, calculated_width),
-            DiskWidgetColumn::Mount => truncate_to_text(&self.mount_point, calculated_width),
+            DiskWidgetColumn::Mount => {
+                truncate_to_text(&self.mount_point_string(), calculated_width)
+            }
             DiskWidgetColumn::Used => truncate_to_text(&self.used_space(), calculated_width),
             DiskWidgetColumn::Free => truncate_to_text(&self.free_space(), calculated_width),
             DiskWidgetColumn::UsedPercent => {
@@ -141,11 +203,65 @@ impl DataToCell<DiskWidgetColumn> for DiskWidgetData {
             DiskWidgetColumn::Total => truncate_to_text(&self.total_space(), calculated_width),
             DiskWidgetColumn::IoRead => truncate_to_text(&self.io_read, calculated_width),
             DiskWidgetColumn::IoWrite => truncate_to_text(&self.io_write, calculated_width),
+            DiskWidgetColumn::ReadLatency => truncate_to_text(
+                &Self::latency_string(self.read_latency_ms),
+                calculated_width,
+            ),
+            DiskWidgetColumn::WriteLatency => truncate_to_text(
+                &Self::latency_string(self.write_latency_ms),
+                calculated_width,
+            ),
+            DiskWidgetColumn::Inodes => truncate_to_text(&self.inodes_string(), calculated_width),
+            DiskWidgetColumn::InodePercent => {
+                truncate_to_text(&self.inode_percent_string(), calculated_width)
+            }
+            DiskWidgetColumn::Scheduler => truncate_to_text(
+                self.io_scheduler.as_deref().unwrap_or("N/A"),
+                calculated_width,
+            ),
         };
 
         Some(text)
     }
 
+    fn style_cell<'a>(
+        &self, mut text: Text<'a>, column: &DiskWidgetColumn, painter: &Painter,
+    ) -> Text<'a> {
+        let latency_style = |latency_ms: f32| {
+            if latency_ms < 10.0 {
+                painter.colours.low_latency_style
+            } else if latency_ms < 100.0 {
+                painter.colours.medium_latency_style
+            } else {
+                painter.colours.high_latency_style
+            }
+        };
+
+        if self.inode_alert {
+            text.patch_style(painter.colours.high_latency_style);
+            return text;
+        }
+
+        match column {
+            DiskWidgetColumn::Mount if self.stale => {
+                text.patch_style(painter.colours.border_style);
+            }
+            DiskWidgetColumn::ReadLatency => {
+                if let Some(latency_ms) = self.read_latency_ms {
+                    text.patch_style(latency_style(latency_ms));
+                }
+            }
+            DiskWidgetColumn::WriteLatency => {
+                if let Some(latency_ms) = self.write_latency_ms {
+                    text.patch_style(latency_style(latency_ms));
+                }
+            }
+            _ => {}
+        }
+
+        text
+    }
+
     fn column_widths<C: DataTableColumn<DiskWidgetColumn>>(
         data: &[Self], _columns: &[C],
     ) -> Vec<u16>
@@ -156,7 +272,7 @@ impl DataToCell<DiskWidgetColumn> for DiskWidgetData {
 
         data.iter().for_each(|row| {
             widths[0] = max(widths[0], row.name.len() as u16);
-            widths[1] = max(widths[1], row.mount_point.len() as u16);
+            widths[1] = max(widths[1], row.mount_point_string().len() as u16);
         });
 
         widths
@@ -204,6 +320,27 @@ impl SortsRow for DiskWidgetColumn {
             DiskWidgetColumn::IoWrite => {
                 data.sort_by(|a, b| sort_partial_fn(descending)(&a.io_write, &b.io_write));
             }
+            DiskWidgetColumn::ReadLatency => {
+                data.sort_by(|a, b| {
+                    sort_partial_fn(descending)(&a.read_latency_ms, &b.read_latency_ms)
+                });
+            }
+            DiskWidgetColumn::WriteLatency => {
+                data.sort_by(|a, b| {
+                    sort_partial_fn(descending)(&a.write_latency_ms, &b.write_latency_ms)
+                });
+            }
+            DiskWidgetColumn::Inodes => {
+                data.sort_by(|a, b| sort_partial_fn(descending)(&a.used_inodes, &b.used_inodes));
+            }
+            DiskWidgetColumn::InodePercent => {
+                data.sort_by(|a, b| {
+                    sort_partial_fn(descending)(&a.inode_percent(), &b.inode_percent())
+                });
+            }
+            DiskWidgetColumn::Scheduler => {
+                data.sort_by(|a, b| sort_partial_fn(descending)(&a.io_scheduler, &b.io_scheduler));
+            }
         }
     }
 }
@@ -219,6 +356,11 @@ impl DiskTableWidget {
             SortColumn::hard(DiskWidgetColumn::UsedPercent, 9).default_descending(),
             SortColumn::hard(DiskWidgetColumn::IoRead, 10).default_descending(),
             SortColumn::hard(DiskWidgetColumn::IoWrite, 11).default_descending(),
+            SortColumn::hard(DiskWidgetColumn::ReadLatency, 9).default_descending(),
+            SortColumn::hard(DiskWidgetColumn::WriteLatency, 9).default_descending(),
+            SortColumn::hard(DiskWidgetColumn::Inodes, 13).default_descending(),
+            SortColumn::hard(DiskWidgetColumn::InodePercent, 8).default_descending(),
+            SortColumn::hard(DiskWidgetColumn::Scheduler, 12),
         ];
 
         let props = SortDataTableProps {
@@ -228,6 +370,7 @@ impl DiskTableWidget {
                 left_to_right: true,
                 is_basic: config.use_basic_mode,
                 show_table_scroll_position: config.show_table_scroll_position,
+                show_scrollbar: config.show_scrollbar,
                 show_current_entry_when_unfocused: false,
             },
             sort_index: 0,
@@ -261,3 +404,37 @@ impl DiskTableWidget {
         self.force_data_update();
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_inodes_string() {
+        let mut data = DiskWidgetData {
+            name: "sda1".into(),
+            mount_point: "/".into(),
+            free_bytes: None,
+            used_bytes: None,
+            total_bytes: None,
+            summed_total_bytes: None,
+            io_read: "0B/s".into(),
+            io_write: "0B/s".into(),
+            read_latency_ms: None,
+            write_latency_ms: None,
+            mount_type: MountType::Local,
+            stale: false,
+            io_scheduler: None,
+            used_inodes: None,
+            total_inodes: None,
+            inode_alert: false,
+        };
+        assert_eq!(data.inodes_string(), "N/A");
+        assert_eq!(data.inode_percent_string(), "N/A");
+
+        data.used_inodes = Some(1_240_000);
+        data.total_inodes = Some(6_500_000);
+        assert_eq!(data.inodes_string(), "1.2M/6.5M");
+        assert_eq!(data.inode_percent_string(), "19.1%");
+    }
+}
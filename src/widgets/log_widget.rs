@@ -0,0 +1,525 @@
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{BufRead, BufReader, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::Sender,
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{
+    components::scrollback::Scrollback,
+    utils::{gen_util::local_offset, json::JsonValue},
+    BottomEvent,
+};
+
+/// How many lines of the log are shown when it is first opened.
+const INITIAL_LINES: usize = 200;
+
+/// How often a followed file is checked for new lines.
+const FILE_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogSeverity {
+    Error,
+    Warning,
+    Info,
+    Debug,
+}
+
+impl LogSeverity {
+    /// Maps a syslog priority, from 0 (emergency) to 7 (debug).
+    fn from_priority(priority: u8) -> Self {
+        match priority {
+            0..=3 => LogSeverity::Error,
+            4 => LogSeverity::Warning,
+            5 | 6 => LogSeverity::Info,
+            _ => LogSeverity::Debug,
+        }
+    }
+
+    /// Guesses the severity of a plain text line from the words in it.
+    fn guess(line: &str) -> Self {
+        let line = line.to_lowercase();
+        if ["error", "fatal", "crit", "panic", "emerg", "fail"]
+            .iter()
+            .any(|word| line.contains(word))
+        {
+            LogSeverity::Error
+        } else if line.contains("warn") {
+            LogSeverity::Warning
+        } else if line.contains("debug") || line.contains("trace") {
+            LogSeverity::Debug
+        } else {
+            LogSeverity::Info
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogLine {
+    pub text: String,
+    pub severity: LogSeverity,
+}
+
+impl LogLine {
+    pub fn plain(text: &str) -> Self {
+        LogLine {
+            text: text.to_string(),
+            severity: LogSeverity::guess(text),
+        }
+    }
+
+    /// Parses an entry of `journalctl --output=json` into a line like
+    /// `Oct 15 13:37:00 sshd[1234]: Accepted publickey for root`.
+    pub fn from_journal_json(json: &str) -> Option<Self> {
+        let entry = JsonValue::parse(json)?;
+        let field = |name: &str| entry.get(name).and_then(JsonValue::as_str);
+
+        // Messages that aren't valid UTF-8 are sent as an array of bytes instead.
+        let message = match entry.get("MESSAGE")? {
+            JsonValue::String(message) => message.clone(),
+            JsonValue::Array(bytes) => String::from_utf8_lossy(
+                &bytes
+                    .iter()
+                    .filter_map(|byte| byte.as_u64().and_then(|byte| u8::try_from(byte).ok()))
+                    .collect::<Vec<_>>(),
+            )
+            .into_owned(),
+            _ => return None,
+        };
+
+        let mut text = String::new();
+        if let Some(time) = field("__REALTIME_TIMESTAMP")
+            .and_then(|usec| usec.parse().ok())
+            .and_then(format_journal_time)
+        {
+            text.push_str(&time);
+            text.push(' ');
+        }
+        if let Some(identifier) = field("SYSLOG_IDENTIFIER").or_else(|| field("_COMM")) {
+            text.push_str(identifier);
+            if let Some(pid) = field("_PID") {
+                text.push_str(&format!("[{pid}]"));
+            }
+            text.push_str(": ");
+        }
+        text.push_str(message.trim_end());
+
+        let severity = field("PRIORITY")
+            .and_then(|priority| priority.parse().ok())
+            .map(LogSeverity::from_priority)
+            .unwrap_or(LogSeverity::Info);
+
+        Some(LogLine { text, severity })
+    }
+}
+
+/// Formats a journal timestamp, in microseconds since the epoch, in local time.
+fn format_journal_time(usec: u64) -> Option<String> {
+    let time = time::OffsetDateTime::from_unix_timestamp_nanos(i128::from(usec) * 1000).ok()?;
+    time.to_offset(local_offset(time.unix_timestamp()))
+        .format(&time::macros::format_description!(
+            "[month repr:short] [day] [hour]:[minute]:[second]"
+        ))
+        .ok()
+}
+
+/// Where a log widget reads its lines from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LogSource {
+    /// The systemd journal, through `journalctl`.
+    Journal,
+    /// A plain text file, like `/var/log/syslog`.
+    File(PathBuf),
+}
+
+pub struct LogWidgetState {
+    pub lines: VecDeque<LogLine>,
+    /// How many lines are kept, dropping the oldest ones past it.
+    pub max_lines: usize,
+    pub scrollback: Scrollback,
+    pub is_paused: bool,
+    /// Lines that came in while paused, added once resumed.
+    paused_lines: Vec<LogLine>,
+    /// Only lines containing this are shown, ignoring case.
+    pub search_query: String,
+    /// Whether keys are being typed into the search query.
+    pub is_typing_search: bool,
+}
+
+impl LogWidgetState {
+    pub fn new(max_lines: usize) -> Self {
+        LogWidgetState {
+            lines: VecDeque::new(),
+            max_lines,
+            scrollback: Scrollback::default(),
+            is_paused: false,
+            paused_lines: Vec::new(),
+            search_query: String::new(),
+            is_typing_search: false,
+        }
+    }
+
+    pub fn push_lines(&mut self, lines: Vec<LogLine>) {
+        if self.is_paused {
+            self.paused_lines.extend(lines);
+            // Only the newest lines would be kept once resumed anyway.
+            let excess = self.paused_lines.len().saturating_sub(self.max_lines);
+            self.paused_lines.drain(..excess);
+        } else {
+            self.lines.extend(lines);
+            while self.lines.len() > self.max_lines {
+                self.lines.pop_front();
+            }
+        }
+    }
+
+    /// Stops showing new lines until resumed, keeping them for later.
+    pub fn toggle_pause(&mut self) {
+        self.is_paused = !self.is_paused;
+        if !self.is_paused {
+            let lines = std::mem::take(&mut self.paused_lines);
+            self.push_lines(lines);
+        }
+    }
+
+    /// How many lines came in while paused.
+    pub fn paused_line_count(&self) -> usize {
+        self.paused_lines.len()
+    }
+
+    pub fn start_search(&mut self) {
+        self.is_typing_search = true;
+    }
+
+    pub fn clear_search(&mut self) {
+        self.search_query.clear();
+        self.is_typing_search = false;
+        self.scrollback.offset = 0;
+    }
+
+    pub fn search_push(&mut self, c: char) {
+        self.search_query.push(c);
+        self.scrollback.offset = 0;
+    }
+
+    pub fn search_pop(&mut self) {
+        self.search_query.pop();
+        self.scrollback.offset = 0;
+    }
+
+    /// Handles the keys for searching and scrolling, returning whether the key was one of them.
+    /// Every key without Ctrl or Alt is taken while the search is being typed.
+    pub fn handle_key(&mut self, event: &KeyEvent) -> bool {
+        let is_control = event
+            .modifiers
+            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+        if is_control {
+            return false;
+        } else if self.is_typing_search {
+            match event.code {
+                KeyCode::Char(c) => self.search_push(c),
+                KeyCode::Backspace => self.search_pop(),
+                KeyCode::Enter => self.is_typing_search = false,
+                KeyCode::Esc => self.clear_search(),
+                _ => {}
+            }
+            return true;
+        }
+
+        let is_shifted = event.modifiers.contains(KeyModifiers::SHIFT);
+        match event.code {
+            KeyCode::Char('/') => self.start_search(),
+            KeyCode::Esc if !self.search_query.is_empty() => self.clear_search(),
+            KeyCode::End => self.scrollback.offset = 0,
+            KeyCode::F(7) => self.scrollback.toggle_wrap(),
+            KeyCode::Up | KeyCode::PageUp => self.scrollback.scroll_up(),
+            KeyCode::Down | KeyCode::PageDown => self.scrollback.scroll_down(),
+            KeyCode::Left if is_shifted => self.scrollback.scroll_left(),
+            KeyCode::Right if is_shifted => self.scrollback.scroll_right(),
+            _ => return false,
+        }
+
+        true
+    }
+
+    /// Returns the lines matching the search, from newest to oldest.
+    pub fn shown_lines(&self) -> impl Iterator<Item = &LogLine> {
+        let query = self.search_query.to_lowercase();
+        self.lines
+            .iter()
+            .rev()
+            .filter(move |line| query.is_empty() || line.text.to_lowercase().contains(&query))
+    }
+}
+
+/// A log followed in the background for a widget, which stops being followed once this is
+/// dropped, like when the widget is taken out of the layout.
+pub struct LogFollower {
+    pub source: LogSource,
+    is_stopped: Arc<AtomicBool>,
+    /// The `journalctl` following the journal, killed to stop the read it blocks on.
+    journal: Arc<Mutex<Option<Child>>>,
+}
+
+impl Drop for LogFollower {
+    fn drop(&mut self) {
+        self.is_stopped.store(true, Ordering::SeqCst);
+        if let Some(child) = self.journal.lock().unwrap().as_mut() {
+            let _ = child.kill();
+        }
+    }
+}
+
+/// Follows the log in the background, sending new lines to the widget as they come in, until the
+/// returned follower is dropped.
+pub fn spawn_follower(
+    source: LogSource, widget_id: u64, sender: Sender<BottomEvent>,
+) -> LogFollower {
+    let follower = LogFollower {
+        source: source.clone(),
+        is_stopped: Arc::default(),
+        journal: Arc::default(),
+    };
+    let (is_stopped, journal) = (follower.is_stopped.clone(), follower.journal.clone());
+
+    thread::spawn(move || {
+        let send = |lines: Vec<LogLine>| {
+            !is_stopped.load(Ordering::SeqCst)
+                && sender
+                    .send(BottomEvent::LogLines { widget_id, lines })
+                    .is_ok()
+        };
+
+        let result = match &source {
+            LogSource::Journal => follow_journal(&journal, &is_stopped, &send),
+            LogSource::File(path) => follow_file(path, &is_stopped, &send),
+        };
+        if let Err(err) = result {
+            send(vec![LogLine {
+                text: format!("Unable to follow the log: {err}"),
+                severity: LogSeverity::Error,
+            }]);
+        }
+    });
+
+    follower
+}
+
+/// Reads every line that is already buffered, so lines that come in together are sent together.
+fn read_lines(reader: &mut impl BufRead) -> std::io::Result<Option<Vec<String>>> {
+    let mut lines = Vec::new();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok((!lines.is_empty()).then_some(lines));
+        }
+        lines.push(line.trim_end_matches(['\n', '\r']).to_string());
+        if reader.fill_buf()?.is_empty() {
+            return Ok(Some(lines));
+        }
+    }
+}
+
+fn follow_journal(
+    journal: &Mutex<Option<Child>>, is_stopped: &AtomicBool, send: &impl Fn(Vec<LogLine>) -> bool,
+) -> std::io::Result<()> {
+    let mut child = Command::new("journalctl")
+        .args([
+            "--follow",
+            "--output=json",
+            &format!("--lines={INITIAL_LINES}"),
+        ])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+    let stdout = child.stdout.take();
+    *journal.lock().unwrap() = Some(child);
+    // Stopping might have happened before there was anything to kill.
+    let Some(stdout) = stdout.filter(|_| !is_stopped.load(Ordering::SeqCst)) else {
+        return stop_journal(journal);
+    };
+
+    let mut reader = BufReader::new(stdout);
+    while let Some(lines) = read_lines(&mut reader)? {
+        let lines = lines
+            .iter()
+            .filter_map(|line| LogLine::from_journal_json(line))
+            .collect();
+        if !send(lines) {
+            break;
+        }
+    }
+
+    stop_journal(journal)
+}
+
+fn stop_journal(journal: &Mutex<Option<Child>>) -> std::io::Result<()> {
+    if let Some(mut child) = journal.lock().unwrap().take() {
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+    Ok(())
+}
+
+fn follow_file(
+    path: &Path, is_stopped: &AtomicBool, send: &impl Fn(Vec<LogLine>) -> bool,
+) -> std::io::Result<()> {
+    let mut file = File::open(path)?;
+
+    // Start from the last few lines, rather than reading the whole file.
+    let start = file
+        .metadata()?
+        .len()
+        .saturating_sub(INITIAL_LINES as u64 * 256);
+    file.seek(SeekFrom::Start(start))?;
+    let mut reader = BufReader::new(file);
+    if start > 0 {
+        reader.read_line(&mut String::new())?;
+    }
+    let mut is_first_read = true;
+
+    loop {
+        match read_lines(&mut reader)? {
+            Some(lines) => {
+                let skip = if is_first_read {
+                    lines.len().saturating_sub(INITIAL_LINES)
+                } else {
+                    0
+                };
+                is_first_read = false;
+                let lines = lines
+                    .iter()
+                    .skip(skip)
+                    .map(|line| LogLine::plain(line))
+                    .collect();
+                if !send(lines) {
+                    return Ok(());
+                }
+            }
+            None => {
+                thread::sleep(FILE_POLL_INTERVAL);
+                if is_stopped.load(Ordering::SeqCst) {
+                    return Ok(());
+                }
+
+                // A file that got shorter was truncated or rotated, so start over from the top.
+                let position = reader.stream_position()?;
+                if std::fs::metadata(path).is_ok_and(|metadata| metadata.len() < position) {
+                    reader = BufReader::new(File::open(path)?);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::mpsc::{self, RecvTimeoutError};
+
+    use super::*;
+
+    #[test]
+    fn test_dropped_follower_stops() {
+        let path = std::env::temp_dir().join(format!("btm_log_follow_{}", std::process::id()));
+        std::fs::write(&path, "first\n").unwrap();
+
+        let (sender, receiver) = mpsc::channel();
+        let follower = spawn_follower(LogSource::File(path.clone()), 1, sender);
+        drop(follower);
+
+        // The thread ends on its next check, taking the last sender with it.
+        let stopped = (0..10).any(|_| {
+            matches!(
+                receiver.recv_timeout(FILE_POLL_INTERVAL * 2),
+                Err(RecvTimeoutError::Disconnected)
+            )
+        });
+        std::fs::remove_file(path).unwrap();
+        assert!(stopped);
+    }
+
+    #[test]
+    fn test_journal_json() {
+        let line = LogLine::from_journal_json(
+            r#"{"MESSAGE":"Accepted publickey for root","PRIORITY":"6","SYSLOG_IDENTIFIER":"sshd","_PID":"1234"}"#,
+        )
+        .unwrap();
+        assert_eq!(line.text, "sshd[1234]: Accepted publickey for root");
+        assert_eq!(line.severity, LogSeverity::Info);
+
+        let line = LogLine::from_journal_json(
+            r#"{"MESSAGE":[104,105,255],"PRIORITY":"3","_COMM":"kernel"}"#,
+        )
+        .unwrap();
+        assert_eq!(line.text, "kernel: hi\u{fffd}");
+        assert_eq!(line.severity, LogSeverity::Error);
+
+        let line = LogLine::from_journal_json(
+            r#"{"MESSAGE":"hello","__REALTIME_TIMESTAMP":"1760535420000000"}"#,
+        )
+        .unwrap();
+        assert!(line.text.ends_with(":00 hello"));
+
+        assert_eq!(LogLine::from_journal_json("not json"), None);
+        assert_eq!(LogLine::from_journal_json(r#"{"PRIORITY":"3"}"#), None);
+    }
+
+    #[test]
+    fn test_guessed_severity() {
+        assert_eq!(
+            LogLine::plain("kernel: EXT4-fs error (device sda1)").severity,
+            LogSeverity::Error
+        );
+        assert_eq!(
+            LogLine::plain("nginx: [warn] conflicting server name").severity,
+            LogSeverity::Warning
+        );
+        assert_eq!(
+            LogLine::plain("cron: (root) CMD (run-parts)").severity,
+            LogSeverity::Info
+        );
+    }
+
+    #[test]
+    fn test_pause_and_search() {
+        let mut state = LogWidgetState::new(3);
+        state.push_lines(vec![LogLine::plain("one"), LogLine::plain("two")]);
+
+        state.toggle_pause();
+        state.push_lines(vec![LogLine::plain("three"), LogLine::plain("four")]);
+        assert_eq!(state.lines.len(), 2);
+        assert_eq!(state.paused_line_count(), 2);
+
+        // Resuming adds what came in, dropping the oldest lines past the limit.
+        state.toggle_pause();
+        let shown = |state: &LogWidgetState| {
+            state
+                .shown_lines()
+                .map(|line| line.text.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(shown(&state), ["four", "three", "two"]);
+
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        assert!(state.handle_key(&key(KeyCode::Char('/'))));
+        assert!(state.handle_key(&key(KeyCode::Char('T'))));
+        assert!(state.handle_key(&key(KeyCode::Enter)));
+        assert_eq!(shown(&state), ["three", "two"]);
+
+        // Once typed, other keys are left to the rest of bottom.
+        assert!(!state.handle_key(&key(KeyCode::Char('q'))));
+        assert!(state.handle_key(&key(KeyCode::Esc)));
+        assert_eq!(shown(&state).len(), 3);
+    }
+}
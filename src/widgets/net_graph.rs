@@ -1,8 +1,108 @@
-use std::time::Instant;
+use std::{
+    collections::{HashMap, VecDeque},
+    time::Instant,
+};
+
+use crate::app::data_harvester::network::{InterfaceErrors, TcpStats};
+
+/// How fast the TCP counters went up between the last two harvests, per second.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TcpStatsRates {
+    pub retransmits: f64,
+    pub resets: f64,
+    pub active_opens: f64,
+    pub passive_opens: f64,
+    pub attempt_fails: f64,
+    pub in_errs: f64,
+    /// The share of sent segments that were retransmits, from 0 to 1.
+    pub retransmit_ratio: f64,
+}
+
+impl TcpStatsRates {
+    /// Computes the rates between two sets of counters taken `elapsed_secs` apart. Counters that
+    /// went down, like after a counter reset, are treated as unchanged.
+    pub fn between(prev: &TcpStats, curr: &TcpStats, elapsed_secs: f64) -> Self {
+        let rate = |prev: u64, curr: u64| {
+            if elapsed_secs > 0.0 {
+                curr.saturating_sub(prev) as f64 / elapsed_secs
+            } else {
+                0.0
+            }
+        };
+        let out_segs = curr.out_segs.saturating_sub(prev.out_segs);
+        let retrans_segs = curr.retrans_segs.saturating_sub(prev.retrans_segs);
+
+        TcpStatsRates {
+            retransmits: rate(prev.retrans_segs, curr.retrans_segs),
+            resets: rate(
+                prev.estab_resets + prev.out_rsts,
+                curr.estab_resets + curr.out_rsts,
+            ),
+            active_opens: rate(prev.active_opens, curr.active_opens),
+            passive_opens: rate(prev.passive_opens, curr.passive_opens),
+            attempt_fails: rate(prev.attempt_fails, curr.attempt_fails),
+            in_errs: rate(prev.in_errs, curr.in_errs),
+            retransmit_ratio: if out_segs > 0 {
+                retrans_segs as f64 / out_segs as f64
+            } else {
+                0.0
+            },
+        }
+    }
+}
+
+/// How fast an interface's error and drop counters went up between the last two harvests, per
+/// second.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InterfaceErrorRates {
+    pub errors: f64,
+    pub drops: f64,
+}
+
+impl InterfaceErrorRates {
+    /// Computes the rates between two sets of counters taken `elapsed_secs` apart, adding up
+    /// both directions.
+    pub fn between(prev: &InterfaceErrors, curr: &InterfaceErrors, elapsed_secs: f64) -> Self {
+        let rate = |prev: u64, curr: u64| {
+            if elapsed_secs > 0.0 {
+                curr.saturating_sub(prev) as f64 / elapsed_secs
+            } else {
+                0.0
+            }
+        };
+        let drops = |errors: &InterfaceErrors| {
+            errors.rx_dropped.unwrap_or(0) + errors.tx_dropped.unwrap_or(0)
+        };
+
+        InterfaceErrorRates {
+            errors: rate(
+                prev.rx_errors + prev.tx_errors,
+                curr.rx_errors + curr.tx_errors,
+            ),
+            drops: rate(drops(prev), drops(curr)),
+        }
+    }
+}
 
 pub struct NetWidgetState {
     pub current_display_time: u64,
     pub autohide_timer: Option<Instant>,
+    /// History of the total TCP connection count, with the time each entry was harvested at.
+    pub tcp_connections: VecDeque<(Instant, u64)>,
+    /// The highest RX rate seen since the last reset, in bits per second.
+    pub peak_rx_bps: f64,
+    /// The highest TX rate seen since the last reset, in bits per second.
+    pub peak_tx_bps: f64,
+    /// Whether the TCP/IP statistics panel is shown next to the graph.
+    pub show_tcp_stats: bool,
+    /// The latest TCP counters, with the time they were harvested at.
+    pub tcp_stats: Option<(Instant, TcpStats)>,
+    pub tcp_stats_rates: Option<TcpStatsRates>,
+    /// Whether the per-interface error panel is shown next to the graph.
+    pub show_interface_errors: bool,
+    /// The latest error counters of each interface, with the time they were harvested at.
+    pub interface_errors: Option<(Instant, HashMap<String, InterfaceErrors>)>,
+    pub interface_error_rates: HashMap<String, InterfaceErrorRates>,
 }
 
 impl NetWidgetState {
@@ -10,6 +110,143 @@ impl NetWidgetState {
         NetWidgetState {
             current_display_time,
             autohide_timer,
+            tcp_connections: VecDeque::new(),
+            peak_rx_bps: 0.0,
+            peak_tx_bps: 0.0,
+            show_tcp_stats: false,
+            tcp_stats: None,
+            tcp_stats_rates: None,
+            show_interface_errors: false,
+            interface_errors: None,
+            interface_error_rates: HashMap::new(),
+        }
+    }
+
+    /// Raises the stored peaks if the given rates, in bits per second, exceed them.
+    pub fn update_peaks(&mut self, rx_bps: f64, tx_bps: f64) {
+        self.peak_rx_bps = self.peak_rx_bps.max(rx_bps);
+        self.peak_tx_bps = self.peak_tx_bps.max(tx_bps);
+    }
+
+    pub fn reset_peaks(&mut self) {
+        self.peak_rx_bps = 0.0;
+        self.peak_tx_bps = 0.0;
+    }
+
+    /// Records a new TCP connection count entry.
+    pub fn push_tcp_connections(&mut self, instant: Instant, count: u64) {
+        self.tcp_connections.push_back((instant, count));
+    }
+
+    pub fn toggle_tcp_stats(&mut self) {
+        self.show_tcp_stats = !self.show_tcp_stats;
+    }
+
+    /// Records a new set of TCP counters, updating the rates if there was a previous set.
+    pub fn update_tcp_stats(&mut self, instant: Instant, stats: TcpStats) {
+        if let Some((prev_instant, prev_stats)) = &self.tcp_stats {
+            let elapsed_secs = instant.duration_since(*prev_instant).as_secs_f64();
+            self.tcp_stats_rates = Some(TcpStatsRates::between(prev_stats, &stats, elapsed_secs));
+        }
+        self.tcp_stats = Some((instant, stats));
+    }
+
+    pub fn toggle_interface_errors(&mut self) {
+        self.show_interface_errors = !self.show_interface_errors;
+    }
+
+    /// Records a new set of interface error counters, updating the rates of the interfaces that
+    /// were in the previous set.
+    pub fn update_interface_errors(
+        &mut self, instant: Instant, errors: HashMap<String, InterfaceErrors>,
+    ) {
+        if let Some((prev_instant, prev_errors)) = &self.interface_errors {
+            let elapsed_secs = instant.duration_since(*prev_instant).as_secs_f64();
+            self.interface_error_rates = errors
+                .iter()
+                .filter_map(|(name, curr)| {
+                    let prev = prev_errors.get(name)?;
+                    Some((
+                        name.clone(),
+                        InterfaceErrorRates::between(prev, curr, elapsed_secs),
+                    ))
+                })
+                .collect();
+        }
+        self.interface_errors = Some((instant, errors));
+    }
+
+    /// Removes any TCP connection count entries older than `max_time_millis`.
+    pub fn clean_tcp_connections(&mut self, max_time_millis: u64) {
+        let current_time = Instant::now();
+
+        while let Some((instant, _)) = self.tcp_connections.front() {
+            if current_time.duration_since(*instant).as_millis() > max_time_millis.into() {
+                self.tcp_connections.pop_front();
+            } else {
+                break;
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_tcp_stats_rates() {
+        let prev = TcpStats {
+            out_segs: 1000,
+            retrans_segs: 10,
+            estab_resets: 5,
+            out_rsts: 5,
+            active_opens: 100,
+            ..TcpStats::default()
+        };
+        let curr = TcpStats {
+            out_segs: 1200,
+            retrans_segs: 20,
+            estab_resets: 7,
+            out_rsts: 8,
+            active_opens: 90,
+            ..TcpStats::default()
+        };
+
+        let rates = TcpStatsRates::between(&prev, &curr, 2.0);
+        assert_eq!(rates.retransmits, 5.0);
+        assert_eq!(rates.resets, 2.5);
+        assert_eq!(rates.active_opens, 0.0);
+        assert_eq!(rates.retransmit_ratio, 0.05);
+
+        assert_eq!(
+            TcpStatsRates::between(&prev, &prev, 0.0),
+            TcpStatsRates::default()
+        );
+    }
+
+    #[test]
+    fn test_interface_error_rates() {
+        let prev = InterfaceErrors {
+            rx_errors: 10,
+            tx_errors: 2,
+            rx_dropped: Some(100),
+            ..InterfaceErrors::default()
+        };
+        let curr = InterfaceErrors {
+            rx_errors: 14,
+            tx_errors: 4,
+            rx_dropped: Some(110),
+            tx_dropped: Some(2),
+            ..InterfaceErrors::default()
+        };
+
+        let rates = InterfaceErrorRates::between(&prev, &curr, 2.0);
+        assert_eq!(rates.errors, 3.0);
+        assert_eq!(rates.drops, 6.0);
+        assert_eq!(
+            InterfaceErrorRates::between(&curr, &prev, 2.0),
+            InterfaceErrorRates::default()
+        );
+    }
+}
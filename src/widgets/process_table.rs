@@ -1,20 +1,26 @@
-use std::{borrow::Cow, collections::BTreeMap};
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    time::{Duration, Instant},
+};
 
-use const_format::formatcp;
 use fxhash::{FxHashMap, FxHashSet};
 use itertools::Itertools;
 
 use crate::{
     app::{
-        data_farmer::{DataCollection, ProcessData},
+        data_farmer::{DataCollection, ProcessData, ProcessHistory},
         data_harvester::processes::ProcessHarvest,
         query::*,
         AppConfigFields, AppSearchState,
     },
     canvas::canvas_styling::CanvasColours,
-    components::data_table::{
-        Column, ColumnHeader, ColumnWidthBounds, DataTable, DataTableColumn, DataTableProps,
-        DataTableStyling, SortColumn, SortDataTable, SortDataTableProps, SortOrder, SortsRow,
+    components::{
+        data_table::{
+            Column, ColumnHeader, ColumnWidthBounds, DataTable, DataTableColumn, DataTableProps,
+            DataTableStyling, SortColumn, SortDataTable, SortDataTableProps, SortOrder, SortsRow,
+        },
+        tree_prefix::TreePrefixes,
     },
     Pid,
 };
@@ -25,6 +31,12 @@ pub use proc_widget_column::*;
 pub mod proc_widget_data;
 pub use proc_widget_data::*;
 
+#[cfg(target_family = "unix")]
+pub mod signals;
+
+pub mod export;
+pub use export::*;
+
 mod sort_table;
 use sort_table::SortTableColumn;
 
@@ -34,6 +46,8 @@ pub struct ProcessSearchState {
     pub is_ignoring_case: bool,
     pub is_searching_whole_word: bool,
     pub is_searching_with_regex: bool,
+    /// Whether the search also matches against each process' full command line.
+    pub is_searching_cmdline: bool,
 }
 
 impl Default for ProcessSearchState {
@@ -43,6 +57,7 @@ impl Default for ProcessSearchState {
             is_ignoring_case: true,
             is_searching_whole_word: false,
             is_searching_with_regex: false,
+            is_searching_cmdline: false,
         }
     }
 }
@@ -59,6 +74,10 @@ impl ProcessSearchState {
     pub fn search_toggle_regex(&mut self) {
         self.is_searching_with_regex = !self.is_searching_with_regex;
     }
+
+    pub fn search_toggle_cmdline(&mut self) {
+        self.is_searching_cmdline = !self.is_searching_cmdline;
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -68,6 +87,48 @@ pub enum ProcWidgetMode {
     Normal,
 }
 
+/// Which users' processes are shown, which is applied before any search.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum UserFilter {
+    #[default]
+    All,
+    /// Only the processes of the user running bottom, with their name.
+    Current(String),
+    /// Only the processes of a user picked from the user picker.
+    User(String),
+}
+
+impl UserFilter {
+    fn matches(&self, process: &ProcessHarvest) -> bool {
+        match self {
+            UserFilter::All => true,
+            UserFilter::Current(user) | UserFilter::User(user) => process.user == user.as_str(),
+        }
+    }
+
+    /// The user shown, if only one is.
+    pub fn user(&self) -> Option<&str> {
+        match self {
+            UserFilter::All => None,
+            UserFilter::Current(user) | UserFilter::User(user) => Some(user),
+        }
+    }
+}
+
+/// What processes are put together by in grouped mode.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GroupBy {
+    /// The name or command shown for each process.
+    #[default]
+    Name,
+    /// The program each process runs, however it names itself, which aggregates every instance
+    /// of that program.
+    Executable,
+    /// The container or systemd unit of each process.
+    #[cfg(target_os = "linux")]
+    Container,
+}
+
 type ProcessTable = SortDataTable<ProcWidgetData, ProcColumn>;
 type SortTable = DataTable<Cow<'static, str>, SortTableColumn>;
 type StringPidMap = FxHashMap<String, Vec<Pid>>;
@@ -75,6 +136,9 @@ type StringPidMap = FxHashMap<String, Vec<Pid>>;
 pub struct ProcWidgetState {
     pub mode: ProcWidgetMode,
 
+    /// What grouped mode puts processes together by.
+    pub group_by: GroupBy,
+
     /// The state of the search box.
     pub proc_search: ProcessSearchState,
 
@@ -87,23 +151,65 @@ pub struct ProcWidgetState {
     /// A name-to-pid mapping.
     pub id_pid_map: StringPidMap,
 
+    /// Which users' processes are shown.
+    pub user_filter: UserFilter,
+
+    /// The PIDs of processes that have been selected to be acted on together.
+    pub selected_pids: FxHashSet<Pid>,
+
+    /// The PIDs of processes that appeared in the latest harvest.
+    pub new_pids: FxHashSet<Pid>,
+
+    /// The PIDs of processes that disappeared in the latest harvest. These are still shown until
+    /// the next harvest.
+    pub dying_pids: FxHashSet<Pid>,
+
+    /// The PIDs from the last harvest, and when it was taken.
+    prev_harvest: Option<(Instant, FxHashSet<Pid>)>,
+
+    /// The file the table was last exported to, and when, so it can be shown in the title for a
+    /// little while.
+    export_notice: Option<(Instant, String)>,
+
     pub is_sort_open: bool,
     pub force_rerender: bool,
     pub force_update_data: bool,
 }
 
-impl ProcWidgetState {
-    pub const PID_OR_COUNT: usize = 0;
-    pub const PROC_NAME_OR_CMD: usize = 1;
-    pub const CPU: usize = 2;
-    pub const MEM: usize = 3;
-    pub const RPS: usize = 4;
-    pub const WPS: usize = 5;
-    pub const T_READ: usize = 6;
-    pub const T_WRITE: usize = 7;
-    pub const USER: usize = 8;
-    pub const STATE: usize = 9;
+/// How long the name of an exported file stays in the title.
+const EXPORT_NOTICE_DURATION: Duration = Duration::from_secs(5);
+
+/// The columns shown when `process_columns` isn't set, in order.
+pub const DEFAULT_PROCESS_COLUMNS: [ProcColumn; 10] = [
+    ProcColumn::Pid,
+    ProcColumn::Name,
+    ProcColumn::CpuPercent,
+    ProcColumn::MemoryPercent,
+    ProcColumn::ReadPerSecond,
+    ProcColumn::WritePerSecond,
+    ProcColumn::TotalRead,
+    ProcColumn::TotalWrite,
+    ProcColumn::User,
+    ProcColumn::State,
+];
+
+fn is_pid_or_count(column: &ProcColumn) -> bool {
+    matches!(column, ProcColumn::Pid | ProcColumn::Count)
+}
 
+fn is_name_or_command(column: &ProcColumn) -> bool {
+    matches!(column, ProcColumn::Name | ProcColumn::Command)
+}
+
+fn is_cpu(column: &ProcColumn) -> bool {
+    matches!(column, ProcColumn::CpuPercent)
+}
+
+fn is_mem(column: &ProcColumn) -> bool {
+    matches!(column, ProcColumn::MemoryVal | ProcColumn::MemoryPercent)
+}
+
+impl ProcWidgetState {
     fn new_sort_table(config: &AppConfigFields, colours: &CanvasColours) -> SortTable {
         const COLUMNS: [Column<SortTableColumn>; 1] = [Column::hard(SortTableColumn, 7)];
 
@@ -113,6 +219,7 @@ impl ProcWidgetState {
             left_to_right: true,
             is_basic: false,
             show_table_scroll_position: false,
+            show_scrollbar: false,
             show_current_entry_when_unfocused: false,
         };
 
@@ -125,42 +232,71 @@ impl ProcWidgetState {
         config: &AppConfigFields, colours: &CanvasColours, mode: &ProcWidgetMode, is_count: bool,
         is_command: bool, show_memory_as_values: bool,
     ) -> ProcessTable {
-        let (default_index, default_order) = if matches!(mode, ProcWidgetMode::Tree { .. }) {
-            (Self::PID_OR_COUNT, SortOrder::Ascending)
-        } else {
-            (Self::CPU, SortOrder::Descending)
-        };
-
         let columns = {
             use ProcColumn::*;
 
-            let pid_or_count = SortColumn::new(if is_count { Count } else { Pid });
-            let name_or_cmd = SortColumn::soft(if is_command { Command } else { Name }, Some(0.3));
-            let cpu = SortColumn::new(CpuPercent).default_descending();
-            let mem = SortColumn::new(if show_memory_as_values {
-                MemoryVal
+            let configured_columns = config
+                .process_columns
+                .iter()
+                .filter_map(|name| ProcColumn::from_config_name(name))
+                .collect::<Vec<_>>();
+            let column_types = if configured_columns.is_empty() {
+                DEFAULT_PROCESS_COLUMNS.to_vec()
             } else {
-                MemoryPercent
-            })
-            .default_descending();
-            let rps = SortColumn::hard(ReadPerSecond, 8).default_descending();
-            let wps = SortColumn::hard(WritePerSecond, 8).default_descending();
-            let tr = SortColumn::hard(TotalRead, 8).default_descending();
-            let tw = SortColumn::hard(TotalWrite, 8).default_descending();
-            let state = SortColumn::hard(State, 7);
+                configured_columns
+            };
+
+            column_types
+                .into_iter()
+                .map(|column| match column {
+                    Pid | Count => SortColumn::new(if is_count { Count } else { Pid }),
+                    Name | Command => SortColumn::soft(
+                        if is_command || column == Command {
+                            Command
+                        } else {
+                            Name
+                        },
+                        Some(0.3),
+                    ),
+                    CpuPercent => SortColumn::new(CpuPercent).default_descending(),
+                    MemoryVal | MemoryPercent => {
+                        SortColumn::new(if show_memory_as_values || column == MemoryVal {
+                            MemoryVal
+                        } else {
+                            MemoryPercent
+                        })
+                        .default_descending()
+                    }
+                    ReadPerSecond | WritePerSecond | TotalRead | TotalWrite => {
+                        SortColumn::hard(column, 8).default_descending()
+                    }
+                    User => SortColumn::soft(User, Some(0.05)),
+                    State => SortColumn::hard(State, 7),
+                    #[cfg(target_family = "unix")]
+                    Nice => SortColumn::hard(Nice, 5),
+                    CpuHistory | MemHistory => SortColumn::hard(column, 12).default_descending(),
+                    #[cfg(target_os = "linux")]
+                    IoPriority => SortColumn::hard(IoPriority, 6),
+                    #[cfg(target_os = "linux")]
+                    Container => SortColumn::soft(Container, Some(0.2)),
+                    #[cfg(feature = "gpu")]
+                    GpuPercent => SortColumn::hard(GpuPercent, 6).default_descending(),
+                    #[cfg(feature = "gpu")]
+                    GpuMem => SortColumn::hard(GpuMem, 8).default_descending(),
+                })
+                .collect::<Vec<_>>()
+        };
 
-            vec![
-                pid_or_count,
-                name_or_cmd,
-                cpu,
-                mem,
-                rps,
-                wps,
-                tr,
-                tw,
-                SortColumn::soft(User, Some(0.05)),
-                state,
-            ]
+        let find_column = |is_column: fn(&ProcColumn) -> bool| {
+            columns
+                .iter()
+                .position(|column| is_column(column.inner()))
+                .unwrap_or(0)
+        };
+        let (default_index, default_order) = if matches!(mode, ProcWidgetMode::Tree { .. }) {
+            (find_column(is_pid_or_count), SortOrder::Ascending)
+        } else {
+            (find_column(is_cpu), SortOrder::Descending)
         };
 
         let inner_props = DataTableProps {
@@ -169,6 +305,7 @@ impl ProcWidgetState {
             left_to_right: true,
             is_basic: config.use_basic_mode,
             show_table_scroll_position: config.show_table_scroll_position,
+            show_scrollbar: config.show_scrollbar,
             show_current_entry_when_unfocused: false,
         };
         let props = SortDataTableProps {
@@ -222,8 +359,15 @@ impl ProcWidgetState {
             table,
             sort_table,
             id_pid_map,
+            user_filter: UserFilter::default(),
+            selected_pids: FxHashSet::default(),
+            new_pids: FxHashSet::default(),
+            dying_pids: FxHashSet::default(),
+            prev_harvest: None,
+            export_notice: None,
             is_sort_open: false,
             mode,
+            group_by: GroupBy::default(),
             force_rerender: true,
             force_update_data: false,
         };
@@ -232,20 +376,26 @@ impl ProcWidgetState {
         table
     }
 
+    /// Returns the index of the first column that `is_column` matches, if any.
+    fn column_index(&self, is_column: fn(&ProcColumn) -> bool) -> Option<usize> {
+        self.table
+            .columns
+            .iter()
+            .position(|col| is_column(col.inner()))
+    }
+
     pub fn is_using_command(&self) -> bool {
         self.table
             .columns
-            .get(ProcWidgetState::PROC_NAME_OR_CMD)
-            .map(|col| matches!(col.inner(), ProcColumn::Command))
-            .unwrap_or(false)
+            .iter()
+            .any(|col| matches!(col.inner(), ProcColumn::Command))
     }
 
     pub fn is_mem_percent(&self) -> bool {
         self.table
             .columns
-            .get(ProcWidgetState::MEM)
-            .map(|col| matches!(col.inner(), ProcColumn::MemoryPercent))
-            .unwrap_or(false)
+            .iter()
+            .any(|col| matches!(col.inner(), ProcColumn::MemoryPercent))
     }
 
     fn get_query(&self) -> &Option<Query> {
@@ -259,27 +409,205 @@ impl ProcWidgetState {
     /// This function *only* updates the displayed process data. If there is a need to update the actual *stored* data,
     /// call it before this function.
     pub fn ingest_data(&mut self, data_collection: &DataCollection) {
-        let data = match &self.mode {
+        let process_harvest = &data_collection.process_data.process_harvest;
+
+        // This is also called when just re-sorting or filtering, which shouldn't count as new
+        // processes appearing or old ones dying.
+        let is_new_harvest = self
+            .prev_harvest
+            .as_ref()
+            .map(|(instant, _)| *instant != data_collection.current_instant)
+            .unwrap_or(true);
+        if is_new_harvest {
+            let pids: FxHashSet<Pid> = process_harvest.keys().copied().collect();
+            if let Some((_, prev_pids)) = &self.prev_harvest {
+                (self.new_pids, self.dying_pids) = diff_pids(prev_pids, &pids);
+            }
+            self.prev_harvest = Some((data_collection.current_instant, pids));
+        }
+
+        let mut data = match &self.mode {
             ProcWidgetMode::Grouped | ProcWidgetMode::Normal => {
-                self.get_normal_data(&data_collection.process_data.process_harvest)
+                self.get_normal_data(&data_collection.process_data)
             }
             ProcWidgetMode::Tree { collapsed_pids } => {
                 self.get_tree_data(collapsed_pids, data_collection)
             }
         };
+
+        // Keep showing dead processes for one more harvest. This is skipped for grouped and tree
+        // modes, where a dead process can't just be slotted back in.
+        if let ProcWidgetMode::Normal = self.mode {
+            if !self.dying_pids.is_empty() {
+                data.extend(
+                    self.table
+                        .data()
+                        .iter()
+                        .filter(|row| self.dying_pids.contains(&row.pid))
+                        .cloned(),
+                );
+
+                // Sorting expects the data to be ordered by PID first.
+                data.sort_by_key(|row| row.pid);
+                if let Some(column) = self.table.columns.get(self.table.sort_index()) {
+                    sort_skip_pid_asc(column.inner(), &mut data, self.table.order());
+                }
+            }
+        }
+
+        // Drop any selected processes that no longer exist.
+        self.selected_pids
+            .retain(|pid| process_harvest.contains_key(pid));
+        self.update_selection_title();
+
+        let data = data
+            .into_iter()
+            .map(|row| {
+                let is_selected = self.selected_pids.contains(&row.pid);
+                let lifetime = if matches!(self.mode, ProcWidgetMode::Grouped) {
+                    ProcessLifetime::Running
+                } else if self.new_pids.contains(&row.pid) {
+                    ProcessLifetime::New
+                } else if self.dying_pids.contains(&row.pid) {
+                    ProcessLifetime::Dying
+                } else {
+                    ProcessLifetime::Running
+                };
+
+                row.selected(is_selected).lifetime(lifetime)
+            })
+            .collect();
         self.table.set_data(data);
     }
 
+    /// Returns the PIDs represented by a row, which is more than one if processes are grouped.
+    fn row_pids(&self, row: &ProcWidgetData) -> Vec<Pid> {
+        match self.mode {
+            ProcWidgetMode::Grouped => self
+                .id_pid_map
+                .get(row.id.as_str())
+                .cloned()
+                .unwrap_or_else(|| vec![row.pid]),
+            ProcWidgetMode::Tree { .. } | ProcWidgetMode::Normal => vec![row.pid],
+        }
+    }
+
+    /// Toggles whether the currently highlighted process is selected.
+    pub fn toggle_current_selection(&mut self) {
+        if let Some(row) = self.table.current_item() {
+            let pids = self.row_pids(row);
+
+            if pids.iter().all(|pid| self.selected_pids.contains(pid)) {
+                for pid in &pids {
+                    self.selected_pids.remove(pid);
+                }
+            } else {
+                self.selected_pids.extend(pids);
+            }
+            self.force_data_update();
+        }
+    }
+
+    /// Selects every process currently shown in the table, so any search filter is respected.
+    pub fn select_all(&mut self) {
+        let pids = self
+            .table
+            .data()
+            .iter()
+            .flat_map(|row| self.row_pids(row))
+            .collect::<Vec<_>>();
+
+        self.selected_pids.extend(pids);
+        self.force_data_update();
+    }
+
+    /// Clears all selected processes, returning whether there were any.
+    pub fn clear_selection(&mut self) -> bool {
+        if self.selected_pids.is_empty() {
+            false
+        } else {
+            self.selected_pids.clear();
+            self.force_data_update();
+            true
+        }
+    }
+
+    fn update_selection_title(&mut self) {
+        let mut title = String::from(" Processes ");
+        if let Some(user) = self.user_filter.user() {
+            title.push_str(&format!("({user}) "));
+        }
+        if !self.selected_pids.is_empty() {
+            title.push_str(&format!("[{} selected] ", self.selected_pids.len()));
+        }
+        if let Some((_, file_name)) = self
+            .export_notice
+            .as_ref()
+            .filter(|(instant, _)| instant.elapsed() < EXPORT_NOTICE_DURATION)
+        {
+            title.push_str(&format!("[saved {file_name}] "));
+        }
+
+        self.table.props.title = Some(title.into());
+    }
+
+    /// Shows the name of the file the table was just exported to in the title.
+    pub fn set_export_notice(&mut self, file_name: String) {
+        self.export_notice = Some((Instant::now(), file_name));
+        self.update_selection_title();
+    }
+
+    /// Sets which users' processes are shown, going back to the top of the table.
+    pub fn set_user_filter(&mut self, user_filter: UserFilter) {
+        self.user_filter = user_filter;
+        self.table.state.display_start_index = 0;
+        self.table.state.current_index = 0;
+        self.force_data_update();
+    }
+
+    /// Returns the users with processes in `process_data`, sorted by name.
+    pub fn get_users(process_data: &ProcessData) -> Vec<String> {
+        process_data
+            .process_harvest
+            .values()
+            .map(|process| process.user.to_string())
+            .sorted()
+            .dedup()
+            .collect()
+    }
+
+    /// Returns the chain of processes from PID 1 (or the furthest known ancestor) down to `pid`,
+    /// each formatted as `pid(name)`.
+    pub fn get_ancestry_chain(pid: Pid, data: &ProcessData) -> Vec<String> {
+        let mut chain = Vec::new();
+        let mut visited = FxHashSet::default();
+        let mut current = data.process_harvest.get(&pid);
+
+        while let Some(process) = current {
+            if !visited.insert(process.pid) {
+                break;
+            }
+
+            chain.push(format!("{}({})", process.pid, process.name));
+            if process.pid == 1 {
+                break;
+            }
+
+            current = process
+                .parent_pid
+                .and_then(|parent_pid| data.process_harvest.get(&parent_pid));
+        }
+
+        chain.reverse();
+        chain
+    }
+
     fn get_tree_data(
         &self, collapsed_pids: &FxHashSet<Pid>, data_collection: &DataCollection,
     ) -> Vec<ProcWidgetData> {
-        const BRANCH_END: char = '└';
-        const BRANCH_VERTICAL: char = '│';
-        const BRANCH_SPLIT: char = '├';
-        const BRANCH_HORIZONTAL: char = '─';
-
         let search_query = self.get_query();
         let is_using_command = self.is_using_command();
+        let is_searching_cmdline = self.proc_search.is_searching_cmdline;
         let is_mem_percent = self.is_mem_percent();
 
         let ProcessData {
@@ -289,16 +617,23 @@ impl ProcWidgetState {
             ..
         } = &data_collection.process_data;
 
+        let history = self.shown_history(&data_collection.process_data);
+        let to_row = |process: &ProcessHarvest| {
+            ProcWidgetData::from_data(process, is_using_command, is_mem_percent)
+                .history(history.and_then(|history| history.get(&process.pid)))
+        };
+
         // Only keep a set of the kept PIDs.
         let kept_pids = data_collection
             .process_data
             .process_harvest
             .iter()
             .filter_map(|(pid, process)| {
-                if search_query
-                    .as_ref()
-                    .map(|q| q.check(process, is_using_command))
-                    .unwrap_or(true)
+                if self.user_filter.matches(process)
+                    && search_query
+                        .as_ref()
+                        .map(|q| matches_query(q, process, is_using_command, is_searching_cmdline))
+                        .unwrap_or(true)
                 {
                     Some(*pid)
                 } else {
@@ -400,14 +735,12 @@ impl ProcWidgetState {
         };
 
         let mut data = vec![];
-        let mut prefixes = vec![];
+        let mut prefixes = TreePrefixes::default();
         let mut stack = orphan_pids
             .iter()
             .filter_map(|pid| {
                 if filtered_tree.contains_key(pid) {
-                    process_harvest.get(pid).map(|process| {
-                        ProcWidgetData::from_data(process, is_using_command, is_mem_percent)
-                    })
+                    process_harvest.get(pid).map(&to_row)
                 } else {
                     None
                 }
@@ -434,70 +767,34 @@ impl ProcWidgetState {
                 if let Some(children_pids) = filtered_tree.get(&process.pid) {
                     let mut sum_queue = children_pids
                         .iter()
-                        .filter_map(|child| {
-                            process_harvest.get(child).map(|p| {
-                                ProcWidgetData::from_data(p, is_using_command, is_mem_percent)
-                            })
-                        })
+                        .filter_map(|child| process_harvest.get(child).map(&to_row))
                         .collect_vec();
 
                     while let Some(process) = sum_queue.pop() {
                         summed_process.add(&process);
 
                         if let Some(pids) = filtered_tree.get(&process.pid) {
-                            sum_queue.extend(pids.iter().filter_map(|child| {
-                                process_harvest.get(child).map(|p| {
-                                    ProcWidgetData::from_data(p, is_using_command, is_mem_percent)
-                                })
-                            }));
+                            sum_queue.extend(
+                                pids.iter()
+                                    .filter_map(|child| process_harvest.get(child).map(&to_row)),
+                            );
                         }
                     }
                 }
 
-                let prefix = if prefixes.is_empty() {
-                    "+ ".to_string()
-                } else {
-                    format!(
-                        "{}{}{} + ",
-                        prefixes.join(""),
-                        if is_last { BRANCH_END } else { BRANCH_SPLIT },
-                        BRANCH_HORIZONTAL
-                    )
-                };
-
+                let prefix = prefixes.entry(is_last, true);
                 data.push(summed_process.prefix(Some(prefix)).disabled(disabled));
             } else {
-                let prefix = if prefixes.is_empty() {
-                    String::default()
-                } else {
-                    format!(
-                        "{}{}{} ",
-                        prefixes.join(""),
-                        if is_last { BRANCH_END } else { BRANCH_SPLIT },
-                        BRANCH_HORIZONTAL
-                    )
-                };
+                let prefix = prefixes.entry(is_last, false);
                 let pid = process.pid;
                 data.push(process.prefix(Some(prefix)).disabled(disabled));
 
                 if let Some(children_pids) = filtered_tree.get(&pid) {
-                    if prefixes.is_empty() {
-                        prefixes.push("");
-                    } else {
-                        prefixes.push(if is_last {
-                            "   "
-                        } else {
-                            formatcp!("{}  ", BRANCH_VERTICAL)
-                        });
-                    }
+                    prefixes.push(is_last);
 
                     let mut children = children_pids
                         .iter()
-                        .filter_map(|child_pid| {
-                            process_harvest.get(child_pid).map(|p| {
-                                ProcWidgetData::from_data(p, is_using_command, is_mem_percent)
-                            })
-                        })
+                        .filter_map(|child_pid| process_harvest.get(child_pid).map(&to_row))
                         .collect_vec();
 
                     column.sort_by(&mut children, self.table.order().rev());
@@ -520,35 +817,52 @@ impl ProcWidgetState {
         data
     }
 
-    fn get_normal_data(
-        &mut self, process_harvest: &BTreeMap<Pid, ProcessHarvest>,
-    ) -> Vec<ProcWidgetData> {
+    /// Returns the recent usage of each process if a history column is shown, as it's otherwise
+    /// not worth copying into every row.
+    fn shown_history<'a>(
+        &self, process_data: &'a ProcessData,
+    ) -> Option<&'a FxHashMap<Pid, ProcessHistory>> {
+        self.table
+            .columns
+            .iter()
+            .any(|column| {
+                matches!(
+                    column.inner(),
+                    ProcColumn::CpuHistory | ProcColumn::MemHistory
+                )
+            })
+            .then_some(&process_data.process_history)
+    }
+
+    fn get_normal_data(&mut self, process_data: &ProcessData) -> Vec<ProcWidgetData> {
+        let process_harvest = &process_data.process_harvest;
+        let history = self.shown_history(process_data);
         let search_query = self.get_query();
         let is_using_command = self.is_using_command();
+        let is_searching_cmdline = self.proc_search.is_searching_cmdline;
         let is_mem_percent = self.is_mem_percent();
 
         let filtered_iter = process_harvest.values().filter(|process| {
-            search_query
-                .as_ref()
-                .map(|query| query.check(process, is_using_command))
-                .unwrap_or(true)
+            self.user_filter.matches(process)
+                && search_query
+                    .as_ref()
+                    .map(|query| {
+                        matches_query(query, process, is_using_command, is_searching_cmdline)
+                    })
+                    .unwrap_or(true)
         });
 
         let mut id_pid_map: FxHashMap<String, Vec<Pid>> = FxHashMap::default();
         let mut filtered_data: Vec<ProcWidgetData> = if let ProcWidgetMode::Grouped = self.mode {
-            let mut id_process_mapping: FxHashMap<&String, ProcessHarvest> = FxHashMap::default();
+            let mut id_process_mapping: FxHashMap<&str, ProcessHarvest> = FxHashMap::default();
             for process in filtered_iter {
-                let id = if is_using_command {
-                    &process.command
-                } else {
-                    &process.name
-                };
+                let id = self.group_id(process, is_using_command);
                 let pid = process.pid;
 
                 if let Some(entry) = id_pid_map.get_mut(id) {
                     entry.push(pid);
                 } else {
-                    id_pid_map.insert(id.clone(), vec![pid]);
+                    id_pid_map.insert(id.to_string(), vec![pid]);
                 }
 
                 if let Some(grouped_process_harvest) = id_process_mapping.get_mut(id) {
@@ -560,23 +874,31 @@ impl ProcWidgetState {
             }
 
             id_process_mapping
-                .values()
-                .map(|process| {
-                    let id = if is_using_command {
-                        &process.command
-                    } else {
-                        &process.name
-                    };
+                .iter()
+                .map(|(id, process)| {
+                    let num_similar = id_pid_map.get(*id).map(|val| val.len()).unwrap_or(1) as u64;
 
-                    let num_similar = id_pid_map.get(id).map(|val| val.len()).unwrap_or(1) as u64;
+                    let mut row =
+                        ProcWidgetData::from_data(process, is_using_command, is_mem_percent)
+                            .name(id.to_string())
+                            .num_similar(num_similar);
+                    if let (Some(history), Some(pids)) = (history, id_pid_map.get(*id)) {
+                        for pid in pids {
+                            if let Some(history) = history.get(pid) {
+                                row.add_history(history);
+                            }
+                        }
+                    }
 
-                    ProcWidgetData::from_data(process, is_using_command, is_mem_percent)
-                        .num_similar(num_similar)
+                    row
                 })
                 .collect()
         } else {
             filtered_iter
-                .map(|process| ProcWidgetData::from_data(process, is_using_command, is_mem_percent))
+                .map(|process| {
+                    ProcWidgetData::from_data(process, is_using_command, is_mem_percent)
+                        .history(history.and_then(|history| history.get(&process.pid)))
+                })
                 .collect()
         };
 
@@ -589,13 +911,25 @@ impl ProcWidgetState {
         filtered_data
     }
 
+    /// Returns what a process is grouped by in grouped mode.
+    fn group_id<'a>(&self, process: &'a ProcessHarvest, is_using_command: bool) -> &'a str {
+        match self.group_by {
+            GroupBy::Name if is_using_command => &process.command,
+            GroupBy::Name => &process.name,
+            GroupBy::Executable => executable_name(process),
+            #[cfg(target_os = "linux")]
+            GroupBy::Container => process.container.as_deref().unwrap_or("N/A"),
+        }
+    }
+
     #[inline(always)]
-    fn get_mut_proc_col(&mut self, index: usize) -> Option<&mut ProcColumn> {
+    fn get_mut_proc_col(&mut self, is_column: fn(&ProcColumn) -> bool) -> Option<&mut ProcColumn> {
+        let index = self.column_index(is_column)?;
         self.table.columns.get_mut(index).map(|col| col.inner_mut())
     }
 
     pub fn toggle_mem_percentage(&mut self) {
-        if let Some(mem) = self.get_mut_proc_col(Self::MEM) {
+        if let Some(mem) = self.get_mut_proc_col(is_mem) {
             match mem {
                 ProcColumn::MemoryVal => {
                     *mem = ProcColumn::MemoryPercent;
@@ -626,20 +960,31 @@ impl ProcWidgetState {
 
     /// Marks the selected column as hidden, and automatically resets the selected column to CPU
     /// and descending if that column was selected.
-    fn hide_column(&mut self, index: usize) {
-        if let Some(col) = self.table.columns.get_mut(index) {
-            col.is_hidden = true;
+    fn hide_column(&mut self, column: ProcColumn) {
+        let index = self
+            .table
+            .columns
+            .iter()
+            .position(|col| *col.inner() == column);
+        if let Some(index) = index {
+            self.table.columns[index].is_hidden = true;
 
             if self.table.sort_index() == index {
-                self.table.set_sort_index(Self::CPU);
+                self.table
+                    .set_sort_index(self.column_index(is_cpu).unwrap_or_default());
                 self.table.set_order(SortOrder::Descending);
             }
         }
     }
 
     /// Marks the selected column as shown.
-    fn show_column(&mut self, index: usize) {
-        if let Some(col) = self.table.columns.get_mut(index) {
+    fn show_column(&mut self, column: ProcColumn) {
+        if let Some(col) = self
+            .table
+            .columns
+            .iter_mut()
+            .find(|col| *col.inner() == column)
+        {
             col.is_hidden = false;
         }
     }
@@ -650,6 +995,30 @@ impl ProcWidgetState {
         self.force_data_update();
     }
 
+    pub fn select_pid_or_count_column(&mut self) {
+        if let Some(index) = self.column_index(is_pid_or_count) {
+            self.select_column(index);
+        }
+    }
+
+    pub fn select_name_or_command_column(&mut self) {
+        if let Some(index) = self.column_index(is_name_or_command) {
+            self.select_column(index);
+        }
+    }
+
+    pub fn select_cpu_column(&mut self) {
+        if let Some(index) = self.column_index(is_cpu) {
+            self.select_column(index);
+        }
+    }
+
+    pub fn select_mem_column(&mut self) {
+        if let Some(index) = self.column_index(is_mem) {
+            self.select_column(index);
+        }
+    }
+
     pub fn toggle_current_tree_branch_entry(&mut self) {
         if let ProcWidgetMode::Tree { collapsed_pids } = &mut self.mode {
             if let Some(process) = self.table.current_item() {
@@ -664,7 +1033,8 @@ impl ProcWidgetState {
     }
 
     pub fn toggle_command(&mut self) {
-        if let Some(col) = self.table.columns.get_mut(Self::PROC_NAME_OR_CMD) {
+        let index = self.column_index(is_name_or_command);
+        if let Some(col) = index.and_then(|index| self.table.columns.get_mut(index)) {
             let inner = col.inner_mut();
             match inner {
                 ProcColumn::Name => {
@@ -699,24 +1069,34 @@ impl ProcWidgetState {
     /// to [`ProcWidgetMode::Normal`].
     pub fn on_tab(&mut self) {
         if !matches!(self.mode, ProcWidgetMode::Tree { .. }) {
-            if let Some(sort_col) = self.table.columns.get_mut(Self::PID_OR_COUNT) {
+            let index = self.column_index(is_pid_or_count);
+            if let Some(sort_col) = index.and_then(|index| self.table.columns.get_mut(index)) {
                 let col = sort_col.inner_mut();
                 match col {
                     ProcColumn::Pid => {
                         *col = ProcColumn::Count;
                         sort_col.default_order = SortOrder::Descending;
 
-                        self.hide_column(Self::USER);
-                        self.hide_column(Self::STATE);
+                        self.hide_column(ProcColumn::User);
+                        self.hide_column(ProcColumn::State);
+                        #[cfg(target_family = "unix")]
+                        self.hide_column(ProcColumn::Nice);
+                        #[cfg(target_os = "linux")]
+                        self.hide_column(ProcColumn::IoPriority);
                         self.mode = ProcWidgetMode::Grouped;
                     }
                     ProcColumn::Count => {
                         *col = ProcColumn::Pid;
                         sort_col.default_order = SortOrder::Ascending;
 
-                        self.show_column(Self::USER);
-                        self.show_column(Self::STATE);
+                        self.show_column(ProcColumn::User);
+                        self.show_column(ProcColumn::State);
+                        #[cfg(target_family = "unix")]
+                        self.show_column(ProcColumn::Nice);
+                        #[cfg(target_os = "linux")]
+                        self.show_column(ProcColumn::IoPriority);
                         self.mode = ProcWidgetMode::Normal;
+                        self.group_by = GroupBy::Name;
                     }
                     _ => unreachable!(),
                 }
@@ -727,6 +1107,29 @@ impl ProcWidgetState {
         }
     }
 
+    /// Toggles grouping processes by `group_by`, like by container or by executable. This enters
+    /// grouped mode if it isn't on, switches to `group_by` if grouping by something else, and
+    /// leaves grouped mode if already grouping by `group_by`.
+    pub fn toggle_group_by(&mut self, group_by: GroupBy) {
+        match self.mode {
+            ProcWidgetMode::Normal => {
+                self.on_tab();
+                if let ProcWidgetMode::Grouped = self.mode {
+                    self.group_by = group_by;
+                }
+            }
+            ProcWidgetMode::Grouped => {
+                if self.group_by == group_by {
+                    self.on_tab();
+                } else {
+                    self.group_by = group_by;
+                    self.force_rerender_and_update();
+                }
+            }
+            ProcWidgetMode::Tree { .. } => {}
+        }
+    }
+
     pub fn column_text(&self) -> Vec<Cow<'static, str>> {
         self.table
             .columns
@@ -821,6 +1224,7 @@ impl ProcWidgetState {
             && self.proc_search.is_ignoring_case == other.proc_search.is_ignoring_case
             && self.proc_search.is_searching_whole_word == other.proc_search.is_searching_whole_word
             && self.proc_search.is_searching_with_regex == other.proc_search.is_searching_with_regex
+            && self.proc_search.is_searching_cmdline == other.proc_search.is_searching_cmdline
             && self
                 .table
                 .columns
@@ -836,6 +1240,32 @@ impl ProcWidgetState {
     }
 }
 
+/// Returns the name of the program a process runs, which is the file name of the first part of
+/// its command. Unlike the process name, this is the same for every instance of a program, even
+/// ones that rename themselves, like `nginx: worker process` or browser helper processes.
+/// Processes without a command, like kernel threads, fall back to their name.
+fn executable_name(process: &ProcessHarvest) -> &str {
+    // Windows paths can have spaces, and process names are already the executable there. Kernel
+    // threads have their name in brackets as their command.
+    if cfg!(target_os = "windows") || process.command.starts_with('[') {
+        return &process.name;
+    }
+
+    process
+        .command
+        .split_whitespace()
+        .next()
+        .map(|program| {
+            program
+                .rsplit('/')
+                .next()
+                .unwrap_or(program)
+                .trim_end_matches(':')
+        })
+        .filter(|program| !program.is_empty())
+        .unwrap_or(&process.name)
+}
+
 #[inline]
 fn sort_skip_pid_asc(column: &ProcColumn, data: &mut [ProcWidgetData], order: SortOrder) {
     let descending = matches!(order, SortOrder::Descending);
@@ -847,11 +1277,291 @@ fn sort_skip_pid_asc(column: &ProcColumn, data: &mut [ProcWidgetData], order: So
     }
 }
 
+/// Checks a process against a search query. If `is_searching_cmdline` is set, the process also
+/// matches if its full command line does.
+fn matches_query(
+    query: &Query, process: &ProcessHarvest, is_using_command: bool, is_searching_cmdline: bool,
+) -> bool {
+    query.check(process, is_using_command) || (is_searching_cmdline && query.check(process, true))
+}
+
+/// Returns the PIDs that appeared and disappeared going from `prev` to `current`.
+fn diff_pids(prev: &FxHashSet<Pid>, current: &FxHashSet<Pid>) -> (FxHashSet<Pid>, FxHashSet<Pid>) {
+    (
+        current.difference(prev).copied().collect(),
+        prev.difference(current).copied().collect(),
+    )
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::widgets::MemUsage;
 
+    #[test]
+    fn test_diff_pids() {
+        let prev = FxHashSet::from_iter([1, 2, 3]);
+        let current = FxHashSet::from_iter([2, 3, 4, 5]);
+
+        let (new_pids, dying_pids) = diff_pids(&prev, &current);
+        assert_eq!(new_pids, FxHashSet::from_iter([4, 5]));
+        assert_eq!(dying_pids, FxHashSet::from_iter([1]));
+    }
+
+    #[test]
+    fn test_configured_columns() {
+        let config = AppConfigFields {
+            process_columns: vec!["cpu%".into(), "PID".into(), "command".into()],
+            ..Default::default()
+        };
+        let state = ProcWidgetState::new(
+            &config,
+            ProcWidgetMode::Normal,
+            false,
+            false,
+            false,
+            true,
+            false,
+            &CanvasColours::default(),
+        );
+
+        assert_eq!(
+            state
+                .table
+                .columns
+                .iter()
+                .map(|col| *col.inner())
+                .collect::<Vec<_>>(),
+            vec![ProcColumn::CpuPercent, ProcColumn::Pid, ProcColumn::Command]
+        );
+        assert_eq!(state.table.sort_index(), 0);
+        assert!(state.is_using_command());
+        assert!(!state.is_mem_percent());
+    }
+
+    #[test]
+    fn test_matches_query_cmdline() {
+        let process = ProcessHarvest {
+            name: "python".to_string(),
+            command: "python my_script.py".to_string(),
+            ..Default::default()
+        };
+        let query = parse_query("my_script", false, true, false).unwrap();
+
+        assert!(!matches_query(&query, &process, false, false));
+        assert!(matches_query(&query, &process, false, true));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_container_grouping() {
+        let process = |pid: Pid, name: &str, container: Option<&str>| ProcessHarvest {
+            pid,
+            name: name.to_string(),
+            cpu_usage_percent: 1.0,
+            container: container.map(str::to_string),
+            ..Default::default()
+        };
+        let data = ProcessData {
+            process_harvest: BTreeMap::from_iter([
+                (1, process(1, "nginx", Some("docker:0123456789ab"))),
+                (2, process(2, "nginx", Some("docker:0123456789ab"))),
+                (3, process(3, "redis", Some("docker:ba9876543210"))),
+                (4, process(4, "kthreadd", None)),
+            ]),
+            ..Default::default()
+        };
+
+        let mut state = ProcWidgetState::new(
+            &AppConfigFields::default(),
+            ProcWidgetMode::Normal,
+            false,
+            false,
+            false,
+            false,
+            false,
+            &CanvasColours::default(),
+        );
+        state.toggle_group_by(GroupBy::Container);
+        assert_eq!(state.mode, ProcWidgetMode::Grouped);
+
+        let mut groups = state
+            .get_normal_data(&data)
+            .into_iter()
+            .map(|row| (row.id.to_string(), row.num_similar, row.cpu_usage_percent))
+            .collect::<Vec<_>>();
+        groups.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            groups,
+            vec![
+                ("N/A".to_string(), 1, 1.0),
+                ("docker:0123456789ab".to_string(), 2, 2.0),
+                ("docker:ba9876543210".to_string(), 1, 1.0),
+            ]
+        );
+        assert_eq!(state.id_pid_map["docker:0123456789ab"], vec![1, 2]);
+
+        state.on_tab();
+        assert_eq!(state.mode, ProcWidgetMode::Normal);
+        assert_eq!(state.group_by, GroupBy::Name);
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn test_executable_grouping() {
+        let process =
+            |pid: Pid, name: &str, command: &str, cpu_usage_percent: f64| ProcessHarvest {
+                pid,
+                name: name.to_string(),
+                command: command.to_string(),
+                cpu_usage_percent,
+                ..Default::default()
+            };
+        let data = ProcessData {
+            process_harvest: BTreeMap::from_iter([
+                (1, process(1, "nginx", "nginx: master process", 1.0)),
+                (2, process(2, "nginx", "nginx: worker process", 2.0)),
+                (
+                    3,
+                    process(
+                        3,
+                        "Web Content",
+                        "/usr/lib/firefox/firefox -contentproc",
+                        3.0,
+                    ),
+                ),
+                (4, process(4, "firefox", "/usr/lib/firefox/firefox", 4.0)),
+                (5, process(5, "kworker/0:1", "[kworker/0:1]", 0.0)),
+            ]),
+            ..Default::default()
+        };
+        assert_eq!(executable_name(&data.process_harvest[&2]), "nginx");
+        assert_eq!(executable_name(&data.process_harvest[&3]), "firefox");
+        assert_eq!(executable_name(&data.process_harvest[&5]), "kworker/0:1");
+        assert_eq!(
+            executable_name(&ProcessHarvest {
+                name: "init".to_string(),
+                ..Default::default()
+            }),
+            "init"
+        );
+
+        let mut state = ProcWidgetState::new(
+            &AppConfigFields::default(),
+            ProcWidgetMode::Normal,
+            false,
+            false,
+            false,
+            false,
+            false,
+            &CanvasColours::default(),
+        );
+        state.toggle_group_by(GroupBy::Executable);
+        assert_eq!(state.mode, ProcWidgetMode::Grouped);
+
+        let mut groups = state
+            .get_normal_data(&data)
+            .into_iter()
+            .map(|row| (row.id.to_string(), row.num_similar, row.cpu_usage_percent))
+            .collect::<Vec<_>>();
+        groups.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            groups,
+            vec![
+                ("firefox".to_string(), 2, 7.0),
+                ("kworker/0:1".to_string(), 1, 0.0),
+                ("nginx".to_string(), 2, 3.0),
+            ]
+        );
+
+        // Grouping by something else switches over, and toggling the same again leaves.
+        state.toggle_group_by(GroupBy::Name);
+        assert_eq!(state.group_by, GroupBy::Name);
+        state.toggle_group_by(GroupBy::Name);
+        assert_eq!(state.mode, ProcWidgetMode::Normal);
+    }
+
+    #[test]
+    fn test_user_filter() {
+        let process = |pid: Pid, user: &'static str| ProcessHarvest {
+            pid,
+            user: user.into(),
+            ..Default::default()
+        };
+        let data = ProcessData {
+            process_harvest: BTreeMap::from_iter([
+                (1, process(1, "root")),
+                (2, process(2, "alice")),
+                (3, process(3, "bob")),
+                (4, process(4, "alice")),
+            ]),
+            ..Default::default()
+        };
+        assert_eq!(ProcWidgetState::get_users(&data), ["alice", "bob", "root"]);
+
+        let mut state = ProcWidgetState::new(
+            &AppConfigFields::default(),
+            ProcWidgetMode::Normal,
+            false,
+            false,
+            false,
+            false,
+            false,
+            &CanvasColours::default(),
+        );
+        let shown_pids = |state: &mut ProcWidgetState| {
+            let mut pids = state
+                .get_normal_data(&data)
+                .iter()
+                .map(|row| row.pid)
+                .collect::<Vec<_>>();
+            pids.sort_unstable();
+            pids
+        };
+
+        state.set_user_filter(UserFilter::User("alice".to_string()));
+        assert_eq!(shown_pids(&mut state), [2, 4]);
+
+        // The user filter applies before any search.
+        state.proc_search.search_state.current_search_query = "pid = 1".to_string();
+        state.update_query();
+        assert!(shown_pids(&mut state).is_empty());
+
+        state.clear_search();
+        state.set_user_filter(UserFilter::All);
+        assert_eq!(shown_pids(&mut state), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_ancestry_chain() {
+        let process = |pid: Pid, parent_pid: Option<Pid>, name: &str| ProcessHarvest {
+            pid,
+            parent_pid,
+            name: name.to_string(),
+            ..Default::default()
+        };
+
+        let data = ProcessData {
+            process_harvest: BTreeMap::from_iter([
+                (1, process(1, Some(0), "init")),
+                (20, process(20, Some(1), "sshd")),
+                (300, process(300, Some(20), "bash")),
+                (400, process(400, Some(999), "orphan")),
+            ]),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            ProcWidgetState::get_ancestry_chain(300, &data),
+            vec!["1(init)", "20(sshd)", "300(bash)"]
+        );
+        assert_eq!(
+            ProcWidgetState::get_ancestry_chain(400, &data),
+            vec!["400(orphan)"]
+        );
+        assert!(ProcWidgetState::get_ancestry_chain(5, &data).is_empty());
+    }
+
     #[test]
     fn test_proc_sort() {
         let a = ProcWidgetData {
@@ -870,8 +1580,24 @@ mod test {
             user: "root".to_string(),
             #[cfg(not(target_family = "unix"))]
             user: "N/A".to_string(),
+            #[cfg(target_family = "unix")]
+            nice: Some(0),
+            #[cfg(target_os = "linux")]
+            io_class: None,
+            #[cfg(target_os = "linux")]
+            io_priority: None,
+            #[cfg(target_os = "linux")]
+            container: None,
+            cpu_history: Vec::new(),
+            mem_history: Vec::new(),
+            #[cfg(feature = "gpu")]
+            gpu_usage_percent: 0.0,
+            #[cfg(feature = "gpu")]
+            gpu_mem: 0,
             num_similar: 0,
             disabled: false,
+            selected: false,
+            lifetime: ProcessLifetime::Running,
         };
 
         let b = ProcWidgetData {
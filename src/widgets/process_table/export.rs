@@ -0,0 +1,164 @@
+//! Writing out what the process table currently shows, for attaching to bug reports or incident
+//! tickets.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use super::{ProcColumn, ProcWidgetData, ProcWidgetState};
+use crate::{
+    components::data_table::{ColumnHeader, DataTableColumn},
+    utils::json,
+};
+
+/// The formats the process table can be exported in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+impl ProcWidgetState {
+    /// Returns the rows the table currently shows in `format`, in the same order and with the same
+    /// columns, so it respects the search, user filter, sorting and grouping.
+    pub fn export(&self, format: ExportFormat) -> String {
+        let columns = self
+            .table
+            .columns
+            .iter()
+            .filter(|column| !column.is_hidden)
+            .map(|column| *column.inner())
+            .collect::<Vec<_>>();
+
+        match format {
+            ExportFormat::Csv => to_csv(&columns, self.table.data()),
+            ExportFormat::Json => to_json(&columns, self.table.data()),
+        }
+    }
+}
+
+/// Writes an export into `dir`, named after the current time, and returns its path.
+pub fn write_export(dir: &Path, format: ExportFormat, contents: &str) -> io::Result<PathBuf> {
+    let timestamp = time::OffsetDateTime::now_utc()
+        .format(&time::macros::format_description!(
+            "[year][month][day]-[hour][minute][second]"
+        ))
+        .map_err(io::Error::other)?;
+    let path = dir.join(format!("btm-processes-{timestamp}.{}", format.extension()));
+    std::fs::write(&path, contents)?;
+
+    Ok(path)
+}
+
+fn to_csv(columns: &[ProcColumn], rows: &[ProcWidgetData]) -> String {
+    let mut csv = String::new();
+    let mut push_line = |fields: Vec<String>| {
+        let line = fields
+            .iter()
+            .map(|field| csv_field(field))
+            .collect::<Vec<_>>();
+        csv.push_str(&line.join(","));
+        csv.push_str("\r\n");
+    };
+
+    push_line(
+        columns
+            .iter()
+            .map(|column| column.text().into_owned())
+            .collect(),
+    );
+    for row in rows {
+        push_line(columns.iter().map(|column| row.to_string(column)).collect());
+    }
+
+    csv
+}
+
+/// Quotes a CSV field if it has anything in it that would otherwise break the line up.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Writes the rows as an array of objects keyed by column header, with every value a string as
+/// it is shown in the table.
+fn to_json(columns: &[ProcColumn], rows: &[ProcWidgetData]) -> String {
+    let mut output = String::from("[");
+    for (row_index, row) in rows.iter().enumerate() {
+        if row_index > 0 {
+            output.push(',');
+        }
+        output.push('{');
+        for (index, column) in columns.iter().enumerate() {
+            if index > 0 {
+                output.push(',');
+            }
+            // Writing to a string can't fail.
+            let _ = json::write_string(&mut output, &column.text());
+            output.push(':');
+            let _ = json::write_string(&mut output, &row.to_string(column));
+        }
+        output.push('}');
+    }
+    output.push_str("]\n");
+
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{app::data_harvester::processes::ProcessHarvest, utils::json::JsonValue};
+
+    fn rows() -> Vec<ProcWidgetData> {
+        let process = |pid, name: &str| ProcessHarvest {
+            pid,
+            name: name.to_string(),
+            cpu_usage_percent: 12.5,
+            ..Default::default()
+        };
+
+        [process(1, "init"), process(42, "say \"hi\", bye")]
+            .iter()
+            .map(|process| ProcWidgetData::from_data(process, false, true))
+            .collect()
+    }
+
+    #[test]
+    fn test_csv_export() {
+        let columns = [ProcColumn::Pid, ProcColumn::Name, ProcColumn::CpuPercent];
+
+        assert_eq!(
+            to_csv(&columns, &rows()),
+            "PID,Name,CPU%\r\n1,init,12.5%\r\n42,\"say \"\"hi\"\", bye\",12.5%\r\n"
+        );
+    }
+
+    #[test]
+    fn test_json_export() {
+        let columns = [ProcColumn::Pid, ProcColumn::Name];
+        let output = JsonValue::parse(&to_json(&columns, &rows())).unwrap();
+        let rows = output.as_array().unwrap();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].get("PID").and_then(JsonValue::as_str), Some("1"));
+        assert_eq!(
+            rows[1].get("Name").and_then(JsonValue::as_str),
+            Some("say \"hi\", bye")
+        );
+        assert_eq!(to_json(&columns, &[]), "[]\n");
+    }
+}
@@ -21,6 +21,52 @@ pub enum ProcColumn {
     TotalWrite,
     State,
     User,
+    #[cfg(target_family = "unix")]
+    Nice,
+    #[cfg(target_os = "linux")]
+    IoPriority,
+    #[cfg(target_os = "linux")]
+    Container,
+    CpuHistory,
+    MemHistory,
+    #[cfg(feature = "gpu")]
+    GpuPercent,
+    #[cfg(feature = "gpu")]
+    GpuMem,
+}
+
+impl ProcColumn {
+    /// Parses a column name from the `process_columns` config option, ignoring case. Columns
+    /// that can be toggled, like PID and count, are named after their default.
+    pub fn from_config_name(name: &str) -> Option<ProcColumn> {
+        Some(match name.to_lowercase().as_str() {
+            "pid" => ProcColumn::Pid,
+            "name" => ProcColumn::Name,
+            "command" | "cmd" => ProcColumn::Command,
+            "cpu%" | "cpu" => ProcColumn::CpuPercent,
+            "mem%" => ProcColumn::MemoryPercent,
+            "mem" => ProcColumn::MemoryVal,
+            "r/s" | "read" | "rps" => ProcColumn::ReadPerSecond,
+            "w/s" | "write" | "wps" => ProcColumn::WritePerSecond,
+            "t.read" | "tread" => ProcColumn::TotalRead,
+            "t.write" | "twrite" => ProcColumn::TotalWrite,
+            "state" => ProcColumn::State,
+            "user" => ProcColumn::User,
+            #[cfg(target_family = "unix")]
+            "nice" | "ni" => ProcColumn::Nice,
+            #[cfg(target_os = "linux")]
+            "ioprio" | "io_priority" | "ionice" => ProcColumn::IoPriority,
+            #[cfg(target_os = "linux")]
+            "container" | "cgroup" | "unit" => ProcColumn::Container,
+            "cpu_history" | "cpu_hist" => ProcColumn::CpuHistory,
+            "mem_history" | "mem_hist" => ProcColumn::MemHistory,
+            #[cfg(feature = "gpu")]
+            "gpu%" | "gpu" => ProcColumn::GpuPercent,
+            #[cfg(feature = "gpu")]
+            "gmem" | "gpu_mem" => ProcColumn::GpuMem,
+            _ => return None,
+        })
+    }
 }
 
 impl ColumnHeader for ProcColumn {
@@ -39,6 +85,18 @@ impl ColumnHeader for ProcColumn {
             ProcColumn::TotalWrite => "T.Write",
             ProcColumn::State => "State",
             ProcColumn::User => "User",
+            #[cfg(target_family = "unix")]
+            ProcColumn::Nice => "Nice",
+            #[cfg(target_os = "linux")]
+            ProcColumn::IoPriority => "IOPrio",
+            #[cfg(target_os = "linux")]
+            ProcColumn::Container => "Container",
+            ProcColumn::CpuHistory => "CPU Hist",
+            ProcColumn::MemHistory => "Mem Hist",
+            #[cfg(feature = "gpu")]
+            ProcColumn::GpuPercent => "GPU%",
+            #[cfg(feature = "gpu")]
+            ProcColumn::GpuMem => "GMem",
         }
         .into()
     }
@@ -58,11 +116,31 @@ impl ColumnHeader for ProcColumn {
             ProcColumn::TotalWrite => "T.Write",
             ProcColumn::State => "State",
             ProcColumn::User => "User",
+            #[cfg(target_family = "unix")]
+            ProcColumn::Nice => "Nice",
+            #[cfg(target_os = "linux")]
+            ProcColumn::IoPriority => "IOPrio",
+            #[cfg(target_os = "linux")]
+            ProcColumn::Container => "Container",
+            ProcColumn::CpuHistory => "CPU Hist",
+            ProcColumn::MemHistory => "Mem Hist",
+            #[cfg(feature = "gpu")]
+            ProcColumn::GpuPercent => "GPU%",
+            #[cfg(feature = "gpu")]
+            ProcColumn::GpuMem => "GMem",
         }
         .into()
     }
 }
 
+fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        0.0
+    } else {
+        values.iter().sum::<f64>() / values.len() as f64
+    }
+}
+
 impl SortsRow for ProcColumn {
     type DataType = ProcWidgetData;
 
@@ -115,6 +193,46 @@ impl SortsRow for ProcColumn {
                     data.sort_by_cached_key(|pd| pd.user.to_lowercase());
                 }
             }
+            #[cfg(target_family = "unix")]
+            ProcColumn::Nice => {
+                data.sort_by(|a, b| sort_partial_fn(descending)(a.nice, b.nice));
+            }
+            #[cfg(target_os = "linux")]
+            ProcColumn::IoPriority => {
+                data.sort_by(|a, b| {
+                    sort_partial_fn(descending)(
+                        (a.io_class, a.io_priority),
+                        (b.io_class, b.io_priority),
+                    )
+                });
+            }
+            #[cfg(target_os = "linux")]
+            ProcColumn::Container => {
+                data.sort_by(|a, b| {
+                    sort_partial_fn(descending)(a.container.as_deref(), b.container.as_deref())
+                });
+            }
+            // Sorting by the average puts the busiest processes over the window first.
+            ProcColumn::CpuHistory => {
+                data.sort_by(|a, b| {
+                    sort_partial_fn(descending)(mean(&a.cpu_history), mean(&b.cpu_history))
+                });
+            }
+            ProcColumn::MemHistory => {
+                data.sort_by(|a, b| {
+                    sort_partial_fn(descending)(mean(&a.mem_history), mean(&b.mem_history))
+                });
+            }
+            #[cfg(feature = "gpu")]
+            ProcColumn::GpuPercent => {
+                data.sort_by(|a, b| {
+                    sort_partial_fn(descending)(a.gpu_usage_percent, b.gpu_usage_percent)
+                });
+            }
+            #[cfg(feature = "gpu")]
+            ProcColumn::GpuMem => {
+                data.sort_by(|a, b| sort_partial_fn(descending)(a.gpu_mem, b.gpu_mem));
+            }
         }
     }
 }
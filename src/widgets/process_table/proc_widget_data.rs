@@ -4,11 +4,20 @@ use std::{
 };
 
 use concat_string::concat_string;
-use tui::{text::Text, widgets::Row};
+use tui::{
+    style::{Color, Modifier, Style},
+    text::Text,
+    widgets::Row,
+};
 
 use super::proc_widget_column::ProcColumn;
+#[cfg(target_os = "linux")]
+use crate::app::data_harvester::processes::{io_priority_string, IOClass};
 use crate::{
-    app::data_harvester::processes::ProcessHarvest,
+    app::{
+        data_farmer::{ProcessHistory, PROCESS_HISTORY_LEN},
+        data_harvester::processes::ProcessHarvest,
+    },
     canvas::Painter,
     components::data_table::{DataTableColumn, DataToCell},
     data_conversion::{binary_byte_string, dec_bytes_per_second_string, dec_bytes_string},
@@ -107,6 +116,63 @@ impl Display for MemUsage {
     }
 }
 
+/// Formats a nice value, or `N/A` if it couldn't be read.
+#[cfg(target_family = "unix")]
+fn nice_string(nice: Option<i32>) -> String {
+    nice.map_or_else(|| "N/A".to_string(), |nice| nice.to_string())
+}
+
+/// Formats the container or unit of a process, or `N/A` for one in the root cgroup.
+#[cfg(target_os = "linux")]
+fn container_string(container: Option<&str>) -> String {
+    container.unwrap_or("N/A").to_string()
+}
+
+/// Adds `other` into `history`, lining the two up by their latest values, since a process that
+/// started more recently has a shorter history.
+fn sum_history<'a>(history: &mut Vec<f64>, other: impl ExactSizeIterator<Item = &'a f64>) {
+    if other.len() > history.len() {
+        history.splice(0..0, std::iter::repeat_n(0.0, other.len() - history.len()));
+    }
+
+    let offset = history.len() - other.len();
+    for (value, other) in history[offset..].iter_mut().zip(other) {
+        *value += other;
+    }
+}
+
+/// Draws the latest `width` values as a sparkline of block characters, scaled so that the
+/// highest of them fills a whole cell. A short history is padded on the left, so the latest
+/// value always lines up on the right.
+fn sparkline(values: &[f64], width: usize) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+    let values = &values[values.len().saturating_sub(width)..];
+    let max = values.iter().copied().fold(0.0, f64::max);
+    let bars = values
+        .iter()
+        .map(|value| {
+            if max > 0.0 {
+                let level = (value / max * (BARS.len() - 1) as f64).round() as usize;
+                BARS[level.min(BARS.len() - 1)]
+            } else {
+                BARS[0]
+            }
+        })
+        .collect::<String>();
+
+    format!("{bars:>width$}")
+}
+
+/// Whether a process has just appeared or disappeared between harvests.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ProcessLifetime {
+    #[default]
+    Running,
+    New,
+    Dying,
+}
+
 #[derive(Clone, Debug)]
 pub struct ProcWidgetData {
     pub pid: Pid,
@@ -121,8 +187,27 @@ pub struct ProcWidgetData {
     pub process_state: String,
     pub process_char: char,
     pub user: String,
+    #[cfg(target_family = "unix")]
+    pub nice: Option<i32>,
+    #[cfg(target_os = "linux")]
+    pub io_class: Option<IOClass>,
+    #[cfg(target_os = "linux")]
+    pub io_priority: Option<u8>,
+    #[cfg(target_os = "linux")]
+    pub container: Option<String>,
+    /// The recent CPU usage, oldest first, which is only filled in if a history column is shown.
+    pub cpu_history: Vec<f64>,
+    /// The recent memory usage as a percentage, oldest first, which is only filled in if a
+    /// history column is shown.
+    pub mem_history: Vec<f64>,
+    #[cfg(feature = "gpu")]
+    pub gpu_usage_percent: f64,
+    #[cfg(feature = "gpu")]
+    pub gpu_mem: u64,
     pub num_similar: u64,
     pub disabled: bool,
+    pub selected: bool,
+    pub lifetime: ProcessLifetime,
 }
 
 impl ProcWidgetData {
@@ -155,8 +240,24 @@ impl ProcWidgetData {
             process_state: process.process_state.0.clone(),
             process_char: process.process_state.1,
             user: process.user.to_string(),
+            #[cfg(target_family = "unix")]
+            nice: process.nice,
+            #[cfg(target_os = "linux")]
+            io_class: process.io_class,
+            #[cfg(target_os = "linux")]
+            io_priority: process.io_priority,
+            #[cfg(target_os = "linux")]
+            container: process.container.clone(),
+            cpu_history: Vec::new(),
+            mem_history: Vec::new(),
+            #[cfg(feature = "gpu")]
+            gpu_usage_percent: process.gpu_usage_percent,
+            #[cfg(feature = "gpu")]
+            gpu_mem: process.gpu_mem_bytes,
             num_similar: 1,
             disabled: false,
+            selected: false,
+            lifetime: ProcessLifetime::Running,
         }
     }
 
@@ -170,11 +271,41 @@ impl ProcWidgetData {
         self
     }
 
+    pub fn selected(mut self, selected: bool) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    pub fn lifetime(mut self, lifetime: ProcessLifetime) -> Self {
+        self.lifetime = lifetime;
+        self
+    }
+
+    /// Replaces the name shown for the process, like with the name a group of processes shares.
+    pub fn name(mut self, name: String) -> Self {
+        self.id.id_type = IdType::Name(name);
+        self
+    }
+
     pub fn prefix(mut self, prefix: Option<String>) -> Self {
         self.id.prefix = prefix;
         self
     }
 
+    pub fn history(mut self, history: Option<&ProcessHistory>) -> Self {
+        if let Some(history) = history {
+            self.cpu_history = history.cpu.iter().copied().collect();
+            self.mem_history = history.mem.iter().copied().collect();
+        }
+        self
+    }
+
+    /// Adds the history of another process into this one's, like for a group of processes.
+    pub fn add_history(&mut self, history: &ProcessHistory) {
+        sum_history(&mut self.cpu_history, history.cpu.iter());
+        sum_history(&mut self.mem_history, history.mem.iter());
+    }
+
     pub fn add(&mut self, other: &Self) {
         self.cpu_usage_percent += other.cpu_usage_percent;
         self.mem_usage = match (&self.mem_usage, &other.mem_usage) {
@@ -189,9 +320,21 @@ impl ProcWidgetData {
         self.wps += other.wps;
         self.total_read += other.total_read;
         self.total_write += other.total_write;
+        sum_history(&mut self.cpu_history, other.cpu_history.iter());
+        sum_history(&mut self.mem_history, other.mem_history.iter());
+        #[cfg(feature = "gpu")]
+        {
+            self.gpu_usage_percent += other.gpu_usage_percent;
+            self.gpu_mem += other.gpu_mem;
+        }
     }
 
-    fn to_string(&self, column: &ProcColumn) -> String {
+    /// Whether the process has been stopped, like with `SIGSTOP`.
+    pub fn is_stopped(&self) -> bool {
+        self.process_char == 'T'
+    }
+
+    pub(super) fn to_string(&self, column: &ProcColumn) -> String {
         match column {
             ProcColumn::CpuPercent => format!("{:.1}%", self.cpu_usage_percent),
             ProcColumn::MemoryVal | ProcColumn::MemoryPercent => self.mem_usage.to_string(),
@@ -202,8 +345,26 @@ impl ProcWidgetData {
             ProcColumn::WritePerSecond => dec_bytes_per_second_string(self.wps),
             ProcColumn::TotalRead => dec_bytes_string(self.total_read),
             ProcColumn::TotalWrite => dec_bytes_string(self.total_write),
-            ProcColumn::State => self.process_char.to_string(),
+            ProcColumn::State => {
+                if self.is_stopped() {
+                    self.process_state.clone()
+                } else {
+                    self.process_char.to_string()
+                }
+            }
             ProcColumn::User => self.user.clone(),
+            #[cfg(target_family = "unix")]
+            ProcColumn::Nice => nice_string(self.nice),
+            #[cfg(target_os = "linux")]
+            ProcColumn::IoPriority => io_priority_string(self.io_class, self.io_priority),
+            #[cfg(target_os = "linux")]
+            ProcColumn::Container => container_string(self.container.as_deref()),
+            ProcColumn::CpuHistory => sparkline(&self.cpu_history, PROCESS_HISTORY_LEN),
+            ProcColumn::MemHistory => sparkline(&self.mem_history, PROCESS_HISTORY_LEN),
+            #[cfg(feature = "gpu")]
+            ProcColumn::GpuPercent => format!("{:.1}%", self.gpu_usage_percent),
+            #[cfg(feature = "gpu")]
+            ProcColumn::GpuMem => binary_byte_string(self.gpu_mem),
         }
     }
 }
@@ -230,13 +391,30 @@ impl DataToCell<ProcColumn> for ProcWidgetData {
                 ProcColumn::TotalRead => dec_bytes_string(self.total_read),
                 ProcColumn::TotalWrite => dec_bytes_string(self.total_write),
                 ProcColumn::State => {
-                    if calculated_width < 8 {
+                    // Stopped processes are spelled out, so they aren't missed.
+                    if calculated_width < 8 && !self.is_stopped() {
                         self.process_char.to_string()
                     } else {
                         self.process_state.clone()
                     }
                 }
                 ProcColumn::User => self.user.clone(),
+                #[cfg(target_family = "unix")]
+                ProcColumn::Nice => nice_string(self.nice),
+                #[cfg(target_os = "linux")]
+                ProcColumn::IoPriority => io_priority_string(self.io_class, self.io_priority),
+                #[cfg(target_os = "linux")]
+                ProcColumn::Container => container_string(self.container.as_deref()),
+                ProcColumn::CpuHistory => {
+                    sparkline(&self.cpu_history, usize::from(calculated_width))
+                }
+                ProcColumn::MemHistory => {
+                    sparkline(&self.mem_history, usize::from(calculated_width))
+                }
+                #[cfg(feature = "gpu")]
+                ProcColumn::GpuPercent => format!("{:.1}%", self.gpu_usage_percent),
+                #[cfg(feature = "gpu")]
+                ProcColumn::GpuMem => binary_byte_string(self.gpu_mem),
             },
             calculated_width,
         ))
@@ -244,10 +422,23 @@ impl DataToCell<ProcColumn> for ProcWidgetData {
 
     #[inline(always)]
     fn style_row<'a>(&self, row: Row<'a>, painter: &Painter) -> Row<'a> {
-        if self.disabled {
-            row.style(painter.colours.disabled_text_style)
+        let style = if self.disabled {
+            painter.colours.disabled_text_style
         } else {
-            row
+            match self.lifetime {
+                ProcessLifetime::Dying => {
+                    Style::default().add_modifier(Modifier::DIM).fg(Color::Red)
+                }
+                _ if self.is_stopped() => Style::default().fg(Color::Yellow),
+                ProcessLifetime::Running => Style::default(),
+                ProcessLifetime::New => Style::default().fg(Color::Green),
+            }
+        };
+
+        if self.selected {
+            row.style(style.add_modifier(Modifier::BOLD))
+        } else {
+            row.style(style)
         }
     }
 
@@ -266,3 +457,28 @@ impl DataToCell<ProcColumn> for ProcWidgetData {
         widths
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sparkline() {
+        assert_eq!(sparkline(&[0.0, 50.0, 100.0], 3), "▁▅█");
+        assert_eq!(sparkline(&[0.0, 0.0], 2), "▁▁");
+
+        // Only the latest values are drawn, and a short history is padded on the left.
+        assert_eq!(sparkline(&[100.0, 10.0, 20.0], 2), "▅█");
+        assert_eq!(sparkline(&[5.0], 3), "  █");
+    }
+
+    #[test]
+    fn test_sum_history() {
+        let mut history = vec![1.0, 2.0];
+        sum_history(&mut history, [10.0, 20.0, 30.0].iter());
+        assert_eq!(history, [10.0, 21.0, 32.0]);
+
+        sum_history(&mut history, [1.0].iter());
+        assert_eq!(history, [10.0, 21.0, 33.0]);
+    }
+}
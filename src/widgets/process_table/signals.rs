@@ -0,0 +1,216 @@
+//! The signals that can be picked from the kill dialog, which differ between platforms.
+//!
+//! The picker lists cancelling first, followed by each signal in [`SIGNALS`], so a position in
+//! the picker is one more than the signal's index in the list.
+
+use crate::app::KillSignal;
+
+/// The number and name of each signal that can be sent, in order.
+#[cfg(target_os = "linux")]
+pub const SIGNALS: &[(usize, &str)] = &[
+    (1, "HUP"),
+    (2, "INT"),
+    (3, "QUIT"),
+    (4, "ILL"),
+    (5, "TRAP"),
+    (6, "ABRT"),
+    (7, "BUS"),
+    (8, "FPE"),
+    (9, "KILL"),
+    (10, "USR1"),
+    (11, "SEGV"),
+    (12, "USR2"),
+    (13, "PIPE"),
+    (14, "ALRM"),
+    (15, "TERM"),
+    (16, "STKFLT"),
+    (17, "CHLD"),
+    (18, "CONT"),
+    (19, "STOP"),
+    (20, "TSTP"),
+    (21, "TTIN"),
+    (22, "TTOU"),
+    (23, "URG"),
+    (24, "XCPU"),
+    (25, "XFSZ"),
+    (26, "VTALRM"),
+    (27, "PROF"),
+    (28, "WINCH"),
+    (29, "IO"),
+    (30, "PWR"),
+    (31, "SYS"),
+    // 32 and 33 are used internally by glibc's threads, so they're skipped.
+    (34, "RTMIN"),
+    (35, "RTMIN+1"),
+    (36, "RTMIN+2"),
+    (37, "RTMIN+3"),
+    (38, "RTMIN+4"),
+    (39, "RTMIN+5"),
+    (40, "RTMIN+6"),
+    (41, "RTMIN+7"),
+    (42, "RTMIN+8"),
+    (43, "RTMIN+9"),
+    (44, "RTMIN+10"),
+    (45, "RTMIN+11"),
+    (46, "RTMIN+12"),
+    (47, "RTMIN+13"),
+    (48, "RTMIN+14"),
+    (49, "RTMIN+15"),
+    (50, "RTMAX-14"),
+    (51, "RTMAX-13"),
+    (52, "RTMAX-12"),
+    (53, "RTMAX-11"),
+    (54, "RTMAX-10"),
+    (55, "RTMAX-9"),
+    (56, "RTMAX-8"),
+    (57, "RTMAX-7"),
+    (58, "RTMAX-6"),
+    (59, "RTMAX-5"),
+    (60, "RTMAX-4"),
+    (61, "RTMAX-3"),
+    (62, "RTMAX-2"),
+    (63, "RTMAX-1"),
+    (64, "RTMAX"),
+];
+
+/// The number and name of each signal that can be sent, in order.
+#[cfg(target_os = "macos")]
+pub const SIGNALS: &[(usize, &str)] = &[
+    (1, "HUP"),
+    (2, "INT"),
+    (3, "QUIT"),
+    (4, "ILL"),
+    (5, "TRAP"),
+    (6, "ABRT"),
+    (7, "EMT"),
+    (8, "FPE"),
+    (9, "KILL"),
+    (10, "BUS"),
+    (11, "SEGV"),
+    (12, "SYS"),
+    (13, "PIPE"),
+    (14, "ALRM"),
+    (15, "TERM"),
+    (16, "URG"),
+    (17, "STOP"),
+    (18, "TSTP"),
+    (19, "CONT"),
+    (20, "CHLD"),
+    (21, "TTIN"),
+    (22, "TTOU"),
+    (23, "IO"),
+    (24, "XCPU"),
+    (25, "XFSZ"),
+    (26, "VTALRM"),
+    (27, "PROF"),
+    (28, "WINCH"),
+    (29, "INFO"),
+    (30, "USR1"),
+    (31, "USR2"),
+];
+
+/// The number and name of each signal that can be sent, in order.
+// https://www.freebsd.org/cgi/man.cgi?query=signal&apropos=0&sektion=3&manpath=FreeBSD+13.1-RELEASE+and+Ports&arch=default&format=html
+#[cfg(target_os = "freebsd")]
+pub const SIGNALS: &[(usize, &str)] = &[
+    (1, "HUP"),
+    (2, "INT"),
+    (3, "QUIT"),
+    (4, "ILL"),
+    (5, "TRAP"),
+    (6, "ABRT"),
+    (7, "EMT"),
+    (8, "FPE"),
+    (9, "KILL"),
+    (10, "BUS"),
+    (11, "SEGV"),
+    (12, "SYS"),
+    (13, "PIPE"),
+    (14, "ALRM"),
+    (15, "TERM"),
+    (16, "URG"),
+    (17, "STOP"),
+    (18, "TSTP"),
+    (19, "CONT"),
+    (20, "CHLD"),
+    (21, "TTIN"),
+    (22, "TTOU"),
+    (23, "IO"),
+    (24, "XCPU"),
+    (25, "XFSZ"),
+    (26, "VTALRM"),
+    (27, "PROF"),
+    (28, "WINCH"),
+    (29, "INFO"),
+    (30, "USR1"),
+    (31, "USR2"),
+    (32, "THR"),
+    (33, "LIBRT"),
+];
+
+/// Whether a signal is in [`SIGNALS`], for when one is typed in by its number.
+pub fn is_pickable(signal: usize) -> bool {
+    SIGNALS.iter().any(|(number, _)| *number == signal)
+}
+
+/// Returns the position of a signal in the picker. Signals that can't be picked are treated as
+/// cancelling.
+pub fn picker_index(signal: &KillSignal) -> usize {
+    match signal {
+        KillSignal::Cancel => 0,
+        KillSignal::Kill(signal) => SIGNALS
+            .iter()
+            .position(|(number, _)| number == signal)
+            .map_or(0, |index| index + 1),
+    }
+}
+
+/// Returns the signal at a position in the picker, stopping at the last one.
+pub fn from_picker_index(index: usize) -> KillSignal {
+    match index.checked_sub(1) {
+        None => KillSignal::Cancel,
+        Some(index) => KillSignal::Kill(SIGNALS[index.min(SIGNALS.len() - 1)].0),
+    }
+}
+
+/// Returns the signal `steps` positions away from `signal` in the picker, stopping at either end.
+pub fn step_signal(signal: &KillSignal, steps: isize) -> KillSignal {
+    from_picker_index(picker_index(signal).saturating_add_signed(steps))
+}
+
+/// Returns the label of a position in the picker, like `9: KILL`.
+pub fn picker_label(index: usize) -> String {
+    match index.checked_sub(1).and_then(|index| SIGNALS.get(index)) {
+        Some((number, name)) => format!("{number}: {name}"),
+        None => "0: Cancel".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_step_signal() {
+        assert!(step_signal(&KillSignal::Cancel, -1) == KillSignal::Cancel);
+        assert!(step_signal(&KillSignal::Cancel, 1) == KillSignal::Kill(1));
+        assert!(step_signal(&KillSignal::Kill(15), -8) == KillSignal::Kill(7));
+
+        let last = SIGNALS.last().unwrap().0;
+        assert!(step_signal(&KillSignal::Kill(last), 8) == KillSignal::Kill(last));
+        assert!(from_picker_index(usize::MAX) == KillSignal::Kill(last));
+
+        #[cfg(target_os = "linux")]
+        {
+            assert!(step_signal(&KillSignal::Kill(31), 1) == KillSignal::Kill(34));
+            assert!(step_signal(&KillSignal::Kill(34), -1) == KillSignal::Kill(31));
+            assert!(!is_pickable(32));
+        }
+    }
+
+    #[test]
+    fn test_picker_label() {
+        assert_eq!(picker_label(0), "0: Cancel");
+        assert_eq!(picker_label(picker_index(&KillSignal::Kill(9))), "9: KILL");
+    }
+}
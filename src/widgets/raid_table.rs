@@ -0,0 +1,290 @@
+use std::borrow::Cow;
+
+use tui::{text::Text, widgets::Row};
+
+use crate::{
+    app::{
+        data_harvester::raid::{RaidMember, RaidSync},
+        AppConfigFields,
+    },
+    canvas::{canvas_styling::CanvasColours, Painter},
+    components::data_table::{
+        ColumnHeader, DataTableColumn, DataTableProps, DataTableStyling, DataToCell, SortColumn,
+        SortDataTable, SortDataTableProps, SortOrder, SortsRow,
+    },
+    utils::gen_util::{sort_partial_fn, truncate_to_text},
+};
+
+#[derive(Clone, Debug)]
+pub struct RaidWidgetData {
+    pub name: String,
+    pub level: Option<String>,
+    pub state: String,
+    pub members: Vec<RaidMember>,
+    pub total_devices: Option<u32>,
+    pub working_devices: Option<u32>,
+    pub sync: Option<RaidSync>,
+    pub is_degraded: bool,
+}
+
+impl RaidWidgetData {
+    fn devices_string(&self) -> Option<String> {
+        Some(format!("{}/{}", self.working_devices?, self.total_devices?))
+    }
+
+    /// Lists the members, marking failed ones with `(F)` and spares with `(S)` like mdstat does.
+    fn members_string(&self) -> String {
+        self.members
+            .iter()
+            .map(|member| {
+                if member.is_failed {
+                    format!("{}(F)", member.name)
+                } else if member.is_spare {
+                    format!("{}(S)", member.name)
+                } else {
+                    member.name.clone()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn sync_string(&self) -> Option<String> {
+        let sync = self.sync.as_ref()?;
+        Some(match (&sync.finish, sync.percent) {
+            (Some(finish), _) => format!("{}, {finish} left", sync.action),
+            (None, Some(_)) => sync.action.clone(),
+            (None, None) => format!("{} (pending)", sync.action),
+        })
+    }
+
+    fn sync_percent(&self) -> Option<f64> {
+        self.sync.as_ref().and_then(|sync| sync.percent)
+    }
+}
+
+/// Draws a progress bar like `[====>      ]  45.0%` that fits in `width`, or just the percentage
+/// if there isn't room for a bar.
+fn progress_bar(percent: f64, width: usize) -> String {
+    let label = format!("{percent:5.1}%");
+    let bar_width = width.saturating_sub(label.len() + 3);
+    if bar_width < 3 {
+        return label.trim_start().to_string();
+    }
+
+    let filled = ((percent.clamp(0.0, 100.0) / 100.0) * bar_width as f64).round() as usize;
+    let bar = if filled == 0 || filled == bar_width {
+        "=".repeat(filled)
+    } else {
+        format!("{}>", "=".repeat(filled - 1))
+    };
+
+    format!("[{bar:bar_width$}] {label}")
+}
+
+pub enum RaidWidgetColumn {
+    Array,
+    Level,
+    State,
+    Devices,
+    Members,
+    Sync,
+    Progress,
+}
+
+impl ColumnHeader for RaidWidgetColumn {
+    fn text(&self) -> Cow<'static, str> {
+        match self {
+            RaidWidgetColumn::Array => "Array".into(),
+            RaidWidgetColumn::Level => "Level".into(),
+            RaidWidgetColumn::State => "State".into(),
+            RaidWidgetColumn::Devices => "Devs".into(),
+            RaidWidgetColumn::Members => "Members".into(),
+            RaidWidgetColumn::Sync => "Sync".into(),
+            RaidWidgetColumn::Progress => "Progress".into(),
+        }
+    }
+}
+
+impl DataToCell<RaidWidgetColumn> for RaidWidgetData {
+    fn to_cell<'a>(&'a self, column: &RaidWidgetColumn, calculated_width: u16) -> Option<Text<'a>> {
+        if calculated_width == 0 {
+            return None;
+        }
+
+        let text = match column {
+            RaidWidgetColumn::Array => Some(self.name.clone()),
+            RaidWidgetColumn::Level => self.level.clone(),
+            RaidWidgetColumn::State => Some(self.state.clone()),
+            RaidWidgetColumn::Devices => self.devices_string(),
+            RaidWidgetColumn::Members => Some(self.members_string()),
+            RaidWidgetColumn::Sync => self.sync_string(),
+            RaidWidgetColumn::Progress => self
+                .sync_percent()
+                .map(|percent| progress_bar(percent, calculated_width.into())),
+        };
+
+        Some(truncate_to_text(
+            text.as_deref().unwrap_or(""),
+            calculated_width,
+        ))
+    }
+
+    fn style_row<'a>(&self, row: Row<'a>, painter: &Painter) -> Row<'a> {
+        if self.is_degraded {
+            row.style(painter.colours.low_battery_colour)
+        } else {
+            row
+        }
+    }
+
+    fn column_widths<C: DataTableColumn<RaidWidgetColumn>>(
+        data: &[RaidWidgetData], _columns: &[C],
+    ) -> Vec<u16>
+    where
+        Self: Sized,
+    {
+        let mut widths = vec![0, 0, 0, 5, 0, 0, 0];
+        for row in data {
+            widths[0] = widths[0].max(row.name.len() as u16);
+            widths[1] = widths[1].max(row.level.as_deref().unwrap_or_default().len() as u16);
+            widths[2] = widths[2].max(row.state.len() as u16);
+            widths[4] = widths[4].max(row.members_string().len() as u16);
+            widths[5] = widths[5].max(row.sync_string().unwrap_or_default().len() as u16);
+        }
+
+        widths
+    }
+}
+
+impl SortsRow for RaidWidgetColumn {
+    type DataType = RaidWidgetData;
+
+    fn sort_data(&self, data: &mut [Self::DataType], descending: bool) {
+        match self {
+            RaidWidgetColumn::Array => {
+                data.sort_by(|a, b| sort_partial_fn(descending)(&a.name, &b.name));
+            }
+            RaidWidgetColumn::Level => {
+                data.sort_by(|a, b| sort_partial_fn(descending)(&a.level, &b.level));
+            }
+            RaidWidgetColumn::State => {
+                data.sort_by(|a, b| sort_partial_fn(descending)(&a.state, &b.state));
+            }
+            RaidWidgetColumn::Devices => {
+                data.sort_by(|a, b| {
+                    sort_partial_fn(descending)(a.working_devices, b.working_devices)
+                });
+            }
+            RaidWidgetColumn::Members => {
+                data.sort_by(|a, b| sort_partial_fn(descending)(a.members.len(), b.members.len()));
+            }
+            RaidWidgetColumn::Sync => {
+                data.sort_by(|a, b| {
+                    sort_partial_fn(descending)(
+                        a.sync.as_ref().map(|sync| &sync.action),
+                        b.sync.as_ref().map(|sync| &sync.action),
+                    )
+                });
+            }
+            RaidWidgetColumn::Progress => {
+                data.sort_by(|a, b| {
+                    sort_partial_fn(descending)(a.sync_percent(), b.sync_percent())
+                });
+            }
+        }
+    }
+}
+
+pub struct RaidWidgetState {
+    pub table: SortDataTable<RaidWidgetData, RaidWidgetColumn>,
+}
+
+impl RaidWidgetState {
+    pub fn new(config: &AppConfigFields, colours: &CanvasColours) -> Self {
+        let columns = [
+            SortColumn::soft(RaidWidgetColumn::Array, Some(0.1)),
+            SortColumn::soft(RaidWidgetColumn::Level, Some(0.1)),
+            SortColumn::soft(RaidWidgetColumn::State, Some(0.15)),
+            SortColumn::hard(RaidWidgetColumn::Devices, 5),
+            SortColumn::soft(RaidWidgetColumn::Members, Some(0.25)),
+            SortColumn::soft(RaidWidgetColumn::Sync, Some(0.15)),
+            SortColumn::soft(RaidWidgetColumn::Progress, Some(0.25)).default_descending(),
+        ];
+
+        let props = SortDataTableProps {
+            inner: DataTableProps {
+                title: Some(" RAID ".into()),
+                table_gap: config.table_gap,
+                left_to_right: true,
+                is_basic: config.use_basic_mode,
+                show_table_scroll_position: config.show_table_scroll_position,
+                show_scrollbar: config.show_scrollbar,
+                show_current_entry_when_unfocused: false,
+            },
+            sort_index: 0,
+            order: SortOrder::Ascending,
+        };
+
+        let styling = DataTableStyling::from_colours(colours);
+
+        Self {
+            table: SortDataTable::new_sortable(columns, props, styling),
+        }
+    }
+
+    pub fn ingest_data(&mut self, arrays: &[RaidWidgetData]) {
+        let mut data = arrays.to_vec();
+        if let Some(column) = self.table.columns.get(self.table.sort_index()) {
+            column.sort_by(&mut data, self.table.order());
+        }
+
+        self.table.set_data(data);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_progress_bar() {
+        assert_eq!(progress_bar(8.5, 20), "[>          ]   8.5%");
+        assert_eq!(progress_bar(45.0, 20), "[====>      ]  45.0%");
+        assert_eq!(progress_bar(100.0, 20), "[===========] 100.0%");
+        assert_eq!(progress_bar(0.0, 20), "[           ]   0.0%");
+        assert_eq!(progress_bar(50.0, 8), "50.0%");
+    }
+
+    #[test]
+    fn test_raid_cells() {
+        let data = RaidWidgetData {
+            name: "md1".to_string(),
+            level: Some("raid5".to_string()),
+            state: "active".to_string(),
+            members: vec![
+                RaidMember {
+                    name: "sda1".to_string(),
+                    ..Default::default()
+                },
+                RaidMember {
+                    name: "sdb1".to_string(),
+                    is_failed: true,
+                    ..Default::default()
+                },
+            ],
+            total_devices: Some(3),
+            working_devices: Some(2),
+            sync: Some(RaidSync {
+                action: "recovery".to_string(),
+                percent: Some(12.5),
+                finish: Some("3.2min".to_string()),
+            }),
+            is_degraded: true,
+        };
+
+        assert_eq!(data.devices_string().as_deref(), Some("2/3"));
+        assert_eq!(data.members_string(), "sda1 sdb1(F)");
+        assert_eq!(data.sync_string().as_deref(), Some("recovery, 3.2min left"));
+    }
+}
@@ -0,0 +1,264 @@
+use std::{borrow::Cow, time::SystemTime};
+
+use tui::{text::Text, widgets::Row};
+
+use crate::{
+    app::{data_harvester::scheduled_jobs::JobKind, AppConfigFields},
+    canvas::{canvas_styling::CanvasColours, Painter},
+    components::data_table::{
+        ColumnHeader, DataTableColumn, DataTableProps, DataTableStyling, DataToCell, SortColumn,
+        SortDataTable, SortDataTableProps, SortOrder, SortsRow,
+    },
+    utils::gen_util::{format_uptime, local_offset, sort_partial_fn, truncate_to_text},
+};
+
+#[derive(Clone, Debug)]
+pub struct ScheduledJobWidgetData {
+    pub kind: JobKind,
+    pub schedule: String,
+    pub command: String,
+    pub user: Option<String>,
+    pub next_run: Option<u64>,
+    pub last_run: Option<u64>,
+    pub last_result: Option<String>,
+    pub has_failed: bool,
+}
+
+impl ScheduledJobWidgetData {
+    fn kind_string(&self) -> &'static str {
+        match self.kind {
+            JobKind::Cron => "cron",
+            JobKind::Timer => "timer",
+        }
+    }
+}
+
+/// Formats a run time as the local date and time, followed by how long until or since it, like
+/// `Oct 16 03:00 (in 13h 22m)`.
+fn run_time_string(time: u64, now: u64) -> Option<String> {
+    let date_time = time::OffsetDateTime::from_unix_timestamp(i64::try_from(time).ok()?).ok()?;
+    let date_time = date_time
+        .to_offset(local_offset(date_time.unix_timestamp()))
+        .format(&time::macros::format_description!(
+            "[month repr:short] [day] [hour]:[minute]"
+        ))
+        .ok()?;
+
+    Some(if time >= now {
+        format!("{date_time} (in {})", format_uptime(time - now))
+    } else {
+        format!("{date_time} ({} ago)", format_uptime(now - time))
+    })
+}
+
+pub enum ScheduledJobWidgetColumn {
+    Type,
+    Schedule,
+    User,
+    Runs,
+    Next,
+    Last,
+    Result,
+}
+
+impl ColumnHeader for ScheduledJobWidgetColumn {
+    fn text(&self) -> Cow<'static, str> {
+        match self {
+            ScheduledJobWidgetColumn::Type => "Type".into(),
+            ScheduledJobWidgetColumn::Schedule => "Schedule".into(),
+            ScheduledJobWidgetColumn::User => "User".into(),
+            ScheduledJobWidgetColumn::Runs => "Runs".into(),
+            ScheduledJobWidgetColumn::Next => "Next".into(),
+            ScheduledJobWidgetColumn::Last => "Last".into(),
+            ScheduledJobWidgetColumn::Result => "Result".into(),
+        }
+    }
+}
+
+impl DataToCell<ScheduledJobWidgetColumn> for ScheduledJobWidgetData {
+    fn to_cell<'a>(
+        &'a self, column: &ScheduledJobWidgetColumn, calculated_width: u16,
+    ) -> Option<Text<'a>> {
+        if calculated_width == 0 {
+            return None;
+        }
+
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|now| now.as_secs())
+            .unwrap_or_default();
+        let text = match column {
+            ScheduledJobWidgetColumn::Type => Some(self.kind_string().to_string()),
+            ScheduledJobWidgetColumn::Schedule => Some(self.schedule.clone()),
+            ScheduledJobWidgetColumn::User => self.user.clone(),
+            ScheduledJobWidgetColumn::Runs => Some(self.command.clone()),
+            ScheduledJobWidgetColumn::Next => {
+                self.next_run.and_then(|next| run_time_string(next, now))
+            }
+            ScheduledJobWidgetColumn::Last => {
+                self.last_run.and_then(|last| run_time_string(last, now))
+            }
+            ScheduledJobWidgetColumn::Result => self.last_result.clone(),
+        };
+
+        Some(truncate_to_text(
+            text.as_deref().unwrap_or(""),
+            calculated_width,
+        ))
+    }
+
+    fn style_row<'a>(&self, row: Row<'a>, painter: &Painter) -> Row<'a> {
+        if self.has_failed {
+            row.style(painter.colours.low_battery_colour)
+        } else {
+            row
+        }
+    }
+
+    fn column_widths<C: DataTableColumn<ScheduledJobWidgetColumn>>(
+        data: &[ScheduledJobWidgetData], _columns: &[C],
+    ) -> Vec<u16>
+    where
+        Self: Sized,
+    {
+        let mut widths = vec![5, 0, 0, 0, 12, 12, 0];
+        for row in data {
+            widths[1] = widths[1].max(row.schedule.len() as u16);
+            widths[2] = widths[2].max(row.user.as_deref().unwrap_or_default().len() as u16);
+            widths[3] = widths[3].max(row.command.len() as u16);
+            widths[6] = widths[6].max(row.last_result.as_deref().unwrap_or_default().len() as u16);
+        }
+
+        widths
+    }
+}
+
+impl SortsRow for ScheduledJobWidgetColumn {
+    type DataType = ScheduledJobWidgetData;
+
+    fn sort_data(&self, data: &mut [Self::DataType], descending: bool) {
+        match self {
+            ScheduledJobWidgetColumn::Type => {
+                data.sort_by(|a, b| sort_partial_fn(descending)(a.kind_string(), b.kind_string()));
+            }
+            ScheduledJobWidgetColumn::Schedule => {
+                data.sort_by(|a, b| sort_partial_fn(descending)(&a.schedule, &b.schedule));
+            }
+            ScheduledJobWidgetColumn::User => {
+                data.sort_by(|a, b| sort_partial_fn(descending)(&a.user, &b.user));
+            }
+            ScheduledJobWidgetColumn::Runs => {
+                data.sort_by(|a, b| sort_partial_fn(descending)(&a.command, &b.command));
+            }
+            // Jobs that never run, like `@reboot` entries, go after the ones that do.
+            ScheduledJobWidgetColumn::Next => {
+                data.sort_by(|a, b| {
+                    sort_partial_fn(descending)(
+                        (a.next_run.is_none(), a.next_run),
+                        (b.next_run.is_none(), b.next_run),
+                    )
+                });
+            }
+            ScheduledJobWidgetColumn::Last => {
+                data.sort_by(|a, b| sort_partial_fn(descending)(a.last_run, b.last_run));
+            }
+            ScheduledJobWidgetColumn::Result => {
+                data.sort_by(|a, b| sort_partial_fn(descending)(&a.last_result, &b.last_result));
+            }
+        }
+    }
+}
+
+pub struct ScheduledJobWidgetState {
+    pub table: SortDataTable<ScheduledJobWidgetData, ScheduledJobWidgetColumn>,
+}
+
+impl ScheduledJobWidgetState {
+    pub fn new(config: &AppConfigFields, colours: &CanvasColours) -> Self {
+        let columns = [
+            SortColumn::hard(ScheduledJobWidgetColumn::Type, 5),
+            SortColumn::soft(ScheduledJobWidgetColumn::Schedule, Some(0.15)),
+            SortColumn::soft(ScheduledJobWidgetColumn::User, Some(0.1)),
+            SortColumn::soft(ScheduledJobWidgetColumn::Runs, Some(0.25)),
+            SortColumn::soft(ScheduledJobWidgetColumn::Next, Some(0.25)),
+            SortColumn::soft(ScheduledJobWidgetColumn::Last, Some(0.2)).default_descending(),
+            SortColumn::soft(ScheduledJobWidgetColumn::Result, Some(0.1)),
+        ];
+
+        let props = SortDataTableProps {
+            inner: DataTableProps {
+                title: Some(" Scheduled Jobs ".into()),
+                table_gap: config.table_gap,
+                left_to_right: true,
+                is_basic: config.use_basic_mode,
+                show_table_scroll_position: config.show_table_scroll_position,
+                show_scrollbar: config.show_scrollbar,
+                show_current_entry_when_unfocused: false,
+            },
+            sort_index: 4,
+            order: SortOrder::Ascending,
+        };
+
+        let styling = DataTableStyling::from_colours(colours);
+
+        Self {
+            table: SortDataTable::new_sortable(columns, props, styling),
+        }
+    }
+
+    pub fn ingest_data(&mut self, jobs: &[ScheduledJobWidgetData]) {
+        let mut data = jobs.to_vec();
+        if let Some(column) = self.table.columns.get(self.table.sort_index()) {
+            column.sort_by(&mut data, self.table.order());
+        }
+
+        self.table.set_data(data);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn job(schedule: &str, next_run: Option<u64>) -> ScheduledJobWidgetData {
+        ScheduledJobWidgetData {
+            kind: JobKind::Cron,
+            schedule: schedule.to_string(),
+            command: String::default(),
+            user: None,
+            next_run,
+            last_run: None,
+            last_result: None,
+            has_failed: false,
+        }
+    }
+
+    #[test]
+    fn test_scheduled_jobs_sorted_by_next_run() {
+        let mut state =
+            ScheduledJobWidgetState::new(&AppConfigFields::default(), &CanvasColours::default());
+        state.ingest_data(&[
+            job("@reboot", None),
+            job("0 3 * * *", Some(200)),
+            job("*/5 * * * *", Some(100)),
+        ]);
+
+        let schedules = state
+            .table
+            .data()
+            .iter()
+            .map(|job| job.schedule.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(schedules, ["*/5 * * * *", "0 3 * * *", "@reboot"]);
+    }
+
+    #[test]
+    fn test_run_time_string() {
+        let now = 1_760_000_000;
+        let next = run_time_string(now + 13 * 60 * 60 + 22 * 60, now).unwrap();
+        assert!(next.ends_with(" (in 13h 22m)"), "{next}");
+
+        let last = run_time_string(now - 2 * 24 * 60 * 60, now).unwrap();
+        assert!(last.ends_with(" (2d 0h 0m ago)"), "{last}");
+    }
+}
@@ -1,12 +1,19 @@
-use std::{borrow::Cow, cmp::max};
+use std::{borrow::Cow, cmp::max, collections::BTreeMap};
 
 use concat_string::concat_string;
 use kstring::KString;
-use tui::text::Text;
+use tui::{
+    style::{Modifier, Style},
+    text::Text,
+    widgets::Row,
+};
 
 use crate::{
-    app::{data_harvester::temperature::TemperatureType, AppConfigFields},
-    canvas::canvas_styling::CanvasColours,
+    app::{
+        data_harvester::temperature::{SensorType, TemperatureType},
+        AppConfigFields,
+    },
+    canvas::{canvas_styling::CanvasColours, Painter},
     components::data_table::{
         ColumnHeader, DataTableColumn, DataTableProps, DataTableStyling, DataToCell, SortColumn,
         SortDataTable, SortDataTableProps, SortOrder, SortsRow,
@@ -14,16 +21,30 @@ use crate::{
     utils::gen_util::{sort_partial_fn, truncate_to_text},
 };
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TempRowKind {
+    Sensor,
+    /// The name of a group of sensors, when sensors are grouped.
+    GroupHeader,
+    /// An empty row between groups of sensors.
+    Separator,
+}
+
 #[derive(Clone, Debug)]
 pub struct TempWidgetData {
     pub sensor: KString,
-    pub temperature_value: u64,
+    pub value: f32,
+    pub sensor_type: SensorType,
     pub temperature_type: TemperatureType,
+    pub kind: TempRowKind,
 }
 
 pub enum TempWidgetColumn {
     Sensor,
     Temp,
+    /// Replaces [`TempWidgetColumn::Temp`] when sensors other than temperatures are shown.
+    Value,
+    Type,
 }
 
 impl ColumnHeader for TempWidgetColumn {
@@ -31,19 +52,49 @@ impl ColumnHeader for TempWidgetColumn {
         match self {
             TempWidgetColumn::Sensor => "Sensor(s)".into(),
             TempWidgetColumn::Temp => "Temp(t)".into(),
+            TempWidgetColumn::Value => "Value(t)".into(),
+            TempWidgetColumn::Type => "Type".into(),
         }
     }
 }
 
 impl TempWidgetData {
-    pub fn temperature(&self) -> KString {
-        let temp_val = self.temperature_value.to_string();
-        let temp_type = match self.temperature_type {
-            TemperatureType::Celsius => "°C",
-            TemperatureType::Kelvin => "K",
-            TemperatureType::Fahrenheit => "°F",
-        };
-        concat_string!(temp_val, temp_type).into()
+    fn group_header(group: &str) -> Self {
+        TempWidgetData {
+            sensor: KString::from_ref(group),
+            value: 0.0,
+            sensor_type: SensorType::default(),
+            temperature_type: TemperatureType::default(),
+            kind: TempRowKind::GroupHeader,
+        }
+    }
+
+    fn separator() -> Self {
+        TempWidgetData {
+            sensor: KString::default(),
+            value: 0.0,
+            sensor_type: SensorType::default(),
+            temperature_type: TemperatureType::default(),
+            kind: TempRowKind::Separator,
+        }
+    }
+
+    /// The reading along with its unit, e.g. `45°C`, `1200RPM` or `1.05V`.
+    pub fn value(&self) -> KString {
+        match self.sensor_type {
+            SensorType::Temperature => {
+                let temp_val = (self.value.ceil() as u64).to_string();
+                let temp_type = match self.temperature_type {
+                    TemperatureType::Celsius => "°C",
+                    TemperatureType::Kelvin => "K",
+                    TemperatureType::Fahrenheit => "°F",
+                };
+                concat_string!(temp_val, temp_type).into()
+            }
+            SensorType::Fan => format!("{:.0}RPM", self.value).into(),
+            SensorType::Voltage => format!("{:.2}V", self.value).into(),
+            SensorType::Power => format!("{:.1}W", self.value).into(),
+        }
     }
 }
 
@@ -55,21 +106,42 @@ impl DataToCell<TempWidgetColumn> for TempWidgetData {
 
         Some(match column {
             TempWidgetColumn::Sensor => truncate_to_text(&self.sensor, calculated_width),
-            TempWidgetColumn::Temp => truncate_to_text(&self.temperature(), calculated_width),
+            TempWidgetColumn::Temp | TempWidgetColumn::Value => match self.kind {
+                TempRowKind::Sensor => truncate_to_text(&self.value(), calculated_width),
+                TempRowKind::GroupHeader | TempRowKind::Separator => Text::default(),
+            },
+            TempWidgetColumn::Type => match self.kind {
+                TempRowKind::Sensor => truncate_to_text(self.sensor_type.name(), calculated_width),
+                TempRowKind::GroupHeader | TempRowKind::Separator => Text::default(),
+            },
         })
     }
 
+    fn style_row<'a>(&self, row: Row<'a>, _painter: &Painter) -> Row<'a> {
+        if let TempRowKind::GroupHeader = self.kind {
+            row.style(Style::default().add_modifier(Modifier::BOLD))
+        } else {
+            row
+        }
+    }
+
     fn column_widths<C: DataTableColumn<TempWidgetColumn>>(
-        data: &[TempWidgetData], _columns: &[C],
+        data: &[TempWidgetData], columns: &[C],
     ) -> Vec<u16>
     where
         Self: Sized,
     {
-        let mut widths = vec![0; 2];
+        let mut widths = vec![0; columns.len()];
 
         data.iter().for_each(|row| {
-            widths[0] = max(widths[0], row.sensor.len() as u16);
-            widths[1] = max(widths[1], row.temperature().len() as u16);
+            for (width, column) in widths.iter_mut().zip(columns) {
+                let len = match column.inner() {
+                    TempWidgetColumn::Sensor => row.sensor.len(),
+                    TempWidgetColumn::Temp | TempWidgetColumn::Value => row.value().len(),
+                    TempWidgetColumn::Type => row.sensor_type.name().len(),
+                };
+                *width = max(*width, len as u16);
+            }
         });
 
         widths
@@ -84,11 +156,17 @@ impl SortsRow for TempWidgetColumn {
             TempWidgetColumn::Sensor => {
                 data.sort_by(move |a, b| sort_partial_fn(descending)(&a.sensor, &b.sensor));
             }
-            TempWidgetColumn::Temp => {
+            TempWidgetColumn::Temp | TempWidgetColumn::Value => {
+                // Readings are only comparable within a type, so sort by the type first.
                 data.sort_by(|a, b| {
-                    sort_partial_fn(descending)(a.temperature_value, b.temperature_value)
+                    a.sensor_type
+                        .cmp(&b.sensor_type)
+                        .then_with(|| sort_partial_fn(descending)(a.value, b.value))
                 });
             }
+            TempWidgetColumn::Type => {
+                data.sort_by(move |a, b| sort_partial_fn(descending)(a.sensor_type, b.sensor_type));
+            }
         }
     }
 }
@@ -96,14 +174,28 @@ impl SortsRow for TempWidgetColumn {
 pub struct TempWidgetState {
     pub table: SortDataTable<TempWidgetData, TempWidgetColumn>,
     pub force_update_data: bool,
+    group_sensors: bool,
+    sensor_group_prefixes: Vec<String>,
 }
 
 impl TempWidgetState {
     pub fn new(config: &AppConfigFields, colours: &CanvasColours) -> Self {
-        let columns = [
-            SortColumn::soft(TempWidgetColumn::Sensor, Some(0.8)),
-            SortColumn::soft(TempWidgetColumn::Temp, None).default_descending(),
-        ];
+        let only_temperatures = config
+            .sensor_types
+            .iter()
+            .all(|sensor_type| *sensor_type == SensorType::Temperature);
+        let columns = if only_temperatures {
+            vec![
+                SortColumn::soft(TempWidgetColumn::Sensor, Some(0.8)),
+                SortColumn::soft(TempWidgetColumn::Temp, None).default_descending(),
+            ]
+        } else {
+            vec![
+                SortColumn::soft(TempWidgetColumn::Sensor, Some(0.7)),
+                SortColumn::soft(TempWidgetColumn::Value, None).default_descending(),
+                SortColumn::hard(TempWidgetColumn::Type, 6),
+            ]
+        };
 
         let props = SortDataTableProps {
             inner: DataTableProps {
@@ -112,6 +204,7 @@ impl TempWidgetState {
                 left_to_right: false,
                 is_basic: config.use_basic_mode,
                 show_table_scroll_position: config.show_table_scroll_position,
+                show_scrollbar: config.show_scrollbar,
                 show_current_entry_when_unfocused: false,
             },
             sort_index: 0,
@@ -123,6 +216,8 @@ impl TempWidgetState {
         Self {
             table: SortDataTable::new_sortable(columns, props, styling),
             force_update_data: false,
+            group_sensors: config.group_sensors,
+            sensor_group_prefixes: config.sensor_group_prefixes.clone(),
         }
     }
 
@@ -137,6 +232,122 @@ impl TempWidgetState {
         if let Some(column) = self.table.columns.get(self.table.sort_index()) {
             column.sort_by(&mut data, self.table.order());
         }
+
+        if self.group_sensors {
+            data = group_sensors(data, &self.sensor_group_prefixes);
+        }
+
         self.table.set_data(data);
     }
 }
+
+/// Returns the group a sensor belongs to. This is the first of `prefixes` that the sensor name
+/// starts with, ignoring case, or otherwise the first word of the name (e.g. "CPU" for
+/// "CPU Core 0", or "k10temp" for "k10temp: Tctl").
+pub fn extract_sensor_group<'a>(name: &'a str, prefixes: &[String]) -> &'a str {
+    let matched_prefix = prefixes.iter().find(|prefix| {
+        name.get(..prefix.len())
+            .map(|start| start.eq_ignore_ascii_case(prefix))
+            .unwrap_or(false)
+    });
+
+    match matched_prefix {
+        Some(prefix) => &name[..prefix.len()],
+        None => name
+            .split(|c: char| c == ':' || c.is_whitespace())
+            .next()
+            .unwrap_or(name),
+    }
+}
+
+/// Splits already sorted sensors into groups, ordered by group name. Each group starts with a
+/// header row, and groups are separated by an empty row.
+fn group_sensors(data: Vec<TempWidgetData>, prefixes: &[String]) -> Vec<TempWidgetData> {
+    let mut groups: BTreeMap<String, Vec<TempWidgetData>> = BTreeMap::new();
+    for row in data {
+        groups
+            .entry(extract_sensor_group(&row.sensor, prefixes).to_string())
+            .or_default()
+            .push(row);
+    }
+
+    let mut grouped = Vec::new();
+    for (index, (group, rows)) in groups.into_iter().enumerate() {
+        if index > 0 {
+            grouped.push(TempWidgetData::separator());
+        }
+        grouped.push(TempWidgetData::group_header(&group));
+        grouped.extend(rows);
+    }
+
+    grouped
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_extract_sensor_group() {
+        assert_eq!(extract_sensor_group("CPU Core 0", &[]), "CPU");
+        assert_eq!(extract_sensor_group("NVMe Composite", &[]), "NVMe");
+        assert_eq!(extract_sensor_group("k10temp: Tctl", &[]), "k10temp");
+
+        let prefixes = ["package id".to_string()];
+        assert_eq!(
+            extract_sensor_group("Package id 0", &prefixes),
+            "Package id"
+        );
+        assert_eq!(extract_sensor_group("Pack", &prefixes), "Pack");
+    }
+
+    #[test]
+    fn test_sensor_value() {
+        let sensor = |value, sensor_type| TempWidgetData {
+            sensor: KString::from_ref("nct6775"),
+            value,
+            sensor_type,
+            temperature_type: TemperatureType::Fahrenheit,
+            kind: TempRowKind::Sensor,
+        };
+
+        assert_eq!(sensor(40.2, SensorType::Temperature).value(), "41°F");
+        assert_eq!(sensor(1234.0, SensorType::Fan).value(), "1234RPM");
+        assert_eq!(sensor(1.048, SensorType::Voltage).value(), "1.05V");
+        assert_eq!(sensor(35.25, SensorType::Power).value(), "35.2W");
+    }
+
+    #[test]
+    fn test_group_sensors() {
+        let sensor = |name: &str| TempWidgetData {
+            sensor: KString::from_ref(name),
+            value: 40.0,
+            sensor_type: SensorType::Temperature,
+            temperature_type: TemperatureType::Celsius,
+            kind: TempRowKind::Sensor,
+        };
+
+        let grouped = group_sensors(
+            vec![
+                sensor("NVMe Composite"),
+                sensor("CPU Core 1"),
+                sensor("CPU Core 0"),
+            ],
+            &[],
+        );
+        assert_eq!(
+            grouped
+                .iter()
+                .map(|row| (row.sensor.as_str(), row.kind))
+                .collect::<Vec<_>>(),
+            vec![
+                ("CPU", TempRowKind::GroupHeader),
+                ("CPU Core 1", TempRowKind::Sensor),
+                ("CPU Core 0", TempRowKind::Sensor),
+                ("", TempRowKind::Separator),
+                ("NVMe", TempRowKind::GroupHeader),
+                ("NVMe Composite", TempRowKind::Sensor),
+            ]
+        );
+    }
+}
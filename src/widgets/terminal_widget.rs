@@ -1,19 +1,49 @@
-use crate::{app::App, BottomEvent};
+use crate::{
+    components::scrollback::Scrollback,
+    constants::DEFAULT_TERMINAL_HISTORY_SIZE,
+    remote::{self, CommandEvent, RemoteCommand},
+    BottomEvent, Pid,
+};
 use serde::__private::from_utf8_lossy;
 use std::{
-    collections::VecDeque,
-    sync::{mpsc::Sender, Mutex, MutexGuard},
+    collections::{HashMap, VecDeque},
+    fs, io,
+    io::{Read, Write},
+    net::{Shutdown, SocketAddr, TcpStream},
+    path::{Path, PathBuf},
+    process::{Command, ExitStatus, Stdio},
+    sync::mpsc::Sender,
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 use strip_ansi_escapes::strip;
+use unicode_segmentation::UnicodeSegmentation;
+
+#[cfg(target_family = "unix")]
+mod pty;
+#[cfg(target_family = "unix")]
+pub use pty::key_to_pty_input;
 
 pub struct TerminalWidgetState {
     pub stdout: String,
+    /// The current input first, followed by past commands from most to least recent.
     pub stdin: VecDeque<String>,
-    pub offset: usize,
+    /// How many past commands are kept.
+    pub history_size: usize,
+    pub scrollback: Scrollback,
+    /// How many graphemes the cursor is from the end of the current input.
     pub input_offset: usize,
     pub selected_input: usize,
     pub is_working: bool,
-    pub sender: Option<*const Sender<BottomEvent>>,
+    /// The directory commands run in, changed with `cd`.
+    pub cwd: PathBuf,
+    /// The process ID of the running command, used to cancel it.
+    pub child_pid: Option<u32>,
+    /// The connection to the agent running the command, when connected to one, shut down to
+    /// cancel it.
+    pub remote_command: Option<TcpStream>,
+    /// Where input for the running command goes: the terminal it is attached to, or its stdin.
+    pub command_input: Option<Box<dyn Write + Send>>,
 }
 
 impl Default for TerminalWidgetState {
@@ -21,16 +51,60 @@ impl Default for TerminalWidgetState {
         Self {
             stdout: String::new(),
             stdin: VecDeque::from([String::new()]),
-            offset: 0,
+            history_size: DEFAULT_TERMINAL_HISTORY_SIZE,
+            scrollback: Scrollback::default(),
             input_offset: 0,
             selected_input: 0,
             is_working: false,
-            sender: None,
+            cwd: std::env::current_dir().unwrap_or_default(),
+            child_pid: None,
+            remote_command: None,
+            command_input: None,
         }
     }
 }
 
 impl TerminalWidgetState {
+    pub fn new(history_size: usize) -> Self {
+        Self {
+            history_size,
+            ..Default::default()
+        }
+    }
+
+    /// Loads past commands saved by [`TerminalWidgetState::save_history`], one per line. A missing
+    /// file just means there is no history yet.
+    pub fn load_history(&mut self, path: &Path) -> io::Result<()> {
+        let history = match fs::read_to_string(path) {
+            Ok(history) => history,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(err),
+        };
+
+        self.stdin.truncate(1);
+        self.stdin.extend(
+            history
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .take(self.history_size)
+                .map(String::from),
+        );
+
+        Ok(())
+    }
+
+    /// Saves past commands, most recent first, leaving out whatever is currently being typed.
+    pub fn save_history(&self, path: &Path) -> io::Result<()> {
+        let history = self
+            .stdin
+            .iter()
+            .skip(1)
+            .take(self.history_size)
+            .fold(String::new(), |history, command| history + command + "\n");
+
+        fs::write(path, history)
+    }
+
     pub fn current_input(&self) -> &String {
         self.stdin.get(self.selected_input).unwrap()
     }
@@ -38,86 +112,782 @@ impl TerminalWidgetState {
     pub fn current_input_mut(&mut self) -> &mut String {
         self.stdin.get_mut(self.selected_input).unwrap()
     }
-}
 
-unsafe impl Sync for TerminalWidgetState {}
-unsafe impl Send for TerminalWidgetState {}
+    /// Expands an alias at the start of the current input in place, returning whether there was
+    /// one. `process` fills in the `{pid}` and `{selected}` placeholders.
+    pub fn expand_alias(
+        &mut self, aliases: &HashMap<String, String>, process: Option<&(Pid, String)>,
+    ) -> bool {
+        let Some(expanded) = expand_alias(self.current_input(), aliases, process) else {
+            return false;
+        };
 
-pub struct UnsafeTerminalWidgetState {
-    pub id: u64,
-    pub app: &'static Mutex<Option<App>>,
-    pub sender: *const Sender<BottomEvent>,
-}
+        *self.current_input_mut() = expanded;
+        self.input_offset = 0;
+        true
+    }
 
-impl UnsafeTerminalWidgetState {
-    fn lock(&self) -> MutexGuard<'_, Option<App>> {
-        self.app.lock().unwrap()
+    /// Returns the byte index of the cursor in the current input.
+    fn cursor_index(&self) -> usize {
+        let input = self.current_input();
+        let graphemes = input.grapheme_indices(true).count();
+        if self.input_offset >= graphemes {
+            0
+        } else {
+            input
+                .grapheme_indices(true)
+                .nth(graphemes - self.input_offset)
+                .map(|(index, _)| index)
+                .unwrap_or(input.len())
+        }
+    }
+
+    /// Splits the current input at the cursor.
+    pub fn input_around_cursor(&self) -> (&str, &str) {
+        self.current_input().split_at(self.cursor_index())
+    }
+
+    pub fn move_cursor_left(&mut self) {
+        if self.input_offset < self.current_input().graphemes(true).count() {
+            self.input_offset += 1;
+        }
     }
 
-    fn get_tws<'a>(
-        &self, app_lock: &'a mut MutexGuard<'_, Option<App>>,
-    ) -> &'a mut TerminalWidgetState {
-        let app = app_lock.as_mut().unwrap();
-        app.terminal_state.widget_states.get_mut(&self.id).unwrap()
+    pub fn move_cursor_right(&mut self) {
+        self.input_offset = self.input_offset.saturating_sub(1);
     }
 
-    pub fn stdin(&mut self) -> String {
-        let mut app_lock = self.lock();
-        let t = self.get_tws(&mut app_lock);
-        let stdin = t.current_input().clone();
+    /// Inserts a character at the cursor.
+    pub fn insert_char(&mut self, c: char) {
+        let index = self.cursor_index();
+        self.current_input_mut().insert(index, c);
+    }
+
+    /// Removes the grapheme before the cursor, like backspace.
+    pub fn remove_before_cursor(&mut self) {
+        let index = self.cursor_index();
+        let input = self.current_input_mut();
+        if let Some((start, _)) = input[..index].grapheme_indices(true).next_back() {
+            input.replace_range(start..index, "");
+        }
+    }
+
+    /// Removes the grapheme after the cursor, like delete.
+    pub fn remove_after_cursor(&mut self) {
+        let index = self.cursor_index();
+        let input = self.current_input_mut();
+        if let Some(grapheme) = input[index..].graphemes(true).next() {
+            let end = index + grapheme.len();
+            input.replace_range(index..end, "");
+            self.input_offset -= 1;
+        }
+    }
+
+    /// Takes the current input, adding it to the history and echoing it into the output.
+    pub fn take_input(&mut self) -> String {
+        let stdin = self.current_input().clone();
         if !stdin.is_empty() {
-            if t.selected_input > 0 {
-                t.stdin.pop_front();
-                t.stdin.push_front(stdin.clone());
+            if self.selected_input > 0 {
+                self.stdin.pop_front();
+                self.stdin.push_front(stdin.clone());
             }
-            t.stdin.push_front(String::new());
-            while t.stdin.len() > 500 {
-                t.stdin.pop_back();
+            self.stdin.push_front(String::new());
+            while self.stdin.len() > self.history_size + 1 {
+                self.stdin.pop_back();
             }
         }
-        t.selected_input = 0;
+        self.selected_input = 0;
         let trimmed = stdin.trim();
         if !trimmed.is_empty() {
-            t.stdout += &format!("$ {trimmed}\n");
+            self.stdout += &format!("$ {trimmed}\n");
         }
         stdin
     }
 
-    pub fn append_output(&mut self, output: &[u8]) {
-        let mut app_lock = self.lock();
-        let t = self.get_tws(&mut app_lock);
+    /// Writes the output so far to `path`, without escape sequences, reporting how it went in the
+    /// output itself.
+    pub fn save_output(&mut self, path: &Path) {
+        let output = strip(&self.stdout).unwrap_or_else(|_| self.stdout.clone().into_bytes());
+        self.stdout += &match fs::write(path, output) {
+            Ok(()) => format!("Saved the output to {}\n", path.display()),
+            Err(err) => format!("Unable to save the output to {}: {err}\n", path.display()),
+        };
+    }
+
+    /// Runs the `:save <path>` action if it is the current input, returning whether it was.
+    /// Without a path, the output is saved like [`TerminalWidgetState::save_output_to_default`].
+    pub fn try_save_action(&mut self) -> bool {
+        let Some(path) = self.current_input().trim().strip_prefix(":save") else {
+            return false;
+        };
+        if !path.is_empty() && !path.starts_with(char::is_whitespace) {
+            return false;
+        }
+
+        let path = PathBuf::from(path.trim());
+        self.take_input();
+        if path.as_os_str().is_empty() {
+            self.save_output_to_default();
+        } else {
+            self.save_output(&path);
+        }
+        true
+    }
+
+    /// Runs `cd <path>` if it is the current input, returning whether it was. Like a shell, a
+    /// missing path goes to the home directory, and a leading `~` stands for it.
+    pub fn try_change_directory(&mut self) -> bool {
+        let input = self.current_input().trim();
+        let Some(path) = input.strip_prefix("cd") else {
+            return false;
+        };
+        if !path.is_empty() && !path.starts_with(char::is_whitespace) {
+            return false;
+        }
+
+        let path = path.trim().to_string();
+        self.take_input();
+
+        let home = dirs::home_dir().unwrap_or_default();
+        let target = match path.strip_prefix('~') {
+            _ if path.is_empty() => home,
+            Some(rest) if rest.is_empty() || rest.starts_with('/') => {
+                home.join(rest.trim_start_matches('/'))
+            }
+            _ => self.cwd.join(&path),
+        };
+
+        match target.canonicalize() {
+            Ok(target) if target.is_dir() => self.cwd = target,
+            Ok(_) => self.stdout += &format!("cd: {path}: Not a directory\n"),
+            Err(err) => self.stdout += &format!("cd: {path}: {err}\n"),
+        }
+        true
+    }
+
+    /// Saves the output to a new file named after the current time in the working directory.
+    pub fn save_output_to_default(&mut self) {
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_secs())
+            .unwrap_or_default();
+        self.save_output(&PathBuf::from(format!("bottom_terminal_{time}.txt")));
+    }
+
+    /// Sends input to the running command. Does nothing if no command is running.
+    pub fn write_to_command(&mut self, input: &[u8]) {
+        if let Some(command_input) = &mut self.command_input {
+            // The command may have just exited, in which case the input has nowhere to go.
+            let _ = command_input
+                .write_all(input)
+                .and_then(|_| command_input.flush());
+        }
+    }
+
+    /// Sends the current input as a line to the running command, showing it in the output as a
+    /// terminal would echo it. Used where keys can't be forwarded one by one as they're typed.
+    pub fn send_input_line(&mut self) {
+        let line = std::mem::take(self.current_input_mut());
+        self.input_offset = 0;
+        self.stdout += &format!("{line}\n");
+        self.write_to_command(format!("{line}\n").as_bytes());
+    }
+
+    /// Adds a command's output. Escape sequences are kept for the widget to draw as colours,
+    /// unless they're disabled.
+    pub fn append_output(&mut self, output: &[u8], keep_colours: bool) {
         let new_output = from_utf8_lossy(output);
-        t.stdout += &new_output;
-        if new_output.contains('\n') {
-            t.stdout = String::from_utf8_lossy(&strip(&t.stdout).unwrap()).to_string();
+        self.stdout += &new_output;
+        if !keep_colours && new_output.contains('\n') {
+            self.stdout = String::from_utf8_lossy(&strip(&self.stdout).unwrap()).to_string();
         }
-        unsafe {
-            (*self.sender).send(BottomEvent::Resize).unwrap_unchecked();
+    }
+
+    /// Frees the widget once its command exits, reporting how it exited if it ran at all.
+    pub fn finish(&mut self, status: Option<ExitStatus>, elapsed: Duration, keep_colours: bool) {
+        self.is_working = false;
+        if let Some(status) = status {
+            self.stdout += &exit_footer(&status, elapsed, keep_colours);
         }
+        self.limit_output();
     }
 
-    pub fn limit_output(&mut self) {
-        let mut app_lock = self.lock();
-        let t = self.get_tws(&mut app_lock);
-        let stdout = &mut t.stdout;
-        if stdout.len() > 100000 {
-            let mut chars = stdout.chars();
-            for _ in 0..stdout.len() - 100000 {
+    fn limit_output(&mut self) {
+        if self.stdout.len() > 100000 {
+            let mut chars = self.stdout.chars();
+            for _ in 0..self.stdout.len() - 100000 {
                 chars.next();
             }
-            t.stdout = chars.collect();
+            self.stdout = chars.collect();
+        }
+    }
+
+    /// Kills the running command along with anything it started, and frees the widget for the
+    /// next command. Does nothing if no command is running.
+    pub fn cancel(&mut self) {
+        if let Some(pid) = self.child_pid.take() {
+            kill_process_tree(pid);
+        } else if let Some(remote_command) = self.remote_command.take() {
+            let _ = remote_command.shutdown(Shutdown::Both);
+        } else {
+            return;
+        }
+
+        self.is_working = false;
+        self.stdout += "^C\n";
+        self.command_input = None;
+    }
+
+    /// The command the widget is running, if it has started.
+    pub fn running_command(&self) -> Option<CommandId> {
+        match (self.child_pid, &self.remote_command) {
+            (Some(pid), _) => Some(CommandId::Local(pid)),
+            (None, Some(stream)) => Some(CommandId::Remote(stream.local_addr().ok())),
+            (None, None) => None,
+        }
+    }
+
+    /// Keeps hold of a command that just started, so it can be sent input and cancelled. If it was
+    /// cancelled before it got going, it is stopped right away instead.
+    pub fn start(&mut self, started: StartedCommand) {
+        if !self.is_working || self.running_command().is_some() {
+            match started.id {
+                CommandId::Local(pid) => kill_process_tree(pid),
+                CommandId::Remote(_) => {
+                    if let Some(stream) = started.remote_command {
+                        let _ = stream.shutdown(Shutdown::Both);
+                    }
+                }
+            }
+            return;
+        }
+
+        match started.id {
+            CommandId::Local(pid) => self.child_pid = Some(pid),
+            CommandId::Remote(_) => self.remote_command = started.remote_command,
+        }
+        self.command_input = started.input;
+    }
+
+    /// Stops `command` once it runs past the timeout, returning whether it did. A command that
+    /// already exited or was cancelled is left alone.
+    pub fn time_out(&mut self, command: CommandId) -> bool {
+        if self.running_command() != Some(command) {
+            return false;
+        }
+
+        match (self.child_pid, &self.remote_command) {
+            (Some(pid), _) => kill_process_tree(pid),
+            // Timing out only hangs up the command's input, so the agent still reports how it
+            // exited.
+            (None, Some(stream)) => {
+                let _ = stream.shutdown(Shutdown::Write);
+            }
+            (None, None) => {}
+        }
+        true
+    }
+
+    /// Lets go of `command` once it exits, returning whether it is still the widget's. One that
+    /// was cancelled has already been let go of, and the widget may belong to the next command.
+    /// Commands that never started have no [`CommandId`], and are always the widget's.
+    pub fn release(&mut self, command: Option<CommandId>) -> bool {
+        if command.is_some() && self.running_command() != command {
+            return false;
+        }
+
+        self.child_pid = None;
+        self.remote_command = None;
+        self.command_input = None;
+        true
+    }
+}
+
+/// Replaces the first word of `input` with the command it is an alias for, if it is one, keeping
+/// the rest of the input as arguments.
+fn expand_alias(
+    input: &str, aliases: &HashMap<String, String>, process: Option<&(Pid, String)>,
+) -> Option<String> {
+    let input = input.trim_start();
+    let (name, rest) = input.split_once(char::is_whitespace).unwrap_or((input, ""));
+    let mut command = aliases.get(name)?.clone();
+
+    if let Some((pid, process_name)) = process {
+        command = command
+            .replace("{pid}", &pid.to_string())
+            .replace("{selected}", process_name);
+    }
+    if !rest.is_empty() {
+        command.push(' ');
+        command.push_str(rest);
+    }
+
+    Some(command)
+}
+
+/// Kills a command's whole process group, which it leads as it was started in its own session.
+#[cfg(target_family = "unix")]
+pub(crate) fn kill_process_tree(pid: u32) {
+    // SAFETY: `kill` only takes integer arguments, and a failure means the group already exited.
+    unsafe {
+        libc::kill(-(pid as libc::pid_t), libc::SIGKILL);
+    }
+}
+
+/// Kills a command and every process it started.
+#[cfg(not(target_family = "unix"))]
+pub(crate) fn kill_process_tree(pid: u32) {
+    let _ = Command::new("taskkill")
+        .args(["/F", "/T", "/PID", &pid.to_string()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+}
+
+/// Describes how a command exited and how long it took, e.g. `[exit 1 in 2.3s]`. With colours, it
+/// is dimmed, or red if the command failed, through escape sequences like any other output.
+fn exit_footer(status: &ExitStatus, elapsed: Duration, colour: bool) -> String {
+    let elapsed = elapsed.as_secs_f64();
+
+    #[cfg(target_family = "unix")]
+    let signal = std::os::unix::process::ExitStatusExt::signal(status);
+    #[cfg(not(target_family = "unix"))]
+    let signal: Option<i32> = None;
+
+    let (sgr, description) = match (status.code(), signal) {
+        (Some(0), _) => ("2", "exit 0".to_string()),
+        (Some(code), _) => ("31", format!("exit {code}")),
+        (None, Some(signal)) => ("31", format!("signal {signal}")),
+        (None, None) => ("31", "exited".to_string()),
+    };
+
+    if colour {
+        format!("\x1b[{sgr}m[{description} in {elapsed:.1}s]\x1b[0m\n")
+    } else {
+        format!("[{description} in {elapsed:.1}s]\n")
+    }
+}
+
+/// Which command an event from a [`TerminalCommandExecutor`] is about, so that one from a cancelled
+/// command doesn't touch the widget once it runs the next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandId {
+    /// A local command, by process ID.
+    Local(u32),
+    /// A command run on an agent, by the local address of its connection.
+    Remote(Option<SocketAddr>),
+}
+
+/// A command that just started, with what the widget needs to send it input and cancel it.
+pub struct StartedCommand {
+    pub id: CommandId,
+    /// The connection to the agent running the command, if it runs on one.
+    pub remote_command: Option<TcpStream>,
+    pub input: Option<Box<dyn Write + Send>>,
+}
+
+impl std::fmt::Debug for StartedCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StartedCommand")
+            .field("id", &self.id)
+            .field("remote_command", &self.remote_command)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Runs a command for a terminal widget on a separate thread. The widget itself stays with the
+/// main loop, so everything about the command, from starting to exiting, is sent back as events.
+pub struct TerminalCommandExecutor {
+    pub id: u64,
+    pub sender: Sender<BottomEvent>,
+    /// How long the command may run before it is stopped, or 0 for no limit.
+    pub timeout_secs: u64,
+}
+
+impl TerminalCommandExecutor {
+    /// Runs a command attached to a pseudo-terminal, forwarding its output to the widget until it
+    /// exits.
+    #[cfg(target_family = "unix")]
+    pub fn run_in_pty(&mut self, shell: &[String], command: &str, cwd: &Path) {
+        let (mut child, mut master) = match pty::spawn_in_pty(shell, command, cwd) {
+            Ok(spawned) => spawned,
+            Err(err) => {
+                self.failed(err);
+                return;
+            }
+        };
+
+        let start = Instant::now();
+        let id = CommandId::Local(child.id());
+        self.started(StartedCommand {
+            id,
+            remote_command: None,
+            input: master
+                .try_clone()
+                .ok()
+                .map(|input| Box::new(input) as Box<dyn Write + Send>),
+        });
+
+        // Reading fails once the command exits and the terminal is closed.
+        let mut buf = [0; 1024];
+        while let Ok(read @ 1..) = master.read(&mut buf) {
+            // Terminals end lines with "\r\n", but the widget only needs the "\n".
+            let output = buf[..read]
+                .iter()
+                .copied()
+                .filter(|byte| *byte != b'\r')
+                .collect::<Vec<_>>();
+            self.send_output(output);
         }
+
+        let status = child.wait();
+        self.exited(Some(id), status.ok(), start, None);
     }
 
-    pub fn finish(&mut self) {
-        unsafe {
-            let mut app_lock = self.lock();
-            let t = self.get_tws(&mut app_lock);
-            t.is_working = false;
-            (*self.sender).send(BottomEvent::Resize).unwrap_unchecked();
+    /// Runs a command with its output piped back to the widget, taking input a line at a time.
+    pub fn run_piped(&mut self, shell: &[String], command: &str, cwd: &Path) {
+        let spawned = Command::new(&shell[0])
+            .args(&shell[1..])
+            .arg(command)
+            .current_dir(cwd)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn();
+        let mut output = match spawned {
+            Ok(output) => output,
+            Err(err) => {
+                self.failed(err);
+                return;
+            }
+        };
+        let start = Instant::now();
+        let id = CommandId::Local(output.id());
+        let input = output.stdin.take();
+        self.started(StartedCommand {
+            id,
+            remote_command: None,
+            input: input.map(|input| Box::new(input) as Box<dyn Write + Send>),
+        });
+        while output.try_wait().unwrap().is_none() {
+            let mut buf = [0];
+            if output.stdout.as_mut().unwrap().read(&mut buf).unwrap() > 0 {
+                self.send_output(buf.to_vec());
+            }
+        }
+        let mut end = Vec::new();
+        if let Some(stdout) = output.stdout.as_mut() {
+            let _ = stdout.read_to_end(&mut end);
+        }
+        if let Some(stderr) = output.stderr.as_mut() {
+            let _ = stderr.read_to_end(&mut end);
+        }
+        self.send_output(end);
+
+        let status = output.wait();
+        self.exited(Some(id), status.ok(), start, None);
+    }
+
+    /// Runs a command on the agent at `address`, forwarding its output to the widget until it
+    /// exits. Input is sent a line at a time, as the agent doesn't run it in a terminal. The agent
+    /// only runs it if `token` matches its own.
+    pub fn run_remote(&mut self, address: &str, command: &str, token: &str) {
+        let started = RemoteCommand::start(address, command, token).and_then(|remote_command| {
+            let input = remote_command.try_clone_stream()?;
+            let handle = remote_command.try_clone_stream()?;
+            Ok((remote_command, input, handle))
+        });
+        let (mut remote_command, input, handle) = match started {
+            Ok(started) => started,
+            Err(err) => {
+                self.failed(err);
+                return;
+            }
+        };
+
+        let start = Instant::now();
+        let id = CommandId::Remote(handle.local_addr().ok());
+        self.started(StartedCommand {
+            id,
+            remote_command: Some(handle),
+            input: Some(Box::new(input)),
+        });
+
+        let status = loop {
+            match remote_command.next_event() {
+                Ok(CommandEvent::Output(chunk)) => self.send_output(chunk),
+                Ok(CommandEvent::Exited(status)) => break Ok(status.map(remote::exit_status)),
+                Err(err) => break Err(err),
+            }
+        };
+        match status {
+            Ok(status) => self.exited(Some(id), status, start, None),
+            Err(err) => self.exited(
+                Some(id),
+                None,
+                start,
+                Some(format!("Lost the connection to the agent: {err}\n")),
+            ),
+        }
+    }
+
+    /// Hands a command that just started to the widget, and has it stopped once it runs past the
+    /// timeout, if there is one.
+    fn started(&mut self, started: StartedCommand) {
+        let command = started.id;
+        let _ = self.sender.send(BottomEvent::TerminalStarted {
+            widget_id: self.id,
+            started,
+        });
+
+        if self.timeout_secs > 0 {
+            let (id, sender, timeout_secs) = (self.id, self.sender.clone(), self.timeout_secs);
+            thread::spawn(move || {
+                thread::sleep(Duration::from_secs(timeout_secs));
+                let _ = sender.send(BottomEvent::TerminalTimedOut {
+                    widget_id: id,
+                    command,
+                    timeout_secs,
+                });
+            });
         }
     }
+
+    /// Reports a command that couldn't be started, freeing the widget for the next one.
+    fn failed(&mut self, err: impl std::fmt::Display) {
+        let _ = self.sender.send(BottomEvent::TerminalFinished {
+            widget_id: self.id,
+            command: None,
+            status: None,
+            elapsed: Duration::ZERO,
+            note: Some(format!("Unable to run the command: {err}\n")),
+        });
+    }
+
+    /// Tells the main loop a command exited, along with why if it was lost along the way.
+    fn exited(
+        &mut self, command: Option<CommandId>, status: Option<ExitStatus>, start: Instant,
+        note: Option<String>,
+    ) {
+        let _ = self.sender.send(BottomEvent::TerminalFinished {
+            widget_id: self.id,
+            command,
+            status,
+            elapsed: start.elapsed(),
+            note,
+        });
+    }
+
+    fn send_output(&mut self, chunk: Vec<u8>) {
+        // The main loop may have already exited, in which case there is nothing left to show.
+        let _ = self.sender.send(BottomEvent::TerminalOutput {
+            widget_id: self.id,
+            chunk,
+        });
+    }
 }
 
-unsafe impl Sync for UnsafeTerminalWidgetState {}
-unsafe impl Send for UnsafeTerminalWidgetState {}
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_history_round_trip() {
+        let path =
+            std::env::temp_dir().join(format!("btm_terminal_history_{}", std::process::id()));
+
+        let mut state = TerminalWidgetState::new(2);
+        state.stdin = VecDeque::from(["typing".into(), "ls".into(), "pwd".into(), "top".into()]);
+        state.save_history(&path).unwrap();
+
+        let mut loaded = TerminalWidgetState::new(2);
+        loaded.load_history(&path).unwrap();
+        let _ = fs::remove_file(&path);
+        assert_eq!(loaded.stdin, ["", "ls", "pwd"]);
+
+        // No saved history yet is not an error.
+        let mut empty = TerminalWidgetState::default();
+        empty.load_history(&path).unwrap();
+        assert_eq!(empty.stdin, [""]);
+    }
+
+    #[test]
+    fn test_send_input_line() {
+        let path = std::env::temp_dir().join(format!("btm_terminal_input_{}", std::process::id()));
+        let file = fs::File::create(&path).unwrap();
+
+        let mut state = TerminalWidgetState {
+            is_working: true,
+            command_input: Some(Box::new(file)),
+            ..Default::default()
+        };
+        state.current_input_mut().push('y');
+        state.input_offset = 1;
+        state.send_input_line();
+
+        let sent = fs::read_to_string(&path).unwrap();
+        let _ = fs::remove_file(&path);
+        assert_eq!(sent, "y\n");
+        assert_eq!(state.stdout, "y\n");
+        assert!(state.current_input().is_empty() && state.input_offset == 0);
+    }
+
+    #[test]
+    fn test_save_action() {
+        let path = std::env::temp_dir().join(format!("btm_terminal_output_{}", std::process::id()));
+
+        let mut state = TerminalWidgetState {
+            stdout: "\x1b[31mred\x1b[0m\n".into(),
+            ..Default::default()
+        };
+        *state.current_input_mut() = format!(":save {}", path.display());
+        assert!(state.try_save_action());
+
+        let saved = fs::read_to_string(&path).unwrap();
+        let _ = fs::remove_file(&path);
+        assert_eq!(saved, format!("red\n$ :save {}\n", path.display()));
+        assert!(state.current_input().is_empty());
+
+        *state.current_input_mut() = ":saved".into();
+        assert!(!state.try_save_action());
+    }
+
+    #[test]
+    fn test_change_directory() {
+        let temp_dir = std::env::temp_dir().canonicalize().unwrap();
+        let mut state = TerminalWidgetState::default();
+
+        *state.current_input_mut() = format!("cd {}", temp_dir.display());
+        assert!(state.try_change_directory());
+        assert_eq!(state.cwd, temp_dir);
+
+        *state.current_input_mut() = "cd ..".into();
+        assert!(state.try_change_directory());
+        assert_eq!(state.cwd, temp_dir.parent().unwrap());
+
+        *state.current_input_mut() = "cd btm_does_not_exist".into();
+        assert!(state.try_change_directory());
+        assert_eq!(state.cwd, temp_dir.parent().unwrap());
+        assert!(state.stdout.contains("cd: btm_does_not_exist: "));
+
+        *state.current_input_mut() = "cdrecord".into();
+        assert!(!state.try_change_directory());
+    }
+
+    #[cfg(target_family = "unix")]
+    #[test]
+    fn test_exit_footer() {
+        use std::os::unix::process::ExitStatusExt;
+
+        let elapsed = Duration::from_millis(2345);
+        assert_eq!(
+            exit_footer(&ExitStatus::from_raw(0), elapsed, true),
+            "\x1b[2m[exit 0 in 2.3s]\x1b[0m\n"
+        );
+        assert_eq!(
+            exit_footer(&ExitStatus::from_raw(1 << 8), elapsed, false),
+            "[exit 1 in 2.3s]\n"
+        );
+        assert_eq!(
+            exit_footer(&ExitStatus::from_raw(libc::SIGKILL), elapsed, false),
+            "[signal 9 in 2.3s]\n"
+        );
+    }
+
+    #[test]
+    fn test_output_and_finish() {
+        let mut state = TerminalWidgetState {
+            is_working: true,
+            ..Default::default()
+        };
+
+        state.append_output(b"\x1b[1mbold\x1b[0m\n", true);
+        state.append_output(b"plain \x1b[31mred\x1b[0m\n", false);
+        assert_eq!(state.stdout, "bold\nplain red\n");
+
+        state.finish(None, Duration::ZERO, false);
+        assert!(!state.is_working);
+        assert_eq!(state.stdout, "bold\nplain red\n");
+    }
+
+    #[test]
+    fn test_events_from_old_commands() {
+        let mut state = TerminalWidgetState {
+            is_working: true,
+            ..Default::default()
+        };
+        state.start(StartedCommand {
+            id: CommandId::Local(1),
+            remote_command: None,
+            input: None,
+        });
+        assert_eq!(state.running_command(), Some(CommandId::Local(1)));
+
+        // A command that was cancelled, with the widget since running another, is left alone.
+        assert!(!state.time_out(CommandId::Local(2)));
+        assert!(!state.release(Some(CommandId::Local(2))));
+        assert_eq!(state.child_pid, Some(1));
+
+        assert!(state.release(Some(CommandId::Local(1))));
+        assert_eq!(state.running_command(), None);
+
+        // One that couldn't be started at all still frees the widget.
+        assert!(state.release(None));
+    }
+
+    #[test]
+    fn test_grapheme_editing() {
+        let mut state = TerminalWidgetState::default();
+        for c in "cafe\u{301} 日本".chars() {
+            state.insert_char(c);
+        }
+
+        // "é" here is two chars, but one grapheme.
+        state.move_cursor_left();
+        state.move_cursor_left();
+        state.move_cursor_left();
+        assert_eq!(state.input_around_cursor(), ("cafe\u{301}", " 日本"));
+
+        state.remove_before_cursor();
+        state.insert_char('é');
+        assert_eq!(state.current_input(), "café 日本");
+
+        state.move_cursor_right();
+        state.remove_after_cursor();
+        assert_eq!(state.current_input(), "café 本");
+        assert_eq!(state.input_around_cursor(), ("café ", "本"));
+
+        for _ in 0..10 {
+            state.move_cursor_left();
+        }
+        state.remove_before_cursor();
+        assert_eq!(state.input_around_cursor(), ("", "café 本"));
+    }
+
+    #[test]
+    fn test_expand_alias() {
+        let aliases = HashMap::from([
+            ("logs".to_string(), "journalctl -f".to_string()),
+            (
+                "trace".to_string(),
+                "strace -p {pid} # {selected}".to_string(),
+            ),
+        ]);
+        let process = (42, "bash".to_string());
+
+        assert_eq!(
+            expand_alias("logs -u sshd", &aliases, None).as_deref(),
+            Some("journalctl -f -u sshd")
+        );
+        assert_eq!(
+            expand_alias("trace", &aliases, Some(&process)).as_deref(),
+            Some("strace -p 42 # bash")
+        );
+        assert_eq!(expand_alias("logsx", &aliases, None), None);
+
+        let mut state = TerminalWidgetState::default();
+        *state.current_input_mut() = "logs".into();
+        assert!(state.expand_alias(&aliases, None));
+        assert_eq!(state.current_input(), "journalctl -f");
+    }
+}
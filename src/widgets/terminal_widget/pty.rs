@@ -0,0 +1,184 @@
+//! Running commands attached to a pseudo-terminal, so interactive programs get a real controlling
+//! terminal instead of a closed stdin.
+
+use std::{
+    fs::File,
+    io,
+    os::{
+        fd::{AsRawFd, FromRawFd, OwnedFd},
+        unix::process::CommandExt,
+    },
+    path::Path,
+    process::{Child, Command, Stdio},
+    ptr,
+};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// The size the pseudo-terminal reports to programs that ask for it.
+const PTY_ROWS: u16 = 24;
+const PTY_COLUMNS: u16 = 80;
+
+fn check(result: libc::c_int) -> io::Result<libc::c_int> {
+    if result < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(result)
+    }
+}
+
+/// Runs `command` through `shell` (a program and its arguments) in `cwd`, with a new
+/// pseudo-terminal as its controlling terminal, returning the child and the master side of the terminal, which reads its
+/// output and writes its input.
+pub fn spawn_in_pty(shell: &[String], command: &str, cwd: &Path) -> io::Result<(Child, File)> {
+    let (mut master, mut slave) = (-1, -1);
+    let size = libc::winsize {
+        ws_row: PTY_ROWS,
+        ws_col: PTY_COLUMNS,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+
+    // SAFETY: The pointers are all valid for the duration of the call, and null is allowed for the
+    // name and terminal attributes. The size is only read, though some platforms take it as `*mut`.
+    check(unsafe {
+        libc::openpty(
+            &mut master,
+            &mut slave,
+            ptr::null_mut(),
+            ptr::null_mut(),
+            ptr::addr_of!(size) as _,
+        )
+    })?;
+    // SAFETY: `openpty` succeeded, so both are open file descriptors that nothing else owns.
+    let (master, slave) = unsafe { (OwnedFd::from_raw_fd(master), OwnedFd::from_raw_fd(slave)) };
+
+    // Neither side should leak into the child past the standard streams.
+    for fd in [&master, &slave] {
+        // SAFETY: `fd` is an open file descriptor.
+        check(unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_SETFD, libc::FD_CLOEXEC) })?;
+    }
+
+    let mut command_builder = Command::new(&shell[0]);
+    command_builder
+        .args(&shell[1..])
+        .arg(command)
+        .current_dir(cwd)
+        .stdin(Stdio::from(slave.try_clone()?))
+        .stdout(Stdio::from(slave.try_clone()?))
+        .stderr(Stdio::from(slave));
+
+    // SAFETY: Only async-signal-safe calls are made between fork and exec.
+    unsafe {
+        command_builder.pre_exec(|| {
+            // Start a new session, then take the terminal (now stdin) as its controlling terminal.
+            check(libc::setsid())?;
+            check(libc::ioctl(0, libc::TIOCSCTTY as _, 0))?;
+            Ok(())
+        });
+    }
+
+    let child = command_builder.spawn()?;
+
+    Ok((child, File::from(master)))
+}
+
+/// Translates a key press into the bytes a terminal would send for it, or [`None`] if the key
+/// should be handled by bottom instead, like scrolling.
+pub fn key_to_pty_input(event: &KeyEvent) -> Option<Vec<u8>> {
+    let bytes: &[u8] = match event.code {
+        KeyCode::Char(c) if event.modifiers.contains(KeyModifiers::CONTROL) => {
+            // Ctrl-A through Ctrl-Z map to 0x01 through 0x1A, e.g. Ctrl-C is 0x03.
+            return c
+                .is_ascii_alphabetic()
+                .then(|| vec![c.to_ascii_lowercase() as u8 - b'a' + 1]);
+        }
+        KeyCode::Char(c) => return Some(c.to_string().into_bytes()),
+        KeyCode::Enter => b"\r",
+        KeyCode::Tab => b"\t",
+        KeyCode::Backspace => b"\x7f",
+        KeyCode::Delete => b"\x1b[3~",
+        KeyCode::Up => b"\x1b[A",
+        KeyCode::Down => b"\x1b[B",
+        KeyCode::Right => b"\x1b[C",
+        KeyCode::Left => b"\x1b[D",
+        KeyCode::Home => b"\x1b[H",
+        _ => return None,
+    };
+
+    Some(bytes.to_vec())
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{Read, Write};
+
+    use super::*;
+
+    #[test]
+    fn test_key_to_pty_input() {
+        let key = |code, modifiers| key_to_pty_input(&KeyEvent::new(code, modifiers));
+
+        assert_eq!(
+            key(KeyCode::Char('y'), KeyModifiers::NONE),
+            Some(b"y".to_vec())
+        );
+        assert_eq!(
+            key(KeyCode::Char('c'), KeyModifiers::CONTROL),
+            Some(vec![3])
+        );
+        assert_eq!(
+            key(KeyCode::Enter, KeyModifiers::NONE),
+            Some(b"\r".to_vec())
+        );
+        assert_eq!(
+            key(KeyCode::Up, KeyModifiers::NONE),
+            Some(b"\x1b[A".to_vec())
+        );
+        assert_eq!(key(KeyCode::PageUp, KeyModifiers::NONE), None);
+    }
+
+    #[test]
+    fn test_spawn_in_pty() {
+        let shell = ["sh".to_string(), "-c".to_string()];
+        let (mut child, mut master) = spawn_in_pty(
+            &shell,
+            "read -r line; [ -t 0 ] && echo \"tty $line\"",
+            Path::new("."),
+        )
+        .unwrap();
+        master.write_all(b"hello\r").unwrap();
+
+        // Reading fails with EIO once the child exits and the terminal is closed.
+        let mut output = Vec::new();
+        let mut buf = [0; 256];
+        while let Ok(read @ 1..) = master.read(&mut buf) {
+            output.extend_from_slice(&buf[..read]);
+        }
+
+        assert!(child.wait().unwrap().success());
+        assert!(String::from_utf8_lossy(&output).contains("tty hello"));
+    }
+
+    #[test]
+    fn test_cancel() {
+        use std::os::unix::process::ExitStatusExt;
+
+        use crate::widgets::TerminalWidgetState;
+
+        let shell = ["sh".to_string(), "-c".to_string()];
+        let (mut child, _master) =
+            spawn_in_pty(&shell, "sleep 30 & sleep 30", Path::new(".")).unwrap();
+
+        let mut state = TerminalWidgetState {
+            is_working: true,
+            child_pid: Some(child.id()),
+            ..Default::default()
+        };
+        state.cancel();
+
+        assert_eq!(child.wait().unwrap().signal(), Some(libc::SIGKILL));
+        assert!(!state.is_working && state.child_pid.is_none());
+        assert!(state.stdout.ends_with("^C\n"));
+    }
+}
@@ -1,25 +1,290 @@
-use std::{
-    fs::{self, File},
-    io::{self, Write},
-};
+use std::{collections::HashMap, fs, io, path::PathBuf};
 
+/// How many boots are remembered.
+const MAX_BOOT_HISTORY: usize = 50;
+
+/// How far apart, in seconds, two boot times can be while still being the same boot. Boot times
+/// are worked out from the current time and uptime, which don't always tick over together.
+const BOOT_TIME_TOLERANCE: u64 = 60;
+
+/// How much longer, in seconds, the current boot has to run before its uptime is saved again.
+const BOOT_SAVE_INTERVAL: u64 = 60;
+
+/// When the system booted and how long it stayed up, both in seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BootRecord {
+    /// The boot time, as a Unix timestamp.
+    pub boot_time: u64,
+    pub uptime: u64,
+}
+
+#[derive(Default)]
 pub struct UptimeWidgetState {
+    /// The longest uptime seen, in days.
     pub streak: u64,
+    /// Where the streak is kept between runs, if anywhere.
+    pub streak_file: Option<PathBuf>,
+    /// Every boot seen, oldest first, including the current one.
+    pub boots: Vec<BootRecord>,
+    /// Where boots are kept between runs, next to the streak file.
+    boot_file: Option<PathBuf>,
+    /// The uptime of the current boot when it was last saved.
+    last_saved_uptime: u64,
+    /// The total uptime of boots too old to be remembered, in seconds.
+    retired_uptime: u64,
+    /// The longest uptime seen of each remote host, in days, keyed by the host's name.
+    pub remote_streaks: HashMap<String, u64>,
+    /// Where remote streaks are kept between runs, next to the streak file.
+    remote_streak_file: Option<PathBuf>,
 }
 
-impl Default for UptimeWidgetState {
-    fn default() -> Self {
-        let saved_days =
-            fs::read_to_string("/home/felix/.config/bottom/days").unwrap_or_else(|_| {
-                let mut file = File::create("/home/felix/.config/bottom/days").unwrap();
-                let mut days = String::new();
-                io::stdin().read_line(&mut days).unwrap();
-                days.pop();
-                file.write_all(days.as_bytes()).unwrap();
-                days
-            });
+impl UptimeWidgetState {
+    /// Loads the streak from `streak_file`, starting from zero if it hasn't been saved yet.
+    pub fn new(streak_file: Option<PathBuf>) -> Self {
+        let streak = streak_file
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|saved_days| saved_days.trim().parse().ok())
+            .unwrap_or(0);
+
+        let boot_file = streak_file
+            .as_ref()
+            .map(|path| path.with_file_name("boots"));
+        let (boots, retired_uptime) = boot_file
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|saved_boots| parse_boots(&saved_boots))
+            .unwrap_or_default();
+
+        let remote_streak_file = streak_file
+            .as_ref()
+            .map(|path| path.with_file_name("remote_days"));
+        let remote_streaks = remote_streak_file
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|saved_streaks| parse_remote_streaks(&saved_streaks))
+            .unwrap_or_default();
+
         Self {
-            streak: saved_days.parse().unwrap_or(0),
+            streak,
+            streak_file,
+            boots,
+            boot_file,
+            last_saved_uptime: 0,
+            retired_uptime,
+            remote_streaks,
+            remote_streak_file,
+        }
+    }
+
+    /// Records the uptime of the current boot at `now`, a Unix timestamp, adding it to the boot
+    /// history if it is a new boot.
+    pub fn record_boot(&mut self, now: u64, uptime: u64) -> io::Result<()> {
+        let boot_time = now.saturating_sub(uptime);
+        let is_new_boot = match self.boots.last_mut() {
+            Some(last) if last.boot_time.abs_diff(boot_time) <= BOOT_TIME_TOLERANCE => {
+                last.uptime = uptime;
+                false
+            }
+            _ => {
+                self.boots.push(BootRecord { boot_time, uptime });
+                if self.boots.len() > MAX_BOOT_HISTORY {
+                    self.retired_uptime += self.boots.remove(0).uptime;
+                }
+                true
+            }
+        };
+
+        if is_new_boot || uptime.saturating_sub(self.last_saved_uptime) >= BOOT_SAVE_INTERVAL {
+            self.last_saved_uptime = uptime;
+            if let Some(path) = &self.boot_file {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(path, format_boots(&self.boots, self.retired_uptime))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the uptime of every boot seen put together, in seconds.
+    pub fn total_uptime(&self) -> u64 {
+        self.retired_uptime + self.boots.iter().map(|boot| boot.uptime).sum::<u64>()
+    }
+
+    /// Returns every boot before the current one, most recent first.
+    pub fn previous_boots(&self) -> impl Iterator<Item = &BootRecord> {
+        self.boots.iter().rev().skip(1)
+    }
+
+    /// Records the current uptime, saving it as the new streak if it is the longest yet.
+    pub fn update_streak(&mut self, days: u64) -> io::Result<()> {
+        if days <= self.streak {
+            return Ok(());
+        }
+
+        self.streak = days;
+        if let Some(path) = &self.streak_file {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, days.to_string())?;
+        }
+
+        Ok(())
+    }
+
+    /// Records the current uptime of a remote host, saving it as the host's new streak if it is
+    /// the longest yet.
+    pub fn update_remote_streak(&mut self, name: &str, days: u64) -> io::Result<()> {
+        if self
+            .remote_streaks
+            .get(name)
+            .is_some_and(|streak| days <= *streak)
+        {
+            return Ok(());
+        }
+
+        self.remote_streaks.insert(name.to_string(), days);
+        if let Some(path) = &self.remote_streak_file {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, format_remote_streaks(&self.remote_streaks))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses saved remote streaks, one per line as the days followed by the host's name.
+fn parse_remote_streaks(saved_streaks: &str) -> HashMap<String, u64> {
+    saved_streaks
+        .lines()
+        .filter_map(|line| {
+            let (days, name) = line.split_once(' ')?;
+            Some((name.to_string(), days.parse().ok()?))
+        })
+        .collect()
+}
+
+fn format_remote_streaks(remote_streaks: &HashMap<String, u64>) -> String {
+    remote_streaks
+        .iter()
+        .map(|(name, days)| format!("{days} {name}\n"))
+        .collect()
+}
+
+/// Parses saved boots, one per line as the boot time followed by the uptime, and the total uptime
+/// of boots no longer remembered, on a line starting with `retired`.
+fn parse_boots(saved_boots: &str) -> (Vec<BootRecord>, u64) {
+    let mut retired_uptime = 0;
+    let boots = saved_boots
+        .lines()
+        .filter_map(|line| {
+            let (first, second) = line.split_once(' ')?;
+            if first == "retired" {
+                retired_uptime = second.parse().ok()?;
+                return None;
+            }
+
+            Some(BootRecord {
+                boot_time: first.parse().ok()?,
+                uptime: second.parse().ok()?,
+            })
+        })
+        .collect();
+
+    (boots, retired_uptime)
+}
+
+fn format_boots(boots: &[BootRecord], retired_uptime: u64) -> String {
+    boots.iter().fold(
+        format!("retired {retired_uptime}\n"),
+        |saved_boots, boot| saved_boots + &format!("{} {}\n", boot.boot_time, boot.uptime),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_streak_file() {
+        let path = std::env::temp_dir().join(format!("btm_uptime_streak_{}", std::process::id()));
+
+        let mut state = UptimeWidgetState::new(Some(path.clone()));
+        assert_eq!(state.streak, 0);
+        state.update_streak(3).unwrap();
+        state.update_streak(2).unwrap();
+
+        let loaded = UptimeWidgetState::new(Some(path.clone()));
+        let _ = fs::remove_file(&path);
+        assert_eq!(loaded.streak, 3);
+    }
+
+    #[test]
+    fn test_boot_history() {
+        let dir = std::env::temp_dir().join(format!("btm_uptime_boots_{}", std::process::id()));
+        let streak_file = dir.join("days");
+
+        let mut state = UptimeWidgetState::new(Some(streak_file.clone()));
+        state.record_boot(1000, 500).unwrap();
+        // The same boot, with the clock and uptime a second out of step.
+        state.record_boot(1600, 1101).unwrap();
+        state.record_boot(5000, 100).unwrap();
+
+        let loaded = UptimeWidgetState::new(Some(streak_file));
+        let _ = fs::remove_dir_all(&dir);
+        assert_eq!(
+            loaded.boots,
+            vec![
+                BootRecord {
+                    boot_time: 500,
+                    uptime: 1101,
+                },
+                BootRecord {
+                    boot_time: 4900,
+                    uptime: 100,
+                },
+            ]
+        );
+        assert_eq!(
+            loaded.previous_boots().collect::<Vec<_>>(),
+            vec![&loaded.boots[0]]
+        );
+        assert_eq!(loaded.total_uptime(), 1201);
+    }
+
+    #[test]
+    fn test_retired_uptime() {
+        let mut state = UptimeWidgetState::default();
+        for boot in 0..=MAX_BOOT_HISTORY as u64 {
+            state.record_boot(boot * 1000 + 100, 100).unwrap();
         }
+
+        assert_eq!(state.boots.len(), MAX_BOOT_HISTORY);
+        assert_eq!(state.total_uptime(), 100 * (MAX_BOOT_HISTORY as u64 + 1));
+
+        let (boots, retired_uptime) = parse_boots(&format_boots(&state.boots, 100));
+        assert_eq!(boots, state.boots);
+        assert_eq!(retired_uptime, 100);
+    }
+
+    #[test]
+    fn test_remote_streaks() {
+        let dir = std::env::temp_dir().join(format!("btm_uptime_remote_{}", std::process::id()));
+        let streak_file = dir.join("days");
+
+        let mut state = UptimeWidgetState::new(Some(streak_file.clone()));
+        state.update_remote_streak("backup server", 4).unwrap();
+        state.update_remote_streak("backup server", 2).unwrap();
+        state.update_remote_streak("router", 0).unwrap();
+
+        let loaded = UptimeWidgetState::new(Some(streak_file));
+        let _ = fs::remove_dir_all(&dir);
+        assert_eq!(loaded.remote_streaks.get("backup server"), Some(&4));
+        assert_eq!(loaded.remote_streaks.get("router"), Some(&0));
     }
 }
@@ -0,0 +1,15 @@
+use std::time::Instant;
+
+pub struct WifiWidgetState {
+    pub current_display_time: u64,
+    pub autohide_timer: Option<Instant>,
+}
+
+impl WifiWidgetState {
+    pub fn init(current_display_time: u64, autohide_timer: Option<Instant>) -> Self {
+        WifiWidgetState {
+            current_display_time,
+            autohide_timer,
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use std::borrow::Cow;
+
+use tui::{text::Text, widgets::Row};
+
+use crate::{
+    app::{data_harvester::zfs::ZfsScan, AppConfigFields},
+    canvas::{canvas_styling::CanvasColours, Painter},
+    components::data_table::{
+        ColumnHeader, DataTableColumn, DataTableProps, DataTableStyling, DataToCell, SortColumn,
+        SortDataTable, SortDataTableProps, SortOrder, SortsRow,
+    },
+    data_conversion::binary_byte_string,
+    utils::gen_util::{sort_partial_fn, truncate_to_text},
+};
+
+#[derive(Clone, Debug)]
+pub struct ZfsPoolWidgetData {
+    pub name: String,
+    pub health: String,
+    pub size: u64,
+    pub allocated: u64,
+    pub free: u64,
+    pub fragmentation: Option<u64>,
+    pub capacity: Option<u64>,
+    pub scan: Option<ZfsScan>,
+    pub is_unhealthy: bool,
+}
+
+impl ZfsPoolWidgetData {
+    /// Describes the last or ongoing scrub or resilver, along with how far along an ongoing one is.
+    fn scan_string(&self) -> Option<String> {
+        let scan = self.scan.as_ref()?;
+        Some(match scan.percent {
+            Some(percent) => format!("{}, {percent:.1}% done", scan.summary),
+            None => scan.summary.clone(),
+        })
+    }
+}
+
+pub enum ZfsPoolWidgetColumn {
+    Pool,
+    Health,
+    Size,
+    Used,
+    Free,
+    Frag,
+    Cap,
+    Scan,
+}
+
+impl ColumnHeader for ZfsPoolWidgetColumn {
+    fn text(&self) -> Cow<'static, str> {
+        match self {
+            ZfsPoolWidgetColumn::Pool => "Pool".into(),
+            ZfsPoolWidgetColumn::Health => "Health".into(),
+            ZfsPoolWidgetColumn::Size => "Size".into(),
+            ZfsPoolWidgetColumn::Used => "Used".into(),
+            ZfsPoolWidgetColumn::Free => "Free".into(),
+            ZfsPoolWidgetColumn::Frag => "Frag".into(),
+            ZfsPoolWidgetColumn::Cap => "Cap".into(),
+            ZfsPoolWidgetColumn::Scan => "Scrub".into(),
+        }
+    }
+}
+
+impl DataToCell<ZfsPoolWidgetColumn> for ZfsPoolWidgetData {
+    fn to_cell<'a>(
+        &'a self, column: &ZfsPoolWidgetColumn, calculated_width: u16,
+    ) -> Option<Text<'a>> {
+        if calculated_width == 0 {
+            return None;
+        }
+
+        let text = match column {
+            ZfsPoolWidgetColumn::Pool => Some(self.name.clone()),
+            ZfsPoolWidgetColumn::Health => Some(self.health.clone()),
+            ZfsPoolWidgetColumn::Size => Some(binary_byte_string(self.size)),
+            ZfsPoolWidgetColumn::Used => Some(binary_byte_string(self.allocated)),
+            ZfsPoolWidgetColumn::Free => Some(binary_byte_string(self.free)),
+            ZfsPoolWidgetColumn::Frag => self.fragmentation.map(|frag| format!("{frag}%")),
+            ZfsPoolWidgetColumn::Cap => self.capacity.map(|cap| format!("{cap}%")),
+            ZfsPoolWidgetColumn::Scan => self.scan_string(),
+        };
+
+        Some(truncate_to_text(
+            text.as_deref().unwrap_or(""),
+            calculated_width,
+        ))
+    }
+
+    fn style_row<'a>(&self, row: Row<'a>, painter: &Painter) -> Row<'a> {
+        if self.is_unhealthy {
+            row.style(painter.colours.low_battery_colour)
+        } else {
+            row
+        }
+    }
+
+    fn column_widths<C: DataTableColumn<ZfsPoolWidgetColumn>>(
+        data: &[ZfsPoolWidgetData], _columns: &[C],
+    ) -> Vec<u16>
+    where
+        Self: Sized,
+    {
+        let mut widths = vec![0, 0, 8, 8, 8, 5, 5, 0];
+        for row in data {
+            widths[0] = widths[0].max(row.name.len() as u16);
+            widths[1] = widths[1].max(row.health.len() as u16);
+            widths[7] = widths[7].max(row.scan_string().unwrap_or_default().len() as u16);
+        }
+
+        widths
+    }
+}
+
+impl SortsRow for ZfsPoolWidgetColumn {
+    type DataType = ZfsPoolWidgetData;
+
+    fn sort_data(&self, data: &mut [Self::DataType], descending: bool) {
+        match self {
+            ZfsPoolWidgetColumn::Pool => {
+                data.sort_by(|a, b| sort_partial_fn(descending)(&a.name, &b.name));
+            }
+            ZfsPoolWidgetColumn::Health => {
+                data.sort_by(|a, b| sort_partial_fn(descending)(&a.health, &b.health));
+            }
+            ZfsPoolWidgetColumn::Size => {
+                data.sort_by(|a, b| sort_partial_fn(descending)(a.size, b.size));
+            }
+            ZfsPoolWidgetColumn::Used => {
+                data.sort_by(|a, b| sort_partial_fn(descending)(a.allocated, b.allocated));
+            }
+            ZfsPoolWidgetColumn::Free => {
+                data.sort_by(|a, b| sort_partial_fn(descending)(a.free, b.free));
+            }
+            ZfsPoolWidgetColumn::Frag => {
+                data.sort_by(|a, b| sort_partial_fn(descending)(a.fragmentation, b.fragmentation));
+            }
+            ZfsPoolWidgetColumn::Cap => {
+                data.sort_by(|a, b| sort_partial_fn(descending)(a.capacity, b.capacity));
+            }
+            ZfsPoolWidgetColumn::Scan => {
+                data.sort_by(|a, b| {
+                    sort_partial_fn(descending)(
+                        a.scan.as_ref().map(|scan| &scan.summary),
+                        b.scan.as_ref().map(|scan| &scan.summary),
+                    )
+                });
+            }
+        }
+    }
+}
+
+pub struct ZfsPoolWidgetState {
+    pub table: SortDataTable<ZfsPoolWidgetData, ZfsPoolWidgetColumn>,
+}
+
+impl ZfsPoolWidgetState {
+    pub fn new(config: &AppConfigFields, colours: &CanvasColours) -> Self {
+        let columns = [
+            SortColumn::soft(ZfsPoolWidgetColumn::Pool, Some(0.15)),
+            SortColumn::soft(ZfsPoolWidgetColumn::Health, Some(0.1)),
+            SortColumn::hard(ZfsPoolWidgetColumn::Size, 8).default_descending(),
+            SortColumn::hard(ZfsPoolWidgetColumn::Used, 8).default_descending(),
+            SortColumn::hard(ZfsPoolWidgetColumn::Free, 8).default_descending(),
+            SortColumn::hard(ZfsPoolWidgetColumn::Frag, 5).default_descending(),
+            SortColumn::hard(ZfsPoolWidgetColumn::Cap, 5).default_descending(),
+            SortColumn::soft(ZfsPoolWidgetColumn::Scan, Some(0.4)),
+        ];
+
+        let props = SortDataTableProps {
+            inner: DataTableProps {
+                title: Some(" ZFS Pools ".into()),
+                table_gap: config.table_gap,
+                left_to_right: true,
+                is_basic: config.use_basic_mode,
+                show_table_scroll_position: config.show_table_scroll_position,
+                show_scrollbar: config.show_scrollbar,
+                show_current_entry_when_unfocused: false,
+            },
+            sort_index: 0,
+            order: SortOrder::Ascending,
+        };
+
+        let styling = DataTableStyling::from_colours(colours);
+
+        Self {
+            table: SortDataTable::new_sortable(columns, props, styling),
+        }
+    }
+
+    pub fn ingest_data(&mut self, pools: &[ZfsPoolWidgetData]) {
+        let mut data = pools.to_vec();
+        if let Some(column) = self.table.columns.get(self.table.sort_index()) {
+            column.sort_by(&mut data, self.table.order());
+        }
+
+        self.table.set_data(data);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_scan_string() {
+        let mut data = ZfsPoolWidgetData {
+            name: "tank".to_string(),
+            health: "ONLINE".to_string(),
+            size: 10_737_418_240,
+            allocated: 5_368_709_120,
+            free: 5_368_709_120,
+            fragmentation: Some(12),
+            capacity: Some(50),
+            scan: None,
+            is_unhealthy: false,
+        };
+        assert_eq!(data.scan_string(), None);
+
+        data.scan = Some(ZfsScan {
+            summary: "scrub in progress since Sun Oct 11 00:24:01 2026".to_string(),
+            percent: Some(4.88),
+        });
+        assert_eq!(
+            data.scan_string().as_deref(),
+            Some("scrub in progress since Sun Oct 11 00:24:01 2026, 4.9% done")
+        );
+    }
+}
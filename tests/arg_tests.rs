@@ -176,3 +176,32 @@ fn test_battery_flag() {
             ));
     }
 }
+
+#[test]
+fn test_generate_config_stdout() {
+    btm_command()
+        .arg("--generate-config")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[flags]"))
+        .stdout(predicate::str::contains("#rate = 1000"));
+}
+
+#[test]
+fn test_generate_config_file() {
+    let path = std::env::temp_dir().join(format!("btm_generated_{}.toml", std::process::id()));
+
+    btm_command()
+        .arg("--generate-config")
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+
+    let config = bottom::create_or_get_config(&Some(path.clone()));
+    std::fs::remove_file(&path).unwrap();
+
+    let config = config.unwrap();
+    assert!(config.colors.is_none());
+    assert!(config.row.is_none());
+}
@@ -8,6 +8,7 @@ use bottom::options::{layout_options::Row, Config};
 use bottom::utils::error;
 
 use toml_edit::de::from_str;
+use tui::style::{Color, Style};
 
 // TODO: Could move these into the library files rather than external tbh.
 
@@ -468,3 +469,37 @@ fn test_proc_custom_layout() {
         Some(13)
     );
 }
+
+#[test]
+fn test_widget_colour_overrides() {
+    const COLOURED_LAYOUT: &str = r##"
+[[row]]
+    [[row.child]]
+        type="proc"
+        border_color="red"
+        title_color="#00ff00"
+    [[row.child]]
+        type="net"
+"##;
+
+    let rows = from_str::<Config>(COLOURED_LAYOUT).unwrap().row.unwrap();
+    let ret_bottom_layout = test_create_layout(&rows, DEFAULT_WIDGET_ID, None, 1, false);
+
+    // The proc widget and its sort and search sub-widgets all share the colours.
+    let proc_col = &ret_bottom_layout.rows[0].children[0];
+    for widget in proc_col
+        .children
+        .iter()
+        .flat_map(|col_row| &col_row.children)
+    {
+        assert_eq!(widget.border_style, Some(Style::default().fg(Color::Red)));
+        assert_eq!(
+            widget.title_style,
+            Some(Style::default().fg(Color::Rgb(0, 255, 0)))
+        );
+    }
+
+    let net_widget = &ret_bottom_layout.rows[0].children[1].children[0].children[0];
+    assert_eq!(net_widget.border_style, None);
+    assert_eq!(net_widget.title_style, None);
+}